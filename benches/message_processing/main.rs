@@ -10,7 +10,9 @@ use rand::seq::SliceRandom;
 use rand::rngs::ThreadRng;
 use rand::thread_rng;
 use rayon::prelude::*;
-use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+use acars_vdlm2_parser::{decode_direct, AcarsVdlm2Message, DecodeMessage};
+use acars_vdlm2_parser::extract::extract_fields;
+use acars_vdlm2_parser::stream::StreamDecoder;
 
 fn load_data() -> Option<Vec<String>> {
     combine_found_files(glob("test_files/*"))
@@ -99,7 +101,7 @@ fn process_messages_from_string(data: &[String]) {
 fn process_messages_to_string(data: &[AcarsVdlm2Message]) {
     let ok_messages: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
     data.par_iter().for_each(| message: &AcarsVdlm2Message | {
-        if let Ok(message) = message.to_string() {
+        if let Ok(message) = message.to_json() {
             ok_messages.lock().unwrap().push(message);
         }
     });
@@ -123,7 +125,7 @@ pub fn bench_processing_from_string(c: &mut Criterion) {
         let test_snippet: Vec<String> = duplicated_data[0..*batch_size].to_vec();
         iter_run.throughput(Throughput::Elements(iter_batch_sizes.len() as u64));
         iter_run.bench_with_input(BenchmarkId::new("AcarsVdlmMessage", batch_size), &test_snippet, |b, data|  {
-            b.iter(|| process_messages_from_string(&data));
+            b.iter(|| process_messages_from_string(data));
         });
     }
 }
@@ -147,10 +149,70 @@ pub fn bench_processing_to_string(c: &mut Criterion) {
         let test_snippet: Vec<AcarsVdlm2Message> = duplicated_data[0..*batch_size].to_vec();
         iter_run.throughput(Throughput::Elements(iter_batch_sizes.len() as u64));
         iter_run.bench_with_input(BenchmarkId::new("AcarsVdlmMessage", batch_size), &test_snippet, |b, data|  {
-            b.iter(|| process_messages_to_string(&data));
+            b.iter(|| process_messages_to_string(data));
         });
     }
 }
 
-criterion_group!(benches, bench_processing_from_string, bench_processing_to_string);
+pub fn bench_extract_fields(c: &mut Criterion) {
+    println!("Loading data");
+    let Some(loaded_data) = load_data() else {
+        eprintln!("Failed to load data.");
+        return;
+    };
+    println!("Duplicating and shuffling data.");
+    let duplicated_data: Vec<String> = duplicate_messages(loaded_data);
+    println!("Starting on the benching.");
+    let mut iter_run: BenchmarkGroup<WallTime> = c.benchmark_group("message_routing_fields");
+    iter_run.measurement_time(Duration::from_secs(60));
+    iter_run.sample_size(200);
+
+    let routing_fields: [&str; 3] = ["label", "station_id", "vdl2.avlc.acars.label"];
+    let iter_batch_sizes: Vec<usize> = vec![1_000, 10_000, 100_000];
+    for batch_size in &iter_batch_sizes {
+        let test_snippet: Vec<String> = duplicated_data[0..*batch_size].to_vec();
+        iter_run.throughput(Throughput::Elements(*batch_size as u64));
+        iter_run.bench_with_input(BenchmarkId::new("extract_fields", batch_size), &test_snippet, |b, data| {
+            b.iter(|| data.iter().map(|message| extract_fields(message, &routing_fields).len()).sum::<usize>());
+        });
+        iter_run.bench_with_input(BenchmarkId::new("decode_message", batch_size), &test_snippet, |b, data| {
+            b.iter(|| data.iter().filter_map(|message| message.decode_message().ok()).filter_map(|message| message.get_label().map(str::len)).sum::<usize>());
+        });
+    }
+}
+
+pub fn bench_burst_decoding(c: &mut Criterion) {
+    println!("Loading data");
+    let Some(loaded_data) = load_data() else {
+        eprintln!("Failed to load data.");
+        return;
+    };
+    println!("Duplicating and shuffling data.");
+    let duplicated_data: Vec<String> = duplicate_messages(loaded_data);
+    println!("Starting on the benching.");
+    let mut iter_run: BenchmarkGroup<WallTime> = c.benchmark_group("message_burst_decoding");
+    iter_run.measurement_time(Duration::from_secs(60));
+    iter_run.sample_size(100);
+
+    let iter_batch_sizes: Vec<usize> = vec![1_000, 10_000, 100_000];
+    for batch_size in &iter_batch_sizes {
+        let test_snippet: Vec<String> = duplicated_data[0..*batch_size].to_vec();
+        let concatenated: Vec<u8> = test_snippet.concat().into_bytes();
+        iter_run.throughput(Throughput::Elements(*batch_size as u64));
+        iter_run.bench_with_input(BenchmarkId::new("decode_message", batch_size), &test_snippet, |b, data| {
+            b.iter(|| data.iter().filter(|message| message.as_bytes().decode_message().is_ok()).count());
+        });
+        iter_run.bench_with_input(BenchmarkId::new("decode_direct", batch_size), &test_snippet, |b, data| {
+            b.iter(|| data.iter().filter(|message| decode_direct(message.as_bytes()).is_ok()).count());
+        });
+        iter_run.bench_with_input(BenchmarkId::new("stream_decoder", batch_size), &concatenated, |b, data| {
+            b.iter(|| StreamDecoder::new(data.as_slice()).filter(|decoded| matches!(decoded, Ok(Ok(_)))).count());
+        });
+        iter_run.bench_with_input(BenchmarkId::new("stream_decoder_direct", batch_size), &concatenated, |b, data| {
+            b.iter(|| StreamDecoder::new(data.as_slice()).direct_decode(true).filter(|decoded| matches!(decoded, Ok(Ok(_)))).count());
+        });
+    }
+}
+
+criterion_group!(benches, bench_processing_from_string, bench_processing_to_string, bench_extract_fields, bench_burst_decoding);
 criterion_main!(benches);
\ No newline at end of file
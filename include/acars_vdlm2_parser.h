@@ -0,0 +1,62 @@
+#ifndef ACARS_VDLM2_PARSER_H
+#define ACARS_VDLM2_PARSER_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The call succeeded and the output holds the decoded message as JSON.
+#define AVP_OK 0
+
+// A pointer that must be set was null.
+#define AVP_ERROR_NULL_POINTER -1
+
+// The input to `avp_decode_json` was not valid UTF-8.
+#define AVP_ERROR_INVALID_UTF8 -2
+
+// The message decoded but could not be written back as JSON.
+#define AVP_ERROR_ENCODE -3
+
+// The decoder panicked, which is a bug in this crate.
+#define AVP_ERROR_PANIC -4
+
+// Decodes a NUL terminated JSON message from acarsdec, dumpvdl2 or dumphfdl.
+//
+// On success `*output` is set to the message in the unified model as NUL terminated JSON. When the input does not
+// decode, `*output` is set to the error text instead. Either string must be released with `avp_string_free`. `*output`
+// is set to null when a negative code is returned.
+//
+// # Safety
+//
+// `input` must be null or point to a NUL terminated string, and `output` must be null or point to writable memory for
+// a pointer.
+int32_t avp_decode_json(const char *input, char **output);
+
+// Decodes a message held in a buffer of `len` bytes, such as a datagram read from a socket, which does not need to be
+// NUL terminated.
+//
+// The buffer can hold JSON, or with the `compression` feature a frame written by
+// `AcarsVdlm2Message::to_bytes_compressed`. `*output` is set the same way as `avp_decode_json`.
+//
+// # Safety
+//
+// `data` must be null or point to at least `len` readable bytes, and `output` must be null or point to writable memory
+// for a pointer.
+int32_t avp_decode_raw_frame(const uint8_t *data, size_t len, char **output);
+
+// Releases a string returned by this library. Passing null does nothing.
+//
+// # Safety
+//
+// `string` must be null or a string returned by this library that has not already been released.
+void avp_string_free(char *string);
+
+// Returns the name of a code returned by this library, such as `json.syntax`, as a static NUL terminated string that
+// must not be released, or null for an unknown code.
+const char *avp_error_code_name(int32_t code);
+
+#endif  /* ACARS_VDLM2_PARSER_H */
@@ -3,7 +3,44 @@
 echo "Running all except ignored tests."
 echo "Ignored tests are for showing parsing error messages."
 echo "All other tests operate on a pass/fail with details if required."
+echo "Randomised tests print their seed on failure, set ACARS_VDLM2_TEST_SEED to replay it."
 echo "---"
 cargo test --no-fail-fast
 echo "---"
+echo "Running all except ignored tests with serde_json arbitrary_precision enabled."
+cargo test --no-fail-fast --features arbitrary_precision
+echo "---"
+echo "Running all except ignored tests with aircraft enrichment enabled."
+cargo test --no-fail-fast --features enrichment
+echo "---"
+echo "Running all except ignored tests with capture directory tailing enabled."
+cargo test --no-fail-fast --features capture
+echo "---"
+echo "Running all except ignored tests with compressed payload detection enabled."
+cargo test --no-fail-fast --features compression
+echo "---"
+echo "Running all except ignored tests with the C bindings enabled."
+cargo test --no-fail-fast --features ffi,compression
+echo "---"
+echo "Linking the C bindings into a C program, against the static and the shared library."
+cargo rustc --lib --features ffi --crate-type staticlib
+cargo rustc --lib --features ffi --crate-type cdylib
+cc -Wall -Werror -Iinclude tests/c/ffi_smoke.c target/debug/libacars_vdlm2_parser.a -lpthread -ldl -lm -o target/debug/ffi_smoke_static && target/debug/ffi_smoke_static
+cc -Wall -Werror -Iinclude tests/c/ffi_smoke.c -Ltarget/debug -lacars_vdlm2_parser -o target/debug/ffi_smoke_shared && LD_LIBRARY_PATH=target/debug target/debug/ffi_smoke_shared
+echo "---"
+echo "Running all except ignored tests with parallel batch transforms enabled."
+cargo test --no-fail-fast --features rayon
+echo "---"
+echo "Running all except ignored tests with the UDP and TCP rebroadcaster enabled."
+cargo test --no-fail-fast --features rebroadcast
+echo "---"
+echo "Running all except ignored tests with the message search index enabled."
+cargo test --no-fail-fast --features search
+echo "---"
+echo "Checking each message family builds on its own."
+for family in acars vdlm2 hfdl uat; do
+  cargo clippy --all-targets --no-default-features --features "$family" -- -D warnings
+  cargo test --no-fail-fast --no-default-features --features "$family" --test feature_tests
+done
+echo "---"
 echo "Tests completed."
\ No newline at end of file
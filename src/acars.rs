@@ -1,7 +1,29 @@
-use serde::{Serialize, Deserialize};
-use crate::{AppDetails, MessageResult};
+use std::fmt;
+use std::io::Write;
+use std::num::ParseFloatError;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use crate::{AppDetails, MessageFamily, MessageResult, SummaryLine};
+use crate::app_template::AppDetailsTemplate;
+use crate::coerce::option_number_or_string;
+use crate::correlate::AckFields;
+use crate::equality::{eq_ignoring, FieldClass};
+#[cfg(feature = "enrichment")]
+use crate::enrichment::AircraftInfo;
+use crate::frequency::Frequency;
+use crate::instrument::decode_span;
+use crate::normalise::TextNormaliser;
+use crate::quality::FrameQuality;
+use crate::source::SourceKind;
+use crate::redact::{AircraftAnonymiser, StationRedactor};
+use crate::timestamp::{MessageTimestamp, TimestampPrecision, TimestampSource};
+use crate::visit::{AcarsBlock, MessageVisitor, SignalInfo};
 
 
+/// A sanitised acarsdec capture returned by `AcarsMessage::example()`.
+const EXAMPLE: &str = r#"{"freq":131.55,"channel":2,"error":0,"level":-18.6,"timestamp":1700000000.25,"app":{"name":"acarsdec","ver":"3.7"},"station_id":"EXAMPLE-STATION","assstat":"complete","mode":"2","label":"H1","block_id":"3","ack":false,"tail":".N123AB","text":"POS N47123W122456,KSEA,KSFO,1530,350,M45","msgno":"F22A","flight":"AB0123"}"#;
+
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
 /// The originating data must be in JSON format and have support for providing a `str`, and will not consume the source.
@@ -16,7 +38,7 @@ pub trait NewAcarsMessage {
 /// This does not consume the `String`.
 impl NewAcarsMessage for String {
     fn to_acars(&self) -> MessageResult<AcarsMessage> {
-        serde_json::from_str(self)
+        decode_span(Some(MessageFamily::Acars), self.len(), || serde_json::from_str(self))
     }
 }
 
@@ -25,16 +47,79 @@ impl NewAcarsMessage for String {
 /// This does not consume the `str`.
 impl NewAcarsMessage for str {
     fn to_acars(&self) -> MessageResult<AcarsMessage> {
-        serde_json::from_str(self)
+        decode_span(Some(MessageFamily::Acars), self.len(), || serde_json::from_str(self))
+    }
+}
+
+/// Supporting `.to_acars()` for JSON held as bytes, such as a socket buffer.
+///
+/// This decodes straight from the bytes without converting them to a `str` first, and does not consume them.
+impl NewAcarsMessage for [u8] {
+    fn to_acars(&self) -> MessageResult<AcarsMessage> {
+        decode_span(Some(MessageFamily::Acars), self.len(), || serde_json::from_slice(self))
+    }
+}
+
+/// Supporting `.to_acars()` for JSON already parsed into a `Value`, such as by an earlier pipeline stage.
+///
+/// This decodes from the `Value` without writing it back out as JSON first, and does not consume it.
+impl NewAcarsMessage for Value {
+    fn to_acars(&self) -> MessageResult<AcarsMessage> {
+        decode_span(Some(MessageFamily::Acars), 0, || AcarsMessage::deserialize(self))
+    }
+}
+
+/// Decodes an owned `Value`, see `AcarsMessage::from_value`.
+impl TryFrom<Value> for AcarsMessage {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> MessageResult<Self> {
+        Self::from_value(value)
     }
 }
 
 impl AcarsMessage {
 
-    /// Converts `AcarsMessage` to `String`.
-    pub fn to_string(&self) -> MessageResult<String> {
+    /// Returns a realistic, fully populated message based on a sanitised acarsdec capture, for doctests and unit tests
+    /// that need more than the empty shell `Default` gives.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use acars_vdlm2_parser::acars::AcarsMessage;
+    /// let example: AcarsMessage = AcarsMessage::example();
+    /// assert_eq!(example.get_flight(), Some("AB0123"));
+    /// let decoded: AcarsVdlm2Message = example.to_json().unwrap().decode_message().unwrap();
+    /// assert_eq!(decoded.to_json().unwrap(), example.to_json().unwrap());
+    /// ```
+    pub fn example() -> Self {
+        // The doctest above checks the example decodes, so the fallback is never taken.
+        serde_json::from_str(EXAMPLE).unwrap_or_default()
+    }
+
+    /// Decodes a `AcarsMessage` from an owned `Value`, moving its strings into the message rather than copying them.
+    pub fn from_value(value: Value) -> MessageResult<Self> {
+        decode_span(Some(MessageFamily::Acars), 0, || serde_json::from_value(value))
+    }
+
+    /// Converts `AcarsMessage` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
         serde_json::to_string(self)
     }
+
+    /// Converts `AcarsMessage` to a JSON `String`.
+    ///
+    /// This shadows `ToString::to_string`, which returns the single line summary of the `Display` implementation, so
+    /// `message.to_string()` and `format!("{}", message)` differ. Use `to_json()` for the JSON and `summary_line()` for
+    /// the summary.
+    #[deprecated(since = "0.3.0", note = "use `to_json()` for the JSON, `to_string()` will return the `Display` summary")]
+    pub fn to_string(&self) -> MessageResult<String> {
+        self.to_json()
+    }
+
+    /// Returns the single line summary written by the `Display` implementation, for logging, see `to_json` for the
+    /// JSON.
+    pub fn summary_line(&self) -> String {
+        ToString::to_string(self)
+    }
     
     /// Converts `AcarsMessage` to `String` and appends a `\n` to the end.
     pub fn to_string_newline(&self) -> MessageResult<String> {
@@ -44,24 +129,33 @@ impl AcarsMessage {
         }
     }
 
-    /// Converts `AcarsMessage` to a `String` encoded as bytes.
+    /// Converts `AcarsMessage` to JSON encoded as bytes.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
-        match self.to_string() {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes())
-        }
+        serde_json::to_vec(self)
     }
-    
-    /// Converts `AcarsMessage` to a `String` terminated with a `\n` and encoded as bytes.
+
+    /// Converts `AcarsMessage` to JSON encoded as bytes and terminated with a `\n`.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes_newline(&self) -> MessageResult<Vec<u8>> {
-        match self.to_string_newline() {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes())
-        }
+        let mut bytes: Vec<u8> = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    /// Serialises `AcarsMessage` as JSON straight into the provided writer.
+    ///
+    /// This avoids allocating an intermediate buffer for the output.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Serialises `AcarsMessage` as JSON straight into the provided writer and terminates it with a `\n`.
+    pub fn write_to_newline<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(&mut *writer, self)?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)
     }
 
     /// Clears a station name that may be set for `AcarsMessage`.
@@ -96,6 +190,19 @@ impl AcarsMessage {
         }
     }
 
+    /// Returns the app block for `AcarsMessage` if there is one.
+    pub fn get_app_details(&self) -> Option<&AppDetails> {
+        self.app.as_ref()
+    }
+
+    /// Applies the provided `AppDetailsTemplate`, see `AppDetailsTemplate` for how an existing app block is merged.
+    pub fn apply_app_template(&mut self, template: &AppDetailsTemplate) {
+        match self.app.as_mut() {
+            None => self.app = Some(template.details().clone()),
+            Some(app_details) => template.merge_into(app_details)
+        }
+    }
+
     pub fn clear_time(&mut self) {
         self.timestamp = None;
     }
@@ -103,7 +210,178 @@ impl AcarsMessage {
     pub fn get_time(&self) -> Option<f64> {
         self.timestamp.as_ref().copied()
     }
+
+    /// Returns the time acarsdec stamped the message with once decoded.
+    pub fn get_timestamp(&self) -> Option<MessageTimestamp> {
+        self.timestamp.map(|seconds| MessageTimestamp::new(seconds, TimestampSource::Decoder, TimestampPrecision::Microseconds))
+    }
+    
+    /// Replaces the station name with a stable pseudonym from the provided `StationRedactor`.
+    pub fn redact_ground_stations(&mut self, redactor: &StationRedactor) {
+        if let Some(station_id) = self.station_id.as_mut() {
+            *station_id = redactor.pseudonym(station_id);
+        }
+    }
+
+    /// Replaces the ICAO address and registration with stable pseudonyms from the provided `AircraftAnonymiser`.
+    pub fn anonymise_aircraft(&mut self, anonymiser: &AircraftAnonymiser) {
+        if let Some(icao) = self.icao.as_mut() {
+            *icao = anonymiser.pseudonym_icao(*icao);
+        }
+        if let Some(tail) = self.tail.as_mut() {
+            *tail = anonymiser.pseudonym_registration(tail);
+        }
+        #[cfg(feature = "enrichment")]
+        if let Some(registration) = self.aircraft_info.as_mut().and_then(|info| info.registration.as_mut()) {
+            *registration = anonymiser.pseudonym_registration(registration);
+        }
+    }
+
+    /// Returns true if the messages are the same apart from the fields in the given classes, see `FieldClass`.
+    pub fn eq_ignoring(&self, other: &AcarsMessage, ignored: &[FieldClass]) -> bool {
+        eq_ignoring(self, other, MessageFamily::Acars, ignored)
+    }
+
+    /// Returns the `FrameQuality` for `AcarsMessage`.
+    ///
+    /// acarsdec only outputs frames that pass their CRC, with `error` counting the bits it had to correct.
+    pub fn get_frame_quality(&self) -> FrameQuality {
+        match self.error {
+            Some(error) if error > 0 => FrameQuality::Corrected,
+            _ => FrameQuality::Ok,
+        }
+    }
+
+    /// Returns the `SourceKind` for `AcarsMessage` from its block ID, which is a digit on downlinks from an aircraft
+    /// and a letter on uplinks from a ground station.
+    pub fn source_kind(&self) -> SourceKind {
+        match self.block_id.as_deref().and_then(|block_id| block_id.chars().next()) {
+            Some(block_id) if block_id.is_ascii_digit() => SourceKind::Aircraft,
+            Some(block_id) if block_id.is_ascii_alphabetic() => SourceKind::GroundStation,
+            _ => SourceKind::Unknown,
+        }
+    }
+
+    /// Returns the number of bit errors acarsdec reported correcting.
+    ///
+    /// Some acarsdec builds report negative or larger than byte sized counts, so this is kept as an `i32`.
+    pub fn error_count(&self) -> Option<i32> {
+        self.error
+    }
+
+    /// Returns the signal level in dBm, whether acarsdec wrote it as a whole number, a decimal or a string.
+    pub fn level_dbm(&self) -> Option<f64> {
+        self.level.as_ref().map(LevelType::as_f64)
+    }
+
+    /// Returns the multi block reassembly status, if acarsdec reported one.
+    pub fn get_assembly_status(&self) -> Option<&AssemblyStatus> {
+        self.assstat.as_ref()
+    }
+
+    /// Returns whether the message is a response to an earlier one, if acarsdec reported it.
+    pub fn is_response(&self) -> Option<bool> {
+        self.is_response.map(|is_response| is_response != 0)
+    }
+
+    /// Returns whether the aircraft is on the ground, if acarsdec reported it.
+    pub fn is_on_ground(&self) -> Option<bool> {
+        self.is_onground.map(|is_onground| is_onground != 0)
+    }
+
+    /// Returns the frequency `AcarsMessage` was received on.
+    pub fn get_frequency(&self) -> Frequency {
+        self.freq
+    }
+
+    /// Returns the station name for `AcarsMessage` if one is set.
+    pub fn get_station_name(&self) -> Option<&str> {
+        self.station_id.as_deref()
+    }
+
+    /// Returns the ACARS label for `AcarsMessage` if one is set.
+    pub fn get_label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Returns the aircraft registration (tail) for `AcarsMessage` with any leading padding dots removed.
+    pub fn get_registration(&self) -> Option<&str> {
+        self.tail.as_deref().map(|tail| tail.trim_start_matches('.'))
+    }
+
+    /// Returns the flight number for `AcarsMessage` if one is set.
+    pub fn get_flight(&self) -> Option<&str> {
+        self.flight.as_deref()
+    }
+
+    /// Returns the message text for `AcarsMessage` if one is set.
+    pub fn get_text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Returns the `aircraft_info` block for `AcarsMessage` if it has been enriched.
+    #[cfg(feature = "enrichment")]
+    pub fn get_aircraft_info(&self) -> Option<&AircraftInfo> {
+        self.aircraft_info.as_ref()
+    }
+
+    /// Sets the `aircraft_info` block for `AcarsMessage`.
+    #[cfg(feature = "enrichment")]
+    pub fn set_aircraft_info(&mut self, info: AircraftInfo) {
+        self.aircraft_info = Some(info);
+    }
+
+    /// Returns `None` as acarsdec does not split out the sublabel.
+    pub fn get_sublabel(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the ICAO address for `AcarsMessage` if acarsdec looked one up.
+    pub fn get_icao(&self) -> Option<u32> {
+        self.icao
+    }
+
+    /// Normalises the message text using the provided `TextNormaliser`.
+    pub fn normalise_text(&mut self, normaliser: &TextNormaliser) {
+        if let Some(text) = self.text.as_mut() {
+            normaliser.normalise_in_place(text);
+        }
+    }
+
+    /// Returns the link layer fields used to pair `AcarsMessage` with its acknowledgement.
+    ///
+    /// acarsdec outputs `false` in the `ack` field for a NAK.
+    pub fn get_ack_fields(&self) -> Option<AckFields<'_>> {
+        Some(AckFields {
+            mode: self.mode.as_deref()?,
+            registration: self.get_registration()?,
+            label: self.label.as_deref().unwrap_or_default(),
+            block_id: self.block_id.as_deref().unwrap_or_default(),
+            msgno: self.msgno.as_deref(),
+            ack: match &self.ack {
+                Some(AckType::String(ack)) => AckFields::parse_ack(ack),
+                _ => None,
+            },
+        })
+    }
     
+    /// Passes the parts of `AcarsMessage` to the visitor, see `visit::MessageVisitor`.
+    pub fn visit(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.on_signal_info(&SignalInfo {
+            frequency: self.freq,
+            level_dbm: self.level_dbm(),
+            noise_level_dbm: None,
+            freq_skew_hz: None,
+            quality: self.get_frame_quality(),
+        });
+        if let Some(app) = &self.app {
+            visitor.on_app_details(app);
+        }
+        if let Some(link) = self.get_ack_fields() {
+            visitor.on_acars_block(&AcarsBlock { link, flight: self.get_flight(), sublabel: None, text: self.get_text() });
+        }
+    }
+
     pub fn clear_channel(&mut self) {
         self.channel = None;
     }
@@ -117,29 +395,54 @@ impl AcarsMessage {
     }
 }
 
+/// Renders a concise single line summary of the message, intended for logging rather than forwarding.
+///
+/// `summary_line()` returns it as a `String`, and `to_json()` returns the JSON.
+impl fmt::Display for AcarsMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        SummaryLine {
+            family: "ACARS",
+            time: self.get_time(),
+            station: self.get_station_name(),
+            freq_mhz: self.freq.as_mhz(),
+            registration: self.get_registration(),
+            flight: self.get_flight(),
+            label: self.get_label(),
+            text: self.get_text(),
+        }.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Default)]
 pub struct AcarsMessage {
-    pub freq: f64,
+    pub freq: Frequency,
+    #[serde(default, deserialize_with = "option_number_or_string")]
     pub channel: Option<u16>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub error: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub level: Option<LevelType>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub timestamp: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub app: Option<AppDetails>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Station name set by the feeder, also read from `station` as dumpvdl2 and dumphfdl name it.
+    #[serde(alias = "station", skip_serializing_if = "Option::is_none")]
     pub station_id: Option<String>,
+    /// Multi block reassembly status written by acarsdec builds using libacars, see `AssemblyStatus`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub assstat: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assstat: Option<AssemblyStatus>,
+    /// Address of the aircraft, as a number rather than hex, when acarsdec has one.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub icao: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Address the message is sent to, as a number rather than hex, when acarsdec has one.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub toaddr: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// `1` when the message is a response to an earlier one and `0` otherwise, see `is_response()`.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub is_response: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// `1` when the aircraft is on the ground and `0` otherwise, see `is_on_ground()`.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub is_onground: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
@@ -156,7 +459,10 @@ pub struct AcarsMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub msgno: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub flight: Option<String>
+    pub flight: Option<String>,
+    #[cfg(feature = "enrichment")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aircraft_info: Option<AircraftInfo>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd)]
@@ -166,12 +472,121 @@ pub enum LevelType {
     Float64(f64)
 }
 
+impl LevelType {
+    /// Returns the level as an `f64` whichever way it was written.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            LevelType::I32(level) => *level as f64,
+            LevelType::Float64(level) => *level,
+        }
+    }
+}
+
 impl Default for LevelType {
     fn default() -> Self {
         Self::I32(0)
     }
 }
 
+/// Parses a quoted level, keeping whole numbers as `LevelType::I32` the same as an unquoted level.
+impl FromStr for LevelType {
+    type Err = ParseFloatError;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.parse::<i32>() {
+            Ok(level) => Ok(Self::I32(level)),
+            Err(_) => level.parse::<f64>().map(Self::Float64),
+        }
+    }
+}
+
+/// Multi block reassembly status of an ACARS message, the `assstat` field written by acarsdec builds using libacars.
+///
+/// The known states are the names libacars gives them. Anything else, such as a state added by a newer libacars,
+/// is kept as `Other` and serialised back unchanged.
+/// ```
+/// use acars_vdlm2_parser::acars::{AcarsMessage, AssemblyStatus, NewAcarsMessage};
+/// let message: AcarsMessage = r#"{"freq":131.55,"channel":2,"assstat":"in progress"}"#.to_acars().unwrap();
+/// assert_eq!(message.get_assembly_status(), Some(&AssemblyStatus::InProgress));
+/// let unknown: AcarsMessage = r#"{"freq":131.55,"channel":2,"assstat":"timed out"}"#.to_acars().unwrap();
+/// assert_eq!(unknown.get_assembly_status(), Some(&AssemblyStatus::Other("timed out".to_string())));
+/// assert!(unknown.to_json().unwrap().contains(r#""assstat":"timed out""#));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum AssemblyStatus {
+    /// libacars could not tell whether the message was part of a multi block message.
+    Unknown,
+    /// The final block arrived and the message was reassembled.
+    Complete,
+    /// More blocks are expected before the message can be reassembled.
+    InProgress,
+    /// Reassembly was not attempted, usually because the message fits in a single block.
+    Skipped,
+    /// The block had already been received.
+    Duplicate,
+    /// The block arrived out of sequence and the partial message was discarded.
+    OutOfSequence,
+    /// libacars was passed invalid arguments, which points to a decoder bug.
+    InvalidArgs,
+    /// A state not listed above, kept as written.
+    Other(String),
+}
+
+impl AssemblyStatus {
+    /// Returns the status as acarsdec writes it.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AssemblyStatus::Unknown => "unknown",
+            AssemblyStatus::Complete => "complete",
+            AssemblyStatus::InProgress => "in progress",
+            AssemblyStatus::Skipped => "skipped",
+            AssemblyStatus::Duplicate => "duplicate",
+            AssemblyStatus::OutOfSequence => "out of sequence",
+            AssemblyStatus::InvalidArgs => "invalid args",
+            AssemblyStatus::Other(status) => status,
+        }
+    }
+
+    /// Returns true if the message text is complete, either because it was reassembled or did not need to be.
+    pub fn is_complete(&self) -> bool {
+        matches!(self, AssemblyStatus::Complete | AssemblyStatus::Skipped)
+    }
+}
+
+impl From<&str> for AssemblyStatus {
+    fn from(status: &str) -> Self {
+        match status {
+            "unknown" => AssemblyStatus::Unknown,
+            "complete" => AssemblyStatus::Complete,
+            "in progress" => AssemblyStatus::InProgress,
+            "skipped" => AssemblyStatus::Skipped,
+            "duplicate" => AssemblyStatus::Duplicate,
+            "out of sequence" => AssemblyStatus::OutOfSequence,
+            "invalid args" => AssemblyStatus::InvalidArgs,
+            other => AssemblyStatus::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for AssemblyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for AssemblyStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AssemblyStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let status: String = String::deserialize(deserializer)?;
+        Ok(AssemblyStatus::from(status.as_str()))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(untagged)]
 pub enum AckType {
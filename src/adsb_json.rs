@@ -0,0 +1,360 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::MessageResult;
+use crate::coerce::via_value;
+use crate::icao::parse_icao_hex;
+use crate::roundtrip::{diff_values, RoundtripReport};
+use crate::source::SourceKind;
+
+/// Value readsb writes in `alt_baro` for an aircraft reporting that it is on the ground.
+const ON_GROUND: &str = "ground";
+/// Source types of data sent by the aircraft itself, including MLAT of its Mode S replies.
+const AIRCRAFT_SOURCE_TYPES: [&str; 4] = ["adsb_icao", "adsb_other", "mode_s", "mlat"];
+/// Source types of data rebroadcast by ground stations.
+const GROUND_STATION_SOURCE_TYPES: [&str; 5] = ["tisb_icao", "tisb_other", "tisb_trackfile", "adsr_icao", "adsr_other"];
+
+/// The `aircraft.json` file readsb, tar1090 and dump1090 write, with every aircraft currently tracked.
+/// ```
+/// use acars_vdlm2_parser::adsb_json::{AdsbJsonFile, AdsbJsonMessage};
+/// let file: AdsbJsonFile = r#"{"now":1700000000.0,"messages":1234,"aircraft":[
+///     {"hex":"a1b2c3","type":"adsb_icao","flight":"AB0123  ","alt_baro":"ground","lat":47.44,"lon":-122.30,"seen":0.4,"rssi":-18.2}
+/// ]}"#.parse().unwrap();
+/// let aircraft: &AdsbJsonMessage = &file.aircraft[0];
+/// assert_eq!(aircraft.get_icao(), Some(0xA1B2C3));
+/// assert_eq!(aircraft.get_flight(), Some("AB0123"));
+/// assert!(aircraft.is_on_ground());
+/// assert_eq!(aircraft.get_position(), Some((47.44, -122.30)));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AdsbJsonFile {
+    /// Time the file was written, in seconds since the Unix epoch.
+    pub now: f64,
+    /// Total number of Mode S messages received since the decoder started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<u64>,
+    #[serde(default)]
+    pub aircraft: Vec<AdsbJsonMessage>,
+}
+
+/// Decodes an `aircraft.json` file.
+impl FromStr for AdsbJsonFile {
+    type Err = serde_json::Error;
+
+    fn from_str(input: &str) -> MessageResult<Self> {
+        serde_json::from_str(input)
+    }
+}
+
+impl AdsbJsonFile {
+    /// Converts `AdsbJsonFile` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+}
+
+/// A single aircraft entry of a readsb `aircraft.json` file, or of the readsb and tar1090 APIs that return the same
+/// entries.
+///
+/// Every field readsb documents is modelled and optional, as which fields are present depends on what the aircraft
+/// has transmitted recently, on the decoder and on whether an aircraft database is loaded. Fields from decoders this
+/// library does not know about are ignored rather than rejected, so newer readsb releases still decode, but are not
+/// written back out. Field names follow readsb, including its camel case database fields.
+/// ```
+/// use acars_vdlm2_parser::adsb_json::AdsbJsonMessage;
+/// let aircraft: AdsbJsonMessage = r#"{"hex":"~2d6e2b","type":"tisb_other","alt_baro":3500,"mlat":[],"tisb":["lat","lon"]}"#.parse().unwrap();
+/// assert_eq!(aircraft.get_icao(), None);
+/// assert!(aircraft.is_non_icao());
+/// assert_eq!(aircraft.get_altitude(), Some(3500));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AdsbJsonMessage {
+    /// 24 bit address as 6 hex characters, prefixed with `~` when it is not an ICAO address.
+    pub hex: String,
+    /// Source of the data shown for the aircraft, such as `adsb_icao`, `mlat` or `tisb_other`.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub source_type: Option<String>,
+    /// Callsign, padded with spaces to 8 characters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flight: Option<String>,
+    // Aircraft database fields, only present when readsb has a database loaded.
+    /// Registration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r: Option<String>,
+    /// ICAO aircraft type designator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    #[serde(default, rename = "ownOp", skip_serializing_if = "Option::is_none")]
+    pub own_op: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<String>,
+    /// Bit field of military, interesting, PIA and LADD flags.
+    #[serde(default, rename = "dbFlags", skip_serializing_if = "Option::is_none")]
+    pub db_flags: Option<u32>,
+    // Altitude, speed and direction.
+    /// Barometric altitude in feet, or `ground`.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "via_value")]
+    pub alt_baro: Option<BaroAltitude>,
+    /// Geometric (GNSS) altitude in feet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_geom: Option<i32>,
+    /// Ground speed in knots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gs: Option<f64>,
+    /// Indicated air speed in knots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ias: Option<u32>,
+    /// True air speed in knots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tas: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mach: Option<f64>,
+    /// Outside and total air temperature in degrees Celsius, calculated from the speeds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oat: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tat: Option<i32>,
+    /// Wind direction in degrees and speed in knots, calculated from the speeds and headings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wd: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws: Option<i32>,
+    /// True track over ground in degrees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<f64>,
+    /// Track calculated from successive positions, when the aircraft does not send one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calc_track: Option<f64>,
+    /// Rate of change of track in degrees a second.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_rate: Option<f64>,
+    /// Roll in degrees, negative is left roll.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mag_heading: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub true_heading: Option<f64>,
+    /// Rate of change of barometric and geometric altitude in feet a minute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baro_rate: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geom_rate: Option<i32>,
+    // Identification and status.
+    /// Mode A code as 4 octal digits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub squawk: Option<String>,
+    /// Emergency or priority status, `none` when there is none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<String>,
+    /// Emitter category, such as `A3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    // Selected values from the autopilot.
+    /// Altimeter setting in hPa.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nav_qnh: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nav_altitude_mcp: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nav_altitude_fms: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nav_heading: Option<f64>,
+    /// Engaged automation modes, such as `autopilot`, `vnav`, `althold`, `approach`, `lnav` and `tcas`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nav_modes: Option<Vec<String>>,
+    // Position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f64>,
+    /// Navigation Integrity Category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nic: Option<u8>,
+    /// Radius of containment in metres, derived from the NIC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rc: Option<u32>,
+    /// Seconds since the position was last updated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seen_pos: Option<f64>,
+    /// Distance in nautical miles and bearing in degrees from the receiver, when its location is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r_dst: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r_dir: Option<f64>,
+    /// Last known position, given instead of `lat` and `lon` once the position is older than readsb shows.
+    #[serde(default, rename = "lastPosition", skip_serializing_if = "Option::is_none")]
+    pub last_position: Option<AdsbLastPosition>,
+    /// Rough position estimated from the receivers that heard the aircraft, when there is no reported position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rr_lat: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rr_lon: Option<f64>,
+    /// Seconds since, and position of, the last GPS position before the aircraft lost GPS.
+    #[serde(default, rename = "gpsOkBefore", skip_serializing_if = "Option::is_none")]
+    pub gps_ok_before: Option<f64>,
+    #[serde(default, rename = "gpsOkLat", skip_serializing_if = "Option::is_none")]
+    pub gps_ok_lat: Option<f64>,
+    #[serde(default, rename = "gpsOkLon", skip_serializing_if = "Option::is_none")]
+    pub gps_ok_lon: Option<f64>,
+    // Accuracy and integrity.
+    /// ADS-B version, 0, 1 or 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nic_baro: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nac_p: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nac_v: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sil: Option<u8>,
+    /// Whether the SIL is `perhour` or `persample`, or `unknown`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sil_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gva: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sda: Option<u8>,
+    /// Flight status alert bit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert: Option<u8>,
+    /// Flight status special position identification bit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spi: Option<u8>,
+    /// Last ACAS resolution advisory, kept as readsb wrote it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acas_ra: Option<Value>,
+    // Reception.
+    /// Fields whose values came from MLAT and from TIS-B.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mlat: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tisb: Option<Vec<String>>,
+    /// Number of Mode S messages received from the aircraft.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<u64>,
+    /// Seconds since a message was last received from the aircraft.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seen: Option<f64>,
+    /// Average signal power of recent messages in dBFS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rssi: Option<f64>,
+}
+
+/// Decodes a single aircraft entry.
+impl FromStr for AdsbJsonMessage {
+    type Err = serde_json::Error;
+
+    fn from_str(input: &str) -> MessageResult<Self> {
+        serde_json::from_str(input)
+    }
+}
+
+impl AdsbJsonMessage {
+    /// Converts `AdsbJsonMessage` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Returns the ICAO address, or `None` for addresses readsb marks with `~` as not being ICAO addresses.
+    pub fn get_icao(&self) -> Option<u32> {
+        match self.is_non_icao() {
+            true => None,
+            false => parse_icao_hex(&self.hex),
+        }
+    }
+
+    /// Returns true if the address is not an ICAO address, such as a TIS-B track file number.
+    pub fn is_non_icao(&self) -> bool {
+        self.hex.starts_with('~')
+    }
+
+    /// Returns the callsign without its padding, if one has been received.
+    pub fn get_flight(&self) -> Option<&str> {
+        self.flight.as_deref().map(str::trim).filter(|flight| !flight.is_empty())
+    }
+
+    /// Returns the registration from the aircraft database, if one is loaded.
+    pub fn get_registration(&self) -> Option<&str> {
+        self.r.as_deref()
+    }
+
+    /// Returns the barometric altitude in feet, or `None` on the ground or when it is not known.
+    pub fn get_altitude(&self) -> Option<i32> {
+        match &self.alt_baro {
+            Some(BaroAltitude::Feet(feet)) => Some(*feet),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the aircraft reports that it is on the ground.
+    pub fn is_on_ground(&self) -> bool {
+        matches!(&self.alt_baro, Some(BaroAltitude::Text(text)) if text == ON_GROUND)
+    }
+
+    /// Returns the current position as latitude and longitude, if there is one.
+    ///
+    /// The `lastPosition` and rough receiver positions are not used, see `last_position`, `rr_lat` and `rr_lon`.
+    pub fn get_position(&self) -> Option<(f64, f64)> {
+        self.lat.zip(self.lon)
+    }
+
+    /// Returns true if any of the fields shown came from MLAT.
+    pub fn is_mlat(&self) -> bool {
+        self.mlat.as_ref().is_some_and(|fields| !fields.is_empty())
+    }
+
+    /// Returns the `SourceKind` from the source type. DF18 non-transponder emitters (`adsb_icao_nt`), which may be
+    /// surface vehicles, and unknown source types are `Unknown`.
+    pub fn source_kind(&self) -> SourceKind {
+        match self.source_type.as_deref() {
+            Some(source_type) if AIRCRAFT_SOURCE_TYPES.contains(&source_type) => SourceKind::Aircraft,
+            Some(source_type) if GROUND_STATION_SOURCE_TYPES.contains(&source_type) => SourceKind::GroundStation,
+            _ => SourceKind::Unknown,
+        }
+    }
+}
+
+/// Barometric altitude, which readsb writes as a number of feet or as `ground`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum BaroAltitude {
+    Feet(i32),
+    Text(String),
+}
+
+/// The last known position of an aircraft, see `AdsbJsonMessage::last_position`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AdsbLastPosition {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nic: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rc: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seen_pos: Option<f64>,
+}
+
+/// Decodes an `aircraft.json` file and writes it back out, reporting any field that was lost or changed, as
+/// `roundtrip::roundtrip_check` does for the message families.
+pub fn roundtrip_check(input: &str) -> RoundtripReport {
+    let original: Value = match serde_json::from_str(input) {
+        Err(parse_error) => return RoundtripReport::failed(format!("input is not valid JSON: {}", parse_error)),
+        Ok(original) => original,
+    };
+    let file: AdsbJsonFile = match serde_json::from_value(original.clone()) {
+        Err(decode_error) => return RoundtripReport::failed(format!("decode failed: {}", decode_error)),
+        Ok(file) => file,
+    };
+    let reencoded: Value = match serde_json::to_value(&file) {
+        Err(encode_error) => return RoundtripReport::failed(format!("serialisation failed: {}", encode_error)),
+        Ok(reencoded) => reencoded,
+    };
+    let mut report: RoundtripReport = RoundtripReport::default();
+    diff_values("", &original, &reencoded, &mut report.differences);
+    report
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::coerce::via_value;
+use crate::redact::AircraftAnonymiser;
+
+/// ADS-C (Automatic Dependent Surveillance - Contract) message as decoded by libacars 2.x.
+///
+/// This is shared by VDLM2 and HFDL as both decoders hand ARINC 622 payloads to libacars.
+/// Every tag libacars 2.x emits is typed, including the contract cancellations, the emergency and event reports and
+/// the airframe ID. A tag this library does not know, or a known tag with fields it does not know, is kept as
+/// `AdscTag::Other` so nothing is lost when re-serialising.
+/// ```
+/// use acars_vdlm2_parser::adsc::{Adsc, AdscTag};
+/// let adsc: Adsc = serde_json::from_str(r#"{"tags":[{"ack":{"contract_num":59}},{"new_tag":{"value":1}}],"err":false}"#).unwrap();
+/// assert!(matches!(&adsc.tags[0], AdscTag::Ack(ack) if ack.contract_num == 59));
+/// assert_eq!(adsc.other_tags().count(), 1);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Adsc {
+    #[serde(deserialize_with = "via_value")]
+    pub tags: Vec<AdscTag>,
+    pub err: bool,
+}
+
+impl Adsc {
+    /// Returns the position reports in the block, from the basic and emergency reports and any event reports.
+    pub fn position_reports(&self) -> impl Iterator<Item = &AdscBasicReport> {
+        self.tags.iter().filter_map(|tag| match tag {
+            AdscTag::BasicReport(report)
+            | AdscTag::EmergencyBasicReport(report)
+            | AdscTag::AltRangeEvent(report)
+            | AdscTag::WptChangeEvent(report)
+            | AdscTag::LateralDevChangeEvent(report)
+            | AdscTag::VspdChangeEvent(report) => Some(report),
+            _ => None,
+        })
+    }
+
+    /// Returns the tags that were not recognised and have been kept as raw JSON.
+    pub fn other_tags(&self) -> impl Iterator<Item = &Value> {
+        self.tags.iter().filter_map(|tag| match tag {
+            AdscTag::Other(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Replaces the ICAO address in the airframe ID tags with stable pseudonyms from the provided `AircraftAnonymiser`.
+    pub fn anonymise_aircraft(&mut self, anonymiser: &AircraftAnonymiser) {
+        for tag in self.tags.iter_mut() {
+            if let AdscTag::AirframeId(airframe) = tag {
+                airframe.icao_hex = anonymiser.pseudonym_icao_hex(&airframe.icao_hex);
+            }
+        }
+    }
+}
+
+/// A single ADS-C tag, keyed by the name libacars uses in its JSON output.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdscTag {
+    // Uplink contract requests and cancellations, and downlink responses to them.
+    PeriodicContractReq(AdscContractRequest),
+    EventContractReq(AdscContractRequest),
+    EmergencyPeriodicContractReq(AdscContractRequest),
+    CancelContract(AdscCancelContract),
+    CancelAllContracts {},
+    CancelEmergencyMode {},
+    Ack(AdscAck),
+    Nack(AdscNack),
+    NoncompNotify(AdscNonCompNotify),
+    Reason(AdscReason),
+    // Downlink reports.
+    BasicReport(AdscBasicReport),
+    EmergencyBasicReport(AdscBasicReport),
+    AltRangeEvent(AdscBasicReport),
+    WptChangeEvent(AdscBasicReport),
+    LateralDevChangeEvent(AdscBasicReport),
+    VspdChangeEvent(AdscBasicReport),
+    FlightId(AdscFlightId),
+    AirframeId(AdscAirframeId),
+    PredictedRoute(AdscPredictedRoute),
+    EarthRefData(AdscEarthRefData),
+    AirRefData(AdscAirRefData),
+    MeteoData(AdscMeteoData),
+    IntermediateProjection(AdscIntermediateProjection),
+    FixedProjection(AdscFixedProjection),
+    /// A tag that is not recognised, kept exactly as it was received.
+    #[serde(untagged)]
+    Other(Value),
+}
+
+/// Contract request sent by a ground station, listing the report groups it wants.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscContractRequest {
+    pub contract_num: u16,
+    pub groups: Vec<AdscContractGroup>,
+}
+
+/// A report group requested in an ADS-C contract.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdscContractGroup {
+    ReportInterval(AdscReportInterval),
+    FlightId(AdscModulus),
+    PredictedRoute(AdscModulus),
+    EarthRefData(AdscModulus),
+    AirRefData(AdscModulus),
+    MeteoData(AdscModulus),
+    AirframeId(AdscModulus),
+    ReportWhenAltOutOfRange(AdscAltRange),
+    ReportWhenLateralDevExceeds(AdscLateralDeviation),
+    ReportWhenVspdExceeds(AdscVspdThreshold),
+    ReportWptChanges {},
+    /// A group that is not recognised, kept exactly as it was received.
+    #[serde(untagged)]
+    Other(Value),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscReportInterval {
+    pub interval_secs: u32,
+}
+
+/// How often a group is included, as a multiple of the reporting interval.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscModulus {
+    pub modulus: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscAltRange {
+    pub floor_alt: i32,
+    pub ceiling_alt: i32,
+}
+
+/// The `treshold` spelling matches libacars.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscLateralDeviation {
+    pub lat_dev_treshold_nm: f64,
+}
+
+/// A vertical speed in feet per minute. A positive threshold reports climbs faster than it, and a negative one
+/// descents faster than it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscVspdThreshold {
+    pub vspd_threshold_ftmin: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscCancelContract {
+    pub contract_num: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscAck {
+    pub contract_num: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscNack {
+    pub contract_req_num: u16,
+    pub reason: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscNonCompNotify {
+    pub contract_req_num: u16,
+    pub msg_groups: Vec<AdscNonCompGroup>,
+}
+
+/// A requested group the aircraft could not comply with, and why.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscNonCompGroup {
+    pub noncomp_tag: u16,
+    pub noncomp_cause: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscReason {
+    pub reason_code: u16,
+}
+
+/// Position report, used for the basic report and for each of the event reports.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscBasicReport {
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: i32,
+    /// Seconds past the hour.
+    pub ts_sec: f64,
+    pub pos_accuracy_nm: f64,
+    pub nav_redundancy: bool,
+    pub tcas_avail: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscFlightId {
+    pub flight_id: String,
+}
+
+/// The 24 bit ICAO address of the airframe, as 6 hex characters.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscAirframeId {
+    pub icao_hex: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscPredictedRoute {
+    pub next_wpt: AdscWaypoint,
+    pub next_next_wpt: AdscWaypoint,
+}
+
+/// A waypoint in a predicted route. Only the next waypoint carries an ETA.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscWaypoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_sec: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscEarthRefData {
+    pub true_trk_deg: f64,
+    pub true_trk_valid: bool,
+    pub gnd_spd_kts: f64,
+    pub vspd_ftmin: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscAirRefData {
+    pub true_hdg_deg: f64,
+    pub true_hdg_valid: bool,
+    pub spd_mach: f64,
+    pub vspd_ftmin: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscMeteoData {
+    pub wind_spd_kts: f64,
+    pub wind_dir_true_deg: f64,
+    pub wind_dir_valid: bool,
+    pub temp_c: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscIntermediateProjection {
+    pub dist_nm: f64,
+    pub true_trk_deg: f64,
+    pub true_trk_valid: bool,
+    pub alt: i32,
+    pub eta_sec: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AdscFixedProjection {
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: i32,
+    pub eta_sec: u32,
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::coerce::via_value;
+use crate::adsc::{Adsc, AdscBasicReport, AdscContractGroup, AdscContractRequest, AdscTag};
+
+/// The kind of ADS-C contract a ground station requested.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractKind {
+    /// Reports at a fixed interval.
+    Periodic,
+    /// Reports when an event such as an altitude or waypoint change happens.
+    Event,
+    /// Reports at a fixed interval while the aircraft is in emergency mode.
+    EmergencyPeriodic,
+}
+
+/// The state of an ADS-C contract after a request, acknowledgement or cancellation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractState {
+    /// The ground station sent the request and the aircraft has not answered yet.
+    Requested,
+    /// The aircraft acknowledged the request.
+    Established,
+    /// The aircraft refused the request with a nack.
+    Rejected,
+    /// The ground station cancelled the contract, or all contracts, or ended emergency mode.
+    Cancelled,
+    /// The aircraft did not answer the request within the tracker's request timeout.
+    Expired,
+}
+
+/// An ADS-C contract between an aircraft and a ground station, as reconstructed by a `ContractTracker`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AdscContract {
+    pub contract_num: u16,
+    pub kind: ContractKind,
+    /// Reporting interval of periodic contracts, where 0 asks for a single report on demand.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u32>,
+    /// Report groups requested in the contract.
+    #[serde(deserialize_with = "via_value")]
+    pub groups: Vec<AdscContractGroup>,
+    pub state: ContractState,
+    /// Time of the message carrying the request, if it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_time: Option<f64>,
+}
+
+/// A change in the state of a contract, recorded by a `ContractTracker`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContractEvent {
+    /// Registration of the aircraft, from the ARINC 622 air address without its padding dots.
+    pub aircraft: String,
+    /// The 7 character ARINC 622 address of the ground station.
+    pub ground_station: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
+    pub contract: AdscContract,
+}
+
+/// An ADS-C position report annotated with the contract it was sent for.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContractReport {
+    /// Registration of the aircraft, from the ARINC 622 air address without its padding dots.
+    pub aircraft: String,
+    /// The 7 character ARINC 622 address of the ground station.
+    pub ground_station: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
+    pub report: AdscBasicReport,
+    /// True for event reports, such as an altitude range or vertical speed change, rather than a basic report.
+    pub event: bool,
+    /// Number of the contract the report was sent for, or `None` if no matching contract was seen. A report following
+    /// an acknowledgement keeps the acknowledged number even if the request was not seen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_num: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ContractKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u32>,
+}
+
+/// Follows ADS-C contracts per aircraft and ground station, and annotates reports with the contract they answer.
+///
+/// A contract request from a ground station is `Requested` until the aircraft acknowledges it, which makes it
+/// `Established`, or refuses it with a nack. It stays established until the ground station cancels it, cancels all
+/// contracts, or, for an emergency contract, ends emergency mode. ADS-C reports do not carry a contract number, so a
+/// report following an acknowledgement in the same message answers that contract, an emergency report answers the emergency contract,
+/// event reports answer the event contract and other basic reports answer the periodic contract. Contracts are keyed
+/// on the ARINC 622 addresses, so VDLM2 and HFDL traffic for the same aircraft and ground station share them.
+///
+/// A request that is still not acknowledged once the message time has moved more than the request timeout past it,
+/// as when the downlink answering it was missed, becomes `Expired`. Requests without a time never expire. Every
+/// change is kept as a `ContractEvent` until `take_events` is called, so a long running tracker must call it regularly.
+/// ```
+/// use acars_vdlm2_parser::AcarsVdlm2Message;
+/// use acars_vdlm2_parser::adsc_contract::{ContractKind, ContractReport, ContractState, ContractTracker};
+/// let request: AcarsVdlm2Message = r#"{"vdl2":{"freq":136975000,"burst_len_octets":75,"idx":0,"t":{"sec":1664123814,"usec":0},"avlc":{"src":{"addr":"10210A","type":"Ground station","status":"On ground"},"dst":{"addr":"A56E0D","type":"Aircraft"},"cr":"Command","frame_type":"I","rseq":7,"sseq":7,"poll":false,"acars":{"err":false,"crc_ok":true,"more":false,"reg":".N449WN","mode":"2","label":"H1","blk_id":"G","ack":"!","msg_text":"","arinc622":{"msg_type":"adsc_msg","crc_ok":true,"gs_addr":"QXHADS2","air_addr":".N449WN","adsc":{"tags":[{"periodic_contract_req":{"contract_num":52,"groups":[{"report_interval":{"interval_secs":320}}]}}],"err":false}}}}}}"#.parse().unwrap();
+/// let report: AcarsVdlm2Message = r#"{"vdl2":{"freq":136975000,"burst_len_octets":64,"idx":0,"t":{"sec":1664124134,"usec":0},"avlc":{"src":{"addr":"A56E0D","type":"Aircraft","status":"Airborne"},"dst":{"addr":"10210A","type":"Ground station"},"cr":"Command","frame_type":"I","rseq":0,"sseq":1,"poll":false,"acars":{"err":false,"crc_ok":true,"more":false,"reg":".N449WN","mode":"2","label":"H1","blk_id":"1","ack":"!","msg_text":"","arinc622":{"msg_type":"adsc_msg","crc_ok":true,"gs_addr":"QXHADS2","air_addr":".N449WN","adsc":{"tags":[{"ack":{"contract_num":52}},{"basic_report":{"lat":35.1,"lon":-106.4,"alt":37004,"ts_sec":1202.0,"pos_accuracy_nm":0.05,"nav_redundancy":true,"tcas_avail":true}}],"err":false}}}}}}"#.parse().unwrap();
+/// let mut tracker: ContractTracker = ContractTracker::new();
+/// assert!(tracker.record(&request).is_empty());
+/// let reports: Vec<ContractReport> = tracker.record(&report);
+/// assert_eq!((reports[0].contract_num, reports[0].kind, reports[0].interval_secs), (Some(52), Some(ContractKind::Periodic), Some(320)));
+/// assert_eq!(tracker.contracts("N449WN", "QXHADS2").next().map(|contract| contract.state), Some(ContractState::Established));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractTracker {
+    contracts: HashMap<(String, String), Vec<AdscContract>>,
+    events: Vec<ContractEvent>,
+    request_timeout_secs: f64,
+}
+
+impl Default for ContractTracker {
+    fn default() -> Self {
+        Self { contracts: HashMap::new(), events: Vec::new(), request_timeout_secs: Self::DEFAULT_REQUEST_TIMEOUT_SECS }
+    }
+}
+
+impl ContractTracker {
+    /// Requests not acknowledged within this time expire unless it is changed with `request_timeout_secs`.
+    pub const DEFAULT_REQUEST_TIMEOUT_SECS: f64 = 600.0;
+
+    /// Creates a new `ContractTracker` with no contracts seen.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long a request may wait for an acknowledgement or nack before it expires.
+    pub fn request_timeout_secs(mut self, request_timeout_secs: f64) -> Self {
+        self.request_timeout_secs = request_timeout_secs;
+        self
+    }
+
+    /// Records a single message, returning the position reports it carries annotated with their contract.
+    ///
+    /// The time of any message expires stale requests, and messages without an ADS-C block are otherwise ignored.
+    pub fn record(&mut self, message: &AcarsVdlm2Message) -> Vec<ContractReport> {
+        let time: Option<f64> = message.get_time();
+        if let Some(time) = time {
+            self.expire(time);
+        }
+        let Some((ground_station, air_addr, adsc)) = adsc_block(message) else {
+            return Vec::new();
+        };
+        let key: (String, String) = (air_addr.trim_start_matches('.').to_string(), ground_station.to_string());
+        let mut changed: Vec<AdscContract> = Vec::new();
+        let contracts: &mut Vec<AdscContract> = self.contracts.entry(key.clone()).or_default();
+        for tag in &adsc.tags {
+            match tag {
+                AdscTag::PeriodicContractReq(request) => changed.push(request_contract(contracts, request, ContractKind::Periodic, time)),
+                AdscTag::EventContractReq(request) => changed.push(request_contract(contracts, request, ContractKind::Event, time)),
+                AdscTag::EmergencyPeriodicContractReq(request) => {
+                    changed.push(request_contract(contracts, request, ContractKind::EmergencyPeriodic, time));
+                }
+                AdscTag::Ack(ack) => {
+                    changed.extend(set_state(contracts, |contract| contract.contract_num == ack.contract_num, ContractState::Established));
+                }
+                AdscTag::Nack(nack) => {
+                    changed.extend(set_state(contracts, |contract| contract.contract_num == nack.contract_req_num, ContractState::Rejected));
+                }
+                AdscTag::CancelContract(cancel) => {
+                    changed.extend(set_state(contracts, |contract| contract.contract_num == cancel.contract_num, ContractState::Cancelled));
+                }
+                AdscTag::CancelAllContracts {} => changed.extend(set_state(contracts, |_| true, ContractState::Cancelled)),
+                AdscTag::CancelEmergencyMode {} => {
+                    changed.extend(set_state(contracts, |contract| contract.kind == ContractKind::EmergencyPeriodic, ContractState::Cancelled));
+                }
+                _ => {}
+            }
+        }
+        let mut acknowledged: Option<u16> = None;
+        let reports: Vec<ContractReport> = adsc.tags.iter().filter_map(|tag| {
+            let (report, event, kind): (&AdscBasicReport, bool, ContractKind) = match tag {
+                AdscTag::Ack(ack) => {
+                    acknowledged = Some(ack.contract_num);
+                    return None;
+                }
+                AdscTag::BasicReport(report) => (report, false, ContractKind::Periodic),
+                AdscTag::EmergencyBasicReport(report) => (report, false, ContractKind::EmergencyPeriodic),
+                AdscTag::AltRangeEvent(report)
+                | AdscTag::WptChangeEvent(report)
+                | AdscTag::LateralDevChangeEvent(report)
+                | AdscTag::VspdChangeEvent(report) => (report, true, ContractKind::Event),
+                _ => return None,
+            };
+            let contract: Option<&AdscContract> = match acknowledged {
+                Some(contract_num) => contracts.iter().find(|contract| contract.contract_num == contract_num),
+                None => contracts.iter().rev().find(|contract| contract.kind == kind && contract.state == ContractState::Established),
+            };
+            Some(ContractReport {
+                aircraft: key.0.clone(),
+                ground_station: key.1.clone(),
+                time,
+                report: *report,
+                event,
+                contract_num: contract.map(|contract| contract.contract_num).or(acknowledged),
+                kind: contract.map(|contract| contract.kind),
+                interval_secs: contract.and_then(|contract| contract.interval_secs),
+            })
+        }).collect();
+        contracts.retain(|contract| matches!(contract.state, ContractState::Requested | ContractState::Established));
+        if contracts.is_empty() {
+            self.contracts.remove(&key);
+        }
+        self.events.extend(changed.into_iter().map(|contract| ContractEvent {
+            aircraft: key.0.clone(),
+            ground_station: key.1.clone(),
+            time,
+            contract,
+        }));
+        reports
+    }
+
+    /// Expires the requests still waiting for an answer more than the request timeout before `time`, returning the
+    /// number expired.
+    ///
+    /// `record` calls this with the time of every message, so it only needs calling directly to expire requests when
+    /// no traffic is arriving.
+    pub fn expire(&mut self, time: f64) -> usize {
+        let cutoff: f64 = time - self.request_timeout_secs;
+        let mut expired: usize = 0;
+        self.contracts.retain(|(aircraft, ground_station), contracts| {
+            let stale = |contract: &AdscContract| contract.state == ContractState::Requested
+                && contract.requested_time.is_some_and(|requested_time| requested_time < cutoff);
+            for contract in contracts.iter_mut().filter(|contract| stale(contract)) {
+                contract.state = ContractState::Expired;
+                self.events.push(ContractEvent {
+                    aircraft: aircraft.clone(),
+                    ground_station: ground_station.clone(),
+                    time: Some(time),
+                    contract: contract.clone(),
+                });
+                expired += 1;
+            }
+            contracts.retain(|contract| contract.state != ContractState::Expired);
+            !contracts.is_empty()
+        });
+        expired
+    }
+
+    /// Returns the contracts currently requested or established between an aircraft and a ground station.
+    pub fn contracts<'a>(&'a self, aircraft: &str, ground_station: &str) -> impl Iterator<Item = &'a AdscContract> {
+        self.contracts.get(&(aircraft.to_string(), ground_station.to_string())).into_iter().flatten()
+    }
+
+    /// Returns the contract changes recorded so far, in the order they were seen.
+    pub fn events(&self) -> &[ContractEvent] {
+        &self.events
+    }
+
+    /// Returns the contract changes recorded so far, leaving the tracker to collect the next ones.
+    pub fn take_events(&mut self) -> Vec<ContractEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Converts the recorded contract changes to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(&self.events)
+    }
+}
+
+/// Replaces any contract with the same number by a newly requested one, returning it.
+fn request_contract(contracts: &mut Vec<AdscContract>, request: &AdscContractRequest, kind: ContractKind, time: Option<f64>) -> AdscContract {
+    let interval_secs: Option<u32> = request.groups.iter().find_map(|group| match group {
+        AdscContractGroup::ReportInterval(interval) => Some(interval.interval_secs),
+        _ => None,
+    });
+    let contract: AdscContract = AdscContract {
+        contract_num: request.contract_num,
+        kind,
+        interval_secs,
+        groups: request.groups.clone(),
+        state: ContractState::Requested,
+        requested_time: time,
+    };
+    contracts.retain(|existing| existing.contract_num != request.contract_num);
+    contracts.push(contract.clone());
+    contract
+}
+
+/// Moves the open contracts that match to the state, returning them as they are after the change.
+fn set_state(contracts: &mut [AdscContract], selected: impl Fn(&AdscContract) -> bool, state: ContractState) -> Vec<AdscContract> {
+    contracts.iter_mut()
+        .filter(|contract| contract.state != state && matches!(contract.state, ContractState::Requested | ContractState::Established))
+        .filter(|contract| selected(contract))
+        .map(|contract| {
+            contract.state = state;
+            contract.clone()
+        })
+        .collect()
+}
+
+/// Returns the ground station address, air address and ADS-C block of a message carrying ADS-C.
+#[allow(unreachable_patterns)]
+fn adsc_block(message: &AcarsVdlm2Message) -> Option<(&str, &str, &Adsc)> {
+    match message {
+        #[cfg(feature = "vdlm2")]
+        AcarsVdlm2Message::Vdlm2Message(vdlm2) => {
+            let arinc622: &crate::vdlm2::Arinc622 = vdlm2.get_acars()?.arinc622.as_ref()?;
+            Some((&arinc622.gs_addr, &arinc622.air_addr, arinc622.adsc.as_ref()?))
+        }
+        #[cfg(feature = "hfdl")]
+        AcarsVdlm2Message::HfdlMessage(hfdl) => {
+            let arinc622: &crate::hfdl::Arinc622 = hfdl.get_acars()?.arinc622.as_ref()?;
+            Some((&arinc622.gs_addr, &arinc622.air_addr, arinc622.adsc.as_ref()?))
+        }
+        _ => None,
+    }
+}
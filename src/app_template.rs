@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::{AcarsVdlm2Message, AppDetails};
+use crate::transform::{Transform, TransformOutcome};
+
+/// Separator used when a message has passed through more than one proxy.
+const PROXY_SEPARATOR: &str = ",";
+
+/// Describes the `app` block a producer stamps on the messages it forwards, for processes that forward on behalf of
+/// several producers at once.
+///
+/// Cloning a template is cheap as the details are shared, and every clone reports the same `acars_router_uuid`.
+///
+/// Applying a template never replaces the name and version of the decoder that produced the message; the template
+/// name and version are only used when the message has none. The proxy details are appended to any already present,
+/// so a message that passes through several proxies keeps a record of each of them, oldest first.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, AppDetails, DecodeMessage};
+/// use acars_vdlm2_parser::app_template::AppDetailsTemplate;
+/// let template: AppDetailsTemplate = AppDetailsTemplate::new("second_router", "2.0.0");
+/// let mut message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"app":{"name":"acarsdec","ver":"3.7","proxied":true,"proxied_by":"first_router","acars_router_version":"1.0.0"}}"#.decode_message().unwrap();
+/// template.apply_to(&mut message);
+/// let app: AppDetails = message.get_app_details().cloned().unwrap();
+/// assert_eq!((app.name.as_str(), app.ver.as_str()), ("acarsdec", "3.7"));
+/// assert_eq!(app.proxied_by.as_deref(), Some("first_router,second_router"));
+/// assert_eq!(app.acars_router_version.as_deref(), Some("1.0.0,2.0.0"));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AppDetailsTemplate {
+    details: Arc<AppDetails>,
+}
+
+impl AppDetailsTemplate {
+    /// Creates a template for a proxy, with a new `acars_router_uuid`.
+    pub fn new(proxied_by: &str, acars_router_version: &str) -> Self {
+        Self { details: Arc::new(AppDetails::new(proxied_by, acars_router_version)) }
+    }
+
+    /// Sets the name and version used for messages that arrive without them.
+    pub fn with_fallback_app(mut self, name: &str, ver: &str) -> Self {
+        let details: &mut AppDetails = Arc::make_mut(&mut self.details);
+        details.name = name.to_string();
+        details.ver = ver.to_string();
+        self
+    }
+
+    /// Sets the `acars_router_uuid` rather than using a randomly generated one.
+    pub fn with_uuid(mut self, uuid: Uuid) -> Self {
+        Arc::make_mut(&mut self.details).acars_router_uuid = Some(uuid.to_string());
+        self
+    }
+
+    /// Returns the `AppDetails` given to messages that arrive without an `app` block.
+    pub fn details(&self) -> &AppDetails {
+        &self.details
+    }
+
+    /// Merges the template into an existing `app` block, keeping the decoder name and version and appending the proxy details.
+    pub fn merge_into(&self, app_details: &mut AppDetails) {
+        if app_details.name.is_empty() {
+            app_details.name.clone_from(&self.details.name);
+        }
+        if app_details.ver.is_empty() {
+            app_details.ver.clone_from(&self.details.ver);
+        }
+        app_details.proxied = Some(true);
+        append_proxy(&mut app_details.proxied_by, self.details.proxied_by.as_deref());
+        append_proxy(&mut app_details.acars_router_version, self.details.acars_router_version.as_deref());
+        if app_details.acars_router_uuid.is_none() {
+            app_details.acars_router_uuid.clone_from(&self.details.acars_router_uuid);
+        }
+    }
+
+    /// Applies the template to the `app` block of the provided message, adding one if it has none.
+    pub fn apply_to(&self, message: &mut AcarsVdlm2Message) {
+        message.apply_app_template(self);
+    }
+}
+
+fn append_proxy(existing: &mut Option<String>, addition: Option<&str>) {
+    let Some(addition) = addition else { return };
+    match existing {
+        Some(existing) if !existing.is_empty() => {
+            existing.push_str(PROXY_SEPARATOR);
+            existing.push_str(addition);
+        }
+        _ => *existing = Some(addition.to_string()),
+    }
+}
+
+impl Transform for AppDetailsTemplate {
+    fn name(&self) -> &str {
+        "app_details_template"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        self.apply_to(message);
+        TransformOutcome::Forward
+    }
+}
@@ -0,0 +1,51 @@
+//! Runs the conformance checks over a directory of captures and writes the report as JSON, for attaching to bug
+//! reports about formats the parser does not handle.
+//!
+//! Usage: `conformance <capture directory> [report file]`. The report is written to standard output when no file is
+//! given. The exit code is 1 if any line failed a check, and 2 if the captures or report could not be read or written.
+#![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+use acars_vdlm2_parser::conformance::{check_directory, ConformanceReport};
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = env::args().skip(1).collect();
+    let (directory, output): (&str, Option<&str>) = match arguments.as_slice() {
+        [directory] => (directory, None),
+        [directory, output] => (directory, Some(output)),
+        _ => {
+            eprintln!("usage: conformance <capture directory> [report file]");
+            return ExitCode::from(2);
+        }
+    };
+    let report: ConformanceReport = match check_directory(directory) {
+        Ok(report) => report,
+        Err(read_error) => {
+            eprintln!("could not read the captures in {}: {}", directory, read_error);
+            return ExitCode::from(2);
+        }
+    };
+    let json: String = match serde_json::to_string_pretty(&report) {
+        Ok(json) => json,
+        Err(encode_error) => {
+            eprintln!("could not write the report: {}", encode_error);
+            return ExitCode::from(2);
+        }
+    };
+    match output {
+        None => println!("{}", json),
+        Some(output) => {
+            if let Err(write_error) = fs::write(output, json + "\n") {
+                eprintln!("could not write the report to {}: {}", output, write_error);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    eprintln!("{} of {} lines decoded, {} lossless, {} failures", report.decoded, report.lines, report.lossless, report.failures.len());
+    match report.is_ok() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::from(1),
+    }
+}
@@ -0,0 +1,237 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use flate2::read::MultiGzDecoder;
+use crate::{AcarsVdlm2Message, DecodeMessage};
+use crate::dead_letter::{attempted_families, FailedDecode};
+use crate::stream::JsonSplitter;
+
+/// Endings of the uncompressed capture files a `DirectoryTailer` follows.
+const PLAIN_EXTENSIONS: [&str; 2] = [".jsonl", ".json"];
+/// Endings of the gzip compressed capture files a `DirectoryTailer` reads when catching up.
+const COMPRESSED_EXTENSIONS: [&str; 2] = [".jsonl.gz", ".json.gz"];
+
+/// Follows a directory of rotating capture files, one message per line or JSON objects written back to back, for
+/// users who cannot connect to the live sockets of a feeder.
+///
+/// The newest uncompressed `.jsonl` or `.json` file is tailed. When a newer file appears, the rest of the current file
+/// is read from the handle already open, so nothing is lost if it was renamed or compressed, and the new file is then
+/// read from its start. A file that is truncated, or replaced by a new file of the same name, is read again from its
+/// start. Compressed `.jsonl.gz` and `.json.gz` files are taken to be rotated copies of data already read, so are only
+/// read when catching up on the files already in the directory with `read_existing`.
+///
+/// The directory is checked each time `poll` is called, so callers choose how often to poll.
+/// ```no_run
+/// use std::thread;
+/// use std::time::Duration;
+/// use acars_vdlm2_parser::capture::DirectoryTailer;
+/// let mut tailer: DirectoryTailer = DirectoryTailer::new("/var/log/acars").read_existing(true);
+/// loop {
+///     for decoded in tailer.poll().unwrap() {
+///         match decoded {
+///             Ok(message) => println!("{}", message),
+///             Err(failed) => eprintln!("{}", failed.error),
+///         }
+///     }
+///     thread::sleep(Duration::from_secs(1));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DirectoryTailer {
+    directory: PathBuf,
+    read_existing: bool,
+    started: bool,
+    current: Option<TailedFile>,
+}
+
+impl DirectoryTailer {
+    /// Creates a tailer for the directory, which starts from the end of the newest file when first polled.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self { directory: directory.as_ref().to_path_buf(), read_existing: false, started: false, current: None }
+    }
+
+    /// Sets whether the first poll reads every file already in the directory, compressed or not, oldest first,
+    /// rather than starting from the end of the newest file.
+    pub fn read_existing(mut self, read_existing: bool) -> Self {
+        self.read_existing = read_existing;
+        self
+    }
+
+    /// Returns the file being tailed, if there is one.
+    pub fn current_file(&self) -> Option<&Path> {
+        self.current.as_ref().map(|current| current.path.as_path())
+    }
+
+    /// Reads everything written since the last poll, handling any rotation, and decodes it.
+    ///
+    /// Lines that fail to decode are returned as a `FailedDecode` with the file they came from as the source. A line
+    /// still being written is held back until it is finished.
+    pub fn poll(&mut self) -> io::Result<Vec<Result<AcarsVdlm2Message, FailedDecode>>> {
+        let mut lines: Vec<(String, PathBuf)> = Vec::new();
+        if !self.started {
+            self.started = true;
+            self.start(&mut lines)?;
+            return Ok(decode_lines(lines));
+        }
+        let newest: Option<PathBuf> = self.capture_files()?.into_iter().rfind(|path| is_plain(path));
+        match (self.current.as_mut(), newest) {
+            (Some(current), Some(newest)) if current.path != newest => {
+                current.finish(&mut lines)?;
+                let mut next: TailedFile = TailedFile::open(&newest, false)?;
+                next.read_lines(&mut lines)?;
+                self.current = Some(next);
+            }
+            (Some(current), _) => {
+                if current.replaced() {
+                    current.finish(&mut lines)?;
+                    *current = TailedFile::open(&current.path.clone(), false)?;
+                }
+                current.read_lines(&mut lines)?;
+            }
+            (None, Some(newest)) => {
+                let mut next: TailedFile = TailedFile::open(&newest, false)?;
+                next.read_lines(&mut lines)?;
+                self.current = Some(next);
+            }
+            (None, None) => {}
+        }
+        Ok(decode_lines(lines))
+    }
+
+    fn start(&mut self, lines: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+        let mut files: Vec<PathBuf> = self.capture_files()?;
+        let newest_plain: Option<usize> = files.iter().rposition(|path| is_plain(path));
+        let newest: Option<PathBuf> = newest_plain.map(|index| files.remove(index));
+        if self.read_existing {
+            for path in files {
+                read_whole_file(&path, lines)?;
+            }
+        }
+        if let Some(newest) = newest {
+            let mut current: TailedFile = TailedFile::open(&newest, !self.read_existing)?;
+            current.read_lines(lines)?;
+            self.current = Some(current);
+        }
+        Ok(())
+    }
+
+    /// Returns the capture files in the directory, oldest first by modification time and then by name.
+    fn capture_files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files: Vec<(SystemTime, PathBuf)> = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let entry: fs::DirEntry = entry?;
+            let path: PathBuf = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_file() && (is_plain(&path) || is_compressed(&path)) {
+                files.push((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), path));
+            }
+        }
+        files.sort();
+        Ok(files.into_iter().map(|(_, path)| path).collect())
+    }
+}
+
+#[derive(Debug)]
+struct TailedFile {
+    path: PathBuf,
+    file: File,
+    position: u64,
+    identity: Option<(u64, u64)>,
+    splitter: JsonSplitter,
+}
+
+impl TailedFile {
+    fn open(path: &Path, from_end: bool) -> io::Result<Self> {
+        let file: File = File::open(path)?;
+        let metadata: Metadata = file.metadata()?;
+        let position: u64 = if from_end { metadata.len() } else { 0 };
+        Ok(Self { path: path.to_path_buf(), file, position, identity: file_identity(&metadata), splitter: JsonSplitter::new() })
+    }
+
+    /// Reads the complete lines and objects written since the last read, keeping any unfinished one for next time.
+    fn read_lines(&mut self, lines: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(self.position))?;
+        let mut written: Vec<u8> = Vec::new();
+        let read: usize = self.file.read_to_end(&mut written)?;
+        self.position += read as u64;
+        push_lines(self.splitter.push(&written), &self.path, lines);
+        Ok(())
+    }
+
+    /// Reads the rest of the file, including a last line without a newline, as nothing more will be written to it.
+    fn finish(&mut self, lines: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+        self.read_lines(lines)?;
+        push_lines(self.splitter.finish(), &self.path, lines);
+        Ok(())
+    }
+
+    /// Returns true if the file at the path has been truncated or replaced since it was opened.
+    fn replaced(&self) -> bool {
+        match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len() < self.position || file_identity(&metadata) != self.identity,
+            Err(_) => false,
+        }
+    }
+}
+
+fn read_whole_file(path: &Path, lines: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    let file: File = File::open(path)?;
+    let mut reader: Box<dyn Read> = match is_compressed(path) {
+        true => Box::new(MultiGzDecoder::new(file)),
+        false => Box::new(file),
+    };
+    let mut splitter: JsonSplitter = JsonSplitter::new();
+    let mut chunk: Vec<u8> = vec![0; 64 * 1024];
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => push_lines(splitter.push(&chunk[..read]), path, lines),
+            Err(read_error) if read_error.kind() == io::ErrorKind::Interrupted => {}
+            Err(read_error) => return Err(read_error),
+        }
+    }
+    push_lines(splitter.finish(), path, lines);
+    Ok(())
+}
+
+fn push_lines(segments: impl IntoIterator<Item = Vec<u8>>, path: &Path, lines: &mut Vec<(String, PathBuf)>) {
+    for segment in segments {
+        lines.push((String::from_utf8_lossy(&segment).into_owned(), path.to_path_buf()));
+    }
+}
+
+fn decode_lines(lines: Vec<(String, PathBuf)>) -> Vec<Result<AcarsVdlm2Message, FailedDecode>> {
+    lines.into_iter()
+        .map(|(line, path)| line.decode_message()
+            .map_err(|error| FailedDecode::new(&line, &error, &attempted_families(line.as_bytes()), Some(&path.display().to_string()))))
+        .collect()
+}
+
+fn file_name_ends_with(path: &Path, extensions: &[&str]) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| extensions.iter().any(|extension| name.ends_with(extension)))
+}
+
+fn is_plain(path: &Path) -> bool {
+    file_name_ends_with(path, &PLAIN_EXTENSIONS)
+}
+
+fn is_compressed(path: &Path) -> bool {
+    file_name_ends_with(path, &COMPRESSED_EXTENSIONS)
+}
+
+/// Returns the device and inode of the file, used to notice a file being replaced by another of the same name.
+#[cfg(unix)]
+fn file_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
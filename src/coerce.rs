@@ -0,0 +1,157 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer};
+use serde::de::DeserializeOwned;
+use serde::de::Error;
+use serde_json::{Number, Value};
+use crate::instrument::fallback;
+
+/// Deserializes a number that some feeders send quoted, such as `"freq":"136975000"`, as well as a plain number.
+///
+/// For use with `#[serde(deserialize_with = "...")]`, the field is always serialised back as a number.
+/// ```
+/// use serde::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Example {
+///     #[serde(deserialize_with = "acars_vdlm2_parser::coerce::number_or_string")]
+///     freq: u64,
+/// }
+/// let quoted: Example = serde_json::from_str(r#"{"freq":"136975000"}"#).unwrap();
+/// let plain: Example = serde_json::from_str(r#"{"freq":136975000}"#).unwrap();
+/// assert_eq!(quoted.freq, plain.freq);
+/// ```
+pub fn number_or_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + for<'a> Deserialize<'a>,
+    T::Err: Display,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(quoted) => {
+            fallback("quoted_number", &quoted);
+            quoted.trim()
+                .parse::<T>()
+                .map_err(|error| D::Error::custom(format!("invalid number string \"{}\": {}", quoted, error)))
+        }
+        value => T::deserialize(normalise_decimals(value)).map_err(D::Error::custom),
+    }
+}
+
+/// Optional version of `number_or_string`, treating `null` as `None`.
+///
+/// Fields using this also need `#[serde(default)]` so a missing field is still `None`.
+pub fn option_number_or_string<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + for<'a> Deserialize<'a>,
+    T::Err: Display,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        value => number_or_string(value).map(Some).map_err(D::Error::custom),
+    }
+}
+
+/// Deserializes a whole number of seconds or microseconds, such as those in a `t` block, that some forks send quoted
+/// or with a fractional part, such as `"sec":"1664035653"` or `"sec":1664035653.308`.
+///
+/// Any fractional part is dropped, as the microseconds are always given separately, and the field is always serialised
+/// back as an integer. Negative values are an error.
+/// ```
+/// use serde::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Example {
+///     #[serde(deserialize_with = "acars_vdlm2_parser::coerce::whole_number")]
+///     sec: u64,
+/// }
+/// for input in [r#"{"sec":1664035653}"#, r#"{"sec":"1664035653"}"#, r#"{"sec":1664035653.308}"#, r#"{"sec":"1664035653.308"}"#] {
+///     assert_eq!(serde_json::from_str::<Example>(input).unwrap().sec, 1664035653);
+/// }
+/// assert!(serde_json::from_str::<Example>(r#"{"sec":-1}"#).is_err());
+/// ```
+pub fn whole_number<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Value = Value::deserialize(deserializer)?;
+    let number: Number = match &value {
+        Value::Number(number) => number.clone(),
+        Value::String(quoted) => {
+            fallback("quoted_number", quoted);
+            quoted.trim()
+                .parse::<Number>()
+                .map_err(|error| D::Error::custom(format!("invalid number string \"{}\": {}", quoted, error)))?
+        }
+        other => return Err(D::Error::custom(format!("invalid type: {}, expected a whole number", other))),
+    };
+    if let Some(whole) = number.as_u64() {
+        return Ok(whole);
+    }
+    match number.as_f64() {
+        Some(fractional) if fractional >= 0.0 && fractional < u64::MAX as f64 => {
+            fallback("fractional_number", &number.to_string());
+            Ok(fractional.trunc() as u64)
+        }
+        _ => Err(D::Error::custom(format!("invalid value: {}, expected a whole number that is not negative", number))),
+    }
+}
+
+/// Deserializes a field that is normally a single object but is sometimes written as an array of them, such as the
+/// ACARS block of a dumpvdl2 frame where reassembly produced more than one.
+///
+/// Either form is read as a `Vec`, with `null` read as empty. Fields using this also need `#[serde(default)]` so a
+/// missing field is empty too.
+/// ```
+/// use serde::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Example {
+///     #[serde(default, deserialize_with = "acars_vdlm2_parser::coerce::one_or_many")]
+///     blocks: Vec<u8>,
+/// }
+/// let one: Example = serde_json::from_str(r#"{"blocks":1}"#).unwrap();
+/// let many: Example = serde_json::from_str(r#"{"blocks":[1,2]}"#).unwrap();
+/// let missing: Example = serde_json::from_str("{}").unwrap();
+/// assert_eq!((one.blocks, many.blocks, missing.blocks), (vec![1], vec![1, 2], vec![]));
+/// ```
+pub fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(values) => {
+            fallback("one_or_many", &format!("array of {} values where one was expected", values.len()));
+            values.into_iter().map(|value| T::deserialize(value).map_err(D::Error::custom)).collect()
+        }
+        value => T::deserialize(value).map(|single| vec![single]).map_err(D::Error::custom),
+    }
+}
+
+/// Deserializes through a `serde_json::Value` first, for fields holding untagged enums with numeric variants.
+///
+/// When serde_json's `arbitrary_precision` feature is enabled anywhere in the dependency tree, a number whose text
+/// is not exactly how Rust would print the `f64`, such as `0.050000`, reaches an untagged enum as a map and never
+/// matches a variant. Decimal numbers are rewritten as plain `f64` values first so these fields decode the same
+/// whether the feature is enabled or not.
+pub fn via_value<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value: Value = normalise_decimals(Value::deserialize(deserializer)?);
+    T::deserialize(value).map_err(D::Error::custom)
+}
+
+/// Rewrites every decimal number as a plain `f64`, leaving integers untouched.
+fn normalise_decimals(value: Value) -> Value {
+    match value {
+        Value::Number(number) if number.is_f64() => number.as_f64()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Number(number)),
+        Value::Array(values) => Value::Array(values.into_iter().map(normalise_decimals).collect()),
+        Value::Object(map) => Value::Object(map.into_iter().map(|(key, value)| (key, normalise_decimals(value))).collect()),
+        other => other,
+    }
+}
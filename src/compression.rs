@@ -0,0 +1,329 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use flate2::{Compression, Decompress, FlushDecompress, Status};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageResult};
+
+/// Largest payload `AcarsVdlm2Message::decompressed_payload()` will inflate to, so a small message cannot expand
+/// without limit.
+pub const DEFAULT_MAX_DECOMPRESSED_LEN: usize = 64 * 1024;
+/// Shortest text checked for a compressed payload, as anything shorter cannot hold a useful compressed stream.
+const MIN_ENCODED_LEN: usize = 12;
+/// How far into the decoded bytes a raw deflate stream is looked for, to allow for a short application header.
+const MAX_HEADER_LEN: usize = 16;
+
+/// First byte of every frame written by `AcarsVdlm2Message::to_bytes_compressed`.
+const FRAME_MAGIC: u8 = 0xAC;
+/// Length of the frame header, the magic byte followed by the codec.
+const FRAME_HEADER_LEN: usize = 2;
+/// Largest message a frame will be decompressed to, well above any decoder output.
+pub const MAX_FRAME_DECOMPRESSED_LEN: usize = 1024 * 1024;
+
+/// How a single message is compressed by `AcarsVdlm2Message::to_bytes_compressed`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    Gzip,
+    Zstd,
+}
+
+impl Codec {
+    fn id(&self) -> u8 {
+        match self {
+            Codec::Gzip => 1,
+            Codec::Zstd => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Codec::Gzip),
+            2 => Some(Codec::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Compresses one message as JSON and frames it with a two byte header, the magic byte `0xAC` and the codec, for
+/// shipping messages one at a time across constrained links such as cellular connected remote receivers.
+///
+/// Each frame stands alone so frames can be sent as datagrams or with any length prefix the transport uses. Use
+/// `decode_compressed` to read a frame back.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::compression::{compress_message, decode_compressed, Codec};
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"H1","text":"POSN42123W071456"}"#.decode_message().unwrap();
+/// for codec in [Codec::Gzip, Codec::Zstd] {
+///     let frame: Vec<u8> = compress_message(&message, codec).unwrap();
+///     assert_eq!(&frame[..1], &[0xAC]);
+///     assert_eq!(decode_compressed(&frame).unwrap().to_json().unwrap(), message.to_json().unwrap());
+/// }
+/// ```
+pub fn compress_message(message: &AcarsVdlm2Message, codec: Codec) -> MessageResult<Vec<u8>> {
+    let json: Vec<u8> = serde_json::to_vec(message)?;
+    let mut frame: Vec<u8> = vec![FRAME_MAGIC, codec.id()];
+    match codec {
+        Codec::Gzip => {
+            let mut encoder: GzEncoder<Vec<u8>> = GzEncoder::new(frame, Compression::best());
+            encoder.write_all(&json).map_err(serde_json::Error::io)?;
+            frame = encoder.finish().map_err(serde_json::Error::io)?;
+        }
+        Codec::Zstd => {
+            let compressed: Vec<u8> = zstd::encode_all(json.as_slice(), zstd::DEFAULT_COMPRESSION_LEVEL).map_err(serde_json::Error::io)?;
+            frame.extend_from_slice(&compressed);
+        }
+    }
+    Ok(frame)
+}
+
+/// Returns true if the bytes start with the header written by `compress_message`, which is never the start of JSON.
+#[cfg(feature = "ffi")]
+pub(crate) fn is_compressed_frame(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&FRAME_MAGIC)
+}
+
+/// Decompresses and decodes a frame written by `compress_message`.
+///
+/// Frames without the header, with an unknown codec, or that decompress to more than `MAX_FRAME_DECOMPRESSED_LEN`
+/// bytes fail with an I/O error of kind `InvalidData`.
+pub fn decode_compressed(frame: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+    let codec: Codec = match frame {
+        [FRAME_MAGIC, id, ..] => Codec::from_id(*id).ok_or_else(|| invalid_frame(&format!("unknown codec {}", id)))?,
+        _ => return Err(invalid_frame("missing compressed frame header")),
+    };
+    let compressed: &[u8] = &frame[FRAME_HEADER_LEN..];
+    let reader: Box<dyn Read + '_> = match codec {
+        Codec::Gzip => Box::new(GzDecoder::new(compressed)),
+        Codec::Zstd => Box::new(zstd::Decoder::new(compressed).map_err(serde_json::Error::io)?),
+    };
+    let mut json: Vec<u8> = Vec::new();
+    reader.take(MAX_FRAME_DECOMPRESSED_LEN as u64 + 1).read_to_end(&mut json).map_err(serde_json::Error::io)?;
+    if json.len() > MAX_FRAME_DECOMPRESSED_LEN {
+        return Err(invalid_frame(&format!("frame decompresses to more than {} bytes", MAX_FRAME_DECOMPRESSED_LEN)));
+    }
+    serde_json::from_slice(&json)
+}
+
+fn invalid_frame(reason: &str) -> serde_json::Error {
+    serde_json::Error::io(io::Error::new(io::ErrorKind::InvalidData, reason))
+}
+
+/// How the compressed bytes are written in the message text.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TextEncoding {
+    /// Standard base64, with or without padding.
+    Base64,
+    /// Two hex digits per byte, in either case.
+    Hex,
+}
+
+/// The compressed stream format found in a payload.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionFormat {
+    /// A deflate stream with a zlib header and checksum.
+    Zlib,
+    /// A deflate stream with a gzip header.
+    Gzip,
+    /// A deflate stream without a header, optionally after a short application header.
+    Deflate,
+}
+
+/// A compressed payload found in message text by `detect`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct CompressedPayload {
+    pub encoding: TextEncoding,
+    pub format: CompressionFormat,
+    /// Number of decoded bytes before the compressed stream starts.
+    pub offset: usize,
+    /// Length of the compressed stream in decoded bytes.
+    pub compressed_len: usize,
+}
+
+/// Why a payload could not be decompressed.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecompressError {
+    /// The text does not hold a compressed stream in a format that is recognised.
+    NotCompressed,
+    /// The payload inflates to more than the limit.
+    TooLarge { limit: usize },
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecompressError::NotCompressed => f.write_str("no compressed payload found"),
+            DecompressError::TooLarge { limit } => write!(f, "payload inflates to more than {} bytes", limit),
+        }
+    }
+}
+
+impl std::error::Error for DecompressError {}
+
+/// Looks for a compressed payload in message text, such as a deflated application message carried in a free text block.
+///
+/// The whole text, ignoring surrounding whitespace, must be base64 or hex. The decoded bytes must then start with a
+/// zlib or gzip stream, or hold a raw deflate stream starting within the first 16 bytes that runs to the end of the
+/// payload. As raw deflate has no header to check, a raw stream must also inflate to text, valid UTF-8 without control
+/// characters other than tabs and line breaks, since stray bytes in ordinary text often inflate to a few bytes of noise.
+/// Text that is not encoded this way, which is nearly every message, is rejected after checking its characters.
+///
+/// Messages that dumpvdl2 and dumphfdl already decode with libacars, such as MIAM, arrive with the decoded content in
+/// their own fields and are not detected here.
+/// ```
+/// use acars_vdlm2_parser::compression::{decompress, detect, CompressedPayload, CompressionFormat, TextEncoding};
+/// // "Hello, Hello, Hello, Hello!" compressed with zlib and base64 encoded.
+/// let text: &str = "eJzzSM3JyddR8MCkFAF9LAjW";
+/// let payload: CompressedPayload = detect(text).unwrap();
+/// assert_eq!((payload.encoding, payload.format), (TextEncoding::Base64, CompressionFormat::Zlib));
+/// assert_eq!(decompress(text, 1024).unwrap(), b"Hello, Hello, Hello, Hello!");
+/// assert!(detect("POS N42123W071456,,123456,350,").is_none());
+/// ```
+pub fn detect(text: &str) -> Option<CompressedPayload> {
+    find_stream(text, DEFAULT_MAX_DECOMPRESSED_LEN).ok().map(|(payload, _)| payload)
+}
+
+/// Decompresses the payload in the message text, failing if it would inflate to more than `max_len` bytes.
+pub fn decompress(text: &str, max_len: usize) -> Result<Vec<u8>, DecompressError> {
+    find_stream(text, max_len).map(|(_, inflated)| inflated)
+}
+
+fn find_stream(text: &str, max_len: usize) -> Result<(CompressedPayload, Vec<u8>), DecompressError> {
+    let text: &str = text.trim();
+    if text.len() < MIN_ENCODED_LEN {
+        return Err(DecompressError::NotCompressed);
+    }
+    let candidates: [(TextEncoding, Option<Vec<u8>>); 2] = [
+        (TextEncoding::Hex, decode_hex(text)),
+        (TextEncoding::Base64, decode_base64(text)),
+    ];
+    let mut too_large: Option<DecompressError> = None;
+    for (encoding, bytes) in candidates {
+        let Some(bytes) = bytes else { continue };
+        let attempts = [(CompressionFormat::Zlib, 0), (CompressionFormat::Gzip, 0)].into_iter()
+            .chain((0..MAX_HEADER_LEN.min(bytes.len())).map(|offset| (CompressionFormat::Deflate, offset)));
+        for (format, offset) in attempts {
+            match inflate(&bytes[offset..], format, max_len) {
+                Ok(Some((inflated, compressed_len))) => {
+                    return Ok((CompressedPayload { encoding, format, offset, compressed_len }, inflated));
+                }
+                Ok(None) => {}
+                Err(error) => too_large = Some(error),
+            }
+        }
+    }
+    Err(too_large.unwrap_or(DecompressError::NotCompressed))
+}
+
+/// Inflates a stream of the format, returning `None` if the bytes are not one.
+///
+/// A stream must finish cleanly; gzip and raw deflate streams must also end within 8 bytes of the end of the payload,
+/// which leaves room for the gzip trailer and stops stray bytes in ordinary text being taken for a stream.
+fn inflate(bytes: &[u8], format: CompressionFormat, max_len: usize) -> Result<Option<(Vec<u8>, usize)>, DecompressError> {
+    let deflate: &[u8] = match format {
+        CompressionFormat::Zlib if bytes.len() >= 2 && bytes[0] & 0x0F == 8 && u16::from_be_bytes([bytes[0], bytes[1]]).is_multiple_of(31) => bytes,
+        CompressionFormat::Gzip if bytes.starts_with(&[0x1F, 0x8B, 0x08]) => match gzip_header_len(bytes) {
+            Some(header_len) => &bytes[header_len..],
+            None => return Ok(None),
+        },
+        CompressionFormat::Deflate => bytes,
+        _ => return Ok(None),
+    };
+    let mut decompress: Decompress = Decompress::new(format == CompressionFormat::Zlib);
+    let mut inflated: Vec<u8> = Vec::with_capacity(max_len.min(4 * deflate.len()).saturating_add(1));
+    loop {
+        let consumed: usize = decompress.total_in() as usize;
+        let status: Status = match decompress.decompress_vec(&deflate[consumed..], &mut inflated, FlushDecompress::None) {
+            Ok(status) => status,
+            Err(_) => return Ok(None),
+        };
+        if format == CompressionFormat::Deflate && !is_plausible_text(&inflated) {
+            return Ok(None);
+        }
+        if inflated.len() > max_len {
+            return Err(DecompressError::TooLarge { limit: max_len });
+        }
+        match status {
+            Status::StreamEnd => break,
+            _ if inflated.len() == inflated.capacity() => inflated.reserve(inflated.capacity().min(max_len.saturating_add(1) - inflated.len())),
+            _ => return Ok(None),
+        }
+    }
+    let stream_len: usize = decompress.total_in() as usize;
+    let trailing: usize = deflate.len() - stream_len;
+    if (format != CompressionFormat::Zlib && trailing > 8) || inflated.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((inflated, bytes.len() - trailing)))
+}
+
+/// Returns true if the bytes are UTF-8 text without control characters other than tabs and line breaks, allowing a
+/// character cut off at the end while the stream is still being inflated.
+fn is_plausible_text(bytes: &[u8]) -> bool {
+    let valid_len: usize = match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        Err(error) if error.error_len().is_none() => error.valid_up_to(),
+        Err(_) => return false,
+    };
+    std::str::from_utf8(&bytes[..valid_len])
+        .is_ok_and(|text| text.chars().all(|character| !character.is_control() || matches!(character, '\t' | '\r' | '\n')))
+}
+
+/// Returns the length of the gzip header, skipping the optional fields the flags say are present.
+fn gzip_header_len(bytes: &[u8]) -> Option<usize> {
+    let flags: u8 = *bytes.get(3)?;
+    let mut length: usize = 10;
+    if flags & 0x04 != 0 {
+        let extra: usize = u16::from_le_bytes([*bytes.get(10)?, *bytes.get(11)?]) as usize;
+        length += 2 + extra;
+    }
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            length += bytes.get(length..)?.iter().position(|byte| *byte == 0)? + 1;
+        }
+    }
+    if flags & 0x02 != 0 {
+        length += 2;
+    }
+    (length <= bytes.len()).then_some(length)
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..text.len()).step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).ok())
+        .collect()
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let trimmed: &str = text.trim_end_matches('=');
+    if text.len() - trimmed.len() > 2 || trimmed.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes: Vec<u8> = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+    for character in trimmed.bytes() {
+        let value: u8 = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+use crate::corpus::{Corpus, CorpusFile, TagFilter};
+use crate::error_code::ErrorCode;
+use crate::roundtrip::{roundtrip_check, DifferenceKind, RoundtripReport};
+
+/// The check a line failed in a conformance run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ConformanceStage {
+    /// The line could not be decoded.
+    Decode,
+    /// The line decoded, but fields were lost or changed when it was written back out.
+    Roundtrip,
+}
+
+/// A line that failed a conformance check.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ConformanceFailure {
+    pub path: PathBuf,
+    /// Line number in the file, starting from 1 and not counting empty lines.
+    pub line: usize,
+    pub stage: ConformanceStage,
+    /// `ErrorCode` of a failed decode, such as `json.unknown_variant`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
+    pub error: String,
+}
+
+/// Machine readable results of a conformance run over a set of captures, for attaching to bug reports about formats
+/// this crate does not handle.
+///
+/// Field paths are in the form `AcarsVdlm2Message::get_field` uses, with array indexes replaced by `*` so the same
+/// field in different entries is counted together.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ConformanceReport {
+    /// Version of this crate that produced the report.
+    pub version: String,
+    pub files: usize,
+    pub lines: usize,
+    pub decoded: usize,
+    /// Lines that decoded and were written back out without any field being lost or changed.
+    pub lossless: usize,
+    /// Lines decoded, by the family they were classified as.
+    pub families: BTreeMap<MessageFamily, usize>,
+    /// Lines that failed to decode, by `ErrorCode`.
+    pub error_codes: BTreeMap<ErrorCode, usize>,
+    /// Fields dropped because the message types do not know them, by path.
+    pub unknown_fields: BTreeMap<String, usize>,
+    /// Fields whose value or type changed when written back out, by path.
+    pub changed_fields: BTreeMap<String, usize>,
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl ConformanceReport {
+    /// Returns true if every line decoded and was written back out unchanged.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Decodes and round trips every line of the file, adding the results to the report.
+    fn check_file(&mut self, file: &CorpusFile) -> io::Result<()> {
+        self.files += 1;
+        for (index, line) in file.read_lines()?.iter().enumerate() {
+            self.lines += 1;
+            let failure = |stage: ConformanceStage, code: Option<ErrorCode>, error: String| ConformanceFailure {
+                path: file.path.clone(),
+                line: index + 1,
+                stage,
+                code,
+                error,
+            };
+            let message: AcarsVdlm2Message = match line.decode_message() {
+                Err(decode_error) => {
+                    let code: ErrorCode = ErrorCode::of(&decode_error);
+                    *self.error_codes.entry(code).or_default() += 1;
+                    self.failures.push(failure(ConformanceStage::Decode, Some(code), decode_error.to_string()));
+                    continue;
+                }
+                Ok(message) => message,
+            };
+            self.decoded += 1;
+            *self.families.entry(message.get_family()).or_default() += 1;
+            // Lines from legacy feeders are not JSON, so there is nothing to compare them against.
+            if !line.trim_start().starts_with('{') {
+                self.lossless += 1;
+                continue;
+            }
+            let roundtrip: RoundtripReport = roundtrip_check(line);
+            if roundtrip.is_lossless() {
+                self.lossless += 1;
+                continue;
+            }
+            for difference in &roundtrip.differences {
+                let counts: &mut BTreeMap<String, usize> = match difference.kind {
+                    DifferenceKind::Lost => &mut self.unknown_fields,
+                    DifferenceKind::Added | DifferenceKind::Changed => &mut self.changed_fields,
+                };
+                *counts.entry(wildcard_indexes(&difference.path)).or_default() += 1;
+            }
+            let paths: Vec<String> = roundtrip.differences.iter()
+                .map(|difference| match difference.kind {
+                    DifferenceKind::Lost => format!("{} lost", difference.path),
+                    DifferenceKind::Added => format!("{} added", difference.path),
+                    DifferenceKind::Changed => format!("{} changed", difference.path),
+                })
+                .collect();
+            let error: String = roundtrip.error.unwrap_or_else(|| format!("fields differ after writing back out: {}", paths.join(", ")));
+            self.failures.push(failure(ConformanceStage::Roundtrip, None, error));
+        }
+        Ok(())
+    }
+}
+
+/// Runs the conformance checks over the files of a `Corpus` matching the filter: every line is decoded, classified
+/// by family, and written back out to find fields that were lost or changed.
+pub fn check_corpus(corpus: &Corpus, filter: &TagFilter) -> io::Result<ConformanceReport> {
+    let mut report: ConformanceReport = ConformanceReport { version: env!("CARGO_PKG_VERSION").to_string(), ..Default::default() };
+    for file in corpus.files(filter) {
+        report.check_file(file)?;
+    }
+    Ok(report)
+}
+
+/// Runs the conformance checks over every file in a directory of captures, one message per line, see `check_corpus`.
+///
+/// This is what the `conformance` binary runs, so `cargo run --bin conformance -- <captures>` gives a report to attach
+/// to a bug report.
+/// ```
+/// use acars_vdlm2_parser::MessageFamily;
+/// use acars_vdlm2_parser::conformance::{check_directory, ConformanceReport};
+/// let report: ConformanceReport = check_directory("test_files").unwrap();
+/// assert!(report.decoded > 0);
+/// assert!(report.families[&MessageFamily::Vdlm2] > 0);
+/// ```
+pub fn check_directory(dir: impl AsRef<Path>) -> io::Result<ConformanceReport> {
+    let mut corpus: Corpus = Corpus::new();
+    corpus.register_dir(dir, "", &[])?;
+    check_corpus(&corpus, &TagFilter::any())
+}
+
+/// Replaces the array indexes in a dot separated path with `*`.
+fn wildcard_indexes(path: &str) -> String {
+    path.split('.')
+        .map(|segment| match !segment.is_empty() && segment.bytes().all(|byte| byte.is_ascii_digit()) {
+            true => "*",
+            false => segment,
+        })
+        .collect::<Vec<&str>>()
+        .join(".")
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+use crate::icao::is_allocated;
+use crate::registrations::{normalise_registration, RegistrationMapping};
+use crate::transform::{Transform, TransformOutcome};
+use crate::vdlm2::Vdlm2Message;
+
+/// Results of cross-checking the AVLC address of a VDLM2 frame against the registration in its ACARS block, set on
+/// the frame by `ConsistencyChecker`.
+///
+/// A mismatch is often a garbled frame, and occasionally spoofed data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ConsistencyFlags {
+    /// The mapping has a different registration for the AVLC address.
+    pub registration_mismatch: bool,
+    /// The mapping has a different address for the ACARS registration.
+    pub address_mismatch: bool,
+    /// The AVLC address is outside every allocated ICAO block, see `icao::is_allocated`.
+    pub unallocated_address: bool,
+    /// Registration the mapping has for the AVLC address, when it differs from the one in the frame.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_registration: Option<String>,
+    /// ICAO address, as 6 uppercase hex characters, the mapping has for the registration when it differs from the
+    /// AVLC address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_icao: Option<String>,
+}
+
+impl ConsistencyFlags {
+    /// Returns true if no check failed.
+    pub fn is_consistent(&self) -> bool {
+        !self.registration_mismatch && !self.address_mismatch && !self.unallocated_address
+    }
+}
+
+/// Opt-in validator that cross-checks the AVLC address and ACARS registration of VDLM2 frames against a known
+/// registration to ICAO address mapping, and sets the result on the frame as its `consistency` block.
+///
+/// Frames that do not carry both an aircraft address and a registration are left unchecked. Registrations are
+/// compared ignoring case and the leading dots ACARS pads them with. As a `Transform` it forwards every frame unless
+/// `reject_inconsistent` is set.
+/// ```
+/// use acars_vdlm2_parser::vdlm2::{NewVdlm2Message, Vdlm2Message};
+/// use acars_vdlm2_parser::consistency::{ConsistencyChecker, ConsistencyFlags};
+/// let mut checker: ConsistencyChecker = ConsistencyChecker::new();
+/// checker.insert(0x7C1234, "VH-ABC");
+/// let frame: &str = r#"{"vdl2":{"avlc":{"cr":"Command","dst":{"addr":"10210A","type":"Ground station"},"frame_type":"I","src":{"addr":"7C1234","status":"Airborne","type":"Aircraft"},"acars":{"err":false,"crc_ok":true,"more":false,"reg":".VH-XYZ","mode":"2","label":"H1","blk_id":"5","ack":"!","msg_text":""}},"freq":136975000}}"#;
+/// let mut message: Vdlm2Message = frame.to_vdlm2().unwrap();
+/// let flags: ConsistencyFlags = checker.check(&message).unwrap();
+/// assert!(flags.registration_mismatch);
+/// assert_eq!(flags.expected_registration.as_deref(), Some("VH-ABC"));
+/// checker.apply_to(&mut message);
+/// assert_eq!(message.consistency(), Some(&flags));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConsistencyChecker {
+    registrations: HashMap<u32, String>,
+    addresses: HashMap<String, u32>,
+    reject_inconsistent: bool,
+}
+
+impl ConsistencyChecker {
+    /// Creates a `ConsistencyChecker` with an empty mapping, which only flags unallocated addresses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `ConsistencyChecker` from the pairings of a `RegistrationTable` with at least the given confidence.
+    pub fn from_mappings(mappings: &[RegistrationMapping], min_confidence: f64) -> Self {
+        let mut checker: ConsistencyChecker = ConsistencyChecker::new();
+        let mut trusted: Vec<&RegistrationMapping> = mappings.iter().filter(|mapping| mapping.confidence >= min_confidence).collect();
+        // Insert the most trusted pairing last, so it wins when an address or registration has several.
+        trusted.sort_by(|first, second| first.confidence.total_cmp(&second.confidence));
+        for mapping in trusted {
+            if let Ok(icao) = u32::from_str_radix(&mapping.icao, 16) {
+                checker.insert(icao, &mapping.registration);
+            }
+        }
+        checker
+    }
+
+    /// Drops frames that fail a check when used as a `Transform`, rather than only flagging them.
+    pub fn reject_inconsistent(mut self, reject: bool) -> Self {
+        self.reject_inconsistent = reject;
+        self
+    }
+
+    /// Adds a known pairing, replacing any pairing already known for the address or registration.
+    pub fn insert(&mut self, icao: u32, registration: &str) {
+        let registration: String = normalise_registration(registration);
+        self.registrations.insert(icao, registration.clone());
+        self.addresses.insert(registration, icao);
+    }
+
+    /// Number of addresses with a known registration.
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    /// Returns true if no pairings are known.
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+
+    /// Cross-checks the frame, returning `None` if it does not carry both an aircraft address and a registration.
+    pub fn check(&self, message: &Vdlm2Message) -> Option<ConsistencyFlags> {
+        let icao: u32 = message.get_icao()?;
+        let registration: String = message.get_registration().map(normalise_registration).filter(|registration| !registration.is_empty())?;
+        let expected_registration: Option<&String> = self.registrations.get(&icao).filter(|expected| **expected != registration);
+        let expected_icao: Option<u32> = self.addresses.get(&registration).copied().filter(|expected| *expected != icao);
+        Some(ConsistencyFlags {
+            registration_mismatch: expected_registration.is_some(),
+            address_mismatch: expected_icao.is_some(),
+            unallocated_address: !is_allocated(icao),
+            expected_registration: expected_registration.cloned(),
+            expected_icao: expected_icao.map(|expected| format!("{:06X}", expected)),
+        })
+    }
+
+    /// Cross-checks the frame and sets the result as its `consistency` block, returning the flags set.
+    pub fn apply_to(&self, message: &mut Vdlm2Message) -> Option<ConsistencyFlags> {
+        let flags: Option<ConsistencyFlags> = self.check(message);
+        message.vdl2.consistency = flags.clone();
+        flags
+    }
+}
+
+impl Transform for ConsistencyChecker {
+    fn name(&self) -> &str {
+        "consistency_check"
+    }
+
+    #[allow(irrefutable_let_patterns)]
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        let AcarsVdlm2Message::Vdlm2Message(vdlm2) = message else {
+            return TransformOutcome::Forward;
+        };
+        match self.apply_to(vdlm2) {
+            Some(flags) if self.reject_inconsistent && !flags.is_consistent() =>
+                TransformOutcome::Drop("registration and ICAO address are inconsistent".to_string()),
+            _ => TransformOutcome::Forward,
+        }
+    }
+}
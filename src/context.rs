@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::extract::duplicate_keys;
+use crate::provider;
+use crate::roundtrip::{diff_values, DifferenceKind, FieldDifference};
+use crate::transform::{TransformOutcome, TransformPipeline};
+
+/// Fallbacks that are the normal path for some decoder's output, so are not reported as warnings.
+const EXPECTED_FALLBACKS: &[&str] = &["no_family_key", "uat_raw"];
+
+thread_local! {
+    /// Soft issues raised on this thread while a `ProcessingContext` is capturing them, as `(reason, detail)` pairs.
+    static CAPTURED: RefCell<Option<Vec<(String, String)>>> = const { RefCell::new(None) };
+}
+
+/// Records a soft issue with the `ProcessingContext` capturing on this thread, if there is one.
+///
+/// This is called for every decode fallback, so issues raised deep inside deserialization reach the context.
+pub(crate) fn record_warning(reason: &str, detail: &str) {
+    CAPTURED.with(|captured| {
+        if let Some(warnings) = captured.borrow_mut().as_mut() {
+            warnings.push((reason.to_string(), detail.to_string()));
+        }
+    });
+}
+
+/// Runs the closure, passing on the soft issues it raises on this thread only if `keep` accepts its result, for decode
+/// attempts that are retried another way when they fail.
+pub(crate) fn record_warnings_if<T>(run: impl FnOnce() -> T, keep: impl FnOnce(&T) -> bool) -> T {
+    let Some(outer) = CAPTURED.with(|captured| captured.borrow_mut().take()) else {
+        return run();
+    };
+    CAPTURED.with(|captured| captured.replace(Some(Vec::new())));
+    let result: T = run();
+    let raised: Vec<(String, String)> = CAPTURED.with(|captured| captured.replace(Some(outer))).unwrap_or_default();
+    if keep(&result) {
+        for (reason, detail) in &raised {
+            record_warning(reason, detail);
+        }
+    }
+    result
+}
+
+/// The stage of processing a `ProcessingWarning` was raised in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessingStage {
+    Decode,
+    Transform,
+    Serialise,
+}
+
+/// A soft issue found while processing a message, which did not stop it being processed.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ProcessingWarning {
+    pub stage: ProcessingStage,
+    /// Short machine readable reason, such as `quoted_number`, `unknown_field` or `repaired_utf8`.
+    pub reason: String,
+    pub detail: String,
+}
+
+/// Carries a message through decoding, transforming and serialising, collecting soft issues along the way.
+///
+/// Warnings cover the fallbacks taken while decoding, such as numbers written as strings, input bytes that were not
+/// valid UTF-8 and had to be repaired, timestamps further in the future than allowed, and optionally fields that were
+/// dropped because the message types do not know them and keys repeated within an object. None of these fail processing; they are kept until taken, so
+/// one context can be used for a single message or a whole batch.
+/// ```
+/// use acars_vdlm2_parser::AcarsVdlm2Message;
+/// use acars_vdlm2_parser::context::{ProcessingContext, ProcessingWarning};
+/// let mut context: ProcessingContext = ProcessingContext::new().report_unknown_fields(true);
+/// let message: AcarsVdlm2Message = context.decode(r#"{"freq":131.55,"channel":"2","extra":1}"#).unwrap();
+/// let reasons: Vec<&str> = context.warnings().iter().map(|warning| warning.reason.as_str()).collect();
+/// assert_eq!(reasons, vec!["quoted_number", "unknown_field"]);
+/// let serialised: String = context.serialise(&message).unwrap();
+/// assert_eq!(context.take_warnings().len(), 2);
+/// assert!(!context.has_warnings());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProcessingContext {
+    warnings: Vec<ProcessingWarning>,
+    report_unknown_fields: bool,
+    detect_duplicate_keys: bool,
+    max_future_secs: Option<f64>,
+}
+
+impl ProcessingContext {
+    /// Creates a new `ProcessingContext` with no warnings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether fields the message types do not know, and so drop, are reported as `unknown_field` warnings.
+    ///
+    /// This is off by default as it compares the input against the re-encoded message, which costs about as much as
+    /// a second decode.
+    pub fn report_unknown_fields(mut self, report: bool) -> Self {
+        self.report_unknown_fields = report;
+        self
+    }
+
+    /// Sets whether keys repeated within the same object are reported as `duplicate_key` warnings.
+    ///
+    /// Only the last value of a repeated key is kept when decoding. This is off by default as it scans the input a
+    /// second time.
+    pub fn detect_duplicate_keys(mut self, detect: bool) -> Self {
+        self.detect_duplicate_keys = detect;
+        self
+    }
+
+    /// Raises a `future_timestamp` warning for messages timestamped more than this many seconds after the current time.
+    pub fn max_future_secs(mut self, seconds: f64) -> Self {
+        self.max_future_secs = Some(seconds);
+        self
+    }
+
+    /// Adds a warning.
+    pub fn warn(&mut self, stage: ProcessingStage, reason: &str, detail: &str) {
+        self.warnings.push(ProcessingWarning { stage, reason: reason.to_string(), detail: detail.to_string() });
+    }
+
+    /// Returns the warnings collected so far.
+    pub fn warnings(&self) -> &[ProcessingWarning] {
+        &self.warnings
+    }
+
+    /// Returns true if any warnings have been collected.
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    /// Returns the warnings collected so far, leaving the context empty for the next message.
+    pub fn take_warnings(&mut self) -> Vec<ProcessingWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Runs the closure, adding any fallbacks it takes on this thread as warnings for the provided stage.
+    pub fn capture<T>(&mut self, stage: ProcessingStage, run: impl FnOnce() -> T) -> T {
+        let outer: Option<Vec<(String, String)>> = CAPTURED.with(|captured| captured.replace(Some(Vec::new())));
+        let result: T = run();
+        let captured: Vec<(String, String)> = CAPTURED.with(|captured| captured.replace(outer)).unwrap_or_default();
+        for (reason, detail) in &captured {
+            // Pass the warnings on to any context capturing around this one.
+            record_warning(reason, detail);
+            if !EXPECTED_FALLBACKS.contains(&reason.as_str()) {
+                self.warn(stage, reason, detail);
+            }
+        }
+        result
+    }
+
+    /// Decodes a message, collecting any warnings.
+    pub fn decode(&mut self, input: &str) -> MessageResult<AcarsVdlm2Message> {
+        let value: Value = serde_json::from_str(input)?;
+        if self.detect_duplicate_keys {
+            for path in duplicate_keys(input) {
+                self.warn(ProcessingStage::Decode, "duplicate_key", &path);
+            }
+        }
+        let message: AcarsVdlm2Message = self.capture(ProcessingStage::Decode, || AcarsVdlm2Message::deserialize(&value))?;
+        if self.report_unknown_fields {
+            let mut differences: Vec<FieldDifference> = Vec::new();
+            diff_values("", &value, &serde_json::to_value(&message)?, &mut differences);
+            for difference in differences.iter().filter(|difference| difference.kind == DifferenceKind::Lost) {
+                self.warn(ProcessingStage::Decode, "unknown_field", &difference.path);
+            }
+        }
+        self.check_time(&message);
+        Ok(message)
+    }
+
+    /// Decodes a message from bytes, replacing any invalid UTF-8 rather than failing and collecting any warnings.
+    pub fn decode_bytes(&mut self, bytes: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+        match std::str::from_utf8(bytes) {
+            Ok(input) => self.decode(input),
+            Err(utf8_error) => {
+                self.warn(ProcessingStage::Decode, "repaired_utf8", &format!("invalid UTF-8 after byte {}", utf8_error.valid_up_to()));
+                self.decode(&String::from_utf8_lossy(bytes))
+            }
+        }
+    }
+
+    /// Runs the message through the pipeline, collecting any warnings.
+    pub fn transform(&mut self, pipeline: &TransformPipeline, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        self.capture(ProcessingStage::Transform, || pipeline.apply(message))
+    }
+
+    /// Serialises the message, collecting any warnings.
+    pub fn serialise(&mut self, message: &AcarsVdlm2Message) -> MessageResult<String> {
+        self.capture(ProcessingStage::Serialise, || message.to_json())
+    }
+
+    fn check_time(&mut self, message: &AcarsVdlm2Message) {
+        let (Some(max_future_secs), Some(time)) = (self.max_future_secs, message.get_time()) else {
+            return;
+        };
+        let now: f64 = provider::now();
+        if time > now + max_future_secs {
+            self.warn(ProcessingStage::Decode, "future_timestamp", &format!("{:.3} is {:.3} seconds ahead of the current time", time, time - now));
+        }
+    }
+}
@@ -0,0 +1,318 @@
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+use serde_json::Value;
+use crate::MessageFamily;
+use crate::acars::AcarsMessage;
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+use crate::acars::{AckType, LevelType};
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+use crate::frequency::Frequency;
+#[cfg(feature = "hfdl")]
+use crate::hfdl::{HfdlMessage, LPDUAcars};
+#[cfg(feature = "hfdl")]
+use crate::icao::parse_icao_hex;
+#[cfg(feature = "vdlm2")]
+use crate::vdlm2::{AvlcAcars, AvlcData, Vdlm2Message};
+
+/// Source and destination type dumpvdl2 gives aircraft.
+#[cfg(feature = "vdlm2")]
+const AIRCRAFT: &str = "Aircraft";
+
+/// The fields of the original message that were not carried over when it was converted to an `AcarsMessage`.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ConversionReport {
+    pub source: MessageFamily,
+    /// Dot separated paths of the fields left out, as the original message is written, with array entries given
+    /// their index. A field is listed once when nothing inside it was carried over.
+    pub dropped: Vec<String>,
+}
+
+impl ConversionReport {
+    /// Returns true if every field of the original message was carried over.
+    pub fn is_lossless(&self) -> bool {
+        self.dropped.is_empty()
+    }
+
+    /// Returns true if the field, or the object holding it, was left out.
+    pub fn dropped(&self, path: &str) -> bool {
+        self.dropped.iter().any(|dropped| path == dropped || path.starts_with(&format!("{}.", dropped)))
+    }
+
+    /// Returns true if every field left out is one of the provided paths or inside one of them, for deciding whether a
+    /// route can accept the conversion.
+    pub fn only_dropped(&self, allowed: &[&str]) -> bool {
+        self.dropped.iter()
+            .all(|dropped| allowed.iter().any(|allowed| dropped == allowed || dropped.starts_with(&format!("{}.", allowed))))
+    }
+}
+
+/// A message converted to the acarsdec layout, with a report of what could not be carried over.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AcarsConversion {
+    pub message: AcarsMessage,
+    pub report: ConversionReport,
+}
+
+/// Converts a dumpvdl2 frame carrying an ACARS block to an `AcarsMessage`, returning `None` for frames without one.
+///
+/// Only the first ACARS block is converted, so any others are reported as dropped. The signal level, station name,
+/// app block, time, aircraft address and whether the aircraft is on the ground are carried over with the ACARS
+/// fields. The remaining AVLC and radio details, such as the frame type, XID parameters and noise level, have no place
+/// in the acarsdec layout.
+/// ```
+/// use acars_vdlm2_parser::convert::{vdlm2_to_acars, AcarsConversion};
+/// use acars_vdlm2_parser::vdlm2::{NewVdlm2Message, Vdlm2Message};
+/// let message: Vdlm2Message = r#"{"vdl2":{"app":{"name":"dumpvdl2","ver":"2.3.0"},"t":{"sec":1664035653,"usec":308386},"freq":136975000,"burst_len_octets":34,"hdr_bits_fixed":0,"octets_corrected_by_fec":0,"idx":0,"sig_level":-22.5,"noise_level":-48.1,"freq_skew":1.2,"avlc":{"src":{"addr":"A1B2C3","type":"Aircraft","status":"Airborne"},"dst":{"addr":"10210A","type":"Ground station"},"cr":"Command","frame_type":"I","rseq":1,"sseq":2,"poll":false,"acars":{"err":false,"crc_ok":true,"more":false,"reg":".N123AB","mode":"2","label":"H1","blk_id":"5","ack":"!","flight":"AB0123","msg_num":"M01","msg_num_seq":"A","msg_text":"HELLO"}}}}"#.to_vdlm2().unwrap();
+/// let conversion: AcarsConversion = vdlm2_to_acars(&message).unwrap();
+/// assert_eq!(conversion.message.msgno.as_deref(), Some("M01A"));
+/// assert_eq!((conversion.message.icao, conversion.message.is_onground), (Some(0xA1B2C3), Some(0)));
+/// assert!(conversion.report.dropped("vdl2.noise_level"));
+/// assert!(!conversion.report.dropped("vdl2.avlc.acars.label"));
+/// assert!(conversion.report.only_dropped(&["vdl2.avlc", "vdl2.burst_len_octets", "vdl2.hdr_bits_fixed", "vdl2.octets_corrected_by_fec", "vdl2.idx", "vdl2.noise_level", "vdl2.freq_skew"]));
+/// ```
+#[cfg(feature = "vdlm2")]
+pub fn vdlm2_to_acars(message: &Vdlm2Message) -> Option<AcarsConversion> {
+    let blocks: &[AvlcAcars] = message.vdl2.avlc.acars_blocks();
+    let acars: &AvlcAcars = blocks.first()?;
+    let acars_path: &str = match blocks.len() {
+        1 => "vdl2.avlc.acars",
+        _ => "vdl2.avlc.acars.0",
+    };
+    let mut carried: CarriedFields = CarriedFields::new(acars_path);
+    carried.add_all(&["vdl2.freq", "vdl2.t", "vdl2.sig_level", "vdl2.station", "vdl2.app"]);
+    #[cfg(feature = "enrichment")]
+    carried.add("vdl2.aircraft_info");
+    let avlc: &AvlcData = &message.vdl2.avlc;
+    let icao: Option<u32> = message.get_icao();
+    let is_onground: Option<u8> = match (icao, avlc.src.source_type.as_str(), avlc.src.status.as_str()) {
+        (Some(_), AIRCRAFT, "On ground") => Some(1),
+        (Some(_), AIRCRAFT, "Airborne") => Some(0),
+        _ => None,
+    };
+    match (icao, avlc.src.source_type.as_str()) {
+        (None, _) => {}
+        (Some(_), AIRCRAFT) => carried.add_all(&["vdl2.avlc.src.addr", "vdl2.avlc.src.type"]),
+        (Some(_), _) => carried.add_all(&["vdl2.avlc.dst.addr", "vdl2.avlc.dst.type"]),
+    }
+    if is_onground.is_some() {
+        carried.add("vdl2.avlc.src.status");
+    }
+    let converted: AcarsMessage = AcarsMessage {
+        freq: Frequency::from_mhz(message.get_frequency().as_mhz()),
+        level: message.vdl2.sig_level.map(LevelType::Float64),
+        timestamp: message.get_time(),
+        app: message.vdl2.app.clone(),
+        station_id: message.vdl2.station.clone(),
+        icao,
+        is_onground,
+        #[cfg(feature = "enrichment")]
+        aircraft_info: message.vdl2.aircraft_info.clone(),
+        ..carried.acars_fields(AcarsFields::from(acars))
+    };
+    let original: Value = serde_json::to_value(message).ok()?;
+    Some(AcarsConversion { message: converted, report: carried.report(MessageFamily::Vdlm2, &original) })
+}
+
+/// Converts a dumphfdl frame carrying an ACARS block to an `AcarsMessage`, returning `None` for frames without one.
+///
+/// The signal level, station name, app block, time and aircraft address are carried over with the ACARS fields. The
+/// remaining LPDU, HFNPDU and radio details, such as the slot, bit rate and any decoded ARINC 622 application, have no
+/// place in the acarsdec layout.
+/// ```
+/// use acars_vdlm2_parser::convert::{hfdl_to_acars, AcarsConversion};
+/// use acars_vdlm2_parser::hfdl::{HfdlMessage, NewHfdlMessage};
+/// let message: HfdlMessage = r#"{"hfdl":{"t":{"sec":1702084822,"usec":286419},"freq":11387000,"bit_rate":300,"sig_level":-99.3,"slot":"S","lpdu":{"err":false,"src":{"type":"Aircraft","id":166,"ac_info":{"icao":"A1B2C3"}},"dst":{"type":"Ground station","id":4},"type":{"id":13,"name":"Unnumbered data"},"hfnpdu":{"err":false,"type":{"id":255,"name":"Enveloped data"},"acars":{"err":false,"crc_ok":true,"more":false,"reg":".N123AB","mode":"2","label":"H1","blk_id":"5","ack":"!","msg_text":"HELLO"}}}}}"#.to_hfdl().unwrap();
+/// let conversion: AcarsConversion = hfdl_to_acars(&message).unwrap();
+/// assert_eq!((conversion.message.icao, conversion.message.text.as_deref()), (Some(0xA1B2C3), Some("HELLO")));
+/// assert!(conversion.report.dropped("hfdl.bit_rate"));
+/// assert!(!conversion.report.dropped("hfdl.lpdu.src.ac_info.icao"));
+/// ```
+#[cfg(feature = "hfdl")]
+pub fn hfdl_to_acars(message: &HfdlMessage) -> Option<AcarsConversion> {
+    let acars: &LPDUAcars = message.get_acars()?;
+    let mut carried: CarriedFields = CarriedFields::new("hfdl.lpdu.hfnpdu.acars");
+    carried.add_all(&["hfdl.freq", "hfdl.t", "hfdl.sig_level", "hfdl.station", "hfdl.app"]);
+    #[cfg(feature = "enrichment")]
+    carried.add("hfdl.aircraft_info");
+    let icao: Option<u32> = message.get_icao();
+    if let (Some(icao), Some(lpdu)) = (icao, message.hfdl.lpdu.as_ref()) {
+        let sides = [
+            ("hfdl.lpdu.ac_info", lpdu.ac_info.as_ref()),
+            ("hfdl.lpdu.src.ac_info", lpdu.src.as_ref().and_then(|src| src.ac_info.as_ref())),
+            ("hfdl.lpdu.dst.ac_info", lpdu.dst.as_ref().and_then(|dst| dst.ac_info.as_ref())),
+        ];
+        if let Some((path, _)) = sides.iter().find(|(_, info)| info.is_some_and(|info| parse_icao_hex(&info.icao) == Some(icao))) {
+            carried.add(&format!("{}.icao", path));
+        }
+    }
+    let converted: AcarsMessage = AcarsMessage {
+        freq: Frequency::from_mhz(message.get_frequency().as_mhz()),
+        level: message.hfdl.sig_level.map(LevelType::Float64),
+        timestamp: message.get_time(),
+        app: message.hfdl.app.clone(),
+        station_id: message.hfdl.station.clone(),
+        icao,
+        #[cfg(feature = "enrichment")]
+        aircraft_info: message.hfdl.aircraft_info.clone(),
+        ..carried.acars_fields(AcarsFields::from(acars))
+    };
+    let original: Value = serde_json::to_value(message).ok()?;
+    Some(AcarsConversion { message: converted, report: carried.report(MessageFamily::Hfdl, &original) })
+}
+
+/// The ACARS block fields shared by dumpvdl2 and dumphfdl.
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+struct AcarsFields<'a> {
+    err: bool,
+    crc_ok: bool,
+    more: bool,
+    reg: &'a str,
+    mode: &'a str,
+    label: &'a str,
+    blk_id: &'a str,
+    ack: &'a str,
+    flight: Option<&'a str>,
+    msg_num: Option<&'a str>,
+    msg_num_seq: Option<&'a str>,
+    msg_text: &'a str,
+}
+
+#[cfg(feature = "vdlm2")]
+impl<'a> From<&'a AvlcAcars> for AcarsFields<'a> {
+    fn from(acars: &'a AvlcAcars) -> Self {
+        Self {
+            err: acars.err,
+            crc_ok: acars.crc_ok,
+            more: acars.more,
+            reg: &acars.reg,
+            mode: &acars.mode,
+            label: &acars.label,
+            blk_id: &acars.blk_id,
+            ack: &acars.ack,
+            flight: acars.flight.as_deref(),
+            msg_num: acars.msg_num.as_deref(),
+            msg_num_seq: acars.msg_num_seq.as_deref(),
+            msg_text: &acars.msg_text,
+        }
+    }
+}
+
+#[cfg(feature = "hfdl")]
+impl<'a> From<&'a LPDUAcars> for AcarsFields<'a> {
+    fn from(acars: &'a LPDUAcars) -> Self {
+        Self {
+            err: acars.err,
+            crc_ok: acars.crc_ok,
+            more: acars.more,
+            reg: &acars.reg,
+            mode: &acars.mode,
+            label: &acars.label,
+            blk_id: &acars.blk_id,
+            ack: &acars.ack,
+            flight: acars.flight.as_deref(),
+            msg_num: acars.msg_num.as_deref(),
+            msg_num_seq: acars.msg_num_seq.as_deref(),
+            msg_text: &acars.msg_text,
+        }
+    }
+}
+
+/// The paths of the original message that were carried over, each covering everything inside it.
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+struct CarriedFields {
+    acars_path: String,
+    paths: HashSet<String>,
+}
+
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+impl CarriedFields {
+    fn new(acars_path: &str) -> Self {
+        Self { acars_path: acars_path.to_string(), paths: HashSet::new() }
+    }
+
+    fn add(&mut self, path: &str) {
+        self.paths.insert(path.to_string());
+    }
+
+    fn add_all(&mut self, paths: &[&str]) {
+        for path in paths {
+            self.add(path);
+        }
+    }
+
+    fn add_acars(&mut self, field: &str) {
+        let path: String = format!("{}.{}", self.acars_path, field);
+        self.paths.insert(path);
+    }
+
+    /// Fills the acarsdec fields from an ACARS block, recording the fields used.
+    ///
+    /// acarsdec only outputs blocks that passed their CRC and gives the multi block status separately, so the error
+    /// and CRC flags and `more` are only carried over when they say the block was good and complete.
+    fn acars_fields(&mut self, acars: AcarsFields) -> AcarsMessage {
+        self.add_all_acars(&["reg", "mode", "label", "blk_id", "ack", "flight", "msg_num", "msg_num_seq", "msg_text"]);
+        if !acars.err {
+            self.add_acars("err");
+        }
+        if acars.crc_ok {
+            self.add_acars("crc_ok");
+        }
+        if !acars.more {
+            self.add_acars("more");
+        }
+        let msgno: Option<String> = match (acars.msg_num, acars.msg_num_seq) {
+            (Some(msg_num), Some(msg_num_seq)) => Some(format!("{}{}", msg_num, msg_num_seq)),
+            (Some(msg_num), None) => Some(msg_num.to_string()),
+            (None, _) => None,
+        };
+        AcarsMessage {
+            mode: Some(acars.mode.to_string()),
+            label: Some(acars.label.to_string()),
+            block_id: Some(acars.blk_id.to_string()),
+            ack: Some(AckType::String(acars.ack.to_string())),
+            tail: Some(acars.reg.to_string()),
+            text: Some(acars.msg_text.to_string()),
+            msgno,
+            flight: acars.flight.map(|flight| flight.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn add_all_acars(&mut self, fields: &[&str]) {
+        for field in fields {
+            self.add_acars(field);
+        }
+    }
+
+    fn report(&self, source: MessageFamily, original: &Value) -> ConversionReport {
+        let mut dropped: Vec<String> = Vec::new();
+        if let Value::Object(map) = original {
+            for (key, value) in map {
+                self.collect_dropped(value, key, &mut dropped);
+            }
+        }
+        ConversionReport { source, dropped }
+    }
+
+    /// Adds the path to the dropped fields if nothing inside it was carried over, otherwise looks inside it.
+    fn collect_dropped(&self, value: &Value, path: &str, dropped: &mut Vec<String>) {
+        if self.paths.contains(path) {
+            return;
+        }
+        let prefix: String = format!("{}.", path);
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map.iter().map(|(key, value)| (format!("{}{}", prefix, key), value)).collect(),
+            Value::Array(values) => values.iter().enumerate().map(|(index, value)| (format!("{}{}", prefix, index), value)).collect(),
+            _ => Vec::new(),
+        };
+        if children.is_empty() || !self.paths.iter().any(|carried| carried.starts_with(&prefix)) {
+            dropped.push(path.to_string());
+            return;
+        }
+        for (child_path, child) in children {
+            self.collect_dropped(child, &child_path, dropped);
+        }
+    }
+}
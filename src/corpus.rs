@@ -0,0 +1,252 @@
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+use crate::station_policy::wildcard_match;
+
+/// Tag for capture files where every line is expected to fail to decode.
+pub const KNOWN_BAD: &str = "known-bad";
+
+/// A capture file registered with a `Corpus`, one message per line, along with its tags.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct CorpusFile {
+    pub path: PathBuf,
+    pub tags: BTreeSet<String>,
+}
+
+impl CorpusFile {
+    /// Returns true if the file has the provided tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Reads the file, returning each non empty line.
+    pub fn read_lines(&self) -> io::Result<Vec<String>> {
+        BufReader::new(File::open(&self.path)?).lines()
+            .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+            .collect()
+    }
+}
+
+/// Selects the files in a `Corpus` by tag.
+///
+/// A file matches when it has every required tag and none of the excluded tags; the default filter matches every file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct TagFilter {
+    pub required: BTreeSet<String>,
+    pub excluded: BTreeSet<String>,
+}
+
+impl TagFilter {
+    /// Creates a `TagFilter` that matches every file.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Only matches files with the provided tag.
+    pub fn require(mut self, tag: &str) -> Self {
+        self.required.insert(tag.to_string());
+        self
+    }
+
+    /// Only matches files without the provided tag.
+    pub fn exclude(mut self, tag: &str) -> Self {
+        self.excluded.insert(tag.to_string());
+        self
+    }
+
+    /// Returns true if the file matches this filter.
+    pub fn matches(&self, file: &CorpusFile) -> bool {
+        self.required.iter().all(|tag| file.has_tag(tag)) && !self.excluded.iter().any(|tag| file.has_tag(tag))
+    }
+}
+
+/// A line that did not behave as expected when checking a `Corpus`.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct CorpusFailure {
+    pub path: PathBuf,
+    /// Line number in the file, starting from 1 and not counting empty lines.
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Results of checking every line of the matching files in a `Corpus`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct CorpusReport {
+    pub files: usize,
+    pub lines: usize,
+    pub decoded: usize,
+    pub failures: Vec<CorpusFailure>,
+}
+
+impl CorpusReport {
+    /// Returns true if every line behaved as expected.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// A set of tagged capture files, for running compatibility checks against captures from your own decoders.
+///
+/// Tags are free form, such as the decoder and version that produced the capture, the protocol, or `KNOWN_BAD`
+/// for captures that are expected to fail.
+/// ```
+/// use acars_vdlm2_parser::corpus::{Corpus, CorpusReport, TagFilter};
+/// let mut corpus: Corpus = Corpus::new();
+/// corpus.register("test_files/vdlm2", &["dumpvdl2", "vdlm2"])
+///     .register("test_files/acars", &["acarsdec", "acars"]);
+/// let report: CorpusReport = corpus.check_roundtrip(&TagFilter::any().require("vdlm2")).unwrap();
+/// assert!(report.is_ok());
+/// assert_eq!(report.files, 1);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct Corpus {
+    files: Vec<CorpusFile>,
+}
+
+impl Corpus {
+    /// Creates a new, empty `Corpus`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a single capture file with the provided tags.
+    pub fn register(&mut self, path: impl AsRef<Path>, tags: &[&str]) -> &mut Self {
+        self.files.push(CorpusFile {
+            path: path.as_ref().to_path_buf(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Registers every file in the directory whose name starts with the provided prefix, returning how many were registered.
+    ///
+    /// Files are registered in name order so checks always run in the same order.
+    pub fn register_dir(&mut self, dir: impl AsRef<Path>, prefix: &str, tags: &[&str]) -> io::Result<usize> {
+        let paths: Vec<PathBuf> = files_in(dir.as_ref(), |name| name.starts_with(prefix))?;
+        for path in &paths {
+            self.register(path, tags);
+        }
+        Ok(paths.len())
+    }
+
+    /// Registers every file in the directory whose name matches the pattern, where `*` matches any run of characters
+    /// and `?` a single character, returning how many were registered.
+    ///
+    /// Files are registered in name order so checks always run in the same order.
+    pub fn register_matching(&mut self, dir: impl AsRef<Path>, pattern: &str, tags: &[&str]) -> io::Result<usize> {
+        let paths: Vec<PathBuf> = files_in(dir.as_ref(), |name| wildcard_match(pattern, name))?;
+        for path in &paths {
+            self.register(path, tags);
+        }
+        Ok(paths.len())
+    }
+
+    /// Registers every file in the directory whose name matches the pattern as samples of the message family, tagged
+    /// with the family name such as `vdlm2`, returning how many were registered.
+    ///
+    /// This is how downstream projects point the sample based checks at captures they vendor themselves, laid out
+    /// however they like.
+    /// ```
+    /// use acars_vdlm2_parser::MessageFamily;
+    /// use acars_vdlm2_parser::corpus::Corpus;
+    /// let mut corpus: Corpus = Corpus::new();
+    /// assert_eq!(corpus.register_family(MessageFamily::Hfdl, "test_files", "hfdl_?").unwrap(), 4);
+    /// assert!(corpus.family_lines(Some(MessageFamily::Hfdl)).unwrap().len() > 0);
+    /// assert!(corpus.family_lines(Some(MessageFamily::Acars)).unwrap().is_empty());
+    /// ```
+    pub fn register_family(&mut self, family: MessageFamily, dir: impl AsRef<Path>, pattern: &str) -> io::Result<usize> {
+        self.register_matching(dir, pattern, &[&family.to_string()])
+    }
+
+    /// Creates a `Corpus` of every file in a directory laid out like the `test_files` of this crate, where each file
+    /// name starts with the family of the samples it holds, such as `acars` or `vdlm2_2`. Files whose names do not
+    /// start with a family are registered without a family tag.
+    pub fn from_sample_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut corpus: Corpus = Corpus::new();
+        for path in files_in(dir.as_ref(), |_| true)? {
+            let name: String = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            match MessageFamily::ALL.iter().find(|family| name.starts_with(&family.to_string())) {
+                Some(family) => corpus.register(&path, &[&family.to_string()]),
+                None => corpus.register(&path, &[]),
+            };
+        }
+        Ok(corpus)
+    }
+
+    /// Returns the registered files matching the filter.
+    pub fn files<'a>(&'a self, filter: &'a TagFilter) -> impl Iterator<Item = &'a CorpusFile> {
+        self.files.iter().filter(move |file| filter.matches(file))
+    }
+
+    /// Reads every line of the files matching the filter into a single `Vec<String>`.
+    pub fn lines(&self, filter: &TagFilter) -> io::Result<Vec<String>> {
+        let mut lines: Vec<String> = Vec::new();
+        for file in self.files(filter) {
+            lines.extend(file.read_lines()?);
+        }
+        Ok(lines)
+    }
+
+    /// Reads every line of the files registered for the family, or of every file if no family is given, into a single
+    /// `Vec<String>`.
+    pub fn family_lines(&self, family: Option<MessageFamily>) -> io::Result<Vec<String>> {
+        match family {
+            Some(family) => self.lines(&TagFilter::any().require(&family.to_string())),
+            None => self.lines(&TagFilter::any()),
+        }
+    }
+
+    /// Checks every line of the files matching the filter.
+    ///
+    /// Lines in files tagged `KNOWN_BAD` must fail to decode. Every other line must decode, serialise, and decode again
+    /// to a message that serialises to exactly the same JSON.
+    pub fn check_roundtrip(&self, filter: &TagFilter) -> io::Result<CorpusReport> {
+        let mut report: CorpusReport = CorpusReport::default();
+        for file in self.files(filter) {
+            report.files += 1;
+            let known_bad: bool = file.has_tag(KNOWN_BAD);
+            for (index, line) in file.read_lines()?.iter().enumerate() {
+                report.lines += 1;
+                let result: Result<(), String> = match (line.decode_message(), known_bad) {
+                    (Ok(_), true) => Err("known bad line decoded".to_string()),
+                    (Err(_), true) => Ok(()),
+                    (Err(error), false) => Err(format!("decode failed: {}", error)),
+                    (Ok(message), false) => {
+                        report.decoded += 1;
+                        roundtrip(&message)
+                    }
+                };
+                if let Err(reason) = result {
+                    report.failures.push(CorpusFailure { path: file.path.clone(), line: index + 1, reason });
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Returns the files in the directory whose names pass the check, in name order.
+fn files_in(dir: &Path, name_matches: impl Fn(&str) -> bool) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    paths.retain(|path| path.is_file() && path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(&name_matches));
+    paths.sort();
+    Ok(paths)
+}
+
+/// Checks that serialising the message and decoding it again gives the same JSON.
+fn roundtrip(message: &AcarsVdlm2Message) -> Result<(), String> {
+    let first: String = message.to_json().map_err(|error| format!("serialisation failed: {}", error))?;
+    let decoded: AcarsVdlm2Message = first.decode_message().map_err(|error| format!("decoding the serialised message failed: {}", error))?;
+    let second: String = decoded.to_json().map_err(|error| format!("serialisation failed: {}", error))?;
+    match first == second {
+        true => Ok(()),
+        false => Err(format!("serialised message changed after decoding again: {} became {}", first, second)),
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageFamily};
+
+/// Character decoders output in the technical acknowledgement field when a block is not acknowledging anything.
+#[cfg_attr(not(any(feature = "acars", feature = "vdlm2", feature = "hfdl")), allow(dead_code))]
+const NAK: &str = "!";
+/// Label used by ground station squitters, which are broadcast and never acknowledged.
+const SQUITTER: &str = "SQ";
+
+/// The ACARS link layer fields used to pair a block with its technical acknowledgement.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AckFields<'a> {
+    pub mode: &'a str,
+    pub registration: &'a str,
+    pub label: &'a str,
+    pub block_id: &'a str,
+    pub msgno: Option<&'a str>,
+    /// Block ID being acknowledged, or `None` for a NAK.
+    pub ack: Option<&'a str>,
+}
+
+impl<'a> AckFields<'a> {
+    /// Converts the technical acknowledgement field as output by the decoders, treating a NAK as `None`.
+    #[cfg_attr(not(any(feature = "acars", feature = "vdlm2", feature = "hfdl")), allow(dead_code))]
+    pub(crate) fn parse_ack(ack: &'a str) -> Option<&'a str> {
+        match ack {
+            "" | NAK => None,
+            ack => Some(ack),
+        }
+    }
+}
+
+/// A block seen by an `AckCorrelator` that is waiting for its acknowledgement.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PendingMessage {
+    pub family: MessageFamily,
+    pub mode: String,
+    pub registration: String,
+    pub label: String,
+    pub block_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msgno: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub station: Option<String>,
+    /// Time the block was first seen, in seconds since the Unix epoch.
+    pub time: f64,
+    /// Number of times the same block was seen again before it was acknowledged.
+    pub retransmissions: u32,
+}
+
+/// A block paired with the message that acknowledged it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AckPair {
+    pub message: PendingMessage,
+    /// Label of the acknowledging message, `_d` when it carried no other data.
+    pub ack_label: String,
+    /// Time the acknowledgement was seen, in seconds since the Unix epoch.
+    pub ack_time: f64,
+    /// Round trip time between the first transmission of the block and its acknowledgement.
+    pub latency_secs: f64,
+}
+
+type PendingKey = (String, String, String);
+
+/// Pairs ACARS blocks with the technical acknowledgements sent in response to them.
+///
+/// Downlink blocks use a numeric block ID and uplink blocks use a letter, and the acknowledging message in the other
+/// direction carries that block ID in its `ack` field, so pairs are matched on mode, registration and block ID.
+/// A block seen again with the same label and message number before being acknowledged is counted as a retransmission,
+/// and latency is measured from the first transmission.
+/// Blocks are reported as unacknowledged once they have waited longer than the timeout, or when their block ID is reused
+/// for a different message. Messages without a timestamp are ignored.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::correlate::{AckCorrelator, AckPair};
+/// let mut correlator: AckCorrelator = AckCorrelator::new(30.0);
+/// let downlink: AcarsVdlm2Message = r#"{"freq":131.55,"timestamp":100.0,"mode":"2","tail":".N123AB","label":"H1","block_id":"4","ack":false,"msgno":"M01A"}"#.decode_message().unwrap();
+/// let uplink: AcarsVdlm2Message = r#"{"freq":131.55,"timestamp":101.5,"mode":"2","tail":".N123AB","label":"_d","block_id":"A","ack":"4"}"#.decode_message().unwrap();
+/// assert!(correlator.push(&downlink).is_none());
+/// let pair: AckPair = correlator.push(&uplink).unwrap();
+/// assert_eq!((pair.message.label.as_str(), pair.latency_secs), ("H1", 1.5));
+/// assert_eq!(correlator.flush().len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AckCorrelator {
+    timeout: f64,
+    pending: HashMap<PendingKey, PendingMessage>,
+    replaced: Vec<PendingMessage>,
+}
+
+impl AckCorrelator {
+    /// Creates an `AckCorrelator` that gives up on a block once it has waited `timeout_secs` for an acknowledgement.
+    pub fn new(timeout_secs: f64) -> Self {
+        Self { timeout: timeout_secs, pending: HashMap::new(), replaced: Vec::new() }
+    }
+
+    /// Number of blocks waiting for an acknowledgement.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Adds a message, returning the pair if it acknowledges a block seen earlier.
+    pub fn push(&mut self, message: &AcarsVdlm2Message) -> Option<AckPair> {
+        let fields: AckFields = message.get_ack_fields()?;
+        let time: f64 = message.get_time()?;
+        let pair: Option<AckPair> = fields.ack
+            .and_then(|ack| self.pending.remove(&(fields.mode.to_string(), fields.registration.to_string(), ack.to_string())))
+            .map(|pending| AckPair {
+                ack_label: fields.label.to_string(),
+                ack_time: time,
+                latency_secs: time - pending.time,
+                message: pending,
+            });
+        if !fields.block_id.is_empty() && fields.label != SQUITTER {
+            self.track(message, &fields, time);
+        }
+        pair
+    }
+
+    fn track(&mut self, message: &AcarsVdlm2Message, fields: &AckFields, time: f64) {
+        let key: PendingKey = (fields.mode.to_string(), fields.registration.to_string(), fields.block_id.to_string());
+        if let Some(existing) = self.pending.get_mut(&key) {
+            if existing.label == fields.label && existing.msgno.as_deref() == fields.msgno {
+                existing.retransmissions += 1;
+                return;
+            }
+        }
+        let pending: PendingMessage = PendingMessage {
+            family: message.get_family(),
+            mode: fields.mode.to_string(),
+            registration: fields.registration.to_string(),
+            label: fields.label.to_string(),
+            block_id: fields.block_id.to_string(),
+            msgno: fields.msgno.map(|msgno| msgno.to_string()),
+            station: message.get_station_name().map(|station| station.to_string()),
+            time,
+            retransmissions: 0,
+        };
+        if let Some(replaced) = self.pending.insert(key, pending) {
+            self.replaced.push(replaced);
+        }
+    }
+
+    /// Returns the blocks that have not been acknowledged by the provided time, along with any whose block ID was reused.
+    pub fn expire(&mut self, now: f64) -> Vec<PendingMessage> {
+        let cutoff: f64 = now - self.timeout;
+        let mut expired: Vec<PendingMessage> = std::mem::take(&mut self.replaced);
+        let keys: Vec<PendingKey> = self.pending.iter()
+            .filter(|(_, pending)| pending.time < cutoff)
+            .map(|(key, _)| key.clone())
+            .collect();
+        expired.extend(keys.iter().filter_map(|key| self.pending.remove(key)));
+        expired.sort_by(|a, b| a.time.total_cmp(&b.time));
+        expired
+    }
+
+    /// Returns every block that has not been acknowledged, regardless of how long it has waited.
+    pub fn flush(&mut self) -> Vec<PendingMessage> {
+        self.expire(f64::INFINITY)
+    }
+}
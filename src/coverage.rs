@@ -0,0 +1,327 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::registrations::csv_field;
+use crate::visit::{MessageVisitor, PositionReport, PositionSource, SignalInfo};
+
+/// Mean radius of the Earth in nautical miles.
+const EARTH_RADIUS_NM: f64 = 3440.065;
+
+/// How positions are grouped into the cells of a `CoverageHeatmap`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case", try_from = "RawBinning")]
+pub enum CoverageBinning {
+    /// Squares of latitude and longitude, `cell_deg` degrees on each side.
+    Geographic { cell_deg: f64 },
+    /// Rings of `range_step_nm` nautical miles and sectors of `bearing_step_deg` degrees around the receiver.
+    RangeBearing { range_step_nm: f64, bearing_step_deg: f64 },
+}
+
+/// Where the messages counted in a `CoverageCell` were sent from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "bin", rename_all = "snake_case", try_from = "RawBin")]
+pub enum CoverageBin {
+    /// No position was known for the aircraft, or the receiver location was not set for range and bearing bins.
+    Unlocated,
+    /// The south west corner of a geographic cell.
+    Geographic { lat: f64, lon: f64 },
+    /// The inner edge and first bearing of a range and bearing cell.
+    RangeBearing { range_nm: f64, bearing_deg: f64 },
+}
+
+/// The number of messages a station received from one bin at one signal level.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawCell")]
+pub struct CoverageCell {
+    /// Station name the messages were tagged with, empty if they had none.
+    pub station: String,
+    #[serde(flatten)]
+    pub bin: CoverageBin,
+    /// Lower edge of the signal level band, or `None` for messages without a signal level.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_dbm: Option<f64>,
+    pub messages: u64,
+}
+
+// Internally tagged and flattened enums are buffered before they are decoded, and a buffered number reaches an `f64`
+// as a map when serde_json's `arbitrary_precision` feature is enabled. These read the same fields flat instead.
+
+/// A `CoverageBinning` as it is written.
+#[derive(Deserialize)]
+struct RawBinning {
+    kind: String,
+    cell_deg: Option<f64>,
+    range_step_nm: Option<f64>,
+    bearing_step_deg: Option<f64>,
+}
+
+impl TryFrom<RawBinning> for CoverageBinning {
+    type Error = String;
+
+    fn try_from(raw: RawBinning) -> Result<Self, Self::Error> {
+        match (raw.kind.as_str(), raw.cell_deg, raw.range_step_nm, raw.bearing_step_deg) {
+            ("geographic", Some(cell_deg), _, _) => Ok(CoverageBinning::Geographic { cell_deg }),
+            ("range_bearing", _, Some(range_step_nm), Some(bearing_step_deg)) =>
+                Ok(CoverageBinning::RangeBearing { range_step_nm, bearing_step_deg }),
+            ("geographic" | "range_bearing", _, _, _) => Err(format!("missing the steps of a {} binning", raw.kind)),
+            (kind, _, _, _) => Err(format!("unknown coverage binning `{}`", kind)),
+        }
+    }
+}
+
+/// A `CoverageBin` as it is written.
+#[derive(Deserialize)]
+struct RawBin {
+    bin: String,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    range_nm: Option<f64>,
+    bearing_deg: Option<f64>,
+}
+
+impl TryFrom<RawBin> for CoverageBin {
+    type Error = String;
+
+    fn try_from(raw: RawBin) -> Result<Self, Self::Error> {
+        match (raw.bin.as_str(), raw.lat, raw.lon, raw.range_nm, raw.bearing_deg) {
+            ("unlocated", _, _, _, _) => Ok(CoverageBin::Unlocated),
+            ("geographic", Some(lat), Some(lon), _, _) => Ok(CoverageBin::Geographic { lat, lon }),
+            ("range_bearing", _, _, Some(range_nm), Some(bearing_deg)) => Ok(CoverageBin::RangeBearing { range_nm, bearing_deg }),
+            ("geographic" | "range_bearing", _, _, _, _) => Err(format!("missing the position of a {} bin", raw.bin)),
+            (bin, _, _, _, _) => Err(format!("unknown coverage bin `{}`", bin)),
+        }
+    }
+}
+
+/// A `CoverageCell` as it is written, with the fields of its bin alongside its own.
+#[derive(Deserialize)]
+struct RawCell {
+    station: String,
+    bin: String,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    range_nm: Option<f64>,
+    bearing_deg: Option<f64>,
+    level_dbm: Option<f64>,
+    messages: u64,
+}
+
+impl TryFrom<RawCell> for CoverageCell {
+    type Error = String;
+
+    fn try_from(raw: RawCell) -> Result<Self, Self::Error> {
+        let bin: CoverageBin = CoverageBin::try_from(RawBin {
+            bin: raw.bin,
+            lat: raw.lat,
+            lon: raw.lon,
+            range_nm: raw.range_nm,
+            bearing_deg: raw.bearing_deg,
+        })?;
+        Ok(Self { station: raw.station, bin, level_dbm: raw.level_dbm, messages: raw.messages })
+    }
+}
+
+/// A bin as whole numbers of steps, so it can be used as a key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+enum BinKey {
+    Unlocated,
+    Cell(i64, i64),
+}
+
+type CellKey = (String, BinKey, Option<i64>);
+
+/// The last position seen for an aircraft, used for its messages that do not carry one.
+#[derive(Debug, Clone, Copy)]
+struct KnownPosition {
+    time: Option<f64>,
+    lat: f64,
+    lon: f64,
+}
+
+/// Counts received messages by station, signal level and where they were sent from, for receivers evaluating antenna
+/// changes.
+///
+/// Messages carrying an aircraft position, such as ADS-C reports and HFDL position reports, are placed by that position,
+/// and later messages from the same ICAO address are placed by it until it is older than the maximum position age, as
+/// long as both messages have a time. Everything else is counted as `Unlocated`, so stations without positions still
+/// get a heatmap of signal levels. Range and bearing bins need the location of each receiver, set with `receiver`.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::coverage::{CoverageBin, CoverageBinning, CoverageCell, CoverageHeatmap};
+/// let mut heatmap: CoverageHeatmap = CoverageHeatmap::new(CoverageBinning::Geographic { cell_deg: 1.0 });
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"level":-23.5,"station_id":"YPPH"}"#.decode_message().unwrap();
+/// heatmap.record(&message);
+/// heatmap.record(&message);
+/// let cells: Vec<CoverageCell> = heatmap.cells();
+/// assert_eq!(cells.len(), 1);
+/// assert_eq!((cells[0].station.as_str(), cells[0].bin, cells[0].level_dbm, cells[0].messages), ("YPPH", CoverageBin::Unlocated, Some(-25.0), 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CoverageHeatmap {
+    binning: CoverageBinning,
+    level_step_db: f64,
+    max_position_age_secs: f64,
+    receivers: HashMap<String, (f64, f64)>,
+    positions: HashMap<u32, KnownPosition>,
+    cells: BTreeMap<CellKey, u64>,
+}
+
+impl CoverageHeatmap {
+    /// Positions older than this are not used for later messages unless the age is changed with `max_position_age_secs`.
+    pub const DEFAULT_MAX_POSITION_AGE_SECS: f64 = 300.0;
+    /// Signal levels are grouped into bands this many dB wide unless changed with `level_step_db`.
+    pub const DEFAULT_LEVEL_STEP_DB: f64 = 5.0;
+
+    /// Creates an empty `CoverageHeatmap` grouping positions with the provided binning.
+    pub fn new(binning: CoverageBinning) -> Self {
+        Self {
+            binning,
+            level_step_db: Self::DEFAULT_LEVEL_STEP_DB,
+            max_position_age_secs: Self::DEFAULT_MAX_POSITION_AGE_SECS,
+            receivers: HashMap::new(),
+            positions: HashMap::new(),
+            cells: BTreeMap::new(),
+        }
+    }
+
+    /// Sets how wide each band of signal levels is, in dB.
+    pub fn level_step_db(mut self, level_step_db: f64) -> Self {
+        self.level_step_db = level_step_db;
+        self
+    }
+
+    /// Sets how long an aircraft's last position is used for its messages that do not carry one.
+    pub fn max_position_age_secs(mut self, max_position_age_secs: f64) -> Self {
+        self.max_position_age_secs = max_position_age_secs;
+        self
+    }
+
+    /// Sets the location of the receiver for a station name, used for range and bearing bins.
+    pub fn receiver(mut self, station: &str, lat: f64, lon: f64) -> Self {
+        self.receivers.insert(station.to_string(), (lat, lon));
+        self
+    }
+
+    /// Counts a single message.
+    pub fn record(&mut self, message: &AcarsVdlm2Message) {
+        let mut found: CoverageVisitor = CoverageVisitor::default();
+        message.visit(&mut found);
+        let time: Option<f64> = message.get_time();
+        let position: Option<(f64, f64)> = match (found.position, message.get_icao()) {
+            (Some((lat, lon)), icao) => {
+                if let Some(icao) = icao {
+                    self.positions.insert(icao, KnownPosition { time, lat, lon });
+                }
+                Some((lat, lon))
+            }
+            (None, Some(icao)) => self.positions.get(&icao)
+                .filter(|known| match (known.time, time) {
+                    (Some(seen), Some(now)) => (now - seen).abs() <= self.max_position_age_secs,
+                    _ => false,
+                })
+                .map(|known| (known.lat, known.lon)),
+            (None, None) => None,
+        };
+        let station: String = message.get_station_name().unwrap_or_default().to_string();
+        let bin: BinKey = position.map_or(BinKey::Unlocated, |(lat, lon)| self.bin_key(&station, lat, lon));
+        let level: Option<i64> = found.level_dbm.map(|level| (level / self.level_step_db).floor() as i64);
+        *self.cells.entry((station, bin, level)).or_default() += 1;
+    }
+
+    /// Returns every cell with messages counted, ordered by station, bin and signal level.
+    pub fn cells(&self) -> Vec<CoverageCell> {
+        self.cells.iter()
+            .map(|((station, bin, level), messages)| CoverageCell {
+                station: station.clone(),
+                bin: self.bin(*bin),
+                level_dbm: level.map(|level| level as f64 * self.level_step_db),
+                messages: *messages,
+            })
+            .collect()
+    }
+
+    /// Writes the cells as a JSON array.
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(writer, &self.cells())
+    }
+
+    /// Writes the cells as CSV with a header row. The two position columns are the latitude and longitude, or the
+    /// range and bearing, of the bin, and are empty for unlocated messages.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "station,bin,position_1,position_2,level_dbm,messages")?;
+        for cell in self.cells() {
+            let (bin, first, second): (&str, Option<f64>, Option<f64>) = match cell.bin {
+                CoverageBin::Unlocated => ("unlocated", None, None),
+                CoverageBin::Geographic { lat, lon } => ("geographic", Some(lat), Some(lon)),
+                CoverageBin::RangeBearing { range_nm, bearing_deg } => ("range_bearing", Some(range_nm), Some(bearing_deg)),
+            };
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                csv_field(&cell.station),
+                bin,
+                first.map(|value| value.to_string()).unwrap_or_default(),
+                second.map(|value| value.to_string()).unwrap_or_default(),
+                cell.level_dbm.map(|level| level.to_string()).unwrap_or_default(),
+                cell.messages,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn bin_key(&self, station: &str, lat: f64, lon: f64) -> BinKey {
+        match self.binning {
+            CoverageBinning::Geographic { cell_deg } => BinKey::Cell((lat / cell_deg).floor() as i64, (lon / cell_deg).floor() as i64),
+            CoverageBinning::RangeBearing { range_step_nm, bearing_step_deg } => match self.receivers.get(station) {
+                Some((receiver_lat, receiver_lon)) => {
+                    let (range_nm, bearing_deg): (f64, f64) = range_and_bearing(*receiver_lat, *receiver_lon, lat, lon);
+                    BinKey::Cell((range_nm / range_step_nm).floor() as i64, (bearing_deg / bearing_step_deg).floor() as i64)
+                }
+                None => BinKey::Unlocated,
+            },
+        }
+    }
+
+    fn bin(&self, key: BinKey) -> CoverageBin {
+        match (key, self.binning) {
+            (BinKey::Unlocated, _) => CoverageBin::Unlocated,
+            (BinKey::Cell(lat, lon), CoverageBinning::Geographic { cell_deg }) =>
+                CoverageBin::Geographic { lat: lat as f64 * cell_deg, lon: lon as f64 * cell_deg },
+            (BinKey::Cell(range, bearing), CoverageBinning::RangeBearing { range_step_nm, bearing_step_deg }) =>
+                CoverageBin::RangeBearing { range_nm: range as f64 * range_step_nm, bearing_deg: bearing as f64 * bearing_step_deg },
+        }
+    }
+}
+
+/// Collects the signal level and the first aircraft position in a message.
+#[derive(Default)]
+struct CoverageVisitor {
+    level_dbm: Option<f64>,
+    position: Option<(f64, f64)>,
+}
+
+impl MessageVisitor for CoverageVisitor {
+    fn on_signal_info(&mut self, signal: &SignalInfo) {
+        self.level_dbm = signal.level_dbm;
+    }
+
+    fn on_position(&mut self, position: &PositionReport) {
+        if position.source != PositionSource::GroundStation && self.position.is_none() {
+            self.position = Some((position.lat, position.lon));
+        }
+    }
+}
+
+/// Returns the great circle distance in nautical miles, and the initial bearing in degrees from north, from the first
+/// position to the second.
+fn range_and_bearing(from_lat: f64, from_lon: f64, to_lat: f64, to_lon: f64) -> (f64, f64) {
+    let (from_lat, to_lat): (f64, f64) = (from_lat.to_radians(), to_lat.to_radians());
+    let delta_lon: f64 = (to_lon - from_lon).to_radians();
+    let delta_lat: f64 = to_lat - from_lat;
+    let haversine: f64 = (delta_lat / 2.0).sin().powi(2) + from_lat.cos() * to_lat.cos() * (delta_lon / 2.0).sin().powi(2);
+    let range_nm: f64 = 2.0 * EARTH_RADIUS_NM * haversine.sqrt().min(1.0).asin();
+    let bearing: f64 = (delta_lon.sin() * to_lat.cos())
+        .atan2(from_lat.cos() * to_lat.sin() - from_lat.sin() * to_lat.cos() * delta_lon.cos());
+    (range_nm, bearing.to_degrees().rem_euclid(360.0))
+}
@@ -0,0 +1,144 @@
+//! Ingest of the CSV lines written by older acars-bridge and "plane finder" style UDP feeders, which predate the JSON
+//! output of acarsdec.
+//!
+//! Each line holds the columns in `COLUMNS` in that order:
+//!
+//! ```text
+//! timestamp,station_id,channel,freq,level,error,mode,label,block_id,ack,tail,flight,msgno,text
+//! ```
+//!
+//! The frequency is in MHz and the timestamp is in seconds since the epoch. Empty columns are left unset. Any column
+//! can be quoted with `"`, doubling a quote inside it, and the text column takes the rest of the line, so unquoted text
+//! can still hold commas.
+//!
+//! Lines are recognised by `is_csv_legacy` whichever families are enabled, but only parsed with the `acars` feature.
+#[cfg(feature = "acars")]
+use serde::de::Error as DeError;
+#[cfg(feature = "acars")]
+use crate::acars::{AckType, AcarsMessage, LevelType};
+#[cfg(feature = "acars")]
+use crate::frequency::Frequency;
+#[cfg(feature = "acars")]
+use crate::MessageResult;
+
+/// Names of the columns of a legacy CSV line, in the order they are written.
+pub const COLUMNS: [&str; 14] = [
+    "timestamp", "station_id", "channel", "freq", "level", "error", "mode",
+    "label", "block_id", "ack", "tail", "flight", "msgno", "text",
+];
+
+/// Returns true if the buffer looks like a legacy CSV line: it starts with a digit, as the timestamp does, and has
+/// at least a separator for every column.
+///
+/// This is checked before any parsing, so a JSON object or other input is never mistaken for a CSV line.
+pub fn is_csv_legacy(bytes: &[u8]) -> bool {
+    let trimmed: &[u8] = bytes.trim_ascii();
+    matches!(trimmed.first(), Some(b'0'..=b'9'))
+        && !trimmed.contains(&b'\n')
+        && trimmed.iter().filter(|byte| **byte == b',').count() >= COLUMNS.len() - 1
+}
+
+/// Parses a legacy CSV line into an `AcarsMessage`, mapping each column in `COLUMNS` to the field of the same name.
+///
+/// `DecodeMessage` calls this for any input `is_csv_legacy` accepts, so these lines can be routed like any other
+/// ACARS message.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::acars::AcarsMessage;
+/// use acars_vdlm2_parser::csv_legacy::parse_csv_legacy;
+/// let line: &str = "1672531200.5,EXAMPLE-STATION,2,131.550,-24.5,0,2,H1,4,!,.N12345,UA0123,M01A,POS N47,W122";
+/// let message: AcarsMessage = parse_csv_legacy(line).unwrap();
+/// assert_eq!(message.tail.as_deref(), Some(".N12345"));
+/// assert_eq!(message.text.as_deref(), Some("POS N47,W122"));
+/// let decoded: AcarsVdlm2Message = line.decode_message().unwrap();
+/// assert_eq!(decoded.get_flight(), Some("UA0123"));
+/// ```
+#[cfg(feature = "acars")]
+pub fn parse_csv_legacy(line: &str) -> MessageResult<AcarsMessage> {
+    trace!("Parsing legacy CSV line {}", line);
+    let line: &str = line.trim_matches(|character: char| character == '\r' || character == '\n');
+    let mut columns: Vec<String> = Vec::with_capacity(COLUMNS.len());
+    let mut rest: &str = line;
+    for _ in 0..COLUMNS.len() - 1 {
+        let (column, remainder): (String, Option<&str>) = split_column(rest)?;
+        columns.push(column);
+        rest = remainder.ok_or_else(|| {
+            serde_json::Error::custom(format!("legacy CSV line has {} columns, expected {}", columns.len(), COLUMNS.len()))
+        })?;
+    }
+    let text: String = match rest.starts_with('"') {
+        true => match split_column(rest)? {
+            (text, None) => text,
+            (_, Some(_)) => return Err(serde_json::Error::custom("unexpected data after the quoted text column")),
+        },
+        false => rest.to_string(),
+    };
+    columns.push(text);
+    let column = |index: usize| -> Option<String> {
+        Some(columns[index].trim().to_string()).filter(|value| !value.is_empty())
+    };
+    let frequency: Frequency = parse_number(column(3), COLUMNS[3])?
+        .ok_or_else(|| serde_json::Error::custom("missing field `freq` in legacy CSV line"))?;
+    Ok(AcarsMessage {
+        freq: frequency,
+        channel: parse_number(column(2), COLUMNS[2])?,
+        error: parse_number(column(5), COLUMNS[5])?,
+        level: match column(4) {
+            None => None,
+            Some(level) => Some(match level.parse::<i32>() {
+                Ok(level) => LevelType::I32(level),
+                Err(_) => LevelType::Float64(parse_number(Some(level), COLUMNS[4])?.unwrap_or_default()),
+            }),
+        },
+        timestamp: parse_number(column(0), COLUMNS[0])?,
+        station_id: column(1),
+        mode: column(6),
+        label: column(7),
+        block_id: column(8),
+        ack: column(9).map(AckType::String),
+        tail: column(10),
+        flight: column(11),
+        msgno: column(12),
+        text: Some(columns[13].clone()).filter(|text| !text.is_empty()),
+        ..Default::default()
+    })
+}
+
+/// Parses a column as a number, naming the column in the error if it is not one.
+#[cfg(feature = "acars")]
+fn parse_number<T: std::str::FromStr>(value: Option<String>, name: &str) -> MessageResult<Option<T>> {
+    value.map(|value| value.parse::<T>().map_err(|_| {
+        serde_json::Error::custom(format!("invalid value `{}` for `{}` in legacy CSV line", value, name))
+    })).transpose()
+}
+
+/// Splits the first column from the line, unquoting it if it is quoted, and returns what follows its separator.
+#[cfg(feature = "acars")]
+fn split_column(line: &str) -> MessageResult<(String, Option<&str>)> {
+    if let Some(quoted) = line.strip_prefix('"') {
+        let mut column: String = String::new();
+        let mut characters = quoted.char_indices().peekable();
+        while let Some((index, character)) = characters.next() {
+            match character {
+                '"' if characters.peek().map(|(_, next)| *next) == Some('"') => {
+                    column.push('"');
+                    characters.next();
+                }
+                '"' => {
+                    let after: &str = &quoted[index + 1..];
+                    return match after.strip_prefix(',') {
+                        Some(rest) => Ok((column, Some(rest))),
+                        None if after.is_empty() => Ok((column, None)),
+                        None => Err(serde_json::Error::custom("expected a separator after a quoted column")),
+                    };
+                }
+                character => column.push(character),
+            }
+        }
+        return Err(serde_json::Error::custom("unterminated quoted column in legacy CSV line"));
+    }
+    match line.split_once(',') {
+        Some((column, rest)) => Ok((column.to_string(), Some(rest))),
+        None => Ok((line.to_string(), None)),
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use std::io::Write;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, DecodeMessage, MessageFamily, MessageResult};
+use crate::csv_legacy::is_csv_legacy;
+use crate::error_code::ErrorCode;
+use crate::provider;
+use crate::sniff::{is_uat_raw, sniff};
+use crate::validate::{RejectReason, ValidationHooks};
+
+/// Details of an input that could not be decoded, kept so it can be collected and attached to bug reports.
+///
+/// This serialises to JSON so it can be written as a line to a dead-letter file or socket.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FailedDecode {
+    /// The input exactly as it was received.
+    pub raw: String,
+    /// The error returned by the decoder.
+    pub error: String,
+    /// Stable code for the kind of error, or `None` when read from entries written before codes were added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
+    /// The message families the decoder tried before giving up, see `attempted_families`. This is empty when the input
+    /// was not identified as any enabled family, such as when it is not JSON.
+    pub attempted_families: Vec<MessageFamily>,
+    /// Seconds since the Unix epoch when the decode failed.
+    pub timestamp: f64,
+    /// Optional tag identifying where the input came from, such as a feeder name or socket address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl FailedDecode {
+    /// Creates a new `FailedDecode` for the provided input and error, timestamped with the current time.
+    pub fn new(raw: &str, error: &serde_json::Error, attempted_families: &[MessageFamily], source: Option<&str>) -> Self {
+        let timestamp: f64 = provider::now();
+        Self {
+            raw: raw.to_string(),
+            error: error.to_string(),
+            code: Some(ErrorCode::of(error)),
+            attempted_families: attempted_families.to_vec(),
+            timestamp,
+            source: source.map(|source| source.to_string()),
+        }
+    }
+
+    /// Creates a new `FailedDecode` for an input that decoded as the family but was rejected by a validation hook,
+    /// timestamped with the current time.
+    pub fn rejected(raw: &str, reason: &RejectReason, family: MessageFamily, source: Option<&str>) -> Self {
+        let timestamp: f64 = provider::now();
+        Self {
+            raw: raw.to_string(),
+            error: reason.to_string(),
+            code: Some(ErrorCode::Rejected),
+            attempted_families: vec![family],
+            timestamp,
+            source: source.map(|source| source.to_string()),
+        }
+    }
+
+    /// Converts `FailedDecode` to JSON encoded as bytes and terminated with a `\n`.
+    pub fn to_bytes_newline(&self) -> MessageResult<Vec<u8>> {
+        let mut bytes: Vec<u8> = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+}
+
+/// Displays the error with its code in front, if it has one, such as `[json.syntax] expected value at line 1 column 1`.
+impl fmt::Display for FailedDecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "[{}] {}", code, self.error),
+            None => f.write_str(&self.error),
+        }
+    }
+}
+
+/// Returns the message families the decoder tries for an input, as recorded in `FailedDecode::attempted_families`.
+///
+/// Legacy CSV lines are decoded as ACARS and raw dump978-fa frames as UAT. A JSON object is decoded only as the family
+/// `sniff::sniff` identifies it as, and anything else fails before a family is tried. A family whose feature is not
+/// enabled is never tried.
+/// ```
+/// use acars_vdlm2_parser::MessageFamily;
+/// use acars_vdlm2_parser::dead_letter::attempted_families;
+/// assert_eq!(attempted_families(br#"{"vdl2":{"freq":136975000}}"#), vec![MessageFamily::Vdlm2]);
+/// assert_eq!(attempted_families(b"not json"), vec![]);
+/// ```
+pub fn attempted_families(raw: &[u8]) -> Vec<MessageFamily> {
+    let family: Option<MessageFamily> = match raw {
+        _ if is_csv_legacy(raw) => Some(MessageFamily::Acars),
+        _ if is_uat_raw(raw) => Some(MessageFamily::Uat),
+        _ => sniff(raw),
+    };
+    family.into_iter().filter(MessageFamily::is_enabled).collect()
+}
+
+/// Decodes a batch of inputs, returning a `FailedDecode` with full context for every input that fails.
+///
+/// The results are returned in the same order as the inputs.
+/// ```
+/// use acars_vdlm2_parser::AcarsVdlm2Message;
+/// use acars_vdlm2_parser::dead_letter::{decode_batch, FailedDecode};
+/// let lines: Vec<&str> = vec![r#"{"freq":131.55,"channel":2}"#, "not json"];
+/// let results: Vec<Result<AcarsVdlm2Message, FailedDecode>> = decode_batch(&lines, Some("feeder-1"));
+/// assert!(results[0].is_ok());
+/// let failed: &FailedDecode = results[1].as_ref().unwrap_err();
+/// assert_eq!(failed.raw, "not json");
+/// assert_eq!(failed.source.as_deref(), Some("feeder-1"));
+/// assert_eq!(failed.to_string(), "[json.syntax] expected ident at line 1 column 2");
+/// ```
+pub fn decode_batch<S: AsRef<str>>(inputs: &[S], source: Option<&str>) -> Vec<Result<AcarsVdlm2Message, FailedDecode>> {
+    inputs.iter()
+        .map(|input| {
+            let input: &str = input.as_ref();
+            input.decode_message()
+                .map_err(|error| FailedDecode::new(input, &error, &attempted_families(input.as_bytes()), source))
+        })
+        .collect()
+}
+
+/// Decodes a batch of inputs like `decode_batch`, then runs the validation hooks against every message that decodes.
+///
+/// Rejected messages are returned as a `FailedDecode` with the code `message.rejected` and the reason as the error.
+/// ```
+/// use acars_vdlm2_parser::AcarsVdlm2Message;
+/// use acars_vdlm2_parser::dead_letter::{decode_batch_validated, FailedDecode};
+/// use acars_vdlm2_parser::validate::{RejectReason, ValidationHooks};
+/// let mut hooks: ValidationHooks = ValidationHooks::new();
+/// hooks.add(Box::new(|message: &AcarsVdlm2Message| match message.get_registration() {
+///     Some("TEST1") => Err(RejectReason::new("ground test aircraft")),
+///     _ => Ok(()),
+/// }));
+/// let lines: Vec<&str> = vec![r#"{"freq":131.55,"channel":2,"tail":".TEST1"}"#, r#"{"freq":131.55,"channel":2,"tail":".VH-ABC"}"#];
+/// let results: Vec<Result<AcarsVdlm2Message, FailedDecode>> = decode_batch_validated(&lines, None, &hooks);
+/// assert_eq!(results[0].as_ref().unwrap_err().to_string(), "[message.rejected] ground test aircraft");
+/// assert!(results[1].is_ok());
+/// ```
+pub fn decode_batch_validated<S: AsRef<str>>(
+    inputs: &[S],
+    source: Option<&str>,
+    hooks: &ValidationHooks,
+) -> Vec<Result<AcarsVdlm2Message, FailedDecode>> {
+    inputs.iter()
+        .zip(decode_batch(inputs, source))
+        .map(|(input, result)| {
+            let message: AcarsVdlm2Message = result?;
+            hooks.check(&message)
+                .map_err(|reason| FailedDecode::rejected(input.as_ref(), &reason, message.get_family(), source))?;
+            Ok(message)
+        })
+        .collect()
+}
+
+/// Writes `FailedDecode` entries as newline delimited JSON to any writer, such as a file or a `TcpStream`.
+pub struct DeadLetterWriter<W: Write> {
+    writer: W,
+    written: usize,
+}
+
+impl<W: Write> DeadLetterWriter<W> {
+    /// Creates a new `DeadLetterWriter` around the provided writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer, written: 0 }
+    }
+
+    /// Writes a single `FailedDecode` as a line of JSON.
+    pub fn write(&mut self, failed: &FailedDecode) -> MessageResult<()> {
+        serde_json::to_writer(&mut self.writer, failed)?;
+        self.writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> MessageResult<()> {
+        self.writer.flush().map_err(serde_json::Error::io)
+    }
+
+    /// Number of entries written so far.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Consumes the `DeadLetterWriter`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
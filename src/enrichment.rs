@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+use crate::icao::parse_icao_hex;
+use crate::transform::{Transform, TransformOutcome};
+
+/// Details about an aircraft from an `AircraftDatabase`, serialised as the `aircraft_info` block on enriched messages.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AircraftInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registration: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub aircraft_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+}
+
+/// A row of an aircraft database CSV file, accepting the column names used by the common public databases.
+#[derive(Deserialize)]
+struct AircraftRow {
+    #[serde(alias = "icao24", alias = "hex")]
+    icao: String,
+    #[serde(default, alias = "reg")]
+    registration: Option<String>,
+    #[serde(default, rename = "type", alias = "typecode", alias = "aircraft_type")]
+    aircraft_type: Option<String>,
+    #[serde(default, alias = "owner")]
+    operator: Option<String>,
+}
+
+/// Trims a CSV value, treating an empty one as missing.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// A user provided table of aircraft keyed by ICAO address, used to annotate messages with an `aircraft_info` block.
+///
+/// Loaded from a CSV file with a header row containing an `icao` column (also accepted as `icao24` or `hex`) and any
+/// of `registration` (`reg`), `type` (`typecode`, `aircraft_type`) and `operator` (`owner`). Other columns are ignored,
+/// as are rows without a valid 6 character hex address.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::enrichment::AircraftDatabase;
+/// let csv: &str = "icao,registration,type,operator\n7C1234,VH-ABC,B738,Example Air\n";
+/// let database: AircraftDatabase = AircraftDatabase::from_csv_reader(csv.as_bytes()).unwrap();
+/// let mut message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"icao":8131124}"#.decode_message().unwrap();
+/// assert!(database.enrich(&mut message));
+/// assert!(message.to_json().unwrap().ends_with(r#""aircraft_info":{"registration":"VH-ABC","type":"B738","operator":"Example Air"}}"#));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AircraftDatabase {
+    aircraft: HashMap<u32, AircraftInfo>,
+}
+
+impl AircraftDatabase {
+    /// Creates a new, empty `AircraftDatabase`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads an `AircraftDatabase` from CSV data.
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self, csv::Error> {
+        let mut database: AircraftDatabase = AircraftDatabase::new();
+        let mut csv_reader: csv::Reader<R> = csv::ReaderBuilder::new().trim(csv::Trim::Headers).from_reader(reader);
+        for row in csv_reader.deserialize::<AircraftRow>() {
+            let row: AircraftRow = row?;
+            if let Some(icao) = parse_icao_hex(row.icao.trim()) {
+                database.insert(icao, AircraftInfo {
+                    registration: non_empty(row.registration),
+                    aircraft_type: non_empty(row.aircraft_type),
+                    operator: non_empty(row.operator),
+                });
+            }
+        }
+        Ok(database)
+    }
+
+    /// Loads an `AircraftDatabase` from a CSV file.
+    pub fn from_csv_path(path: impl AsRef<Path>) -> Result<Self, csv::Error> {
+        Self::from_csv_reader(std::fs::File::open(path)?)
+    }
+
+    /// Adds or replaces the details for an aircraft.
+    pub fn insert(&mut self, icao: u32, info: AircraftInfo) {
+        self.aircraft.insert(icao, info);
+    }
+
+    /// Returns the details for an aircraft if the database has them.
+    pub fn get(&self, icao: u32) -> Option<&AircraftInfo> {
+        self.aircraft.get(&icao)
+    }
+
+    /// Returns the number of aircraft in the database.
+    pub fn len(&self) -> usize {
+        self.aircraft.len()
+    }
+
+    /// Returns true if the database has no aircraft.
+    pub fn is_empty(&self) -> bool {
+        self.aircraft.is_empty()
+    }
+
+    /// Sets the `aircraft_info` block on the message from its ICAO address, returning true if the aircraft was found.
+    ///
+    /// Messages without an address, or whose aircraft is not in the database, are left unchanged.
+    pub fn enrich(&self, message: &mut AcarsVdlm2Message) -> bool {
+        match message.get_icao().and_then(|icao| self.get(icao)) {
+            None => false,
+            Some(info) => {
+                message.set_aircraft_info(info.clone());
+                true
+            }
+        }
+    }
+}
+
+impl Transform for AircraftDatabase {
+    fn name(&self) -> &str {
+        "aircraft_enrichment"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        self.enrich(message);
+        TransformOutcome::Forward
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::Write;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error as DeError;
+use serde_json::Value;
+use crate::{AcarsVdlm2Message, MessageFamily, MessageResult};
+
+/// Version of the envelope written by this library. Envelopes with a newer version are rejected on ingest.
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// A message wrapped with the envelope version and its family, such as
+/// `{"v":1,"type":"vdlm2","payload":{"vdl2":{...}}}`, so downstream consumers can tell what they are reading
+/// without looking inside the message, and can handle changes to the layout by version.
+///
+/// Reading an envelope decodes the payload as the family given in `type` rather than detecting it.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+/// use acars_vdlm2_parser::envelope::{decode_enveloped, Envelope};
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.decode_message().unwrap();
+/// let json: String = message.to_string_enveloped().unwrap();
+/// assert_eq!(json, r#"{"v":1,"type":"acars","payload":{"freq":131.55,"channel":2}}"#);
+/// let envelope: Envelope = serde_json::from_str(&json).unwrap();
+/// assert_eq!((envelope.v, envelope.family), (1, MessageFamily::Acars));
+/// assert_eq!(decode_enveloped(&json).unwrap().to_json().unwrap(), message.to_json().unwrap());
+/// assert!(decode_enveloped(r#"{"v":2,"type":"acars","payload":{"freq":131.55}}"#).is_err());
+/// ```
+#[derive(Serialize, Debug, Clone)]
+pub struct Envelope {
+    pub v: u32,
+    #[serde(rename = "type")]
+    pub family: MessageFamily,
+    pub payload: AcarsVdlm2Message,
+}
+
+impl Envelope {
+    /// Wraps a message in an envelope of the current version.
+    pub fn new(message: AcarsVdlm2Message) -> Self {
+        Self { v: ENVELOPE_VERSION, family: message.get_family(), payload: message }
+    }
+
+    /// Discards the envelope, returning the message.
+    pub fn into_message(self) -> AcarsVdlm2Message {
+        self.payload
+    }
+}
+
+impl From<AcarsVdlm2Message> for Envelope {
+    fn from(message: AcarsVdlm2Message) -> Self {
+        Self::new(message)
+    }
+}
+
+impl<'de> Deserialize<'de> for Envelope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct RawEnvelope {
+            v: u32,
+            #[serde(rename = "type")]
+            family: MessageFamily,
+            payload: Value,
+        }
+        let raw: RawEnvelope = RawEnvelope::deserialize(deserializer)?;
+        if raw.v == 0 || raw.v > ENVELOPE_VERSION {
+            return Err(D::Error::custom(format!("unsupported envelope version {}, expected at most {}", raw.v, ENVELOPE_VERSION)));
+        }
+        let payload: AcarsVdlm2Message = AcarsVdlm2Message::deserialize_family(raw.family, raw.payload)
+            .map_err(D::Error::custom)?;
+        Ok(Self { v: raw.v, family: raw.family, payload })
+    }
+}
+
+/// Borrows the message so it can be written in an envelope without copying it.
+#[derive(Serialize)]
+pub(crate) struct EnvelopeRef<'a> {
+    v: u32,
+    #[serde(rename = "type")]
+    family: MessageFamily,
+    payload: &'a AcarsVdlm2Message,
+}
+
+impl<'a> EnvelopeRef<'a> {
+    pub(crate) fn new(message: &'a AcarsVdlm2Message) -> Self {
+        Self { v: ENVELOPE_VERSION, family: message.get_family(), payload: message }
+    }
+}
+
+/// Whether messages are written as the decoder wrote them or wrapped in an `Envelope`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    #[default]
+    Plain,
+    Enveloped,
+}
+
+impl OutputMode {
+    /// Converts the message to JSON encoded as bytes in this mode, terminated with a `\n`.
+    pub fn to_bytes_newline(&self, message: &AcarsVdlm2Message) -> MessageResult<Vec<u8>> {
+        match self {
+            OutputMode::Plain => message.to_bytes_newline(),
+            OutputMode::Enveloped => message.to_bytes_enveloped_newline(),
+        }
+    }
+
+    /// Serialises the message as JSON in this mode straight into the writer, terminated with a `\n`.
+    pub fn write_newline<W: Write>(&self, message: &AcarsVdlm2Message, writer: &mut W) -> MessageResult<()> {
+        match self {
+            OutputMode::Plain => message.write_to_newline(writer),
+            OutputMode::Enveloped => {
+                serde_json::to_writer(&mut *writer, &EnvelopeRef::new(message))?;
+                writer.write_all(b"\n").map_err(serde_json::Error::io)
+            }
+        }
+    }
+}
+
+/// Decodes a message written in an `Envelope`, checking the version and decoding the payload as the family it names.
+///
+/// Plain messages without an envelope are an error, see `DecodeMessage` for those.
+pub fn decode_enveloped(input: &str) -> MessageResult<AcarsVdlm2Message> {
+    serde_json::from_str::<Envelope>(input).map(Envelope::into_message)
+}
+
+/// Decodes a message written in an `Envelope` from bytes, such as a socket buffer, see `decode_enveloped`.
+pub fn decode_enveloped_bytes(input: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+    serde_json::from_slice::<Envelope>(input).map(Envelope::into_message)
+}
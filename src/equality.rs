@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::MessageFamily;
+
+/// Groups of fields that differ between receptions of the same transmission, or between runs of the same decoder,
+/// and can be left out when comparing messages with `AcarsVdlm2Message::eq_ignoring`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldClass {
+    /// Signal and noise levels, frequency skew and error correction counts.
+    Signal,
+    /// The `app` block naming the decoder and any proxies.
+    AppDetails,
+    /// The time the message was received.
+    Time,
+    /// The receiving station's name and the receiver channel it was heard on.
+    Station,
+    /// Blocks added after decoding, the `aircraft_info` block from enrichment and the `consistency` block.
+    Enrichment,
+}
+
+impl FieldClass {
+    /// Every class, in declaration order.
+    pub const ALL: [FieldClass; 5] = [
+        FieldClass::Signal,
+        FieldClass::AppDetails,
+        FieldClass::Time,
+        FieldClass::Station,
+        FieldClass::Enrichment,
+    ];
+
+    /// Returns the dot separated paths of the fields in this class for a family, in the same form as
+    /// `AcarsVdlm2Message::get_field`.
+    /// ```
+    /// use acars_vdlm2_parser::MessageFamily;
+    /// use acars_vdlm2_parser::equality::FieldClass;
+    /// assert_eq!(FieldClass::Time.paths(MessageFamily::Acars), ["timestamp"]);
+    /// assert_eq!(FieldClass::Time.paths(MessageFamily::Vdlm2), ["vdl2.t"]);
+    /// ```
+    pub fn paths(&self, family: MessageFamily) -> &'static [&'static str] {
+        match (self, family) {
+            (FieldClass::Signal, MessageFamily::Acars) => &["level", "error"],
+            (FieldClass::Signal, MessageFamily::Vdlm2) => &[
+                "vdl2.sig_level", "vdl2.noise_level", "vdl2.freq_skew", "vdl2.hdr_bits_fixed", "vdl2.octets_corrected_by_fec",
+            ],
+            (FieldClass::Signal, MessageFamily::Hfdl) => &["hfdl.sig_level", "hfdl.noise_level", "hfdl.freq_skew"],
+            (FieldClass::Signal, MessageFamily::Uat) => &["metadata.rssi", "metadata.errors", "uat_frame.rssi", "uat_frame.rs"],
+            (FieldClass::AppDetails, MessageFamily::Acars) => &["app"],
+            (FieldClass::AppDetails, MessageFamily::Vdlm2) => &["vdl2.app"],
+            (FieldClass::AppDetails, MessageFamily::Hfdl) => &["hfdl.app"],
+            (FieldClass::AppDetails, MessageFamily::Uat) => &["app", "uat_frame.app"],
+            (FieldClass::Time, MessageFamily::Acars) => &["timestamp"],
+            (FieldClass::Time, MessageFamily::Vdlm2) => &["vdl2.t"],
+            (FieldClass::Time, MessageFamily::Hfdl) => &["hfdl.t"],
+            (FieldClass::Time, MessageFamily::Uat) => &["metadata.received_at", "uat_frame.t"],
+            (FieldClass::Station, MessageFamily::Acars) => &["station_id", "channel"],
+            (FieldClass::Station, MessageFamily::Vdlm2) => &["vdl2.station", "vdl2.idx"],
+            (FieldClass::Station, MessageFamily::Hfdl) => &["hfdl.station"],
+            (FieldClass::Station, MessageFamily::Uat) => &["station", "uat_frame.station"],
+            (FieldClass::Enrichment, MessageFamily::Acars) => &["aircraft_info"],
+            (FieldClass::Enrichment, MessageFamily::Vdlm2) => &["vdl2.aircraft_info", "vdl2.consistency"],
+            (FieldClass::Enrichment, MessageFamily::Hfdl) => &["hfdl.aircraft_info"],
+            (FieldClass::Enrichment, MessageFamily::Uat) => &["aircraft_info", "uat_frame.aircraft_info"],
+        }
+    }
+}
+
+/// Compares two messages of the same family as JSON, leaving out the fields in the given classes.
+///
+/// Messages that cannot be serialised are never equal.
+pub(crate) fn eq_ignoring<T: Serialize>(first: &T, second: &T, family: MessageFamily, ignored: &[FieldClass]) -> bool {
+    let (Ok(mut first), Ok(mut second)) = (serde_json::to_value(first), serde_json::to_value(second)) else {
+        return false;
+    };
+    for path in ignored.iter().flat_map(|class| class.paths(family)) {
+        remove_path(&mut first, path);
+        remove_path(&mut second, path);
+    }
+    first == second
+}
+
+/// Removes the field at a dot separated path, if there is one.
+fn remove_path(value: &mut Value, path: &str) {
+    let (parent, key): (Option<&str>, &str) = match path.rsplit_once('.') {
+        Some((parent, key)) => (Some(parent), key),
+        None => (None, path),
+    };
+    let parent: Option<&mut Value> = match parent {
+        Some(parent) => parent.split('.').try_fold(value, |value, segment| value.get_mut(segment)),
+        None => Some(value),
+    };
+    if let Some(Value::Object(object)) = parent {
+        object.remove(key);
+    }
+}
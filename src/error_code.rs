@@ -0,0 +1,130 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use serde_json::error::Category;
+
+/// Stable, machine readable kinds of decode failure, written as codes such as `json.unknown_field`.
+///
+/// Codes starting with `json.` are problems with the JSON or its shape, and codes starting with `message.` are values
+/// this library rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ErrorCode {
+    #[serde(rename = "json.syntax")]
+    Syntax,
+    #[serde(rename = "json.eof")]
+    Eof,
+    #[serde(rename = "json.io")]
+    Io,
+    #[serde(rename = "json.unknown_field")]
+    UnknownField,
+    #[serde(rename = "json.missing_field")]
+    MissingField,
+    #[serde(rename = "json.duplicate_field")]
+    DuplicateField,
+    #[serde(rename = "json.unknown_variant")]
+    UnknownVariant,
+    #[serde(rename = "json.invalid_type")]
+    InvalidType,
+    #[serde(rename = "json.invalid_value")]
+    InvalidValue,
+    #[serde(rename = "json.invalid_length")]
+    InvalidLength,
+    /// Any other problem with the shape of the JSON.
+    #[serde(rename = "json.data")]
+    Data,
+    #[serde(rename = "message.not_object")]
+    NotObject,
+    #[serde(rename = "message.bad_frequency")]
+    BadFrequency,
+    #[serde(rename = "message.bad_number")]
+    BadNumber,
+    /// The message decoded but a validation hook rejected it, see `validate::ValidationHooks`.
+    #[serde(rename = "message.rejected")]
+    Rejected,
+}
+
+/// Messages raised while decoding, checked in order against the error text, with the code each is reported as.
+///
+/// Errors from inside a message are wrapped with the family they were decoded as, so these are matched anywhere in
+/// the text rather than only at the start.
+const DATA_CODES: [(&str, ErrorCode); 10] = [
+    ("expected a JSON object", ErrorCode::NotObject),
+    ("invalid frequency", ErrorCode::BadFrequency),
+    ("invalid number string", ErrorCode::BadNumber),
+    ("unknown field", ErrorCode::UnknownField),
+    ("missing field", ErrorCode::MissingField),
+    ("duplicate field", ErrorCode::DuplicateField),
+    ("unknown variant", ErrorCode::UnknownVariant),
+    ("invalid type", ErrorCode::InvalidType),
+    ("invalid value", ErrorCode::InvalidValue),
+    ("invalid length", ErrorCode::InvalidLength),
+];
+
+impl ErrorCode {
+    /// Every code, for building alerting rules and dashboards ahead of seeing each failure.
+    pub const ALL: [ErrorCode; 15] = [
+        ErrorCode::Syntax, ErrorCode::Eof, ErrorCode::Io, ErrorCode::UnknownField, ErrorCode::MissingField,
+        ErrorCode::DuplicateField, ErrorCode::UnknownVariant, ErrorCode::InvalidType, ErrorCode::InvalidValue,
+        ErrorCode::InvalidLength, ErrorCode::Data, ErrorCode::NotObject, ErrorCode::BadFrequency, ErrorCode::BadNumber,
+        ErrorCode::Rejected,
+    ];
+
+    /// Returns the code for why a decode failed.
+    pub fn of(error: &serde_json::Error) -> Self {
+        match error.classify() {
+            Category::Syntax => ErrorCode::Syntax,
+            Category::Eof => ErrorCode::Eof,
+            Category::Io => ErrorCode::Io,
+            Category::Data => {
+                let text: String = error.to_string();
+                DATA_CODES.iter()
+                    .find(|(message, _)| text.contains(message))
+                    .map_or(ErrorCode::Data, |(_, code)| *code)
+            }
+        }
+    }
+
+    /// Returns the code as it is written, such as `json.unknown_field`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Syntax => "json.syntax",
+            ErrorCode::Eof => "json.eof",
+            ErrorCode::Io => "json.io",
+            ErrorCode::UnknownField => "json.unknown_field",
+            ErrorCode::MissingField => "json.missing_field",
+            ErrorCode::DuplicateField => "json.duplicate_field",
+            ErrorCode::UnknownVariant => "json.unknown_variant",
+            ErrorCode::InvalidType => "json.invalid_type",
+            ErrorCode::InvalidValue => "json.invalid_value",
+            ErrorCode::InvalidLength => "json.invalid_length",
+            ErrorCode::Data => "json.data",
+            ErrorCode::NotObject => "message.not_object",
+            ErrorCode::BadFrequency => "message.bad_frequency",
+            ErrorCode::BadNumber => "message.bad_number",
+            ErrorCode::Rejected => "message.rejected",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Returns a stable, machine readable code for why a decode failed, such as `json.unknown_field`.
+///
+/// Error text includes the field and value involved, and its wording can change between releases, so alerting rules
+/// and dashboards should group failures by this code instead. See `ErrorCode` for every code.
+/// ```
+/// use acars_vdlm2_parser::DecodeMessage;
+/// use acars_vdlm2_parser::error_code::error_code;
+/// let error: serde_json::Error = "not json".decode_message().unwrap_err();
+/// assert_eq!(error_code(&error), "json.syntax");
+/// let error: serde_json::Error = r#"{"hfdl":{"freq":13312000,"bit_rate":300,"slot":"S","extra":1}}"#.decode_message().unwrap_err();
+/// assert_eq!(error_code(&error), "json.unknown_field");
+/// let error: serde_json::Error = "[1,2]".decode_message().unwrap_err();
+/// assert_eq!(error_code(&error), "message.not_object");
+/// ```
+pub fn error_code(error: &serde_json::Error) -> &'static str {
+    ErrorCode::of(error).as_str()
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+use crate::correlate::AckFields;
+use crate::frequency::{Frequency, FrequencyBand};
+use crate::icao::IcaoAllocation;
+use crate::labels::label_description;
+use crate::quality::FrameQuality;
+#[cfg(feature = "vdlm2")]
+use crate::vdlm2::GroundStationInfo;
+
+/// Aircraft address used for frames sent to every aircraft, such as ground station information frames.
+const BROADCAST_ADDRESS: u32 = 0xFFFFFF;
+
+/// A single field in an `Explanation`, with a note on what the value means where there is one.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ExplainLine {
+    pub field: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meaning: Option<String>,
+}
+
+/// A field by field breakdown of a message, returned by `AcarsVdlm2Message::explain()`.
+///
+/// Displays as one `field: value // meaning` line per field with the values aligned, similar to the human readable
+/// output of dumpvdl2, for use in debugging and terminal tools.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct Explanation {
+    pub lines: Vec<ExplainLine>,
+}
+
+impl Explanation {
+    fn push(&mut self, field: &str, value: impl fmt::Display, meaning: Option<String>) {
+        self.lines.push(ExplainLine { field: field.to_string(), value: value.to_string(), meaning });
+    }
+
+    /// Returns the line for the provided field if there is one.
+    pub fn get(&self, field: &str) -> Option<&ExplainLine> {
+        self.lines.iter().find(|line| line.field == field)
+    }
+
+    /// Builds the explanation for the provided message.
+    #[allow(irrefutable_let_patterns)]
+    pub(crate) fn of(message: &AcarsVdlm2Message) -> Self {
+        let mut explanation: Explanation = Explanation::default();
+        explanation.push("family", message.get_family(), None);
+        if let Some(time) = message.get_time() {
+            explanation.push("time", format!("{:.3}", time), Some("seconds since the Unix epoch".to_string()));
+        }
+        if let Some(station) = message.get_station_name() {
+            explanation.push("station", station, None);
+        }
+        let frequency: Frequency = message.get_frequency();
+        explanation.push("frequency", frequency, Some(band_meaning(frequency.band()).to_string()));
+        explanation.push("quality", format!("{:?}", message.get_frame_quality()).to_lowercase(), Some(quality_meaning(message.get_frame_quality()).to_string()));
+        if let Some(icao) = message.get_icao() {
+            let meaning: String = match message.icao_allocation() {
+                _ if icao == BROADCAST_ADDRESS => "broadcast to all aircraft".to_string(),
+                Some(IcaoAllocation { country, .. }) => format!("allocated to {}", country),
+                None => "not in any allocated block, possibly garbled".to_string(),
+            };
+            explanation.push("icao", format!("{:06X}", icao), Some(meaning));
+        }
+        if let Some(registration) = message.get_registration().filter(|registration| !registration.is_empty()) {
+            explanation.push("registration", registration, None);
+        }
+        if let Some(flight) = message.get_flight().filter(|flight| !flight.is_empty()) {
+            explanation.push("flight", flight, None);
+        }
+        if let Some(fields) = message.get_ack_fields() {
+            explain_link(&mut explanation, &fields);
+        }
+        if let Some(label) = message.get_label() {
+            explanation.push("label", label, label_description(label).map(|description| description.to_string()));
+        }
+        if let Some(sublabel) = message.get_sublabel() {
+            explanation.push("sublabel", sublabel, None);
+        }
+        if let Some(text) = message.get_text().filter(|text| !text.is_empty()) {
+            explanation.push("text", format!("{:?}", text), None);
+        }
+        #[cfg(feature = "vdlm2")]
+        if let AcarsVdlm2Message::Vdlm2Message(vdlm2) = message {
+            if let Some(info) = vdlm2.get_ground_station_info() {
+                explain_ground_station(&mut explanation, &info);
+            }
+        }
+        if let Some(app) = message.get_app_details().filter(|app| !app.name.is_empty()) {
+            explanation.push("app", format!("{} {}", app.name, app.ver), app.proxied_by.as_ref().map(|proxied_by| format!("proxied by {}", proxied_by)));
+        }
+        explanation
+    }
+}
+
+fn explain_link(explanation: &mut Explanation, fields: &AckFields) {
+    explanation.push("mode", fields.mode, None);
+    if !fields.block_id.is_empty() {
+        let direction: &str = match fields.block_id.chars().next() {
+            Some(first) if first.is_ascii_digit() => "downlink from the aircraft",
+            _ => "uplink from the ground",
+        };
+        explanation.push("block_id", fields.block_id, Some(direction.to_string()));
+    }
+    match fields.ack {
+        Some(ack) => explanation.push("ack", ack, Some(format!("acknowledges block {}", ack))),
+        None => explanation.push("ack", "NAK", Some("not acknowledging a block".to_string())),
+    }
+    if let Some(msgno) = fields.msgno {
+        explanation.push("msgno", msgno, None);
+    }
+}
+
+#[cfg(feature = "vdlm2")]
+fn explain_ground_station(explanation: &mut Explanation, info: &GroundStationInfo) {
+    if !info.airport_coverage.is_empty() {
+        explanation.push("airport_coverage", info.airport_coverage.join(" "), Some("airports served by the ground station".to_string()));
+    }
+    if let Some(location) = &info.location {
+        explanation.push("gs_location", format!("{:.4} {:.4}", location.lat(), location.lon()), Some("ground station latitude and longitude".to_string()));
+    }
+    if !info.frequencies_mhz.is_empty() {
+        let frequencies: Vec<String> = info.frequencies_mhz.iter().map(|frequency| format!("{:.3}", frequency)).collect();
+        explanation.push("gs_frequencies", frequencies.join(" "), Some("MHz".to_string()));
+    }
+}
+
+fn band_meaning(band: FrequencyBand) -> &'static str {
+    match band {
+        FrequencyBand::Hf => "HF band",
+        FrequencyBand::Vhf => "VHF band",
+        FrequencyBand::Uhf => "UHF band",
+        FrequencyBand::LBand => "L band",
+        FrequencyBand::Other => "outside the aviation data link bands",
+    }
+}
+
+fn quality_meaning(quality: FrameQuality) -> &'static str {
+    match quality {
+        FrameQuality::Ok => "received without errors",
+        FrameQuality::Corrected => "bit errors corrected by the decoder",
+        FrameQuality::Failed => "failed its integrity check",
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field_width: usize = self.lines.iter().map(|line| line.field.len()).max().unwrap_or_default();
+        let value_width: usize = self.lines.iter()
+            .filter(|line| line.meaning.is_some())
+            .map(|line| line.value.chars().count())
+            .max()
+            .unwrap_or_default();
+        for line in &self.lines {
+            match &line.meaning {
+                None => writeln!(f, "{:>field_width$}: {}", line.field, line.value)?,
+                Some(meaning) => writeln!(f, "{:>field_width$}: {:<value_width$} // {}", line.field, line.value, meaning)?,
+            }
+        }
+        Ok(())
+    }
+}
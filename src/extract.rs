@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+use serde_json::Value;
+use crate::sniff::string_end;
+
+/// Extracts the requested fields from a JSON message without decoding the rest of it, for routing by a handful of
+/// fields at rates where a full decode is too expensive.
+///
+/// Fields are dot separated paths into the input as written by the decoder, such as `label` for acarsdec or
+/// `vdl2.avlc.acars.label` for dumpvdl2. Only objects on the way to a requested field are looked inside, everything
+/// else is skipped without being parsed, and the scan stops as soon as every requested field has been found. Arrays
+/// are not looked inside. Fields that are missing, or after the point where the input turns out not to be valid JSON,
+/// are left out of the returned map.
+/// ```
+/// use std::collections::HashMap;
+/// use serde_json::{json, Value};
+/// use acars_vdlm2_parser::extract::extract_fields;
+/// let input: &str = r#"{"freq":131.55,"channel":2,"label":"Q0","station_id":"YPPH","text":"{\"label\":\"H1\"}"}"#;
+/// let fields: HashMap<String, Value> = extract_fields(input, &["label", "station_id"]);
+/// assert_eq!(fields["label"], json!("Q0"));
+/// assert_eq!(fields["station_id"], json!("YPPH"));
+/// let input: &str = r#"{"vdl2":{"station":"YPPH","avlc":{"acars":{"label":"H1"}}}}"#;
+/// let fields: HashMap<String, Value> = extract_fields(input, &["vdl2.avlc.acars.label", "vdl2.station", "vdl2.missing"]);
+/// assert_eq!(fields.len(), 2);
+/// assert_eq!(fields["vdl2.avlc.acars.label"], json!("H1"));
+/// ```
+pub fn extract_fields(input: &str, fields: &[&str]) -> HashMap<String, Value> {
+    let mut found: HashMap<String, Value> = HashMap::with_capacity(fields.len());
+    let bytes: &[u8] = input.as_bytes();
+    let start: usize = skip_whitespace(bytes, 0);
+    if !fields.is_empty() && bytes.get(start) == Some(&b'{') {
+        scan_object(bytes, start, "", fields, &mut found);
+    }
+    found
+}
+
+/// Returns the dot separated path of every key that appears more than once in the same object, in the order the
+/// repeats appear.
+///
+/// Decoding keeps only the last value of a repeated key, so a feed that emits two `freq` fields is otherwise routed on
+/// whichever came last without any sign of a problem. Array entries are given their index in the path. Keys after the
+/// point where the input turns out not to be valid JSON are not checked.
+/// ```
+/// use acars_vdlm2_parser::extract::duplicate_keys;
+/// assert!(duplicate_keys(r#"{"freq":131.55,"channel":2}"#).is_empty());
+/// let input: &str = r#"{"freq":131.55,"vdl2":{"freq":1,"avlc":[{"src":1,"src":2}]},"freq":131.725}"#;
+/// assert_eq!(duplicate_keys(input), vec!["vdl2.avlc.0.src", "freq"]);
+/// ```
+pub fn duplicate_keys(input: &str) -> Vec<String> {
+    let mut duplicates: Vec<String> = Vec::new();
+    let bytes: &[u8] = input.as_bytes();
+    let start: usize = skip_whitespace(bytes, 0);
+    if bytes.get(start) == Some(&b'{') {
+        scan_duplicates(bytes, start, "", &mut duplicates);
+    }
+    duplicates
+}
+
+/// Checks the object or array starting at `start` for repeated keys, returning the index after it.
+fn scan_duplicates(bytes: &[u8], start: usize, prefix: &str, duplicates: &mut Vec<String>) -> Option<usize> {
+    let (is_object, close): (bool, u8) = match bytes[start] {
+        b'{' => (true, b'}'),
+        _ => (false, b']'),
+    };
+    let mut keys: HashSet<&[u8]> = HashSet::new();
+    let mut index: usize = skip_whitespace(bytes, start + 1);
+    if bytes.get(index) == Some(&close) {
+        return Some(index + 1);
+    }
+    let mut entry: usize = 0;
+    loop {
+        let segment: String = match is_object {
+            true => {
+                if bytes.get(index) != Some(&b'"') {
+                    return None;
+                }
+                let key_end: usize = string_end(bytes, index + 1)?;
+                let key: &[u8] = &bytes[index + 1..key_end];
+                let segment: String = String::from_utf8_lossy(key).into_owned();
+                let repeated: bool = !keys.insert(key);
+                index = skip_whitespace(bytes, key_end + 1);
+                if bytes.get(index) != Some(&b':') {
+                    return None;
+                }
+                index = skip_whitespace(bytes, index + 1);
+                if repeated {
+                    duplicates.push(join_path(prefix, &segment));
+                }
+                segment
+            }
+            false => entry.to_string(),
+        };
+        entry += 1;
+        index = match bytes.get(index)? {
+            b'{' | b'[' => scan_duplicates(bytes, index, &join_path(prefix, &segment), duplicates)?,
+            _ => skip_value(bytes, index)?,
+        };
+        index = skip_whitespace(bytes, index);
+        match bytes.get(index)? {
+            b',' => index = skip_whitespace(bytes, index + 1),
+            byte if *byte == close => return Some(index + 1),
+            _ => return None,
+        }
+    }
+}
+
+fn join_path(prefix: &str, segment: &str) -> String {
+    match prefix.is_empty() {
+        true => segment.to_string(),
+        false => format!("{}.{}", prefix, segment),
+    }
+}
+
+/// Whether the scan should carry on, or stop because every field was found or the input is malformed.
+enum Scan {
+    Continue(usize),
+    Stop,
+}
+
+/// Scans the object starting at `start`, returning the index after its closing brace.
+fn scan_object(bytes: &[u8], start: usize, prefix: &str, fields: &[&str], found: &mut HashMap<String, Value>) -> Scan {
+    let mut index: usize = skip_whitespace(bytes, start + 1);
+    if bytes.get(index) == Some(&b'}') {
+        return Scan::Continue(index + 1);
+    }
+    loop {
+        if bytes.get(index) != Some(&b'"') {
+            return Scan::Stop;
+        }
+        let Some(key_end) = string_end(bytes, index + 1) else {
+            return Scan::Stop;
+        };
+        let Ok(key) = std::str::from_utf8(&bytes[index + 1..key_end]) else {
+            return Scan::Stop;
+        };
+        let path: String = join_path(prefix, key);
+        index = skip_whitespace(bytes, key_end + 1);
+        if bytes.get(index) != Some(&b':') {
+            return Scan::Stop;
+        }
+        index = skip_whitespace(bytes, index + 1);
+        let wanted: bool = fields.contains(&path.as_str());
+        let leads_to_wanted: bool = bytes.get(index) == Some(&b'{') && fields.iter()
+            .any(|field| field.len() > path.len() && field.starts_with(path.as_str()) && field.as_bytes()[path.len()] == b'.');
+        let value_start: usize = index;
+        index = match leads_to_wanted {
+            true => match scan_object(bytes, index, &path, fields, found) {
+                Scan::Continue(end) => end,
+                Scan::Stop => return Scan::Stop,
+            },
+            false => match skip_value(bytes, index) {
+                Some(end) => end,
+                None => return Scan::Stop,
+            },
+        };
+        if wanted {
+            let Ok(value) = serde_json::from_slice::<Value>(&bytes[value_start..index]) else {
+                return Scan::Stop;
+            };
+            found.insert(path, value);
+        }
+        if found.len() == fields.len() {
+            return Scan::Stop;
+        }
+        index = skip_whitespace(bytes, index);
+        match bytes.get(index) {
+            Some(b',') => index = skip_whitespace(bytes, index + 1),
+            Some(b'}') => return Scan::Continue(index + 1),
+            _ => return Scan::Stop,
+        }
+    }
+}
+
+/// Returns the index after the value starting at `start`, without parsing it.
+fn skip_value(bytes: &[u8], start: usize) -> Option<usize> {
+    match bytes.get(start)? {
+        b'"' => string_end(bytes, start + 1).map(|end| end + 1),
+        b'{' | b'[' => {
+            let mut depth: usize = 0;
+            let mut index: usize = start;
+            while index < bytes.len() {
+                match bytes[index] {
+                    b'"' => index = string_end(bytes, index + 1)?,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(index + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                index += 1;
+            }
+            None
+        }
+        _ => bytes[start..].iter()
+            .position(|byte| matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace())
+            .map(|length| start + length),
+    }
+}
+
+fn skip_whitespace(bytes: &[u8], start: usize) -> usize {
+    start + bytes[start.min(bytes.len())..].iter().take_while(|byte| byte.is_ascii_whitespace()).count()
+}
@@ -0,0 +1,146 @@
+//! C bindings for the decoder, so C tools such as acarsdec forks and dumpvdl2 plugins can decode into the unified
+//! model of this crate.
+//!
+//! Every function returns `AVP_OK` on success, a positive code when the input could not be decoded, or a negative code
+//! when the call itself was wrong. Positive codes are the position of the `ErrorCode` in `ErrorCode::ALL` plus one, and
+//! `avp_error_code_name` returns the name of any code. The header in `include/acars_vdlm2_parser.h` is generated from
+//! this module with `cbindgen --config cbindgen.toml --output include/acars_vdlm2_parser.h`.
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::OnceLock;
+use crate::{decode_bytes, AcarsVdlm2Message, ExpectedMessageType, MessageResult};
+use crate::error_code::ErrorCode;
+
+/// The call succeeded and the output holds the decoded message as JSON.
+pub const AVP_OK: i32 = 0;
+/// A pointer that must be set was null.
+pub const AVP_ERROR_NULL_POINTER: i32 = -1;
+/// The input to `avp_decode_json` was not valid UTF-8.
+pub const AVP_ERROR_INVALID_UTF8: i32 = -2;
+/// The message decoded but could not be written back as JSON.
+pub const AVP_ERROR_ENCODE: i32 = -3;
+/// The decoder panicked, which is a bug in this crate.
+pub const AVP_ERROR_PANIC: i32 = -4;
+
+/// Decodes a NUL terminated JSON message from acarsdec, dumpvdl2 or dumphfdl.
+///
+/// On success `*output` is set to the message in the unified model as NUL terminated JSON. When the input does not
+/// decode, `*output` is set to the error text instead. Either string must be released with `avp_string_free`. `*output`
+/// is set to null when a negative code is returned.
+///
+/// # Safety
+///
+/// `input` must be null or point to a NUL terminated string, and `output` must be null or point to writable memory for
+/// a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn avp_decode_json(input: *const c_char, output: *mut *mut c_char) -> i32 {
+    if output.is_null() {
+        return AVP_ERROR_NULL_POINTER;
+    }
+    *output = ptr::null_mut();
+    if input.is_null() {
+        return AVP_ERROR_NULL_POINTER;
+    }
+    let Ok(input) = CStr::from_ptr(input).to_str() else {
+        return AVP_ERROR_INVALID_UTF8;
+    };
+    decode_into(output, || decode_bytes(input.as_bytes(), ExpectedMessageType::Any))
+}
+
+/// Decodes a message held in a buffer of `len` bytes, such as a datagram read from a socket, which does not need to be
+/// NUL terminated.
+///
+/// The buffer can hold JSON, or with the `compression` feature a frame written by
+/// `AcarsVdlm2Message::to_bytes_compressed`. `*output` is set the same way as `avp_decode_json`.
+///
+/// # Safety
+///
+/// `data` must be null or point to at least `len` readable bytes, and `output` must be null or point to writable memory
+/// for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn avp_decode_raw_frame(data: *const u8, len: usize, output: *mut *mut c_char) -> i32 {
+    if output.is_null() {
+        return AVP_ERROR_NULL_POINTER;
+    }
+    *output = ptr::null_mut();
+    if data.is_null() {
+        return AVP_ERROR_NULL_POINTER;
+    }
+    let frame: &[u8] = std::slice::from_raw_parts(data, len);
+    decode_into(output, || decode_frame(frame))
+}
+
+/// Releases a string returned by this library. Passing null does nothing.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by this library that has not already been released.
+#[no_mangle]
+pub unsafe extern "C" fn avp_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Returns the name of a code returned by this library, such as `json.syntax`, as a static NUL terminated string that
+/// must not be released, or null for an unknown code.
+#[no_mangle]
+pub extern "C" fn avp_error_code_name(code: i32) -> *const c_char {
+    static NAMES: OnceLock<Vec<CString>> = OnceLock::new();
+    let name: Option<&CStr> = match code {
+        AVP_OK => Some(c"ok"),
+        AVP_ERROR_NULL_POINTER => Some(c"ffi.null_pointer"),
+        AVP_ERROR_INVALID_UTF8 => Some(c"ffi.invalid_utf8"),
+        AVP_ERROR_ENCODE => Some(c"ffi.encode"),
+        AVP_ERROR_PANIC => Some(c"ffi.panic"),
+        code => {
+            let names: &Vec<CString> = NAMES.get_or_init(|| {
+                ErrorCode::ALL.iter().map(|code| CString::new(code.as_str()).unwrap_or_default()).collect()
+            });
+            usize::try_from(code).ok()
+                .and_then(|code| code.checked_sub(1))
+                .and_then(|index| names.get(index))
+                .map(CString::as_c_str)
+        }
+    };
+    name.map_or(ptr::null(), CStr::as_ptr)
+}
+
+/// Returns the code `avp_decode_json` and `avp_decode_raw_frame` return for an `ErrorCode`.
+///
+/// Every code is in `ErrorCode::ALL`, so the fallback is never returned.
+pub fn ffi_code(code: ErrorCode) -> i32 {
+    ErrorCode::ALL.iter().position(|known| *known == code).map_or(AVP_ERROR_ENCODE, |index| index as i32 + 1)
+}
+
+#[cfg(feature = "compression")]
+fn decode_frame(frame: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+    match crate::compression::is_compressed_frame(frame) {
+        true => AcarsVdlm2Message::decode_compressed(frame),
+        false => decode_bytes(frame, ExpectedMessageType::Any),
+    }
+}
+
+#[cfg(not(feature = "compression"))]
+fn decode_frame(frame: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+    decode_bytes(frame, ExpectedMessageType::Any)
+}
+
+/// Runs the decode without letting a panic unwind into the caller, and writes the JSON or error text to `output`.
+unsafe fn decode_into<F>(output: *mut *mut c_char, decode: F) -> i32
+where
+    F: FnOnce() -> MessageResult<AcarsVdlm2Message>,
+{
+    let Ok(result) = panic::catch_unwind(AssertUnwindSafe(|| decode().map(|message| message.to_json()))) else {
+        return AVP_ERROR_PANIC;
+    };
+    let (code, text): (i32, String) = match result {
+        Ok(Ok(json)) => (AVP_OK, json),
+        Ok(Err(_)) => return AVP_ERROR_ENCODE,
+        Err(error) => (ffi_code(ErrorCode::of(&error)), error.to_string()),
+    };
+    // JSON escapes NUL, but error text can quote the input as it was.
+    *output = CString::new(text.replace('\0', "\\u0000")).unwrap_or_default().into_raw();
+    code
+}
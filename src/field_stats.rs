@@ -0,0 +1,146 @@
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use serde_ignored::Path;
+use serde_json::Value;
+use crate::{AcarsVdlm2Message, MessageFamily, MessageResult};
+
+/// Field counts for the messages of one family seen by a `FieldStatistics`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct FieldCounts {
+    /// Number of messages decoded.
+    pub messages: u64,
+    /// Number of keys in the input, including those in nested objects.
+    pub present: u64,
+    /// Number of keys the message types do not know, so were skipped while decoding. A skipped object counts once.
+    pub ignored: u64,
+    /// How often each skipped key was seen, by dot separated path with array entries given their index.
+    pub ignored_fields: BTreeMap<String, u64>,
+}
+
+impl FieldCounts {
+    /// Returns the share of the keys present that were ignored, or 0 if no keys have been seen.
+    pub fn ignored_ratio(&self) -> f64 {
+        match self.present {
+            0 => 0.0,
+            present => self.ignored as f64 / present as f64,
+        }
+    }
+
+    fn add(&mut self, other: &FieldCounts) {
+        self.messages += other.messages;
+        self.present += other.present;
+        self.ignored += other.ignored;
+        for (path, count) in &other.ignored_fields {
+            *self.ignored_fields.entry(path.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Decodes messages while counting the fields present in each and the fields the message types skip, per family, to
+/// measure schema drift between decoder versions from production traffic.
+///
+/// Skipped fields are reported by serde as they are passed over during the decode, so unlike
+/// `ProcessingContext::report_unknown_fields` nothing is re-encoded and compared. Counting the fields present walks the
+/// parsed input once more. Fields skipped inside the few values decoded through a buffer, such as untagged enums, are
+/// not seen. Parts of a message that reject unknown fields, such as the HFDL body, still fail to decode as they
+/// normally would.
+/// ```
+/// use acars_vdlm2_parser::MessageFamily;
+/// use acars_vdlm2_parser::field_stats::{FieldCounts, FieldStatistics};
+/// let mut statistics: FieldStatistics = FieldStatistics::new();
+/// statistics.decode(r#"{"freq":131.55,"channel":2,"label":"H1","new_field":1}"#).unwrap();
+/// statistics.decode(r#"{"freq":131.55,"channel":2,"new_field":{"a":1}}"#).unwrap();
+/// let acars: FieldCounts = statistics.family(MessageFamily::Acars);
+/// assert_eq!((acars.messages, acars.present, acars.ignored), (2, 8, 2));
+/// assert_eq!(acars.ignored_fields["new_field"], 2);
+/// assert_eq!(statistics.totals().messages, 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FieldStatistics {
+    families: HashMap<MessageFamily, FieldCounts>,
+}
+
+impl FieldStatistics {
+    /// Creates a new `FieldStatistics` with nothing counted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes a message, counting its fields if it decodes.
+    pub fn decode(&mut self, input: &str) -> MessageResult<AcarsVdlm2Message> {
+        let value: Value = serde_json::from_str(input)?;
+        if !value.is_object() {
+            return AcarsVdlm2Message::deserialize(value);
+        }
+        let family: MessageFamily = MessageFamily::detect(&value);
+        let present: u64 = count_keys(&value);
+        let mut ignored: Vec<String> = Vec::new();
+        let mut record = |path: Path| ignored.push(path_to_string(&path));
+        let message: AcarsVdlm2Message = AcarsVdlm2Message::deserialize_family(family, serde_ignored::Deserializer::new(value, &mut record))?;
+        let counts: &mut FieldCounts = self.families.entry(family).or_default();
+        counts.messages += 1;
+        counts.present += present;
+        counts.ignored += ignored.len() as u64;
+        for path in ignored {
+            *counts.ignored_fields.entry(path).or_default() += 1;
+        }
+        Ok(message)
+    }
+
+    /// Returns the counts for the family.
+    pub fn family(&self, family: MessageFamily) -> FieldCounts {
+        self.families.get(&family).cloned().unwrap_or_default()
+    }
+
+    /// Returns the counts across every family.
+    pub fn totals(&self) -> FieldCounts {
+        let mut totals: FieldCounts = FieldCounts::default();
+        for counts in self.families.values() {
+            totals.add(counts);
+        }
+        totals
+    }
+
+    /// Adds the counts from another `FieldStatistics`, such as one kept by another thread.
+    pub fn merge(&mut self, other: &FieldStatistics) {
+        for (family, counts) in &other.families {
+            self.families.entry(*family).or_default().add(counts);
+        }
+    }
+
+    /// Clears every count.
+    pub fn reset(&mut self) {
+        self.families.clear();
+    }
+}
+
+fn count_keys(value: &Value) -> u64 {
+    match value {
+        Value::Object(map) => map.values().map(|value| 1 + count_keys(value)).sum(),
+        Value::Array(values) => values.iter().map(count_keys).sum(),
+        _ => 0,
+    }
+}
+
+/// Returns the path as dot separated keys and array indices, leaving out the steps serde takes through `Option` and
+/// newtypes that are not in the input.
+fn path_to_string(path: &Path) -> String {
+    let mut segments: Vec<String> = Vec::new();
+    let mut current: &Path = path;
+    loop {
+        match current {
+            Path::Root => break,
+            Path::Seq { parent, index } => {
+                segments.push(index.to_string());
+                current = parent;
+            }
+            Path::Map { parent, key } => {
+                segments.push(key.clone());
+                current = parent;
+            }
+            Path::Some { parent } | Path::NewtypeStruct { parent } | Path::NewtypeVariant { parent } => current = parent,
+        }
+    }
+    segments.reverse();
+    segments.join(".")
+}
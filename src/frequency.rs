@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as DeError;
+use serde_json::{Number, Value};
+use crate::instrument::fallback;
+
+const HZ_PER_MHZ: f64 = 1_000_000.0;
+
+/// Unit a `Frequency` was written in, so it is serialised back the same way.
+///
+/// acarsdec writes frequencies in MHz while dumpvdl2 and dumphfdl write them in Hz.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FrequencyUnit {
+    #[default]
+    Hz,
+    Mhz,
+}
+
+/// Radio band a `Frequency` falls in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FrequencyBand {
+    /// 2 to 30 MHz, which includes the lowest HFDL channels just below the 3 MHz ITU boundary.
+    Hf,
+    /// 30 to 300 MHz, used by ACARS and VDL mode 2.
+    Vhf,
+    /// 300 MHz to 1 GHz, used by UAT on 978 MHz.
+    Uhf,
+    /// 1 to 2 GHz, used by Inmarsat and Iridium satellite links.
+    LBand,
+    /// Anything outside the bands above.
+    Other,
+}
+
+/// A receive frequency, stored in Hz.
+///
+/// Deserializes from Hz or MHz, as a number or a string, and remembers which unit it was written in so messages
+/// serialise back unchanged. Whole numbers of at least 1,000,000 are taken as Hz and anything smaller as MHz.
+/// Equality and ordering only consider the frequency, not the unit.
+///
+/// Some decoders store MHz frequencies as `f32` and write them out as `f64`, so `131.55` arrives as
+/// `131.5500030517578`. A frequency in MHz that reads back as the same `f32` as a whole number of kHz is taken as that
+/// number of kHz, so the noise does not defeat deduplication, and the decimal places it was written with are kept so
+/// `as_written` gives back the original text.
+/// ```
+/// use acars_vdlm2_parser::frequency::{Frequency, FrequencyBand};
+/// let acars: Frequency = serde_json::from_str("131.55").unwrap();
+/// let vdlm2: Frequency = serde_json::from_str("136975000").unwrap();
+/// let quoted: Frequency = serde_json::from_str(r#""136.975""#).unwrap();
+/// assert_eq!(acars.as_hz(), 131_550_000);
+/// assert_eq!(vdlm2, quoted);
+/// assert_eq!(vdlm2.as_mhz(), 136.975);
+/// assert_eq!(vdlm2.band(), FrequencyBand::Vhf);
+/// assert_eq!(serde_json::to_string(&acars).unwrap(), "131.55");
+/// let noisy: Frequency = serde_json::from_str("131.5500030517578").unwrap();
+/// assert_eq!(noisy, acars);
+/// assert_eq!(serde_json::to_string(&noisy).unwrap(), "131.55");
+/// let padded: Frequency = "131.550".parse().unwrap();
+/// assert_eq!((padded.precision(), padded.as_written()), (Some(3), "131.550".to_string()));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Frequency {
+    hz: u64,
+    unit: FrequencyUnit,
+    precision: Option<u8>,
+}
+
+impl Frequency {
+    /// Creates a `Frequency` from Hz, serialised in Hz.
+    pub fn from_hz(hz: u64) -> Self {
+        Self { hz, unit: FrequencyUnit::Hz, precision: None }
+    }
+
+    /// Creates a `Frequency` from MHz, rounded to the nearest Hz and serialised in MHz.
+    pub fn from_mhz(mhz: f64) -> Self {
+        Self { hz: (mhz * HZ_PER_MHZ).round() as u64, unit: FrequencyUnit::Mhz, precision: None }
+    }
+
+    /// Creates a `Frequency` from MHz as read from `text`, removing `f32` noise and keeping the decimal places written.
+    fn from_written_mhz(mhz: f64, text: &str) -> Self {
+        let khz: f64 = (mhz * 1_000.0).round() / 1_000.0;
+        let written: String = match khz != mhz && khz as f32 == mhz as f32 {
+            true => {
+                fallback("float_noise", text);
+                khz.to_string()
+            }
+            false => text.to_string(),
+        };
+        let precision: Option<u8> = written.split_once('.')
+            .map(|(_, fraction)| fraction.bytes().take_while(u8::is_ascii_digit).count().min(u8::MAX as usize) as u8);
+        Self { precision: Some(precision.unwrap_or_default()), ..Self::from_mhz(written.parse::<f64>().unwrap_or(mhz)) }
+    }
+
+    /// Creates a `Frequency` from a number in either unit, using the same rule as deserializing.
+    pub fn from_number(number: f64) -> Self {
+        match number >= HZ_PER_MHZ {
+            true => Self::from_hz(number.round() as u64),
+            false => Self::from_mhz(number),
+        }
+    }
+
+    /// Returns the frequency in Hz.
+    pub fn as_hz(&self) -> u64 {
+        self.hz
+    }
+
+    /// Returns the frequency in MHz.
+    pub fn as_mhz(&self) -> f64 {
+        self.hz as f64 / HZ_PER_MHZ
+    }
+
+    /// Returns the unit the frequency is serialised in.
+    pub fn unit(&self) -> FrequencyUnit {
+        self.unit
+    }
+
+    /// Returns the number of decimal places a frequency in MHz was written with, or `None` if it was not read from
+    /// text in MHz.
+    ///
+    /// Numbers are read through serde_json, which drops trailing zeros unless its `arbitrary_precision` feature is
+    /// enabled, so `131.550` gives `Some(2)` without it. Quoted numbers always keep them. Serialising writes these
+    /// decimal places back only with `arbitrary_precision` enabled.
+    pub fn precision(&self) -> Option<u8> {
+        self.precision
+    }
+
+    /// Returns the frequency as it was written, such as `131.550` or `136975000`, without `f32` noise.
+    pub fn as_written(&self) -> String {
+        match (self.unit, self.precision) {
+            (FrequencyUnit::Hz, _) => self.hz.to_string(),
+            (FrequencyUnit::Mhz, Some(precision)) => format!("{:.*}", precision as usize, self.as_mhz()),
+            (FrequencyUnit::Mhz, None) => self.as_mhz().to_string(),
+        }
+    }
+
+    /// Returns the radio band the frequency falls in.
+    pub fn band(&self) -> FrequencyBand {
+        match self.hz {
+            2_000_000..=29_999_999 => FrequencyBand::Hf,
+            30_000_000..=299_999_999 => FrequencyBand::Vhf,
+            300_000_000..=999_999_999 => FrequencyBand::Uhf,
+            1_000_000_000..=1_999_999_999 => FrequencyBand::LBand,
+            _ => FrequencyBand::Other,
+        }
+    }
+}
+
+impl PartialEq for Frequency {
+    fn eq(&self, other: &Self) -> bool {
+        self.hz == other.hz
+    }
+}
+
+impl Eq for Frequency {}
+
+impl Hash for Frequency {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hz.hash(state);
+    }
+}
+
+impl PartialOrd for Frequency {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frequency {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hz.cmp(&other.hz)
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3}MHz", self.as_mhz())
+    }
+}
+
+/// Parses a frequency such as `136975000`, `136.975` or `136.975 MHz`, with an optional `Hz`, `kHz` or `MHz` suffix.
+impl FromStr for Frequency {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let trimmed: &str = text.trim();
+        let lower: String = trimmed.to_ascii_lowercase();
+        let suffix: usize = ["mhz", "khz", "hz"].iter()
+            .find(|suffix| lower.ends_with(*suffix))
+            .map_or(0, |suffix| suffix.len());
+        let number: &str = trimmed[..trimmed.len() - suffix].trim();
+        let value: f64 = number.parse::<f64>()
+            .map_err(|error| format!("invalid frequency \"{}\": {}", text, error))?;
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("invalid frequency \"{}\"", text));
+        }
+        match &lower[lower.len() - suffix..] {
+            "mhz" => Ok(Self::from_written_mhz(value, number)),
+            "khz" => Ok(Self::from_hz((value * 1_000.0).round() as u64)),
+            "hz" => Ok(Self::from_hz(value.round() as u64)),
+            _ if value >= HZ_PER_MHZ => Ok(Self::from_number(value)),
+            _ => Ok(Self::from_written_mhz(value, number)),
+        }
+    }
+}
+
+/// Writes the frequency in the unit it was read in. A frequency in MHz read from text is written with the decimal
+/// places it was read with when serde_json's `arbitrary_precision` feature is enabled, and as the shortest `f64`
+/// otherwise, since only that feature lets serde_json write a number's digits as given.
+impl Serialize for Frequency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (self.unit, self.precision) {
+            (FrequencyUnit::Hz, _) => serializer.serialize_u64(self.hz),
+            (FrequencyUnit::Mhz, Some(_)) => {
+                let written: String = self.as_written();
+                match written.parse::<Number>() {
+                    Ok(number) if number.to_string() == written => number.serialize(serializer),
+                    _ => serializer.serialize_f64(self.as_mhz()),
+                }
+            }
+            (FrequencyUnit::Mhz, None) => serializer.serialize_f64(self.as_mhz()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Frequency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Read through a Value so numbers decode the same with serde_json's arbitrary_precision feature enabled.
+        match Value::deserialize(deserializer)? {
+            Value::Number(number) => match (number.as_u64(), number.as_f64()) {
+                (Some(hz), _) if hz as f64 >= HZ_PER_MHZ => Ok(Self::from_hz(hz)),
+                (_, Some(value)) if value.is_finite() && value >= HZ_PER_MHZ => Ok(Self::from_number(value)),
+                (_, Some(value)) if value.is_finite() && value >= 0.0 => Ok(Self::from_written_mhz(value, &number.to_string())),
+                _ => Err(D::Error::custom(format!("invalid frequency {}", number))),
+            },
+            Value::String(quoted) => {
+                fallback("quoted_number", &quoted);
+                quoted.parse::<Frequency>().map_err(D::Error::custom)
+            }
+            other => Err(D::Error::custom(format!("invalid frequency {}, expected a number or string", other))),
+        }
+    }
+}
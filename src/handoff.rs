@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::frequency::Frequency;
+use crate::vdlm2::{AvlcLink, FrequencySupport, Vdlm2Message, XidBlock};
+
+/// What told an aircraft to change channel.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelChangeKind {
+    /// A handoff XID moved the aircraft to another ground station.
+    Handoff,
+    /// A ground station told the aircraft to tune to another frequency with an autotune parameter.
+    AutoTune,
+}
+
+/// A change of ground station or frequency by an aircraft, reconstructed by a `HandoffAnalyzer`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChannelChange {
+    /// AVLC address of the aircraft, as dumpvdl2 writes it.
+    pub aircraft: String,
+    pub kind: ChannelChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
+    /// Ground station the aircraft was last seen with, or `None` if it had not been seen before.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_ground_station: Option<String>,
+    pub to_ground_station: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_frequency: Option<Frequency>,
+    pub to_frequency: Frequency,
+}
+
+/// The ground station and frequency an aircraft was last seen on.
+#[derive(Debug, Clone, PartialEq)]
+struct Channel {
+    ground_station: String,
+    frequency: Frequency,
+}
+
+/// Reconstructs the channel changes of aircraft from the handoff and autotune XIDs in VDLM2 traffic.
+///
+/// Every frame between an aircraft and a ground station updates the channel the aircraft was last seen on. A handoff
+/// XID from an aircraft to a ground station other than its last one, or on another frequency, is recorded as a
+/// `Handoff`. A handoff XID from a ground station carrying an autotune parameter is recorded as an `AutoTune` to that
+/// frequency, with the first replacement ground station listed, if there is one, as the new ground station. Repeated
+/// XIDs, such as retries, are only recorded once as the aircraft is already on the new channel.
+/// ```
+/// use acars_vdlm2_parser::frequency::Frequency;
+/// use acars_vdlm2_parser::handoff::{ChannelChange, ChannelChangeKind, HandoffAnalyzer};
+/// use acars_vdlm2_parser::vdlm2::{NewVdlm2Message, Vdlm2Message};
+/// let autotune: Vdlm2Message = r#"{"vdl2":{"freq":136975000,"burst_len_octets":88,"idx":0,"t":{"sec":1664122840,"usec":0},"avlc":{"src":{"addr":"10210A","type":"Ground station","status":"On ground"},"dst":{"addr":"A50064","type":"Aircraft"},"cr":"Command","frame_type":"U","cmd":"XID","pf":false,"xid":{"err":false,"type":"XID_CMD_HO","type_descr":"Handoff Request / Broadcast Handoff","vdl_params":[{"name":"autotune_freq","value":{"freq_mhz":136.100006,"modulation_support":["VDL-M2, D8PSK, 31500 bps"]}},{"name":"replacement_ground_stations","value":["12340A"]}]}}}}"#.to_vdlm2().unwrap();
+/// let mut analyzer: HandoffAnalyzer = HandoffAnalyzer::new();
+/// let change: ChannelChange = analyzer.record(&autotune).unwrap();
+/// assert_eq!(change.kind, ChannelChangeKind::AutoTune);
+/// assert_eq!(change.from_ground_station.as_deref(), Some("10210A"));
+/// assert_eq!((change.to_ground_station.as_str(), change.to_frequency), ("12340A", Frequency::from_hz(136_100_000)));
+/// assert!(analyzer.record(&autotune).is_none());
+/// assert_eq!(analyzer.changes().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HandoffAnalyzer {
+    channels: HashMap<String, Channel>,
+    changes: Vec<ChannelChange>,
+}
+
+impl HandoffAnalyzer {
+    /// Creates a new `HandoffAnalyzer` with no aircraft seen.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a single `Vdlm2Message`, returning the channel change it shows, if there is one.
+    pub fn record(&mut self, message: &Vdlm2Message) -> Option<ChannelChange> {
+        let link: AvlcLink<'_> = message.get_link()?;
+        let current: Channel = Channel { ground_station: link.ground_station.to_string(), frequency: message.get_frequency() };
+        let previous: Option<Channel> = self.channels.get(link.aircraft).cloned();
+        let handoff: Option<&XidBlock> = message.vdl2.avlc.xid().filter(|xid| xid.is_handoff());
+        let (kind, next, from): (ChannelChangeKind, Channel, Option<Channel>) = match handoff {
+            Some(xid) if link.uplink => match xid.autotune() {
+                Some(autotune) => (ChannelChangeKind::AutoTune, autotune_channel(xid, &autotune, &current), Some(current)),
+                None => (ChannelChangeKind::Handoff, current, previous.clone()),
+            },
+            Some(_) => (ChannelChangeKind::Handoff, current, previous.clone()),
+            None => {
+                self.channels.insert(link.aircraft.to_string(), current);
+                return None;
+            }
+        };
+        self.channels.insert(link.aircraft.to_string(), next.clone());
+        if previous.as_ref() == Some(&next) {
+            return None;
+        }
+        let change: ChannelChange = ChannelChange {
+            aircraft: link.aircraft.to_string(),
+            kind,
+            time: message.get_time(),
+            from_ground_station: from.as_ref().map(|channel| channel.ground_station.clone()),
+            to_ground_station: next.ground_station,
+            from_frequency: from.map(|channel| channel.frequency),
+            to_frequency: next.frequency,
+        };
+        self.changes.push(change.clone());
+        Some(change)
+    }
+
+    /// Records the message if it is a `Vdlm2Message`, other message families are ignored.
+    #[allow(unreachable_patterns)]
+    pub fn record_message(&mut self, message: &AcarsVdlm2Message) -> Option<ChannelChange> {
+        match message {
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => self.record(vdlm2),
+            _ => None,
+        }
+    }
+
+    /// Returns the channel changes recorded so far, in the order they were seen.
+    pub fn changes(&self) -> &[ChannelChange] {
+        &self.changes
+    }
+
+    /// Returns the channel changes recorded for one aircraft, by AVLC address.
+    pub fn changes_for<'a>(&'a self, aircraft: &'a str) -> impl Iterator<Item = &'a ChannelChange> {
+        self.changes.iter().filter(move |change| change.aircraft == aircraft)
+    }
+
+    /// Returns the ground station and frequency the aircraft was last seen on.
+    pub fn current_channel(&self, aircraft: &str) -> Option<(&str, Frequency)> {
+        self.channels.get(aircraft).map(|channel| (channel.ground_station.as_str(), channel.frequency))
+    }
+
+    /// Returns the channel changes recorded so far, leaving the analyzer to collect the next ones.
+    pub fn take_changes(&mut self) -> Vec<ChannelChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Converts the recorded channel changes to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(&self.changes)
+    }
+}
+
+fn autotune_channel(xid: &XidBlock, autotune: &FrequencySupport, current: &Channel) -> Channel {
+    let ground_station: String = xid.replacement_ground_stations()
+        .first()
+        .map_or_else(|| current.ground_station.clone(), |address| address.to_string());
+    Channel { ground_station, frequency: autotune.frequency() }
+}
@@ -1,9 +1,35 @@
-use std::num::ParseFloatError;
+use std::fmt;
+use std::io::Write;
 
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
-use crate::{AppDetails, MessageResult};
-
+use crate::{AppDetails, MessageFamily, MessageResult, SummaryLine};
+use crate::adsc::Adsc;
+use crate::app_template::AppDetailsTemplate;
+use crate::coerce::{number_or_string, option_number_or_string, whole_number};
+use crate::correlate::AckFields;
+use crate::equality::{eq_ignoring, FieldClass};
+#[cfg(feature = "enrichment")]
+use crate::enrichment::AircraftInfo;
+use crate::icao::parse_icao_hex;
+use crate::frequency::Frequency;
+use crate::instrument::decode_span;
+use crate::normalise::TextNormaliser;
+use crate::quality::FrameQuality;
+use crate::source::SourceKind;
+use crate::redact::{AircraftAnonymiser, StationRedactor};
+use crate::timestamp::MessageTimestamp;
+use crate::visit::{AcarsBlock, MessageVisitor, PositionReport, PositionSource, SignalInfo};
+
+/// The latitude and longitude HFDL aircraft report when they do not have a position fix.
+const NO_POSITION_FIX: (f64, f64) = (180.0, 180.0);
+
+/// Source type used by dumphfdl for ground stations.
+const GROUND_STATION: &str = "Ground station";
+
+
+/// A sanitised dumphfdl capture returned by `HfdlMessage::example()`.
+const EXAMPLE: &str = r#"{"hfdl":{"app":{"name":"dumphfdl","ver":"1.5.0"},"station":"EXAMPLE-STATION","t":{"sec":1700000000,"usec":250000},"freq":6661000,"bit_rate":1800,"sig_level":-91.8,"noise_level":-93.6,"freq_skew":2.3,"slot":"S","lpdu":{"err":false,"src":{"type":"Aircraft","id":46},"dst":{"type":"Ground station","id":4},"type":{"id":29,"name":"Unnumbered ack'ed data"},"hfnpdu":{"err":false,"type":{"id":255,"name":"Enveloped data"},"acars":{"err":false,"crc_ok":true,"more":false,"reg":".N123AB","mode":"2","label":"H1","blk_id":"4","ack":"!","flight":"AB0123","msg_num":"D48","msg_num_seq":"A","msg_text":"POS N47123W122456,KSEA,KSFO,1530,350,M45"}}}}}"#;
 
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
@@ -19,7 +45,7 @@ pub trait NewHfdlMessage {
 /// This does not consume the `String`.
 impl NewHfdlMessage for String {
     fn to_hfdl(&self) -> MessageResult<HfdlMessage> {
-        serde_json::from_str(self)
+        decode_span(Some(MessageFamily::Hfdl), self.len(), || serde_json::from_str(self))
     }
 }
 
@@ -28,17 +54,80 @@ impl NewHfdlMessage for String {
 /// This does not consume the `str`.
 impl NewHfdlMessage for str {
     fn to_hfdl(&self) -> MessageResult<HfdlMessage> {
-        serde_json::from_str(self)
+        decode_span(Some(MessageFamily::Hfdl), self.len(), || serde_json::from_str(self))
+    }
+}
+
+/// Supporting `.to_hfdl()` for JSON held as bytes, such as a socket buffer.
+///
+/// This decodes straight from the bytes without converting them to a `str` first, and does not consume them.
+impl NewHfdlMessage for [u8] {
+    fn to_hfdl(&self) -> MessageResult<HfdlMessage> {
+        decode_span(Some(MessageFamily::Hfdl), self.len(), || serde_json::from_slice(self))
+    }
+}
+
+/// Supporting `.to_hfdl()` for JSON already parsed into a `Value`, such as by an earlier pipeline stage.
+///
+/// This decodes from the `Value` without writing it back out as JSON first, and does not consume it.
+impl NewHfdlMessage for Value {
+    fn to_hfdl(&self) -> MessageResult<HfdlMessage> {
+        decode_span(Some(MessageFamily::Hfdl), 0, || HfdlMessage::deserialize(self))
+    }
+}
+
+/// Decodes an owned `Value`, see `HfdlMessage::from_value`.
+impl TryFrom<Value> for HfdlMessage {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> MessageResult<Self> {
+        Self::from_value(value)
     }
 }
 
 impl HfdlMessage {
 
-    /// Converts `HfdlMessage` to `String`.
-    pub fn to_string(&self) -> MessageResult<String> {
+    /// Returns a realistic, fully populated message based on a sanitised dumphfdl capture, for doctests and unit tests
+    /// that need more than the empty shell `Default` gives.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use acars_vdlm2_parser::hfdl::HfdlMessage;
+    /// let example: HfdlMessage = HfdlMessage::example();
+    /// assert_eq!(example.get_flight(), Some("AB0123"));
+    /// let decoded: AcarsVdlm2Message = example.to_json().unwrap().decode_message().unwrap();
+    /// assert_eq!(decoded.to_json().unwrap(), example.to_json().unwrap());
+    /// ```
+    pub fn example() -> Self {
+        // The doctest above checks the example decodes, so the fallback is never taken.
+        serde_json::from_str(EXAMPLE).unwrap_or_default()
+    }
+
+    /// Decodes a `HfdlMessage` from an owned `Value`, moving its strings into the message rather than copying them.
+    pub fn from_value(value: Value) -> MessageResult<Self> {
+        decode_span(Some(MessageFamily::Hfdl), 0, || serde_json::from_value(value))
+    }
+
+    /// Converts `HfdlMessage` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
         serde_json::to_string(self)
     }
 
+    /// Converts `HfdlMessage` to a JSON `String`.
+    ///
+    /// This shadows `ToString::to_string`, which returns the single line summary of the `Display` implementation, so
+    /// `message.to_string()` and `format!("{}", message)` differ. Use `to_json()` for the JSON and `summary_line()` for
+    /// the summary.
+    #[deprecated(since = "0.3.0", note = "use `to_json()` for the JSON, `to_string()` will return the `Display` summary")]
+    pub fn to_string(&self) -> MessageResult<String> {
+        self.to_json()
+    }
+
+    /// Returns the single line summary written by the `Display` implementation, for logging, see `to_json` for the
+    /// JSON.
+    pub fn summary_line(&self) -> String {
+        ToString::to_string(self)
+    }
+
 
     /// Converts `HfdlMessage` to `String` and appends a `\n` to the end.
     pub fn to_string_newline(&self) -> MessageResult<String> {
@@ -49,27 +138,33 @@ impl HfdlMessage {
         }
     }
 
-    /// Converts `HfdlMessage` to a `String` encoded as bytes.
+    /// Converts `HfdlMessage` to JSON encoded as bytes.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
-        let string_conversion: MessageResult<String> = self.to_string();
-        match string_conversion {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes())
-        }
+        serde_json::to_vec(self)
     }
 
-
-    /// Converts `HfdlMessage` to a `String` terminated with a `\n` and encoded as bytes.
+    /// Converts `HfdlMessage` to JSON encoded as bytes and terminated with a `\n`.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes_newline(&self) -> MessageResult<Vec<u8>> {
-        let string_conversion: MessageResult<String> = self.to_string_newline();
-        match string_conversion {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes())
-        }
+        let mut bytes: Vec<u8> = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    /// Serialises `HfdlMessage` as JSON straight into the provided writer.
+    ///
+    /// This avoids allocating an intermediate buffer for the output.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Serialises `HfdlMessage` as JSON straight into the provided writer and terminates it with a `\n`.
+    pub fn write_to_newline<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(&mut *writer, self)?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)
     }
 
     /// Clears a station name that may be set for `HfdlMessage`.
@@ -107,23 +202,292 @@ impl HfdlMessage {
         }
     }
 
+    /// Returns the app block for `HfdlMessage` if there is one.
+    pub fn get_app_details(&self) -> Option<&AppDetails> {
+        self.hfdl.app.as_ref()
+    }
+
+    /// Applies the provided `AppDetailsTemplate`, see `AppDetailsTemplate` for how an existing app block is merged.
+    pub fn apply_app_template(&mut self, template: &AppDetailsTemplate) {
+        match self.hfdl.app.as_mut() {
+            None => self.hfdl.app = Some(template.details().clone()),
+            Some(app_details) => template.merge_into(app_details)
+        }
+    }
+
     pub fn clear_time(&mut self) {
         self.hfdl.t = None;
     }
 
     pub fn get_time(&self) -> Option<f64> {
-        match &self.hfdl.t {
-            None => None,
-            Some(time_block) => {
-                // This will do until there's a more elegant solution found.
-                let build_float_string: String = format!("{}.{}", time_block.sec, time_block.usec);
-                let parse_f64: Result<f64, ParseFloatError> = build_float_string.parse::<f64>();
-                match parse_f64 {
-                    Err(_) => None,
-                    Ok(value) => Some(value)
+        self.get_timestamp().map(|timestamp| timestamp.seconds)
+    }
+
+    /// Returns the time the station heard the burst, as dumpvdl2 and dumphfdl report it to the microsecond.
+    pub fn get_timestamp(&self) -> Option<MessageTimestamp> {
+        self.hfdl.t.as_ref().map(|time_block| MessageTimestamp::from_station(time_block.sec, time_block.usec))
+    }
+
+    /// Replaces every station identifying field with a stable pseudonym from the provided `StationRedactor`.
+    ///
+    /// This covers the station name, the ARINC 622 `gs_addr`, and the id and name of every ground station the frame
+    /// names: the sender of a squitter and the stations in its status list, either end of an LPDU, the ground station
+    /// of an HFNPDU and its frequency data, and the ids in a system table. The positions in a system table are kept.
+    pub fn redact_ground_stations(&mut self, redactor: &StationRedactor) {
+        if let Some(station) = self.hfdl.station.as_mut() {
+            *station = redactor.pseudonym(station);
+        }
+        for ground_station in self.ground_stations_mut() {
+            ground_station.id = redactor.pseudonym_id(ground_station.id);
+            if let Some(name) = ground_station.name.as_mut() {
+                *name = redactor.pseudonym(name);
+            }
+        }
+        let systable: Option<&mut SysTable> = self.hfdl.lpdu.as_mut()
+            .and_then(|lpdu| lpdu.hfnpdu.as_mut())
+            .and_then(|hfnpdu| hfnpdu.systable_complete.as_mut());
+        for ground_station in systable.into_iter().flat_map(|systable| systable.ground_stations.iter_mut()) {
+            ground_station.id = redactor.pseudonym_id(ground_station.id);
+        }
+        let arinc622: Option<&mut Arinc622> = self.hfdl.lpdu.as_mut()
+            .and_then(|lpdu| lpdu.hfnpdu.as_mut())
+            .and_then(|hfnpdu| hfnpdu.acars.as_mut())
+            .and_then(|acars| acars.arinc622.as_mut());
+        if let Some(arinc622) = arinc622 {
+            arinc622.gs_addr = redactor.pseudonym_gs_addr(&arinc622.gs_addr);
+        }
+    }
+
+    /// Replaces the ICAO address and registration in the `ac_info` blocks, the ACARS registration, the ARINC 622 air
+    /// address and the ADS-C airframe ID with stable pseudonyms from the provided `AircraftAnonymiser`.
+    pub fn anonymise_aircraft(&mut self, anonymiser: &AircraftAnonymiser) {
+        if let Some(lpdu) = self.hfdl.lpdu.as_mut() {
+            let src_info: Option<&mut LPDUAircraftInfo> = lpdu.src.as_mut().and_then(|src| src.ac_info.as_mut());
+            let dst_info: Option<&mut LPDUAircraftInfo> = lpdu.dst.as_mut().and_then(|dst| dst.ac_info.as_mut());
+            for ac_info in [lpdu.ac_info.as_mut(), src_info, dst_info].into_iter().flatten() {
+                ac_info.icao = anonymiser.pseudonym_icao_hex(&ac_info.icao);
+                if let Some(regnr) = ac_info.regnr.as_mut() {
+                    *regnr = anonymiser.pseudonym_registration(regnr);
+                }
+            }
+            if let Some(acars) = lpdu.hfnpdu.as_mut().and_then(|hfnpdu| hfnpdu.acars.as_mut()) {
+                acars.reg = anonymiser.pseudonym_registration(&acars.reg);
+                if let Some(arinc622) = acars.arinc622.as_mut() {
+                    arinc622.air_addr = anonymiser.pseudonym_registration(&arinc622.air_addr);
+                    if let Some(adsc) = arinc622.adsc.as_mut() {
+                        adsc.anonymise_aircraft(anonymiser);
+                    }
                 }
             }
         }
+        #[cfg(feature = "enrichment")]
+        if let Some(registration) = self.hfdl.aircraft_info.as_mut().and_then(|info| info.registration.as_mut()) {
+            *registration = anonymiser.pseudonym_registration(registration);
+        }
+    }
+
+    /// Returns true if the messages are the same apart from the fields in the given classes, see `FieldClass`.
+    pub fn eq_ignoring(&self, other: &HfdlMessage, ignored: &[FieldClass]) -> bool {
+        eq_ignoring(self, other, MessageFamily::Hfdl, ignored)
+    }
+
+    /// Returns the `FrameQuality` for `HfdlMessage`.
+    ///
+    /// dumphfdl does not report FEC corrections, so frames are either `Ok` or `Failed` when any
+    /// of the SPDU, LPDU, HFNPDU or ACARS layers report an error.
+    pub fn get_frame_quality(&self) -> FrameQuality {
+        let spdu_failed: bool = self.hfdl.spdu.as_ref().is_some_and(|spdu| spdu.err);
+        let lpdu_failed: bool = self.hfdl.lpdu.as_ref().is_some_and(|lpdu| {
+            lpdu.err || lpdu.hfnpdu.as_ref().is_some_and(|hfnpdu| hfnpdu.err)
+        });
+        let acars_failed: bool = self.get_acars().is_some_and(|acars| acars.err || !acars.crc_ok);
+        match spdu_failed || lpdu_failed || acars_failed {
+            true => FrameQuality::Failed,
+            false => FrameQuality::Ok,
+        }
+    }
+
+    /// Returns the ground station taking part in the frame.
+    ///
+    /// This is the sender of a squitter (SPDU), otherwise whichever end of the LPDU is a ground station.
+    fn get_ground_station(&self) -> Option<&SPDUorLPDUSource> {
+        if let Some(spdu) = self.hfdl.spdu.as_ref() {
+            return Some(&spdu.src);
+        }
+        let lpdu: &LPDU = self.hfdl.lpdu.as_ref()?;
+        [lpdu.src.as_ref(), lpdu.dst.as_ref()].into_iter()
+            .flatten()
+            .find(|source| source.source_type == GROUND_STATION)
+    }
+
+    /// Returns every source in the frame that is a ground station.
+    fn ground_stations_mut(&mut self) -> Vec<&mut SPDUorLPDUSource> {
+        let mut sources: Vec<&mut SPDUorLPDUSource> = Vec::new();
+        if let Some(spdu) = self.hfdl.spdu.as_mut() {
+            sources.push(&mut spdu.src);
+            sources.extend(spdu.gs_status.iter_mut().map(|status| &mut status.gs));
+        }
+        if let Some(lpdu) = self.hfdl.lpdu.as_mut() {
+            sources.extend(lpdu.src.as_mut());
+            sources.extend(lpdu.dst.as_mut());
+            if let Some(hfnpdu) = lpdu.hfnpdu.as_mut() {
+                sources.extend(hfnpdu.gs.as_mut());
+                sources.extend(hfnpdu.freq_data.iter_mut().flatten().map(|freq_data| &mut freq_data.gs));
+            }
+        }
+        sources.retain(|source| source.source_type == GROUND_STATION);
+        sources
+    }
+
+    /// Returns the id of the ground station taking part in the frame.
+    pub fn get_ground_station_id(&self) -> Option<u16> {
+        self.get_ground_station().map(|ground_station| ground_station.id)
+    }
+
+    /// Returns the name of the ground station taking part in the frame, when dumphfdl has a system table loaded.
+    pub fn get_ground_station_name(&self) -> Option<&str> {
+        self.get_ground_station().and_then(|ground_station| ground_station.name.as_deref())
+    }
+
+    /// Returns `true` when the frame was sent by a ground station and `false` when it was sent by an aircraft.
+    pub fn is_uplink(&self) -> Option<bool> {
+        if self.hfdl.spdu.is_some() {
+            return Some(true);
+        }
+        self.hfdl.lpdu.as_ref()
+            .and_then(|lpdu| lpdu.src.as_ref())
+            .map(|source| source.source_type == GROUND_STATION)
+    }
+
+    /// Returns the `SourceKind` for `HfdlMessage`, see `is_uplink`.
+    pub fn source_kind(&self) -> SourceKind {
+        match self.is_uplink() {
+            Some(true) => SourceKind::GroundStation,
+            Some(false) => SourceKind::Aircraft,
+            None => SourceKind::Unknown,
+        }
+    }
+
+    /// Returns the ICAO address of the aircraft taking part in the frame, when dumphfdl knows it.
+    ///
+    /// dumphfdl only reports the address once the aircraft has logged on, or in the logon messages themselves.
+    pub fn get_icao(&self) -> Option<u32> {
+        let lpdu: &LPDU = self.hfdl.lpdu.as_ref()?;
+        let src_info: Option<&LPDUAircraftInfo> = lpdu.src.as_ref().and_then(|src| src.ac_info.as_ref());
+        let dst_info: Option<&LPDUAircraftInfo> = lpdu.dst.as_ref().and_then(|dst| dst.ac_info.as_ref());
+        [lpdu.ac_info.as_ref(), src_info, dst_info].into_iter()
+            .flatten()
+            .find_map(|ac_info| parse_icao_hex(&ac_info.icao))
+    }
+
+    /// Returns the frequency `HfdlMessage` was received on.
+    pub fn get_frequency(&self) -> Frequency {
+        self.hfdl.freq
+    }
+
+    /// Returns the station name for `HfdlMessage` if one is set.
+    pub fn get_station_name(&self) -> Option<&str> {
+        self.hfdl.station.as_deref()
+    }
+
+    /// Returns the ACARS block carried by the HFNPDU if there is one.
+    pub fn get_acars(&self) -> Option<&LPDUAcars> {
+        self.hfdl.lpdu.as_ref()
+            .and_then(|lpdu| lpdu.hfnpdu.as_ref())
+            .and_then(|hfnpdu| hfnpdu.acars.as_ref())
+    }
+
+    /// Returns the ACARS label for `HfdlMessage` if the frame carries an ACARS block.
+    pub fn get_label(&self) -> Option<&str> {
+        self.get_acars().map(|acars| acars.label.as_str())
+    }
+
+    /// Returns the ACARS sublabel for `HfdlMessage` if libacars decoded one.
+    pub fn get_sublabel(&self) -> Option<&str> {
+        self.get_acars().and_then(|acars| acars.sublabel.as_deref())
+    }
+
+    /// Normalises the ACARS message text using the provided `TextNormaliser`.
+    pub fn normalise_text(&mut self, normaliser: &TextNormaliser) {
+        let acars: Option<&mut LPDUAcars> = self.hfdl.lpdu.as_mut()
+            .and_then(|lpdu| lpdu.hfnpdu.as_mut())
+            .and_then(|hfnpdu| hfnpdu.acars.as_mut());
+        if let Some(acars) = acars {
+            normaliser.normalise_in_place(&mut acars.msg_text);
+        }
+    }
+
+    /// Returns the link layer fields used to pair `HfdlMessage` with its acknowledgement.
+    pub fn get_ack_fields(&self) -> Option<AckFields<'_>> {
+        self.get_acars().map(|acars| AckFields {
+            mode: &acars.mode,
+            registration: acars.reg.trim_start_matches('.'),
+            label: &acars.label,
+            block_id: &acars.blk_id,
+            msgno: acars.msg_num.as_deref(),
+            ack: AckFields::parse_ack(&acars.ack),
+        })
+    }
+
+    /// Passes the parts of `HfdlMessage` to the visitor, see `visit::MessageVisitor`.
+    ///
+    /// Positions come from aircraft position reports, ADS-C reports and the ground stations in the system table.
+    pub fn visit(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.on_signal_info(&SignalInfo {
+            frequency: self.hfdl.freq,
+            level_dbm: self.hfdl.sig_level,
+            noise_level_dbm: self.hfdl.noise_level,
+            freq_skew_hz: self.hfdl.freq_skew,
+            quality: self.get_frame_quality(),
+        });
+        if let Some(app) = &self.hfdl.app {
+            visitor.on_app_details(app);
+        }
+        if let Some(link) = self.get_ack_fields() {
+            visitor.on_acars_block(&AcarsBlock { link, flight: self.get_flight(), sublabel: self.get_sublabel(), text: self.get_text() });
+        }
+        let Some(hfnpdu) = self.hfdl.lpdu.as_ref().and_then(|lpdu| lpdu.hfnpdu.as_ref()) else {
+            return;
+        };
+        // Aircraft without a position fix report 180,180, which is not a position.
+        if let Some(position) = hfnpdu.pos.as_ref().filter(|position| (position.lat, position.lon) != NO_POSITION_FIX) {
+            visitor.on_position(&PositionReport { lat: position.lat, lon: position.lon, altitude_ft: None, source: PositionSource::HfdlAircraft });
+        }
+        let adsc: Option<&Adsc> = hfnpdu.acars.as_ref().and_then(|acars| acars.arinc622.as_ref()).and_then(|arinc622| arinc622.adsc.as_ref());
+        for report in adsc.into_iter().flat_map(|adsc| adsc.position_reports()) {
+            visitor.on_position(&PositionReport { lat: report.lat, lon: report.lon, altitude_ft: Some(report.alt), source: PositionSource::AdscReport });
+        }
+        for ground_station in hfnpdu.systable_complete.iter().flat_map(|systable| &systable.ground_stations) {
+            let location: &Position = &ground_station.location;
+            visitor.on_position(&PositionReport { lat: location.lat, lon: location.lon, altitude_ft: None, source: PositionSource::GroundStation });
+        }
+    }
+
+    /// Returns the aircraft registration for `HfdlMessage` with any leading padding dots removed.
+    pub fn get_registration(&self) -> Option<&str> {
+        self.get_acars().map(|acars| acars.reg.trim_start_matches('.'))
+    }
+
+    /// Returns the flight number for `HfdlMessage` if the frame carries one.
+    pub fn get_flight(&self) -> Option<&str> {
+        self.get_acars().and_then(|acars| acars.flight.as_deref())
+    }
+
+    /// Returns the ACARS message text for `HfdlMessage` if the frame carries an ACARS block.
+    pub fn get_text(&self) -> Option<&str> {
+        self.get_acars().map(|acars| acars.msg_text.as_str())
+    }
+
+    /// Returns the `aircraft_info` block for `HfdlMessage` if it has been enriched.
+    #[cfg(feature = "enrichment")]
+    pub fn get_aircraft_info(&self) -> Option<&AircraftInfo> {
+        self.hfdl.aircraft_info.as_ref()
+    }
+
+    /// Sets the `aircraft_info` block for `HfdlMessage`.
+    #[cfg(feature = "enrichment")]
+    pub fn set_aircraft_info(&mut self, info: AircraftInfo) {
+        self.hfdl.aircraft_info = Some(info);
     }
 
     pub fn clear_freq_skew(&mut self) {
@@ -139,6 +503,51 @@ impl HfdlMessage {
     }
 }
 
+/// Renders a concise single line summary of the message, intended for logging rather than forwarding.
+///
+/// `summary_line()` returns it as a `String`, and `to_json()` returns the JSON.
+impl fmt::Display for HfdlMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        SummaryLine {
+            family: "HFDL",
+            time: self.get_time(),
+            station: self.get_station_name(),
+            freq_mhz: self.hfdl.freq.as_mhz(),
+            registration: self.get_registration(),
+            flight: self.get_flight(),
+            label: self.get_label(),
+            text: self.get_text(),
+        }.fmt(f)
+    }
+}
+
+/// A dumphfdl frame.
+///
+/// Every field of the HFDL types is public, so messages can be built and inspected directly, starting from `default`
+/// for the parts that are not needed. The HFDL types reject unknown fields only when reading, so a message built in
+/// code is written as it is and reads back the same.
+/// ```
+/// use acars_vdlm2_parser::frequency::Frequency;
+/// use acars_vdlm2_parser::hfdl::{HfdlBody, HfdlMessage, LPDUAcars, LPDUHfnPdu, LPDU, NewHfdlMessage};
+/// let message: HfdlMessage = HfdlMessage {
+///     hfdl: HfdlBody {
+///         freq: Frequency::from_hz(13_312_000),
+///         bit_rate: 1800,
+///         slot: "S".to_string(),
+///         lpdu: Some(LPDU {
+///             hfnpdu: Some(LPDUHfnPdu {
+///                 acars: Some(LPDUAcars { reg: ".N123AB".to_string(), label: "H1".to_string(), msg_text: "HELLO".to_string(), ..Default::default() }),
+///                 ..Default::default()
+///             }),
+///             ..Default::default()
+///         }),
+///         ..Default::default()
+///     },
+/// };
+/// let read_back: HfdlMessage = message.to_json().unwrap().to_hfdl().unwrap();
+/// assert_eq!((read_back.get_label(), read_back.get_text()), (Some("H1"), Some("HELLO")));
+/// assert_eq!(read_back.hfdl.lpdu.unwrap().hfnpdu.unwrap().acars.unwrap().reg, ".N123AB");
+/// ```
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct HfdlMessage {
     pub hfdl: HfdlBody,
@@ -149,111 +558,122 @@ pub struct HfdlMessage {
 pub struct HfdlBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub app: Option<AppDetails>,
-    pub freq: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freq: Frequency,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub noise_level: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub sig_level: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Station name set by the feeder, also read from `station_id` as acarsdec names it.
+    #[serde(alias = "station_id", skip_serializing_if = "Option::is_none")]
     pub station: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub t: Option<TBlock>,
+    #[serde(deserialize_with = "number_or_string")]
     pub bit_rate: u16,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub freq_skew: Option<f64>,
     pub slot: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lpdu: Option<LPDU>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub spdu: Option<SPDU>,
+    #[cfg(feature = "enrichment")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aircraft_info: Option<AircraftInfo>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct SPDU {
-    err: bool,
-    src: SPDUorLPDUSource,
-    spdu_version: u8,
-    rls: bool,
-    iso: bool,
-    change_note: String,
-    frame_index: u16,
-    frame_offset: u8,
-    min_priority: u8,
-    systable_version: u8,
-    gs_status: Vec<SPDUGroundStationStatus>,
+    pub err: bool,
+    pub src: SPDUorLPDUSource,
+    pub spdu_version: u8,
+    pub rls: bool,
+    pub iso: bool,
+    pub change_note: String,
+    pub frame_index: u16,
+    pub frame_offset: u8,
+    pub min_priority: u8,
+    pub systable_version: u8,
+    pub gs_status: Vec<SPDUGroundStationStatus>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct SPDUGroundStationStatus {
-    gs: SPDUorLPDUSource,
-    utc_sync: bool,
-    freqs: Vec<FreqId>,
+    pub gs: SPDUorLPDUSource,
+    pub utc_sync: bool,
+    pub freqs: Vec<FreqId>,
 }
 
+/// Time the frame was received, as whole seconds and microseconds since the Unix epoch.
+///
+/// Either part can also be read quoted or with a fractional part, as some forks write them, and is dropped to a whole
+/// number.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct TBlock {
+    #[serde(deserialize_with = "whole_number")]
     pub sec: u64,
+    #[serde(deserialize_with = "whole_number")]
     pub usec: u64
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDU {
-    err: bool,
+    pub err: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    dst: Option<SPDUorLPDUSource>,
+    pub dst: Option<SPDUorLPDUSource>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    src: Option<SPDUorLPDUSource>,
+    pub src: Option<SPDUorLPDUSource>,
     #[serde(rename = "type")]
-    lpdu_type: LPDUType,
+    pub lpdu_type: LPDUType,
     #[serde(skip_serializing_if = "Option::is_none")]
-    ac_info: Option<LPDUAircraftInfo>,
+    pub ac_info: Option<LPDUAircraftInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    hfnpdu: Option<LPDUHfnPdu>,
+    pub hfnpdu: Option<LPDUHfnPdu>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    assigned_ac_id: Option<u16>,
+    pub assigned_ac_id: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    reason: Option<LPDUReason>,
+    pub reason: Option<LPDUReason>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUReason {
-    code: u16,
-    descr: String,
+    pub code: u16,
+    pub descr: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUAcars {
-    err: bool,
-    crc_ok: bool,
-    more: bool,
-    reg: String,
-    mode: String,
-    label: String,
+    pub err: bool,
+    pub crc_ok: bool,
+    pub more: bool,
+    pub reg: String,
+    pub mode: String,
+    pub label: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sublabel: Option<String>,
-    blk_id: String,
-    ack: String,
+    pub sublabel: Option<String>,
+    pub blk_id: String,
+    pub ack: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    flight: Option<String>,
+    pub flight: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    msg_num: Option<String>,
+    pub msg_num: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    msg_num_seq: Option<String>,
-    msg_text: String,
+    pub msg_num_seq: Option<String>,
+    pub msg_text: String,
     #[serde(rename = "media-adv", skip_serializing_if = "Option::is_none")]
-    media_advisory: Option<LPDUAcarsMediaAdvisory>,
+    pub media_advisory: Option<LPDUAcarsMediaAdvisory>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    mfi: Option<String>,
+    pub mfi: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    arinc622: Option<Arinc622>,
+    pub arinc622: Option<Arinc622>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    miam: Option<Miam>,
+    pub miam: Option<Miam>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -298,14 +718,13 @@ pub struct Arinc622 {
     pub adsc: Option<ADSC>,
 }
 
-// TODO: I think VDLM and HFDL share the same ADSC and CPDLC structures, so this should be moved to a common location.
-// Also, I really think this should be enumerated out in to structs/enums instead of using serde_json::Value.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
-#[serde(deny_unknown_fields)]
-pub struct ADSC {
-    pub tags: Vec<Value>,
-    pub err: bool
-}
+/// ADS-C block carried by an ARINC 622 message, shared with VDLM2, see `adsc::Adsc`.
+///
+/// This is a breaking change from 0.2, where `ADSC` was a struct holding each tag as a raw `Value`, see
+/// `vdlm2::AdscEntry`.
+pub type ADSC = Adsc;
+
+// TODO: I think VDLM and HFDL share the same CPDLC structures, so this should be moved to a common location.
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -329,118 +748,118 @@ pub struct ATCDownUplinkMessageElementId {
 #[serde(deny_unknown_fields)]
 pub struct ATCData {
     #[serde(skip_serializing_if = "Option::is_none")]
-    free_text: Option<String>,
+    pub free_text: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    icao_facility_designation: Option<String>,
+    pub icao_facility_designation: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    freq: Option<ATCFreq>,
+    pub freq: Option<ATCFreq>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    icao_unit_name_freq: Option<ATCIcaoUnitNameFreq>,
+    pub icao_unit_name_freq: Option<ATCIcaoUnitNameFreq>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    alt: Option<ATCDataAlt>,
+    pub alt: Option<ATCDataAlt>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    alt_alt: Option<Vec<ATCDataBlockAlt>>,
+    pub alt_alt: Option<Vec<ATCDataBlockAlt>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    beacon_code: Option<String>,
+    pub beacon_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    dist_offset_dir: Option<ATCDataDistOffsetDir>,
+    pub dist_offset_dir: Option<ATCDataDistOffsetDir>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    time: Option<UTCTime>,
+    pub time: Option<UTCTime>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    icao_facility_designation_tp4_table: Option<ICAOFacilityDesignationTP4Table>,
-    pos: Option<DownlinkPosition>,
+    pub icao_facility_designation_tp4_table: Option<ICAOFacilityDesignationTP4Table>,
+    pub pos: Option<DownlinkPosition>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct DownlinkPosition {
-    choice: String,
-    data: DownlinkPositionData,
+    pub choice: String,
+    pub data: DownlinkPositionData,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct DownlinkPositionData {
-    fix: String
+    pub fix: String
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ICAOFacilityDesignationTP4Table {
-    icao_facility_designation: String,
-    tp4table: String,
+    pub icao_facility_designation: String,
+    pub tp4table: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ATCDataDistOffsetDir {
-    dir: String,
-    dist_offset: DistOffset
+    pub dir: String,
+    pub dist_offset: DistOffset
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct DistOffset {
-    choice: String,
-    data: DistOffsetData
+    pub choice: String,
+    pub data: DistOffsetData
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct DistOffsetData {
     #[serde(skip_serializing_if = "Option::is_none")]
-    dist_offset_nm: Option<Offset>,
+    pub dist_offset_nm: Option<Offset>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct Offset {
-    val: f64,
-    unit: String
+    pub val: f64,
+    pub unit: String
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ATCDataBlockAlt {
-    alt: ATCDataAlt
+    pub alt: ATCDataAlt
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ATCDataAlt {
-    choice: String,
-    data: Value,
+    pub choice: String,
+    pub data: Value,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ATCIcaoUnitNameFreq {
-    icao_unit_name: ATCICAOUnitName,
-    freq: ATCFreq,
+    pub icao_unit_name: ATCICAOUnitName,
+    pub freq: ATCFreq,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ATCICAOUnitName {
     #[serde(skip_serializing_if = "Option::is_none")]
-    icao_facility_id: Option<ICAOFacilityId>,
-    icao_facility_function: String,
+    pub icao_facility_id: Option<ICAOFacilityId>,
+    pub icao_facility_function: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ICAOFacilityId {
-    choice: String,
-    data: ICAOFacilityIdData,
+    pub choice: String,
+    pub data: ICAOFacilityIdData,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ICAOFacilityIdData {
     #[serde(skip_serializing_if = "Option::is_none")]
-    icao_facility_name: Option<String>,
+    pub icao_facility_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    icao_facility_designation: Option<String>,
+    pub icao_facility_designation: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -454,16 +873,16 @@ pub struct ATCFreq {
 #[serde(deny_unknown_fields)]
 pub struct ATCFreqData {
     #[serde(skip_serializing_if = "Option::is_none")]
-    vhf: Option<ATCFreqDataType>,
+    pub vhf: Option<ATCFreqDataType>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    hf: Option<ATCFreqDataType>,
+    pub hf: Option<ATCFreqDataType>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ATCFreqDataType {
-    val: f64,
-    unit: String
+    pub val: f64,
+    pub unit: String
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -487,115 +906,115 @@ pub struct ATCUplinkMessageElementIdSequence {
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ATCDownUplinkHeader {
-    msg_id: u16,
+    pub msg_id: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
-    msg_ref: Option<u16>,
+    pub msg_ref: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    timestamp: Option<UTCTime>,
+    pub timestamp: Option<UTCTime>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUAcarsMediaAdvisory {
-    err: bool,
-    version: u8,
-    current_link: LPDUAcarsMediaAdvisoryLink,
-    links_avail: Vec<LPDUACARSMediaAdivsoryLinksAvailble>,
+    pub err: bool,
+    pub version: u8,
+    pub current_link: LPDUAcarsMediaAdvisoryLink,
+    pub links_avail: Vec<LPDUACARSMediaAdivsoryLinksAvailble>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUAcarsMediaAdvisoryLink {
-    code: String,
-    descr: String,
-    established: bool,
-    time: UTCTime
+    pub code: String,
+    pub descr: String,
+    pub established: bool,
+    pub time: UTCTime
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUACARSMediaAdivsoryLinksAvailble {
-    code: String,
-    descr: String,
+    pub code: String,
+    pub descr: String,
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct SPDUorLPDUSource {
     #[serde(rename = "type")]
-    source_type: String,
-    id: u16,
+    pub source_type: String,
+    pub id: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
-    ac_info: Option<LPDUAircraftInfo>,
+    pub ac_info: Option<LPDUAircraftInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    name: Option<String>,
+    pub name: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUType {
-    name: String,
-    id: u16,
+    pub name: String,
+    pub id: u16,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUAircraftInfo {
-    icao: String,
+    pub icao: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    regnr: Option<String>,
+    pub regnr: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    typecode: Option<String>,
+    pub typecode: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    opercode: Option<String>,
+    pub opercode: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    manuf: Option<String>,
+    pub manuf: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    model: Option<String>,
+    pub model: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    owner: Option<String>,
+    pub owner: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUHfnPdu {
-    err: bool,
+    pub err: bool,
     #[serde(rename = "type")]
-    lpdu_type: LPDUType,
+    pub lpdu_type: LPDUType,
     #[serde(skip_serializing_if = "Option::is_none")]
-    flight_id: Option<String>,
+    pub flight_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pos: Option<Position>,
+    pub pos: Option<Position>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    utc_time: Option<UTCTime>,
+    pub utc_time: Option<UTCTime>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    freq_data: Option<Vec<LPDUFreqData>>,
+    pub freq_data: Option<Vec<LPDUFreqData>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    version: Option<u8>,
+    pub version: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    time: Option<UTCTime>,
+    pub time: Option<UTCTime>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    flight_leg_num: Option<u16>,
+    pub flight_leg_num: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    gs: Option<SPDUorLPDUSource>,
+    pub gs: Option<SPDUorLPDUSource>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    frequency: Option<FreqId>,
+    pub frequency: Option<FreqId>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    freq_search_cnt: Option<LPDUHfnPduCount>,
+    pub freq_search_cnt: Option<LPDUHfnPduCount>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    hfdl_disabled_duration: Option<LPDUHfnPduDisabledCount>,
+    pub hfdl_disabled_duration: Option<LPDUHfnPduDisabledCount>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pdu_stats: Option<PDUStats>,
+    pub pdu_stats: Option<PDUStats>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    last_freq_change_cause: Option<LastFreqChangeCause>,
+    pub last_freq_change_cause: Option<LastFreqChangeCause>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    acars: Option<LPDUAcars>,
+    pub acars: Option<LPDUAcars>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    request_data: Option<u32>,
+    pub request_data: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    systable_partial: Option<SysTablePartial>,
+    pub systable_partial: Option<SysTablePartial>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    systable_complete: Option<SysTable>
+    pub systable_complete: Option<SysTable>
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -619,90 +1038,90 @@ pub struct SysTableGroundStation {
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct SysTableFreqs {
-    freq: f32,
-    master_frame_slot: u8,
+    pub freq: f32,
+    pub master_frame_slot: u8,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct SysTablePartial {
-    part_num: u32,
-    parts_cnt: u32,
+    pub part_num: u32,
+    pub parts_cnt: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LastFreqChangeCause {
-    code: u8,
-    descr: String,
+    pub code: u8,
+    pub descr: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct PDUStats {
-    mpdus_rx_ok_cnt: PDUStatCounts,
-    mpdus_rx_err_cnt: PDUStatCounts,
-    mpdus_tx_cnt: PDUStatCounts,
-    mpdus_delivered_cnt: PDUStatCounts,
-    spdus_rx_ok_cnt: u16,
-    spdus_missed_cnt: u16,
+    pub mpdus_rx_ok_cnt: PDUStatCounts,
+    pub mpdus_rx_err_cnt: PDUStatCounts,
+    pub mpdus_tx_cnt: PDUStatCounts,
+    pub mpdus_delivered_cnt: PDUStatCounts,
+    pub spdus_rx_ok_cnt: u16,
+    pub spdus_missed_cnt: u16,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct PDUStatCounts {
     #[serde(rename = "300bps")]
-    three_hundred_bps: u8,
+    pub three_hundred_bps: u8,
     #[serde(rename = "600bps")]
-    six_hundred_bps: u8,
+    pub six_hundred_bps: u8,
     #[serde(rename = "1200bps")]
-    twelve_hundred_bps: u8,
+    pub twelve_hundred_bps: u8,
     #[serde(rename = "1800bps")]
-    eighteen_hundred_bps: u8,
+    pub eighteen_hundred_bps: u8,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUHfnPduDisabledCount {
-    this_leg: u16,
-    prev_leg: u16,
+    pub this_leg: u16,
+    pub prev_leg: u16,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUHfnPduCount {
-    cur_leg: u16,
-    prev_leg: u16,
+    pub cur_leg: u16,
+    pub prev_leg: u16,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct Position {
-    lat: f64,
-    lon: f64,
+    pub lat: f64,
+    pub lon: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct UTCTime {
-    hour: u8,
-    min: u8,
+    pub hour: u8,
+    pub min: u8,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sec: Option<u8>,
+    pub sec: Option<u8>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct LPDUFreqData {
-    gs: SPDUorLPDUSource,
-    listening_on_freqs: Vec<FreqId>,
-    heard_on_freqs: Vec<FreqId>,
+    pub gs: SPDUorLPDUSource,
+    pub listening_on_freqs: Vec<FreqId>,
+    pub heard_on_freqs: Vec<FreqId>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct FreqId {
-    id: u16,
+    pub id: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
-    freq: Option<f32>,
+    pub freq: Option<f32>,
 }
\ No newline at end of file
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::hfdl::HfdlMessage;
+
+/// Per-frequency utilisation of HFDL traffic.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct HfdlFrequencyStats {
+    pub messages: u64,
+    pub uplinks: u64,
+    pub downlinks: u64,
+    pub by_slot: BTreeMap<String, u64>,
+    pub by_bit_rate: BTreeMap<u16, u64>,
+}
+
+/// Reception counts for a single HFDL ground station.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct HfdlGroundStationStats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub messages: u64,
+    pub frequencies: BTreeSet<u64>,
+}
+
+/// Collects HFDL slot, frequency, bit rate and ground station statistics, exportable as JSON.
+///
+/// This covers the reports dumphfdl users otherwise build with `jq` pipelines.
+/// ```
+/// use acars_vdlm2_parser::hfdl::{HfdlMessage, NewHfdlMessage};
+/// use acars_vdlm2_parser::hfdl_analytics::HfdlAnalytics;
+/// let message: HfdlMessage = r#"{"hfdl":{"freq":6661000,"bit_rate":300,"slot":"S","spdu":{"err":false,"src":{"type":"Ground station","id":17,"name":"Canarias, Spain"},"spdu_version":0,"rls":false,"iso":false,"change_note":"None","frame_index":2395,"frame_offset":8,"min_priority":0,"systable_version":51,"gs_status":[]}}}"#.to_hfdl().unwrap();
+/// let mut analytics: HfdlAnalytics = HfdlAnalytics::default();
+/// analytics.record(&message);
+/// assert_eq!(analytics.by_frequency[&6661000].uplinks, 1);
+/// assert_eq!(analytics.by_ground_station[&17].name.as_deref(), Some("Canarias, Spain"));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct HfdlAnalytics {
+    pub messages: u64,
+    pub by_bit_rate: BTreeMap<u16, u64>,
+    pub by_frequency: BTreeMap<u64, HfdlFrequencyStats>,
+    pub by_ground_station: BTreeMap<u16, HfdlGroundStationStats>,
+}
+
+impl HfdlAnalytics {
+    /// Records a single `HfdlMessage`.
+    pub fn record(&mut self, message: &HfdlMessage) {
+        let body = &message.hfdl;
+        self.messages += 1;
+        *self.by_bit_rate.entry(body.bit_rate).or_default() += 1;
+        let frequency: &mut HfdlFrequencyStats = self.by_frequency.entry(body.freq.as_hz()).or_default();
+        frequency.messages += 1;
+        *frequency.by_slot.entry(body.slot.clone()).or_default() += 1;
+        *frequency.by_bit_rate.entry(body.bit_rate).or_default() += 1;
+        match message.is_uplink() {
+            Some(true) => frequency.uplinks += 1,
+            Some(false) => frequency.downlinks += 1,
+            None => {}
+        }
+        if let Some(id) = message.get_ground_station_id() {
+            let ground_station: &mut HfdlGroundStationStats = self.by_ground_station.entry(id).or_default();
+            ground_station.messages += 1;
+            ground_station.frequencies.insert(body.freq.as_hz());
+            if ground_station.name.is_none() {
+                ground_station.name = message.get_ground_station_name().map(|name| name.to_string());
+            }
+        }
+    }
+
+    /// Records the message if it is an `HfdlMessage`, other message families are ignored.
+    #[allow(irrefutable_let_patterns)]
+    pub fn record_message(&mut self, message: &AcarsVdlm2Message) {
+        if let AcarsVdlm2Message::HfdlMessage(hfdl) = message {
+            self.record(hfdl);
+        }
+    }
+
+    /// Fraction of the messages on a frequency that were received in the provided slot, between `0.0` and `1.0`.
+    pub fn slot_utilisation(&self, frequency: u64, slot: &str) -> Option<f64> {
+        let stats: &HfdlFrequencyStats = self.by_frequency.get(&frequency)?;
+        let in_slot: u64 = stats.by_slot.get(slot).copied().unwrap_or_default();
+        match stats.messages {
+            0 => None,
+            messages => Some(in_slot as f64 / messages as f64),
+        }
+    }
+
+    /// Converts the collected statistics to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+}
@@ -0,0 +1,395 @@
+//! Parsing of the HFDL system table file dumphfdl reads with `--system-table`, and a ground station database built
+//! from it.
+//!
+//! The file is in libconfig format and lists every ground station with its name, location and frequencies in kHz:
+//!
+//! ```text
+//! version = 51;
+//! stations = (
+//!     {
+//!         id = 1;
+//!         name = "San Francisco, California";
+//!         lat = 37.6156;
+//!         lon = -122.4048;
+//!         frequencies = ( 21934.0, 17919.0, 13276.0, 11327.0, 10081.0, 8927.0, 6559.0, 5508.0 );
+//!     }
+//! );
+//! ```
+//!
+//! Ground stations broadcast updates to the table, which dumphfdl writes out as a `systable_complete` block, so a
+//! database loaded from a file can be kept current with `HfdlGroundStationDatabase::update_from_message` without
+//! waiting for a new file.
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use crate::MessageResult;
+use crate::frequency::Frequency;
+use crate::hfdl::{HfdlMessage, SysTable};
+
+/// A dumphfdl system table file.
+/// ```
+/// use acars_vdlm2_parser::hfdl_systable::HfdlSystable;
+/// let systable: HfdlSystable = r#"
+///     version = 51;   # comments in any of the libconfig styles are skipped
+///     stations = ( { id = 1; name = "San Francisco, California"; lat = 37.6156; lon = -122.4048;
+///                    frequencies = ( 21934.0, 17919.0 ); } );
+/// "#.parse().unwrap();
+/// assert_eq!(systable.version, 51);
+/// assert_eq!(systable.stations[0].name, "San Francisco, California");
+/// assert_eq!(systable.stations[0].frequencies, vec![21934.0, 17919.0]);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HfdlSystable {
+    pub version: u16,
+    pub stations: Vec<HfdlSystableStation>,
+}
+
+/// A ground station entry of an `HfdlSystable`. Settings other than these are ignored.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HfdlSystableStation {
+    pub id: u16,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Frequencies in kHz, in the order the ground station numbers them.
+    pub frequencies: Vec<f64>,
+}
+
+/// Parses a system table file.
+impl FromStr for HfdlSystable {
+    type Err = serde_json::Error;
+
+    fn from_str(input: &str) -> MessageResult<Self> {
+        serde_json::from_value(parse_libconfig(input)?)
+    }
+}
+
+impl HfdlSystable {
+    /// Reads and parses a system table file.
+    pub fn from_path(path: impl AsRef<Path>) -> MessageResult<Self> {
+        std::fs::read_to_string(path).map_err(serde_json::Error::io)?.parse()
+    }
+}
+
+/// Parses libconfig text into a JSON object: groups become objects, lists and arrays become arrays, and scalars become
+/// strings, numbers and booleans. `@include` directives are not supported.
+fn parse_libconfig(input: &str) -> MessageResult<Value> {
+    let mut parser: LibconfigParser = LibconfigParser { input: input.as_bytes(), position: 0 };
+    let settings: Map<String, Value> = parser.settings(None)?;
+    Ok(Value::Object(settings))
+}
+
+/// A recursive descent parser over the bytes of a libconfig file.
+struct LibconfigParser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl LibconfigParser<'_> {
+    /// Returns an error naming the line the parser has reached.
+    fn error(&self, message: &str) -> serde_json::Error {
+        let line: usize = self.input[..self.position].iter().filter(|byte| **byte == b'\n').count() + 1;
+        serde_json::Error::custom(format!("system table line {}: {}", line, message))
+    }
+
+    /// Skips whitespace and `#`, `//` and `/* */` comments, returning the next byte.
+    fn peek(&mut self) -> MessageResult<Option<u8>> {
+        loop {
+            let rest: &[u8] = &self.input[self.position..];
+            match rest {
+                [byte, ..] if byte.is_ascii_whitespace() => self.position += 1,
+                [b'#', ..] | [b'/', b'/', ..] => {
+                    self.position += rest.iter().position(|byte| *byte == b'\n').unwrap_or(rest.len());
+                }
+                [b'/', b'*', ..] => match rest.windows(2).position(|window| window == b"*/") {
+                    Some(end) => self.position += end + 2,
+                    None => return Err(self.error("unterminated comment")),
+                },
+                [byte, ..] => return Ok(Some(*byte)),
+                [] => return Ok(None),
+            }
+        }
+    }
+
+    /// Consumes the next byte if it is `expected`.
+    fn accept(&mut self, expected: u8) -> MessageResult<bool> {
+        let found: bool = self.peek()? == Some(expected);
+        if found {
+            self.position += 1;
+        }
+        Ok(found)
+    }
+
+    /// Parses `name = value;` settings until `end`, or until the end of the input for the top level.
+    fn settings(&mut self, end: Option<u8>) -> MessageResult<Map<String, Value>> {
+        let mut settings: Map<String, Value> = Map::new();
+        loop {
+            match self.peek()? {
+                None if end.is_none() => return Ok(settings),
+                None => return Err(self.error("unexpected end of file")),
+                Some(byte) if Some(byte) == end => {
+                    self.position += 1;
+                    return Ok(settings);
+                }
+                Some(b'@') => return Err(self.error("@include is not supported")),
+                Some(_) => {}
+            }
+            let start: usize = self.position;
+            while self.input.get(self.position).is_some_and(|byte| byte.is_ascii_alphanumeric() || b"_-*".contains(byte)) {
+                self.position += 1;
+            }
+            if start == self.position {
+                return Err(self.error("expected a setting name"));
+            }
+            let name: String = String::from_utf8_lossy(&self.input[start..self.position]).into_owned();
+            if !self.accept(b'=')? && !self.accept(b':')? {
+                return Err(self.error(&format!("expected = after {}", name)));
+            }
+            let value: Value = self.value()?;
+            if !self.accept(b';')? {
+                self.accept(b',')?;
+            }
+            settings.insert(name, value);
+        }
+    }
+
+    /// Parses the values of a list or array until `end`.
+    fn values(&mut self, end: u8) -> MessageResult<Vec<Value>> {
+        let mut values: Vec<Value> = Vec::new();
+        if self.accept(end)? {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.value()?);
+            if self.accept(end)? {
+                return Ok(values);
+            }
+            if !self.accept(b',')? {
+                return Err(self.error(&format!("expected , or {}", end as char)));
+            }
+        }
+    }
+
+    /// Parses a group, list, array, string or scalar value.
+    fn value(&mut self) -> MessageResult<Value> {
+        match self.peek()? {
+            Some(b'{') => {
+                self.position += 1;
+                Ok(Value::Object(self.settings(Some(b'}'))?))
+            }
+            Some(b'(') => {
+                self.position += 1;
+                Ok(Value::Array(self.values(b')')?))
+            }
+            Some(b'[') => {
+                self.position += 1;
+                Ok(Value::Array(self.values(b']')?))
+            }
+            Some(b'"') => {
+                // Adjacent strings are joined, as libconfig does.
+                let mut text: String = String::new();
+                while self.peek()? == Some(b'"') {
+                    text.push_str(&self.string()?);
+                }
+                Ok(Value::String(text))
+            }
+            Some(_) => self.scalar(),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Parses a quoted string, with the escapes libconfig supports.
+    fn string(&mut self) -> MessageResult<String> {
+        self.position += 1;
+        let mut bytes: Vec<u8> = Vec::new();
+        loop {
+            let byte: u8 = *self.input.get(self.position).ok_or_else(|| self.error("unterminated string"))?;
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escaped: u8 = *self.input.get(self.position).ok_or_else(|| self.error("unterminated string"))?;
+                    self.position += 1;
+                    match escaped {
+                        b'n' => bytes.push(b'\n'),
+                        b't' => bytes.push(b'\t'),
+                        b'r' => bytes.push(b'\r'),
+                        b'f' => bytes.push(0x0C),
+                        b'x' => {
+                            let hex: &[u8] = self.input.get(self.position..self.position + 2).ok_or_else(|| self.error("invalid \\x escape"))?;
+                            let value: u8 = std::str::from_utf8(hex).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                                .ok_or_else(|| self.error("invalid \\x escape"))?;
+                            bytes.push(value);
+                            self.position += 2;
+                        }
+                        other => bytes.push(other),
+                    }
+                }
+                other => bytes.push(other),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("string is not valid UTF-8"))
+    }
+
+    /// Parses a boolean, an integer in decimal or hex with an optional `L` suffix, or a float.
+    fn scalar(&mut self) -> MessageResult<Value> {
+        let start: usize = self.position;
+        while self.input.get(self.position).is_some_and(|byte| byte.is_ascii_alphanumeric() || b"+-.".contains(byte)) {
+            self.position += 1;
+        }
+        let token: &str = std::str::from_utf8(&self.input[start..self.position]).map_err(|_| self.error("invalid value"))?;
+        let number: &str = token.trim_end_matches(['L', 'l']);
+        let hex: Option<&str> = number.strip_prefix("0x").or_else(|| number.strip_prefix("0X"));
+        match (token.to_ascii_lowercase().as_str(), hex) {
+            ("true", _) => Ok(Value::Bool(true)),
+            ("false", _) => Ok(Value::Bool(false)),
+            (_, Some(hex)) => i64::from_str_radix(hex, 16).map(Value::from).map_err(|_| self.error(&format!("invalid value {}", token))),
+            _ => match number.parse::<i64>() {
+                Ok(integer) => Ok(Value::from(integer)),
+                Err(_) => number.parse::<f64>().ok().and_then(Number::from_f64).map(Value::Number)
+                    .ok_or_else(|| self.error(&format!("invalid value {}", token))),
+            },
+        }
+    }
+}
+
+/// A ground station in an `HfdlGroundStationDatabase`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HfdlGroundStation {
+    pub id: u16,
+    /// Name from the system table file, empty for a station only known from a broadcast table.
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Frequencies in the order the ground station numbers them, so a frequency id in a squitter indexes this list.
+    pub frequencies: Vec<Frequency>,
+}
+
+/// The HFDL ground stations keyed by id, with their names, locations and frequencies, for looking up the ground station
+/// taking part in an `HfdlMessage`.
+/// ```
+/// use acars_vdlm2_parser::frequency::Frequency;
+/// use acars_vdlm2_parser::hfdl_systable::HfdlGroundStationDatabase;
+/// let database: HfdlGroundStationDatabase = HfdlGroundStationDatabase::from_systable_str(r#"version = 51;
+///     stations = ( { id = 17; name = "Canarias, Spain"; lat = 27.9; lon = -15.4; frequencies = ( 21955.0, 8942.0 ); } );"#).unwrap();
+/// assert_eq!(database.get(17).map(|station| station.name.as_str()), Some("Canarias, Spain"));
+/// assert_eq!(database.stations_on(Frequency::from_hz(8_942_000)).map(|station| station.id).collect::<Vec<u16>>(), vec![17]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HfdlGroundStationDatabase {
+    version: u16,
+    stations: BTreeMap<u16, HfdlGroundStation>,
+}
+
+impl HfdlGroundStationDatabase {
+    /// Creates a new, empty `HfdlGroundStationDatabase`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a database from a parsed system table file.
+    pub fn from_systable(systable: &HfdlSystable) -> Self {
+        let mut database: HfdlGroundStationDatabase = HfdlGroundStationDatabase { version: systable.version, ..Default::default() };
+        for station in &systable.stations {
+            database.insert(HfdlGroundStation {
+                id: station.id,
+                name: station.name.clone(),
+                lat: station.lat,
+                lon: station.lon,
+                frequencies: station.frequencies.iter().map(|khz| Frequency::from_hz((khz * 1_000.0).round() as u64)).collect(),
+            });
+        }
+        database
+    }
+
+    /// Builds a database from the text of a system table file.
+    pub fn from_systable_str(input: &str) -> MessageResult<Self> {
+        Ok(Self::from_systable(&input.parse()?))
+    }
+
+    /// Builds a database from a system table file.
+    pub fn from_systable_path(path: impl AsRef<Path>) -> MessageResult<Self> {
+        Ok(Self::from_systable(&HfdlSystable::from_path(path)?))
+    }
+
+    /// Returns the version of the system table the database was last built or updated from.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// Adds or replaces a ground station.
+    pub fn insert(&mut self, station: HfdlGroundStation) {
+        self.stations.insert(station.id, station);
+    }
+
+    /// Returns the ground station with the id, if the database has it.
+    pub fn get(&self, id: u16) -> Option<&HfdlGroundStation> {
+        self.stations.get(&id)
+    }
+
+    /// Returns the ground stations in id order.
+    pub fn iter(&self) -> impl Iterator<Item = &HfdlGroundStation> {
+        self.stations.values()
+    }
+
+    /// Returns the ground stations that use a frequency. Some frequencies are shared by stations far enough apart not
+    /// to interfere.
+    pub fn stations_on(&self, frequency: Frequency) -> impl Iterator<Item = &HfdlGroundStation> {
+        self.stations.values().filter(move |station| station.frequencies.contains(&frequency))
+    }
+
+    /// Returns the ground station taking part in the frame, by its id, or by the frequency the frame was heard on when
+    /// the frame names no ground station and only one station uses that frequency.
+    pub fn get_for_message(&self, message: &HfdlMessage) -> Option<&HfdlGroundStation> {
+        if let Some(id) = message.get_ground_station_id() {
+            return self.get(id);
+        }
+        let mut stations = self.stations_on(message.hfdl.freq);
+        match (stations.next(), stations.next()) {
+            (Some(station), None) => Some(station),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of ground stations in the database.
+    pub fn len(&self) -> usize {
+        self.stations.len()
+    }
+
+    /// Returns true if the database has no ground stations.
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty()
+    }
+
+    /// Applies the system table broadcast in the frame, if it has a complete one that is newer than the database,
+    /// returning true if it was applied.
+    ///
+    /// Locations and frequencies are replaced from the broadcast table, names are kept from the file, and stations no
+    /// longer in the table are removed.
+    pub fn update_from_message(&mut self, message: &HfdlMessage) -> bool {
+        let systable: Option<&SysTable> = message.hfdl.lpdu.as_ref()
+            .and_then(|lpdu| lpdu.hfnpdu.as_ref())
+            .and_then(|hfnpdu| hfnpdu.systable_complete.as_ref())
+            .filter(|systable| !systable.err && u16::from(systable.version) > self.version);
+        let Some(systable) = systable else {
+            return false;
+        };
+        let mut stations: BTreeMap<u16, HfdlGroundStation> = BTreeMap::new();
+        for broadcast in &systable.ground_stations {
+            let name: String = self.get(broadcast.id).map(|station| station.name.clone()).unwrap_or_default();
+            stations.insert(broadcast.id, HfdlGroundStation {
+                id: broadcast.id,
+                name,
+                lat: broadcast.location.lat,
+                lon: broadcast.location.lon,
+                frequencies: broadcast.freqs.iter().map(|freq| Frequency::from_hz(freq.freq.round() as u64)).collect(),
+            });
+        }
+        self.version = u16::from(systable.version);
+        self.stations = stations;
+        true
+    }
+}
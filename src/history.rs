@@ -0,0 +1,181 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageFamily};
+use crate::registrations::normalise_registration;
+use crate::timestamp::MessageTimestamp;
+
+/// How an aircraft is looked up in a `HistoryBuffer`.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AircraftKey {
+    Icao(u32),
+    /// Registration without the leading dots ACARS pads it with, in uppercase.
+    Registration(String),
+}
+
+impl AircraftKey {
+    /// Creates a registration key, normalising it the same way messages are.
+    pub fn registration(registration: &str) -> Self {
+        Self::Registration(normalise_registration(registration))
+    }
+
+    /// Returns the keys the message can be found by: its ICAO address and its registration, where it has them.
+    pub fn of_message(message: &AcarsVdlm2Message) -> Vec<Self> {
+        let mut keys: Vec<Self> = Vec::with_capacity(2);
+        keys.extend(message.get_icao().map(Self::Icao));
+        keys.extend(message.get_registration()
+            .map(normalise_registration)
+            .filter(|registration| !registration.is_empty())
+            .map(Self::Registration));
+        keys
+    }
+}
+
+impl fmt::Display for AircraftKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AircraftKey::Icao(icao) => write!(f, "{:06X}", icao),
+            AircraftKey::Registration(registration) => f.write_str(registration),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    time: f64,
+    message: Arc<AcarsVdlm2Message>,
+}
+
+type AircraftHistory = HashMap<MessageFamily, VecDeque<HistoryEntry>>;
+
+/// Keeps the most recent messages from each aircraft, for "recent messages for this aircraft" views.
+///
+/// Messages are kept per aircraft and per family, so a busy VDLM2 aircraft does not push its HFDL messages out. Each
+/// message is found by both its ICAO address and its registration, sharing one copy of the message. Messages are aged
+/// by their own time, or the time they were pushed if they do not have one, and only removed by age when `expire` is
+/// called. Messages without an ICAO address or registration are not kept.
+/// ```
+/// use std::sync::Arc;
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+/// use acars_vdlm2_parser::history::{AircraftKey, HistoryBuffer};
+/// let mut history: HistoryBuffer = HistoryBuffer::new(10).family_capacity(MessageFamily::Acars, 2);
+/// for (timestamp, label) in [(100, "H1"), (110, "Q0"), (120, "5Z")] {
+///     let input: String = format!(r#"{{"freq":131.55,"timestamp":{},"tail":".VH-ABC","icao":8131124,"label":"{}"}}"#, timestamp, label);
+///     history.push(input.decode_message().unwrap());
+/// }
+/// let recent: Vec<Arc<AcarsVdlm2Message>> = history.get(&AircraftKey::registration("vh-abc"));
+/// assert_eq!(recent.iter().map(|message| message.get_label().unwrap()).collect::<Vec<&str>>(), vec!["Q0", "5Z"]);
+/// assert_eq!(history.get(&AircraftKey::Icao(0x7C1234)).len(), 2);
+/// assert_eq!(history.expire(125.0 + 600.0), 4);
+/// assert!(history.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HistoryBuffer {
+    capacity: usize,
+    family_capacities: HashMap<MessageFamily, usize>,
+    max_age_secs: f64,
+    aircraft: HashMap<AircraftKey, AircraftHistory>,
+}
+
+impl HistoryBuffer {
+    /// Messages older than this are removed by `expire` unless the age is changed with `max_age_secs`.
+    pub const DEFAULT_MAX_AGE_SECS: f64 = 600.0;
+
+    /// Creates a `HistoryBuffer` keeping the last `capacity` messages of each family for every aircraft.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, family_capacities: HashMap::new(), max_age_secs: Self::DEFAULT_MAX_AGE_SECS, aircraft: HashMap::new() }
+    }
+
+    /// Sets how many messages of the family are kept for every aircraft, in place of the capacity given to `new`.
+    pub fn family_capacity(mut self, family: MessageFamily, capacity: usize) -> Self {
+        self.family_capacities.insert(family, capacity);
+        self
+    }
+
+    /// Sets how old a message can get before `expire` removes it.
+    pub fn max_age_secs(mut self, max_age_secs: f64) -> Self {
+        self.max_age_secs = max_age_secs;
+        self
+    }
+
+    /// Returns the number of messages kept for every aircraft for the family.
+    pub fn capacity_of(&self, family: MessageFamily) -> usize {
+        self.family_capacities.get(&family).copied().unwrap_or(self.capacity)
+    }
+
+    /// Adds a message, removing the oldest of its family for the aircraft if there are too many.
+    ///
+    /// Returns false if the message has no ICAO address or registration, so was not kept.
+    pub fn push(&mut self, message: AcarsVdlm2Message) -> bool {
+        let keys: Vec<AircraftKey> = AircraftKey::of_message(&message);
+        let family: MessageFamily = message.get_family();
+        let capacity: usize = self.capacity_of(family);
+        if keys.is_empty() || capacity == 0 {
+            return false;
+        }
+        let time: f64 = message.get_timestamp().unwrap_or_else(MessageTimestamp::ingested_now).seconds;
+        let message: Arc<AcarsVdlm2Message> = Arc::new(message);
+        for key in keys {
+            let entries: &mut VecDeque<HistoryEntry> = self.aircraft.entry(key).or_default().entry(family).or_default();
+            while entries.len() >= capacity {
+                entries.pop_front();
+            }
+            entries.push_back(HistoryEntry { time, message: Arc::clone(&message) });
+        }
+        true
+    }
+
+    /// Returns the messages kept for the aircraft across every family, oldest first.
+    pub fn get(&self, key: &AircraftKey) -> Vec<Arc<AcarsVdlm2Message>> {
+        let Some(history) = self.aircraft.get(key) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<&HistoryEntry> = history.values().flatten().collect();
+        entries.sort_by(|first, second| first.time.total_cmp(&second.time));
+        entries.into_iter().map(|entry| Arc::clone(&entry.message)).collect()
+    }
+
+    /// Returns the messages of one family kept for the aircraft, oldest first.
+    pub fn get_family(&self, key: &AircraftKey, family: MessageFamily) -> Vec<Arc<AcarsVdlm2Message>> {
+        self.aircraft.get(key)
+            .and_then(|history| history.get(&family))
+            .map(|entries| entries.iter().map(|entry| Arc::clone(&entry.message)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns every key with messages kept.
+    pub fn keys(&self) -> impl Iterator<Item = &AircraftKey> {
+        self.aircraft.keys()
+    }
+
+    /// Returns the number of keys with messages kept, counting an aircraft seen with both an address and a
+    /// registration twice.
+    pub fn len(&self) -> usize {
+        self.aircraft.len()
+    }
+
+    /// Returns true if no messages are kept.
+    pub fn is_empty(&self) -> bool {
+        self.aircraft.is_empty()
+    }
+
+    /// Removes the messages older than the maximum age at the provided time, and any aircraft left without messages.
+    ///
+    /// Returns the number of entries removed, counting a message kept under both an address and a registration twice.
+    pub fn expire(&mut self, now: f64) -> usize {
+        let cutoff: f64 = now - self.max_age_secs;
+        let mut removed: usize = 0;
+        self.aircraft.retain(|_, history| {
+            history.retain(|_, entries| {
+                let before: usize = entries.len();
+                entries.retain(|entry| entry.time >= cutoff);
+                removed += before - entries.len();
+                !entries.is_empty()
+            });
+            !history.is_empty()
+        });
+        removed
+    }
+}
@@ -0,0 +1,50 @@
+use serde::Serialize;
+use crate::icao_table::ICAO_ALLOCATIONS;
+
+/// A block of ICAO 24 bit aircraft addresses allocated to a state or to ICAO itself.
+///
+/// The table is generated from `data/icao_allocations.csv` by `update_icao_allocations.sh`.
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct IcaoAllocation {
+    /// First address in the block, inclusive.
+    pub start: u32,
+    /// Last address in the block, inclusive.
+    pub end: u32,
+    /// The state, or ICAO use, the block is allocated to.
+    pub country: &'static str,
+}
+
+impl IcaoAllocation {
+    /// Returns true if the address falls inside this block.
+    pub fn contains(&self, address: u32) -> bool {
+        (self.start..=self.end).contains(&address)
+    }
+}
+
+/// Looks up the allocation block for an ICAO 24 bit address.
+///
+/// Returns `None` for addresses outside every allocated block, which are commonly the result of a garbled frame.
+/// ```
+/// use acars_vdlm2_parser::icao::{icao_allocation, IcaoAllocation};
+/// let allocation: &IcaoAllocation = icao_allocation(0x7C1234).unwrap();
+/// assert_eq!(allocation.country, "Australia");
+/// assert!(icao_allocation(0xFFFFFF).is_none());
+/// ```
+pub fn icao_allocation(address: u32) -> Option<&'static IcaoAllocation> {
+    let index: usize = ICAO_ALLOCATIONS.partition_point(|allocation| allocation.end < address);
+    ICAO_ALLOCATIONS.get(index)
+        .filter(|allocation| allocation.contains(address))
+}
+
+/// Returns true if the address falls inside an allocated block.
+pub fn is_allocated(address: u32) -> bool {
+    icao_allocation(address).is_some()
+}
+
+/// Parses a 6 character hex ICAO address as output by the decoders, such as `7C1234`.
+pub fn parse_icao_hex(address: &str) -> Option<u32> {
+    match address.len() == 6 && address.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        true => u32::from_str_radix(address, 16).ok(),
+        false => None,
+    }
+}
@@ -0,0 +1,197 @@
+// Generated by update_icao_allocations.sh from data/icao_allocations.csv, do not edit by hand.
+
+use crate::icao::IcaoAllocation;
+
+/// Allocated ICAO 24 bit address blocks, sorted by start address.
+pub(crate) const ICAO_ALLOCATIONS: &[IcaoAllocation] = &[
+    IcaoAllocation { start: 0x004000, end: 0x0043FF, country: "Zimbabwe" },
+    IcaoAllocation { start: 0x006000, end: 0x006FFF, country: "Mozambique" },
+    IcaoAllocation { start: 0x008000, end: 0x00FFFF, country: "South Africa" },
+    IcaoAllocation { start: 0x010000, end: 0x017FFF, country: "Egypt" },
+    IcaoAllocation { start: 0x018000, end: 0x01FFFF, country: "Libya" },
+    IcaoAllocation { start: 0x020000, end: 0x027FFF, country: "Morocco" },
+    IcaoAllocation { start: 0x028000, end: 0x02FFFF, country: "Tunisia" },
+    IcaoAllocation { start: 0x030000, end: 0x0303FF, country: "Botswana" },
+    IcaoAllocation { start: 0x032000, end: 0x032FFF, country: "Burundi" },
+    IcaoAllocation { start: 0x034000, end: 0x034FFF, country: "Cameroon" },
+    IcaoAllocation { start: 0x035000, end: 0x0353FF, country: "Comoros" },
+    IcaoAllocation { start: 0x036000, end: 0x036FFF, country: "Congo" },
+    IcaoAllocation { start: 0x038000, end: 0x038FFF, country: "Cote d'Ivoire" },
+    IcaoAllocation { start: 0x03E000, end: 0x03EFFF, country: "Gabon" },
+    IcaoAllocation { start: 0x040000, end: 0x040FFF, country: "Ethiopia" },
+    IcaoAllocation { start: 0x042000, end: 0x042FFF, country: "Equatorial Guinea" },
+    IcaoAllocation { start: 0x044000, end: 0x044FFF, country: "Ghana" },
+    IcaoAllocation { start: 0x046000, end: 0x046FFF, country: "Guinea" },
+    IcaoAllocation { start: 0x048000, end: 0x0483FF, country: "Guinea-Bissau" },
+    IcaoAllocation { start: 0x04A000, end: 0x04A3FF, country: "Lesotho" },
+    IcaoAllocation { start: 0x04C000, end: 0x04CFFF, country: "Kenya" },
+    IcaoAllocation { start: 0x050000, end: 0x050FFF, country: "Liberia" },
+    IcaoAllocation { start: 0x054000, end: 0x054FFF, country: "Madagascar" },
+    IcaoAllocation { start: 0x058000, end: 0x058FFF, country: "Malawi" },
+    IcaoAllocation { start: 0x05A000, end: 0x05A3FF, country: "Maldives" },
+    IcaoAllocation { start: 0x05C000, end: 0x05CFFF, country: "Mali" },
+    IcaoAllocation { start: 0x05E000, end: 0x05E3FF, country: "Mauritania" },
+    IcaoAllocation { start: 0x060000, end: 0x0603FF, country: "Mauritius" },
+    IcaoAllocation { start: 0x062000, end: 0x062FFF, country: "Niger" },
+    IcaoAllocation { start: 0x064000, end: 0x064FFF, country: "Nigeria" },
+    IcaoAllocation { start: 0x068000, end: 0x068FFF, country: "Uganda" },
+    IcaoAllocation { start: 0x06A000, end: 0x06A3FF, country: "Qatar" },
+    IcaoAllocation { start: 0x06C000, end: 0x06CFFF, country: "Central African Republic" },
+    IcaoAllocation { start: 0x06E000, end: 0x06EFFF, country: "Rwanda" },
+    IcaoAllocation { start: 0x070000, end: 0x070FFF, country: "Senegal" },
+    IcaoAllocation { start: 0x074000, end: 0x0743FF, country: "Seychelles" },
+    IcaoAllocation { start: 0x076000, end: 0x0763FF, country: "Sierra Leone" },
+    IcaoAllocation { start: 0x078000, end: 0x078FFF, country: "Somalia" },
+    IcaoAllocation { start: 0x07A000, end: 0x07A3FF, country: "Eswatini" },
+    IcaoAllocation { start: 0x07C000, end: 0x07CFFF, country: "Sudan" },
+    IcaoAllocation { start: 0x080000, end: 0x080FFF, country: "Tanzania" },
+    IcaoAllocation { start: 0x084000, end: 0x084FFF, country: "Chad" },
+    IcaoAllocation { start: 0x088000, end: 0x088FFF, country: "Togo" },
+    IcaoAllocation { start: 0x08A000, end: 0x08AFFF, country: "Zambia" },
+    IcaoAllocation { start: 0x08C000, end: 0x08CFFF, country: "Democratic Republic of the Congo" },
+    IcaoAllocation { start: 0x090000, end: 0x090FFF, country: "Angola" },
+    IcaoAllocation { start: 0x094000, end: 0x0943FF, country: "Benin" },
+    IcaoAllocation { start: 0x096000, end: 0x0963FF, country: "Cabo Verde" },
+    IcaoAllocation { start: 0x098000, end: 0x0983FF, country: "Djibouti" },
+    IcaoAllocation { start: 0x09A000, end: 0x09AFFF, country: "Gambia" },
+    IcaoAllocation { start: 0x09C000, end: 0x09CFFF, country: "Burkina Faso" },
+    IcaoAllocation { start: 0x09E000, end: 0x09E3FF, country: "Sao Tome and Principe" },
+    IcaoAllocation { start: 0x0A0000, end: 0x0A7FFF, country: "Algeria" },
+    IcaoAllocation { start: 0x0A8000, end: 0x0A8FFF, country: "Bahamas" },
+    IcaoAllocation { start: 0x0AA000, end: 0x0AA3FF, country: "Barbados" },
+    IcaoAllocation { start: 0x0AB000, end: 0x0AB3FF, country: "Belize" },
+    IcaoAllocation { start: 0x0AC000, end: 0x0ACFFF, country: "Colombia" },
+    IcaoAllocation { start: 0x0AE000, end: 0x0AEFFF, country: "Costa Rica" },
+    IcaoAllocation { start: 0x0B0000, end: 0x0B0FFF, country: "Cuba" },
+    IcaoAllocation { start: 0x0B2000, end: 0x0B2FFF, country: "El Salvador" },
+    IcaoAllocation { start: 0x0B4000, end: 0x0B4FFF, country: "Guatemala" },
+    IcaoAllocation { start: 0x0B6000, end: 0x0B6FFF, country: "Guyana" },
+    IcaoAllocation { start: 0x0B8000, end: 0x0B8FFF, country: "Haiti" },
+    IcaoAllocation { start: 0x0BA000, end: 0x0BAFFF, country: "Honduras" },
+    IcaoAllocation { start: 0x0BC000, end: 0x0BC3FF, country: "Saint Vincent and the Grenadines" },
+    IcaoAllocation { start: 0x0BE000, end: 0x0BEFFF, country: "Jamaica" },
+    IcaoAllocation { start: 0x0C0000, end: 0x0C0FFF, country: "Nicaragua" },
+    IcaoAllocation { start: 0x0C2000, end: 0x0C2FFF, country: "Panama" },
+    IcaoAllocation { start: 0x0C4000, end: 0x0C4FFF, country: "Dominican Republic" },
+    IcaoAllocation { start: 0x0C6000, end: 0x0C6FFF, country: "Trinidad and Tobago" },
+    IcaoAllocation { start: 0x0C8000, end: 0x0C8FFF, country: "Suriname" },
+    IcaoAllocation { start: 0x0CA000, end: 0x0CA3FF, country: "Antigua and Barbuda" },
+    IcaoAllocation { start: 0x0CC000, end: 0x0CC3FF, country: "Grenada" },
+    IcaoAllocation { start: 0x0D0000, end: 0x0D7FFF, country: "Mexico" },
+    IcaoAllocation { start: 0x0D8000, end: 0x0DFFFF, country: "Venezuela" },
+    IcaoAllocation { start: 0x100000, end: 0x1FFFFF, country: "Russian Federation" },
+    IcaoAllocation { start: 0x201000, end: 0x2013FF, country: "Namibia" },
+    IcaoAllocation { start: 0x202000, end: 0x2023FF, country: "Eritrea" },
+    IcaoAllocation { start: 0x300000, end: 0x33FFFF, country: "Italy" },
+    IcaoAllocation { start: 0x340000, end: 0x37FFFF, country: "Spain" },
+    IcaoAllocation { start: 0x380000, end: 0x3BFFFF, country: "France" },
+    IcaoAllocation { start: 0x3C0000, end: 0x3FFFFF, country: "Germany" },
+    IcaoAllocation { start: 0x400000, end: 0x43FFFF, country: "United Kingdom" },
+    IcaoAllocation { start: 0x440000, end: 0x447FFF, country: "Austria" },
+    IcaoAllocation { start: 0x448000, end: 0x44FFFF, country: "Belgium" },
+    IcaoAllocation { start: 0x450000, end: 0x457FFF, country: "Bulgaria" },
+    IcaoAllocation { start: 0x458000, end: 0x45FFFF, country: "Denmark" },
+    IcaoAllocation { start: 0x460000, end: 0x467FFF, country: "Finland" },
+    IcaoAllocation { start: 0x468000, end: 0x46FFFF, country: "Greece" },
+    IcaoAllocation { start: 0x470000, end: 0x477FFF, country: "Hungary" },
+    IcaoAllocation { start: 0x478000, end: 0x47FFFF, country: "Norway" },
+    IcaoAllocation { start: 0x480000, end: 0x487FFF, country: "Netherlands" },
+    IcaoAllocation { start: 0x488000, end: 0x48FFFF, country: "Poland" },
+    IcaoAllocation { start: 0x490000, end: 0x497FFF, country: "Portugal" },
+    IcaoAllocation { start: 0x498000, end: 0x49FFFF, country: "Czech Republic" },
+    IcaoAllocation { start: 0x4A0000, end: 0x4A7FFF, country: "Romania" },
+    IcaoAllocation { start: 0x4A8000, end: 0x4AFFFF, country: "Sweden" },
+    IcaoAllocation { start: 0x4B0000, end: 0x4B7FFF, country: "Switzerland" },
+    IcaoAllocation { start: 0x4B8000, end: 0x4BFFFF, country: "Turkey" },
+    IcaoAllocation { start: 0x4C0000, end: 0x4C7FFF, country: "Serbia" },
+    IcaoAllocation { start: 0x4C8000, end: 0x4C83FF, country: "Cyprus" },
+    IcaoAllocation { start: 0x4CA000, end: 0x4CAFFF, country: "Ireland" },
+    IcaoAllocation { start: 0x4CC000, end: 0x4CCFFF, country: "Iceland" },
+    IcaoAllocation { start: 0x4D0000, end: 0x4D03FF, country: "Luxembourg" },
+    IcaoAllocation { start: 0x4D2000, end: 0x4D2FFF, country: "Malta" },
+    IcaoAllocation { start: 0x4D4000, end: 0x4D43FF, country: "Monaco" },
+    IcaoAllocation { start: 0x500000, end: 0x5003FF, country: "San Marino" },
+    IcaoAllocation { start: 0x501000, end: 0x5013FF, country: "Albania" },
+    IcaoAllocation { start: 0x501C00, end: 0x501FFF, country: "Croatia" },
+    IcaoAllocation { start: 0x502C00, end: 0x502FFF, country: "Latvia" },
+    IcaoAllocation { start: 0x503C00, end: 0x503FFF, country: "Lithuania" },
+    IcaoAllocation { start: 0x504C00, end: 0x504FFF, country: "Moldova" },
+    IcaoAllocation { start: 0x505C00, end: 0x505FFF, country: "Slovakia" },
+    IcaoAllocation { start: 0x506C00, end: 0x506FFF, country: "Slovenia" },
+    IcaoAllocation { start: 0x507C00, end: 0x507FFF, country: "Uzbekistan" },
+    IcaoAllocation { start: 0x508000, end: 0x50FFFF, country: "Ukraine" },
+    IcaoAllocation { start: 0x510000, end: 0x5103FF, country: "Belarus" },
+    IcaoAllocation { start: 0x511000, end: 0x5113FF, country: "Estonia" },
+    IcaoAllocation { start: 0x512000, end: 0x5123FF, country: "North Macedonia" },
+    IcaoAllocation { start: 0x513000, end: 0x5133FF, country: "Bosnia and Herzegovina" },
+    IcaoAllocation { start: 0x514000, end: 0x5143FF, country: "Georgia" },
+    IcaoAllocation { start: 0x515000, end: 0x5153FF, country: "Tajikistan" },
+    IcaoAllocation { start: 0x516000, end: 0x5163FF, country: "Montenegro" },
+    IcaoAllocation { start: 0x600000, end: 0x6003FF, country: "Armenia" },
+    IcaoAllocation { start: 0x600800, end: 0x600BFF, country: "Azerbaijan" },
+    IcaoAllocation { start: 0x601000, end: 0x6013FF, country: "Kyrgyzstan" },
+    IcaoAllocation { start: 0x601800, end: 0x601BFF, country: "Turkmenistan" },
+    IcaoAllocation { start: 0x680000, end: 0x6803FF, country: "Bhutan" },
+    IcaoAllocation { start: 0x681000, end: 0x6813FF, country: "Micronesia" },
+    IcaoAllocation { start: 0x682000, end: 0x6823FF, country: "Mongolia" },
+    IcaoAllocation { start: 0x683000, end: 0x6833FF, country: "Kazakhstan" },
+    IcaoAllocation { start: 0x684000, end: 0x6843FF, country: "Palau" },
+    IcaoAllocation { start: 0x700000, end: 0x700FFF, country: "Afghanistan" },
+    IcaoAllocation { start: 0x702000, end: 0x702FFF, country: "Bangladesh" },
+    IcaoAllocation { start: 0x704000, end: 0x704FFF, country: "Myanmar" },
+    IcaoAllocation { start: 0x706000, end: 0x706FFF, country: "Kuwait" },
+    IcaoAllocation { start: 0x708000, end: 0x708FFF, country: "Laos" },
+    IcaoAllocation { start: 0x70A000, end: 0x70AFFF, country: "Nepal" },
+    IcaoAllocation { start: 0x70C000, end: 0x70C3FF, country: "Oman" },
+    IcaoAllocation { start: 0x70E000, end: 0x70EFFF, country: "Cambodia" },
+    IcaoAllocation { start: 0x710000, end: 0x717FFF, country: "Saudi Arabia" },
+    IcaoAllocation { start: 0x718000, end: 0x71FFFF, country: "South Korea" },
+    IcaoAllocation { start: 0x720000, end: 0x727FFF, country: "North Korea" },
+    IcaoAllocation { start: 0x728000, end: 0x72FFFF, country: "Iraq" },
+    IcaoAllocation { start: 0x730000, end: 0x737FFF, country: "Iran" },
+    IcaoAllocation { start: 0x738000, end: 0x73FFFF, country: "Israel" },
+    IcaoAllocation { start: 0x740000, end: 0x747FFF, country: "Jordan" },
+    IcaoAllocation { start: 0x748000, end: 0x74FFFF, country: "Lebanon" },
+    IcaoAllocation { start: 0x750000, end: 0x757FFF, country: "Malaysia" },
+    IcaoAllocation { start: 0x758000, end: 0x75FFFF, country: "Philippines" },
+    IcaoAllocation { start: 0x760000, end: 0x767FFF, country: "Pakistan" },
+    IcaoAllocation { start: 0x768000, end: 0x76FFFF, country: "Singapore" },
+    IcaoAllocation { start: 0x770000, end: 0x777FFF, country: "Sri Lanka" },
+    IcaoAllocation { start: 0x778000, end: 0x77FFFF, country: "Syria" },
+    IcaoAllocation { start: 0x780000, end: 0x7BFFFF, country: "China" },
+    IcaoAllocation { start: 0x7C0000, end: 0x7FFFFF, country: "Australia" },
+    IcaoAllocation { start: 0x800000, end: 0x83FFFF, country: "India" },
+    IcaoAllocation { start: 0x840000, end: 0x87FFFF, country: "Japan" },
+    IcaoAllocation { start: 0x880000, end: 0x887FFF, country: "Thailand" },
+    IcaoAllocation { start: 0x888000, end: 0x88FFFF, country: "Viet Nam" },
+    IcaoAllocation { start: 0x890000, end: 0x890FFF, country: "Yemen" },
+    IcaoAllocation { start: 0x894000, end: 0x894FFF, country: "Bahrain" },
+    IcaoAllocation { start: 0x895000, end: 0x8953FF, country: "Brunei Darussalam" },
+    IcaoAllocation { start: 0x896000, end: 0x896FFF, country: "United Arab Emirates" },
+    IcaoAllocation { start: 0x897000, end: 0x8973FF, country: "Solomon Islands" },
+    IcaoAllocation { start: 0x898000, end: 0x898FFF, country: "Papua New Guinea" },
+    IcaoAllocation { start: 0x899000, end: 0x8993FF, country: "Taiwan" },
+    IcaoAllocation { start: 0x8A0000, end: 0x8A7FFF, country: "Indonesia" },
+    IcaoAllocation { start: 0x900000, end: 0x9003FF, country: "Marshall Islands" },
+    IcaoAllocation { start: 0x901000, end: 0x9013FF, country: "Cook Islands" },
+    IcaoAllocation { start: 0x902000, end: 0x9023FF, country: "Samoa" },
+    IcaoAllocation { start: 0xA00000, end: 0xAFFFFF, country: "United States" },
+    IcaoAllocation { start: 0xC00000, end: 0xC3FFFF, country: "Canada" },
+    IcaoAllocation { start: 0xC80000, end: 0xC87FFF, country: "New Zealand" },
+    IcaoAllocation { start: 0xC88000, end: 0xC88FFF, country: "Fiji" },
+    IcaoAllocation { start: 0xC8A000, end: 0xC8A3FF, country: "Nauru" },
+    IcaoAllocation { start: 0xC8C000, end: 0xC8C3FF, country: "Saint Lucia" },
+    IcaoAllocation { start: 0xC8D000, end: 0xC8D3FF, country: "Tonga" },
+    IcaoAllocation { start: 0xC8E000, end: 0xC8E3FF, country: "Kiribati" },
+    IcaoAllocation { start: 0xC90000, end: 0xC903FF, country: "Vanuatu" },
+    IcaoAllocation { start: 0xE00000, end: 0xE3FFFF, country: "Argentina" },
+    IcaoAllocation { start: 0xE40000, end: 0xE7FFFF, country: "Brazil" },
+    IcaoAllocation { start: 0xE80000, end: 0xE80FFF, country: "Chile" },
+    IcaoAllocation { start: 0xE84000, end: 0xE84FFF, country: "Ecuador" },
+    IcaoAllocation { start: 0xE88000, end: 0xE88FFF, country: "Paraguay" },
+    IcaoAllocation { start: 0xE8C000, end: 0xE8CFFF, country: "Peru" },
+    IcaoAllocation { start: 0xE90000, end: 0xE90FFF, country: "Uruguay" },
+    IcaoAllocation { start: 0xE94000, end: 0xE94FFF, country: "Bolivia" },
+    IcaoAllocation { start: 0xF00000, end: 0xF07FFF, country: "ICAO (temporary address)" },
+    IcaoAllocation { start: 0xF09000, end: 0xF093FF, country: "ICAO (special use)" },
+];
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error as DeError;
+use serde_json::Value;
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::provenance::{Provenance, PROVENANCE_KEY};
+use crate::transform::{TransformOutcome, TransformPipeline};
+
+/// Key the `IngestMeta` is stored under, alongside the top level keys of the wrapped message.
+pub const INGEST_META_KEY: &str = "ingest_meta";
+
+/// Provenance details for a message, for when a single process ingests from several receivers or frequencies.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct IngestMeta {
+    /// Identifies the receiver, dongle or channel the message came from.
+    pub source_id: String,
+    /// Frequency the receiver was tuned to in Hz, which may differ from the frequency reported in the message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub antenna: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_lon: Option<f64>,
+}
+
+impl IngestMeta {
+    /// Creates a new `IngestMeta` for the provided source with no other details set.
+    pub fn new(source_id: &str) -> Self {
+        Self { source_id: source_id.to_string(), ..Default::default() }
+    }
+}
+
+/// A message along with optional `IngestMeta` and `Provenance`, serialised as the message JSON with extra
+/// `ingest_meta` and `x_provenance` keys.
+///
+/// Use `strip()` to get the plain message back before forwarding to consumers that do not expect the extra keys.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::ingest::{IngestMeta, TaggedMessage};
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.decode_message().unwrap();
+/// let tagged: TaggedMessage = TaggedMessage::new(message, IngestMeta::new("dongle-1"));
+/// let json: String = tagged.to_json().unwrap();
+/// assert_eq!(json, r#"{"freq":131.55,"channel":2,"ingest_meta":{"source_id":"dongle-1"}}"#);
+/// let read_back: TaggedMessage = serde_json::from_str(&json).unwrap();
+/// assert_eq!(read_back.ingest_meta.unwrap().source_id, "dongle-1");
+/// assert_eq!(read_back.message.to_json().unwrap(), r#"{"freq":131.55,"channel":2}"#);
+/// ```
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TaggedMessage {
+    #[serde(flatten)]
+    pub message: AcarsVdlm2Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingest_meta: Option<IngestMeta>,
+    /// Transforms applied by `apply`, including those applied by earlier processes the message was tagged by.
+    #[serde(rename = "x_provenance", skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl TaggedMessage {
+    /// Wraps a message with the provided `IngestMeta`.
+    pub fn new(message: AcarsVdlm2Message, ingest_meta: IngestMeta) -> Self {
+        Self { message, ingest_meta: Some(ingest_meta), provenance: None }
+    }
+
+    /// Runs the message through the pipeline, recording the transforms applied and the fields they changed in its
+    /// `Provenance`. See `TransformPipeline::apply_recording`.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use acars_vdlm2_parser::ingest::TaggedMessage;
+    /// use acars_vdlm2_parser::provider::{FixedClock, Providers};
+    /// use acars_vdlm2_parser::redact::StationRedactor;
+    /// use acars_vdlm2_parser::transform::TransformPipeline;
+    /// let mut pipeline: TransformPipeline = TransformPipeline::new();
+    /// pipeline.add(StationRedactor::new("salt"));
+    /// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"station_id":"MN-YPPH"}"#.decode_message().unwrap();
+    /// let mut tagged: TaggedMessage = TaggedMessage::from(message);
+    /// Providers::new().with_clock(FixedClock(1700000000.0)).scope(|| tagged.apply(&pipeline));
+    /// let json: String = tagged.to_json().unwrap();
+    /// assert!(json.ends_with(r#""x_provenance":[{"transform":"station_redactor","applied_at":1700000000.0,"changed":["station_id"]}]}"#));
+    /// ```
+    pub fn apply(&mut self, pipeline: &TransformPipeline) -> TransformOutcome {
+        pipeline.apply_recording(&mut self.message, self.provenance.get_or_insert_with(Provenance::new))
+    }
+
+    /// Discards the `IngestMeta`, returning the plain message for forwarding.
+    pub fn strip(self) -> AcarsVdlm2Message {
+        self.message
+    }
+
+    /// Converts `TaggedMessage` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Converts `TaggedMessage` to a JSON `String` terminated with a `\n`.
+    pub fn to_json_newline(&self) -> MessageResult<String> {
+        self.to_json().map(|json| format!("{}\n", json))
+    }
+}
+
+impl From<AcarsVdlm2Message> for TaggedMessage {
+    fn from(message: AcarsVdlm2Message) -> Self {
+        Self { message, ingest_meta: None, provenance: None }
+    }
+}
+
+impl<'de> Deserialize<'de> for TaggedMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value: Value = Value::deserialize(deserializer)?;
+        let ingest_meta: Option<IngestMeta> = match value.as_object_mut().and_then(|map| map.remove(INGEST_META_KEY)) {
+            None => None,
+            Some(ingest_meta) => Some(IngestMeta::deserialize(ingest_meta).map_err(D::Error::custom)?),
+        };
+        let provenance: Option<Provenance> = match value.as_object_mut().and_then(|map| map.remove(PROVENANCE_KEY)) {
+            None => None,
+            Some(provenance) => Some(Provenance::deserialize(provenance).map_err(D::Error::custom)?),
+        };
+        let message: AcarsVdlm2Message = AcarsVdlm2Message::deserialize(value).map_err(D::Error::custom)?;
+        Ok(Self { message, ingest_meta, provenance })
+    }
+}
@@ -0,0 +1,102 @@
+//! Optional `tracing` instrumentation, enabled with the `tracing` feature.
+//!
+//! Without the feature these compile down to calling the decode directly, and the existing `log` output is unchanged.
+
+use crate::{AcarsVdlm2Message, MessageFamily, MessageResult};
+use crate::context::record_warning;
+#[cfg(feature = "acars")]
+use crate::acars::AcarsMessage;
+#[cfg(feature = "hfdl")]
+use crate::hfdl::HfdlMessage;
+#[cfg(feature = "uat")]
+use crate::uat::UatMessage;
+#[cfg(feature = "vdlm2")]
+use crate::vdlm2::Vdlm2Message;
+
+/// Implemented by every decoded message type so a span can record the family that was decoded.
+pub(crate) trait DecodedFamily {
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    fn decoded_family(&self) -> MessageFamily;
+}
+
+impl DecodedFamily for AcarsVdlm2Message {
+    fn decoded_family(&self) -> MessageFamily {
+        self.get_family()
+    }
+}
+
+#[cfg(feature = "acars")]
+impl DecodedFamily for AcarsMessage {
+    fn decoded_family(&self) -> MessageFamily {
+        MessageFamily::Acars
+    }
+}
+
+#[cfg(feature = "vdlm2")]
+impl DecodedFamily for Vdlm2Message {
+    fn decoded_family(&self) -> MessageFamily {
+        MessageFamily::Vdlm2
+    }
+}
+
+#[cfg(feature = "hfdl")]
+impl DecodedFamily for HfdlMessage {
+    fn decoded_family(&self) -> MessageFamily {
+        MessageFamily::Hfdl
+    }
+}
+
+#[cfg(feature = "uat")]
+impl DecodedFamily for UatMessage {
+    fn decoded_family(&self) -> MessageFamily {
+        MessageFamily::Uat
+    }
+}
+
+/// Runs a decode inside a `decode` span recording the message family, the input size in bytes and the outcome.
+///
+/// `attempted` is the family being decoded, or `None` when it is only known once the decode succeeds.
+#[cfg(feature = "tracing")]
+pub(crate) fn decode_span<T: DecodedFamily>(attempted: Option<MessageFamily>, bytes: usize, decode: impl FnOnce() -> MessageResult<T>) -> MessageResult<T> {
+    let span: tracing::Span = tracing::debug_span!(
+        "decode",
+        family = tracing::field::Empty,
+        bytes,
+        outcome = tracing::field::Empty,
+    );
+    let _entered: tracing::span::Entered = span.enter();
+    let decoded: MessageResult<T> = decode();
+    let family: Option<MessageFamily> = attempted.or_else(|| decoded.as_ref().ok().map(|message| message.decoded_family()));
+    if let Some(family) = family {
+        span.record("family", tracing::field::display(family));
+    }
+    match &decoded {
+        Ok(_) => {
+            span.record("outcome", "ok");
+        }
+        Err(error) => {
+            span.record("outcome", "error");
+            tracing::debug!(error = %error, "decode failed");
+        }
+    }
+    decoded
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn decode_span<T: DecodedFamily>(_attempted: Option<MessageFamily>, _bytes: usize, decode: impl FnOnce() -> MessageResult<T>) -> MessageResult<T> {
+    decode()
+}
+
+/// Emits an event when a decode takes a fallback path, such as coercing a quoted number.
+///
+/// The fallback is also recorded as a warning with any `ProcessingContext` capturing on this thread.
+#[cfg(feature = "tracing")]
+pub(crate) fn fallback(reason: &str, detail: &str) {
+    tracing::debug!(reason, detail, "decode fallback");
+    record_warning(reason, detail);
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn fallback(reason: &str, detail: &str) {
+    record_warning(reason, detail);
+}
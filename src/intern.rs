@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+use std::mem::size_of;
+use std::sync::Arc;
+use crate::AcarsVdlm2Message;
+
+/// Deduplicating store for strings that repeat constantly across messages.
+///
+/// Station names, labels, registrations and flight numbers are shared between a huge number of messages
+/// in a long-running aggregation, so storing them once as `Arc<str>` saves a lot of memory.
+/// ```
+/// use std::sync::Arc;
+/// use acars_vdlm2_parser::intern::StringInterner;
+/// let mut interner: StringInterner = StringInterner::new();
+/// let first: Arc<str> = interner.intern("YSSY");
+/// let second: Arc<str> = interner.intern("YSSY");
+/// assert!(Arc::ptr_eq(&first, &second));
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StringInterner {
+    strings: HashSet<Arc<str>>,
+    requested_bytes: usize,
+}
+
+impl StringInterner {
+    /// Creates a new, empty `StringInterner`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared copy of `value`, storing it first if it has not been seen before.
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        self.requested_bytes += value.len();
+        match self.strings.get(value) {
+            Some(existing) => Arc::clone(existing),
+            None => {
+                let shared: Arc<str> = Arc::from(value);
+                self.strings.insert(Arc::clone(&shared));
+                shared
+            }
+        }
+    }
+
+    /// Number of unique strings held by the interner.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns true if nothing has been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Estimated bytes of memory held by the interner.
+    ///
+    /// This counts each string with the two reference counts `Arc` stores alongside it, and one `Arc<str>` pointer for
+    /// every slot the hash set has room for. It leaves out the hash set's own bookkeeping and any rounding by the
+    /// allocator, so the memory actually allocated is somewhat higher.
+    pub fn stored_bytes(&self) -> usize {
+        let strings: usize = self.strings.iter()
+            .map(|value| 2 * size_of::<usize>() + value.len())
+            .sum();
+        strings + self.strings.capacity() * size_of::<Arc<str>>()
+    }
+
+    /// Total bytes of string data that was requested to be interned.
+    ///
+    /// This is what storing every value as an owned `String` would have cost.
+    pub fn requested_bytes(&self) -> usize {
+        self.requested_bytes
+    }
+}
+
+/// Compact view of an `AcarsVdlm2Message` intended for long-running aggregation.
+///
+/// Repeating fields are interned, so many `CompactMessage` entries share the same allocations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompactMessage {
+    pub time: Option<f64>,
+    pub station: Option<Arc<str>>,
+    pub label: Option<Arc<str>>,
+    pub registration: Option<Arc<str>>,
+    pub flight: Option<Arc<str>>,
+}
+
+impl CompactMessage {
+    /// Builds a `CompactMessage` from the provided message, interning the repeating fields.
+    pub fn from_message(message: &AcarsVdlm2Message, interner: &mut StringInterner) -> Self {
+        Self {
+            time: message.get_time(),
+            station: message.get_station_name().map(|value| interner.intern(value)),
+            label: message.get_label().map(|value| interner.intern(value)),
+            registration: message.get_registration().map(|value| interner.intern(value)),
+            flight: message.get_flight().map(|value| interner.intern(value)),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+/// Meanings of the ACARS labels defined by ARINC 618 that are commonly seen on air.
+///
+/// Most other labels are airline defined and have no fixed meaning.
+const LABELS: &[(&str, &str)] = &[
+    ("5U", "Weather request"),
+    ("5Z", "Airline designated downlink"),
+    ("80", "Airline designated downlink"),
+    ("A0", "FANS application message"),
+    ("A6", "ADS-C contract request"),
+    ("A9", "ATIS report"),
+    ("AA", "FANS-1/A CPDLC uplink"),
+    ("B6", "ADS-C report"),
+    ("B9", "ATIS request"),
+    ("BA", "FANS-1/A CPDLC downlink"),
+    ("C1", "Uplink to cockpit printer"),
+    ("H1", "Message to or from an onboard terminal, identified by the sublabel"),
+    ("MA", "MIAM compressed message"),
+    ("Q0", "Link test"),
+    ("SA", "Media advisory"),
+    ("SQ", "Squitter, a ground station beacon"),
+    ("_d", "General response, no information to transmit"),
+];
+
+/// Returns the meaning of an ACARS label, or `None` for labels without a fixed meaning.
+/// ```
+/// use acars_vdlm2_parser::labels::label_description;
+/// assert_eq!(label_description("Q0"), Some("Link test"));
+/// assert_eq!(label_description("4C"), None);
+/// ```
+pub fn label_description(label: &str) -> Option<&'static str> {
+    LABELS.binary_search_by(|(known, _)| (*known).cmp(label))
+        .ok()
+        .map(|index| LABELS[index].1)
+}
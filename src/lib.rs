@@ -1,16 +1,150 @@
+// This parser runs in always-on daemons where a panic takes down the whole router, so library code handles every
+// failure. Only the assertion helpers in `testing` panic, as they are meant to, and they are only built with the
+// `testing` feature.
+#![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+#[cfg(not(any(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat")))]
+compile_error!("at least one message family feature must be enabled: acars, vdlm2, hfdl or uat");
+
 extern crate serde;
 extern crate serde_json;
 #[macro_use] extern crate log;
 
-use crate::acars::AcarsMessage;
-use crate::vdlm2::Vdlm2Message;
-use crate::hfdl::HfdlMessage;
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::fmt;
+use std::io::Write;
+#[cfg(feature = "acars")]
+use crate::acars::{AcarsMessage, NewAcarsMessage};
+#[cfg(feature = "vdlm2")]
+use crate::vdlm2::{NewVdlm2Message, Vdlm2Message};
+#[cfg(feature = "hfdl")]
+use crate::hfdl::{HfdlMessage, NewHfdlMessage};
+#[cfg(feature = "uat")]
+use crate::uat::{parse_uat_raw, NewUatMessage, UatMessage};
+use crate::app_template::AppDetailsTemplate;
+#[cfg(feature = "acars")]
+use crate::convert::{AcarsConversion, ConversionReport};
+#[cfg(all(feature = "acars", feature = "hfdl"))]
+use crate::convert::hfdl_to_acars;
+#[cfg(all(feature = "acars", feature = "vdlm2"))]
+use crate::convert::vdlm2_to_acars;
+#[cfg(feature = "vdlm2")]
+use crate::consistency::ConsistencyFlags;
+use crate::correlate::AckFields;
+use crate::csv_legacy::is_csv_legacy;
+#[cfg(feature = "acars")]
+use crate::csv_legacy::parse_csv_legacy;
+#[cfg(feature = "enrichment")]
+use crate::enrichment::AircraftInfo;
+use crate::envelope::EnvelopeRef;
+use crate::equality::FieldClass;
+use crate::explain::Explanation;
+use crate::frequency::Frequency;
+use crate::icao::{icao_allocation, is_allocated, IcaoAllocation};
+use crate::context::record_warnings_if;
+use crate::instrument::{decode_span, fallback};
+use crate::normalise::TextNormaliser;
+use crate::quality::FrameQuality;
+use crate::redact::{AircraftAnonymiser, StationRedactor};
+use crate::sniff::{is_uat_raw, sniff, HFDL_KEY, UAT_ADDRESS_QUALIFIER_KEY, UAT_FRAME_KEY, VDLM2_KEY};
+use crate::source::SourceKind;
+use crate::summary::MessageSummary;
+use crate::timestamp::MessageTimestamp;
+use crate::visit::MessageVisitor;
+use crate::text_decoder::{DecodedText, TextDecoderRegistry};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error as DeError;
+use serde_json::Value;
 
+#[cfg(feature = "acars")]
 pub mod acars;
+#[cfg(feature = "adsb-json")]
+pub mod adsb_json;
+pub mod adsc;
+#[cfg(any(feature = "vdlm2", feature = "hfdl"))]
+pub mod adsc_contract;
+pub mod app_template;
+#[cfg(feature = "capture")]
+pub mod capture;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod conformance;
+#[cfg(feature = "vdlm2")]
+pub mod consistency;
+pub mod coerce;
+pub mod context;
+pub mod corpus;
+pub mod correlate;
+pub mod coverage;
+pub mod csv_legacy;
+#[cfg(feature = "acars")]
+pub mod convert;
+#[cfg(feature = "vdlm2")]
 pub mod vdlm2;
+#[cfg(feature = "hfdl")]
 pub mod hfdl;
+#[cfg(feature = "hfdl")]
+pub mod hfdl_analytics;
+#[cfg(feature = "hfdl")]
+pub mod hfdl_systable;
+pub mod dead_letter;
+pub mod error_code;
+pub mod equality;
+#[cfg(feature = "enrichment")]
+pub mod enrichment;
+pub mod envelope;
+pub mod explain;
+pub mod extract;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod field_stats;
+pub mod frequency;
+#[cfg(feature = "vdlm2")]
+pub mod handoff;
+pub mod history;
+pub mod icao;
+mod icao_table;
+pub mod ingest;
+mod instrument;
+pub mod intern;
+pub mod labels;
+pub mod merge;
+pub mod ndjson;
+pub mod normalise;
+pub mod pool;
+pub mod prelude;
+pub mod provenance;
+pub mod provider;
+pub mod quality;
+pub mod rate;
+#[cfg(feature = "rebroadcast")]
+pub mod rebroadcast;
+pub mod redact;
+pub mod registrations;
+pub mod required;
+pub mod roundtrip;
+pub mod router;
+pub mod sampler;
+pub mod sbs;
+#[cfg(feature = "search")]
+pub mod search;
+pub mod sniff;
+pub mod source;
+pub mod station_policy;
+pub mod summary;
+pub mod stream;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod text_decoder;
+pub mod time_index;
+pub mod timestamp;
+pub mod transform;
+#[cfg(feature = "uat")]
+pub mod uat;
+#[cfg(all(feature = "acars", feature = "vdlm2"))]
+pub mod uplink;
+pub mod validate;
+pub mod visit;
+pub mod window;
 
 /// Common return type for all serialisation/deserialisation functions.
 ///
@@ -20,6 +154,8 @@ pub type MessageResult<T> = Result<T, serde_json::Error>;
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
 /// The originating data must be in JSON format and have support for providing a `str`, and will not consume the source.
+/// Lines from legacy CSV feeders are also accepted and decoded as ACARS messages, see `csv_legacy`, and raw dump978-fa
+/// frames are decoded as UAT messages, see `uat::parse_uat_raw`.
 pub trait DecodeMessage {
     fn decode_message(&self) -> MessageResult<AcarsVdlm2Message>;
 }
@@ -29,7 +165,7 @@ pub trait DecodeMessage {
 /// This does not consume the `String`.
 impl DecodeMessage for String {
     fn decode_message(&self) -> MessageResult<AcarsVdlm2Message> {
-        serde_json::from_str(self)
+        self.as_str().decode_message()
     }
 }
 
@@ -38,18 +174,251 @@ impl DecodeMessage for String {
 /// This does not consume the `str`.
 impl DecodeMessage for str {
     fn decode_message(&self) -> MessageResult<AcarsVdlm2Message> {
-        serde_json::from_str(self)
+        if is_csv_legacy(self.as_bytes()) {
+            return decode_span(None, self.len(), || decode_csv_legacy(self));
+        }
+        if is_uat_raw(self.as_bytes()) {
+            return decode_span(None, self.len(), || decode_uat_raw(self));
+        }
+        decode_span(None, self.len(), || serde_json::from_str(self))
+    }
+}
+
+/// Provides functionality for decoding JSON held as bytes, such as a socket buffer, to `AcarsVdlm2Message`.
+///
+/// This decodes straight from the bytes without converting them to a `str` first, and does not consume them.
+/// A `Vec<u8>` or any other type that dereferences to `[u8]` can be decoded the same way.
+impl DecodeMessage for [u8] {
+    fn decode_message(&self) -> MessageResult<AcarsVdlm2Message> {
+        if is_csv_legacy(self) {
+            let line: &str = std::str::from_utf8(self).map_err(serde_json::Error::custom)?;
+            return decode_span(None, self.len(), || decode_csv_legacy(line));
+        }
+        if is_uat_raw(self) {
+            let line: &str = std::str::from_utf8(self).map_err(serde_json::Error::custom)?;
+            return decode_span(None, self.len(), || decode_uat_raw(line));
+        }
+        decode_span(None, self.len(), || serde_json::from_slice(self))
+    }
+}
+
+/// Provides functionality for decoding JSON already parsed into a `Value`, such as by an earlier pipeline stage, to
+/// `AcarsVdlm2Message`.
+///
+/// This decodes from the `Value` without writing it back out as JSON first, and does not consume it. Use
+/// `AcarsVdlm2Message::from_value` to decode a `Value` that is no longer needed.
+/// ```
+/// use serde_json::{json, Value};
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+/// let value: Value = json!({"freq": 131.55, "channel": 2, "label": "H1"});
+/// let message: AcarsVdlm2Message = value.decode_message().unwrap();
+/// assert_eq!(message.get_family(), MessageFamily::Acars);
+/// let owned: AcarsVdlm2Message = AcarsVdlm2Message::try_from(value).unwrap();
+/// assert_eq!(owned.to_json().unwrap(), message.to_json().unwrap());
+/// assert!(AcarsVdlm2Message::from_value(json!([1, 2])).is_err());
+/// ```
+impl DecodeMessage for Value {
+    fn decode_message(&self) -> MessageResult<AcarsVdlm2Message> {
+        decode_span(None, 0, || AcarsVdlm2Message::deserialize(self))
+    }
+}
+
+/// Decodes an owned `Value`, see `AcarsVdlm2Message::from_value`.
+impl TryFrom<Value> for AcarsVdlm2Message {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> MessageResult<Self> {
+        Self::from_value(value)
+    }
+}
+
+/// Decodes a line from a legacy CSV feeder, which is always an ACARS message.
+#[cfg(feature = "acars")]
+fn decode_csv_legacy(line: &str) -> MessageResult<AcarsVdlm2Message> {
+    fallback("csv_legacy", "decoded a legacy CSV line");
+    parse_csv_legacy(line).map(AcarsVdlm2Message::AcarsMessage)
+}
+
+/// Rejects a line from a legacy CSV feeder, as ACARS messages were not compiled in.
+#[cfg(not(feature = "acars"))]
+fn decode_csv_legacy(_line: &str) -> MessageResult<AcarsVdlm2Message> {
+    Err(family_not_enabled(MessageFamily::Acars))
+}
+
+/// Decodes a raw frame from dump978-fa, which is always a UAT message.
+#[cfg(feature = "uat")]
+fn decode_uat_raw(line: &str) -> MessageResult<AcarsVdlm2Message> {
+    fallback("uat_raw", "decoded a raw dump978-fa frame");
+    parse_uat_raw(line).map(|frame| AcarsVdlm2Message::UatMessage(UatMessage::Frame(frame)))
+}
+
+/// Rejects a raw frame from dump978-fa, as UAT messages were not compiled in.
+#[cfg(not(feature = "uat"))]
+fn decode_uat_raw(_line: &str) -> MessageResult<AcarsVdlm2Message> {
+    Err(family_not_enabled(MessageFamily::Uat))
+}
+
+/// Error returned for a message of a family whose feature is not enabled, naming the feature to enable.
+pub(crate) fn family_not_enabled<E: DeError>(family: MessageFamily) -> E {
+    E::custom(format!("{} messages are not supported, enable the `{}` feature", family, family))
+}
+
+/// The message family an ingest source is expected to produce, used by `decode_bytes`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpectedMessageType {
+    /// Identify the family from the message, see `MessageFamily::detect`.
+    #[default]
+    Any,
+    Acars,
+    Vdlm2,
+    Hfdl,
+    Uat,
+}
+
+impl ExpectedMessageType {
+    /// Returns the family expected, or `None` if any family is accepted.
+    pub fn family(&self) -> Option<MessageFamily> {
+        match self {
+            ExpectedMessageType::Any => None,
+            ExpectedMessageType::Acars => Some(MessageFamily::Acars),
+            ExpectedMessageType::Vdlm2 => Some(MessageFamily::Vdlm2),
+            ExpectedMessageType::Hfdl => Some(MessageFamily::Hfdl),
+            ExpectedMessageType::Uat => Some(MessageFamily::Uat),
+        }
+    }
+}
+
+impl From<MessageFamily> for ExpectedMessageType {
+    fn from(family: MessageFamily) -> Self {
+        match family {
+            MessageFamily::Acars => ExpectedMessageType::Acars,
+            MessageFamily::Vdlm2 => ExpectedMessageType::Vdlm2,
+            MessageFamily::Hfdl => ExpectedMessageType::Hfdl,
+            MessageFamily::Uat => ExpectedMessageType::Uat,
+        }
     }
 }
 
+/// Decodes JSON held as bytes as the expected message type, for ingest loops that read straight from sockets.
+///
+/// Nothing is converted to a `String` along the way. When a specific family is expected the message is decoded
+/// directly as that family, skipping the detection done for `ExpectedMessageType::Any`.
+/// ```
+/// use acars_vdlm2_parser::{decode_bytes, AcarsVdlm2Message, ExpectedMessageType, MessageFamily};
+/// let buffer: &[u8] = br#"{"freq":131.55,"channel":2,"label":"Q0"}"#;
+/// let message: AcarsVdlm2Message = decode_bytes(buffer, ExpectedMessageType::Acars).unwrap();
+/// assert_eq!(message.get_family(), MessageFamily::Acars);
+/// assert!(decode_bytes(buffer, ExpectedMessageType::Vdlm2).is_err());
+/// ```
+pub fn decode_bytes(bytes: &[u8], expected: ExpectedMessageType) -> MessageResult<AcarsVdlm2Message> {
+    match expected {
+        ExpectedMessageType::Any => bytes.decode_message(),
+        #[cfg(feature = "acars")]
+        ExpectedMessageType::Acars => bytes.to_acars().map(AcarsVdlm2Message::AcarsMessage),
+        #[cfg(feature = "vdlm2")]
+        ExpectedMessageType::Vdlm2 => bytes.to_vdlm2().map(AcarsVdlm2Message::Vdlm2Message),
+        #[cfg(feature = "hfdl")]
+        ExpectedMessageType::Hfdl => bytes.to_hfdl().map(AcarsVdlm2Message::HfdlMessage),
+        #[cfg(feature = "uat")]
+        ExpectedMessageType::Uat => bytes.to_uat().map(AcarsVdlm2Message::UatMessage),
+        #[cfg(not(feature = "acars"))]
+        ExpectedMessageType::Acars => Err(family_not_enabled(MessageFamily::Acars)),
+        #[cfg(not(feature = "vdlm2"))]
+        ExpectedMessageType::Vdlm2 => Err(family_not_enabled(MessageFamily::Vdlm2)),
+        #[cfg(not(feature = "hfdl"))]
+        ExpectedMessageType::Hfdl => Err(family_not_enabled(MessageFamily::Hfdl)),
+        #[cfg(not(feature = "uat"))]
+        ExpectedMessageType::Uat => Err(family_not_enabled(MessageFamily::Uat)),
+    }
+}
+
+/// Decodes JSON held as bytes straight into the type of its family, found with `sniff::sniff`, for high rate ingestion
+/// where allocations dominate.
+///
+/// `decode_message` parses the whole object into a `Value` tree to detect its family before decoding it, allocating
+/// every key and value along the way. This skips the tree, so little more than the message itself is allocated. Input
+/// that does not decode this way, such as legacy CSV lines, raw UAT frames and objects that repeat a key, is decoded
+/// by `decode_message` instead, so the result is always the same.
+/// ```
+/// use acars_vdlm2_parser::{decode_direct, AcarsVdlm2Message, DecodeMessage, MessageFamily};
+/// let buffer: String = AcarsVdlm2Message::example(MessageFamily::Vdlm2).to_json().unwrap();
+/// let message: AcarsVdlm2Message = decode_direct(buffer.as_bytes()).unwrap();
+/// assert_eq!(message.get_family(), MessageFamily::Vdlm2);
+/// assert_eq!(message.to_json().unwrap(), buffer.decode_message().unwrap().to_json().unwrap());
+/// let repeated: &[u8] = br#"{"freq":131.55,"channel":2,"label":"H1","label":"Q0"}"#;
+/// assert_eq!(decode_direct(repeated).unwrap().get_label(), Some("Q0"));
+/// ```
+pub fn decode_direct(bytes: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+    let Some(family) = sniff(bytes) else {
+        return bytes.decode_message();
+    };
+    // Warnings from an attempt that fails are raised again by `decode_message`, so are only kept on success.
+    let decoded: MessageResult<AcarsVdlm2Message> = record_warnings_if(|| {
+        if family == MessageFamily::Acars {
+            fallback("no_family_key", "no vdl2, hfdl or uat key, decoding as acars");
+        }
+        decode_bytes(bytes, family.into())
+    }, Result::is_ok);
+    decoded.or_else(|_| bytes.decode_message())
+}
+
 /// Implementation of `AcarsVdlm2Message`.
 impl AcarsVdlm2Message {
-    /// Converts `AcarsVdlm2Message` to `String`.
-    pub fn to_string(&self) -> MessageResult<String> {
+    /// Decodes an `AcarsVdlm2Message` from an owned `Value`, detecting the family as `DecodeMessage` does, without
+    /// writing the `Value` back out as JSON first.
+    pub fn from_value(value: Value) -> MessageResult<Self> {
+        decode_span(None, 0, || serde_json::from_value(value))
+    }
+
+    /// Returns the realistic example message of the family, see `AcarsMessage::example()`, `Vdlm2Message::example()`,
+    /// `HfdlMessage::example()` and `UatMessage::example()`.
+    ///
+    /// The default message is returned for a family whose feature is not enabled.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, MessageFamily};
+    /// let example: AcarsVdlm2Message = AcarsVdlm2Message::example(MessageFamily::Hfdl);
+    /// assert_eq!(example.get_family(), MessageFamily::Hfdl);
+    /// assert_eq!(example.get_registration(), Some("N123AB"));
+    /// ```
+    #[allow(unreachable_patterns)]
+    pub fn example(family: MessageFamily) -> Self {
+        trace!("Building the {} example message", family);
+        match family {
+            #[cfg(feature = "acars")]
+            MessageFamily::Acars => AcarsVdlm2Message::AcarsMessage(AcarsMessage::example()),
+            #[cfg(feature = "vdlm2")]
+            MessageFamily::Vdlm2 => AcarsVdlm2Message::Vdlm2Message(Vdlm2Message::example()),
+            #[cfg(feature = "hfdl")]
+            MessageFamily::Hfdl => AcarsVdlm2Message::HfdlMessage(HfdlMessage::example()),
+            #[cfg(feature = "uat")]
+            MessageFamily::Uat => AcarsVdlm2Message::UatMessage(UatMessage::example()),
+            _ => Self::default(),
+        }
+    }
+
+    /// Converts `AcarsVdlm2Message` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
         trace!("Converting {:?} to a string", &self);
         serde_json::to_string(self)
     }
 
+    /// Converts `AcarsVdlm2Message` to a JSON `String`.
+    ///
+    /// This shadows `ToString::to_string`, which returns the single line summary of the `Display` implementation, so
+    /// `message.to_string()` and `format!("{}", message)` differ. Use `to_json()` for the JSON and `summary_line()` for
+    /// the summary.
+    #[deprecated(since = "0.3.0", note = "use `to_json()` for the JSON, `to_string()` will return the `Display` summary")]
+    pub fn to_string(&self) -> MessageResult<String> {
+        self.to_json()
+    }
+
+    /// Returns the single line summary written by the `Display` implementation, for logging, see `to_json` for the
+    /// JSON.
+    pub fn summary_line(&self) -> String {
+        ToString::to_string(self)
+    }
+
     /// Converts `AcarsVdlm2Message` to `String` and appends a `\n` to the end.
     pub fn to_string_newline(&self) -> MessageResult<String> {
         trace!("Converting {:?} to a string and appending a newline", &self);
@@ -59,35 +428,157 @@ impl AcarsVdlm2Message {
         }
     }
 
-    /// Converts `AcarsVdlm2Message` to a `String` encoded as bytes.
+    /// Converts `AcarsVdlm2Message` to JSON encoded as bytes.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
-        trace!("Converting {:?} into a string and encoding as bytes", &self);
-        match self.to_string() {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes()),
-        }
+        trace!("Converting {:?} into bytes", &self);
+        serde_json::to_vec(self)
     }
 
-    /// Converts `AcarsVdlm2Message` to a `String` terminated with a `\n` and encoded as bytes.
+    /// Converts `AcarsVdlm2Message` to JSON encoded as bytes and terminated with a `\n`.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes_newline(&self) -> MessageResult<Vec<u8>> {
-        trace!("Converting {:?} into a string, appending a newline and encoding as bytes", &self);
-        match self.to_string_newline() {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes())
+        trace!("Converting {:?} into bytes and appending a newline", &self);
+        let mut bytes: Vec<u8> = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    /// Converts `AcarsVdlm2Message` to a `String` wrapped in an `Envelope` with the envelope version and the family,
+    /// which can be read back with `envelope::decode_enveloped`.
+    pub fn to_string_enveloped(&self) -> MessageResult<String> {
+        trace!("Converting {:?} to an enveloped string", &self);
+        serde_json::to_string(&EnvelopeRef::new(self))
+    }
+
+    /// Converts `AcarsVdlm2Message` to JSON encoded as bytes wrapped in an `Envelope`, and terminated with a `\n`.
+    pub fn to_bytes_enveloped_newline(&self) -> MessageResult<Vec<u8>> {
+        trace!("Converting {:?} into enveloped bytes and appending a newline", &self);
+        let mut bytes: Vec<u8> = serde_json::to_vec(&EnvelopeRef::new(self))?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    /// Converts `AcarsVdlm2Message` to JSON compressed with the codec, framed with a small header so it can be read
+    /// back with `decode_compressed`, see `compression::compress_message`.
+    #[cfg(feature = "compression")]
+    pub fn to_bytes_compressed(&self, codec: compression::Codec) -> MessageResult<Vec<u8>> {
+        trace!("Converting {:?} into compressed bytes", &self);
+        compression::compress_message(self, codec)
+    }
+
+    /// Decodes a frame written by `to_bytes_compressed`, see `compression::decode_compressed`.
+    #[cfg(feature = "compression")]
+    pub fn decode_compressed(frame: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+        trace!("Decoding a compressed frame of {} bytes", frame.len());
+        compression::decode_compressed(frame)
+    }
+
+    /// Serialises `AcarsVdlm2Message` as JSON straight into the provided writer.
+    ///
+    /// This avoids allocating an intermediate buffer, which is useful when writing to sockets or files.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.decode_message().unwrap();
+    /// let mut output: Vec<u8> = Vec::new();
+    /// message.write_to(&mut output).unwrap();
+    /// assert_eq!(output, message.to_bytes().unwrap());
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        trace!("Writing {:?} to the provided writer", &self);
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Serialises `AcarsVdlm2Message` as JSON straight into the provided writer and terminates it with a `\n`.
+    pub fn write_to_newline<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        trace!("Writing {:?} to the provided writer and appending a newline", &self);
+        serde_json::to_writer(&mut *writer, self)?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)
+    }
+
+    /// Returns the `MessageFamily` of the wrapped message.
+    pub fn get_family(&self) -> MessageFamily {
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(_) => MessageFamily::Vdlm2,
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(_) => MessageFamily::Acars,
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(_) => MessageFamily::Hfdl,
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => MessageFamily::Uat,
         }
     }
 
+    /// Retrieves the value at a dot separated path into the serialised form of the message.
+    ///
+    /// Path segments are object keys, or indexes when the current value is an array.
+    /// This is intended for scripting layers that need to reach fields generically.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use serde_json::json;
+    /// let mut message: AcarsVdlm2Message = r#"{"vdl2":{"avlc":{"cr":"Command","dst":{"addr":"10317A","type":"Ground station"},"frame_type":"S","src":{"addr":"A0FC23","status":"On ground","type":"Aircraft"}},"burst_len_octets":13,"freq":136975000,"idx":0}}"#.decode_message().unwrap();
+    /// assert_eq!(message.get_field("vdl2.avlc.src.addr"), Some(json!("A0FC23")));
+    /// message.set_field("vdl2.station", json!("CS-KABQ-VDLM")).unwrap();
+    /// assert_eq!(message.get_station_name(), Some("CS-KABQ-VDLM"));
+    /// assert!(message.set_field("vdl2.freq", json!("not a number")).is_err());
+    /// ```
+    pub fn get_field(&self, path: &str) -> Option<Value> {
+        trace!("Getting the field {} from {:?}", path, &self);
+        let value: Value = serde_json::to_value(self).ok()?;
+        path.split('.')
+            .try_fold(&value, |current, segment| match current {
+                Value::Array(entries) => segment.parse::<usize>().ok().and_then(|index| entries.get(index)),
+                _ => current.get(segment),
+            })
+            .cloned()
+    }
+
+    /// Sets the value at a dot separated path into the serialised form of the message.
+    ///
+    /// A missing key is created when its parent is an object. The message is rebuilt as the same message family,
+    /// so an error is returned if the new value does not fit the field, and the message is left unchanged.
+    pub fn set_field(&mut self, path: &str, new_value: Value) -> MessageResult<()> {
+        trace!("Setting the field {} to {} for {:?}", path, new_value, &self);
+        let mut value: Value = serde_json::to_value(&*self)?;
+        let mut current: &mut Value = &mut value;
+        for segment in path.split('.') {
+            current = match current {
+                Value::Array(entries) => segment.parse::<usize>().ok()
+                    .and_then(|index| entries.get_mut(index))
+                    .ok_or_else(|| serde::de::Error::custom(format!("index {} not found in {}", segment, path)))?,
+                Value::Object(map) => map.entry(segment).or_insert(Value::Null),
+                _ => return Err(serde::de::Error::custom(format!("{} is not an object or array in {}", segment, path))),
+            };
+        }
+        *current = new_value;
+        *self = match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(_) => AcarsVdlm2Message::Vdlm2Message(serde_json::from_value(value)?),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(_) => AcarsVdlm2Message::AcarsMessage(serde_json::from_value(value)?),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(_) => AcarsVdlm2Message::HfdlMessage(serde_json::from_value(value)?),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => AcarsVdlm2Message::UatMessage(serde_json::from_value(value)?),
+        };
+        Ok(())
+    }
+
     /// Clears a station name that may be set for either `Vdlm2Message` or `AcarsMessage`.
     pub fn clear_station_name(&mut self) {
         trace!("Clearing the station name for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_station_name(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) => acars.clear_station_name(),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.clear_station_name(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.clear_station_name(),
         }
     }
 
@@ -95,12 +586,18 @@ impl AcarsVdlm2Message {
     pub fn set_station_name(&mut self, station_name: &str) {
         trace!("Setting the station name to {} for {:?}", station_name, &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) =>
                 vdlm2.set_station_name(station_name),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) =>
                 acars.set_station_name(station_name),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(hfdl) =>
                 hfdl.set_station_name(station_name),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) =>
+                uat.set_station_name(station_name),
         }
     }
 
@@ -108,15 +605,21 @@ impl AcarsVdlm2Message {
     pub fn clear_proxy_details(&mut self) {
         trace!("Clearing the proxy details for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_proxy_details(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) => acars.clear_proxy_details(),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.clear_proxy_details(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.clear_proxy_details(),
         }
     }
 
     /// Sets proxy details to the provided details and sets `proxied` to true.
     ///
-    /// This invokes `AppDetails::new()` for either `Vdlm2Message` or `AcarsMessage` and updates the record.
+    /// This invokes `AppDetails::new()` for either `Vdlm2Message` or `AcarsMessage` and updates the record. A new
+    /// `acars_router_uuid` comes from the `provider::Providers` installed on the thread, so tests can pin it.
     pub fn set_proxy_details(
         &mut self,
         proxied_by: &str,
@@ -125,12 +628,48 @@ impl AcarsVdlm2Message {
         trace!("Setting the proxy details for {:?} to include proxy {} and router version {}",
             &self, proxied_by, acars_router_version);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) =>
                 vdlm2.set_proxy_details(proxied_by, acars_router_version),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) =>
                 acars.set_proxy_details(proxied_by, acars_router_version),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(hfdl) =>
                 hfdl.set_proxy_details(proxied_by, acars_router_version),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) =>
+                uat.set_proxy_details(proxied_by, acars_router_version),
+        }
+    }
+
+    /// Retrieves the app block from the message if there is one.
+    pub fn get_app_details(&self) -> Option<&AppDetails> {
+        trace!("Getting the app details from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_app_details(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_app_details(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_app_details(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_app_details(),
+        }
+    }
+
+    /// Applies the provided `AppDetailsTemplate` to the app block, keeping the original decoder name and version.
+    pub fn apply_app_template(&mut self, template: &AppDetailsTemplate) {
+        trace!("Applying an app details template to {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.apply_app_template(template),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.apply_app_template(template),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.apply_app_template(template),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.apply_app_template(template),
         }
     }
 
@@ -138,19 +677,459 @@ impl AcarsVdlm2Message {
     pub fn clear_time(&mut self) {
         trace!("Clearing the time for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_time(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) => acars.clear_time(),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.clear_time(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.clear_time(),
+        }
+    }
+
+    /// Replaces every field identifying the receiving or ground station with a stable pseudonym.
+    pub fn redact_ground_stations(&mut self, redactor: &StationRedactor) {
+        trace!("Redacting the ground stations for {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.redact_ground_stations(redactor),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.redact_ground_stations(redactor),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.redact_ground_stations(redactor),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.redact_ground_stations(redactor),
+        }
+    }
+
+    /// Replaces the ICAO address and registration with stable pseudonyms from the provided `AircraftAnonymiser`, see
+    /// `AircraftAnonymiser` for which fields are covered.
+    pub fn anonymise_aircraft(&mut self, anonymiser: &AircraftAnonymiser) {
+        trace!("Anonymising the aircraft for {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.anonymise_aircraft(anonymiser),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.anonymise_aircraft(anonymiser),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.anonymise_aircraft(anonymiser),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.anonymise_aircraft(anonymiser),
+        }
+    }
+
+    /// Returns true if the messages are the same apart from the fields in the given classes, such as the signal
+    /// levels and receive time of the same transmission heard by two stations.
+    ///
+    /// Messages of different families are never equal.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use acars_vdlm2_parser::equality::FieldClass;
+    /// let first: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"level":-24,"timestamp":1672531200.1,"text":"HELLO"}"#.decode_message().unwrap();
+    /// let second: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"level":-31,"timestamp":1672531200.4,"text":"HELLO"}"#.decode_message().unwrap();
+    /// assert!(!first.eq_ignoring(&second, &[FieldClass::Signal]));
+    /// assert!(first.eq_ignoring(&second, &[FieldClass::Signal, FieldClass::Time]));
+    /// ```
+    #[allow(unreachable_patterns)]
+    pub fn eq_ignoring(&self, other: &AcarsVdlm2Message, ignored: &[FieldClass]) -> bool {
+        trace!("Comparing {:?} with {:?} ignoring {:?}", &self, other, ignored);
+        match (self, other) {
+            #[cfg(feature = "vdlm2")]
+            (AcarsVdlm2Message::Vdlm2Message(vdlm2), AcarsVdlm2Message::Vdlm2Message(other)) => vdlm2.eq_ignoring(other, ignored),
+            #[cfg(feature = "acars")]
+            (AcarsVdlm2Message::AcarsMessage(acars), AcarsVdlm2Message::AcarsMessage(other)) => acars.eq_ignoring(other, ignored),
+            #[cfg(feature = "hfdl")]
+            (AcarsVdlm2Message::HfdlMessage(hfdl), AcarsVdlm2Message::HfdlMessage(other)) => hfdl.eq_ignoring(other, ignored),
+            #[cfg(feature = "uat")]
+            (AcarsVdlm2Message::UatMessage(uat), AcarsVdlm2Message::UatMessage(other)) => uat.eq_ignoring(other, ignored),
+            _ => false,
         }
     }
 
+    /// Normalises the ACARS message text using the provided `TextNormaliser`, see `TextNormaliser` for what is changed.
+    #[cfg_attr(not(any(feature = "acars", feature = "vdlm2", feature = "hfdl")), allow(unused_variables))]
+    pub fn normalise_text(&mut self, normaliser: &TextNormaliser) {
+        trace!("Normalising the text for {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.normalise_text(normaliser),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.normalise_text(normaliser),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.normalise_text(normaliser),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
+        }
+    }
+
+    /// Returns whether the message was sent by an aircraft or a ground station, the same way for every family, see
+    /// `SourceKind`.
+    pub fn source_kind(&self) -> SourceKind {
+        trace!("Getting the source kind for {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.source_kind(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.source_kind(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.source_kind(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.source_kind(),
+        }
+    }
+
+    /// Retrieves the `FrameQuality` of the message, showing whether it was received cleanly, corrected or garbled.
+    pub fn get_frame_quality(&self) -> FrameQuality {
+        trace!("Getting the frame quality for {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_frame_quality(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_frame_quality(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_frame_quality(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_frame_quality(),
+        }
+    }
+
+    /// Returns the ICAO 24 bit address of the aircraft taking part in the message, if the decoder reported one.
+    pub fn get_icao(&self) -> Option<u32> {
+        trace!("Getting the ICAO address from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_icao(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_icao(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_icao(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_icao(),
+        }
+    }
+
+    /// Returns the ICAO allocation block for the aircraft address in the message.
+    ///
+    /// See `icao::icao_allocation` for details.
+    pub fn icao_allocation(&self) -> Option<&'static IcaoAllocation> {
+        trace!("Getting the ICAO allocation for {:?}", &self);
+        self.get_icao().and_then(icao_allocation)
+    }
+
+    /// Returns `Some(false)` when the message carries an ICAO address outside every allocated block.
+    ///
+    /// These addresses are commonly the result of a garbled frame. Returns `None` when the message has no address.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// let valid: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"icao":8154676}"#.decode_message().unwrap();
+    /// let garbled: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"icao":16777215}"#.decode_message().unwrap();
+    /// assert_eq!(valid.icao_allocation().map(|allocation| allocation.country), Some("Australia"));
+    /// assert_eq!(valid.icao_is_allocated(), Some(true));
+    /// assert_eq!(garbled.icao_is_allocated(), Some(false));
+    /// ```
+    pub fn icao_is_allocated(&self) -> Option<bool> {
+        trace!("Checking the ICAO address is allocated for {:?}", &self);
+        self.get_icao().map(is_allocated)
+    }
+
     /// Retrieves the time information from the message.
     pub fn get_time(&self) -> Option<f64> {
         trace!("Getting the time from {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_time(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) => acars.get_time(),
-            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_time()
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_time(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_time(),
+        }
+    }
+
+    /// Retrieves the time of the message along with where the time came from and its precision.
+    ///
+    /// See `timestamp::MessageTimestamp` for details.
+    pub fn get_timestamp(&self) -> Option<MessageTimestamp> {
+        trace!("Getting the timestamp from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_timestamp(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_timestamp(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_timestamp(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_timestamp(),
+        }
+    }
+
+    /// Passes the parts of the message to the visitor, whichever family it is.
+    ///
+    /// See `visit::MessageVisitor` for the callbacks and the order they are called in.
+    pub fn visit(&self, visitor: &mut dyn MessageVisitor) {
+        trace!("Visiting {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.visit(visitor),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.visit(visitor),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.visit(visitor),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.visit(visitor),
+        }
+    }
+
+    /// Retrieves the frequency the message was received on.
+    pub fn get_frequency(&self) -> Frequency {
+        trace!("Getting the frequency from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_frequency(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_frequency(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_frequency(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_frequency(),
+        }
+    }
+
+    /// Retrieves the station name from the message.
+    pub fn get_station_name(&self) -> Option<&str> {
+        trace!("Getting the station name from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_station_name(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_station_name(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_station_name(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_station_name(),
+        }
+    }
+
+    /// Retrieves the ACARS label from the message.
+    pub fn get_label(&self) -> Option<&str> {
+        trace!("Getting the label from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_label(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_label(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_label(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => None,
+        }
+    }
+
+    /// Returns the result of cross-checking the address and registration of a `Vdlm2Message`, if a
+    /// `ConsistencyChecker` has checked it.
+    #[cfg(feature = "vdlm2")]
+    pub fn consistency(&self) -> Option<&ConsistencyFlags> {
+        trace!("Getting the consistency flags from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.consistency(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(_) => None,
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(_) => None,
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => None,
+        }
+    }
+
+    /// Retrieves the aircraft registration from the message, without any leading padding dots.
+    pub fn get_registration(&self) -> Option<&str> {
+        trace!("Getting the registration from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_registration(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_registration(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_registration(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => None,
+        }
+    }
+
+    /// Retrieves the flight number from the message.
+    pub fn get_flight(&self) -> Option<&str> {
+        trace!("Getting the flight from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_flight(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_flight(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_flight(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_flight(),
+        }
+    }
+
+    /// Retrieves the ACARS message text from the message.
+    pub fn get_text(&self) -> Option<&str> {
+        trace!("Getting the text from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_text(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_text(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_text(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => None,
+        }
+    }
+
+    /// Returns the inflated payload if the message text is a compressed payload, see `compression::detect` for the
+    /// formats recognised.
+    ///
+    /// The payload is inflated to at most `compression::DEFAULT_MAX_DECOMPRESSED_LEN` bytes, and `None` is returned if
+    /// it would be larger; use `compression::decompress` to choose the limit.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"H1","text":"eJzzSM3JyddR8MCkFAF9LAjW"}"#.decode_message().unwrap();
+    /// assert_eq!(message.decompressed_payload(), Some(b"Hello, Hello, Hello, Hello!".to_vec()));
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn decompressed_payload(&self) -> Option<Vec<u8>> {
+        trace!("Decompressing the payload of {:?}", &self);
+        compression::decompress(self.get_text()?, compression::DEFAULT_MAX_DECOMPRESSED_LEN).ok()
+    }
+
+    /// Returns the `aircraft_info` block added by an `enrichment::AircraftDatabase`, if any.
+    #[cfg(feature = "enrichment")]
+    pub fn get_aircraft_info(&self) -> Option<&AircraftInfo> {
+        trace!("Getting the aircraft info from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_aircraft_info(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_aircraft_info(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_aircraft_info(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.get_aircraft_info(),
+        }
+    }
+
+    /// Sets the `aircraft_info` block, which is serialised alongside the decoder output.
+    #[cfg(feature = "enrichment")]
+    pub fn set_aircraft_info(&mut self, info: AircraftInfo) {
+        trace!("Setting the aircraft info for {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.set_aircraft_info(info),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.set_aircraft_info(info),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.set_aircraft_info(info),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.set_aircraft_info(info),
+        }
+    }
+
+    /// Retrieves the ACARS sublabel from the message, which is only reported by dumpvdl2 and dumphfdl.
+    pub fn get_sublabel(&self) -> Option<&str> {
+        trace!("Getting the sublabel from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_sublabel(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_sublabel(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_sublabel(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => None,
+        }
+    }
+
+    /// Retrieves the ACARS link layer fields used to pair the message with its acknowledgement, see `AckCorrelator`.
+    pub fn get_ack_fields(&self) -> Option<AckFields<'_>> {
+        trace!("Getting the ack fields from {:?}", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.get_ack_fields(),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.get_ack_fields(),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.get_ack_fields(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => None,
+        }
+    }
+
+    /// Returns a field by field breakdown of the message, noting what each value means where that is known.
+    ///
+    /// This draws on the ACARS label dictionary, the frequency band and the ICAO address allocations,
+    /// and for VDLM2 ground station information frames the decoded GSIF parameters.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"mode":"2","tail":".N123AB","label":"Q0","block_id":"4","ack":false}"#.decode_message().unwrap();
+    /// let explanation: String = message.explain().to_string();
+    /// assert!(explanation.contains("label: Q0"));
+    /// assert!(explanation.contains("// Link test"));
+    /// assert!(explanation.contains("// VHF band"));
+    /// ```
+    pub fn explain(&self) -> Explanation {
+        trace!("Explaining {:?}", &self);
+        Explanation::of(self)
+    }
+
+    /// Returns the time, family, address, tail, flight, label and a snippet of the text, borrowed from the message, for
+    /// populating list views without walking the full message for every row.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+    /// use acars_vdlm2_parser::summary::MessageSummary;
+    /// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"timestamp":1700000000.5,"tail":".N123AB","flight":"UA0123","label":"H1","text":"\r\nPOS N47,W122 ALT 35000\r\nFUEL 123"}"#.decode_message().unwrap();
+    /// let summary: MessageSummary = message.summary();
+    /// assert_eq!((summary.time, summary.family), (Some(1700000000.5), MessageFamily::Acars));
+    /// assert_eq!((summary.tail, summary.flight, summary.label), (Some("N123AB"), Some("UA0123"), Some("H1")));
+    /// assert_eq!(summary.snippet, Some("POS N47,W122 ALT 35000"));
+    /// assert!(summary.is_truncated(&message));
+    /// ```
+    pub fn summary(&self) -> MessageSummary<'_> {
+        trace!("Summarising {:?}", &self);
+        MessageSummary::of(self)
+    }
+
+    /// Runs the message text through the first matching decoder in the provided `TextDecoderRegistry`.
+    ///
+    /// See `TextDecoderRegistry` for how decoders are matched.
+    pub fn decode_text(&self, registry: &TextDecoderRegistry) -> Option<DecodedText> {
+        trace!("Decoding the text from {:?}", &self);
+        registry.decode_text(self)
+    }
+
+    /// Converts the message to the acarsdec layout with a report of the fields that could not be carried over, so
+    /// feeds of every family can be handled as one.
+    ///
+    /// `AcarsMessage` is returned unchanged with nothing dropped. VDLM2 and HFDL frames are converted with
+    /// `convert::vdlm2_to_acars` and `convert::hfdl_to_acars`, and `None` is returned for frames without an ACARS block
+    /// and for UAT messages, which never carry one.
+    #[cfg(feature = "acars")]
+    pub fn to_acars_message(&self) -> Option<AcarsConversion> {
+        trace!("Converting {:?} to an ACARS message", &self);
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2_to_acars(vdlm2),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl_to_acars(hfdl),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => None,
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => Some(AcarsConversion {
+                message: acars.clone(),
+                report: ConversionReport { source: MessageFamily::Acars, dropped: Vec::new() },
+            }),
         }
     }
 
@@ -158,9 +1137,14 @@ impl AcarsVdlm2Message {
     pub fn clear_freq_skew(&mut self) {
         trace!("Clearing the frequency skew for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_freq_skew(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(_) => {}
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.clear_freq_skew(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
         }
     }
 
@@ -168,9 +1152,14 @@ impl AcarsVdlm2Message {
     pub fn clear_hdr_bits_fixed(&mut self) {
         trace!("Clearing the hdr bits fixed for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_hdr_bits_fixed(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(_) => {}
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(_) => {}
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
         }
     }
 
@@ -178,9 +1167,14 @@ impl AcarsVdlm2Message {
     pub fn clear_noise_level(&mut self) {
         trace!("Clearing the noise level for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_noise_level(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(_) => {}
-            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.clear_noise_level()
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.clear_noise_level(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
         }
     }
 
@@ -188,9 +1182,14 @@ impl AcarsVdlm2Message {
     pub fn clear_octets_corrected_by_fec(&mut self) {
         trace!("Clearing the octets corrected by fec for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_octets_corrected_by_fec(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(_) => {}
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(_) => {}
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
         }
     }
 
@@ -198,9 +1197,14 @@ impl AcarsVdlm2Message {
     pub fn clear_sig_level(&mut self) {
         trace!("Clearing the signal level for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.clear_sig_level(),
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(_) => {}
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.clear_sig_level(),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.clear_sig_level(),
         }
     }
 
@@ -208,9 +1212,14 @@ impl AcarsVdlm2Message {
     pub fn clear_channel(&mut self) {
         trace!("Clearing the channel for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(_) => {}
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) => acars.clear_channel(),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(_) => {}
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
         }
     }
 
@@ -218,9 +1227,14 @@ impl AcarsVdlm2Message {
     pub fn clear_error(&mut self) {
         trace!("Clearing the error field for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(_) => {}
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) => acars.clear_error(),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(_) => {}
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
         }
     }
 
@@ -228,32 +1242,291 @@ impl AcarsVdlm2Message {
     pub fn clear_level(&mut self) {
         trace!("Clearing the level field for {:?}", &self);
         match self {
+            #[cfg(feature = "vdlm2")]
             AcarsVdlm2Message::Vdlm2Message(_) => {}
+            #[cfg(feature = "acars")]
             AcarsVdlm2Message::AcarsMessage(acars) => acars.clear_level(),
+            #[cfg(feature = "hfdl")]
             AcarsVdlm2Message::HfdlMessage(_) => {}
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(_) => {}
         }
     }
 }
 
-/// This will automagically serialise to either a `Vdlm2Message` or `AcarsMessage`.
+/// The message families supported by this library.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFamily {
+    Acars,
+    Vdlm2,
+    Hfdl,
+    Uat,
+}
+
+impl MessageFamily {
+    /// Every supported message family, including those whose feature is not enabled, see `is_enabled`.
+    pub const ALL: [MessageFamily; 4] = [MessageFamily::Acars, MessageFamily::Vdlm2, MessageFamily::Hfdl, MessageFamily::Uat];
+
+    /// Returns true if the feature for the family is enabled, so messages of the family can be decoded.
+    ///
+    /// Families that are not enabled are still identified by `detect` and `sniff::sniff`, and fail to decode with an
+    /// error naming the feature.
+    /// ```
+    /// use acars_vdlm2_parser::MessageFamily;
+    /// assert_eq!(MessageFamily::Hfdl.is_enabled(), cfg!(feature = "hfdl"));
+    /// ```
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            MessageFamily::Acars => cfg!(feature = "acars"),
+            MessageFamily::Vdlm2 => cfg!(feature = "vdlm2"),
+            MessageFamily::Hfdl => cfg!(feature = "hfdl"),
+            MessageFamily::Uat => cfg!(feature = "uat"),
+        }
+    }
+
+    /// Identifies the message family from the top level keys of a JSON object.
+    ///
+    /// dumpvdl2 and dumphfdl nest their output under a `vdl2` or `hfdl` key, dump978-fa writes an `address_qualifier` in
+    /// every message and raw UAT frames are nested under `uat_frame`. Anything else is treated as acarsdec output.
+    /// See `sniff::sniff` for doing the same on raw bytes without parsing them first.
+    pub fn detect(value: &Value) -> MessageFamily {
+        match value {
+            Value::Object(map) if map.contains_key(VDLM2_KEY) => MessageFamily::Vdlm2,
+            Value::Object(map) if map.contains_key(HFDL_KEY) => MessageFamily::Hfdl,
+            Value::Object(map) if map.contains_key(UAT_FRAME_KEY) || map.contains_key(UAT_ADDRESS_QUALIFIER_KEY) => MessageFamily::Uat,
+            _ => MessageFamily::Acars,
+        }
+    }
+}
+
+impl fmt::Display for MessageFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageFamily::Acars => write!(f, "acars"),
+            MessageFamily::Vdlm2 => write!(f, "vdlm2"),
+            MessageFamily::Hfdl => write!(f, "hfdl"),
+            MessageFamily::Uat => write!(f, "uat"),
+        }
+    }
+}
+
+/// This will automagically serialise to either a `Vdlm2Message`, `AcarsMessage`, `HfdlMessage` or `UatMessage`.
 ///
 /// This simplifies the handling of messaging by not needing to identify it first.
-/// It handles identification by looking at the provided data and seeing which format matches it best.
+/// It handles identification by looking at the top level keys of the provided data, see `MessageFamily::detect`,
+/// and then decodes it as that family so the error returned is the real reason that family failed to decode.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// let error: serde_json::Error = r#"{"vdl2":{"freq":136975000}}"#.decode_message().unwrap_err();
+/// assert!(error.to_string().starts_with("invalid vdlm2 message: missing field"));
+/// ```
 #[allow(clippy::large_enum_variant)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum AcarsVdlm2Message {
+    #[cfg(feature = "vdlm2")]
     Vdlm2Message(Vdlm2Message),
+    #[cfg(feature = "acars")]
     AcarsMessage(AcarsMessage),
+    #[cfg(feature = "hfdl")]
     HfdlMessage(HfdlMessage),
+    #[cfg(feature = "uat")]
+    UatMessage(UatMessage),
+}
+
+/// Renders a concise single line summary of the wrapped message, intended for logging rather than forwarding.
+///
+/// `summary_line()` returns it as a `String`, and `to_json()` returns the JSON. The inherent `to_string()` of every
+/// message type still returns the JSON and takes precedence over `ToString::to_string`, so it is deprecated in favour
+/// of `to_json()`.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"timestamp":1645287877.5,"station_id":"MN-YPPH","tail":"VH-EBA","label":"H1","text":"HELLO\r\nWORLD"}"#.decode_message().unwrap();
+/// assert_eq!(format!("{}", message), r#"ACARS t=1645287877.500 freq=131.550MHz station=MN-YPPH reg=VH-EBA label=H1 text="HELLO\r\nWORLD""#);
+/// assert_eq!(message.summary_line(), format!("{}", message));
+/// assert!(message.to_json().unwrap().starts_with('{'));
+/// ```
+impl fmt::Display for AcarsVdlm2Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "vdlm2")]
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => vdlm2.fmt(f),
+            #[cfg(feature = "acars")]
+            AcarsVdlm2Message::AcarsMessage(acars) => acars.fmt(f),
+            #[cfg(feature = "hfdl")]
+            AcarsVdlm2Message::HfdlMessage(hfdl) => hfdl.fmt(f),
+            #[cfg(feature = "uat")]
+            AcarsVdlm2Message::UatMessage(uat) => uat.fmt(f),
+        }
+    }
+}
+
+/// Shared single line layout used by the `Display` implementations of every message type.
+///
+/// Fields that are not present are left out, and the text is escaped so the output stays on one line.
+pub(crate) struct SummaryLine<'a> {
+    pub(crate) family: &'a str,
+    pub(crate) time: Option<f64>,
+    pub(crate) station: Option<&'a str>,
+    pub(crate) freq_mhz: f64,
+    pub(crate) registration: Option<&'a str>,
+    pub(crate) flight: Option<&'a str>,
+    pub(crate) label: Option<&'a str>,
+    pub(crate) text: Option<&'a str>,
+}
+
+impl fmt::Display for SummaryLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.family)?;
+        if let Some(time) = self.time {
+            write!(f, " t={:.3}", time)?;
+        }
+        write!(f, " freq={:.3}MHz", self.freq_mhz)?;
+        if let Some(station) = self.station {
+            write!(f, " station={}", station)?;
+        }
+        if let Some(registration) = self.registration.filter(|value| !value.is_empty()) {
+            write!(f, " reg={}", registration)?;
+        }
+        if let Some(flight) = self.flight.filter(|value| !value.is_empty()) {
+            write!(f, " flight={}", flight)?;
+        }
+        if let Some(label) = self.label {
+            write!(f, " label={}", label)?;
+        }
+        if let Some(text) = self.text.filter(|value| !value.is_empty()) {
+            write!(f, " text={:?}", text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes the family identified by `MessageFamily::detect`, propagating that family's error rather than
+/// the "data did not match any variant" error an untagged enum would produce.
+impl<'de> Deserialize<'de> for AcarsVdlm2Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value: Value = Value::deserialize(deserializer)?;
+        let found: &str = match &value {
+            Value::Object(_) => "",
+            Value::Array(_) => "an array",
+            Value::String(_) => "a string",
+            Value::Number(_) => "a number",
+            Value::Bool(_) => "a boolean",
+            Value::Null => "null",
+        };
+        if !found.is_empty() {
+            return Err(D::Error::custom(format!("expected a JSON object, found {}", found)));
+        }
+        let family: MessageFamily = MessageFamily::detect(&value);
+        if family == MessageFamily::Acars {
+            fallback("no_family_key", "no vdl2, hfdl or uat key, decoding as acars");
+        }
+        AcarsVdlm2Message::deserialize_family(family, value).map_err(D::Error::custom)
+    }
+}
+
+impl AcarsVdlm2Message {
+    /// Decodes the message as the family, once it is known, prefixing any error with the family. A family whose
+    /// feature is not enabled is rejected without decoding it.
+    #[allow(unreachable_patterns)]
+    pub(crate) fn deserialize_family<'de, D: Deserializer<'de>>(family: MessageFamily, deserializer: D) -> Result<Self, D::Error> {
+        let decoded: Result<Self, D::Error> = match family {
+            #[cfg(feature = "vdlm2")]
+            MessageFamily::Vdlm2 => Vdlm2Message::deserialize(deserializer).map(AcarsVdlm2Message::Vdlm2Message),
+            #[cfg(feature = "acars")]
+            MessageFamily::Acars => AcarsMessage::deserialize(deserializer).map(AcarsVdlm2Message::AcarsMessage),
+            #[cfg(feature = "hfdl")]
+            MessageFamily::Hfdl => HfdlMessage::deserialize(deserializer).map(AcarsVdlm2Message::HfdlMessage),
+            #[cfg(feature = "uat")]
+            MessageFamily::Uat => UatMessage::deserialize(deserializer).map(AcarsVdlm2Message::UatMessage),
+            _ => return Err(family_not_enabled(family)),
+        };
+        decoded.map_err(|error| D::Error::custom(format!("invalid {} message: {}", family, error)))
+    }
 }
 
 impl Default for AcarsVdlm2Message {
+    /// The default of the first enabled family, in the order VDLM2, ACARS, HFDL and UAT.
     fn default() -> Self {
-        Self::Vdlm2Message(Default::default())
+        #[cfg(feature = "vdlm2")]
+        let message: Self = Self::Vdlm2Message(Default::default());
+        #[cfg(all(not(feature = "vdlm2"), feature = "acars"))]
+        let message: Self = Self::AcarsMessage(Default::default());
+        #[cfg(all(not(feature = "vdlm2"), not(feature = "acars"), feature = "hfdl"))]
+        let message: Self = Self::HfdlMessage(Default::default());
+        #[cfg(all(not(feature = "vdlm2"), not(feature = "acars"), not(feature = "hfdl")))]
+        let message: Self = Self::UatMessage(Default::default());
+        message
+    }
+}
+
+/// Decodes a message in any of the forms `DecodeMessage` accepts, so it can be read with `str::parse`.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, MessageFamily};
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.parse().unwrap();
+/// assert_eq!(message.get_family(), MessageFamily::Acars);
+/// ```
+impl std::str::FromStr for AcarsVdlm2Message {
+    type Err = serde_json::Error;
+
+    fn from_str(input: &str) -> MessageResult<Self> {
+        input.decode_message()
     }
 }
 
+/// Wraps an `AcarsMessage`, so any family can be passed where an `AcarsVdlm2Message` is expected with `into()`.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, MessageFamily};
+/// use acars_vdlm2_parser::acars::AcarsMessage;
+/// let message: AcarsVdlm2Message = AcarsMessage::default().into();
+/// assert_eq!(message.get_family(), MessageFamily::Acars);
+/// ```
+#[cfg(feature = "acars")]
+impl From<AcarsMessage> for AcarsVdlm2Message {
+    fn from(message: AcarsMessage) -> Self {
+        Self::AcarsMessage(message)
+    }
+}
+
+/// Wraps a `Vdlm2Message`.
+#[cfg(feature = "vdlm2")]
+impl From<Vdlm2Message> for AcarsVdlm2Message {
+    fn from(message: Vdlm2Message) -> Self {
+        Self::Vdlm2Message(message)
+    }
+}
+
+/// Wraps an `HfdlMessage`.
+#[cfg(feature = "hfdl")]
+impl From<HfdlMessage> for AcarsVdlm2Message {
+    fn from(message: HfdlMessage) -> Self {
+        Self::HfdlMessage(message)
+    }
+}
+
+/// Wraps a `UatMessage`.
+#[cfg(feature = "uat")]
+impl From<UatMessage> for AcarsVdlm2Message {
+    fn from(message: UatMessage) -> Self {
+        Self::UatMessage(message)
+    }
+}
+
+/// Former name of `AcarsVdlm2Message` for a message received from a feeder, kept so older code keeps compiling.
+///
+/// It is the same type, so values can be passed between code using either name. See "Migrating from older names" in
+/// the README.
+#[deprecated(since = "0.3.0", note = "use `AcarsVdlm2Message`, which is the same type")]
+pub type ReceivedMessage = AcarsVdlm2Message;
+
+/// Former name of `AcarsVdlm2Message` for a message that has been decoded, kept so older code keeps compiling.
+///
+/// It is the same type, so values can be passed between code using either name. See "Migrating from older names" in
+/// the README.
+#[deprecated(since = "0.3.0", note = "use `AcarsVdlm2Message`, which is the same type")]
+pub type DecodedMessage = AcarsVdlm2Message;
+
 /// This struct lives here because it is used by both `Vdlm2Message` and `AcarsMessage`.
 ///
 /// This does not normally exist on `AcarsMessage` and has been added as part of the implementation for the acars_router project.
@@ -295,7 +1568,7 @@ impl AppDetails {
             proxied: Some(true),
             proxied_by: Some(proxied_by.to_string()),
             acars_router_version: Some(acars_router_version.to_string()),
-            acars_router_uuid: Some(Uuid::new_v4().to_string()),
+            acars_router_uuid: Some(provider::new_uuid().to_string()),
         }
     }
     /// Updates an existing entry of `AppDetails` with the provided details.
@@ -315,7 +1588,7 @@ impl AppDetails {
         self.proxied_by = Some(proxied_by.to_string());
         self.acars_router_version = Some(acars_router_version.to_string());
         if self.acars_router_uuid.is_none() {
-            self.acars_router_uuid = Some(Uuid::new_v4().to_string());
+            self.acars_router_uuid = Some(provider::new_uuid().to_string());
         }
     }
     /// Removes the proxy information from an existing `AppDetails`.
@@ -0,0 +1,236 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use crate::AcarsVdlm2Message;
+
+/// Reordering window, in seconds, used by `merge_streams` callers that have no better figure for how far out of order
+/// a single feeder's messages can be.
+pub const DEFAULT_REORDER_WINDOW_SECS: f64 = 2.0;
+
+/// A buffered message, ordered so the `BinaryHeap` pops the earliest first and keeps arrival order for equal times.
+#[derive(Debug)]
+struct Buffered {
+    time: f64,
+    sequence: u64,
+    message: AcarsVdlm2Message,
+}
+
+impl PartialEq for Buffered {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Buffered {}
+
+impl PartialOrd for Buffered {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Buffered {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.time.total_cmp(&self.time).then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Merges messages pushed from several sources, such as different stations or protocols, into a single sequence ordered
+/// by message time, see `AcarsVdlm2Message::get_time()`.
+///
+/// A message is held until every open source has sent a message at least `reorder_window_secs` later than it, so
+/// messages a source sends up to that far out of order are still put in order. A source that goes quiet holds back
+/// the merged output until it sends again or is closed. Messages older than one already released are released straight
+/// away and counted as late, and messages without a time are released straight away and counted as untimed; neither is
+/// ever dropped.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::merge::StreamMerger;
+/// let message = |time: f64| -> AcarsVdlm2Message {
+///     format!(r#"{{"freq":131.55,"timestamp":{}}}"#, time).decode_message().unwrap()
+/// };
+/// let mut merger: StreamMerger = StreamMerger::new(2, 1.0);
+/// assert!(merger.push(0, message(10.0)).is_empty());
+/// assert!(merger.push(1, message(9.5)).is_empty());
+/// assert!(merger.push(1, message(12.0)).is_empty());
+/// let released: Vec<AcarsVdlm2Message> = merger.push(0, message(11.0));
+/// assert_eq!(released.iter().map(|message| message.get_time().unwrap()).collect::<Vec<f64>>(), vec![9.5, 10.0]);
+/// let rest: Vec<AcarsVdlm2Message> = merger.flush();
+/// assert_eq!(rest.iter().map(|message| message.get_time().unwrap()).collect::<Vec<f64>>(), vec![11.0, 12.0]);
+/// ```
+#[derive(Debug)]
+pub struct StreamMerger {
+    reorder_window_secs: f64,
+    /// Latest message time seen from each source, `None` until it sends a timed message.
+    latest: Vec<Option<f64>>,
+    open: Vec<bool>,
+    buffer: BinaryHeap<Buffered>,
+    sequence: u64,
+    released_up_to: Option<f64>,
+    late: u64,
+    untimed: u64,
+}
+
+impl StreamMerger {
+    /// Creates a `StreamMerger` for the given number of sources, numbered from 0, all open.
+    pub fn new(sources: usize, reorder_window_secs: f64) -> Self {
+        Self {
+            reorder_window_secs: reorder_window_secs.max(0.0),
+            latest: vec![None; sources],
+            open: vec![true; sources],
+            buffer: BinaryHeap::new(),
+            sequence: 0,
+            released_up_to: None,
+            late: 0,
+            untimed: 0,
+        }
+    }
+
+    /// Number of messages released out of order because a later message had already been released.
+    pub fn late(&self) -> u64 {
+        self.late
+    }
+
+    /// Number of messages released without a time.
+    pub fn untimed(&self) -> u64 {
+        self.untimed
+    }
+
+    /// Number of messages held waiting for the other sources.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Adds a message from the source, returning the messages that can now be released, in time order.
+    ///
+    /// Sources past the number the merger was created with are added as they are first seen.
+    pub fn push(&mut self, source: usize, message: AcarsVdlm2Message) -> Vec<AcarsVdlm2Message> {
+        if source >= self.latest.len() {
+            self.latest.resize(source + 1, None);
+            self.open.resize(source + 1, true);
+        }
+        let Some(time) = message.get_time() else {
+            self.untimed += 1;
+            let mut released: Vec<AcarsVdlm2Message> = vec![message];
+            released.extend(self.release());
+            return released;
+        };
+        if self.released_up_to.is_some_and(|released_up_to| time < released_up_to) {
+            self.late += 1;
+            let mut released: Vec<AcarsVdlm2Message> = vec![message];
+            released.extend(self.release());
+            return released;
+        }
+        self.latest[source] = Some(self.latest[source].map_or(time, |latest| latest.max(time)));
+        self.sequence += 1;
+        self.buffer.push(Buffered { time, sequence: self.sequence, message });
+        self.release()
+    }
+
+    /// Marks the source as finished, so the merged output no longer waits for it, returning the messages that can now
+    /// be released.
+    pub fn close(&mut self, source: usize) -> Vec<AcarsVdlm2Message> {
+        if let Some(open) = self.open.get_mut(source) {
+            *open = false;
+        }
+        self.release()
+    }
+
+    /// Releases every held message in time order, as when all sources have finished.
+    pub fn flush(&mut self) -> Vec<AcarsVdlm2Message> {
+        self.open.iter_mut().for_each(|open| *open = false);
+        self.release()
+    }
+
+    /// Time up to which every open source has moved on far enough, or `None` if an open source has not sent a timed
+    /// message yet. Infinite once every source is closed.
+    fn watermark(&self) -> Option<f64> {
+        self.latest.iter().zip(&self.open)
+            .filter(|(_, open)| **open)
+            .try_fold(f64::INFINITY, |watermark, (latest, _)| latest.map(|latest| watermark.min(latest - self.reorder_window_secs)))
+    }
+
+    fn release(&mut self) -> Vec<AcarsVdlm2Message> {
+        let mut released: Vec<AcarsVdlm2Message> = Vec::new();
+        let Some(watermark) = self.watermark() else {
+            return released;
+        };
+        while self.buffer.peek().is_some_and(|next| next.time <= watermark) {
+            if let Some(next) = self.buffer.pop() {
+                self.released_up_to = Some(next.time);
+                released.push(next.message);
+            }
+        }
+        released
+    }
+}
+
+/// An iterator over several streams of decoded messages merged in time order, returned by `merge_streams`.
+///
+/// The stream whose latest message is oldest is always read next, so no more is read ahead than the reordering window
+/// needs.
+pub struct MergedStream<I: Iterator<Item = AcarsVdlm2Message>> {
+    streams: Vec<Option<I>>,
+    merger: StreamMerger,
+    ready: VecDeque<AcarsVdlm2Message>,
+}
+
+impl<I: Iterator<Item = AcarsVdlm2Message>> MergedStream<I> {
+    /// Returns the merger, for its counts of late and untimed messages.
+    pub fn merger(&self) -> &StreamMerger {
+        &self.merger
+    }
+
+    /// Returns the open stream that has read least far, preferring one that has not read a timed message yet.
+    fn next_source(&self) -> Option<usize> {
+        self.streams.iter().enumerate()
+            .filter(|(_, stream)| stream.is_some())
+            .map(|(source, _)| source)
+            .min_by(|first, second| {
+                let time = |source: &usize| self.merger.latest[*source].unwrap_or(f64::NEG_INFINITY);
+                time(first).total_cmp(&time(second))
+            })
+    }
+}
+
+impl<I: Iterator<Item = AcarsVdlm2Message>> Iterator for MergedStream<I> {
+    type Item = AcarsVdlm2Message;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.ready.is_empty() {
+            let Some(source) = self.next_source() else {
+                self.ready.extend(self.merger.flush());
+                break;
+            };
+            let next: Option<AcarsVdlm2Message> = self.streams[source].as_mut().and_then(Iterator::next);
+            let released: Vec<AcarsVdlm2Message> = match next {
+                Some(message) => self.merger.push(source, message),
+                None => {
+                    self.streams[source] = None;
+                    self.merger.close(source)
+                }
+            };
+            self.ready.extend(released);
+        }
+        self.ready.pop_front()
+    }
+}
+
+/// Merges several streams of decoded messages, such as captures from different stations or a `Receiver` per feeder,
+/// into a single stream ordered by message time within a reordering window, see `StreamMerger`.
+///
+/// Each stream is read until it ends, so a `Receiver` only ends the merge once its senders are dropped.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::merge::{merge_streams, DEFAULT_REORDER_WINDOW_SECS};
+/// let decode = |times: &[f64]| -> Vec<AcarsVdlm2Message> {
+///     times.iter().map(|time| format!(r#"{{"freq":131.55,"timestamp":{}}}"#, time).decode_message().unwrap()).collect()
+/// };
+/// let merged: Vec<f64> = merge_streams(vec![decode(&[1.0, 4.0, 3.5, 7.0]), decode(&[2.0, 3.0, 8.0])], DEFAULT_REORDER_WINDOW_SECS)
+///     .map(|message| message.get_time().unwrap())
+///     .collect();
+/// assert_eq!(merged, vec![1.0, 2.0, 3.0, 3.5, 4.0, 7.0, 8.0]);
+/// ```
+pub fn merge_streams<S: IntoIterator<Item = AcarsVdlm2Message>>(streams: impl IntoIterator<Item = S>, reorder_window_secs: f64) -> MergedStream<S::IntoIter> {
+    let streams: Vec<Option<S::IntoIter>> = streams.into_iter().map(|stream| Some(stream.into_iter())).collect();
+    MergedStream { merger: StreamMerger::new(streams.len(), reorder_window_secs), streams, ready: VecDeque::new() }
+}
@@ -0,0 +1,182 @@
+use std::io::{self, ErrorKind, Write};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::envelope::OutputMode;
+
+/// Buffered bytes at which `NdjsonWriter` writes the buffer out to the underlying writer.
+pub const DEFAULT_FLUSH_THRESHOLD_BYTES: usize = 64 * 1024;
+/// Buffered bytes at which `NdjsonWriter` reports `WriteStatus::Backpressure`.
+pub const DEFAULT_HIGH_WATER_BYTES: usize = 1024 * 1024;
+
+/// Whether an `NdjsonWriter` can take more messages, returned by every write and flush.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteStatus {
+    /// Fewer bytes than the high water mark are buffered.
+    Ready,
+    /// The underlying writer is not keeping up and at least the high water mark is buffered. The caller should stop
+    /// feeding messages, or drop them, until a `flush` returns `Ready` again.
+    Backpressure,
+}
+
+/// Writes decoded messages as newline delimited JSON, serialising each one straight into a single reused buffer, so a
+/// sink does not allocate a `String` or `Vec<u8>` for every message.
+///
+/// The buffer is written out to the underlying writer once it reaches the flush threshold. A non-blocking writer, such
+/// as a `TcpStream` set to non-blocking, may take only part of the buffer or return `WouldBlock`; whatever it does not
+/// take stays buffered for the next write or flush, and once the buffer reaches the high water mark every call
+/// returns `WriteStatus::Backpressure`. Messages are never dropped by the writer itself. Lines still buffered when the
+/// `NdjsonWriter` is dropped are lost, so call `flush` or `into_inner` first.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, MessageFamily};
+/// use acars_vdlm2_parser::ndjson::{NdjsonWriter, WriteStatus};
+/// let mut writer: NdjsonWriter<Vec<u8>> = NdjsonWriter::new(Vec::new()).flush_threshold(1024);
+/// let message: AcarsVdlm2Message = AcarsVdlm2Message::example(MessageFamily::Acars);
+/// assert_eq!(writer.write(&message).unwrap(), WriteStatus::Ready);
+/// assert_eq!(writer.write(&message).unwrap(), WriteStatus::Ready);
+/// assert_eq!(writer.written(), 2);
+/// let output: Vec<u8> = writer.into_inner().unwrap();
+/// assert_eq!(output, [message.to_bytes_newline().unwrap(), message.to_bytes_newline().unwrap()].concat());
+/// ```
+#[derive(Debug)]
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+    flush_threshold: usize,
+    high_water: usize,
+    output_mode: OutputMode,
+    written: u64,
+    bytes_flushed: u64,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    /// Creates an `NdjsonWriter` around the writer, with `DEFAULT_FLUSH_THRESHOLD_BYTES`, `DEFAULT_HIGH_WATER_BYTES`
+    /// and messages written as the decoder wrote them.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::with_capacity(DEFAULT_FLUSH_THRESHOLD_BYTES),
+            flush_threshold: DEFAULT_FLUSH_THRESHOLD_BYTES,
+            high_water: DEFAULT_HIGH_WATER_BYTES,
+            output_mode: OutputMode::Plain,
+            written: 0,
+            bytes_flushed: 0,
+        }
+    }
+
+    /// Sets the buffered bytes at which the buffer is written out. 0 writes out every message as it is written.
+    pub fn flush_threshold(mut self, bytes: usize) -> Self {
+        self.flush_threshold = bytes;
+        self
+    }
+
+    /// Sets the buffered bytes at which `WriteStatus::Backpressure` is reported.
+    pub fn high_water_mark(mut self, bytes: usize) -> Self {
+        self.high_water = bytes;
+        self
+    }
+
+    /// Sets whether messages are written as the decoder wrote them or wrapped in an `Envelope`.
+    pub fn output_mode(mut self, output_mode: OutputMode) -> Self {
+        self.output_mode = output_mode;
+        self
+    }
+
+    /// Serialises the message as a line of JSON into the buffer, writing the buffer out if it has reached the flush
+    /// threshold.
+    ///
+    /// A message that fails to serialise leaves nothing in the buffer. Errors from the underlying writer, other than
+    /// `WouldBlock` and `Interrupted`, are returned, and the bytes it did not take stay buffered.
+    pub fn write(&mut self, message: &AcarsVdlm2Message) -> MessageResult<WriteStatus> {
+        let start: usize = self.buffer.len();
+        if let Err(serialise_error) = self.output_mode.write_newline(message, &mut self.buffer) {
+            self.buffer.truncate(start);
+            return Err(serialise_error);
+        }
+        self.written += 1;
+        if self.buffer.len() >= self.flush_threshold {
+            self.write_buffer()?;
+        }
+        Ok(self.status())
+    }
+
+    /// Writes out as much of the buffer as the underlying writer takes, then flushes it if the buffer was emptied.
+    pub fn flush(&mut self) -> MessageResult<WriteStatus> {
+        self.write_buffer()?;
+        if self.buffer.is_empty() {
+            match self.writer.flush() {
+                Err(flush_error) if !is_retryable(&flush_error) => return Err(serde_json::Error::io(flush_error)),
+                _ => {}
+            }
+        }
+        Ok(self.status())
+    }
+
+    /// Returns `WriteStatus::Backpressure` if at least the high water mark is buffered.
+    pub fn status(&self) -> WriteStatus {
+        match self.buffer.len() >= self.high_water {
+            true => WriteStatus::Backpressure,
+            false => WriteStatus::Ready,
+        }
+    }
+
+    /// Number of bytes waiting to be written out.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of messages written so far, including those still buffered.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Number of bytes the underlying writer has taken so far.
+    pub fn bytes_flushed(&self) -> u64 {
+        self.bytes_flushed
+    }
+
+    /// Returns the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns the underlying writer mutably. Writing to it directly can interleave with buffered lines.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Flushes the buffer and returns the underlying writer. It is an error if the writer did not take the whole
+    /// buffer.
+    pub fn into_inner(mut self) -> MessageResult<W> {
+        self.flush()?;
+        match self.buffer.is_empty() {
+            true => Ok(self.writer),
+            false => Err(serde_json::Error::io(io::Error::new(ErrorKind::WouldBlock,
+                format!("{} buffered bytes could not be written out", self.buffer.len())))),
+        }
+    }
+
+    /// Writes the buffer to the underlying writer until it is empty or the writer would block, removing what was taken.
+    fn write_buffer(&mut self) -> MessageResult<()> {
+        let mut taken: usize = 0;
+        let result: io::Result<()> = loop {
+            if taken == self.buffer.len() {
+                break Ok(());
+            }
+            match self.writer.write(&self.buffer[taken..]) {
+                Ok(0) => break Err(io::Error::from(ErrorKind::WriteZero)),
+                Ok(bytes) => taken += bytes,
+                Err(write_error) if write_error.kind() == ErrorKind::Interrupted => continue,
+                Err(write_error) if write_error.kind() == ErrorKind::WouldBlock => break Ok(()),
+                Err(write_error) => break Err(write_error),
+            }
+        };
+        self.buffer.drain(..taken);
+        self.bytes_flushed += taken as u64;
+        result.map_err(serde_json::Error::io)
+    }
+}
+
+fn is_retryable(error: &io::Error) -> bool {
+    matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::Interrupted)
+}
@@ -0,0 +1,94 @@
+use std::borrow::Cow;
+use crate::AcarsVdlm2Message;
+use crate::transform::{Transform, TransformOutcome};
+
+/// Cleans up ACARS message text so it can be loaded into CSV files or databases that do not cope with control characters.
+///
+/// Line endings, `\r\n` or a lone `\r`, are always normalised to `\n` and NUL characters are always removed.
+/// Optionally every other non printable character, apart from `\n` and `\t`, is escaped as `\xNN`,
+/// and typographic quotes are replaced with their ASCII equivalents.
+///
+/// This covers the ACARS text of every message type, `text` for ACARS and `msg_text` for VDLM2 and HFDL.
+/// ```
+/// use acars_vdlm2_parser::normalise::TextNormaliser;
+/// let normaliser: TextNormaliser = TextNormaliser::new().escape_non_printable(true).ascii_quotes(true);
+/// assert_eq!(normaliser.normalise("POS\r\nN1234\0\u{3}"), "POS\nN1234\\x03");
+/// assert_eq!(normaliser.normalise("\u{201C}ETA\u{201D}"), "\"ETA\"");
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct TextNormaliser {
+    escape_non_printable: bool,
+    ascii_quotes: bool,
+}
+
+impl TextNormaliser {
+    /// Creates a `TextNormaliser` that only normalises line endings and removes NUL characters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether non printable characters are escaped as `\xNN`.
+    pub fn escape_non_printable(mut self, escape_non_printable: bool) -> Self {
+        self.escape_non_printable = escape_non_printable;
+        self
+    }
+
+    /// Sets whether typographic single and double quotes are replaced with `'` and `"`.
+    pub fn ascii_quotes(mut self, ascii_quotes: bool) -> Self {
+        self.ascii_quotes = ascii_quotes;
+        self
+    }
+
+    fn needs_change(&self, character: char) -> bool {
+        match character {
+            '\r' | '\0' => true,
+            '\n' | '\t' => false,
+            '\u{2018}' | '\u{2019}' | '\u{201C}' | '\u{201D}' => self.ascii_quotes,
+            character => self.escape_non_printable && character.is_control(),
+        }
+    }
+
+    /// Returns the normalised text, only allocating when something needs to change.
+    pub fn normalise<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !text.chars().any(|character| self.needs_change(character)) {
+            return Cow::Borrowed(text);
+        }
+        let mut normalised: String = String::with_capacity(text.len());
+        let mut characters = text.chars().peekable();
+        while let Some(character) = characters.next() {
+            match character {
+                '\0' => {}
+                '\r' if characters.peek() == Some(&'\n') => {}
+                '\r' => normalised.push('\n'),
+                '\u{2018}' | '\u{2019}' if self.ascii_quotes => normalised.push('\''),
+                '\u{201C}' | '\u{201D}' if self.ascii_quotes => normalised.push('"'),
+                character if self.needs_change(character) => normalised.push_str(&format!("\\x{:02X}", character as u32)),
+                character => normalised.push(character),
+            }
+        }
+        Cow::Owned(normalised)
+    }
+
+    /// Normalises the text in place, leaving it untouched when nothing needs to change.
+    pub fn normalise_in_place(&self, text: &mut String) {
+        if let Cow::Owned(normalised) = self.normalise(text) {
+            *text = normalised;
+        }
+    }
+
+    /// Normalises every text field in the provided message.
+    pub fn apply_to(&self, message: &mut AcarsVdlm2Message) {
+        message.normalise_text(self);
+    }
+}
+
+impl Transform for TextNormaliser {
+    fn name(&self) -> &str {
+        "text_normaliser"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        self.apply_to(message);
+        TransformOutcome::Forward
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvError, SyncSender};
+use std::thread::{self, JoinHandle};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, DecodeMessage};
+use crate::dead_letter::{attempted_families, FailedDecode};
+use crate::validate::ValidationHooks;
+
+/// Inputs a `DecodePool` queues for its workers, and decoded messages it queues for the consumer, by default.
+const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// An input waiting for a worker, with its sequence number.
+type QueuedInput = (u64, Vec<u8>);
+
+/// The order a `DecodePool` returns decoded messages in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputOrder {
+    /// As soon as each message is decoded, which gives the highest throughput.
+    #[default]
+    Completion,
+    /// The same order the inputs were submitted in, holding back messages that finish ahead of earlier ones.
+    Submission,
+}
+
+/// Settings for a `DecodePool`.
+#[derive(Debug, Clone)]
+pub struct DecodePoolConfig {
+    workers: usize,
+    queue_capacity: usize,
+    order: OutputOrder,
+    source: Option<String>,
+    validation: Option<Arc<ValidationHooks>>,
+}
+
+impl Default for DecodePoolConfig {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            order: OutputOrder::default(),
+            source: None,
+            validation: None,
+        }
+    }
+}
+
+impl DecodePoolConfig {
+    /// Creates a config with a worker per available CPU, returning messages in completion order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of worker threads, of which there is always at least one.
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Sets how many inputs and how many decoded messages can be queued before `submit` blocks.
+    pub fn queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity.max(1);
+        self
+    }
+
+    /// Sets the order decoded messages are returned in.
+    pub fn order(mut self, order: OutputOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the source given to the `FailedDecode` of every input that fails to decode.
+    pub fn source(mut self, source: &str) -> Self {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    /// Sets validation hooks the workers run against every message that decodes. Rejected messages are returned as a
+    /// `FailedDecode` with the code `message.rejected`, and counted by the hooks.
+    pub fn validation(mut self, validation: Arc<ValidationHooks>) -> Self {
+        self.validation = Some(validation);
+        self
+    }
+}
+
+/// The result of decoding one input submitted to a `DecodePool`.
+#[derive(Debug, Clone)]
+pub struct DecodedInput {
+    /// The number `DecodePool::submit` returned for the input, counting from 0.
+    pub sequence: u64,
+    pub result: Result<AcarsVdlm2Message, FailedDecode>,
+}
+
+struct OutputQueue {
+    receiver: Receiver<DecodedInput>,
+    held_back: BTreeMap<u64, DecodedInput>,
+    next_sequence: u64,
+}
+
+/// Decodes raw lines or frames on a pool of worker threads, for the common topology of one or more producers reading
+/// from sockets and a consumer handling the decoded messages.
+///
+/// Both queues are bounded, so `submit` blocks when the workers fall behind, and the workers block when the consumer
+/// falls behind, rather than memory growing without limit. The pool can be shared between producer and consumer
+/// threads behind an `Arc`. Closing the pool stops new inputs being accepted; every input already submitted is still
+/// decoded and returned. Dropping the pool waits for the workers to finish.
+/// ```
+/// use acars_vdlm2_parser::pool::{DecodePool, DecodePoolConfig, DecodedInput, OutputOrder};
+/// let pool: DecodePool = DecodePool::new(DecodePoolConfig::new().workers(2).order(OutputOrder::Submission));
+/// pool.submit(br#"{"freq":131.55,"channel":2}"#.to_vec()).unwrap();
+/// pool.submit(b"not json".to_vec()).unwrap();
+/// let decoded: Vec<DecodedInput> = pool.shutdown();
+/// assert_eq!(decoded.iter().map(|input| input.sequence).collect::<Vec<u64>>(), vec![0, 1]);
+/// assert!(decoded[0].result.is_ok());
+/// assert_eq!(decoded[1].result.as_ref().unwrap_err().raw, "not json");
+/// ```
+pub struct DecodePool {
+    input: Mutex<Option<SyncSender<QueuedInput>>>,
+    output: Mutex<OutputQueue>,
+    submitted: AtomicU64,
+    order: OutputOrder,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl DecodePool {
+    /// Starts the worker threads.
+    pub fn new(config: DecodePoolConfig) -> Self {
+        let (input_sender, input_receiver) = mpsc::sync_channel::<QueuedInput>(config.queue_capacity);
+        let (output_sender, output_receiver) = mpsc::sync_channel::<DecodedInput>(config.queue_capacity);
+        let input_receiver: Arc<Mutex<Receiver<QueuedInput>>> = Arc::new(Mutex::new(input_receiver));
+        let workers: Vec<JoinHandle<()>> = (0..config.workers)
+            .map(|_| {
+                let input_receiver: Arc<Mutex<Receiver<QueuedInput>>> = Arc::clone(&input_receiver);
+                let output_sender: SyncSender<DecodedInput> = output_sender.clone();
+                let source: Option<String> = config.source.clone();
+                let validation: Option<Arc<ValidationHooks>> = config.validation.clone();
+                thread::spawn(move || run_worker(&input_receiver, &output_sender, source.as_deref(), validation.as_deref()))
+            })
+            .collect();
+        Self {
+            input: Mutex::new(Some(input_sender)),
+            output: Mutex::new(OutputQueue { receiver: output_receiver, held_back: BTreeMap::new(), next_sequence: 0 }),
+            submitted: AtomicU64::new(0),
+            order: config.order,
+            workers,
+        }
+    }
+
+    /// Queues an input to be decoded, blocking while the input queue is full, and returns its sequence number.
+    ///
+    /// The input is handed back if the pool has been closed.
+    pub fn submit(&self, input: Vec<u8>) -> Result<u64, Vec<u8>> {
+        let Some(sender) = lock(&self.input).clone() else {
+            return Err(input);
+        };
+        let sequence: u64 = self.submitted.fetch_add(1, Ordering::Relaxed);
+        sender.send((sequence, input)).map_err(|failed| failed.0.1)?;
+        Ok(sequence)
+    }
+
+    /// Returns the next decoded input, blocking until there is one.
+    ///
+    /// Returns `None` once the pool is closed and every submitted input has been returned.
+    pub fn recv(&self) -> Option<DecodedInput> {
+        let mut output: MutexGuard<OutputQueue> = lock(&self.output);
+        if self.order == OutputOrder::Completion {
+            return output.receiver.recv().ok();
+        }
+        loop {
+            let next_sequence: u64 = output.next_sequence;
+            if let Some(decoded) = output.held_back.remove(&next_sequence) {
+                output.next_sequence += 1;
+                return Some(decoded);
+            }
+            match output.receiver.recv() {
+                Ok(decoded) if decoded.sequence == next_sequence => {
+                    output.next_sequence += 1;
+                    return Some(decoded);
+                }
+                Ok(decoded) => {
+                    output.held_back.insert(decoded.sequence, decoded);
+                }
+                // The sequence numbers of inputs that were handed back by `submit` are never filled, so return the rest.
+                Err(_) => return output.held_back.pop_first().map(|(_, decoded)| decoded),
+            }
+        }
+    }
+
+    /// Returns an iterator over the decoded inputs, which ends once the pool is closed and every input has been returned.
+    pub fn results(&self) -> impl Iterator<Item = DecodedInput> + '_ {
+        std::iter::from_fn(|| self.recv())
+    }
+
+    /// Stops accepting new inputs. Inputs already submitted are still decoded.
+    pub fn close(&self) {
+        lock(&self.input).take();
+    }
+
+    /// Closes the pool and returns every decoded input not yet returned, once the workers have finished.
+    pub fn shutdown(mut self) -> Vec<DecodedInput> {
+        self.close();
+        let remaining: Vec<DecodedInput> = self.results().collect();
+        self.join_workers();
+        remaining
+    }
+
+    fn join_workers(&mut self) {
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for DecodePool {
+    fn drop(&mut self) {
+        self.close();
+        // Keep draining so workers blocked on a full output queue can finish.
+        while self.recv().is_some() {}
+        self.join_workers();
+    }
+}
+
+fn run_worker(
+    input_receiver: &Mutex<Receiver<QueuedInput>>,
+    output_sender: &SyncSender<DecodedInput>,
+    source: Option<&str>,
+    validation: Option<&ValidationHooks>,
+) {
+    loop {
+        // The lock is released before decoding so the other workers can take the next input.
+        let received: Result<QueuedInput, RecvError> = lock(input_receiver).recv();
+        let Ok((sequence, input)) = received else {
+            return;
+        };
+        let result: Result<AcarsVdlm2Message, FailedDecode> = input.as_slice().decode_message()
+            .map_err(|error| FailedDecode::new(&String::from_utf8_lossy(&input), &error, &attempted_families(&input), source))
+            .and_then(|message| match validation.map_or(Ok(()), |hooks| hooks.check(&message)) {
+                Ok(()) => Ok(message),
+                Err(reason) => Err(FailedDecode::rejected(&String::from_utf8_lossy(&input), &reason, message.get_family(), source)),
+            });
+        if output_sender.send(DecodedInput { sequence, result }).is_err() {
+            return;
+        }
+    }
+}
+
+/// Locks the mutex, carrying on if another thread panicked while holding it as the queues are always left consistent.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,26 @@
+//! Re-exports the traits, message types and error types most downstream code needs.
+//!
+//! Importing from here rather than the individual modules means code keeps compiling if items move between modules.
+//! ```
+//! use acars_vdlm2_parser::prelude::*;
+//! let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.decode_message().unwrap();
+//! assert_eq!(message.get_family(), MessageFamily::Acars);
+//! let acars: MessageResult<AcarsMessage> = r#"{"freq":131.55,"channel":2}"#.to_acars();
+//! assert!(acars.is_ok());
+//! ```
+
+pub use crate::{decode_bytes, AcarsVdlm2Message, AppDetails, DecodeMessage, ExpectedMessageType, MessageFamily, MessageResult};
+#[cfg(feature = "acars")]
+pub use crate::acars::{AcarsMessage, NewAcarsMessage};
+#[cfg(feature = "vdlm2")]
+pub use crate::vdlm2::{NewVdlm2Message, Vdlm2Message};
+#[cfg(feature = "hfdl")]
+pub use crate::hfdl::{HfdlMessage, NewHfdlMessage};
+#[cfg(feature = "uat")]
+pub use crate::uat::{NewUatMessage, UatMessage};
+pub use crate::dead_letter::FailedDecode;
+pub use crate::quality::FrameQuality;
+pub use crate::source::SourceKind;
+pub use crate::transform::{Transform, TransformOutcome, TransformPipeline};
+#[allow(deprecated)]
+pub use crate::{DecodedMessage, ReceivedMessage};
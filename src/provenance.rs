@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use crate::provider;
+
+/// Key a `Provenance` is stored under by `TaggedMessage`, alongside the top level keys of the wrapped message.
+pub const PROVENANCE_KEY: &str = "x_provenance";
+
+/// A transform that was applied to a message, with what it did to it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ProvenanceStep {
+    /// Name of the transform, as returned by `Transform::name`.
+    pub transform: String,
+    /// When the transform was applied, in seconds since the Unix epoch.
+    pub applied_at: f64,
+    /// Paths of the fields the transform added, changed or removed, in the same form as
+    /// `AcarsVdlm2Message::get_field`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<String>,
+    /// Reason the transform dropped the message, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dropped: Option<String>,
+}
+
+/// The transforms applied to a message in this process, in the order they ran, for working out which stage of a
+/// multi-stage pipeline modified a field, such as which one overwrote the `app` details.
+///
+/// Serialised as a list of `ProvenanceStep`. Each process a message passes through adds its own steps after those it
+/// was received with when the message is carried in a `TaggedMessage`.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::provenance::Provenance;
+/// use acars_vdlm2_parser::redact::StationRedactor;
+/// use acars_vdlm2_parser::transform::{TransformOutcome, TransformPipeline};
+/// let mut pipeline: TransformPipeline = TransformPipeline::new();
+/// pipeline.add(StationRedactor::new("salt"));
+/// let mut message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"station_id":"MN-YPPH"}"#.decode_message().unwrap();
+/// let mut provenance: Provenance = Provenance::new();
+/// assert_eq!(pipeline.apply_recording(&mut message, &mut provenance), TransformOutcome::Forward);
+/// assert_eq!(provenance.transforms(), vec!["station_redactor"]);
+/// assert_eq!(provenance.last_change("station_id").map(|step| step.transform.as_str()), Some("station_redactor"));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Provenance {
+    pub steps: Vec<ProvenanceStep>,
+}
+
+impl Provenance {
+    /// Creates an empty `Provenance`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step for a transform applied now.
+    pub fn record(&mut self, transform: &str, changed: Vec<String>, dropped: Option<String>) {
+        self.steps.push(ProvenanceStep { transform: transform.to_string(), applied_at: provider::now(), changed, dropped });
+    }
+
+    /// Names of the transforms applied, in the order they ran.
+    pub fn transforms(&self) -> Vec<&str> {
+        self.steps.iter().map(|step| step.transform.as_str()).collect()
+    }
+
+    /// Returns the last step that added, changed or removed the field, or one of the fields beneath it.
+    pub fn last_change(&self, path: &str) -> Option<&ProvenanceStep> {
+        self.steps.iter().rev().find(|step| step.changed.iter().any(|changed| {
+            changed == path || changed.strip_prefix(path).is_some_and(|rest| rest.starts_with('.'))
+        }))
+    }
+
+    /// Number of steps recorded.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns true if no steps have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
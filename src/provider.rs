@@ -0,0 +1,191 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+thread_local! {
+    /// Providers installed on this thread by `Providers::scope`, innermost last.
+    static INSTALLED: RefCell<Vec<Providers>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Source of the UUIDs this library stamps on messages, such as the `acars_router_uuid` of `AppDetails`.
+///
+/// Implemented for closures returning a `Uuid`.
+pub trait UuidProvider: Send + Sync {
+    fn new_uuid(&self) -> Uuid;
+}
+
+impl<F: Fn() -> Uuid + Send + Sync> UuidProvider for F {
+    fn new_uuid(&self) -> Uuid {
+        self()
+    }
+}
+
+/// Source of the current time, in seconds since the Unix epoch, for ingest timestamps, dead letters, sampling and
+/// future timestamp checks.
+///
+/// Implemented for closures returning an `f64`.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> f64;
+}
+
+impl<F: Fn() -> f64 + Send + Sync> Clock for F {
+    fn now(&self) -> f64 {
+        self()
+    }
+}
+
+/// Random version 4 UUIDs, the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomUuids;
+
+impl UuidProvider for RandomUuids {
+    fn new_uuid(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// UUIDs counting up from a starting value, `00000000-0000-0000-0000-000000000001` and so on, for snapshots that
+/// need a different UUID each time but the same ones on every run.
+#[derive(Debug, Default)]
+pub struct SequentialUuids {
+    next: AtomicU64,
+}
+
+impl SequentialUuids {
+    /// Creates a provider whose first UUID is `first`.
+    pub fn starting_at(first: u64) -> Self {
+        Self { next: AtomicU64::new(first) }
+    }
+}
+
+impl UuidProvider for SequentialUuids {
+    fn new_uuid(&self) -> Uuid {
+        Uuid::from_u128(u128::from(self.next.fetch_add(1, Ordering::Relaxed)))
+    }
+}
+
+/// The system clock, the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs_f64())
+            .unwrap_or_default()
+    }
+}
+
+/// A clock that always reads the same time.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FixedClock(pub f64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> f64 {
+        self.0
+    }
+}
+
+/// The UUID provider and clock used wherever this library generates a UUID or reads the time, so test suites can pin
+/// the `acars_router_uuid` stamped by `set_proxy_details`, `AppDetails::new`, `AppDetailsTemplate::new` and
+/// `RouterConfig::new`, and the times read by `MessageTimestamp::ingested_now`, `FailedDecode`, `Sampler` and
+/// `ProcessingContext`.
+///
+/// The defaults are `RandomUuids` and `SystemClock`. Other providers are installed for the duration of a closure with
+/// `scope`, on the calling thread only, so tests running in parallel do not see each other's providers. Work moved to
+/// other threads, such as `TransformPipeline::apply_batch` with the `rayon` feature, uses the defaults.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, MessageFamily};
+/// use acars_vdlm2_parser::provider::{FixedClock, Providers, SequentialUuids};
+/// use acars_vdlm2_parser::timestamp::MessageTimestamp;
+/// let providers: Providers = Providers::new().with_uuids(SequentialUuids::starting_at(1)).with_clock(FixedClock(1700000000.0));
+/// let mut message: AcarsVdlm2Message = AcarsVdlm2Message::example(MessageFamily::Acars);
+/// message.clear_proxy_details();
+/// providers.scope(|| {
+///     message.set_proxy_details("acars_router", "1.0.0");
+///     assert_eq!(MessageTimestamp::ingested_now().seconds, 1700000000.0);
+/// });
+/// let uuid: Option<&str> = message.get_app_details().and_then(|app| app.acars_router_uuid.as_deref());
+/// assert_eq!(uuid, Some("00000000-0000-0000-0000-000000000001"));
+/// ```
+#[derive(Clone)]
+pub struct Providers {
+    uuids: Arc<dyn UuidProvider>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for Providers {
+    fn default() -> Self {
+        Self { uuids: Arc::new(RandomUuids), clock: Arc::new(SystemClock) }
+    }
+}
+
+impl fmt::Debug for Providers {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_struct("Providers").finish_non_exhaustive()
+    }
+}
+
+impl Providers {
+    /// Creates `Providers` with random UUIDs and the system clock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the source of UUIDs.
+    pub fn with_uuids(mut self, uuids: impl UuidProvider + 'static) -> Self {
+        self.uuids = Arc::new(uuids);
+        self
+    }
+
+    /// Sets the clock.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Returns a new UUID from these providers.
+    pub fn new_uuid(&self) -> Uuid {
+        self.uuids.new_uuid()
+    }
+
+    /// Returns the current time from these providers.
+    pub fn now(&self) -> f64 {
+        self.clock.now()
+    }
+
+    /// Runs the closure with these providers installed on the calling thread, restoring the previous ones afterwards,
+    /// even if the closure panics.
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        struct Uninstall;
+        impl Drop for Uninstall {
+            fn drop(&mut self) {
+                INSTALLED.with(|installed| installed.borrow_mut().pop());
+            }
+        }
+        INSTALLED.with(|installed| installed.borrow_mut().push(self.clone()));
+        let _uninstall: Uninstall = Uninstall;
+        f()
+    }
+}
+
+/// Returns a new UUID from the providers installed on this thread, or a random one.
+pub fn new_uuid() -> Uuid {
+    match INSTALLED.with(|installed| installed.borrow().last().cloned()) {
+        Some(providers) => providers.new_uuid(),
+        None => RandomUuids.new_uuid(),
+    }
+}
+
+/// Returns the current time, in seconds since the Unix epoch, from the providers installed on this thread, or from
+/// the system clock.
+pub fn now() -> f64 {
+    match INSTALLED.with(|installed| installed.borrow().last().cloned()) {
+        Some(providers) => providers.now(),
+        None => SystemClock.now(),
+    }
+}
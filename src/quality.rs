@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+
+/// Integrity of a received frame, derived from the error and correction details reported by the decoder.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameQuality {
+    /// The frame was received without any reported bit errors.
+    Ok,
+    /// The frame had bit errors that were corrected by the decoder.
+    Corrected,
+    /// The frame, or a block within it, failed its integrity check and likely contains garbled data.
+    Failed,
+}
+
+/// Running totals of `FrameQuality` across every message recorded, for use by receiver tuning tools.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::quality::{FrameQuality, FrameQualityCounter};
+/// let mut counter: FrameQualityCounter = FrameQualityCounter::default();
+/// let clean: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"error":0}"#.decode_message().unwrap();
+/// let corrected: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"error":2}"#.decode_message().unwrap();
+/// assert_eq!(counter.record(&clean), FrameQuality::Ok);
+/// assert_eq!(counter.record(&corrected), FrameQuality::Corrected);
+/// assert_eq!((counter.ok, counter.corrected, counter.failed), (1, 1, 0));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct FrameQualityCounter {
+    pub ok: u64,
+    pub corrected: u64,
+    pub failed: u64,
+}
+
+impl FrameQualityCounter {
+    /// Records the quality of the provided message and returns it.
+    pub fn record(&mut self, message: &AcarsVdlm2Message) -> FrameQuality {
+        let quality: FrameQuality = message.get_frame_quality();
+        self.record_quality(quality);
+        quality
+    }
+
+    /// Records an already determined `FrameQuality`.
+    pub fn record_quality(&mut self, quality: FrameQuality) {
+        match quality {
+            FrameQuality::Ok => self.ok += 1,
+            FrameQuality::Corrected => self.corrected += 1,
+            FrameQuality::Failed => self.failed += 1,
+        }
+    }
+
+    /// Total number of frames recorded.
+    pub fn total(&self) -> u64 {
+        self.ok + self.corrected + self.failed
+    }
+
+    /// Fraction of recorded frames that needed correction or failed, between `0.0` and `1.0`.
+    pub fn error_rate(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => (self.corrected + self.failed) as f64 / total as f64,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, DecodeMessage, MessageFamily, MessageResult};
+use crate::provider;
+
+/// Seconds it takes for the weight of a message in the averages to halve, unless set with `RateTracker::half_life_secs`.
+pub const DEFAULT_HALF_LIFE_SECS: f64 = 60.0;
+
+/// Throughput of a family, station or the whole feed at the time of a `RateSnapshot`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rate {
+    /// Exponentially weighted moving average of the messages per second.
+    pub messages_per_sec: f64,
+    /// Exponentially weighted moving average of the bytes per second.
+    pub bytes_per_sec: f64,
+    /// Messages counted since the tracker was created or reset.
+    pub messages: u64,
+    /// Bytes counted since the tracker was created or reset.
+    pub bytes: u64,
+}
+
+impl Rate {
+    /// The average messages per minute, as feeder status pages show it.
+    pub fn messages_per_minute(&self) -> f64 {
+        self.messages_per_sec * 60.0
+    }
+}
+
+/// The throughput of a feed at one time, broken down by family and by station.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RateSnapshot {
+    /// Time of the snapshot in seconds since the Unix epoch.
+    pub at: f64,
+    pub total: Rate,
+    pub by_family: BTreeMap<MessageFamily, Rate>,
+    pub by_station: BTreeMap<String, Rate>,
+}
+
+/// Running averages for one family, station or the whole feed.
+#[derive(Debug, Clone, Copy, Default)]
+struct Ewma {
+    messages_per_sec: f64,
+    bytes_per_sec: f64,
+    messages: u64,
+    bytes: u64,
+    updated: f64,
+}
+
+impl Ewma {
+    /// Decays the averages to the time, weighting each message by `1 / time_constant` so a steady feed averages out at
+    /// its rate.
+    fn record(&mut self, bytes: usize, now: f64, time_constant: f64) {
+        let decay: f64 = self.decay(now, time_constant);
+        self.messages_per_sec = self.messages_per_sec * decay + 1.0 / time_constant;
+        self.bytes_per_sec = self.bytes_per_sec * decay + bytes as f64 / time_constant;
+        self.messages += 1;
+        self.bytes += bytes as u64;
+        self.updated = self.updated.max(now);
+    }
+
+    fn decay(&self, now: f64, time_constant: f64) -> f64 {
+        match self.messages {
+            0 => 0.0,
+            _ => (-(now - self.updated).max(0.0) / time_constant).exp(),
+        }
+    }
+
+    fn rate(&self, now: f64, time_constant: f64) -> Rate {
+        let decay: f64 = self.decay(now, time_constant);
+        Rate {
+            messages_per_sec: self.messages_per_sec * decay,
+            bytes_per_sec: self.bytes_per_sec * decay,
+            messages: self.messages,
+            bytes: self.bytes,
+        }
+    }
+}
+
+/// Tracks the messages and bytes per second of a feed, in total, per family and per station, as exponentially
+/// weighted moving averages that are cheap enough to update on every decode.
+///
+/// A message counts for less the longer ago it arrived, halving every half life, so the averages follow changes in
+/// the feed without keeping any history. They are taken at the arrival time read from `provider::now`, not the
+/// timestamp of the message, so a feed replayed from a capture does not skew them. See `Windower` for counts by
+/// message timestamp.
+/// ```
+/// use acars_vdlm2_parser::MessageFamily;
+/// use acars_vdlm2_parser::provider::{FixedClock, Providers};
+/// use acars_vdlm2_parser::rate::{RateSnapshot, RateTracker};
+/// let mut tracker: RateTracker = RateTracker::new();
+/// for second in 0..600 {
+///     Providers::new().with_clock(FixedClock(second as f64)).scope(|| {
+///         tracker.decode(r#"{"freq":131.55,"channel":2,"station_id":"EXAMPLE-STATION"}"#).unwrap();
+///     });
+/// }
+/// let snapshot: RateSnapshot = tracker.snapshot_at(600.0);
+/// assert!((snapshot.total.messages_per_minute() - 60.0).abs() < 1.0);
+/// assert_eq!(snapshot.by_family[&MessageFamily::Acars].messages, 600);
+/// assert!(tracker.snapshot_at(1200.0).total.messages_per_sec < 0.01);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateTracker {
+    time_constant: f64,
+    total: Ewma,
+    families: HashMap<MessageFamily, Ewma>,
+    stations: HashMap<String, Ewma>,
+}
+
+impl Default for RateTracker {
+    fn default() -> Self {
+        Self {
+            time_constant: DEFAULT_HALF_LIFE_SECS / std::f64::consts::LN_2,
+            total: Ewma::default(),
+            families: HashMap::new(),
+            stations: HashMap::new(),
+        }
+    }
+}
+
+impl RateTracker {
+    /// Creates a new `RateTracker` with nothing counted and a half life of `DEFAULT_HALF_LIFE_SECS`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the seconds it takes for the weight of a message in the averages to halve. Shorter half lives follow
+    /// bursts more closely, longer ones give steadier figures.
+    pub fn half_life_secs(mut self, seconds: f64) -> Self {
+        self.time_constant = seconds / std::f64::consts::LN_2;
+        self
+    }
+
+    /// Decodes a message, counting it and its size if it decodes.
+    pub fn decode(&mut self, input: &str) -> MessageResult<AcarsVdlm2Message> {
+        let message: AcarsVdlm2Message = input.decode_message()?;
+        self.record(&message, input.len());
+        Ok(message)
+    }
+
+    /// Counts a message that arrived now, along with the size it was received as.
+    pub fn record(&mut self, message: &AcarsVdlm2Message, bytes: usize) {
+        self.record_at(message, bytes, provider::now());
+    }
+
+    /// Counts a message that arrived at the time, in seconds since the Unix epoch.
+    pub fn record_at(&mut self, message: &AcarsVdlm2Message, bytes: usize, now: f64) {
+        let time_constant: f64 = self.time_constant;
+        self.total.record(bytes, now, time_constant);
+        self.families.entry(message.get_family()).or_default().record(bytes, now, time_constant);
+        if let Some(station) = message.get_station_name() {
+            match self.stations.get_mut(station) {
+                Some(ewma) => ewma.record(bytes, now, time_constant),
+                None => self.stations.entry(station.to_string()).or_default().record(bytes, now, time_constant),
+            }
+        }
+    }
+
+    /// Returns the throughput as of now.
+    pub fn snapshot(&self) -> RateSnapshot {
+        self.snapshot_at(provider::now())
+    }
+
+    /// Returns the throughput as of the time, in seconds since the Unix epoch.
+    pub fn snapshot_at(&self, now: f64) -> RateSnapshot {
+        RateSnapshot {
+            at: now,
+            total: self.total.rate(now, self.time_constant),
+            by_family: self.families.iter().map(|(family, ewma)| (*family, ewma.rate(now, self.time_constant))).collect(),
+            by_station: self.stations.iter().map(|(station, ewma)| (station.clone(), ewma.rate(now, self.time_constant))).collect(),
+        }
+    }
+
+    /// Clears every count and average.
+    pub fn reset(&mut self) {
+        self.total = Ewma::default();
+        self.families.clear();
+        self.stations.clear();
+    }
+}
@@ -0,0 +1,311 @@
+use std::io::{self, ErrorKind, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::envelope::OutputMode;
+
+/// Time a TCP client has to accept a message before it is disconnected, so one slow client cannot hold up the rest.
+pub const DEFAULT_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Messages queued for a TCP client before it is taken to have fallen behind and is disconnected.
+pub const DEFAULT_CLIENT_QUEUE_LEN: usize = 1024;
+/// How often a TCP listener checks whether it has been stopped while no clients are connecting.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Decides whether a message is sent to an output. Shared by every client of a TCP listener.
+pub type MessageFilter = Arc<dyn Fn(&AcarsVdlm2Message) -> bool + Send + Sync>;
+
+/// Counts of what happened to a single message passed to `Rebroadcaster::broadcast`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct BroadcastStats {
+    /// UDP targets the message was sent to.
+    pub udp_sent: usize,
+    /// TCP clients the message was queued for, each written to on its own thread.
+    pub tcp_sent: usize,
+    /// Outputs whose filter did not accept the message.
+    pub filtered: usize,
+    /// UDP sends that failed.
+    pub udp_failed: usize,
+    /// TCP clients disconnected because writing to them failed or timed out, or because they fell so far behind that
+    /// their queue was full.
+    pub tcp_disconnected: usize,
+}
+
+/// A UDP destination messages are sent to.
+struct UdpTarget {
+    address: SocketAddr,
+    filter: Option<MessageFilter>,
+}
+
+/// A serialised message, shared by every client it is queued for.
+type Line = Arc<Vec<u8>>;
+
+/// A connected TCP client, written to on its own thread from a queue, with the filter of the listener it connected to.
+struct TcpClient {
+    stream: TcpStream,
+    sender: SyncSender<Line>,
+    filter: Option<MessageFilter>,
+}
+
+/// How a listener sets up the clients it accepts.
+#[derive(Clone)]
+struct ClientOptions {
+    filter: Option<MessageFilter>,
+    write_timeout: Duration,
+    queue_len: usize,
+}
+
+/// A TCP listener accepting clients on its own thread.
+struct Listener {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Re-emits decoded messages as newline delimited JSON to UDP targets and to clients of TCP listeners, a minimal
+/// acars_router core for simple deployments and for testing code that reads a stream of messages.
+///
+/// Each UDP target and TCP listener can have a `MessageFilter`, so different consumers can be sent different traffic.
+/// Each message is serialised once, in the `OutputMode` set, however many outputs it is sent to. TCP clients are
+/// accepted on a thread per listener, which is stopped when the `Rebroadcaster` is dropped. Each client is written to
+/// on its own thread from a queue, so `broadcast` never waits on a slow client, and a client whose queue fills up is
+/// disconnected.
+/// ```
+/// use std::net::UdpSocket;
+/// use std::sync::Arc;
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+/// use acars_vdlm2_parser::rebroadcast::{BroadcastStats, Rebroadcaster};
+/// let receiver: UdpSocket = UdpSocket::bind("127.0.0.1:0").unwrap();
+/// let mut rebroadcaster: Rebroadcaster = Rebroadcaster::new();
+/// rebroadcaster.add_udp_target(receiver.local_addr().unwrap(), Some(Arc::new(|message: &AcarsVdlm2Message| {
+///     message.get_family() == MessageFamily::Acars
+/// }))).unwrap();
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.decode_message().unwrap();
+/// let stats: BroadcastStats = rebroadcaster.broadcast(&message).unwrap();
+/// assert_eq!(stats.udp_sent, 1);
+/// let mut buffer: [u8; 1500] = [0; 1500];
+/// let length: usize = receiver.recv(&mut buffer).unwrap();
+/// assert_eq!(&buffer[..length], b"{\"freq\":131.55,\"channel\":2}\n");
+/// ```
+pub struct Rebroadcaster {
+    output_mode: OutputMode,
+    write_timeout: Duration,
+    client_queue_len: usize,
+    udp_v4: Option<UdpSocket>,
+    udp_v6: Option<UdpSocket>,
+    udp_targets: Vec<UdpTarget>,
+    clients: Arc<Mutex<Vec<TcpClient>>>,
+    listeners: Vec<Listener>,
+}
+
+impl Default for Rebroadcaster {
+    fn default() -> Self {
+        Self {
+            output_mode: OutputMode::default(),
+            write_timeout: DEFAULT_WRITE_TIMEOUT,
+            client_queue_len: DEFAULT_CLIENT_QUEUE_LEN,
+            udp_v4: None,
+            udp_v6: None,
+            udp_targets: Vec::new(),
+            clients: Arc::new(Mutex::new(Vec::new())),
+            listeners: Vec::new(),
+        }
+    }
+}
+
+impl Rebroadcaster {
+    /// Creates a `Rebroadcaster` with no outputs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether messages are written as decoded or wrapped in an envelope, see `OutputMode`.
+    pub fn output_mode(mut self, output_mode: OutputMode) -> Self {
+        self.output_mode = output_mode;
+        self
+    }
+
+    /// Sets the time a TCP client has to accept each message, see `DEFAULT_WRITE_TIMEOUT`.
+    pub fn write_timeout(mut self, write_timeout: Duration) -> Self {
+        self.write_timeout = write_timeout;
+        self
+    }
+
+    /// Sets how many messages may be queued for a TCP client before it is disconnected, see `DEFAULT_CLIENT_QUEUE_LEN`.
+    pub fn client_queue_len(mut self, client_queue_len: usize) -> Self {
+        self.client_queue_len = client_queue_len.max(1);
+        self
+    }
+
+    /// Sends every message accepted by the filter to the UDP address, one message per datagram.
+    pub fn add_udp_target(&mut self, address: impl ToSocketAddrs, filter: Option<MessageFilter>) -> io::Result<()> {
+        let address: SocketAddr = address.to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to send to"))?;
+        let socket: &mut Option<UdpSocket> = match address {
+            SocketAddr::V4(_) => &mut self.udp_v4,
+            SocketAddr::V6(_) => &mut self.udp_v6,
+        };
+        if socket.is_none() {
+            let unspecified: IpAddr = match address {
+                SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            };
+            *socket = Some(UdpSocket::bind((unspecified, 0))?);
+        }
+        debug!("Rebroadcasting to UDP {}", address);
+        self.udp_targets.push(UdpTarget { address, filter });
+        Ok(())
+    }
+
+    /// Listens for TCP clients on the address, sending each of them every message accepted by the filter, and returns
+    /// the address listened on, which gives the port chosen when listening on port 0.
+    pub fn listen_tcp(&mut self, address: impl ToSocketAddrs, filter: Option<MessageFilter>) -> io::Result<SocketAddr> {
+        let listener: TcpListener = TcpListener::bind(address)?;
+        let address: SocketAddr = listener.local_addr()?;
+        // Polled rather than blocking in `accept`, so the thread sees it has been stopped without being woken.
+        listener.set_nonblocking(true)?;
+        let stop: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let clients: Arc<Mutex<Vec<TcpClient>>> = Arc::clone(&self.clients);
+        let options: ClientOptions = ClientOptions { filter, write_timeout: self.write_timeout, queue_len: self.client_queue_len };
+        let handle: JoinHandle<()> = {
+            let stop: Arc<AtomicBool> = Arc::clone(&stop);
+            thread::Builder::new()
+                .name(format!("rebroadcast {}", address))
+                .spawn(move || accept_clients(&listener, &stop, &clients, &options))?
+        };
+        debug!("Rebroadcasting to TCP clients of {}", address);
+        self.listeners.push(Listener { stop, handle: Some(handle) });
+        Ok(address)
+    }
+
+    /// Number of TCP clients connected.
+    pub fn tcp_clients(&self) -> usize {
+        lock(&self.clients).len()
+    }
+
+    /// Sends the message to every output whose filter accepts it, disconnecting any TCP client that cannot keep up.
+    ///
+    /// Messages are only queued for TCP clients, so this does not wait for them to be written. Only a failure to
+    /// serialise the message is an error; failures to send are counted in the returned stats.
+    pub fn broadcast(&self, message: &AcarsVdlm2Message) -> MessageResult<BroadcastStats> {
+        let mut stats: BroadcastStats = BroadcastStats::default();
+        let accepts = |filter: &Option<MessageFilter>| filter.as_ref().is_none_or(|filter| filter(message));
+        let mut line: Option<Line> = None;
+        for target in &self.udp_targets {
+            if !accepts(&target.filter) {
+                stats.filtered += 1;
+                continue;
+            }
+            let bytes: &Line = match &mut line {
+                Some(bytes) => bytes,
+                None => line.insert(Arc::new(self.output_mode.to_bytes_newline(message)?)),
+            };
+            let socket: Option<&UdpSocket> = match target.address {
+                SocketAddr::V4(_) => self.udp_v4.as_ref(),
+                SocketAddr::V6(_) => self.udp_v6.as_ref(),
+            };
+            match socket.map(|socket| socket.send_to(bytes, target.address)) {
+                Some(Ok(_)) => stats.udp_sent += 1,
+                _ => stats.udp_failed += 1,
+            }
+        }
+        let mut clients: MutexGuard<Vec<TcpClient>> = lock(&self.clients);
+        let mut index: usize = 0;
+        while index < clients.len() {
+            if !accepts(&clients[index].filter) {
+                stats.filtered += 1;
+                index += 1;
+                continue;
+            }
+            let bytes: &Line = match &mut line {
+                Some(bytes) => bytes,
+                None => line.insert(Arc::new(self.output_mode.to_bytes_newline(message)?)),
+            };
+            match clients[index].sender.try_send(Arc::clone(bytes)) {
+                Ok(()) => {
+                    stats.tcp_sent += 1;
+                    index += 1;
+                }
+                Err(send_error) => {
+                    let reason: &str = match send_error {
+                        TrySendError::Full(_) => "its queue is full",
+                        TrySendError::Disconnected(_) => "writing to it failed",
+                    };
+                    debug!("Disconnecting TCP client {:?}, {}", clients[index].stream.peer_addr(), reason);
+                    let _ = clients.swap_remove(index).stream.shutdown(Shutdown::Both);
+                    stats.tcp_disconnected += 1;
+                }
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// Stops the listener threads. Clients are sent whatever is already queued for them and then disconnected.
+impl Drop for Rebroadcaster {
+    fn drop(&mut self) {
+        for listener in &self.listeners {
+            listener.stop.store(true, Ordering::SeqCst);
+        }
+        for listener in &mut self.listeners {
+            if let Some(handle) = listener.handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+/// Accepts clients until stopped, adding them to the shared list.
+fn accept_clients(listener: &TcpListener, stop: &AtomicBool, clients: &Mutex<Vec<TcpClient>>, options: &ClientOptions) {
+    while !stop.load(Ordering::SeqCst) {
+        let stream: TcpStream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(accept_error) => {
+                if accept_error.kind() != ErrorKind::WouldBlock {
+                    warn!("Failed to accept a TCP client: {}", accept_error);
+                }
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+                continue;
+            }
+        };
+        match start_client(stream, options) {
+            Ok(client) => {
+                debug!("Accepted TCP client {:?}", client.stream.peer_addr());
+                lock(clients).push(client);
+            }
+            Err(setup_error) => warn!("Failed to set up a TCP client: {}", setup_error),
+        }
+    }
+}
+
+/// Sets up an accepted client and starts the thread that writes its queue to it.
+fn start_client(stream: TcpStream, options: &ClientOptions) -> io::Result<TcpClient> {
+    // Accepted sockets inherit non-blocking mode from the listener on some platforms.
+    stream.set_nonblocking(false)?;
+    stream.set_write_timeout(Some(options.write_timeout))?;
+    stream.set_nodelay(true)?;
+    let writer: TcpStream = stream.try_clone()?;
+    let (sender, receiver): (SyncSender<Line>, Receiver<Line>) = mpsc::sync_channel(options.queue_len);
+    thread::Builder::new()
+        .name(format!("rebroadcast client {:?}", stream.peer_addr()))
+        .spawn(move || write_client(writer, receiver))?;
+    Ok(TcpClient { stream, sender, filter: options.filter.clone() })
+}
+
+/// Writes every queued line to the client until the queue is closed or a write fails, which drops the queue so the
+/// next `broadcast` disconnects the client.
+fn write_client(mut writer: TcpStream, receiver: Receiver<Line>) {
+    for line in receiver {
+        if let Err(write_error) = writer.write_all(&line) {
+            debug!("Writing to TCP client {:?} failed: {}", writer.peer_addr(), write_error);
+            return;
+        }
+    }
+}
+
+fn lock(clients: &Mutex<Vec<TcpClient>>) -> MutexGuard<'_, Vec<TcpClient>> {
+    clients.lock().unwrap_or_else(PoisonError::into_inner)
+}
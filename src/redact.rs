@@ -0,0 +1,220 @@
+use hmac_sha256::HMAC;
+use crate::AcarsVdlm2Message;
+use crate::icao::parse_icao_hex;
+use crate::transform::{Transform, TransformOutcome};
+
+/// Rounds of the Feistel network in `keyed_permutation`, well above the 4 needed for a pseudorandom permutation.
+const FEISTEL_ROUNDS: u8 = 8;
+
+/// Replaces the fields identifying a receiving or ground station with stable pseudonyms.
+///
+/// The same input and salt always produce the same pseudonym, across runs and across machines,
+/// so data shared publicly can still be grouped per station without revealing which station it is.
+/// Pseudonyms keep the shape of the original field, so AVLC addresses stay 6 hex characters and
+/// ARINC 622 ground station addresses stay 7 letters. They are built from HMAC-SHA256 keyed with the salt, and
+/// addresses and HFDL ids are mapped through a keyed permutation, so two stations never share a pseudonym and the salt
+/// cannot be recovered from known stations and their pseudonyms.
+///
+/// This covers the station name for every message type, the ARINC 622 `gs_addr`, for VDLM2
+/// the AVLC source/destination addresses of ground stations and the ground station lists in XID parameters,
+/// and for HFDL the id and name of every ground station in the frame.
+/// ```
+/// use acars_vdlm2_parser::redact::StationRedactor;
+/// let redactor: StationRedactor = StationRedactor::new("my salt");
+/// assert_eq!(redactor.pseudonym("MN-YPPH"), redactor.pseudonym("MN-YPPH"));
+/// assert_ne!(redactor.pseudonym("MN-YPPH"), StationRedactor::new("other salt").pseudonym("MN-YPPH"));
+/// assert_eq!(redactor.pseudonym_address("10214A").len(), 6);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StationRedactor {
+    salt: String,
+}
+
+impl StationRedactor {
+    /// Creates a new `StationRedactor` using the provided salt for all pseudonyms.
+    pub fn new(salt: &str) -> Self {
+        Self { salt: salt.to_string() }
+    }
+
+    /// Returns the pseudonym used for station names.
+    pub fn pseudonym(&self, value: &str) -> String {
+        format!("STATION-{:08X}", prf_u64(&self.salt, "station", value.as_bytes()) as u32)
+    }
+
+    /// Returns the pseudonym used for 24 bit hex addresses, such as AVLC ground station addresses.
+    ///
+    /// A value that is not 6 hex characters is still replaced, keyed on the value as written.
+    pub fn pseudonym_address(&self, value: &str) -> String {
+        match parse_icao_hex(value) {
+            Some(address) => format!("{:06X}", keyed_permutation(&self.salt, "address", address, 24)),
+            None => format!("{:06X}", prf_u64(&self.salt, "address_text", value.as_bytes()) & 0x00FF_FFFF),
+        }
+    }
+
+    /// Returns the pseudonym used for numbered ground stations, such as HFDL ground station ids.
+    pub fn pseudonym_id(&self, value: u16) -> u16 {
+        keyed_permutation(&self.salt, "id", u32::from(value), 16) as u16
+    }
+
+    /// Returns the pseudonym used for 7 letter ARINC 622 ground station addresses.
+    pub fn pseudonym_gs_addr(&self, value: &str) -> String {
+        let mut hash: u64 = prf_u64(&self.salt, "gs_addr", value.as_bytes());
+        (0..7).map(|_| {
+            let letter: char = (b'A' + (hash % 26) as u8) as char;
+            hash /= 26;
+            letter
+        }).collect()
+    }
+
+    /// Redacts every station identifying field in the provided message.
+    pub fn redact(&self, message: &mut AcarsVdlm2Message) {
+        message.redact_ground_stations(self);
+    }
+}
+
+impl Transform for StationRedactor {
+    fn name(&self) -> &str {
+        "station_redactor"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        self.redact(message);
+        TransformOutcome::Forward
+    }
+}
+
+/// Replaces the fields identifying an aircraft, its 24 bit ICAO address and its registration, with stable pseudonyms.
+///
+/// Pseudonyms depend only on the key and the aircraft, not on the message family, so the same aircraft heard on ACARS,
+/// VDLM2, HFDL and UAT keeps one pseudonym across all of them, and across any other tool using the same key. The ICAO
+/// address is keyed on its 6 hex character form, whether a message writes it as hex or as a number, and the
+/// registration is keyed without its leading dots and dash, so `.N123AB` and `N123AB` map to the same pseudonym.
+/// Pseudonyms keep the shape of the original: addresses stay 24 bit, and registrations keep their length, leading dots,
+/// dash and which characters are letters or digits. They are built from HMAC-SHA256 keyed with the key, and addresses
+/// are mapped through a keyed permutation, so two aircraft never share a pseudonym and the key cannot be recovered from
+/// known aircraft and their pseudonyms.
+///
+/// This covers the ICAO address and registration of every message type, including the aircraft addresses of VDLM2
+/// AVLC frames, the HFDL `ac_info` blocks, the ARINC 622 air address and ADS-C airframe ID of VDLM2 and HFDL, the UAT
+/// address and the `aircraft_info` block when the `enrichment` feature is enabled. It does not rewrite registrations
+/// written into the free message text.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, MessageFamily};
+/// use acars_vdlm2_parser::redact::AircraftAnonymiser;
+/// let anonymiser: AircraftAnonymiser = AircraftAnonymiser::new("shared key");
+/// assert_eq!(format!("{:06X}", anonymiser.pseudonym_icao(0xA1B2C3)), anonymiser.pseudonym_icao_hex("a1b2c3"));
+/// assert_eq!(anonymiser.pseudonym_registration(".N123AB").trim_start_matches('.'), anonymiser.pseudonym_registration("N123AB"));
+/// let mut acars: AcarsVdlm2Message = AcarsVdlm2Message::example(MessageFamily::Acars);
+/// let mut vdlm2: AcarsVdlm2Message = AcarsVdlm2Message::example(MessageFamily::Vdlm2);
+/// anonymiser.anonymise(&mut acars);
+/// anonymiser.anonymise(&mut vdlm2);
+/// assert_ne!(acars.get_registration(), Some("N123AB"));
+/// assert_eq!(acars.get_registration(), vdlm2.get_registration());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AircraftAnonymiser {
+    key: String,
+}
+
+impl AircraftAnonymiser {
+    /// Creates a new `AircraftAnonymiser` using the provided key for all pseudonyms.
+    pub fn new(key: &str) -> Self {
+        Self { key: key.to_string() }
+    }
+
+    /// Returns the pseudonym for a 24 bit ICAO address.
+    pub fn pseudonym_icao(&self, icao: u32) -> u32 {
+        keyed_permutation(&self.key, "icao", icao & 0x00FF_FFFF, 24)
+    }
+
+    /// Returns the pseudonym for an ICAO address written as hex, as 6 uppercase hex characters.
+    ///
+    /// A value that is not a valid address is still replaced, keyed on the value as written.
+    pub fn pseudonym_icao_hex(&self, icao: &str) -> String {
+        match parse_icao_hex(icao) {
+            Some(icao) => format!("{:06X}", self.pseudonym_icao(icao)),
+            None => format!("{:06X}", prf_u64(&self.key, "icao_text", icao.as_bytes()) & 0x00FF_FFFF),
+        }
+    }
+
+    /// Returns the pseudonym for a registration, keeping its length, leading dots, dash and the positions of letters
+    /// and digits.
+    pub fn pseudonym_registration(&self, registration: &str) -> String {
+        let trimmed: &str = registration.trim_start_matches('.');
+        let keyed_on: String = trimmed.replace('-', "").to_uppercase();
+        let mut block: u64 = 0;
+        let mut counter: u32 = 0;
+        let mut next = |range: u8| -> u8 {
+            // Each character takes 16 bits of a fresh 64 bit block, so the bias of the modulo stays negligible.
+            if counter.is_multiple_of(4) {
+                block = prf_u64(&self.key, "registration", &[&(counter / 4).to_be_bytes(), keyed_on.as_bytes()].concat());
+            }
+            counter += 1;
+            let value: u8 = ((block & 0xFFFF) % range as u64) as u8;
+            block >>= 16;
+            value
+        };
+        let pseudonym: String = trimmed.chars().map(|character| match character {
+            character if character.is_ascii_alphabetic() => (b'A' + next(26)) as char,
+            character if character.is_ascii_digit() => (b'0' + next(10)) as char,
+            character => character,
+        }).collect();
+        format!("{}{}", &registration[..registration.len() - trimmed.len()], pseudonym)
+    }
+
+    /// Anonymises every aircraft identifying field in the provided message.
+    pub fn anonymise(&self, message: &mut AcarsVdlm2Message) {
+        message.anonymise_aircraft(self);
+    }
+}
+
+impl Transform for AircraftAnonymiser {
+    fn name(&self) -> &str {
+        "aircraft_anonymiser"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        self.anonymise(message);
+        TransformOutcome::Forward
+    }
+}
+
+/// The first 64 bits of HMAC-SHA256 of the purpose and value under the key.
+///
+/// The purpose keeps the pseudonyms of different fields independent of each other, and the output is stable across
+/// releases and machines, unlike the standard library hasher.
+fn prf_u64(key: &str, purpose: &str, value: &[u8]) -> u64 {
+    finish_prf(keyed_prf(key, purpose), value)
+}
+
+/// HMAC-SHA256 keyed with the key and fed the purpose, ready for the value, see `prf_u64`.
+fn keyed_prf(key: &str, purpose: &str) -> HMAC {
+    let mut hmac: HMAC = HMAC::new(key);
+    hmac.update(purpose);
+    hmac.update([0]);
+    hmac
+}
+
+/// Feeds the value to a `keyed_prf` and returns the first 64 bits of the MAC.
+fn finish_prf(mut hmac: HMAC, value: &[u8]) -> u64 {
+    hmac.update(value);
+    let mac: [u8; 32] = hmac.finalize();
+    u64::from_be_bytes([mac[0], mac[1], mac[2], mac[3], mac[4], mac[5], mac[6], mac[7]])
+}
+
+/// Keyed permutation of `bits` wide values, for an even `bits` of at most 32.
+///
+/// This is a balanced Feistel network with `prf_u64` as its round function, so every input maps to a different output
+/// of the same width and the mapping cannot be inverted without the key.
+fn keyed_permutation(key: &str, purpose: &str, value: u32, bits: u32) -> u32 {
+    let keyed: HMAC = keyed_prf(key, purpose);
+    let half: u32 = bits / 2;
+    let mask: u32 = (1 << half) - 1;
+    let (mut left, mut right): (u32, u32) = ((value >> half) & mask, value & mask);
+    for round in 0..FEISTEL_ROUNDS {
+        let mixed: u32 = left ^ (finish_prf(keyed.clone(), &[&[round][..], &right.to_be_bytes()].concat()) as u32 & mask);
+        left = right;
+        right = mixed;
+    }
+    (left << half) | right
+}
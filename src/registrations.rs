@@ -0,0 +1,154 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, Write};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageFamily, MessageResult};
+
+/// A registration seen with an ICAO address, with how strongly the observations support the pairing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegistrationMapping {
+    /// ICAO address as 6 uppercase hex characters.
+    pub icao: String,
+    pub registration: String,
+    /// Number of messages that carried both the address and the registration.
+    pub observations: u64,
+    /// Message families the pairing was seen in.
+    pub families: BTreeSet<MessageFamily>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_seen: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<f64>,
+    /// From 0 to 1, see `RegistrationTable` for how it is worked out.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PairStats {
+    observations: u64,
+    families: BTreeSet<MessageFamily>,
+    first_seen: Option<f64>,
+    last_seen: Option<f64>,
+}
+
+/// Builds a table of the registration and ICAO address pairs seen across every message family during a run,
+/// the raw material for community aircraft databases.
+///
+/// Registrations are compared ignoring case and the leading dots ACARS pads them with. Each pairing gets a confidence
+/// from 0 to 1: the share of the observations of its address and of its registration that agree with it, taking the
+/// lower of the two, scaled by `observations / (observations + 1)` so a pairing seen once is never fully trusted.
+/// Conflicting pairings, such as a garbled address, therefore pull each other's confidence down.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::registrations::{RegistrationMapping, RegistrationTable};
+/// let mut table: RegistrationTable = RegistrationTable::new();
+/// for _ in 0..3 {
+///     let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"tail":".VH-ABC","icao":8131124}"#.decode_message().unwrap();
+///     table.observe(&message);
+/// }
+/// let mappings: Vec<RegistrationMapping> = table.mappings();
+/// assert_eq!((mappings[0].icao.as_str(), mappings[0].registration.as_str()), ("7C1234", "VH-ABC"));
+/// assert_eq!(mappings[0].confidence, 0.75);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RegistrationTable {
+    pairs: HashMap<(u32, String), PairStats>,
+    icao_observations: HashMap<u32, u64>,
+    registration_observations: HashMap<String, u64>,
+}
+
+impl RegistrationTable {
+    /// Creates a new, empty `RegistrationTable`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the registration and ICAO address pairing in the message, returning false if it does not carry both.
+    pub fn observe(&mut self, message: &AcarsVdlm2Message) -> bool {
+        let registration: String = match message.get_registration().map(normalise_registration) {
+            Some(registration) if !registration.is_empty() => registration,
+            _ => return false,
+        };
+        let Some(icao) = message.get_icao() else {
+            return false;
+        };
+        *self.icao_observations.entry(icao).or_default() += 1;
+        *self.registration_observations.entry(registration.clone()).or_default() += 1;
+        let stats: &mut PairStats = self.pairs.entry((icao, registration)).or_default();
+        stats.observations += 1;
+        stats.families.insert(message.get_family());
+        if let Some(time) = message.get_time() {
+            stats.first_seen = Some(stats.first_seen.map_or(time, |first_seen| first_seen.min(time)));
+            stats.last_seen = Some(stats.last_seen.map_or(time, |last_seen| last_seen.max(time)));
+        }
+        true
+    }
+
+    /// Returns the number of distinct pairings seen.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns true if no pairings have been seen.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Returns every pairing seen, ordered by address and then registration.
+    pub fn mappings(&self) -> Vec<RegistrationMapping> {
+        let mut mappings: Vec<RegistrationMapping> = self.pairs.iter()
+            .map(|((icao, registration), stats)| {
+                let icao_total: u64 = self.icao_observations.get(icao).copied().unwrap_or(stats.observations);
+                let registration_total: u64 = self.registration_observations.get(registration).copied().unwrap_or(stats.observations);
+                let agreement: f64 = stats.observations as f64 / icao_total.max(registration_total) as f64;
+                let evidence: f64 = stats.observations as f64 / (stats.observations + 1) as f64;
+                RegistrationMapping {
+                    icao: format!("{:06X}", icao),
+                    registration: registration.clone(),
+                    observations: stats.observations,
+                    families: stats.families.clone(),
+                    first_seen: stats.first_seen,
+                    last_seen: stats.last_seen,
+                    confidence: agreement * evidence,
+                }
+            })
+            .collect();
+        mappings.sort_by(|first, second| (&first.icao, &first.registration).cmp(&(&second.icao, &second.registration)));
+        mappings
+    }
+
+    /// Writes the mappings as a JSON array.
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(writer, &self.mappings())
+    }
+
+    /// Writes the mappings as CSV with a header row, listing the families separated by `;`.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "icao,registration,observations,families,first_seen,last_seen,confidence")?;
+        for mapping in self.mappings() {
+            let families: Vec<String> = mapping.families.iter().map(|family| family.to_string()).collect();
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{:.3}",
+                mapping.icao,
+                csv_field(&mapping.registration),
+                mapping.observations,
+                families.join(";"),
+                mapping.first_seen.map(|time| format!("{:.3}", time)).unwrap_or_default(),
+                mapping.last_seen.map(|time| format!("{:.3}", time)).unwrap_or_default(),
+                mapping.confidence,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn normalise_registration(registration: &str) -> String {
+    registration.trim().trim_start_matches('.').to_ascii_uppercase()
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+pub(crate) fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+use crate::transform::{Transform, TransformOutcome};
+
+/// A field a `RequiredFields` policy can require every message to carry.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum RequiredField {
+    /// The name of the station that received the message, as returned by `get_station_name`.
+    Station,
+    /// The time the message was received, as returned by `get_time`.
+    Time,
+    /// The `app` block naming the decoder, as returned by `get_app_details`.
+    App,
+}
+
+impl RequiredField {
+    /// Reason given when a message is dropped for not carrying this field, such as `missing_station`.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            RequiredField::Station => "missing_station",
+            RequiredField::Time => "missing_time",
+            RequiredField::App => "missing_app",
+        }
+    }
+}
+
+impl fmt::Display for RequiredField {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequiredField::Station => write!(formatter, "station"),
+            RequiredField::Time => write!(formatter, "time"),
+            RequiredField::App => write!(formatter, "app"),
+        }
+    }
+}
+
+/// A strict mode `Transform` dropping messages that do not carry a station, time or app block, for aggregators that
+/// require every forwarded message to say where and when it was received.
+///
+/// Nothing is required by default. A station name that is empty or only whitespace counts as missing. A dropped
+/// message is given the `reason` of the first missing field, checked in the order station, time, app, so consumers
+/// can tell the rejections apart. It can be read from a configuration file, with the fields left out not required.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::required::{RequiredField, RequiredFields};
+/// use acars_vdlm2_parser::transform::{Transform, TransformOutcome};
+/// let policy: RequiredFields = serde_json::from_str(r#"{"station":true,"time":true}"#).unwrap();
+/// let mut complete: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"station_id":"MN-YPPH","timestamp":1700000000.25}"#.decode_message().unwrap();
+/// let mut untimed: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"station_id":"MN-YPPH"}"#.decode_message().unwrap();
+/// assert_eq!(policy.apply(&mut complete), TransformOutcome::Forward);
+/// assert_eq!(policy.check(&untimed), Err(RequiredField::Time));
+/// assert_eq!(policy.apply(&mut untimed), TransformOutcome::Drop("missing_time".to_string()));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[serde(default)]
+pub struct RequiredFields {
+    pub station: bool,
+    pub time: bool,
+    pub app: bool,
+}
+
+impl RequiredFields {
+    /// Creates a `RequiredFields` requiring nothing, which lets every message through.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `RequiredFields` requiring a station, time and app block.
+    pub fn all() -> Self {
+        Self { station: true, time: true, app: true }
+    }
+
+    /// Sets whether a station name is required.
+    pub fn station(mut self, required: bool) -> Self {
+        self.station = required;
+        self
+    }
+
+    /// Sets whether a time is required.
+    pub fn time(mut self, required: bool) -> Self {
+        self.time = required;
+        self
+    }
+
+    /// Sets whether an app block is required.
+    pub fn app(mut self, required: bool) -> Self {
+        self.app = required;
+        self
+    }
+
+    /// Returns every required field the message does not carry, in the order station, time, app.
+    pub fn missing(&self, message: &AcarsVdlm2Message) -> Vec<RequiredField> {
+        let mut missing: Vec<RequiredField> = Vec::new();
+        if self.station && message.get_station_name().is_none_or(|station| station.trim().is_empty()) {
+            missing.push(RequiredField::Station);
+        }
+        if self.time && message.get_time().is_none() {
+            missing.push(RequiredField::Time);
+        }
+        if self.app && message.get_app_details().is_none() {
+            missing.push(RequiredField::App);
+        }
+        missing
+    }
+
+    /// Checks the message carries every required field, returning the first one missing if not.
+    pub fn check(&self, message: &AcarsVdlm2Message) -> Result<(), RequiredField> {
+        match self.missing(message).first() {
+            Some(field) => Err(*field),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Transform for RequiredFields {
+    fn name(&self) -> &str {
+        "required_fields"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        match self.check(message) {
+            Ok(()) => TransformOutcome::Forward,
+            Err(field) => TransformOutcome::Drop(field.reason().to_string()),
+        }
+    }
+}
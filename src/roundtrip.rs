@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use crate::{AcarsVdlm2Message, MessageFamily};
+
+/// How a field differs between the original input and the re-encoded message.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DifferenceKind {
+    /// The field was in the input but is missing from the re-encoded message, so it would be lost when forwarding.
+    Lost,
+    /// The field was not in the input but is in the re-encoded message.
+    Added,
+    /// The field is in both but its value or type changed.
+    Changed,
+}
+
+/// A single field that did not survive decoding and re-encoding unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldDifference {
+    /// Dot separated path to the field, in the same form as `AcarsVdlm2Message::get_field`.
+    pub path: String,
+    pub kind: DifferenceKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reencoded: Option<Value>,
+}
+
+/// Result of `roundtrip_check`, listing every field that was lost, added or changed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RoundtripReport {
+    /// Family the input decoded as, or `None` if it failed to decode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<MessageFamily>,
+    /// Why the input could not be checked, if it could not be parsed, decoded or re-encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub differences: Vec<FieldDifference>,
+}
+
+impl RoundtripReport {
+    /// Returns true if the input decoded and re-encoded without any field being lost, added or changed.
+    pub fn is_lossless(&self) -> bool {
+        self.error.is_none() && self.differences.is_empty()
+    }
+
+    /// Returns the differences of the provided kind.
+    pub fn of_kind(&self, kind: DifferenceKind) -> impl Iterator<Item = &FieldDifference> {
+        self.differences.iter().filter(move |difference| difference.kind == kind)
+    }
+
+    pub(crate) fn failed(error: String) -> Self {
+        Self { error: Some(error), ..Default::default() }
+    }
+}
+
+/// Decodes the input, re-encodes it, and reports every field that differs between the two, for catching silent data
+/// loss in a feed before it reaches production.
+///
+/// The comparison is done on the parsed JSON, so key order and whitespace are ignored, and numbers are equal when
+/// their values are, so `1` and `1.0` do not count as a change. A number written as a string and re-encoded as a
+/// number does count as a change.
+/// ```
+/// use acars_vdlm2_parser::roundtrip::{roundtrip_check, DifferenceKind, RoundtripReport};
+/// let report: RoundtripReport = roundtrip_check(r#"{"freq":131.55,"channel":2,"label":"Q0"}"#);
+/// assert!(report.is_lossless());
+/// let report: RoundtripReport = roundtrip_check(r#"{"freq":131.55,"channel":"2","unknown_field":true}"#);
+/// let paths: Vec<(&str, DifferenceKind)> = report.differences.iter().map(|difference| (difference.path.as_str(), difference.kind)).collect();
+/// assert_eq!(paths, vec![("channel", DifferenceKind::Changed), ("unknown_field", DifferenceKind::Lost)]);
+/// ```
+pub fn roundtrip_check(input: &str) -> RoundtripReport {
+    let original: Value = match serde_json::from_str(input) {
+        Err(parse_error) => return RoundtripReport::failed(format!("input is not valid JSON: {}", parse_error)),
+        Ok(original) => original,
+    };
+    let message: AcarsVdlm2Message = match serde_json::from_value(original.clone()) {
+        Err(decode_error) => return RoundtripReport::failed(format!("decode failed: {}", decode_error)),
+        Ok(message) => message,
+    };
+    let reencoded: Value = match serde_json::to_value(&message) {
+        Err(encode_error) => return RoundtripReport::failed(format!("serialisation failed: {}", encode_error)),
+        Ok(reencoded) => reencoded,
+    };
+    let mut report: RoundtripReport = RoundtripReport { family: Some(message.get_family()), ..Default::default() };
+    diff_values("", &original, &reencoded, &mut report.differences);
+    report
+}
+
+fn join_path(parent: &str, segment: &str) -> String {
+    match parent.is_empty() {
+        true => segment.to_string(),
+        false => format!("{}.{}", parent, segment),
+    }
+}
+
+fn numbers_equal(original: &Number, reencoded: &Number) -> bool {
+    match (original.as_u64(), reencoded.as_u64(), original.as_i64(), reencoded.as_i64()) {
+        (Some(original), Some(reencoded), _, _) => original == reencoded,
+        (_, _, Some(original), Some(reencoded)) => original == reencoded,
+        _ => original.as_f64() == reencoded.as_f64(),
+    }
+}
+
+pub(crate) fn diff_values(path: &str, original: &Value, reencoded: &Value, differences: &mut Vec<FieldDifference>) {
+    match (original, reencoded) {
+        (Value::Object(original_map), Value::Object(reencoded_map)) => {
+            for (key, original_value) in original_map {
+                let child: String = join_path(path, key);
+                match reencoded_map.get(key) {
+                    Some(reencoded_value) => diff_values(&child, original_value, reencoded_value, differences),
+                    None => differences.push(FieldDifference { path: child, kind: DifferenceKind::Lost, original: Some(original_value.clone()), reencoded: None }),
+                }
+            }
+            for (key, reencoded_value) in reencoded_map.iter().filter(|(key, _)| !original_map.contains_key(*key)) {
+                differences.push(FieldDifference { path: join_path(path, key), kind: DifferenceKind::Added, original: None, reencoded: Some(reencoded_value.clone()) });
+            }
+        }
+        (Value::Array(original_entries), Value::Array(reencoded_entries)) if original_entries.len() == reencoded_entries.len() => {
+            for (index, (original_entry, reencoded_entry)) in original_entries.iter().zip(reencoded_entries).enumerate() {
+                diff_values(&join_path(path, &index.to_string()), original_entry, reencoded_entry, differences);
+            }
+        }
+        (Value::Number(original_number), Value::Number(reencoded_number)) if numbers_equal(original_number, reencoded_number) => {}
+        _ if original == reencoded => {}
+        _ => differences.push(FieldDifference { path: path.to_string(), kind: DifferenceKind::Changed, original: Some(original.clone()), reencoded: Some(reencoded.clone()) }),
+    }
+}
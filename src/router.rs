@@ -0,0 +1,142 @@
+use uuid::Uuid;
+use crate::{AcarsVdlm2Message, DecodeMessage, MessageFamily, MessageResult};
+use crate::app_template::AppDetailsTemplate;
+use crate::envelope::OutputMode;
+use crate::provider;
+
+/// Version given to messages that reach the router without an `app` block, as the decoder version is not known.
+const UNKNOWN_DECODER_VERSION: &str = "unknown";
+
+/// How `prepare_for_forwarding` should treat the messages passing through a router, mirroring the acars_router options.
+///
+/// Every family shares the same `acars_router_uuid`, so all messages from one router instance can be traced back to it.
+/// ```
+/// use acars_vdlm2_parser::router::RouterConfig;
+/// let config: RouterConfig = RouterConfig::new("acars_router", "1.3.1")
+///     .override_station_name("XX-YYYY-ACARS")
+///     .add_proxy_id(true);
+/// assert_eq!(config.get_station_name(), Some("XX-YYYY-ACARS"));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RouterConfig {
+    acars: AppDetailsTemplate,
+    vdlm2: AppDetailsTemplate,
+    hfdl: AppDetailsTemplate,
+    uat: AppDetailsTemplate,
+    station_name: Option<String>,
+    add_proxy_id: bool,
+    output_mode: OutputMode,
+}
+
+impl RouterConfig {
+    /// Creates a config that adds the proxy details of the named router to every message, and leaves the station
+    /// name as the decoder set it.
+    pub fn new(proxied_by: &str, acars_router_version: &str) -> Self {
+        let uuid: Uuid = provider::new_uuid();
+        let template = |family: MessageFamily| AppDetailsTemplate::new(proxied_by, acars_router_version)
+            .with_uuid(uuid)
+            .with_fallback_app(decoder_name(family), UNKNOWN_DECODER_VERSION);
+        Self {
+            acars: template(MessageFamily::Acars),
+            vdlm2: template(MessageFamily::Vdlm2),
+            hfdl: template(MessageFamily::Hfdl),
+            uat: template(MessageFamily::Uat),
+            station_name: None,
+            add_proxy_id: true,
+            output_mode: OutputMode::default(),
+        }
+    }
+
+    /// Replaces the station name of every message with the provided one.
+    pub fn override_station_name(mut self, station_name: &str) -> Self {
+        self.station_name = Some(station_name.to_string());
+        self
+    }
+
+    /// Sets whether the proxy details of the router are added to the `app` block, which is the default.
+    pub fn add_proxy_id(mut self, add_proxy_id: bool) -> Self {
+        self.add_proxy_id = add_proxy_id;
+        self
+    }
+
+    /// Sets whether messages are written as the decoder wrote them, which is the default, or wrapped in an `Envelope`.
+    pub fn output_mode(mut self, output_mode: OutputMode) -> Self {
+        self.output_mode = output_mode;
+        self
+    }
+
+    /// Sets the `acars_router_uuid` rather than using a randomly generated one.
+    pub fn with_uuid(mut self, uuid: Uuid) -> Self {
+        self.acars = self.acars.with_uuid(uuid);
+        self.vdlm2 = self.vdlm2.with_uuid(uuid);
+        self.hfdl = self.hfdl.with_uuid(uuid);
+        self.uat = self.uat.with_uuid(uuid);
+        self
+    }
+
+    /// Returns the station name messages are given, if it is overridden.
+    pub fn get_station_name(&self) -> Option<&str> {
+        self.station_name.as_deref()
+    }
+
+    /// Returns the template used for the `app` block of messages of the provided family.
+    pub fn app_template(&self, family: MessageFamily) -> &AppDetailsTemplate {
+        match family {
+            MessageFamily::Acars => &self.acars,
+            MessageFamily::Vdlm2 => &self.vdlm2,
+            MessageFamily::Hfdl => &self.hfdl,
+            MessageFamily::Uat => &self.uat,
+        }
+    }
+}
+
+/// Decodes a message received by a router, ignoring any whitespace and line endings around it.
+pub fn decode_for_forwarding(input: &[u8]) -> MessageResult<AcarsVdlm2Message> {
+    input.trim_ascii().decode_message()
+}
+
+/// Applies the router config to the message and returns it ready to be written to an output, in the order
+/// acars_router applies its options.
+///
+/// The station name is overridden first, then the proxy details are added, then the message is written in the output
+/// mode of the config. Adding proxy details never replaces or empties the `app` name and version of the decoder, and a
+/// message without an `app` block is given one named after the decoder of its family. The returned bytes always end
+/// with exactly one newline, so outputs never need to frame the messages themselves.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, AppDetails, DecodeMessage};
+/// use acars_vdlm2_parser::router::{decode_for_forwarding, prepare_for_forwarding, RouterConfig};
+/// let config: RouterConfig = RouterConfig::new("acars_router", "1.3.1").override_station_name("XX-YYYY-ACARS");
+/// let mut message: AcarsVdlm2Message = decode_for_forwarding(b"{\"freq\":131.55,\"channel\":2,\"station_id\":\"old\"}\r\n").unwrap();
+/// let output: Vec<u8> = prepare_for_forwarding(&mut message, &config).unwrap();
+/// assert!(output.ends_with(b"}\n"));
+/// let forwarded: AcarsVdlm2Message = output.decode_message().unwrap();
+/// assert_eq!(forwarded.get_station_name(), Some("XX-YYYY-ACARS"));
+/// let app: &AppDetails = forwarded.get_app_details().unwrap();
+/// assert_eq!((app.name.as_str(), app.ver.as_str()), ("acarsdec", "unknown"));
+/// assert_eq!(app.proxied_by.as_deref(), Some("acars_router"));
+/// ```
+pub fn prepare_for_forwarding(message: &mut AcarsVdlm2Message, config: &RouterConfig) -> MessageResult<Vec<u8>> {
+    if let Some(station_name) = config.get_station_name() {
+        message.set_station_name(station_name);
+    }
+    if config.add_proxy_id {
+        config.app_template(message.get_family()).apply_to(message);
+    }
+    config.output_mode.to_bytes_newline(message)
+}
+
+/// Decodes a message received by a router and prepares it for forwarding in one call.
+///
+/// See `decode_for_forwarding` and `prepare_for_forwarding`.
+pub fn forward(input: &[u8], config: &RouterConfig) -> MessageResult<Vec<u8>> {
+    prepare_for_forwarding(&mut decode_for_forwarding(input)?, config)
+}
+
+fn decoder_name(family: MessageFamily) -> &'static str {
+    match family {
+        MessageFamily::Acars => "acarsdec",
+        MessageFamily::Vdlm2 => "dumpvdl2",
+        MessageFamily::Hfdl => "dumphfdl",
+        MessageFamily::Uat => "dump978-fa",
+    }
+}
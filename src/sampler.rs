@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+use crate::provider;
+use crate::transform::{Transform, TransformOutcome};
+
+/// Keys a `Sampler` keeps counts for by default, see `Sampler::max_keys`.
+pub const DEFAULT_MAX_KEYS: usize = 100_000;
+
+/// What a `Sampler` counts messages against.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SampleKey {
+    /// Every message shares a single count.
+    All,
+    /// Messages are counted per receiving station name.
+    Station,
+    /// Messages are counted per ACARS label.
+    Label,
+    /// Messages are counted per aircraft ICAO address.
+    Icao,
+}
+
+impl SampleKey {
+    /// Returns the value of this key for the message, or `None` when the message does not have it.
+    ///
+    /// Messages without the key are counted together.
+    pub fn key_for(&self, message: &AcarsVdlm2Message) -> Option<String> {
+        match self {
+            SampleKey::All => None,
+            SampleKey::Station => message.get_station_name().map(|station| station.to_string()),
+            SampleKey::Label => message.get_label().map(|label| label.to_string()),
+            SampleKey::Icao => message.get_icao().map(|icao| format!("{:06X}", icao)),
+        }
+    }
+}
+
+/// How a `Sampler` decides which messages to pass.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleMode {
+    /// Pass the first message of every `n` seen for each key.
+    OneIn(u64),
+    /// Pass at most this many messages per second for each key.
+    MaxPerSecond(u64),
+}
+
+/// Count kept for each key.
+#[derive(Debug, Clone, Copy, Default)]
+struct SampleState {
+    second: i64,
+    count: u64,
+}
+
+/// Counts kept by a `Sampler`, with the newest second any message was counted in.
+#[derive(Debug)]
+struct SamplerState {
+    counts: HashMap<Option<String>, SampleState>,
+    newest_second: i64,
+}
+
+/// A `Transform` that passes 1 in N messages, or at most N messages per second, for each key and drops the rest.
+///
+/// Place it in a `TransformPipeline` ahead of expensive sinks to keep high volume feeds manageable.
+/// The per second limit uses the message timestamp when there is one, and the current time otherwise.
+///
+/// Counts are only kept while they matter, so feeds with many aircraft do not grow the sampler without bound: a 1 in N
+/// count is dropped once it has seen N messages, and per second counts are dropped once a newer second starts. If
+/// more than `max_keys` keys are still counting at once, every count is cleared and starts again.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::sampler::{SampleKey, Sampler};
+/// use acars_vdlm2_parser::transform::{Transform, TransformOutcome};
+/// let sampler: Sampler = Sampler::one_in(2, SampleKey::Label);
+/// let mut message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"H1"}"#.decode_message().unwrap();
+/// assert_eq!(sampler.apply(&mut message), TransformOutcome::Forward);
+/// assert!(matches!(sampler.apply(&mut message), TransformOutcome::Drop(_)));
+/// assert_eq!(sampler.apply(&mut message), TransformOutcome::Forward);
+/// ```
+#[derive(Debug)]
+pub struct Sampler {
+    mode: SampleMode,
+    key: SampleKey,
+    max_keys: usize,
+    state: Mutex<SamplerState>,
+}
+
+impl Sampler {
+    /// Creates a new `Sampler` using the provided mode and key.
+    pub fn new(mode: SampleMode, key: SampleKey) -> Self {
+        Self { mode, key, max_keys: DEFAULT_MAX_KEYS, state: Mutex::new(SamplerState { counts: HashMap::new(), newest_second: i64::MIN }) }
+    }
+
+    /// Creates a `Sampler` passing the first of every `n` messages for each key.
+    pub fn one_in(n: u64, key: SampleKey) -> Self {
+        Self::new(SampleMode::OneIn(n), key)
+    }
+
+    /// Creates a `Sampler` passing at most `limit` messages per second for each key.
+    pub fn max_per_second(limit: u64, key: SampleKey) -> Self {
+        Self::new(SampleMode::MaxPerSecond(limit), key)
+    }
+
+    /// Sets how many keys counts are kept for at once, `DEFAULT_MAX_KEYS` by default.
+    pub fn max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys.max(1);
+        self
+    }
+
+    /// Returns how many keys counts are currently kept for.
+    pub fn tracked_keys(&self) -> usize {
+        self.lock().counts.len()
+    }
+
+    /// Returns true if the message should be passed, updating the count for its key.
+    pub fn sample(&self, message: &AcarsVdlm2Message) -> bool {
+        let key: Option<String> = self.key.key_for(message);
+        let mut state: MutexGuard<SamplerState> = self.lock();
+        match self.mode {
+            SampleMode::OneIn(n) => {
+                self.make_room(&mut state, &key);
+                let count: u64 = state.counts.get(&key).map_or(0, |entry| entry.count);
+                // A count that has seen n messages is the same as no count, so it is dropped rather than kept.
+                match count + 1 >= n {
+                    true => state.counts.remove(&key),
+                    false => state.counts.insert(key, SampleState { second: 0, count: count + 1 }),
+                };
+                count == 0
+            }
+            SampleMode::MaxPerSecond(limit) => {
+                let second: i64 = message.get_time()
+                    .unwrap_or_else(provider::now)
+                    .floor() as i64;
+                if second > state.newest_second {
+                    state.newest_second = second;
+                    state.counts.retain(|_, entry| entry.second >= second);
+                }
+                self.make_room(&mut state, &key);
+                let entry: &mut SampleState = state.counts.entry(key).or_insert(SampleState { second, count: 0 });
+                // Late messages are counted against the newest second seen rather than restarting an old one.
+                if second > entry.second {
+                    *entry = SampleState { second, count: 0 };
+                }
+                entry.count += 1;
+                entry.count <= limit
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SamplerState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Clears every count if a new key would take the sampler past `max_keys`.
+    fn make_room(&self, state: &mut SamplerState, key: &Option<String>) {
+        if state.counts.len() >= self.max_keys && !state.counts.contains_key(key) {
+            debug!("Sampler is counting {} keys, clearing the counts", state.counts.len());
+            state.counts.clear();
+        }
+    }
+}
+
+impl Transform for Sampler {
+    fn name(&self) -> &str {
+        "sampler"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        match self.sample(message) {
+            true => TransformOutcome::Forward,
+            false => TransformOutcome::Drop(format!("sampled out by {:?} per {:?}", self.mode, self.key)),
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Ingest of the SBS, or BaseStation, port 30003 output of dump1090, readsb and older feeders that expose nothing else.
+//!
+//! Each `MSG` line holds the columns in `COLUMNS` in that order:
+//!
+//! ```text
+//! MSG,transmission_type,session_id,aircraft_id,hex_ident,flight_id,date_generated,time_generated,date_logged,
+//! time_logged,callsign,altitude,ground_speed,track,lat,lon,vertical_rate,squawk,alert,emergency,spi,is_on_ground
+//! ```
+//!
+//! Each transmission type fills only some of the columns, so an aircraft is built up from several lines, see
+//! `SbsMessage::update_adsb_json`. Empty columns are left unset, as are columns missing from the end of a line, which
+//! some feeders leave off. The flag columns are `0` for false and `-1` or `1` for true. The other line types of the
+//! BaseStation protocol, such as `SEL`, `ID`, `AIR`, `STA` and `CLK`, carry no aircraft data and are rejected.
+use std::str::FromStr;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Serialize};
+use crate::MessageResult;
+#[cfg(feature = "adsb-json")]
+use crate::adsb_json::{AdsbJsonMessage, BaroAltitude};
+use crate::icao::parse_icao_hex;
+
+/// Names of the columns of an SBS line, in the order they are written.
+pub const COLUMNS: [&str; 22] = [
+    "message_type", "transmission_type", "session_id", "aircraft_id", "hex_ident", "flight_id",
+    "date_generated", "time_generated", "date_logged", "time_logged", "callsign", "altitude", "ground_speed",
+    "track", "lat", "lon", "vertical_rate", "squawk", "alert", "emergency", "spi", "is_on_ground",
+];
+/// Message type of the SBS lines that carry aircraft data.
+pub const SBS_MESSAGE_TYPE: &str = "MSG";
+/// Number of columns up to and including `hex_ident`, the fewest a line can have.
+const MIN_COLUMNS: usize = 5;
+
+/// Returns true if the buffer looks like an SBS `MSG` line.
+/// ```
+/// use acars_vdlm2_parser::sbs::is_sbs;
+/// assert!(is_sbs(b"MSG,3,1,1,A1B2C3,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,,35000,,,47.44,-122.30,,,0,0,0,0\r\n"));
+/// assert!(!is_sbs(b"SEL,,1,1,A1B2C3,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,AB0123"));
+/// ```
+pub fn is_sbs(bytes: &[u8]) -> bool {
+    bytes.trim_ascii_start().starts_with(b"MSG,")
+}
+
+/// Parses an SBS `MSG` line, mapping each column in `COLUMNS` to the field of the same name.
+/// ```
+/// use acars_vdlm2_parser::sbs::{parse_sbs, SbsMessage};
+/// let line: &str = "MSG,3,1,1,A1B2C3,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,,35000,,,47.44,-122.30,,,0,0,0,0";
+/// let message: SbsMessage = parse_sbs(line).unwrap();
+/// assert_eq!(message.get_icao(), Some(0xA1B2C3));
+/// assert_eq!(message.altitude, Some(35000));
+/// assert_eq!(message.get_position(), Some((47.44, -122.30)));
+/// assert_eq!(message.is_on_ground, Some(false));
+/// assert_eq!(message.get_time(), Some(1700000000.125));
+/// ```
+pub fn parse_sbs(line: &str) -> MessageResult<SbsMessage> {
+    trace!("Parsing SBS line {}", line);
+    let line: &str = line.trim_matches(|character: char| character == '\r' || character == '\n');
+    let columns: Vec<&str> = line.split(',').collect();
+    if columns[0] != SBS_MESSAGE_TYPE {
+        return Err(serde_json::Error::custom(format!("SBS line type `{}` carries no aircraft data, expected {}", columns[0], SBS_MESSAGE_TYPE)));
+    }
+    if columns.len() < MIN_COLUMNS || columns.len() > COLUMNS.len() {
+        return Err(serde_json::Error::custom(format!("SBS line has {} columns, expected {} to {}", columns.len(), MIN_COLUMNS, COLUMNS.len())));
+    }
+    let column = |index: usize| -> Option<String> {
+        columns.get(index).map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+    };
+    let transmission_type: u8 = parse_number(column(1), COLUMNS[1])?
+        .filter(|transmission_type: &u8| (1..=8).contains(transmission_type))
+        .ok_or_else(|| serde_json::Error::custom(format!("invalid `transmission_type` in SBS line, expected 1 to 8: {}", line)))?;
+    Ok(SbsMessage {
+        transmission_type,
+        session_id: column(2),
+        aircraft_id: column(3),
+        hex_ident: column(4).ok_or_else(|| serde_json::Error::custom("missing field `hex_ident` in SBS line"))?,
+        flight_id: column(5),
+        date_generated: column(6),
+        time_generated: column(7),
+        date_logged: column(8),
+        time_logged: column(9),
+        callsign: column(10),
+        altitude: parse_number(column(11), COLUMNS[11])?,
+        ground_speed: parse_number(column(12), COLUMNS[12])?,
+        track: parse_number(column(13), COLUMNS[13])?,
+        lat: parse_number(column(14), COLUMNS[14])?,
+        lon: parse_number(column(15), COLUMNS[15])?,
+        vertical_rate: parse_number(column(16), COLUMNS[16])?,
+        squawk: column(17),
+        alert: parse_flag(column(18), COLUMNS[18])?,
+        emergency: parse_flag(column(19), COLUMNS[19])?,
+        spi: parse_flag(column(20), COLUMNS[20])?,
+        is_on_ground: parse_flag(column(21), COLUMNS[21])?,
+    })
+}
+
+/// Parses a column as a number, naming the column in the error if it is not one.
+fn parse_number<T: FromStr>(value: Option<String>, name: &str) -> MessageResult<Option<T>> {
+    value.map(|value| value.parse::<T>().map_err(|_| {
+        serde_json::Error::custom(format!("invalid value `{}` for `{}` in SBS line", value, name))
+    })).transpose()
+}
+
+/// Parses a flag column, which BaseStation writes as `0` or `-1` and some feeders as `0` or `1`.
+fn parse_flag(value: Option<String>, name: &str) -> MessageResult<Option<bool>> {
+    value.map(|value| match value.as_str() {
+        "0" => Ok(false),
+        "-1" | "1" => Ok(true),
+        _ => Err(serde_json::Error::custom(format!("invalid value `{}` for `{}` in SBS line", value, name))),
+    }).transpose()
+}
+
+/// A single SBS `MSG` line.
+///
+/// The transmission type says which columns are filled in:
+///
+/// | Type | Content | Columns |
+/// |------|---------|---------|
+/// | 1 | Identification and category | `callsign` |
+/// | 2 | Surface position | `altitude`, `ground_speed`, `track`, `lat`, `lon`, `is_on_ground` |
+/// | 3 | Airborne position | `altitude`, `lat`, `lon`, `alert`, `emergency`, `spi`, `is_on_ground` |
+/// | 4 | Airborne velocity | `ground_speed`, `track`, `vertical_rate` |
+/// | 5 | Surveillance altitude | `altitude`, `alert`, `spi`, `is_on_ground` |
+/// | 6 | Surveillance identity | `altitude`, `squawk`, `alert`, `emergency`, `spi`, `is_on_ground` |
+/// | 7 | Air to air | `altitude`, `is_on_ground` |
+/// | 8 | All call reply | `is_on_ground` |
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SbsMessage {
+    pub transmission_type: u8,
+    /// Database identifiers written by BaseStation, which other feeders fill with placeholders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aircraft_id: Option<String>,
+    /// 24 bit address as 6 hex characters, in upper case from most feeders.
+    pub hex_ident: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flight_id: Option<String>,
+    /// Date as `YYYY/MM/DD` and time of day as `HH:MM:SS.sss`, in UTC, when the message was received and when the
+    /// line was written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_generated: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_generated: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_logged: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_logged: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callsign: Option<String>,
+    /// Barometric altitude in feet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<i32>,
+    /// Ground speed in knots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ground_speed: Option<f64>,
+    /// Track over ground in degrees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f64>,
+    /// Vertical rate in feet a minute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertical_rate: Option<i32>,
+    /// Mode A code as 4 octal digits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub squawk: Option<String>,
+    /// Whether the squawk has changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert: Option<bool>,
+    /// Whether an emergency squawk, 7500, 7600 or 7700, is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<bool>,
+    /// Whether the special position identification (ident) is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spi: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_on_ground: Option<bool>,
+}
+
+/// Decodes a single SBS line, as `parse_sbs` does.
+impl FromStr for SbsMessage {
+    type Err = serde_json::Error;
+
+    fn from_str(input: &str) -> MessageResult<Self> {
+        parse_sbs(input)
+    }
+}
+
+impl SbsMessage {
+    /// Converts `SbsMessage` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Returns the ICAO address, or `None` if `hex_ident` is not 6 hex characters, as for the `~` prefixed non-ICAO
+    /// addresses some feeders write.
+    pub fn get_icao(&self) -> Option<u32> {
+        parse_icao_hex(&self.hex_ident)
+    }
+
+    /// Returns the callsign without its padding, if the line has one.
+    pub fn get_flight(&self) -> Option<&str> {
+        self.callsign.as_deref().map(str::trim).filter(|callsign| !callsign.is_empty())
+    }
+
+    /// Returns the position as latitude and longitude, if the line has one.
+    pub fn get_position(&self) -> Option<(f64, f64)> {
+        self.lat.zip(self.lon)
+    }
+
+    /// Returns the time the message was received, in seconds since the Unix epoch, from `date_generated` and
+    /// `time_generated`, or `None` if either is missing, not in the BaseStation format, or out of range, such as a
+    /// year past 9999 or an hour past 23.
+    pub fn get_time(&self) -> Option<f64> {
+        let date: &str = self.date_generated.as_deref()?;
+        let time: &str = self.time_generated.as_deref()?;
+        let mut date_parts = date.split('/').map(str::parse::<i64>);
+        let (year, month, day): (i64, i64, i64) = (date_parts.next()?.ok()?, date_parts.next()?.ok()?, date_parts.next()?.ok()?);
+        let mut time_parts = time.split(':');
+        let (hours, minutes): (i64, i64) = (time_parts.next()?.parse().ok()?, time_parts.next()?.parse().ok()?);
+        let seconds: f64 = time_parts.next()?.parse().ok()?;
+        if date_parts.next().is_some() || time_parts.next().is_some() {
+            return None;
+        }
+        let date_valid: bool = (0..=9999).contains(&year) && (1..=12).contains(&month) && (1..=31).contains(&day);
+        let time_valid: bool = (0..24).contains(&hours) && (0..60).contains(&minutes) && (0.0..61.0).contains(&seconds);
+        if !date_valid || !time_valid {
+            return None;
+        }
+        let whole_seconds: i64 = days_from_civil(year, month, day) * 86_400 + hours * 3_600 + minutes * 60;
+        Some(whole_seconds as f64 + seconds)
+    }
+
+    /// Converts the line to an `AdsbJsonMessage` for the aircraft, with only the fields this line carries.
+    /// ```
+    /// use acars_vdlm2_parser::adsb_json::AdsbJsonMessage;
+    /// use acars_vdlm2_parser::sbs::SbsMessage;
+    /// let message: SbsMessage = "MSG,1,1,1,A1B2C3,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,AB0123  ,,,,,,,,,,,".parse().unwrap();
+    /// let aircraft: AdsbJsonMessage = message.to_adsb_json();
+    /// assert_eq!(aircraft.hex, "a1b2c3");
+    /// assert_eq!(aircraft.get_flight(), Some("AB0123"));
+    /// ```
+    #[cfg(feature = "adsb-json")]
+    pub fn to_adsb_json(&self) -> AdsbJsonMessage {
+        let mut aircraft: AdsbJsonMessage = AdsbJsonMessage { hex: self.hex_ident.to_lowercase(), ..Default::default() };
+        self.update_adsb_json(&mut aircraft);
+        aircraft
+    }
+
+    /// Copies the fields this line carries onto an `AdsbJsonMessage` for the same aircraft, leaving its other fields
+    /// as they are, so an aircraft can be built up from the lines of each transmission type as they arrive.
+    ///
+    /// An aircraft on the ground gets an `alt_baro` of `ground`. The `emergency` flag gives `none` or `general`,
+    /// or the emergency of a 7500, 7600 or 7700 squawk.
+    #[cfg(feature = "adsb-json")]
+    pub fn update_adsb_json(&self, aircraft: &mut AdsbJsonMessage) {
+        if let Some(callsign) = &self.callsign {
+            aircraft.flight = Some(callsign.clone());
+        }
+        match (self.is_on_ground, self.altitude) {
+            (Some(true), _) => aircraft.alt_baro = Some(BaroAltitude::Text("ground".to_string())),
+            (_, Some(altitude)) => aircraft.alt_baro = Some(BaroAltitude::Feet(altitude)),
+            _ => {}
+        }
+        aircraft.gs = self.ground_speed.or(aircraft.gs);
+        aircraft.track = self.track.or(aircraft.track);
+        if let Some((lat, lon)) = self.get_position() {
+            (aircraft.lat, aircraft.lon) = (Some(lat), Some(lon));
+        }
+        aircraft.baro_rate = self.vertical_rate.or(aircraft.baro_rate);
+        if let Some(squawk) = &self.squawk {
+            aircraft.squawk = Some(squawk.clone());
+        }
+        if let Some(emergency) = self.emergency {
+            let status: &str = match (emergency, self.squawk.as_deref().or(aircraft.squawk.as_deref())) {
+                (false, _) => "none",
+                (true, Some("7500")) => "unlawful",
+                (true, Some("7600")) => "nordo",
+                (true, _) => "general",
+            };
+            aircraft.emergency = Some(status.to_string());
+        }
+        aircraft.alert = self.alert.map(u8::from).or(aircraft.alert);
+        aircraft.spi = self.spi.map(u8::from).or(aircraft.spi);
+    }
+}
+
+/// Returns the number of days from the Unix epoch to a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year: i64 = if month <= 2 { year - 1 } else { year };
+    let era: i64 = year.div_euclid(400);
+    let year_of_era: i64 = year - era * 400;
+    let day_of_year: i64 = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
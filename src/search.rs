@@ -0,0 +1,382 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+
+/// Fields that can be searched with `field:value` in a `SearchIndex` query.
+pub const SEARCH_FIELDS: [&str; 8] = ["text", "label", "sublabel", "flight", "tail", "icao", "station", "family"];
+/// Deepest a `SearchIndex` query may nest parentheses and `NOT`, so a hostile query cannot overflow the stack.
+pub const MAX_QUERY_DEPTH: usize = 64;
+
+/// Why a `SearchIndex` query could not be parsed.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchErrorKind {
+    /// An operator or parenthesis where a term was expected, or a closing parenthesis with nothing to close.
+    UnexpectedToken { token: String },
+    /// The query ends where a term was expected.
+    UnexpectedEnd,
+    /// Parentheses and `NOT` are nested more than `MAX_QUERY_DEPTH` deep.
+    TooDeep { limit: usize },
+    /// A parenthesis is never closed.
+    Unclosed,
+    /// A `field:value` term names a field that is not one of `SEARCH_FIELDS`.
+    UnknownField { field: String },
+    /// A term has no letters or digits to search for.
+    EmptyTerm { term: String },
+}
+
+/// A `SearchIndex` query syntax error, with the byte offset in the query where it was found.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct SearchError {
+    /// Byte offset of the token the error was found at, or the length of the query if it ended too early.
+    pub position: usize,
+    pub kind: SearchErrorKind,
+}
+
+impl SearchError {
+    fn new(position: usize, kind: SearchErrorKind) -> Self {
+        Self { position, kind }
+    }
+}
+
+impl fmt::Display for SearchErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchErrorKind::UnexpectedToken { token } => write!(f, "unexpected `{}`", token),
+            SearchErrorKind::UnexpectedEnd => f.write_str("query ends where a term was expected"),
+            SearchErrorKind::TooDeep { limit } => write!(f, "query nests more than {} deep", limit),
+            SearchErrorKind::Unclosed => f.write_str("unclosed `(`"),
+            SearchErrorKind::UnknownField { field } => {
+                write!(f, "unknown search field `{}`, expected one of {}", field, SEARCH_FIELDS.join(", "))
+            }
+            SearchErrorKind::EmptyTerm { term } => write!(f, "search term `{}` has nothing to search for", term),
+        }
+    }
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {} in search query", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+/// A parsed `SearchIndex` query.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SearchQuery {
+    /// Matches messages with the token in the field, or in any field when `field` is `None`.
+    Term { field: Option<String>, token: String },
+    And(Vec<SearchQuery>),
+    Or(Vec<SearchQuery>),
+    Not(Box<SearchQuery>),
+}
+
+impl SearchQuery {
+    /// Parses a query made of terms joined by `AND`, `OR` and `NOT`, with parentheses for grouping.
+    ///
+    /// A term is a word, matched against every field, or `field:value` to match one of `SEARCH_FIELDS`. Terms next to
+    /// each other without an operator are joined by `AND`, which binds tighter than `OR`. A term prefixed with `-` is
+    /// the same as `NOT term`. Matching ignores case. Parentheses and `NOT` may be nested at most `MAX_QUERY_DEPTH`
+    /// deep.
+    pub fn parse(query: &str) -> Result<Self, SearchError> {
+        let tokens: Vec<QueryToken> = lex(query);
+        let mut position: usize = 0;
+        let parsed: SearchQuery = parse_or(&tokens, &mut position, 0)?;
+        match tokens.get(position) {
+            None => Ok(parsed),
+            Some(token) => Err(SearchError::new(token.offset, SearchErrorKind::UnexpectedToken { token: token.text.clone() })),
+        }
+    }
+}
+
+/// An in-memory inverted index over the text and key fields of decoded messages, for searching recent traffic without
+/// an external database.
+///
+/// Text is split into tokens on anything that is not a letter or digit. The registration is also indexed without the
+/// leading dots and dash, so `tail:N12345` and `tail:VH-ABC` both match however the tail was written. ICAO addresses
+/// are indexed as 6 hex characters. Results are returned in the order the messages were added.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::search::SearchIndex;
+/// let mut index: SearchIndex = SearchIndex::new();
+/// index.insert(r#"{"freq":131.55,"label":"H1","flight":"UA0123","text":"DEP KLAX ARR KSFO"}"#.decode_message().unwrap());
+/// index.insert(r#"{"freq":131.55,"label":"SQ","text":"02XAKLAXKLAX"}"#.decode_message().unwrap());
+/// index.insert(r#"{"freq":131.55,"label":"H1","text":"FUEL 123"}"#.decode_message().unwrap());
+/// let found: Vec<&AcarsVdlm2Message> = index.query("KLAX AND label:H1").unwrap();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].get_flight(), Some("UA0123"));
+/// assert_eq!(index.query("label:H1 -ksfo").unwrap().len(), 1);
+/// assert_eq!(index.query("klax OR fuel").unwrap().len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    messages: Vec<AcarsVdlm2Message>,
+    /// Id of each message in `messages`, in ascending order, which stays the same when earlier messages are removed.
+    ids: Vec<u64>,
+    next_id: u64,
+    /// Postings by field, then token, each a list of message ids in ascending order.
+    postings: HashMap<&'static str, HashMap<String, Vec<u64>>>,
+}
+
+impl SearchIndex {
+    /// Creates an empty `SearchIndex`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `SearchIndex` holding the messages.
+    pub fn from_messages(messages: impl IntoIterator<Item = AcarsVdlm2Message>) -> Self {
+        let mut index: SearchIndex = SearchIndex::new();
+        for message in messages {
+            index.insert(message);
+        }
+        index
+    }
+
+    /// Adds a message to the index.
+    pub fn insert(&mut self, message: AcarsVdlm2Message) {
+        let id: u64 = self.next_id;
+        self.next_id += 1;
+        for (field, tokens) in field_tokens(&message) {
+            let field_postings: &mut HashMap<String, Vec<u64>> = self.postings.entry(field).or_default();
+            for token in tokens {
+                let postings: &mut Vec<u64> = field_postings.entry(token).or_default();
+                if postings.last() != Some(&id) {
+                    postings.push(id);
+                }
+            }
+        }
+        self.messages.push(message);
+        self.ids.push(id);
+    }
+
+    /// Number of messages in the index.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns true if the index holds no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns the messages in the index, in the order they were added.
+    pub fn messages(&self) -> &[AcarsVdlm2Message] {
+        &self.messages
+    }
+
+    /// Removes the messages timed before `time` and those without a time, see `AcarsVdlm2Message::get_time()`, so an
+    /// index fed continuously can be kept to a window such as the last 24 hours. Returns how many were removed.
+    ///
+    /// Only the postings of the removed messages are touched, so the cost follows the number of messages removed
+    /// rather than the size of the index.
+    pub fn remove_before(&mut self, time: f64) -> usize {
+        let keep = |message: &AcarsVdlm2Message| message.get_time().is_some_and(|message_time| message_time >= time);
+        let mut removed: HashSet<u64> = HashSet::new();
+        let mut affected: HashSet<(&'static str, String)> = HashSet::new();
+        for (message, id) in self.messages.iter().zip(&self.ids).filter(|(message, _)| !keep(message)) {
+            removed.insert(*id);
+            for (field, tokens) in field_tokens(message) {
+                affected.extend(tokens.into_iter().map(|token| (field, token)));
+            }
+        }
+        if removed.is_empty() {
+            return 0;
+        }
+        for (field, token) in affected {
+            let Some(field_postings) = self.postings.get_mut(field) else {
+                continue;
+            };
+            if let Some(postings) = field_postings.get_mut(&token) {
+                postings.retain(|id| !removed.contains(id));
+                if postings.is_empty() {
+                    field_postings.remove(&token);
+                }
+            }
+        }
+        let mut ids = self.ids.iter();
+        self.messages.retain(|_| ids.next().is_some_and(|id| !removed.contains(id)));
+        self.ids.retain(|id| !removed.contains(id));
+        removed.len()
+    }
+
+    /// Parses the query, see `SearchQuery::parse`, and returns the messages matching it.
+    pub fn query(&self, query: &str) -> Result<Vec<&AcarsVdlm2Message>, SearchError> {
+        trace!("Searching for {}", query);
+        Ok(self.search(&SearchQuery::parse(query)?))
+    }
+
+    /// Returns the messages matching an already parsed query.
+    pub fn search(&self, query: &SearchQuery) -> Vec<&AcarsVdlm2Message> {
+        self.matching(query).into_iter()
+            .filter_map(|id| self.ids.binary_search(&id).ok())
+            .map(|position| &self.messages[position])
+            .collect()
+    }
+
+    fn matching(&self, query: &SearchQuery) -> BTreeSet<u64> {
+        match query {
+            SearchQuery::Term { field: Some(field), token } => self.postings(field, token).collect(),
+            SearchQuery::Term { field: None, token } => SEARCH_FIELDS.iter().flat_map(|field| self.postings(field, token)).collect(),
+            SearchQuery::And(queries) => {
+                let mut queries = queries.iter();
+                let first: BTreeSet<u64> = queries.next().map(|query| self.matching(query)).unwrap_or_default();
+                queries.fold(first, |matched, query| &matched & &self.matching(query))
+            }
+            SearchQuery::Or(queries) => queries.iter().flat_map(|query| self.matching(query)).collect(),
+            SearchQuery::Not(query) => {
+                let excluded: BTreeSet<u64> = self.matching(query);
+                self.ids.iter().copied().filter(|id| !excluded.contains(id)).collect()
+            }
+        }
+    }
+
+    fn postings<'a>(&'a self, field: &str, token: &str) -> impl Iterator<Item = u64> + 'a {
+        self.postings.get(field).and_then(|field_postings| field_postings.get(token)).into_iter().flatten().copied()
+    }
+}
+
+/// Returns the tokens of every searchable field of the message.
+fn field_tokens(message: &AcarsVdlm2Message) -> Vec<(&'static str, Vec<String>)> {
+    let mut fields: Vec<(&'static str, Vec<String>)> = Vec::new();
+    let mut add = |field: &'static str, value: Option<&str>| {
+        if let Some(value) = value {
+            fields.push((field, tokenise(value)));
+        }
+    };
+    add("text", message.get_text());
+    add("label", message.get_label());
+    add("sublabel", message.get_sublabel());
+    add("flight", message.get_flight());
+    add("station", message.get_station_name());
+    add("family", Some(&message.get_family().to_string()));
+    add("icao", message.get_icao().map(|icao| format!("{:06X}", icao)).as_deref());
+    if let Some(registration) = message.get_registration() {
+        let mut tokens: Vec<String> = tokenise(registration);
+        tokens.push(registration.trim_start_matches('.').replace('-', "").to_uppercase());
+        fields.push(("tail", tokens));
+    }
+    fields
+}
+
+/// Splits the value into uppercase tokens on anything that is not a letter or digit.
+fn tokenise(value: &str) -> Vec<String> {
+    value.split(|character: char| !character.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_uppercase)
+        .collect()
+}
+
+/// A word, parenthesis or operator in a query, with its byte offset.
+struct QueryToken {
+    offset: usize,
+    text: String,
+}
+
+/// Splits a query into parentheses and whitespace separated words.
+fn lex(query: &str) -> Vec<QueryToken> {
+    let mut tokens: Vec<QueryToken> = Vec::new();
+    let mut word: Option<usize> = None;
+    for (offset, character) in query.char_indices() {
+        if character.is_whitespace() || character == '(' || character == ')' {
+            if let Some(start) = word.take() {
+                tokens.push(QueryToken { offset: start, text: query[start..offset].to_string() });
+            }
+            if !character.is_whitespace() {
+                tokens.push(QueryToken { offset, text: character.to_string() });
+            }
+        } else if word.is_none() {
+            word = Some(offset);
+        }
+    }
+    if let Some(start) = word {
+        tokens.push(QueryToken { offset: start, text: query[start..].to_string() });
+    }
+    tokens
+}
+
+/// Returns the offset just after the last token, where a query that ends too early is reported.
+fn end_offset(tokens: &[QueryToken]) -> usize {
+    tokens.last().map(|token| token.offset + token.text.len()).unwrap_or_default()
+}
+
+fn parse_or(tokens: &[QueryToken], position: &mut usize, depth: usize) -> Result<SearchQuery, SearchError> {
+    let mut queries: Vec<SearchQuery> = vec![parse_and(tokens, position, depth)?];
+    while tokens.get(*position).is_some_and(|token| token.text == "OR") {
+        *position += 1;
+        queries.push(parse_and(tokens, position, depth)?);
+    }
+    Ok(match queries.len() {
+        1 => queries.remove(0),
+        _ => SearchQuery::Or(queries),
+    })
+}
+
+fn parse_and(tokens: &[QueryToken], position: &mut usize, depth: usize) -> Result<SearchQuery, SearchError> {
+    let mut queries: Vec<SearchQuery> = vec![parse_unary(tokens, position, depth)?];
+    while let Some(token) = tokens.get(*position) {
+        match token.text.as_str() {
+            "OR" | ")" => break,
+            "AND" => *position += 1,
+            _ => {}
+        }
+        queries.push(parse_unary(tokens, position, depth)?);
+    }
+    Ok(match queries.len() {
+        1 => queries.remove(0),
+        _ => SearchQuery::And(queries),
+    })
+}
+
+fn parse_unary(tokens: &[QueryToken], position: &mut usize, depth: usize) -> Result<SearchQuery, SearchError> {
+    let token: &QueryToken = tokens.get(*position)
+        .ok_or_else(|| SearchError::new(end_offset(tokens), SearchErrorKind::UnexpectedEnd))?;
+    *position += 1;
+    if matches!(token.text.as_str(), "NOT" | "(") && depth >= MAX_QUERY_DEPTH {
+        return Err(SearchError::new(token.offset, SearchErrorKind::TooDeep { limit: MAX_QUERY_DEPTH }));
+    }
+    match token.text.as_str() {
+        "NOT" => Ok(SearchQuery::Not(Box::new(parse_unary(tokens, position, depth + 1)?))),
+        "(" => {
+            let query: SearchQuery = parse_or(tokens, position, depth + 1)?;
+            match tokens.get(*position).map(|closing| closing.text.as_str()) {
+                Some(")") => {
+                    *position += 1;
+                    Ok(query)
+                }
+                _ => Err(SearchError::new(token.offset, SearchErrorKind::Unclosed)),
+            }
+        }
+        "AND" | "OR" | ")" => Err(SearchError::new(token.offset, SearchErrorKind::UnexpectedToken { token: token.text.clone() })),
+        text => match text.strip_prefix('-').filter(|negated| !negated.is_empty()) {
+            Some(negated) => Ok(SearchQuery::Not(Box::new(parse_term(negated, token.offset + 1)?))),
+            None => parse_term(text, token.offset),
+        },
+    }
+}
+
+fn parse_term(term: &str, offset: usize) -> Result<SearchQuery, SearchError> {
+    let (field, value): (Option<&str>, &str) = match term.split_once(':') {
+        Some((field, value)) => (Some(field), value),
+        None => (None, term),
+    };
+    if let Some(field) = field.filter(|field| !SEARCH_FIELDS.contains(&field.to_lowercase().as_str())) {
+        return Err(SearchError::new(offset, SearchErrorKind::UnknownField { field: field.to_string() }));
+    }
+    let mut tokens: Vec<String> = tokenise(value);
+    if field.is_some_and(|field| field.eq_ignore_ascii_case("tail")) {
+        tokens = vec![value.trim_start_matches('.').replace('-', "").to_uppercase()];
+    }
+    let field: Option<String> = field.map(str::to_lowercase);
+    let mut terms: Vec<SearchQuery> = tokens.into_iter()
+        .filter(|token| !token.is_empty())
+        .map(|token| SearchQuery::Term { field: field.clone(), token })
+        .collect();
+    match terms.len() {
+        0 => Err(SearchError::new(offset, SearchErrorKind::EmptyTerm { term: term.to_string() })),
+        1 => Ok(terms.remove(0)),
+        _ => Ok(SearchQuery::And(terms)),
+    }
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use crate::MessageFamily;
+use crate::csv_legacy::is_csv_legacy;
+use crate::sbs::is_sbs;
+
+/// Top level key dumpvdl2 nests its output under.
+pub const VDLM2_KEY: &str = "vdl2";
+/// Top level key dumphfdl nests its output under.
+pub const HFDL_KEY: &str = "hfdl";
+/// Top level key a raw dump978-fa frame is nested under once decoded.
+pub const UAT_FRAME_KEY: &str = "uat_frame";
+/// Top level key dump978-fa writes in every decoded ADS-B message.
+pub const UAT_ADDRESS_QUALIFIER_KEY: &str = "address_qualifier";
+/// Byte starting every frame in the Beast binary protocol.
+pub const BEAST_ESCAPE: u8 = 0x1A;
+
+/// Wire format of a buffer, as guessed by `sniff_format`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WireFormat {
+    /// A JSON object on a single line, as written by the decoders.
+    Json,
+    /// A JSON object spread over several lines.
+    PrettyJson,
+    /// Beast binary frames, which start with `BEAST_ESCAPE`.
+    Beast,
+    /// AVR style raw hex frames such as `*8D4840D6202CC371C32CE0576098;`.
+    Raw,
+    /// Raw dump978-fa UAT frames such as `-<hex>;rs=1;rssi=-12.3;`, see `uat::parse_uat_raw`.
+    UatRaw,
+    /// Comma separated ACARS lines from legacy feeders, see `csv_legacy`.
+    CsvLegacy,
+    /// SBS (BaseStation) `MSG` lines, see `sbs::parse_sbs`.
+    Sbs,
+    /// Anything else.
+    Unknown,
+}
+
+/// Guesses the wire format of a buffer from its first and last bytes, without parsing it.
+/// ```
+/// use acars_vdlm2_parser::sniff::{sniff_format, WireFormat};
+/// assert_eq!(sniff_format(br#"{"freq":131.55,"channel":2}"#), WireFormat::Json);
+/// assert_eq!(sniff_format(b"{\n  \"freq\": 131.55\n}\n"), WireFormat::PrettyJson);
+/// assert_eq!(sniff_format(b"*8D4840D6202CC371C32CE0576098;"), WireFormat::Raw);
+/// assert_eq!(sniff_format(&[0x1A, b'3', 0x00]), WireFormat::Beast);
+/// assert_eq!(sniff_format(b"-00a1b2c30000000000000000000000000000;rs=1;"), WireFormat::UatRaw);
+/// assert_eq!(sniff_format(b"1672531200.5,STATION,2,131.550,-24,0,2,H1,4,!,.N12345,UA0123,M01A,TEXT"), WireFormat::CsvLegacy);
+/// assert_eq!(sniff_format(b"MSG,4,1,1,A1B2C3,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,,,452,87.4,,,-64,,,,,0"), WireFormat::Sbs);
+/// ```
+pub fn sniff_format(bytes: &[u8]) -> WireFormat {
+    let trimmed: &[u8] = bytes.trim_ascii();
+    match (trimmed.first(), trimmed.get(1), trimmed.last()) {
+        (Some(&BEAST_ESCAPE), Some(b'1'..=b'4'), _) => WireFormat::Beast,
+        (Some(b'{'), _, Some(b'}')) if trimmed.contains(&b'\n') => WireFormat::PrettyJson,
+        (Some(b'{'), _, Some(b'}')) => WireFormat::Json,
+        (Some(b'*' | b'@' | b':'), _, Some(b';')) => WireFormat::Raw,
+        _ if is_uat_raw(trimmed) => WireFormat::UatRaw,
+        _ if is_csv_legacy(trimmed) => WireFormat::CsvLegacy,
+        _ if is_sbs(trimmed) => WireFormat::Sbs,
+        _ => WireFormat::Unknown,
+    }
+}
+
+/// Returns true if the buffer looks like a raw dump978-fa frame, a `-` for a downlink or `+` for an uplink followed by
+/// the payload in hex and ending with `;`.
+/// ```
+/// use acars_vdlm2_parser::sniff::is_uat_raw;
+/// assert!(is_uat_raw(b"-0b28c8a1b2c3;rs=1;rssi=-12.3;\n"));
+/// assert!(!is_uat_raw(b"*8D4840D6202CC371C32CE0576098;"));
+/// assert!(!is_uat_raw(br#"{"address":"a1b2c3"}"#));
+/// ```
+pub fn is_uat_raw(bytes: &[u8]) -> bool {
+    let trimmed: &[u8] = bytes.trim_ascii();
+    matches!((trimmed.first(), trimmed.get(1), trimmed.last()), (Some(b'-' | b'+'), Some(digit), Some(b';')) if digit.is_ascii_hexdigit())
+}
+
+/// Identifies the message family of a JSON object from its top level keys, without fully parsing it.
+///
+/// This gives the same answer as `MessageFamily::detect` for valid JSON, so ingest servers can route traffic to
+/// per-family workers cheaply. Only the top level keys are looked at, so a `vdl2` key inside message text or a nested
+/// object does not count. Returns `None` if the buffer is not a JSON object. The scan stops at the first `vdl2`, `hfdl`,
+/// `address_qualifier` or `uat_frame` key, so a truncated object may still be identified, and will fail when it is
+/// decoded.
+/// ```
+/// use acars_vdlm2_parser::MessageFamily;
+/// use acars_vdlm2_parser::sniff::sniff;
+/// assert_eq!(sniff(br#"{"vdl2":{"freq":136975000}}"#), Some(MessageFamily::Vdlm2));
+/// assert_eq!(sniff(br#"{"freq":131.55,"text":"{\"hfdl\":1}"}"#), Some(MessageFamily::Acars));
+/// assert_eq!(sniff(br#"{"address":"a1b2c3","address_qualifier":"adsb_icao"}"#), Some(MessageFamily::Uat));
+/// assert_eq!(sniff(b"*8D4840D6202CC371C32CE0576098;"), None);
+/// ```
+pub fn sniff(bytes: &[u8]) -> Option<MessageFamily> {
+    let trimmed: &[u8] = bytes.trim_ascii_start();
+    if trimmed.first() != Some(&b'{') {
+        return None;
+    }
+    let mut depth: usize = 0;
+    let mut index: usize = 0;
+    // Whether the next string at depth 1 is a key, which is the case after `{` or `,`.
+    let mut expecting_key: bool = false;
+    while index < trimmed.len() {
+        match trimmed[index] {
+            b'"' => {
+                let end: usize = string_end(trimmed, index + 1)?;
+                if depth == 1 && expecting_key {
+                    match &trimmed[index + 1..end] {
+                        key if key == VDLM2_KEY.as_bytes() => return Some(MessageFamily::Vdlm2),
+                        key if key == HFDL_KEY.as_bytes() => return Some(MessageFamily::Hfdl),
+                        key if key == UAT_FRAME_KEY.as_bytes() || key == UAT_ADDRESS_QUALIFIER_KEY.as_bytes() => return Some(MessageFamily::Uat),
+                        _ => expecting_key = false,
+                    }
+                }
+                index = end;
+            }
+            b'{' | b'[' => {
+                depth += 1;
+                expecting_key = depth == 1;
+            }
+            b'}' | b']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(MessageFamily::Acars);
+                }
+            }
+            b',' if depth == 1 => expecting_key = true,
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Returns the index of the closing quote of a JSON string whose contents start at `start`.
+pub(crate) fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut index: usize = start;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' => return Some(index),
+            _ => index += 1,
+        }
+    }
+    None
+}
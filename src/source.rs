@@ -0,0 +1,88 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+use crate::transform::{Transform, TransformOutcome};
+
+/// What sent a message over the air, worked out the same way for every message family.
+///
+/// | Family | Aircraft | Ground station |
+/// |--------|----------|----------------|
+/// | ACARS | numeric block ID | letter block ID |
+/// | VDLM2 | AVLC source type `Aircraft` | AVLC source type `Ground station` |
+/// | HFDL | LPDU source type `Aircraft` | squitters and LPDU source type `Ground station` |
+/// | UAT | ADS-B address qualifiers | uplinks, TIS-B, ADS-R and fixed beacons |
+///
+/// ADS-B from surface vehicles and non-transponder emitters, ACARS blocks without a block ID and frames whose
+/// sender the decoder did not report are `Unknown`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    Aircraft,
+    GroundStation,
+    Unknown,
+}
+
+impl SourceKind {
+    /// Returns true if the message was sent by an aircraft.
+    pub fn is_aircraft(&self) -> bool {
+        *self == SourceKind::Aircraft
+    }
+
+    /// Returns true if the message was sent by a ground station, including ground stations rebroadcasting traffic.
+    pub fn is_ground_station(&self) -> bool {
+        *self == SourceKind::GroundStation
+    }
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceKind::Aircraft => write!(formatter, "aircraft"),
+            SourceKind::GroundStation => write!(formatter, "ground_station"),
+            SourceKind::Unknown => write!(formatter, "unknown"),
+        }
+    }
+}
+
+/// A `Transform` that forwards only the messages sent by the allowed kinds of source, such as only air-originated
+/// traffic, whatever their family.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::source::{SourceFilter, SourceKind};
+/// use acars_vdlm2_parser::transform::{Transform, TransformOutcome};
+/// let filter: SourceFilter = SourceFilter::only(&[SourceKind::Aircraft]);
+/// let mut downlink: AcarsVdlm2Message = r#"{"freq":131.55,"mode":"2","label":"H1","block_id":"4"}"#.decode_message().unwrap();
+/// let mut uplink: AcarsVdlm2Message = r#"{"freq":131.55,"mode":"2","label":"_d","block_id":"A"}"#.decode_message().unwrap();
+/// assert_eq!(downlink.source_kind(), SourceKind::Aircraft);
+/// assert_eq!(filter.apply(&mut downlink), TransformOutcome::Forward);
+/// assert!(matches!(filter.apply(&mut uplink), TransformOutcome::Drop(_)));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SourceFilter {
+    allowed: Vec<SourceKind>,
+}
+
+impl SourceFilter {
+    /// Creates a filter forwarding only messages whose `source_kind` is one of `allowed`.
+    pub fn only(allowed: &[SourceKind]) -> Self {
+        Self { allowed: allowed.to_vec() }
+    }
+
+    /// Returns true if the message would be forwarded.
+    pub fn allows(&self, message: &AcarsVdlm2Message) -> bool {
+        self.allowed.contains(&message.source_kind())
+    }
+}
+
+impl Transform for SourceFilter {
+    fn name(&self) -> &str {
+        "source_filter"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        match message.source_kind() {
+            kind if self.allowed.contains(&kind) => TransformOutcome::Forward,
+            kind => TransformOutcome::Drop(format!("sent by {} source", kind)),
+        }
+    }
+}
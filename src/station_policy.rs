@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+use crate::transform::{Transform, TransformOutcome};
+
+/// Whether a `StationPolicy` rule lets matching stations through or drops them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum StationAction {
+    Allow,
+    Deny,
+}
+
+/// A single allow or deny rule, along with how many messages it has dropped.
+#[derive(Debug)]
+struct StationRule {
+    action: StationAction,
+    pattern: String,
+    dropped: AtomicU64,
+}
+
+/// Counts of the messages dropped by a `StationPolicy`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct StationPolicyStats {
+    /// Messages dropped by each deny rule, keyed by pattern.
+    pub denied: BTreeMap<String, u64>,
+    /// Messages dropped because their station did not match any allow rule.
+    pub not_allowed: u64,
+    /// Messages dropped because they had no station name while allow rules are set.
+    pub no_station: u64,
+}
+
+/// Filters messages by the name of the station that received them, for aggregators dealing with misbehaving feeders.
+///
+/// Patterns match the whole station name, ignoring ASCII case, with `*` matching any run of characters and `?`
+/// matching a single character. A message is dropped if its station matches any deny rule. When there are allow
+/// rules, it is also dropped unless its station matches one of them, and messages without a station name are dropped.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::station_policy::StationPolicy;
+/// use acars_vdlm2_parser::transform::{Transform, TransformOutcome};
+/// let policy: StationPolicy = StationPolicy::new().allow("MN-*").deny("MN-TEST?");
+/// let mut allowed: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"station_id":"mn-ypph"}"#.decode_message().unwrap();
+/// let mut denied: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"station_id":"MN-TEST1"}"#.decode_message().unwrap();
+/// assert_eq!(policy.apply(&mut allowed), TransformOutcome::Forward);
+/// assert!(matches!(policy.apply(&mut denied), TransformOutcome::Drop(_)));
+/// assert_eq!(policy.stats().denied.get("MN-TEST?"), Some(&1));
+/// ```
+#[derive(Debug, Default)]
+pub struct StationPolicy {
+    rules: Vec<StationRule>,
+    not_allowed: AtomicU64,
+    no_station: AtomicU64,
+}
+
+impl StationPolicy {
+    /// Creates a `StationPolicy` with no rules, which lets every message through.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule letting stations matching the pattern through.
+    pub fn allow(self, pattern: &str) -> Self {
+        self.rule(StationAction::Allow, pattern)
+    }
+
+    /// Adds a rule dropping stations matching the pattern.
+    pub fn deny(self, pattern: &str) -> Self {
+        self.rule(StationAction::Deny, pattern)
+    }
+
+    /// Adds a rule with the provided action.
+    pub fn rule(mut self, action: StationAction, pattern: &str) -> Self {
+        self.rules.push(StationRule { action, pattern: pattern.to_string(), dropped: AtomicU64::new(0) });
+        self
+    }
+
+    fn has_allow_rules(&self) -> bool {
+        self.rules.iter().any(|rule| rule.action == StationAction::Allow)
+    }
+
+    /// Checks the message against the rules, returning the reason if it should be dropped and counting it.
+    pub fn check(&self, message: &AcarsVdlm2Message) -> Result<(), String> {
+        let Some(station) = message.get_station_name() else {
+            if self.has_allow_rules() {
+                self.no_station.fetch_add(1, Ordering::Relaxed);
+                return Err("no station name to match against the allow list".to_string());
+            }
+            return Ok(());
+        };
+        let denied: Option<&StationRule> = self.rules.iter()
+            .filter(|rule| rule.action == StationAction::Deny)
+            .find(|rule| wildcard_match(&rule.pattern, station));
+        if let Some(rule) = denied {
+            rule.dropped.fetch_add(1, Ordering::Relaxed);
+            return Err(format!("station {} denied by {}", station, rule.pattern));
+        }
+        let allowed: bool = self.rules.iter()
+            .filter(|rule| rule.action == StationAction::Allow)
+            .any(|rule| wildcard_match(&rule.pattern, station));
+        if self.has_allow_rules() && !allowed {
+            self.not_allowed.fetch_add(1, Ordering::Relaxed);
+            return Err(format!("station {} is not on the allow list", station));
+        }
+        Ok(())
+    }
+
+    /// Returns the number of messages dropped so far.
+    pub fn stats(&self) -> StationPolicyStats {
+        StationPolicyStats {
+            denied: self.rules.iter()
+                .filter(|rule| rule.action == StationAction::Deny)
+                .map(|rule| (rule.pattern.clone(), rule.dropped.load(Ordering::Relaxed)))
+                .collect(),
+            not_allowed: self.not_allowed.load(Ordering::Relaxed),
+            no_station: self.no_station.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Transform for StationPolicy {
+    fn name(&self) -> &str {
+        "station_policy"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        match self.check(message) {
+            Ok(()) => TransformOutcome::Forward,
+            Err(reason) => TransformOutcome::Drop(reason),
+        }
+    }
+}
+
+/// Matches the whole value against a pattern where `*` matches any run of characters and `?` a single character,
+/// ignoring ASCII case.
+pub fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|character| character.to_ascii_lowercase()).collect();
+    let value: Vec<char> = value.chars().map(|character| character.to_ascii_lowercase()).collect();
+    let (mut pattern_index, mut value_index): (usize, usize) = (0, 0);
+    // Where to resume after the most recent `*` if the rest of the pattern fails to match.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while value_index < value.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                backtrack = Some((pattern_index, value_index));
+                pattern_index += 1;
+            }
+            Some(character) if *character == '?' || *character == value[value_index] => {
+                pattern_index += 1;
+                value_index += 1;
+            }
+            _ => match backtrack {
+                Some((star_index, star_value_index)) => {
+                    pattern_index = star_index + 1;
+                    value_index = star_value_index + 1;
+                    backtrack = Some((star_index, star_value_index + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[pattern_index..].iter().all(|character| *character == '*')
+}
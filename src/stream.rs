@@ -0,0 +1,333 @@
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind, Read};
+use crate::{decode_direct, AcarsVdlm2Message, DecodeMessage, MessageResult};
+use crate::dead_letter::{attempted_families, FailedDecode};
+
+/// Bytes a single object or line may grow to before `JsonSplitter` gives up on finding its end.
+pub const DEFAULT_MAX_SEGMENT_BYTES: usize = 1024 * 1024;
+/// Bytes `StreamDecoder` asks the reader for at a time.
+const READ_CHUNK_BYTES: usize = 64 * 1024;
+
+/// What the splitter is in the middle of.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+enum SplitState {
+    /// Between segments.
+    #[default]
+    Idle,
+    /// Inside a JSON object, nested `depth` deep.
+    Object,
+    /// Inside a line that is not a JSON object, such as a legacy CSV line or a raw UAT frame.
+    Line,
+}
+
+/// Splits a stream of bytes into the segments a decoder takes one at a time, wherever the chunks it arrives in happen
+/// to end.
+///
+/// Newline delimited JSON, JSON objects written back to back without any separator (`}{`), as some satellite feed
+/// bridges do, objects spread over several lines and the objects of top level JSON arrays are all split into one
+/// segment per object. Braces inside strings, including escaped quotes, do not end an object. Input that does not
+/// start with `{` is split into lines as before, so legacy CSV lines and raw frames still come through.
+///
+/// JSON strings cannot hold a newline, so an object whose string runs on to the end of its line, such as one cut off
+/// by a feeder restarting, is returned at the newline so it fails to decode and the lines after it are still split.
+/// Once an object has come on a line of its own, the stream is taken to be newline delimited, and an object cut off
+/// outside a string is also returned at its newline when the next line starts another object.
+/// A segment that grows past the maximum size without ending is returned as it is for the same reason, rather than
+/// holding everything after it back.
+/// ```
+/// use acars_vdlm2_parser::stream::JsonSplitter;
+/// let mut splitter: JsonSplitter = JsonSplitter::new();
+/// let mut segments: Vec<Vec<u8>> = splitter.push(br#"{"text":"a } b"}{"freq":131.5"#);
+/// segments.extend(splitter.push(b"5}\n[{\"a\":1},{\"b\":2}]"));
+/// assert_eq!(segments, vec![br#"{"text":"a } b"}"#.to_vec(), br#"{"freq":131.55}"#.to_vec(), br#"{"a":1}"#.to_vec(), br#"{"b":2}"#.to_vec()]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonSplitter {
+    buffer: Vec<u8>,
+    scanned: usize,
+    state: SplitState,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    in_array: bool,
+    spans_lines: bool,
+    ended_on_one_line: bool,
+    newline_delimited: bool,
+    resync_at: Option<usize>,
+    max_segment_bytes: usize,
+}
+
+impl Default for JsonSplitter {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            scanned: 0,
+            state: SplitState::Idle,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            in_array: false,
+            spans_lines: false,
+            ended_on_one_line: false,
+            newline_delimited: false,
+            resync_at: None,
+            max_segment_bytes: DEFAULT_MAX_SEGMENT_BYTES,
+        }
+    }
+}
+
+impl JsonSplitter {
+    /// Creates a `JsonSplitter` with `DEFAULT_MAX_SEGMENT_BYTES`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the bytes a segment may grow to before it is returned unfinished.
+    pub fn max_segment_bytes(mut self, bytes: usize) -> Self {
+        self.max_segment_bytes = bytes;
+        self
+    }
+
+    /// Number of bytes held back as part of an unfinished segment.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Adds the next chunk of the stream, returning every segment it completes.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut segments: Vec<Vec<u8>> = Vec::new();
+        self.push_each(bytes, |segment| segments.push(segment.to_vec()));
+        segments
+    }
+
+    /// Adds the next chunk of the stream, passing every segment it completes to the closure without copying it.
+    ///
+    /// The buffer the segments are held in keeps its capacity between chunks, so once it has grown to fit the largest
+    /// unfinished segment, splitting allocates nothing.
+    pub fn push_each(&mut self, bytes: &[u8], mut each: impl FnMut(&[u8])) {
+        self.buffer.extend_from_slice(bytes);
+        let mut start: usize = 0;
+        for index in self.scanned..self.buffer.len() {
+            let byte: u8 = self.buffer[index];
+            match self.state {
+                SplitState::Idle => match byte {
+                    b'{' => {
+                        (self.state, self.depth, start) = (SplitState::Object, 1, index);
+                    }
+                    b'[' if !self.in_array => {
+                        self.in_array = true;
+                        start = index + 1;
+                    }
+                    b']' | b',' if self.in_array => {
+                        self.in_array = byte == b',';
+                        start = index + 1;
+                    }
+                    b'\n' => {
+                        self.newline_delimited |= self.ended_on_one_line;
+                        start = index + 1;
+                    }
+                    _ if byte.is_ascii_whitespace() => start = index + 1,
+                    _ => (self.state, start) = (SplitState::Line, index),
+                },
+                SplitState::Object if self.in_string => match byte {
+                    b'\n' => {
+                        emit_trimmed(&mut each, &self.buffer[start..index]);
+                        self.end_segment();
+                        start = index + 1;
+                    }
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                },
+                SplitState::Object => {
+                    // A newline and then `{` in a newline delimited stream starts the next line, so whatever was cut
+                    // off before it is returned on its own.
+                    if let (b'{', Some(newline)) = (byte, self.resync_at) {
+                        emit_trimmed(&mut each, &self.buffer[start..start + newline]);
+                        self.end_segment();
+                        (self.state, self.depth, start) = (SplitState::Object, 1, index);
+                        continue;
+                    }
+                    if !byte.is_ascii_whitespace() {
+                        self.resync_at = None;
+                    }
+                    match byte {
+                        b'\n' => {
+                            self.spans_lines = true;
+                            if self.newline_delimited {
+                                self.resync_at = Some(index - start);
+                            }
+                        }
+                        b'"' => self.in_string = true,
+                        b'{' | b'[' => self.depth += 1,
+                        b'}' | b']' => {
+                            self.depth -= 1;
+                            if self.depth == 0 {
+                                each(&self.buffer[start..=index]);
+                                let ended_on_one_line: bool = !self.spans_lines;
+                                self.end_segment();
+                                self.ended_on_one_line = ended_on_one_line;
+                                start = index + 1;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                SplitState::Line if byte == b'\n' => {
+                    self.newline_delimited = true;
+                    emit_trimmed(&mut each, &self.buffer[start..index]);
+                    (self.state, start) = (SplitState::Idle, index + 1);
+                }
+                SplitState::Line => {}
+            }
+        }
+        self.buffer.drain(..start);
+        self.scanned = self.buffer.len();
+        if self.buffer.len() > self.max_segment_bytes {
+            self.finish_each(each);
+        }
+    }
+
+    /// Returns whatever is left at the end of the stream as a final segment, such as a last line without a newline or
+    /// an object that was cut off, and resets the splitter.
+    pub fn finish(&mut self) -> Option<Vec<u8>> {
+        let mut remaining: Option<Vec<u8>> = None;
+        self.finish_each(|segment| remaining = Some(segment.to_vec()));
+        remaining
+    }
+
+    /// Passes whatever is left at the end of the stream to the closure as a final segment, like `finish`, keeping the
+    /// capacity of the buffer for the next stream.
+    pub fn finish_each(&mut self, mut each: impl FnMut(&[u8])) {
+        emit_trimmed(&mut each, &self.buffer);
+        self.buffer.clear();
+        self.end_segment();
+        (self.scanned, self.in_array, self.newline_delimited) = (0, false, false);
+    }
+
+    fn end_segment(&mut self) {
+        (self.state, self.depth, self.in_string, self.escaped) = (SplitState::Idle, 0, false, false);
+        (self.spans_lines, self.ended_on_one_line, self.resync_at) = (false, false, None);
+    }
+}
+
+fn emit_trimmed(each: &mut impl FnMut(&[u8]), segment: &[u8]) {
+    let trimmed: &[u8] = segment.trim_ascii();
+    if !trimmed.is_empty() {
+        each(trimmed);
+    }
+}
+
+/// Decodes every message in a byte stream, such as a `TcpStream` from a feed bridge or a capture file, split with a
+/// `JsonSplitter` so it does not need to be newline delimited.
+///
+/// Each item is a decoded message, or a `FailedDecode` for a segment that did not decode. Reading stops at the end of
+/// the stream, and a read error is returned as an item, after which reading can be tried again. A reader that returns
+/// `WouldBlock` ends the iteration early, and it can be resumed with `next` once more data is available.
+///
+/// Segments are decoded in place in the buffer of the `JsonSplitter`, without copying them. For bursts of high rate
+/// traffic, `direct_decode` also skips the `Value` tree each message is otherwise parsed into, see `decode_direct`.
+/// ```
+/// use acars_vdlm2_parser::stream::StreamDecoder;
+/// let input: &[u8] = br#"{"freq":131.55,"channel":2}{"freq":131.725,"channel":3,"text":"}{"}not json"#;
+/// let decoded: Vec<_> = StreamDecoder::new(input).source("bridge").map(Result::unwrap).collect();
+/// assert_eq!(decoded.len(), 3);
+/// assert!(decoded[0].is_ok() && decoded[1].is_ok());
+/// assert_eq!(decoded[2].as_ref().err().and_then(|failed| failed.source.as_deref()), Some("bridge"));
+/// ```
+#[derive(Debug)]
+pub struct StreamDecoder<R: Read> {
+    reader: R,
+    splitter: JsonSplitter,
+    pending: VecDeque<Result<AcarsVdlm2Message, FailedDecode>>,
+    /// Read buffer, reused for every read rather than zeroed on each call to `next`.
+    chunk: Box<[u8]>,
+    source: Option<String>,
+    direct: bool,
+    finished: bool,
+}
+
+impl<R: Read> StreamDecoder<R> {
+    /// Creates a `StreamDecoder` reading from the reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            splitter: JsonSplitter::new(),
+            pending: VecDeque::new(),
+            chunk: vec![0; READ_CHUNK_BYTES].into_boxed_slice(),
+            source: None,
+            direct: false,
+            finished: false,
+        }
+    }
+
+    /// Sets the source recorded on every `FailedDecode`, such as a feeder name or socket address.
+    pub fn source(mut self, source: &str) -> Self {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    /// Sets the bytes a single object or line may grow to, see `JsonSplitter::max_segment_bytes`.
+    pub fn max_segment_bytes(mut self, bytes: usize) -> Self {
+        self.splitter = self.splitter.max_segment_bytes(bytes);
+        self
+    }
+
+    /// Sets whether messages are decoded with `decode_direct`, which allocates less for the same result. This is off
+    /// by default.
+    pub fn direct_decode(mut self, direct: bool) -> Self {
+        self.direct = direct;
+        self
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn fill(&mut self, chunk: Option<&[u8]>) {
+        let source: Option<&str> = self.source.as_deref();
+        let pending: &mut VecDeque<Result<AcarsVdlm2Message, FailedDecode>> = &mut self.pending;
+        let decode = |segment: &[u8]| {
+            let decoded: MessageResult<AcarsVdlm2Message> = match self.direct {
+                true => decode_direct(segment),
+                false => segment.decode_message(),
+            };
+            pending.push_back(decoded.map_err(|error| {
+                FailedDecode::new(&String::from_utf8_lossy(segment), &error, &attempted_families(segment), source)
+            }));
+        };
+        match chunk {
+            Some(chunk) => self.splitter.push_each(chunk, decode),
+            None => self.splitter.finish_each(decode),
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamDecoder<R> {
+    type Item = io::Result<Result<AcarsVdlm2Message, FailedDecode>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(decoded) = self.pending.pop_front() {
+            return Some(Ok(decoded));
+        }
+        while self.pending.is_empty() && !self.finished {
+            match self.reader.read(&mut self.chunk) {
+                Ok(0) => {
+                    self.finished = true;
+                    self.fill(None);
+                }
+                Ok(read) => {
+                    let chunk: Box<[u8]> = std::mem::take(&mut self.chunk);
+                    self.fill(Some(&chunk[..read]));
+                    self.chunk = chunk;
+                }
+                Err(read_error) if read_error.kind() == ErrorKind::Interrupted => {}
+                Err(read_error) if read_error.kind() == ErrorKind::WouldBlock => return None,
+                Err(read_error) => return Some(Err(read_error)),
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
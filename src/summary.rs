@@ -0,0 +1,50 @@
+use serde::Serialize;
+use crate::{AcarsVdlm2Message, MessageFamily};
+
+/// Maximum number of characters of message text kept in `MessageSummary::snippet`.
+pub const SNIPPET_CHARS: usize = 48;
+
+/// The few fields a list view shows for a message, returned by `AcarsVdlm2Message::summary()`.
+///
+/// Every string borrows from the message, so building a summary for each row of a list does not allocate. The
+/// registration has its leading dots removed, and the snippet is the first line of the text cut to `SNIPPET_CHARS`
+/// characters.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct MessageSummary<'a> {
+    /// Time the message was received, in seconds since the Unix epoch.
+    pub time: Option<f64>,
+    pub family: MessageFamily,
+    pub icao: Option<u32>,
+    pub tail: Option<&'a str>,
+    pub flight: Option<&'a str>,
+    pub label: Option<&'a str>,
+    pub snippet: Option<&'a str>,
+}
+
+impl<'a> MessageSummary<'a> {
+    pub(crate) fn of(message: &'a AcarsVdlm2Message) -> Self {
+        Self {
+            time: message.get_time(),
+            family: message.get_family(),
+            icao: message.get_icao(),
+            tail: message.get_registration().filter(|tail| !tail.is_empty()),
+            flight: message.get_flight().map(str::trim).filter(|flight| !flight.is_empty()),
+            label: message.get_label(),
+            snippet: message.get_text().map(snippet).filter(|snippet| !snippet.is_empty()),
+        }
+    }
+
+    /// Returns true if the snippet is shorter than the message text, so a list can show that it was cut.
+    pub fn is_truncated(&self, message: &AcarsVdlm2Message) -> bool {
+        self.snippet.map_or(0, str::len) < message.get_text().map_or(0, |text| text.trim().len())
+    }
+}
+
+/// Returns the first non empty line of the text, cut to `SNIPPET_CHARS` characters on a character boundary.
+fn snippet(text: &str) -> &str {
+    let line: &str = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    match line.char_indices().nth(SNIPPET_CHARS) {
+        Some((end, _)) => line[..end].trim_end(),
+        None => line,
+    }
+}
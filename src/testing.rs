@@ -0,0 +1,193 @@
+//! Helpers for reproducible tests: a seedable random number generator for shuffling test data, golden output files
+//! to compare serialised messages against, and an assertion that a corpus of captures round trips. These are only
+//! built with the `testing` feature, as they print and panic.
+//!
+//! A randomised run prints the seed it used, so a failure can be replayed exactly by setting `SEED_VARIABLE` to that
+//! seed. Golden files are rewritten from the current output when `UPDATE_GOLDEN_VARIABLE` is set.
+// Everything here is for test suites, which should stop on a bad seed or a golden file mismatch.
+#![allow(clippy::panic)]
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use uuid::Uuid;
+use crate::{AcarsVdlm2Message, MessageResult};
+use crate::corpus::{Corpus, CorpusReport, TagFilter};
+use crate::ndjson::NdjsonWriter;
+
+/// Environment variable holding the seed `SeededRng::from_env` uses, as a decimal or `0x` prefixed hex number.
+pub const SEED_VARIABLE: &str = "ACARS_VDLM2_TEST_SEED";
+
+/// Environment variable that, when set to anything other than `0`, makes `assert_golden` write the output it is given
+/// to the golden file rather than comparing against it.
+pub const UPDATE_GOLDEN_VARIABLE: &str = "ACARS_VDLM2_UPDATE_GOLDEN";
+
+/// A small seedable pseudo-random number generator (SplitMix64) for shuffling and sampling test data.
+///
+/// The same seed always gives the same sequence, on every platform and in every version of this crate, so a failing
+/// randomised test can be replayed from its seed. It is not suitable for anything that needs unpredictable numbers.
+/// ```
+/// use acars_vdlm2_parser::testing::SeededRng;
+/// let mut first: SeededRng = SeededRng::new(42);
+/// let mut second: SeededRng = SeededRng::new(42);
+/// let mut lines: Vec<u32> = (0..10).collect();
+/// let mut replayed: Vec<u32> = lines.clone();
+/// first.shuffle(&mut lines);
+/// second.shuffle(&mut replayed);
+/// assert_eq!(lines, replayed);
+/// assert_ne!(lines, (0..10).collect::<Vec<u32>>());
+/// assert_eq!(first.seed(), 42);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SeededRng {
+    seed: u64,
+    state: u64,
+}
+
+impl SeededRng {
+    /// Creates a `SeededRng` that replays the sequence of the provided seed.
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// Creates a `SeededRng` from the seed in `SEED_VARIABLE`, or from a random seed if it is not set, and prints the
+    /// seed so the run can be replayed.
+    ///
+    /// The test harness only shows the printed seed for tests that fail. A seed that cannot be parsed is a panic, as
+    /// silently using another seed would not replay the run asked for.
+    pub fn from_env() -> Self {
+        let seed: u64 = match env::var(SEED_VARIABLE) {
+            Ok(value) => parse_seed(&value).unwrap_or_else(|| panic!("{} is not a valid seed: {}", SEED_VARIABLE, value)),
+            Err(_) => Uuid::new_v4().as_u64_pair().0,
+        };
+        eprintln!("Shuffling with seed {}, set {}={} to replay this run", seed, SEED_VARIABLE, seed);
+        Self::new(seed)
+    }
+
+    /// The seed this generator was created from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the next number in the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value: u64 = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Returns a number below the bound, which must not be 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "SeededRng::below called with a bound of 0");
+        // Reject the top of the range that does not divide evenly, so every result is equally likely.
+        let bound: u64 = bound as u64;
+        let zone: u64 = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value: u64 = self.next_u64();
+            if value < zone {
+                return (value % bound) as usize;
+            }
+        }
+    }
+
+    /// Shuffles the slice in place with a Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other: usize = self.below(index + 1);
+            items.swap(index, other);
+        }
+    }
+}
+
+/// Parses a seed written as a decimal or `0x` prefixed hex number.
+fn parse_seed(value: &str) -> Option<u64> {
+    let value: &str = value.trim();
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse::<u64>().ok(),
+    }
+}
+
+/// Compares the output against the golden file at the path, panicking with the first line that differs.
+///
+/// When `UPDATE_GOLDEN_VARIABLE` is set the file is written from the output instead, creating any missing
+/// directories, so golden files are created and updated with `ACARS_VDLM2_UPDATE_GOLDEN=1 cargo test` and the change
+/// reviewed in the diff. Line endings are compared ignoring `\r`, so files checked out on Windows still match.
+pub fn assert_golden(path: impl AsRef<Path>, output: &str) {
+    let path: &Path = path.as_ref();
+    if env::var(UPDATE_GOLDEN_VARIABLE).is_ok_and(|value| value != "0") {
+        if let Err(write_error) = write_golden(path, output) {
+            panic!("Could not write the golden file {}: {}", path.display(), write_error);
+        }
+        return;
+    }
+    let expected: String = match fs::read_to_string(path) {
+        Err(read_error) => panic!("Could not read the golden file {}: {}, set {}=1 to create it",
+                                  path.display(), read_error, UPDATE_GOLDEN_VARIABLE),
+        Ok(expected) => expected.replace('\r', ""),
+    };
+    let output: String = output.replace('\r', "");
+    if expected == output {
+        return;
+    }
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let output_lines: Vec<&str> = output.lines().collect();
+    let line: usize = expected_lines.iter().zip(&output_lines)
+        .position(|(expected, output)| expected != output)
+        .unwrap_or(expected_lines.len().min(output_lines.len()));
+    panic!("Output differs from the golden file {} at line {}:\nexpected: {}\n  actual: {}\nset {}=1 to update it",
+           path.display(), line + 1,
+           expected_lines.get(line).unwrap_or(&"<end of file>"),
+           output_lines.get(line).unwrap_or(&"<end of output>"),
+           UPDATE_GOLDEN_VARIABLE);
+}
+
+/// Serialises each message to a line of JSON and compares them against the golden file at the path, see
+/// `assert_golden`.
+pub fn assert_golden_messages(path: impl AsRef<Path>, messages: &[AcarsVdlm2Message]) {
+    let mut writer: NdjsonWriter<Vec<u8>> = NdjsonWriter::new(Vec::new()).flush_threshold(usize::MAX);
+    let output: MessageResult<Vec<u8>> = messages.iter()
+        .try_for_each(|message| writer.write(message).map(|_| ()))
+        .and_then(|_| writer.into_inner());
+    match output.map(String::from_utf8) {
+        Err(serialisation_error) => panic!("Could not serialise the messages for {}: {}", path.as_ref().display(), serialisation_error),
+        Ok(Err(utf8_error)) => panic!("Serialised messages for {} are not UTF-8: {}", path.as_ref().display(), utf8_error),
+        Ok(Ok(lines)) => assert_golden(path, &lines),
+    }
+}
+
+/// Checks every line of the files in the corpus matching the filter, panicking with every failure if any line misbehaves.
+///
+/// See `Corpus::check_roundtrip` for what is checked. Returns the report so callers can make further assertions.
+/// ```
+/// use acars_vdlm2_parser::corpus::{Corpus, CorpusReport, TagFilter};
+/// use acars_vdlm2_parser::testing::assert_corpus_roundtrip;
+/// let mut corpus: Corpus = Corpus::new();
+/// corpus.register("test_files/vdlm2", &["dumpvdl2", "vdlm2"]);
+/// let report: CorpusReport = assert_corpus_roundtrip(&corpus, &TagFilter::any().require("vdlm2"));
+/// assert_eq!(report.files, 1);
+/// ```
+pub fn assert_corpus_roundtrip(corpus: &Corpus, tag_filter: &TagFilter) -> CorpusReport {
+    let report: CorpusReport = match corpus.check_roundtrip(tag_filter) {
+        Err(read_error) => panic!("Could not read the corpus: {}", read_error),
+        Ok(report) => report,
+    };
+    assert!(report.files > 0, "No corpus files match {:?}", tag_filter);
+    if !report.is_ok() {
+        let failures: Vec<String> = report.failures.iter()
+            .map(|failure| format!("{}:{} {}", failure.path.display(), failure.line, failure.reason))
+            .collect();
+        panic!("{} of {} corpus lines failed:\n{}", failures.len(), report.lines, failures.join("\n"));
+    }
+    report
+}
+
+fn write_golden(path: &Path, output: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, output)
+}
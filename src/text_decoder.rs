@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::AcarsVdlm2Message;
+
+/// Trait for a decoder that turns airline specific ACARS message text, such as label `5Z`, into structured data.
+///
+/// Implementations take `&self` so a registry can be shared between threads.
+pub trait TextDecoder: Send + Sync {
+    /// Name of the decoder, included with the decoded payload.
+    fn name(&self) -> &str;
+    /// Decodes the message text, returning `None` if the text is not in a format this decoder understands.
+    fn decode(&self, text: &str) -> Option<Value>;
+}
+
+/// Describes which messages a `TextDecoder` should be offered.
+///
+/// The label must match, and the sublabel and airline prefix must match when they are set.
+/// The airline prefix is matched against the start of the flight number, such as `UA` or `UAL`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct TextDecoderKey {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sublabel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airline: Option<String>,
+}
+
+impl TextDecoderKey {
+    /// Creates a `TextDecoderKey` matching every message with the provided label.
+    pub fn label(label: &str) -> Self {
+        Self { label: label.to_string(), ..Default::default() }
+    }
+
+    /// Restricts the key to messages with the provided sublabel.
+    pub fn with_sublabel(mut self, sublabel: &str) -> Self {
+        self.sublabel = Some(sublabel.to_string());
+        self
+    }
+
+    /// Restricts the key to flights whose number starts with the provided airline prefix.
+    pub fn with_airline(mut self, airline: &str) -> Self {
+        self.airline = Some(airline.to_string());
+        self
+    }
+
+    /// Returns true if the message matches this key.
+    pub fn matches(&self, message: &AcarsVdlm2Message) -> bool {
+        let sublabel_matches: bool = match &self.sublabel {
+            None => true,
+            Some(sublabel) => message.get_sublabel() == Some(sublabel.as_str()),
+        };
+        let airline_matches: bool = match &self.airline {
+            None => true,
+            Some(airline) => message.get_flight().is_some_and(|flight| flight.starts_with(airline.as_str())),
+        };
+        message.get_label() == Some(self.label.as_str()) && sublabel_matches && airline_matches
+    }
+
+    /// Number of optional restrictions set, used to prefer the most specific decoder.
+    fn specificity(&self) -> usize {
+        [self.sublabel.is_some(), self.airline.is_some()].into_iter()
+            .filter(|restricted| *restricted)
+            .count()
+    }
+}
+
+/// Structured payload returned by a `TextDecoder`, along with the name of the decoder that produced it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DecodedText {
+    pub decoder: String,
+    pub payload: Value,
+}
+
+/// Registry of `TextDecoder` implementations keyed by label, sublabel and airline prefix.
+///
+/// When several decoders match a message they are tried from the most specific key to the least specific,
+/// and in the order they were registered when equally specific, until one of them decodes the text.
+/// ```
+/// use serde_json::{json, Value};
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::text_decoder::{DecodedText, TextDecoder, TextDecoderKey, TextDecoderRegistry};
+/// struct GateDecoder;
+/// impl TextDecoder for GateDecoder {
+///     fn name(&self) -> &str { "gate" }
+///     fn decode(&self, text: &str) -> Option<Value> {
+///         text.strip_prefix("GATE ").map(|gate| json!({ "gate": gate }))
+///     }
+/// }
+/// let mut registry: TextDecoderRegistry = TextDecoderRegistry::new();
+/// registry.register(TextDecoderKey::label("5Z").with_airline("UA"), GateDecoder);
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"5Z","flight":"UA0123","text":"GATE B12"}"#.decode_message().unwrap();
+/// let decoded: DecodedText = message.decode_text(&registry).unwrap();
+/// assert_eq!(decoded.payload, json!({ "gate": "B12" }));
+/// ```
+#[derive(Default)]
+pub struct TextDecoderRegistry {
+    decoders: Vec<(TextDecoderKey, Box<dyn TextDecoder>)>,
+}
+
+impl TextDecoderRegistry {
+    /// Creates a new, empty `TextDecoderRegistry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a decoder for messages matching the provided key.
+    pub fn register<T: TextDecoder + 'static>(&mut self, key: TextDecoderKey, decoder: T) {
+        self.decoders.push((key, Box::new(decoder)));
+        // A stable sort keeps registration order between equally specific keys.
+        self.decoders.sort_by_key(|(key, _)| std::cmp::Reverse(key.specificity()));
+    }
+
+    /// Number of decoders registered.
+    pub fn len(&self) -> usize {
+        self.decoders.len()
+    }
+
+    /// Returns true if no decoders are registered.
+    pub fn is_empty(&self) -> bool {
+        self.decoders.is_empty()
+    }
+
+    /// Decodes the message text with the first matching decoder that understands it.
+    pub fn decode_text(&self, message: &AcarsVdlm2Message) -> Option<DecodedText> {
+        let text: &str = message.get_text()?;
+        self.decoders.iter()
+            .filter(|(key, _)| key.matches(message))
+            .find_map(|(_, decoder)| {
+                trace!("Trying text decoder {} on {:?}", decoder.name(), text);
+                decoder.decode(text).map(|payload| DecodedText { decoder: decoder.name().to_string(), payload })
+            })
+    }
+}
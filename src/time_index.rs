@@ -0,0 +1,138 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, DecodeMessage};
+
+/// A block of consecutive lines in a capture file, with the range of message times in it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TimeIndexEntry {
+    /// Byte offset of the first line of the block.
+    pub offset: u64,
+    /// Earliest message time in the block, or `None` if no message in it has a time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_time: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_time: Option<f64>,
+}
+
+impl TimeIndexEntry {
+    /// Returns true if a message in the block may have a time from `start` up to but not including `end`.
+    pub fn overlaps(&self, start: f64, end: f64) -> bool {
+        match (self.min_time, self.max_time) {
+            (Some(min_time), Some(max_time)) => min_time < end && max_time >= start,
+            _ => false,
+        }
+    }
+}
+
+/// An index over a capture file with an entry for every block of `lines_per_entry` lines.
+///
+/// This is serialisable, so an index built once can be stored next to a capture that no longer changes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TimeIndex {
+    pub lines_per_entry: usize,
+    /// Length of the capture file when the index was built.
+    pub file_len: u64,
+    pub entries: Vec<TimeIndexEntry>,
+}
+
+/// Reads the messages in a time range from a large capture file, one message per line, without scanning the whole file.
+///
+/// Opening the reader scans the file once to build a `TimeIndex`, keeping the offset and the earliest and latest
+/// message time of every block of lines. `read_range` then only reads the blocks whose times overlap the range. As each
+/// block keeps both its earliest and latest time, captures merged from several feeders that are not strictly in time
+/// order are still read correctly. Times come from `AcarsVdlm2Message::get_time()`, so every family is indexed the same
+/// way; messages without a time, and lines that fail to decode, are never returned.
+/// ```
+/// use acars_vdlm2_parser::AcarsVdlm2Message;
+/// use acars_vdlm2_parser::time_index::TimeIndexedReader;
+/// let reader: TimeIndexedReader = TimeIndexedReader::open("test_files/hfdl", 100).unwrap();
+/// let first_time: f64 = reader.index().entries[0].min_time.unwrap();
+/// let messages: Vec<AcarsVdlm2Message> = reader.read_range(first_time, first_time + 60.0).unwrap();
+/// assert!(messages.iter().all(|message| message.get_time().is_some_and(|time| time < first_time + 60.0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeIndexedReader {
+    path: PathBuf,
+    index: TimeIndex,
+}
+
+impl TimeIndexedReader {
+    /// Builds an index over the file with an entry for every `lines_per_entry` lines.
+    ///
+    /// Smaller blocks make ranges quicker to read at the cost of a larger index.
+    pub fn open(path: impl AsRef<Path>, lines_per_entry: usize) -> io::Result<Self> {
+        let lines_per_entry: usize = lines_per_entry.max(1);
+        let mut reader: BufReader<File> = BufReader::new(File::open(path.as_ref())?);
+        let mut index: TimeIndex = TimeIndex { lines_per_entry, ..Default::default() };
+        let mut line: Vec<u8> = Vec::new();
+        let mut offset: u64 = 0;
+        let mut line_number: usize = 0;
+        loop {
+            line.clear();
+            let read: usize = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
+                break;
+            }
+            if line_number.is_multiple_of(lines_per_entry) {
+                index.entries.push(TimeIndexEntry { offset, min_time: None, max_time: None });
+            }
+            if let (Some(time), Some(entry)) = (message_time(&line), index.entries.last_mut()) {
+                entry.min_time = Some(entry.min_time.map_or(time, |min_time| min_time.min(time)));
+                entry.max_time = Some(entry.max_time.map_or(time, |max_time| max_time.max(time)));
+            }
+            offset += read as u64;
+            line_number += 1;
+        }
+        index.file_len = offset;
+        Ok(Self::with_index(path, index))
+    }
+
+    /// Creates a reader from an index built earlier for the same file.
+    pub fn with_index(path: impl AsRef<Path>, index: TimeIndex) -> Self {
+        Self { path: path.as_ref().to_path_buf(), index }
+    }
+
+    /// Returns the index.
+    pub fn index(&self) -> &TimeIndex {
+        &self.index
+    }
+
+    /// Returns the messages timed from `start` up to but not including `end`, in the order they are in the file.
+    ///
+    /// Returns an `InvalidData` error if the index does not match the file, as when the file changed after the index
+    /// was built or a stored index is corrupt, with entries out of order or past the end of the file.
+    pub fn read_range(&self, start: f64, end: f64) -> io::Result<Vec<AcarsVdlm2Message>> {
+        let mut file: File = File::open(&self.path)?;
+        let file_len: u64 = file.metadata()?.len();
+        if file_len != self.index.file_len {
+            return Err(invalid_index(format!("index is for a file of {} bytes but the file is {} bytes", self.index.file_len, file_len)));
+        }
+        let mut messages: Vec<AcarsVdlm2Message> = Vec::new();
+        let mut buffer: Vec<u8> = Vec::new();
+        for (position, entry) in self.index.entries.iter().enumerate() {
+            if !entry.overlaps(start, end) {
+                continue;
+            }
+            let block_end: u64 = self.index.entries.get(position + 1).map_or(self.index.file_len, |next| next.offset);
+            buffer.clear();
+            file.seek(SeekFrom::Start(entry.offset))?;
+            let block_len: u64 = block_end.checked_sub(entry.offset)
+                .ok_or_else(|| invalid_index(format!("index entry {} at byte {} ends before it starts", position, entry.offset)))?;
+            (&mut file).take(block_len).read_to_end(&mut buffer)?;
+            messages.extend(buffer.split(|byte| *byte == b'\n')
+                .filter_map(|line| line.decode_message().ok())
+                .filter(|message| message.get_time().is_some_and(|time| time >= start && time < end)));
+        }
+        Ok(messages)
+    }
+}
+
+fn invalid_index(error: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn message_time(line: &[u8]) -> Option<f64> {
+    line.decode_message().ok().and_then(|message| message.get_time())
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use crate::provider;
+
+const MICROS_PER_SEC: f64 = 1_000_000.0;
+
+/// Where the time of a `MessageTimestamp` came from, so feeds can be merged knowing which times are comparable.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampSource {
+    /// The time the receiving station heard the burst on the radio, as reported by dumpvdl2 and dumphfdl.
+    Station,
+    /// The time the decoder stamped the message with once it was decoded, as reported by acarsdec.
+    Decoder,
+    /// The time the message was received by the ingest layer, for messages that do not carry a time of their own.
+    Ingest,
+}
+
+/// The resolution the seconds of a `MessageTimestamp` were reported at.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampPrecision {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+}
+
+/// The time of a message along with where it came from, returned by `AcarsVdlm2Message::get_timestamp()`.
+///
+/// `get_time()` returns the same seconds without the source and precision.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::timestamp::{MessageTimestamp, TimestampPrecision, TimestampSource};
+/// let message: AcarsVdlm2Message = r#"{"hfdl":{"freq":8942000,"bit_rate":1800,"slot":"S","t":{"sec":1700000000,"usec":5000}}}"#.decode_message().unwrap();
+/// let timestamp: MessageTimestamp = message.get_timestamp().unwrap();
+/// assert_eq!(timestamp.seconds, 1700000000.005);
+/// assert_eq!(timestamp.source, TimestampSource::Station);
+/// assert_eq!(timestamp.precision, TimestampPrecision::Microseconds);
+/// assert_eq!(message.get_time(), Some(1700000000.005));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MessageTimestamp {
+    /// Seconds since the Unix epoch.
+    pub seconds: f64,
+    pub source: TimestampSource,
+    pub precision: TimestampPrecision,
+}
+
+impl MessageTimestamp {
+    /// Creates a `MessageTimestamp`.
+    pub fn new(seconds: f64, source: TimestampSource, precision: TimestampPrecision) -> Self {
+        Self { seconds, source, precision }
+    }
+
+    /// Creates a `MessageTimestamp` from the seconds and microseconds reported by dumpvdl2 and dumphfdl.
+    pub fn from_station(sec: u64, usec: u64) -> Self {
+        Self::new(sec as f64 + usec as f64 / MICROS_PER_SEC, TimestampSource::Station, TimestampPrecision::Microseconds)
+    }
+
+    /// Creates an ingest `MessageTimestamp` for the current time, for messages without a time of their own.
+    pub fn ingested_now() -> Self {
+        let seconds: f64 = provider::now();
+        Self::new(seconds, TimestampSource::Ingest, TimestampPrecision::Microseconds)
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde_json::Value;
+use crate::AcarsVdlm2Message;
+use crate::provenance::Provenance;
+use crate::roundtrip::{diff_values, FieldDifference};
+
+/// Result of running a `Transform` against a message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TransformOutcome {
+    /// The message should continue through the pipeline.
+    Forward,
+    /// The message should be dropped, with the reason it was dropped.
+    Drop(String),
+}
+
+/// Outcome of every message in a batch run through `TransformPipeline::apply_batch`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BatchReport {
+    /// Outcome of each message, in the same order as the batch.
+    pub outcomes: Vec<TransformOutcome>,
+}
+
+impl BatchReport {
+    /// Number of messages that made it through the pipeline.
+    pub fn forwarded(&self) -> usize {
+        self.outcomes.iter().filter(|outcome| **outcome == TransformOutcome::Forward).count()
+    }
+
+    /// Returns the position in the batch and the reason of every message that was dropped.
+    pub fn dropped(&self) -> Vec<(usize, &str)> {
+        self.outcomes.iter().enumerate()
+            .filter_map(|(index, outcome)| match outcome {
+                TransformOutcome::Drop(reason) => Some((index, reason.as_str())),
+                TransformOutcome::Forward => None,
+            })
+            .collect()
+    }
+
+    /// Keeps the messages that were forwarded, given the batch the report was made from.
+    pub fn retain_forwarded(&self, messages: Vec<AcarsVdlm2Message>) -> Vec<AcarsVdlm2Message> {
+        messages.into_iter().zip(&self.outcomes)
+            .filter(|(_, outcome)| **outcome == TransformOutcome::Forward)
+            .map(|(message, _)| message)
+            .collect()
+    }
+}
+
+/// Trait for a single step that inspects or modifies messages before they are forwarded.
+///
+/// Implementations take `&self` so a pipeline can be shared between threads; any state must use interior mutability.
+pub trait Transform: Send + Sync {
+    /// Name of the transform, used when reporting what happened to a message.
+    fn name(&self) -> &str;
+    /// Applies the transform to the message.
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome;
+}
+
+/// An ordered list of `Transform` steps that are applied to each message in turn.
+///
+/// Processing stops at the first transform that drops the message.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::redact::StationRedactor;
+/// use acars_vdlm2_parser::transform::{TransformOutcome, TransformPipeline};
+/// let mut pipeline: TransformPipeline = TransformPipeline::new();
+/// pipeline.add(StationRedactor::new("salt"));
+/// let mut message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"station_id":"MN-YPPH"}"#.decode_message().unwrap();
+/// assert_eq!(pipeline.apply(&mut message), TransformOutcome::Forward);
+/// assert_ne!(message.get_station_name(), Some("MN-YPPH"));
+/// ```
+#[derive(Default)]
+pub struct TransformPipeline {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl TransformPipeline {
+    /// Creates a new, empty `TransformPipeline`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a transform to the end of the pipeline.
+    pub fn add<T: Transform + 'static>(&mut self, transform: T) {
+        self.transforms.push(Box::new(transform));
+    }
+
+    /// Number of transforms in the pipeline.
+    pub fn len(&self) -> usize {
+        self.transforms.len()
+    }
+
+    /// Returns true if the pipeline has no transforms.
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// Applies every transform in order, stopping at the first one that drops the message.
+    pub fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        for transform in &self.transforms {
+            trace!("Applying transform {} to {:?}", transform.name(), &message);
+            if let TransformOutcome::Drop(reason) = transform.apply(message) {
+                debug!("Transform {} dropped the message: {}", transform.name(), reason);
+                return TransformOutcome::Drop(reason);
+            }
+        }
+        TransformOutcome::Forward
+    }
+
+    /// Applies every transform in order like `apply`, adding a step to the provenance for each one that ran.
+    ///
+    /// The message is serialised before and after each transform to find the fields it changed, so this costs
+    /// noticeably more than `apply` and is meant for debugging pipelines rather than every message.
+    pub fn apply_recording(&self, message: &mut AcarsVdlm2Message, provenance: &mut Provenance) -> TransformOutcome {
+        let mut before: Value = serde_json::to_value(&*message).unwrap_or_default();
+        for transform in &self.transforms {
+            let outcome: TransformOutcome = transform.apply(message);
+            let after: Value = serde_json::to_value(&*message).unwrap_or_default();
+            let mut differences: Vec<FieldDifference> = Vec::new();
+            diff_values("", &before, &after, &mut differences);
+            let changed: Vec<String> = differences.into_iter().map(|difference| difference.path).collect();
+            match outcome {
+                TransformOutcome::Forward => provenance.record(transform.name(), changed, None),
+                TransformOutcome::Drop(reason) => {
+                    debug!("Transform {} dropped the message: {}", transform.name(), reason);
+                    provenance.record(transform.name(), changed, Some(reason.clone()));
+                    return TransformOutcome::Drop(reason);
+                }
+            }
+            before = after;
+        }
+        TransformOutcome::Forward
+    }
+
+    /// Applies the pipeline to every message in a batch, such as when re-annotating an archive, and reports the
+    /// outcome of each one. Dropped messages are left in the batch, modified by the transforms that ran before the drop.
+    ///
+    /// With the `rayon` feature the messages are processed in parallel, otherwise one after another.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use acars_vdlm2_parser::redact::StationRedactor;
+    /// use acars_vdlm2_parser::transform::{BatchReport, TransformPipeline};
+    /// let mut pipeline: TransformPipeline = TransformPipeline::new();
+    /// pipeline.add(StationRedactor::new("salt"));
+    /// let mut batch: Vec<AcarsVdlm2Message> = vec![
+    ///     r#"{"freq":131.55,"channel":2,"station_id":"MN-YPPH"}"#.decode_message().unwrap(),
+    ///     r#"{"freq":131.725,"channel":1,"station_id":"MN-YSSY"}"#.decode_message().unwrap(),
+    /// ];
+    /// let report: BatchReport = pipeline.apply_batch(&mut batch);
+    /// assert_eq!(report.forwarded(), 2);
+    /// assert!(batch.iter().all(|message| !message.get_station_name().unwrap().starts_with("MN-")));
+    /// ```
+    pub fn apply_batch(&self, messages: &mut [AcarsVdlm2Message]) -> BatchReport {
+        trace!("Applying the pipeline to a batch of {} messages", messages.len());
+        #[cfg(feature = "rayon")]
+        let outcomes: Vec<TransformOutcome> = messages.par_iter_mut().map(|message| self.apply(message)).collect();
+        #[cfg(not(feature = "rayon"))]
+        let outcomes: Vec<TransformOutcome> = messages.iter_mut().map(|message| self.apply(message)).collect();
+        BatchReport { outcomes }
+    }
+}
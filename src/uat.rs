@@ -0,0 +1,896 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::Error as DeError;
+use serde_json::Value;
+use crate::{AppDetails, MessageFamily, MessageResult, SummaryLine};
+use crate::app_template::AppDetailsTemplate;
+use crate::coerce::option_number_or_string;
+use crate::equality::{eq_ignoring, FieldClass};
+#[cfg(feature = "enrichment")]
+use crate::enrichment::AircraftInfo;
+use crate::frequency::Frequency;
+use crate::icao::parse_icao_hex;
+use crate::instrument::decode_span;
+use crate::quality::FrameQuality;
+use crate::source::SourceKind;
+use crate::redact::{AircraftAnonymiser, StationRedactor};
+use crate::timestamp::{MessageTimestamp, TimestampPrecision, TimestampSource};
+use crate::visit::{MessageVisitor, PositionReport, PositionSource, SignalInfo};
+// These live in `sniff` so input can be recognised as UAT without the `uat` feature.
+pub use crate::sniff::{is_uat_raw, UAT_ADDRESS_QUALIFIER_KEY, UAT_FRAME_KEY};
+
+/// The single frequency UAT is transmitted on.
+pub const UAT_FREQUENCY_HZ: u64 = 978_000_000;
+/// Length in bytes of a basic UAT ADS-B downlink payload.
+pub const DOWNLINK_BASIC_BYTES: usize = 18;
+/// Length in bytes of a long UAT ADS-B downlink payload.
+pub const DOWNLINK_LONG_BYTES: usize = 34;
+/// Length in bytes of a UAT ground uplink payload.
+pub const UPLINK_BYTES: usize = 432;
+
+/// Address qualifiers, as dump978-fa names them, whose address is an ICAO 24 bit address.
+const ICAO_QUALIFIERS: [&str; 2] = ["adsb_icao", "tisb_icao"];
+/// Address qualifier numbers, from the raw downlink header, whose address is an ICAO 24 bit address.
+const ICAO_QUALIFIER_NUMBERS: [u8; 2] = [0, 2];
+/// Address qualifiers of ADS-B sent by the aircraft itself.
+const AIRCRAFT_QUALIFIERS: [&str; 2] = ["adsb_icao", "adsb_other"];
+/// Address qualifiers of TIS-B and ADS-R rebroadcast by ground stations, and of fixed beacons.
+const GROUND_STATION_QUALIFIERS: [&str; 4] = ["tisb_icao", "tisb_trackfile", "fixed_beacon", "adsr_other"];
+/// Address qualifier numbers, from the raw downlink header, matching `AIRCRAFT_QUALIFIERS` and
+/// `GROUND_STATION_QUALIFIERS`.
+const AIRCRAFT_QUALIFIER_NUMBERS: [u8; 2] = [0, 1];
+const GROUND_STATION_QUALIFIER_NUMBERS: [u8; 4] = [2, 3, 5, 6];
+/// Degrees per unit of the 24 bit angles in UAT headers and state vectors.
+const DEGREES_PER_UNIT: f64 = 360.0 / 16_777_216.0;
+
+/// A sanitised dump978-fa capture returned by `UatMessage::example()`.
+const EXAMPLE: &str = r#"{"address":"a1b2c3","address_qualifier":"adsb_icao","airground_state":"airborne","callsign":"AB0123  ","capability_codes":{"es_in":true,"tcas_operational":true,"uat_in":true},"east_velocity":-412,"emergency":"none","emitter_category":"A3","flightplan_id":"2317","geometric_altitude":35650,"ground_speed":413,"gva":2,"metadata":{"errors":0,"received_at":1700000000.125,"rssi":-14.2},"mops_version":2,"nac_p":9,"nac_v":1,"nic":8,"nic_baro":1,"nic_supplement":false,"north_velocity":-39,"operational_modes":{"atc_services":false,"ident_active":false,"tcas_ra_active":false},"position":{"lat":47.446548,"lon":-122.301712},"pressure_altitude":35000,"sda":2,"sil":3,"sil_supplement":"per_hour","single_antenna":false,"station":"EXAMPLE-STATION","transmit_mso":37,"true_track":264.6,"utc_coupled":true,"vertical_velocity_barometric":-64}"#;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be in JSON format, or a raw dump978-fa frame such as `-<hex>;rs=1;rssi=-12.3;`, and have
+/// support for providing a `str`, and will not consume the source.
+///
+/// This is intended for specifically decoding to `UatMessage`.
+pub trait NewUatMessage {
+    fn to_uat(&self) -> MessageResult<UatMessage>;
+}
+
+/// Implementing `.to_uat()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewUatMessage for String {
+    fn to_uat(&self) -> MessageResult<UatMessage> {
+        self.as_str().to_uat()
+    }
+}
+
+/// Supporting `.to_uat()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewUatMessage for str {
+    fn to_uat(&self) -> MessageResult<UatMessage> {
+        if is_uat_raw(self.as_bytes()) {
+            return decode_span(Some(MessageFamily::Uat), self.len(), || parse_uat_raw(self).map(UatMessage::Frame));
+        }
+        decode_span(Some(MessageFamily::Uat), self.len(), || serde_json::from_str(self))
+    }
+}
+
+/// Supporting `.to_uat()` for JSON or a raw frame held as bytes, such as a socket buffer.
+///
+/// JSON is decoded straight from the bytes without converting them to a `str` first, and the bytes are not consumed.
+impl NewUatMessage for [u8] {
+    fn to_uat(&self) -> MessageResult<UatMessage> {
+        if is_uat_raw(self) {
+            let line: &str = std::str::from_utf8(self).map_err(serde_json::Error::custom)?;
+            return line.to_uat();
+        }
+        decode_span(Some(MessageFamily::Uat), self.len(), || serde_json::from_slice(self))
+    }
+}
+
+/// Supporting `.to_uat()` for JSON already parsed into a `Value`, such as by an earlier pipeline stage.
+///
+/// This decodes from the `Value` without writing it back out as JSON first, and does not consume it.
+impl NewUatMessage for Value {
+    fn to_uat(&self) -> MessageResult<UatMessage> {
+        decode_span(Some(MessageFamily::Uat), 0, || UatMessage::deserialize(self))
+    }
+}
+
+/// Decodes an owned `Value`, see `UatMessage::from_value`.
+impl TryFrom<Value> for UatMessage {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> MessageResult<Self> {
+        Self::from_value(value)
+    }
+}
+
+/// Parses a raw frame written by dump978-fa with `--raw-port`, such as `-<hex>;rs=1;rssi=-12.3;t=1700000000.123;`.
+///
+/// The payload must be the length of a basic or long ADS-B downlink, or of a ground uplink. The `rs`, `rssi` and `t`
+/// fields are optional, and any other `key=value` fields are kept in `UatFrame::metadata`.
+/// ```
+/// use acars_vdlm2_parser::uat::{parse_uat_raw, UatDirection, UatFrameMessage};
+/// let line: &str = "-00a1b2c30000000000000000000000000000;rs=2;rssi=-17.5;t=1700000000.123;";
+/// let frame: UatFrameMessage = parse_uat_raw(line).unwrap();
+/// assert_eq!(frame.uat_frame.direction, UatDirection::Downlink);
+/// assert_eq!((frame.uat_frame.rs, frame.uat_frame.rssi), (Some(2), Some(-17.5)));
+/// assert_eq!(frame.uat_frame.get_icao(), Some(0xA1B2C3));
+/// assert_eq!(frame.uat_frame.to_raw_line(), line);
+/// assert!(parse_uat_raw("-0b28c8;").is_err());
+/// ```
+pub fn parse_uat_raw(line: &str) -> MessageResult<UatFrameMessage> {
+    let trimmed: &str = line.trim();
+    let (direction, rest): (UatDirection, &str) = match trimmed.split_at_checked(1) {
+        Some(("-", rest)) => (UatDirection::Downlink, rest),
+        Some(("+", rest)) => (UatDirection::Uplink, rest),
+        _ => return Err(serde_json::Error::custom(format!("a raw UAT frame starts with - or +: {}", trimmed))),
+    };
+    let mut fields = rest.split(';');
+    let payload: &str = fields.next().unwrap_or_default();
+    let bytes: Vec<u8> = decode_hex(payload)
+        .ok_or_else(|| serde_json::Error::custom(format!("invalid UAT payload hex: {}", payload)))?;
+    let expected: &[usize] = match direction {
+        UatDirection::Downlink => &[DOWNLINK_BASIC_BYTES, DOWNLINK_LONG_BYTES],
+        UatDirection::Uplink => &[UPLINK_BYTES],
+    };
+    if !expected.contains(&bytes.len()) {
+        return Err(serde_json::Error::custom(format!("invalid {} payload length {} bytes, expected {:?}", direction, bytes.len(), expected)));
+    }
+    let mut frame: UatFrame = UatFrame { direction, payload: payload.to_string(), ..Default::default() };
+    for field in fields.filter(|field| !field.is_empty()) {
+        let (key, value): (&str, &str) = field.split_once('=')
+            .ok_or_else(|| serde_json::Error::custom(format!("invalid UAT frame field {}", field)))?;
+        let invalid = |error: &dyn fmt::Display| serde_json::Error::custom(format!("invalid UAT frame field {}: {}", field, error));
+        match key {
+            "rs" => frame.rs = Some(value.parse().map_err(|error| invalid(&error))?),
+            "rssi" => frame.rssi = Some(value.parse().map_err(|error| invalid(&error))?),
+            "t" => frame.t = Some(value.parse().map_err(|error| invalid(&error))?),
+            _ => {
+                frame.metadata.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    Ok(UatFrameMessage { uat_frame: frame })
+}
+
+/// Decodes a string of hex pairs, returning `None` if it has an odd length or a character that is not hex.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len()).step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+impl UatMessage {
+
+    /// Returns a realistic, fully populated message based on a sanitised dump978-fa capture, for doctests and unit
+    /// tests that need more than the empty shell `Default` gives.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use acars_vdlm2_parser::uat::UatMessage;
+    /// let example: UatMessage = UatMessage::example();
+    /// assert_eq!(example.get_flight(), Some("AB0123"));
+    /// assert_eq!(example.get_icao(), Some(0xA1B2C3));
+    /// let decoded: AcarsVdlm2Message = example.to_json().unwrap().decode_message().unwrap();
+    /// assert_eq!(decoded.to_json().unwrap(), example.to_json().unwrap());
+    /// ```
+    pub fn example() -> Self {
+        // The doctest above checks the example decodes, so the fallback is never taken.
+        serde_json::from_str(EXAMPLE).unwrap_or_default()
+    }
+
+    /// Decodes a `UatMessage` from an owned `Value`, moving its strings into the message rather than copying them.
+    pub fn from_value(value: Value) -> MessageResult<Self> {
+        decode_span(Some(MessageFamily::Uat), 0, || serde_json::from_value(value))
+    }
+
+    /// Converts `UatMessage` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Converts `UatMessage` to a JSON `String`.
+    ///
+    /// This shadows `ToString::to_string`, which returns the single line summary of the `Display` implementation, so
+    /// `message.to_string()` and `format!("{}", message)` differ. Use `to_json()` for the JSON and `summary_line()` for
+    /// the summary.
+    #[deprecated(since = "0.3.0", note = "use `to_json()` for the JSON, `to_string()` will return the `Display` summary")]
+    pub fn to_string(&self) -> MessageResult<String> {
+        self.to_json()
+    }
+
+    /// Returns the single line summary written by the `Display` implementation, for logging, see `to_json` for the
+    /// JSON.
+    pub fn summary_line(&self) -> String {
+        ToString::to_string(self)
+    }
+
+    /// Converts `UatMessage` to `String` and appends a `\n` to the end.
+    pub fn to_string_newline(&self) -> MessageResult<String> {
+        let data = serde_json::to_string(self);
+        match data {
+            Err(to_string_error) => Err(to_string_error),
+            Ok(string) => Ok(format!("{}\n", string))
+        }
+    }
+
+    /// Converts `UatMessage` to JSON encoded as bytes.
+    ///
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
+    pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
+        serde_json::to_vec(self)
+    }
+
+    /// Converts `UatMessage` to JSON encoded as bytes and terminated with a `\n`.
+    ///
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
+    pub fn to_bytes_newline(&self) -> MessageResult<Vec<u8>> {
+        let mut bytes: Vec<u8> = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    /// Serialises `UatMessage` as JSON straight into the provided writer.
+    ///
+    /// This avoids allocating an intermediate buffer for the output.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Serialises `UatMessage` as JSON straight into the provided writer and terminates it with a `\n`.
+    pub fn write_to_newline<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(&mut *writer, self)?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)
+    }
+
+    fn station_mut(&mut self) -> &mut Option<String> {
+        match self {
+            UatMessage::Adsb(adsb) => &mut adsb.station,
+            UatMessage::Frame(frame) => &mut frame.uat_frame.station,
+        }
+    }
+
+    fn app_mut(&mut self) -> &mut Option<AppDetails> {
+        match self {
+            UatMessage::Adsb(adsb) => &mut adsb.app,
+            UatMessage::Frame(frame) => &mut frame.uat_frame.app,
+        }
+    }
+
+    /// Clears a station name that may be set for `UatMessage`.
+    pub fn clear_station_name(&mut self) {
+        *self.station_mut() = None;
+    }
+
+    /// Sets a station name to the provided value for `UatMessage`.
+    pub fn set_station_name(&mut self, station_name: &str) {
+        *self.station_mut() = Some(station_name.to_string());
+    }
+
+    /// Clears any proxy details that may be set for `UatMessage`.
+    pub fn clear_proxy_details(&mut self) {
+        if let Some(app_details) = self.app_mut().as_mut() {
+            app_details.remove_proxy();
+        }
+    }
+
+    /// Sets proxy details to the provided details and sets `proxied` to true.
+    ///
+    /// This invokes `AppDetails::new()` for `UatMessage` if there is no app block, as dump978-fa does not write one.
+    /// This invokes `AppDetails::proxy()` for `UatMessage` if there is an app block to add proxy details.
+    pub fn set_proxy_details(&mut self, proxied_by: &str, acars_router_version: &str) {
+        let app: &mut Option<AppDetails> = self.app_mut();
+        match app.as_mut() {
+            None => *app = Some(AppDetails::new(proxied_by, acars_router_version)),
+            Some(app_details) => app_details.proxy(proxied_by, acars_router_version)
+        }
+    }
+
+    /// Returns the app block for `UatMessage` if there is one.
+    pub fn get_app_details(&self) -> Option<&AppDetails> {
+        match self {
+            UatMessage::Adsb(adsb) => adsb.app.as_ref(),
+            UatMessage::Frame(frame) => frame.uat_frame.app.as_ref(),
+        }
+    }
+
+    /// Applies the provided `AppDetailsTemplate`, see `AppDetailsTemplate` for how an existing app block is merged.
+    pub fn apply_app_template(&mut self, template: &AppDetailsTemplate) {
+        let app: &mut Option<AppDetails> = self.app_mut();
+        match app.as_mut() {
+            None => *app = Some(template.details().clone()),
+            Some(app_details) => template.merge_into(app_details)
+        }
+    }
+
+    pub fn clear_time(&mut self) {
+        match self {
+            UatMessage::Adsb(adsb) => {
+                if let Some(metadata) = adsb.metadata.as_mut() {
+                    metadata.received_at = None;
+                }
+            }
+            UatMessage::Frame(frame) => frame.uat_frame.t = None,
+        }
+    }
+
+    pub fn get_time(&self) -> Option<f64> {
+        self.get_timestamp().map(|timestamp| timestamp.seconds)
+    }
+
+    /// Returns the time the station heard the frame, which dump978-fa reports to the millisecond.
+    pub fn get_timestamp(&self) -> Option<MessageTimestamp> {
+        let seconds: Option<f64> = match self {
+            UatMessage::Adsb(adsb) => adsb.metadata.as_ref().and_then(|metadata| metadata.received_at),
+            UatMessage::Frame(frame) => frame.uat_frame.t,
+        };
+        seconds.map(|seconds| MessageTimestamp::new(seconds, TimestampSource::Station, TimestampPrecision::Milliseconds))
+    }
+
+    /// Replaces the station name with a stable pseudonym from the provided `StationRedactor`.
+    pub fn redact_ground_stations(&mut self, redactor: &StationRedactor) {
+        if let Some(station) = self.station_mut().as_mut() {
+            *station = redactor.pseudonym(station);
+        }
+    }
+
+    /// Replaces the aircraft address with a stable pseudonym from the provided `AircraftAnonymiser`.
+    ///
+    /// The address is replaced whatever its qualifier, and for raw downlinks it is rewritten inside the payload, so
+    /// the frame no longer matches its original error correction. Uplinks carry no aircraft address.
+    pub fn anonymise_aircraft(&mut self, anonymiser: &AircraftAnonymiser) {
+        match self {
+            UatMessage::Adsb(adsb) => {
+                adsb.address = anonymiser.pseudonym_icao_hex(&adsb.address).to_lowercase();
+                #[cfg(feature = "enrichment")]
+                if let Some(registration) = adsb.aircraft_info.as_mut().and_then(|info| info.registration.as_mut()) {
+                    *registration = anonymiser.pseudonym_registration(registration);
+                }
+            }
+            UatMessage::Frame(frame) => {
+                let frame: &mut UatFrame = &mut frame.uat_frame;
+                if let Some(address) = frame.get_address() {
+                    let pseudonym: String = format!("{:06x}", anonymiser.pseudonym_icao(address));
+                    frame.payload.replace_range(2..8, &pseudonym);
+                }
+                #[cfg(feature = "enrichment")]
+                if let Some(registration) = frame.aircraft_info.as_mut().and_then(|info| info.registration.as_mut()) {
+                    *registration = anonymiser.pseudonym_registration(registration);
+                }
+            }
+        }
+    }
+
+    /// Returns true if the messages are the same apart from the fields in the given classes, see `FieldClass`.
+    pub fn eq_ignoring(&self, other: &UatMessage, ignored: &[FieldClass]) -> bool {
+        eq_ignoring(self, other, MessageFamily::Uat, ignored)
+    }
+
+    /// Returns the `FrameQuality` for `UatMessage`.
+    ///
+    /// dump978-fa drops frames that fail Reed-Solomon decoding, so frames are either `Ok` or `Corrected` when any
+    /// errors were corrected.
+    pub fn get_frame_quality(&self) -> FrameQuality {
+        let corrected: Option<u8> = match self {
+            UatMessage::Adsb(adsb) => adsb.metadata.as_ref().and_then(|metadata| metadata.errors),
+            UatMessage::Frame(frame) => frame.uat_frame.rs,
+        };
+        match corrected.unwrap_or_default() > 0 {
+            true => FrameQuality::Corrected,
+            false => FrameQuality::Ok,
+        }
+    }
+
+    /// Returns the `SourceKind` for `UatMessage`: uplinks come from ground stations, and downlinks from whatever their
+    /// address qualifier says. Surface vehicles are `Unknown`.
+    pub fn source_kind(&self) -> SourceKind {
+        match self {
+            UatMessage::Adsb(adsb) => adsb.source_kind(),
+            UatMessage::Frame(frame) => frame.uat_frame.source_kind(),
+        }
+    }
+
+    /// Returns the ICAO address of the aircraft, when its address qualifier says the address is an ICAO address.
+    ///
+    /// Addresses of TIS-B track files, surface vehicles and other non ICAO addresses are not returned.
+    pub fn get_icao(&self) -> Option<u32> {
+        match self {
+            UatMessage::Adsb(adsb) => adsb.get_icao(),
+            UatMessage::Frame(frame) => frame.uat_frame.get_icao(),
+        }
+    }
+
+    /// Returns the frequency `UatMessage` was received on, which is always 978 MHz.
+    pub fn get_frequency(&self) -> Frequency {
+        Frequency::from_hz(UAT_FREQUENCY_HZ)
+    }
+
+    /// Returns the station name for `UatMessage` if one is set.
+    pub fn get_station_name(&self) -> Option<&str> {
+        match self {
+            UatMessage::Adsb(adsb) => adsb.station.as_deref(),
+            UatMessage::Frame(frame) => frame.uat_frame.station.as_deref(),
+        }
+    }
+
+    /// Returns the callsign for `UatMessage` with the padding removed, if the aircraft reported one.
+    pub fn get_flight(&self) -> Option<&str> {
+        match self {
+            UatMessage::Adsb(adsb) => adsb.callsign.as_deref().map(str::trim).filter(|callsign| !callsign.is_empty()),
+            UatMessage::Frame(_) => None,
+        }
+    }
+
+    /// Passes the parts of `UatMessage` to the visitor, see `visit::MessageVisitor`.
+    ///
+    /// Positions come from the aircraft state vector decoded by dump978-fa, and from the ground station position in
+    /// the header of raw uplinks.
+    pub fn visit(&self, visitor: &mut dyn MessageVisitor) {
+        let rssi: Option<f64> = match self {
+            UatMessage::Adsb(adsb) => adsb.metadata.as_ref().and_then(|metadata| metadata.rssi),
+            UatMessage::Frame(frame) => frame.uat_frame.rssi,
+        };
+        visitor.on_signal_info(&SignalInfo {
+            frequency: self.get_frequency(),
+            level_dbm: rssi,
+            noise_level_dbm: None,
+            freq_skew_hz: None,
+            quality: self.get_frame_quality(),
+        });
+        if let Some(app) = self.get_app_details() {
+            visitor.on_app_details(app);
+        }
+        match self {
+            UatMessage::Adsb(adsb) => {
+                if let Some(position) = &adsb.position {
+                    let altitude_ft: Option<i32> = adsb.pressure_altitude.or(adsb.geometric_altitude);
+                    visitor.on_position(&PositionReport { lat: position.lat, lon: position.lon, altitude_ft, source: PositionSource::UatAircraft });
+                }
+            }
+            UatMessage::Frame(frame) => {
+                if let Some((lat, lon)) = frame.uat_frame.get_ground_station_position() {
+                    visitor.on_position(&PositionReport { lat, lon, altitude_ft: None, source: PositionSource::GroundStation });
+                }
+            }
+        }
+    }
+
+    /// Returns the `aircraft_info` block for `UatMessage` if it has been enriched.
+    #[cfg(feature = "enrichment")]
+    pub fn get_aircraft_info(&self) -> Option<&AircraftInfo> {
+        match self {
+            UatMessage::Adsb(adsb) => adsb.aircraft_info.as_ref(),
+            UatMessage::Frame(frame) => frame.uat_frame.aircraft_info.as_ref(),
+        }
+    }
+
+    /// Sets the `aircraft_info` block for `UatMessage`.
+    #[cfg(feature = "enrichment")]
+    pub fn set_aircraft_info(&mut self, info: AircraftInfo) {
+        match self {
+            UatMessage::Adsb(adsb) => adsb.aircraft_info = Some(info),
+            UatMessage::Frame(frame) => frame.uat_frame.aircraft_info = Some(info),
+        }
+    }
+
+    pub fn clear_sig_level(&mut self) {
+        match self {
+            UatMessage::Adsb(adsb) => {
+                if let Some(metadata) = adsb.metadata.as_mut() {
+                    metadata.rssi = None;
+                }
+            }
+            UatMessage::Frame(frame) => frame.uat_frame.rssi = None,
+        }
+    }
+}
+
+/// Renders a concise single line summary of the message, intended for logging rather than forwarding.
+///
+/// `summary_line()` returns it as a `String`, and `to_json()` returns the JSON.
+impl fmt::Display for UatMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        SummaryLine {
+            family: "UAT",
+            time: self.get_time(),
+            station: self.get_station_name(),
+            freq_mhz: self.get_frequency().as_mhz(),
+            registration: None,
+            flight: self.get_flight(),
+            label: None,
+            text: None,
+        }.fmt(f)
+    }
+}
+
+/// A message from dump978-fa, either an ADS-B message it decoded or a raw frame from its raw output.
+///
+/// Raw frames are read from lines such as `-<hex>;rs=1;rssi=-12.3;t=1700000000.123;` with `parse_uat_raw`, and are
+/// written as JSON nested under a `uat_frame` key so they can be told apart from decoded messages.
+/// ```
+/// use acars_vdlm2_parser::uat::{NewUatMessage, UatMessage};
+/// let decoded: UatMessage = r#"{"address":"a1b2c3","address_qualifier":"adsb_icao","callsign":"AB0123  "}"#.to_uat().unwrap();
+/// assert!(matches!(decoded, UatMessage::Adsb(_)));
+/// let raw: UatMessage = "-00a1b2c30000000000000000000000000000;rs=1;".to_uat().unwrap();
+/// assert!(matches!(raw, UatMessage::Frame(_)));
+/// assert!(raw.to_json().unwrap().starts_with(r#"{"uat_frame":{"direction":"downlink""#));
+/// ```
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum UatMessage {
+    Adsb(UatAdsbMessage),
+    Frame(UatFrameMessage),
+}
+
+impl Default for UatMessage {
+    fn default() -> Self {
+        Self::Adsb(Default::default())
+    }
+}
+
+/// Decodes a raw frame when the `uat_frame` key is present and a decoded ADS-B message otherwise, propagating that
+/// type's error rather than the "data did not match any variant" error an untagged enum would produce.
+impl<'de> Deserialize<'de> for UatMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value: Value = Value::deserialize(deserializer)?;
+        let decoded: MessageResult<UatMessage> = match value.get(UAT_FRAME_KEY).is_some() {
+            true => UatFrameMessage::deserialize(value).map(UatMessage::Frame),
+            false => UatAdsbMessage::deserialize(value).map(UatMessage::Adsb),
+        };
+        decoded.map_err(D::Error::custom)
+    }
+}
+
+/// An ADS-B message decoded by dump978-fa from a UAT downlink, as written to its JSON output.
+///
+/// Every field but the address is optional, as dump978-fa only writes the fields present in the frame it decoded, and
+/// fields this library does not model are dropped rather than rejected. Top level numbers may be quoted.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatAdsbMessage {
+    /// The 24 bit address in hex, which is an ICAO address only for some qualifiers, see `address_qualifier`.
+    pub address: String,
+    /// What the address is, such as `adsb_icao`, `adsb_other`, `tisb_icao`, `tisb_trackfile` or `vehicle`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_qualifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<UatMetadata>,
+    /// `airborne`, `ground`, `supersonic` or `reserved`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airground_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<UatPosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub nic: Option<u8>,
+    /// Barometric altitude in feet.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub pressure_altitude: Option<i32>,
+    /// GNSS altitude in feet.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub geometric_altitude: Option<i32>,
+    /// Velocity north in knots, negative when heading south.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub north_velocity: Option<i32>,
+    /// Velocity east in knots, negative when heading west.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub east_velocity: Option<i32>,
+    /// Vertical rate from the barometric altitude in feet per minute.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub vertical_velocity_barometric: Option<i32>,
+    /// Vertical rate from the GNSS altitude in feet per minute.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub vertical_velocity_geometric: Option<i32>,
+    /// Ground speed in knots.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub ground_speed: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub true_track: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub magnetic_heading: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub true_heading: Option<f64>,
+    /// Length and width of the aircraft in metres, reported on the ground.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aircraft_size: Option<(f64, f64)>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub gps_lateral_offset: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub gps_longitudinal_offset: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gps_position_offset_applied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utc_coupled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub uplink_feedback: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub tisb_site_id: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emitter_category: Option<String>,
+    /// Callsign padded with spaces to 8 characters, see `UatMessage::get_flight` for it trimmed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callsign: Option<String>,
+    /// The squawk code, or the flight plan ID when the aircraft sends one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flightplan_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub mops_version: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub sil: Option<u8>,
+    /// The message start opportunity, the slot the aircraft transmitted in, from 0 to 3951.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub transmit_mso: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub sda: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub nac_p: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub nac_v: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub nic_baro: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capability_codes: Option<UatCapabilityCodes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operational_modes: Option<UatOperationalModes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sil_supplement: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub gva: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub single_antenna: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic_supplement: Option<bool>,
+    /// `mcp_fcu` or `fms`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_altitude_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub selected_altitude: Option<i32>,
+    /// QNH in millibars.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub barometric_pressure_setting: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
+    pub selected_heading: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode_indicators: Option<UatModeIndicators>,
+    /// Station name set by the feeder, also read from `station_id` as acarsdec names it.
+    #[serde(alias = "station_id", skip_serializing_if = "Option::is_none")]
+    pub station: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<AppDetails>,
+    #[cfg(feature = "enrichment")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aircraft_info: Option<AircraftInfo>,
+}
+
+impl UatAdsbMessage {
+    /// Returns the address if the qualifier says it is an ICAO address.
+    pub fn get_icao(&self) -> Option<u32> {
+        match ICAO_QUALIFIERS.contains(&self.address_qualifier.as_deref()?) {
+            true => parse_icao_hex(&self.address),
+            false => None,
+        }
+    }
+
+    /// Returns the `SourceKind` from the address qualifier.
+    pub fn source_kind(&self) -> SourceKind {
+        match self.address_qualifier.as_deref() {
+            Some(qualifier) if AIRCRAFT_QUALIFIERS.contains(&qualifier) => SourceKind::Aircraft,
+            Some(qualifier) if GROUND_STATION_QUALIFIERS.contains(&qualifier) => SourceKind::GroundStation,
+            _ => SourceKind::Unknown,
+        }
+    }
+}
+
+/// Reception details dump978-fa adds to each message.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatMetadata {
+    /// Time the frame was received, in seconds since the Unix epoch to the millisecond.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rssi: Option<f64>,
+    /// Number of errors corrected by Reed-Solomon decoding.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct UatPosition {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct UatCapabilityCodes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uat_in: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub es_in: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcas_operational: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct UatOperationalModes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcas_ra_active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ident_active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub atc_services: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct UatModeIndicators {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autopilot: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vnav: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude_hold: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approach: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lnav: Option<bool>,
+}
+
+/// A raw frame from dump978-fa, written as JSON nested under `uat_frame`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UatFrameMessage {
+    pub uat_frame: UatFrame,
+}
+
+/// Whether a raw frame was sent by an aircraft or a ground station.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum UatDirection {
+    /// An ADS-B frame sent by an aircraft or vehicle, written with a leading `-`.
+    #[default]
+    Downlink,
+    /// A FIS-B or TIS-B frame sent by a ground station, written with a leading `+`.
+    Uplink,
+}
+
+impl fmt::Display for UatDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UatDirection::Downlink => write!(f, "downlink"),
+            UatDirection::Uplink => write!(f, "uplink"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UatFrame {
+    pub direction: UatDirection,
+    /// The payload in hex, after error correction.
+    pub payload: String,
+    /// Number of errors corrected by Reed-Solomon decoding.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rs: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rssi: Option<f64>,
+    /// Time the frame was received, in seconds since the Unix epoch to the millisecond.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t: Option<f64>,
+    /// Any other `key=value` fields of the raw line.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Station name set by the feeder, also read from `station_id` as acarsdec names it.
+    #[serde(alias = "station_id", skip_serializing_if = "Option::is_none")]
+    pub station: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<AppDetails>,
+    #[cfg(feature = "enrichment")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aircraft_info: Option<AircraftInfo>,
+}
+
+impl UatFrame {
+    /// Returns the payload as bytes, or `None` if it is not valid hex.
+    pub fn payload_bytes(&self) -> Option<Vec<u8>> {
+        decode_hex(&self.payload)
+    }
+
+    /// Returns the first bytes of the payload, or `None` if it is not valid hex or is shorter than `length`.
+    fn header(&self, length: usize) -> Option<Vec<u8>> {
+        self.payload.get(..length * 2).and_then(decode_hex)
+    }
+
+    /// Returns the payload type of a downlink, from 0 for a basic frame to 10 for the long frame types.
+    pub fn get_payload_type(&self) -> Option<u8> {
+        match self.direction {
+            UatDirection::Downlink => self.header(1).map(|header| header[0] >> 3),
+            UatDirection::Uplink => None,
+        }
+    }
+
+    /// Returns the address qualifier number of a downlink, where 0 is an ADS-B ICAO address and 2 a TIS-B ICAO
+    /// address.
+    pub fn get_address_qualifier(&self) -> Option<u8> {
+        match self.direction {
+            UatDirection::Downlink => self.header(1).map(|header| header[0] & 0x07),
+            UatDirection::Uplink => None,
+        }
+    }
+
+    /// Returns the 24 bit address of a downlink, whatever its qualifier.
+    pub fn get_address(&self) -> Option<u32> {
+        match self.direction {
+            UatDirection::Downlink => self.header(4).map(|header| u32::from_be_bytes([0, header[1], header[2], header[3]])),
+            UatDirection::Uplink => None,
+        }
+    }
+
+    /// Returns the address of a downlink if its qualifier says it is an ICAO address.
+    pub fn get_icao(&self) -> Option<u32> {
+        match ICAO_QUALIFIER_NUMBERS.contains(&self.get_address_qualifier()?) {
+            true => self.get_address(),
+            false => None,
+        }
+    }
+
+    /// Returns `GroundStation` for uplinks, and the `SourceKind` from the address qualifier for downlinks.
+    pub fn source_kind(&self) -> SourceKind {
+        match (self.direction, self.get_address_qualifier()) {
+            (UatDirection::Uplink, _) => SourceKind::GroundStation,
+            (UatDirection::Downlink, Some(qualifier)) if AIRCRAFT_QUALIFIER_NUMBERS.contains(&qualifier) => SourceKind::Aircraft,
+            (UatDirection::Downlink, Some(qualifier)) if GROUND_STATION_QUALIFIER_NUMBERS.contains(&qualifier) => SourceKind::GroundStation,
+            (UatDirection::Downlink, _) => SourceKind::Unknown,
+        }
+    }
+
+    /// Returns the position of the ground station that sent an uplink, from its header, if the station marked it valid.
+    pub fn get_ground_station_position(&self) -> Option<(f64, f64)> {
+        if self.direction != UatDirection::Uplink {
+            return None;
+        }
+        let header: Vec<u8> = self.header(6)?;
+        if header[5] & 0x01 == 0 {
+            return None;
+        }
+        let raw_lat: u32 = (header[0] as u32) << 15 | (header[1] as u32) << 7 | (header[2] as u32) >> 1;
+        let raw_lon: u32 = (header[2] as u32 & 0x01) << 23 | (header[3] as u32) << 15 | (header[4] as u32) << 7 | (header[5] as u32) >> 1;
+        let mut lat: f64 = raw_lat as f64 * DEGREES_PER_UNIT;
+        let mut lon: f64 = raw_lon as f64 * DEGREES_PER_UNIT;
+        if lat > 90.0 {
+            lat -= 180.0;
+        }
+        if lon > 180.0 {
+            lon -= 360.0;
+        }
+        Some((lat, lon))
+    }
+
+    /// Writes the frame back out in the dump978-fa raw format, see `parse_uat_raw`.
+    pub fn to_raw_line(&self) -> String {
+        let mut line: String = format!("{}{};", match self.direction {
+            UatDirection::Downlink => '-',
+            UatDirection::Uplink => '+',
+        }, self.payload);
+        if let Some(rs) = self.rs {
+            line.push_str(&format!("rs={};", rs));
+        }
+        if let Some(rssi) = self.rssi {
+            line.push_str(&format!("rssi={:.1};", rssi));
+        }
+        if let Some(t) = self.t {
+            line.push_str(&format!("t={:.3};", t));
+        }
+        for (key, value) in &self.metadata {
+            line.push_str(&format!("{}={};", key, value));
+        }
+        line
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use crate::AppDetails;
+use crate::acars::{AckType, AcarsMessage};
+use crate::frequency::Frequency;
+use crate::vdlm2::{AvlcAcars, AvlcData, AvlcFrame, AvlcInformationFrame, AvlcUnnumberedFrame, CoOrdinates, DstBlock,
+                   ParamValueType, SrcBlock, TBlock, Vdlm2Body, Vdlm2Message, XidBlock, XidParam};
+
+/// Common VDL2 frequency ground stations broadcast on, 136.975 MHz.
+pub const DEFAULT_VDL2_FREQUENCY_HZ: u64 = 136_975_000;
+/// Primary ACARS frequency in North America, in MHz as acarsdec writes it, used for uplink blocks.
+pub const DEFAULT_ACARS_FREQUENCY_MHZ: f64 = 131.55;
+/// Name written in the `app` block of simulated frames, so they cannot be mistaken for received traffic.
+pub const SIMULATOR_APP_NAME: &str = "acars_vdlm2_parser_simulator";
+
+const GROUND_STATION: &str = "Ground station";
+const AIRCRAFT: &str = "Aircraft";
+const ON_GROUND: &str = "On ground";
+const BROADCAST_ADDRESS: &str = "FFFFFF";
+/// A ground station only sends an ACARS mode `2` to VDL2 aircraft.
+const ACARS_MODE: &str = "2";
+/// ACARS ack character meaning no downlink block is being acknowledged.
+const NO_ACK: &str = "!";
+/// Uplink block identifiers cycle through the letters, where downlinks use digits.
+const UPLINK_BLOCK_IDS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// ACARS registrations are 7 characters, padded on the left with dots.
+const REGISTRATION_LEN: usize = 7;
+
+/// Synthesises the frames a VDL2 ground station sends, Ground Station Information Frames (GSIF) and ACARS uplinks, as
+/// dumpvdl2 would output them, plus ACARS uplink blocks as acarsdec would, for lab testing of airborne side decoders
+/// and of anything reading this crate's output.
+///
+/// Frames carry the defaults a real ground station uses: GSIFs are broadcast to `FFFFFF` with the parameter set,
+/// system mask and HDLC options dumpvdl2 shows for ARINC stations, uplinks are Command information frames with the
+/// send sequence number counted per aircraft modulo 8, uplink block identifiers cycle through `A` to `Z`, and
+/// registrations are padded with dots to 7 characters. Every frame decodes with this crate and writes back unchanged.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::uplink::GroundStationSimulator;
+/// use acars_vdlm2_parser::vdlm2::{GroundStationInfo, Vdlm2Message};
+/// let mut simulator: GroundStationSimulator = GroundStationSimulator::new(0x10210A)
+///     .airport_coverage(&["KABQ"])
+///     .location(35.04, -106.61);
+/// let gsif: Vdlm2Message = simulator.gsif(1664041504.25);
+/// let info: GroundStationInfo = gsif.get_ground_station_info().unwrap();
+/// assert_eq!(info.airport_coverage, vec!["KABQ".to_string()]);
+/// let uplink: Vdlm2Message = simulator.acars_uplink(0xA48321, "N390DN", "H1", "DEP KABQ");
+/// assert_eq!(uplink.get_acars().map(|acars| acars.reg.as_str()), Some(".N390DN"));
+/// let decoded: AcarsVdlm2Message = uplink.to_json().unwrap().decode_message().unwrap();
+/// assert_eq!(decoded.get_text(), Some("DEP KABQ"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GroundStationSimulator {
+    address: u32,
+    frequency: Frequency,
+    airport_coverage: Vec<String>,
+    location: Option<CoOrdinates>,
+    station: Option<String>,
+    time: Option<f64>,
+    next_block: usize,
+    send_sequences: HashMap<u32, u16>,
+}
+
+impl GroundStationSimulator {
+    /// Creates a simulator for the ground station with the AVLC address, on `DEFAULT_VDL2_FREQUENCY_HZ`.
+    pub fn new(address: u32) -> Self {
+        Self {
+            address: address & 0xFF_FFFF,
+            frequency: Frequency::from_hz(DEFAULT_VDL2_FREQUENCY_HZ),
+            airport_coverage: Vec::new(),
+            location: None,
+            station: None,
+            time: None,
+            next_block: 0,
+            send_sequences: HashMap::new(),
+        }
+    }
+
+    /// Sets the frequency frames are sent on.
+    pub fn frequency(mut self, frequency: Frequency) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Sets the ICAO codes of the airports listed in the GSIF.
+    pub fn airport_coverage(mut self, airports: &[&str]) -> Self {
+        self.airport_coverage = airports.iter().map(|airport| airport.to_string()).collect();
+        self
+    }
+
+    /// Sets the location listed in the GSIF, in decimal degrees.
+    pub fn location(mut self, lat: f64, lon: f64) -> Self {
+        self.location = Some(CoOrdinates::new(lat, lon));
+        self
+    }
+
+    /// Sets the feeder station name written on every frame.
+    pub fn station(mut self, station: &str) -> Self {
+        self.station = Some(station.to_string());
+        self
+    }
+
+    /// Builds a Ground Station Information Frame broadcast at the time, in seconds since the Unix epoch.
+    ///
+    /// Uplinks built afterwards are timed from this, so a sequence of frames can be built from a single start time.
+    pub fn gsif(&mut self, time: f64) -> Vdlm2Message {
+        self.time = Some(time);
+        let mut vdl_params: Vec<XidParam> = vec![
+            param("param_set_id", ParamValueType::String("V".to_string())),
+            param("avlc_specific_options", ParamValueType::VecInteger(vec![32])),
+        ];
+        if !self.airport_coverage.is_empty() {
+            vdl_params.push(param("airport_coverage", ParamValueType::String(self.airport_coverage.join(" "))));
+        }
+        vdl_params.push(param("atn_router_nets", ParamValueType::VecInteger(vec![0; 6])));
+        vdl_params.push(param("system_mask", ParamValueType::VecString(vec!["200000".to_string()])));
+        if let Some(location) = &self.location {
+            vdl_params.push(param("gs_location", ParamValueType::CoOrdinates(location.clone())));
+        }
+        let xid: XidBlock = XidBlock {
+            err: false,
+            pub_params: Some(vec![
+                param("param_set_id", ParamValueType::String("8885:1993".to_string())),
+                param("procedure_classes", ParamValueType::VecInteger(vec![0, 1])),
+                param("hdlc_options", ParamValueType::VecInteger(vec![32, 164, 136])),
+            ]),
+            xid_type: "GSIF".to_string(),
+            xid_type_descr: "Ground Station Information Frame".to_string(),
+            vdl_params,
+        };
+        let frame: AvlcFrame = AvlcFrame::Unnumbered(AvlcUnnumberedFrame { cmd: Some("XID".to_string()), pf: Some(false), xid: Some(xid) });
+        self.frame(BROADCAST_ADDRESS.to_string(), frame, 72, time)
+    }
+
+    /// Builds an ACARS uplink to the aircraft, one second after the previous frame.
+    pub fn acars_uplink(&mut self, icao: u32, registration: &str, label: &str, text: &str) -> Vdlm2Message {
+        let icao: u32 = icao & 0xFF_FFFF;
+        let send_sequence: &mut u16 = self.send_sequences.entry(icao).or_default();
+        let sseq: u16 = *send_sequence;
+        *send_sequence = (sseq + 1) % 8;
+        let acars: AvlcAcars = AvlcAcars {
+            err: false,
+            crc_ok: true,
+            more: false,
+            reg: pad_registration(registration),
+            mode: ACARS_MODE.to_string(),
+            label: label.to_string(),
+            blk_id: self.next_block_id(),
+            ack: NO_ACK.to_string(),
+            msg_text: text.to_string(),
+            ..Default::default()
+        };
+        let frame: AvlcFrame = AvlcFrame::Information(AvlcInformationFrame { rseq: Some(0), sseq: Some(sseq), poll: Some(false), acars: vec![acars] });
+        // Header, ACARS preamble and CRC come to 22 octets around the text.
+        let burst_len_octets: u16 = u16::try_from(text.len() + 22).unwrap_or(u16::MAX);
+        let time: f64 = self.next_time();
+        self.frame(format!("{:06X}", icao), frame, burst_len_octets, time)
+    }
+
+    /// Builds an ACARS uplink block as acarsdec outputs it, on `DEFAULT_ACARS_FREQUENCY_MHZ`, one second after the
+    /// previous frame.
+    pub fn acars_block(&mut self, registration: &str, label: &str, text: &str) -> AcarsMessage {
+        AcarsMessage {
+            freq: Frequency::from_mhz(DEFAULT_ACARS_FREQUENCY_MHZ),
+            channel: Some(0),
+            timestamp: Some(self.next_time()),
+            app: Some(simulator_app()),
+            station_id: self.station.clone(),
+            mode: Some(ACARS_MODE.to_string()),
+            label: Some(label.to_string()),
+            block_id: Some(self.next_block_id()),
+            ack: Some(AckType::String(NO_ACK.to_string())),
+            tail: Some(pad_registration(registration)),
+            text: Some(text.to_string()).filter(|text| !text.is_empty()),
+            ..Default::default()
+        }
+    }
+
+    fn frame(&self, destination: String, frame: AvlcFrame, burst_len_octets: u16, time: f64) -> Vdlm2Message {
+        Vdlm2Message {
+            vdl2: Vdlm2Body {
+                app: Some(simulator_app()),
+                avlc: AvlcData {
+                    cr: "Command".to_string(),
+                    dst: DstBlock { addr: destination, vehicle_type: AIRCRAFT.to_string() },
+                    src: SrcBlock { addr: format!("{:06X}", self.address), status: ON_GROUND.to_string(), source_type: GROUND_STATION.to_string() },
+                    frame,
+                },
+                burst_len_octets,
+                freq: self.frequency,
+                idx: 0,
+                station: self.station.clone(),
+                t: Some(TBlock { sec: time.trunc() as u64, usec: ((time.fract() * 1_000_000.0).round() as u64).min(999_999) }),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Returns the time of the next frame, one second after the previous one, or 0 if no GSIF has set a start time.
+    fn next_time(&mut self) -> f64 {
+        let time: f64 = self.time.map_or(0.0, |time| time + 1.0);
+        self.time = Some(time);
+        time
+    }
+
+    fn next_block_id(&mut self) -> String {
+        let block_id: char = UPLINK_BLOCK_IDS[self.next_block % UPLINK_BLOCK_IDS.len()] as char;
+        self.next_block += 1;
+        block_id.to_string()
+    }
+}
+
+fn param(name: &str, value: ParamValueType) -> XidParam {
+    XidParam { name: name.to_string(), value }
+}
+
+fn simulator_app() -> AppDetails {
+    AppDetails {
+        name: SIMULATOR_APP_NAME.to_string(),
+        ver: env!("CARGO_PKG_VERSION").to_string(),
+        proxied: None,
+        proxied_by: None,
+        acars_router_version: None,
+        acars_router_uuid: None,
+    }
+}
+
+/// Pads a registration on the left with dots to the 7 characters ACARS sends.
+fn pad_registration(registration: &str) -> String {
+    let registration: &str = registration.trim_start_matches('.');
+    format!("{}{}", ".".repeat(REGISTRATION_LEN.saturating_sub(registration.len())), registration)
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use serde::{Deserialize, Serialize};
+use crate::AcarsVdlm2Message;
+
+/// Why a validation hook rejected a message, counted by `ValidationHooks` and given as the error of its `FailedDecode`.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RejectReason {
+    pub reason: String,
+}
+
+impl RejectReason {
+    /// Creates a `RejectReason`, such as `ground test aircraft`.
+    pub fn new(reason: &str) -> Self {
+        Self { reason: reason.to_string() }
+    }
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
+/// A user-defined check run against every message once it has decoded.
+pub type Validator = Box<dyn Fn(&AcarsVdlm2Message) -> Result<(), RejectReason> + Send + Sync>;
+
+/// Counts of the messages checked and rejected by `ValidationHooks`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ValidationStats {
+    /// Messages run through the hooks.
+    pub checked: u64,
+    /// Messages rejected, keyed by the reason given.
+    pub rejected: BTreeMap<String, u64>,
+}
+
+/// Domain-specific checks run inside the decode pipeline, such as dropping ground test aircraft or particular labels,
+/// so rejected messages are counted by reason instead of being filtered out after the fact.
+///
+/// Hooks are run in the order they were added, stopping at the first one that rejects the message. The hooks can be
+/// shared between threads behind an `Arc`, as `DecodePoolConfig::validation` does. A decoder given hooks returns a
+/// `FailedDecode` with the code `message.rejected` for each rejected message.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::validate::{RejectReason, ValidationHooks};
+/// let mut hooks: ValidationHooks = ValidationHooks::new();
+/// hooks.add(Box::new(|message: &AcarsVdlm2Message| match message.get_label() {
+///     Some("SQ") => Err(RejectReason::new("squitter label")),
+///     _ => Ok(()),
+/// }));
+/// let squitter: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"SQ"}"#.decode_message().unwrap();
+/// let other: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"H1"}"#.decode_message().unwrap();
+/// assert_eq!(hooks.check(&squitter), Err(RejectReason::new("squitter label")));
+/// assert!(hooks.check(&other).is_ok());
+/// assert_eq!((hooks.stats().checked, hooks.stats().rejected["squitter label"]), (2, 1));
+/// ```
+#[derive(Default)]
+pub struct ValidationHooks {
+    hooks: Vec<Validator>,
+    stats: Mutex<ValidationStats>,
+}
+
+impl ValidationHooks {
+    /// Creates `ValidationHooks` with no hooks, which accepts every message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a hook, run after those already added.
+    pub fn add(&mut self, hook: Validator) {
+        self.hooks.push(hook);
+    }
+
+    /// Number of hooks added.
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Returns true if no hooks have been added.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Runs every hook against the message, returning the reason given by the first one that rejects it.
+    pub fn check(&self, message: &AcarsVdlm2Message) -> Result<(), RejectReason> {
+        let result: Result<(), RejectReason> = self.hooks.iter().try_for_each(|hook| hook(message));
+        let mut stats: MutexGuard<ValidationStats> = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+        stats.checked += 1;
+        if let Err(rejected) = &result {
+            debug!("Validation hook rejected the message: {}", rejected);
+            *stats.rejected.entry(rejected.reason.clone()).or_default() += 1;
+        }
+        result
+    }
+
+    /// Returns the counts of messages checked and rejected so far.
+    pub fn stats(&self) -> ValidationStats {
+        self.stats.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Clears the counts, keeping the hooks.
+    pub fn reset(&self) {
+        *self.stats.lock().unwrap_or_else(PoisonError::into_inner) = ValidationStats::default();
+    }
+}
+
+impl fmt::Debug for ValidationHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidationHooks").field("hooks", &self.hooks.len()).field("stats", &self.stats()).finish()
+    }
+}
@@ -1,7 +1,41 @@
-use std::num::ParseFloatError;
-use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::io::Write;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as DeError;
 use serde_json::Value;
-use crate::{AppDetails, MessageResult};
+use crate::{AppDetails, MessageFamily, MessageResult, SummaryLine};
+use crate::adsc::Adsc;
+use crate::app_template::AppDetailsTemplate;
+use crate::coerce::{number_or_string, one_or_many, option_number_or_string, via_value, whole_number};
+use crate::consistency::ConsistencyFlags;
+use crate::correlate::AckFields;
+use crate::equality::{eq_ignoring, FieldClass};
+#[cfg(feature = "enrichment")]
+use crate::enrichment::AircraftInfo;
+use crate::icao::parse_icao_hex;
+use crate::frequency::Frequency;
+use crate::instrument::{decode_span, fallback};
+use crate::normalise::TextNormaliser;
+use crate::quality::FrameQuality;
+use crate::source::SourceKind;
+use crate::redact::{AircraftAnonymiser, StationRedactor};
+use crate::timestamp::MessageTimestamp;
+use crate::visit::{AcarsBlock, MessageVisitor, PositionReport, PositionSource, SignalInfo};
+
+/// Address type used by dumpvdl2 for ground stations.
+const GROUND_STATION: &str = "Ground station";
+const AIRCRAFT: &str = "Aircraft";
+const GSIF: &str = "GSIF";
+/// XID types for handoff commands and responses start with these, including the broadcast and request variants.
+const HANDOFF_XID_PREFIXES: [&str; 2] = ["XID_CMD_HO", "XID_RSP_HO"];
+/// Address dumpvdl2 shows for frames broadcast to every aircraft.
+const BROADCAST_ADDRESS: &str = "FFFFFF";
+const INFORMATION_FRAME: &str = "I";
+const SUPERVISORY_FRAME: &str = "S";
+const UNNUMBERED_FRAME: &str = "U";
+
+/// A sanitised dumpvdl2 capture returned by `Vdlm2Message::example()`.
+const EXAMPLE: &str = r#"{"vdl2":{"app":{"name":"dumpvdl2","ver":"2.3.0"},"avlc":{"cr":"Command","dst":{"addr":"10214A","type":"Ground station"},"frame_type":"I","src":{"addr":"A1B2C3","status":"Airborne","type":"Aircraft"},"rseq":3,"sseq":2,"poll":false,"acars":{"err":false,"crc_ok":true,"more":false,"reg":".N123AB","mode":"2","label":"H1","blk_id":"4","ack":"!","flight":"AB0123","msg_num":"F18","msg_num_seq":"A","sublabel":"DF","msg_text":"POS N47123W122456,KSEA,KSFO,1530,350,M45"}},"burst_len_octets":74,"freq":136975000,"idx":0,"freq_skew":2.77,"hdr_bits_fixed":0,"noise_level":-40.7,"octets_corrected_by_fec":0,"sig_level":-14.6,"station":"EXAMPLE-STATION","t":{"sec":1700000000,"usec":250000}}}"#;
 
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
@@ -17,7 +51,7 @@ pub trait NewVdlm2Message {
 /// This does not consume the `String`.
 impl NewVdlm2Message for String {
     fn to_vdlm2(&self) -> MessageResult<Vdlm2Message> {
-        serde_json::from_str(self)
+        decode_span(Some(MessageFamily::Vdlm2), self.len(), || serde_json::from_str(self))
     }
 }
 
@@ -26,18 +60,81 @@ impl NewVdlm2Message for String {
 /// This does not consume the `str`.
 impl NewVdlm2Message for str {
     fn to_vdlm2(&self) -> MessageResult<Vdlm2Message> {
-        serde_json::from_str(self)
+        decode_span(Some(MessageFamily::Vdlm2), self.len(), || serde_json::from_str(self))
+    }
+}
+
+/// Supporting `.to_vdlm2()` for JSON held as bytes, such as a socket buffer.
+///
+/// This decodes straight from the bytes without converting them to a `str` first, and does not consume them.
+impl NewVdlm2Message for [u8] {
+    fn to_vdlm2(&self) -> MessageResult<Vdlm2Message> {
+        decode_span(Some(MessageFamily::Vdlm2), self.len(), || serde_json::from_slice(self))
+    }
+}
+
+/// Supporting `.to_vdlm2()` for JSON already parsed into a `Value`, such as by an earlier pipeline stage.
+///
+/// This decodes from the `Value` without writing it back out as JSON first, and does not consume it.
+impl NewVdlm2Message for Value {
+    fn to_vdlm2(&self) -> MessageResult<Vdlm2Message> {
+        decode_span(Some(MessageFamily::Vdlm2), 0, || Vdlm2Message::deserialize(self))
+    }
+}
+
+/// Decodes an owned `Value`, see `Vdlm2Message::from_value`.
+impl TryFrom<Value> for Vdlm2Message {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> MessageResult<Self> {
+        Self::from_value(value)
     }
 }
 
 /// Implementation of `Vdlm2Message`.
 impl Vdlm2Message {
 
-    /// Converts `Vdlm2Message` to `String`.
-    pub fn to_string(&self) -> MessageResult<String> {
+    /// Returns a realistic, fully populated message based on a sanitised dumpvdl2 capture, for doctests and unit tests
+    /// that need more than the empty shell `Default` gives.
+    /// ```
+    /// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+    /// use acars_vdlm2_parser::vdlm2::Vdlm2Message;
+    /// let example: Vdlm2Message = Vdlm2Message::example();
+    /// assert_eq!(example.get_flight(), Some("AB0123"));
+    /// let decoded: AcarsVdlm2Message = example.to_json().unwrap().decode_message().unwrap();
+    /// assert_eq!(decoded.to_json().unwrap(), example.to_json().unwrap());
+    /// ```
+    pub fn example() -> Self {
+        // The doctest above checks the example decodes, so the fallback is never taken.
+        serde_json::from_str(EXAMPLE).unwrap_or_default()
+    }
+
+    /// Decodes a `Vdlm2Message` from an owned `Value`, moving its strings into the message rather than copying them.
+    pub fn from_value(value: Value) -> MessageResult<Self> {
+        decode_span(Some(MessageFamily::Vdlm2), 0, || serde_json::from_value(value))
+    }
+
+    /// Converts `Vdlm2Message` to a JSON `String`.
+    pub fn to_json(&self) -> MessageResult<String> {
         serde_json::to_string(self)
     }
 
+    /// Converts `Vdlm2Message` to a JSON `String`.
+    ///
+    /// This shadows `ToString::to_string`, which returns the single line summary of the `Display` implementation, so
+    /// `message.to_string()` and `format!("{}", message)` differ. Use `to_json()` for the JSON and `summary_line()` for
+    /// the summary.
+    #[deprecated(since = "0.3.0", note = "use `to_json()` for the JSON, `to_string()` will return the `Display` summary")]
+    pub fn to_string(&self) -> MessageResult<String> {
+        self.to_json()
+    }
+
+    /// Returns the single line summary written by the `Display` implementation, for logging, see `to_json` for the
+    /// JSON.
+    pub fn summary_line(&self) -> String {
+        ToString::to_string(self)
+    }
+
 
     /// Converts `Vdlm2Message` to `String` and appends a `\n` to the end.
     pub fn to_string_newline(&self) -> MessageResult<String> {
@@ -48,27 +145,33 @@ impl Vdlm2Message {
         }
     }
 
-    /// Converts `Vdlm2Message` to a `String` encoded as bytes.
+    /// Converts `Vdlm2Message` to JSON encoded as bytes.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
-        let string_conversion: MessageResult<String> = self.to_string();
-        match string_conversion {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes())
-        }
+        serde_json::to_vec(self)
     }
 
-
-    /// Converts `Vdlm2Message` to a `String` terminated with a `\n` and encoded as bytes.
+    /// Converts `Vdlm2Message` to JSON encoded as bytes and terminated with a `\n`.
     ///
-    /// The output is returned as a `Vec<u8>`.
+    /// The output is returned as a `Vec<u8>` and is serialised directly without an intermediate `String`.
     pub fn to_bytes_newline(&self) -> MessageResult<Vec<u8>> {
-        let string_conversion: MessageResult<String> = self.to_string_newline();
-        match string_conversion {
-            Err(conversion_failed) => Err(conversion_failed),
-            Ok(string) => Ok(string.into_bytes())
-        }
+        let mut bytes: Vec<u8> = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    /// Serialises `Vdlm2Message` as JSON straight into the provided writer.
+    ///
+    /// This avoids allocating an intermediate buffer for the output.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(writer, self)
+    }
+
+    /// Serialises `Vdlm2Message` as JSON straight into the provided writer and terminates it with a `\n`.
+    pub fn write_to_newline<W: Write>(&self, writer: &mut W) -> MessageResult<()> {
+        serde_json::to_writer(&mut *writer, self)?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)
     }
 
     /// Clears a station name that may be set for `Vdlm2Message`.
@@ -106,25 +209,249 @@ impl Vdlm2Message {
         }
     }
 
+    /// Returns the app block for `Vdlm2Message` if there is one.
+    pub fn get_app_details(&self) -> Option<&AppDetails> {
+        self.vdl2.app.as_ref()
+    }
+
+    /// Applies the provided `AppDetailsTemplate`, see `AppDetailsTemplate` for how an existing app block is merged.
+    pub fn apply_app_template(&mut self, template: &AppDetailsTemplate) {
+        match self.vdl2.app.as_mut() {
+            None => self.vdl2.app = Some(template.details().clone()),
+            Some(app_details) => template.merge_into(app_details)
+        }
+    }
+
     pub fn clear_time(&mut self) {
         self.vdl2.t = None;
     }
 
     pub fn get_time(&self) -> Option<f64> {
-        match &self.vdl2.t {
-            None => None,
-            Some(time_block) => {
-                // This will do until there's a more elegant solution found.
-                let build_float_string: String = format!("{}.{}", time_block.sec, time_block.usec);
-                let parse_f64: Result<f64, ParseFloatError> = build_float_string.parse::<f64>();
-                match parse_f64 {
-                    Err(_) => None,
-                    Ok(value) => Some(value)
+        self.get_timestamp().map(|timestamp| timestamp.seconds)
+    }
+
+    /// Returns the time the station heard the burst, as dumpvdl2 and dumphfdl report it to the microsecond.
+    pub fn get_timestamp(&self) -> Option<MessageTimestamp> {
+        self.vdl2.t.as_ref().map(|time_block| MessageTimestamp::from_station(time_block.sec, time_block.usec))
+    }
+
+    /// Replaces every station identifying field with a stable pseudonym from the provided `StationRedactor`.
+    ///
+    /// This covers the station name, AVLC addresses belonging to ground stations,
+    /// ground station lists in XID parameters and the ARINC 622 `gs_addr`.
+    pub fn redact_ground_stations(&mut self, redactor: &StationRedactor) {
+        if let Some(station) = self.vdl2.station.as_mut() {
+            *station = redactor.pseudonym(station);
+        }
+        let avlc: &mut AvlcData = &mut self.vdl2.avlc;
+        if avlc.src.source_type == GROUND_STATION {
+            avlc.src.addr = redactor.pseudonym_address(&avlc.src.addr);
+        }
+        if avlc.dst.vehicle_type == GROUND_STATION {
+            avlc.dst.addr = redactor.pseudonym_address(&avlc.dst.addr);
+        }
+        if let Some(xid) = avlc.xid_mut() {
+            let pub_params = xid.pub_params.iter_mut().flatten();
+            for param in pub_params.chain(xid.vdl_params.iter_mut()) {
+                if let ("replacement_ground_stations" | "alternate_ground_stations", ParamValueType::VecString(addresses)) =
+                    (param.name.as_str(), &mut param.value) {
+                    for address in addresses.iter_mut() {
+                        *address = redactor.pseudonym_address(address);
+                    }
+                }
+            }
+        }
+        if let Some(arinc622) = avlc.acars_mut().and_then(|acars| acars.arinc622.as_mut()) {
+            arinc622.gs_addr = redactor.pseudonym_gs_addr(&arinc622.gs_addr);
+        }
+    }
+
+    /// Replaces the aircraft AVLC address, the ACARS registration, the ARINC 622 air address and the ADS-C airframe ID
+    /// with stable pseudonyms from the provided `AircraftAnonymiser`.
+    pub fn anonymise_aircraft(&mut self, anonymiser: &AircraftAnonymiser) {
+        let avlc: &mut AvlcData = &mut self.vdl2.avlc;
+        if avlc.src.source_type == AIRCRAFT {
+            avlc.src.addr = anonymiser.pseudonym_icao_hex(&avlc.src.addr);
+        }
+        if avlc.dst.vehicle_type == AIRCRAFT {
+            avlc.dst.addr = anonymiser.pseudonym_icao_hex(&avlc.dst.addr);
+        }
+        if let AvlcFrame::Information(frame) = &mut avlc.frame {
+            for acars in frame.acars.iter_mut() {
+                acars.reg = anonymiser.pseudonym_registration(&acars.reg);
+                if let Some(arinc622) = acars.arinc622.as_mut() {
+                    arinc622.air_addr = anonymiser.pseudonym_registration(&arinc622.air_addr);
+                    if let Some(adsc) = arinc622.adsc.as_mut() {
+                        adsc.anonymise_aircraft(anonymiser);
+                    }
                 }
             }
         }
+        #[cfg(feature = "enrichment")]
+        if let Some(registration) = self.vdl2.aircraft_info.as_mut().and_then(|info| info.registration.as_mut()) {
+            *registration = anonymiser.pseudonym_registration(registration);
+        }
+    }
+
+    /// Returns true if the messages are the same apart from the fields in the given classes, see `FieldClass`.
+    pub fn eq_ignoring(&self, other: &Vdlm2Message, ignored: &[FieldClass]) -> bool {
+        eq_ignoring(self, other, MessageFamily::Vdlm2, ignored)
+    }
+
+    /// Returns the `SourceKind` for `Vdlm2Message` from the type of the AVLC source address.
+    pub fn source_kind(&self) -> SourceKind {
+        match self.vdl2.avlc.src.source_type.as_str() {
+            AIRCRAFT => SourceKind::Aircraft,
+            GROUND_STATION => SourceKind::GroundStation,
+            _ => SourceKind::Unknown,
+        }
     }
 
+    /// Returns the `FrameQuality` for `Vdlm2Message`.
+    ///
+    /// Frames with a failed XID or ACARS block are `Failed`, while frames where header bits or octets
+    /// were fixed by the FEC are `Corrected`.
+    pub fn get_frame_quality(&self) -> FrameQuality {
+        let xid_failed: bool = self.vdl2.avlc.xid().is_some_and(|xid| xid.err);
+        let acars_failed: bool = self.get_acars().is_some_and(|acars| acars.err || !acars.crc_ok);
+        let corrected: bool = self.vdl2.hdr_bits_fixed.is_some_and(|fixed| fixed > 0) ||
+            self.vdl2.octets_corrected_by_fec.is_some_and(|corrected| corrected > 0);
+        match (xid_failed || acars_failed, corrected) {
+            (true, _) => FrameQuality::Failed,
+            (false, true) => FrameQuality::Corrected,
+            (false, false) => FrameQuality::Ok,
+        }
+    }
+
+    /// Returns the frequency `Vdlm2Message` was received on.
+    pub fn get_frequency(&self) -> Frequency {
+        self.vdl2.freq
+    }
+
+    /// Returns the station name for `Vdlm2Message` if one is set.
+    pub fn get_station_name(&self) -> Option<&str> {
+        self.vdl2.station.as_deref()
+    }
+
+    /// Returns the ACARS block carried by the AVLC frame if there is one.
+    pub fn get_acars(&self) -> Option<&AvlcAcars> {
+        self.vdl2.avlc.acars()
+    }
+
+    /// Returns the ACARS label for `Vdlm2Message` if the frame carries an ACARS block.
+    pub fn get_label(&self) -> Option<&str> {
+        self.get_acars().map(|acars| acars.label.as_str())
+    }
+
+    /// Returns the aircraft registration for `Vdlm2Message` with any leading padding dots removed.
+    pub fn get_registration(&self) -> Option<&str> {
+        self.get_acars().map(|acars| acars.reg.trim_start_matches('.'))
+    }
+
+    /// Returns the result of cross-checking the address and registration, if a `ConsistencyChecker` has checked the
+    /// frame.
+    pub fn consistency(&self) -> Option<&ConsistencyFlags> {
+        self.vdl2.consistency.as_ref()
+    }
+
+    /// Returns the flight number for `Vdlm2Message` if the frame carries one.
+    pub fn get_flight(&self) -> Option<&str> {
+        self.get_acars().and_then(|acars| acars.flight.as_deref())
+    }
+
+    /// Returns the ACARS message text for `Vdlm2Message` if the frame carries an ACARS block.
+    pub fn get_text(&self) -> Option<&str> {
+        self.get_acars().map(|acars| acars.msg_text.as_str())
+    }
+
+    /// Returns the `aircraft_info` block for `Vdlm2Message` if it has been enriched.
+    #[cfg(feature = "enrichment")]
+    pub fn get_aircraft_info(&self) -> Option<&AircraftInfo> {
+        self.vdl2.aircraft_info.as_ref()
+    }
+
+    /// Sets the `aircraft_info` block for `Vdlm2Message`.
+    #[cfg(feature = "enrichment")]
+    pub fn set_aircraft_info(&mut self, info: AircraftInfo) {
+        self.vdl2.aircraft_info = Some(info);
+    }
+
+    /// Returns the ACARS sublabel for `Vdlm2Message` if libacars decoded one.
+    pub fn get_sublabel(&self) -> Option<&str> {
+        self.get_acars().and_then(|acars| acars.sublabel.as_deref())
+    }
+
+    /// Normalises the ACARS message text using the provided `TextNormaliser`.
+    pub fn normalise_text(&mut self, normaliser: &TextNormaliser) {
+        if let Some(acars) = self.vdl2.avlc.acars_mut() {
+            normaliser.normalise_in_place(&mut acars.msg_text);
+        }
+    }
+
+    /// Returns the link layer fields used to pair `Vdlm2Message` with its acknowledgement.
+    pub fn get_ack_fields(&self) -> Option<AckFields<'_>> {
+        self.get_acars().map(|acars| AckFields {
+            mode: &acars.mode,
+            registration: acars.reg.trim_start_matches('.'),
+            label: &acars.label,
+            block_id: &acars.blk_id,
+            msgno: acars.msg_num.as_deref(),
+            ack: AckFields::parse_ack(&acars.ack),
+        })
+    }
+
+    /// Passes the parts of `Vdlm2Message` to the visitor, see `visit::MessageVisitor`.
+    ///
+    /// Positions come from ADS-C reports and the location in ground station information frames.
+    pub fn visit(&self, visitor: &mut dyn MessageVisitor) {
+        visitor.on_signal_info(&SignalInfo {
+            frequency: self.vdl2.freq,
+            level_dbm: self.vdl2.sig_level,
+            noise_level_dbm: self.vdl2.noise_level,
+            freq_skew_hz: self.vdl2.freq_skew,
+            quality: self.get_frame_quality(),
+        });
+        if let Some(app) = &self.vdl2.app {
+            visitor.on_app_details(app);
+        }
+        if let Some(link) = self.get_ack_fields() {
+            visitor.on_acars_block(&AcarsBlock { link, flight: self.get_flight(), sublabel: self.get_sublabel(), text: self.get_text() });
+        }
+        let adsc: Option<&Adsc> = self.get_acars().and_then(|acars| acars.arinc622.as_ref()).and_then(|arinc622| arinc622.adsc.as_ref());
+        for report in adsc.into_iter().flat_map(|adsc| adsc.position_reports()) {
+            visitor.on_position(&PositionReport { lat: report.lat, lon: report.lon, altitude_ft: Some(report.alt), source: PositionSource::AdscReport });
+        }
+        if let Some(location) = self.get_ground_station_info().and_then(|info| info.location) {
+            visitor.on_position(&PositionReport { lat: location.lat(), lon: location.lon(), altitude_ft: None, source: PositionSource::GroundStation });
+        }
+    }
+
+    /// Returns the decoded ground station information if the frame is a GSIF, see `XidBlock::ground_station_info`.
+    pub fn get_ground_station_info(&self) -> Option<GroundStationInfo> {
+        self.vdl2.avlc.xid().and_then(|xid| xid.ground_station_info())
+    }
+
+    /// Returns the aircraft and ground station at the two ends of the AVLC frame, or `None` if it is not between one
+    /// of each, such as a frame broadcast to every aircraft.
+    pub fn get_link(&self) -> Option<AvlcLink<'_>> {
+        let avlc: &AvlcData = &self.vdl2.avlc;
+        let link: AvlcLink<'_> = match (avlc.src.source_type.as_str(), avlc.dst.vehicle_type.as_str()) {
+            (AIRCRAFT, GROUND_STATION) => AvlcLink { aircraft: &avlc.src.addr, ground_station: &avlc.dst.addr, uplink: false },
+            (GROUND_STATION, AIRCRAFT) => AvlcLink { aircraft: &avlc.dst.addr, ground_station: &avlc.src.addr, uplink: true },
+            _ => return None,
+        };
+        (link.aircraft != BROADCAST_ADDRESS && link.ground_station != BROADCAST_ADDRESS).then_some(link)
+    }
+
+    /// Returns the ICAO address of the aircraft at either end of the AVLC frame.
+    pub fn get_icao(&self) -> Option<u32> {
+        let avlc: &AvlcData = &self.vdl2.avlc;
+        match (avlc.src.source_type.as_str(), avlc.dst.vehicle_type.as_str()) {
+            (AIRCRAFT, _) => parse_icao_hex(&avlc.src.addr),
+            (_, AIRCRAFT) => parse_icao_hex(&avlc.dst.addr),
+            _ => None,
+        }
+    }
 
     pub fn clear_freq_skew(&mut self) {
         self.vdl2.freq_skew = None;
@@ -151,61 +478,358 @@ impl Vdlm2Message {
     }
 }
 
+/// Renders a concise single line summary of the message, intended for logging rather than forwarding.
+///
+/// `summary_line()` returns it as a `String`, and `to_json()` returns the JSON.
+impl fmt::Display for Vdlm2Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        SummaryLine {
+            family: "VDLM2",
+            time: self.get_time(),
+            station: self.get_station_name(),
+            freq_mhz: self.vdl2.freq.as_mhz(),
+            registration: self.get_registration(),
+            flight: self.get_flight(),
+            label: self.get_label(),
+            text: self.get_text(),
+        }.fmt(f)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Vdlm2Message {
     pub vdl2: Vdlm2Body
 }
 
+/// The body of a dumpvdl2 message.
+///
+/// dumpvdl2 run with `--msg-filter` can leave out fields it normally writes, so only `avlc` and `freq` are required.
+/// A missing `burst_len_octets` or `idx` reads as `0` and is written back as `0`.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Vdlm2Body {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub app: Option<AppDetails>,
     pub avlc: AvlcData,
+    #[serde(default, deserialize_with = "number_or_string")]
     pub burst_len_octets: u16,
-    pub freq: u64,
+    pub freq: Frequency,
+    #[serde(default, deserialize_with = "number_or_string")]
     pub idx: u16,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub freq_skew: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub hdr_bits_fixed: Option<u16>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub noise_level: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub octets_corrected_by_fec: Option<u16>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "option_number_or_string")]
     pub sig_level: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Station name set by the feeder, also read from `station_id` as acarsdec names it.
+    #[serde(alias = "station_id", skip_serializing_if = "Option::is_none")]
     pub station: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub t: Option<TBlock>
+    pub t: Option<TBlock>,
+    /// Result of cross-checking the address and registration, set by `ConsistencyChecker`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<ConsistencyFlags>,
+    #[cfg(feature = "enrichment")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aircraft_info: Option<AircraftInfo>,
 }
 
+/// Time the frame was received, as whole seconds and microseconds since the Unix epoch.
+///
+/// Either part can also be read quoted or with a fractional part, as some forks write them, and is dropped to a whole
+/// number.
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Default)]
 pub struct TBlock {
+    #[serde(deserialize_with = "whole_number")]
     pub sec: u64,
+    #[serde(deserialize_with = "whole_number")]
     pub usec: u64
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// AVLC frame, with the fields that only apply to one kind of frame held in `AvlcFrame`.
+///
+/// This reads and writes the flat layout dumpvdl2 outputs, where `frame_type` decides which other fields are present.
+/// Any of those fields that are missing are kept as `None` and left out again when written, and an unknown
+/// `frame_type` is an error. Fields that do not belong to the frame type, such as an `acars` block on an unnumbered
+/// frame, are dropped with a fallback warning.
+/// Output filtered with `--msg-filter` can also leave out `cr` and `frame_type`, in which case the frame type is worked
+/// out from the fields that are present.
+/// ```
+/// use acars_vdlm2_parser::vdlm2::{AvlcData, AvlcFrame};
+/// let avlc: AvlcData = serde_json::from_str(r#"{"cmd":"Receive Ready","cr":"Response","dst":{"addr":"10214A","type":"Ground station"},"frame_type":"S","pf":true,"src":{"addr":"7C6C32","status":"Airborne","type":"Aircraft"},"rseq":3}"#).unwrap();
+/// assert!(matches!(&avlc.frame, AvlcFrame::Supervisory(frame) if frame.rseq == Some(3)));
+/// assert_eq!(avlc.frame_type(), "S");
+/// ```
+#[derive(Debug, Clone, Default)]
 pub struct AvlcData {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cmd: Option<String>,
     pub cr: String,
     pub dst: DstBlock,
-    pub frame_type: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pf: Option<bool>,
     pub src: SrcBlock,
+    pub frame: AvlcFrame,
+}
+
+impl AvlcData {
+    /// Returns the frame type as dumpvdl2 outputs it, `I`, `S` or `U`.
+    pub fn frame_type(&self) -> &'static str {
+        match self.frame {
+            AvlcFrame::Information(_) => INFORMATION_FRAME,
+            AvlcFrame::Supervisory(_) => SUPERVISORY_FRAME,
+            AvlcFrame::Unnumbered(_) => UNNUMBERED_FRAME,
+        }
+    }
+
+    /// Returns the XID block if this is an unnumbered frame carrying one.
+    pub fn xid(&self) -> Option<&XidBlock> {
+        match &self.frame {
+            AvlcFrame::Unnumbered(frame) => frame.xid.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the XID block for editing if this is an unnumbered frame carrying one.
+    pub fn xid_mut(&mut self) -> Option<&mut XidBlock> {
+        match &mut self.frame {
+            AvlcFrame::Unnumbered(frame) => frame.xid.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Returns the ACARS block if this is an information frame carrying one, or the first block if it carries several.
+    pub fn acars(&self) -> Option<&AvlcAcars> {
+        self.acars_blocks().first()
+    }
+
+    /// Returns the ACARS block for editing if this is an information frame carrying one, or the first block if it
+    /// carries several.
+    pub fn acars_mut(&mut self) -> Option<&mut AvlcAcars> {
+        match &mut self.frame {
+            AvlcFrame::Information(frame) => frame.acars.first_mut(),
+            _ => None,
+        }
+    }
+
+    /// Returns every ACARS block carried by the frame, which is empty for frames other than information frames.
+    pub fn acars_blocks(&self) -> &[AvlcAcars] {
+        match &self.frame {
+            AvlcFrame::Information(frame) => &frame.acars,
+            _ => &[],
+        }
+    }
+}
+
+/// The fields of an `AvlcData` that depend on the kind of frame.
+///
+/// Information frames make up most traffic, so the ACARS block is kept inline rather than boxed.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum AvlcFrame {
+    /// An information frame, `frame_type` `I`, carrying data such as an ACARS block.
+    Information(AvlcInformationFrame),
+    /// A supervisory frame, `frame_type` `S`, such as Receive Ready.
+    Supervisory(AvlcSupervisoryFrame),
+    /// An unnumbered frame, `frame_type` `U`, such as an XID exchange.
+    Unnumbered(AvlcUnnumberedFrame),
+}
+
+impl Default for AvlcFrame {
+    fn default() -> Self {
+        Self::Information(Default::default())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AvlcInformationFrame {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rseq: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sseq: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll: Option<bool>,
+    /// ACARS blocks carried by the frame. There is normally at most one, but dumpvdl2 occasionally writes several as
+    /// an array when reassembly produced more than one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty", serialize_with = "serialize_acars_blocks", deserialize_with = "one_or_many")]
+    pub acars: Vec<AvlcAcars>,
+}
+
+/// Writes a single ACARS block as an object, as dumpvdl2 normally does, and several as an array.
+fn serialize_acars_blocks<S: Serializer>(blocks: &[AvlcAcars], serializer: S) -> Result<S::Ok, S::Error> {
+    match blocks {
+        [block] => block.serialize(serializer),
+        blocks => blocks.serialize(serializer),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AvlcSupervisoryFrame {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pf: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rseq: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AvlcUnnumberedFrame {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pf: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub xid: Option<XidBlock>,
+}
+
+/// The flat layout dumpvdl2 outputs, used to read an `AvlcData`.
+#[derive(Deserialize)]
+struct FlatAvlcData {
+    cmd: Option<String>,
+    #[serde(default)]
+    cr: String,
+    dst: DstBlock,
+    frame_type: Option<String>,
+    pf: Option<bool>,
+    src: SrcBlock,
+    xid: Option<XidBlock>,
+    rseq: Option<u16>,
+    sseq: Option<u16>,
+    poll: Option<bool>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    acars: Vec<AvlcAcars>,
+}
+
+impl FlatAvlcData {
+    /// Works out the frame type from the fields present, for output filtered so that `frame_type` is missing.
+    ///
+    /// Only supervisory frames have both a command and a receive sequence number, and only unnumbered frames have a
+    /// command without one.
+    fn infer_frame_type(&self) -> &'static str {
+        match (&self.cmd, &self.rseq, &self.xid) {
+            (Some(_), Some(_), _) => SUPERVISORY_FRAME,
+            (Some(_), None, _) | (None, _, Some(_)) => UNNUMBERED_FRAME,
+            (None, _, None) => INFORMATION_FRAME,
+        }
+    }
+
+    /// Returns the names of the fields present that do not belong to the frame type, which are dropped when reading.
+    fn fields_outside(&self, frame_type: &str) -> Vec<&'static str> {
+        let present: [(&'static str, bool, &[&str]); 7] = [
+            ("cmd", self.cmd.is_some(), &[SUPERVISORY_FRAME, UNNUMBERED_FRAME]),
+            ("pf", self.pf.is_some(), &[SUPERVISORY_FRAME, UNNUMBERED_FRAME]),
+            ("xid", self.xid.is_some(), &[UNNUMBERED_FRAME]),
+            ("rseq", self.rseq.is_some(), &[INFORMATION_FRAME, SUPERVISORY_FRAME]),
+            ("sseq", self.sseq.is_some(), &[INFORMATION_FRAME]),
+            ("poll", self.poll.is_some(), &[INFORMATION_FRAME]),
+            ("acars", !self.acars.is_empty(), &[INFORMATION_FRAME]),
+        ];
+        present.into_iter()
+            .filter(|(_, is_present, frame_types)| *is_present && !frame_types.contains(&frame_type))
+            .map(|(field, _, _)| field)
+            .collect()
+    }
+}
+
+/// The flat layout dumpvdl2 outputs, borrowed from an `AvlcData` to write it.
+#[derive(Serialize)]
+struct FlatAvlcDataRef<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub rseq: Option<u16>,
+    cmd: Option<&'a str>,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    cr: &'a str,
+    dst: &'a DstBlock,
+    frame_type: &'a str,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub sseq: Option<u16>,
+    pf: Option<bool>,
+    src: &'a SrcBlock,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub poll: Option<bool>,
+    xid: Option<&'a XidBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rseq: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub acars: Option<AvlcAcars>
+    sseq: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    poll: Option<bool>,
+    #[serde(skip_serializing_if = "<[AvlcAcars]>::is_empty", serialize_with = "serialize_acars_blocks")]
+    acars: &'a [AvlcAcars],
+}
+
+impl Serialize for AvlcData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut flat: FlatAvlcDataRef = FlatAvlcDataRef {
+            cmd: None,
+            cr: &self.cr,
+            dst: &self.dst,
+            frame_type: self.frame_type(),
+            pf: None,
+            src: &self.src,
+            xid: None,
+            rseq: None,
+            sseq: None,
+            poll: None,
+            acars: &[],
+        };
+        match &self.frame {
+            AvlcFrame::Information(frame) => {
+                flat.rseq = frame.rseq;
+                flat.sseq = frame.sseq;
+                flat.poll = frame.poll;
+                flat.acars = &frame.acars;
+            }
+            AvlcFrame::Supervisory(frame) => {
+                flat.cmd = frame.cmd.as_deref();
+                flat.pf = frame.pf;
+                flat.rseq = frame.rseq;
+            }
+            AvlcFrame::Unnumbered(frame) => {
+                flat.cmd = frame.cmd.as_deref();
+                flat.pf = frame.pf;
+                flat.xid = frame.xid.as_ref();
+            }
+        }
+        flat.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AvlcData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let flat: FlatAvlcData = FlatAvlcData::deserialize(deserializer)?;
+        let frame_type: String = match flat.frame_type.clone() {
+            Some(frame_type) => frame_type,
+            None => {
+                let inferred: &str = flat.infer_frame_type();
+                fallback("inferred_frame_type", inferred);
+                inferred.to_string()
+            }
+        };
+        let dropped: Vec<&str> = flat.fields_outside(&frame_type);
+        let frame: AvlcFrame = match frame_type.as_str() {
+            INFORMATION_FRAME => AvlcFrame::Information(AvlcInformationFrame {
+                rseq: flat.rseq,
+                sseq: flat.sseq,
+                poll: flat.poll,
+                acars: flat.acars,
+            }),
+            SUPERVISORY_FRAME => AvlcFrame::Supervisory(AvlcSupervisoryFrame { cmd: flat.cmd, pf: flat.pf, rseq: flat.rseq }),
+            UNNUMBERED_FRAME => AvlcFrame::Unnumbered(AvlcUnnumberedFrame { cmd: flat.cmd, pf: flat.pf, xid: flat.xid }),
+            other => return Err(D::Error::unknown_variant(other, &[INFORMATION_FRAME, SUPERVISORY_FRAME, UNNUMBERED_FRAME])),
+        };
+        if !dropped.is_empty() {
+            fallback("dropped_avlc_field", &format!("{} on frame type {}", dropped.join(", "), frame_type));
+        }
+        Ok(Self { cr: flat.cr, dst: flat.dst, src: flat.src, frame })
+    }
+}
+
+/// The AVLC addresses of the aircraft and ground station a frame was sent between, see `Vdlm2Message::get_link`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct AvlcLink<'a> {
+    pub aircraft: &'a str,
+    pub ground_station: &'a str,
+    /// True if the ground station sent the frame.
+    pub uplink: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Default)]
@@ -218,6 +842,8 @@ pub struct DstBlock {
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Default)]
 pub struct SrcBlock {
     pub addr: String,
+    /// Whether the aircraft is airborne or on the ground, which filtered output can leave out.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub status: String,
     #[serde(rename = "type")]
     pub source_type: String
@@ -232,12 +858,114 @@ pub struct XidBlock {
     pub xid_type: String,
     #[serde(rename = "type_descr")]
     pub xid_type_descr: String,
+    #[serde(default)]
     pub vdl_params: Vec<XidParam>
 }
 
+impl XidBlock {
+    /// Returns true if this is a Ground Station Information Frame broadcast by a ground station.
+    pub fn is_gsif(&self) -> bool {
+        self.xid_type == GSIF
+    }
+
+    /// Returns the parameters from both the public and VDL specific parameter lists.
+    pub fn params(&self) -> impl Iterator<Item = &XidParam> {
+        self.pub_params.iter().flatten().chain(self.vdl_params.iter())
+    }
+
+    /// Returns the first parameter with the provided name.
+    pub fn get_param(&self, name: &str) -> Option<&ParamValueType> {
+        self.params()
+            .find(|param| param.name == name)
+            .map(|param| &param.value)
+    }
+
+    /// Returns true if this is a handoff command or response, including a ground station requesting a handoff.
+    pub fn is_handoff(&self) -> bool {
+        HANDOFF_XID_PREFIXES.iter().any(|prefix| self.xid_type.starts_with(prefix))
+    }
+
+    /// Returns the frequency an aircraft is told to tune to, from the autotune parameter of a handoff.
+    pub fn autotune(&self) -> Option<FrequencySupport> {
+        self.params().find_map(|param| match &param.value {
+            ParamValueType::AutoTune { freq_mhz, modulation_support } =>
+                Some(FrequencySupport { freq_mhz: *freq_mhz, modulation_support: Some(modulation_support.clone()) }),
+            _ => None,
+        })
+    }
+
+    /// Collects every frequency in the autotune and frequency support parameters into a `FrequencySupportList`.
+    pub fn frequency_support(&self) -> FrequencySupportList {
+        let mut list: FrequencySupportList = FrequencySupportList::default();
+        for param in self.params() {
+            match &param.value {
+                ParamValueType::AutoTune { freq_mhz, modulation_support } =>
+                    list.entries.push(FrequencySupport { freq_mhz: *freq_mhz, modulation_support: Some(modulation_support.clone()) }),
+                ParamValueType::FrequencySupport(frequencies) => list.entries.extend(frequencies.iter().cloned()),
+                _ => {}
+            }
+        }
+        list
+    }
+
+    /// Returns the ground stations listed as replacements in a handoff, which the aircraft is expected to move to.
+    pub fn replacement_ground_stations(&self) -> Vec<&str> {
+        match self.get_param("replacement_ground_stations") {
+            Some(ParamValueType::VecString(addresses)) => addresses.iter().map(|address| address.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Collects the parameters of a GSIF into a `GroundStationInfo`, or returns `None` if this is not a GSIF.
+    pub fn ground_station_info(&self) -> Option<GroundStationInfo> {
+        if !self.is_gsif() {
+            return None;
+        }
+        let mut info: GroundStationInfo = GroundStationInfo::default();
+        for param in self.vdl_params.iter() {
+            match (param.name.as_str(), &param.value) {
+                ("param_set_id", ParamValueType::String(id)) => info.param_set_id = Some(id.clone()),
+                ("airport_coverage", ParamValueType::String(airports)) =>
+                    info.airport_coverage.extend(airports.split_whitespace().map(|airport| airport.to_string())),
+                ("gs_location", ParamValueType::CoOrdinates(location)) => info.location = Some(location.clone()),
+                ("system_mask", ParamValueType::VecString(masks)) => info.system_mask = masks.first().cloned(),
+                ("atn_router_nets", ParamValueType::VecInteger(nets)) => info.atn_router_nets = nets.clone(),
+                _ => {}
+            }
+        }
+        info.frequencies_mhz = self.frequency_support().entries.iter().map(|frequency| frequency.freq_mhz).collect();
+        Some(info)
+    }
+}
+
+/// Ground station details broadcast in a Ground Station Information Frame (GSIF).
+/// ```
+/// use acars_vdlm2_parser::vdlm2::{GroundStationInfo, NewVdlm2Message, Vdlm2Message};
+/// let gsif: &str = r#"{"vdl2":{"avlc":{"cmd":"XID","cr":"Command","dst":{"addr":"FFFFFF","type":"Aircraft"},"frame_type":"U","src":{"addr":"281658","status":"On ground","type":"Ground station"},"xid":{"err":false,"type":"GSIF","type_descr":"Ground Station Information Frame","vdl_params":[{"name":"param_set_id","value":"V"},{"name":"airport_coverage","value":"YPPH"},{"name":"gs_location","value":{"lat":-31.9,"lon":116.0}}]}},"burst_len_octets":72,"freq":136975000,"idx":0}}"#;
+/// let message: Vdlm2Message = gsif.to_vdlm2().unwrap();
+/// let info: GroundStationInfo = message.get_ground_station_info().unwrap();
+/// assert_eq!(info.airport_coverage, vec!["YPPH".to_string()]);
+/// assert_eq!(info.location.map(|location| (location.lat(), location.lon())), Some((-31.9, 116.0)));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct GroundStationInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param_set_id: Option<String>,
+    /// ICAO codes of the airports the ground station covers.
+    pub airport_coverage: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<CoOrdinates>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_mask: Option<String>,
+    pub atn_router_nets: Vec<u64>,
+    /// Frequencies the ground station advertises, in MHz.
+    pub frequencies_mhz: Vec<f64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Default)]
 pub struct XidParam {
     pub name: String,
+    #[serde(deserialize_with = "via_value")]
     pub value: ParamValueType
 }
 
@@ -271,6 +999,60 @@ pub enum ParamValueType {
     AutoTune {
         freq_mhz: f64,
         modulation_support: Vec<String>
+    },
+    FrequencySupport(Vec<FrequencySupport>)
+}
+
+/// A frequency advertised by a ground station along with the modulations it supports.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Default)]
+pub struct FrequencySupport {
+    pub freq_mhz: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modulation_support: Option<Vec<String>>
+}
+
+impl FrequencySupport {
+    /// Returns the frequency rounded to the nearest kHz, as dumpvdl2 writes it with single precision.
+    pub fn frequency(&self) -> Frequency {
+        Frequency::from_hz(((self.freq_mhz * 1_000.0).round() * 1_000.0) as u64)
+    }
+
+    /// Returns true if the modulations listed include VDL mode 2.
+    pub fn supports_vdlm2(&self) -> bool {
+        self.modulation_support.iter().flatten().any(|modulation| modulation.starts_with("VDL-M2"))
+    }
+}
+
+/// The frequencies a ground station advertises or tells an aircraft to use, each with the modulations it supports.
+/// ```
+/// use acars_vdlm2_parser::frequency::Frequency;
+/// use acars_vdlm2_parser::vdlm2::{FrequencySupportList, XidBlock};
+/// let xid: XidBlock = serde_json::from_str(r#"{"err":false,"type":"XID_CMD_HO","type_descr":"Handoff Request / Broadcast Handoff","vdl_params":[{"name":"autotune_freq","value":{"freq_mhz":136.100006,"modulation_support":["VDL-M2, D8PSK, 31500 bps"]}}]}"#).unwrap();
+/// let list: FrequencySupportList = xid.frequency_support();
+/// assert!(xid.is_handoff());
+/// assert_eq!(list.frequencies(), vec![Frequency::from_mhz(136.1)]);
+/// assert!(list.supports(Frequency::from_hz(136_100_000)));
+/// assert!(list.entries[0].supports_vdlm2());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct FrequencySupportList {
+    pub entries: Vec<FrequencySupport>,
+}
+
+impl FrequencySupportList {
+    /// Returns every frequency in the list, in the order they were listed.
+    pub fn frequencies(&self) -> Vec<Frequency> {
+        self.entries.iter().map(|entry| entry.frequency()).collect()
+    }
+
+    /// Returns true if the frequency is in the list.
+    pub fn supports(&self, frequency: Frequency) -> bool {
+        self.entries.iter().any(|entry| entry.frequency() == frequency)
+    }
+
+    /// Returns true if there are no frequencies in the list.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
     }
 }
 
@@ -280,10 +1062,32 @@ impl Default for ParamValueType {
     }
 }
 
+/// A location carried in XID parameters such as `gs_location`.
+///
+/// The fields are public like those of every other decoded type; `lat()` and `lon()` are kept for existing callers.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Default)]
 pub struct CoOrdinates {
-    lat: f64,
-    lon: f64
+    /// Latitude in decimal degrees.
+    pub lat: f64,
+    /// Longitude in decimal degrees.
+    pub lon: f64
+}
+
+impl CoOrdinates {
+    /// Creates co-ordinates from a latitude and longitude in decimal degrees.
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Latitude in decimal degrees.
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    /// Longitude in decimal degrees.
+    pub fn lon(&self) -> f64 {
+        self.lon
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -323,12 +1127,15 @@ pub struct Arinc622 {
     pub cpdlc: Option<CPDLC>
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
-pub struct AdscEntry {
-    pub tags: Vec<Value>,
-    pub err: bool
-}
+/// ADS-C block carried by an ARINC 622 message, see `adsc::Adsc`.
+///
+/// This is a breaking change from 0.2, where `AdscEntry` was a struct holding each tag as a raw `Value`. The tags are
+/// now typed as `adsc::AdscTag`, and a tag that is not recognised is still kept as a `Value` in `AdscTag::Other`.
+pub type AdscEntry = Adsc;
 
+/// The report interval group as it was written before ADS-C was typed. It is no longer decoded into; use
+/// `adsc::AdscContractGroup`.
+#[deprecated(since = "0.3.0", note = "ADS-C is decoded into the `adsc` module, use `adsc::AdscContractGroup`")]
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(rename_all = "snake_case")]
 pub enum AdscTagGroups {
@@ -337,12 +1144,15 @@ pub enum AdscTagGroups {
     }
 }
 
+#[allow(deprecated)]
 impl Default for AdscTagGroups {
     fn default() -> Self {
         Self::ReportInterval { interval_secs: 0 }
     }
 }
 
+/// A waypoint as it was written before ADS-C was typed. It is no longer decoded into; use `adsc::AdscWaypoint`.
+#[deprecated(since = "0.3.0", note = "ADS-C is decoded into the `adsc` module, use `adsc::AdscWaypoint`")]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Default)]
 pub struct AdscWaypoint {
     pub lat: f64,
@@ -351,12 +1161,18 @@ pub struct AdscWaypoint {
     pub eta_sec: Option<i16>
 }
 
+/// A non-compliance group as it was written before ADS-C was typed. It is no longer decoded into; use
+/// `adsc::AdscNonCompGroup`.
+#[deprecated(since = "0.3.0", note = "ADS-C is decoded into the `adsc` module, use `adsc::AdscNonCompGroup`")]
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Default)]
 pub struct NonCompMessageGroup {
     pub noncomp_tag: i64,
     pub noncomp_cause: String
 }
 
+/// An event report as it was written before ADS-C was typed. It is no longer decoded into; use
+/// `adsc::AdscBasicReport`.
+#[deprecated(since = "0.3.0", note = "ADS-C is decoded into the `adsc` module, use `adsc::AdscBasicReport`")]
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct AdscEventData {
     pub alt: i64,
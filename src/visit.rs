@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use crate::AppDetails;
+use crate::correlate::AckFields;
+use crate::frequency::Frequency;
+use crate::quality::FrameQuality;
+
+/// The ACARS block of a message, passed to `MessageVisitor::on_acars_block` whichever family carried it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AcarsBlock<'a> {
+    /// Mode, registration, label, block ID, message number and acknowledgement.
+    pub link: AckFields<'a>,
+    pub flight: Option<&'a str>,
+    pub sublabel: Option<&'a str>,
+    pub text: Option<&'a str>,
+}
+
+/// What a `PositionReport` is the position of, and how it was reported.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionSource {
+    /// An aircraft position from an ADS-C basic report or event report.
+    AdscReport,
+    /// An aircraft position sent in an HFDL performance or frequency data message.
+    HfdlAircraft,
+    /// An aircraft position from a UAT ADS-B state vector, as decoded by dump978-fa.
+    UatAircraft,
+    /// The location of a ground station, from a VDLM2 ground station information frame, the HFDL system table or the
+    /// header of a UAT uplink.
+    GroundStation,
+}
+
+/// A position found anywhere in a message, passed to `MessageVisitor::on_position`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PositionReport {
+    /// Latitude in decimal degrees.
+    pub lat: f64,
+    /// Longitude in decimal degrees.
+    pub lon: f64,
+    /// Altitude in feet, if the report has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude_ft: Option<i32>,
+    pub source: PositionSource,
+}
+
+/// Radio details of a message, passed to `MessageVisitor::on_signal_info`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SignalInfo {
+    pub frequency: Frequency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_dbm: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub noise_level_dbm: Option<f64>,
+    /// Offset from the channel frequency in Hz, as reported by dumpvdl2 and dumphfdl.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freq_skew_hz: Option<f64>,
+    pub quality: FrameQuality,
+}
+
+/// Callbacks for the parts of a message, called by `AcarsVdlm2Message::visit()` whichever family the message is.
+///
+/// Every callback does nothing by default, so a visitor only implements the parts it is interested in. This lets
+/// cross cutting features such as statistics be written once rather than matching on each message type.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::visit::{AcarsBlock, MessageVisitor, SignalInfo};
+/// #[derive(Default)]
+/// struct LabelCounter {
+///     labels: Vec<String>,
+///     levels: Vec<f64>,
+/// }
+/// impl MessageVisitor for LabelCounter {
+///     fn on_acars_block(&mut self, block: &AcarsBlock<'_>) {
+///         self.labels.push(block.link.label.to_string());
+///     }
+///     fn on_signal_info(&mut self, signal: &SignalInfo) {
+///         self.levels.extend(signal.level_dbm);
+///     }
+/// }
+/// let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"level":-31,"mode":"2","label":"Q0","block_id":"7","ack":false,"tail":".N12345"}"#.decode_message().unwrap();
+/// let mut counter: LabelCounter = LabelCounter::default();
+/// message.visit(&mut counter);
+/// assert_eq!(counter.labels, vec!["Q0".to_string()]);
+/// assert_eq!(counter.levels, vec![-31.0]);
+/// ```
+pub trait MessageVisitor {
+    /// Called with the signal details of every message, before any other callback.
+    fn on_signal_info(&mut self, _signal: &SignalInfo) {}
+
+    /// Called with the application details if the message has them.
+    fn on_app_details(&mut self, _app: &AppDetails) {}
+
+    /// Called with the ACARS block if the message carries one.
+    fn on_acars_block(&mut self, _block: &AcarsBlock<'_>) {}
+
+    /// Called once for every position in the message, in the order they appear.
+    fn on_position(&mut self, _position: &PositionReport) {}
+}
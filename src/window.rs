@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::{AcarsVdlm2Message, MessageFamily};
+
+/// Most windows a single message may fall in, which bounds how much smaller the step may be than the window.
+pub const MAX_OVERLAPPING_WINDOWS: f64 = 10_000.0;
+
+/// Summary of the messages whose timestamp fell inside a single time window.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct WindowSummary {
+    /// Start of the window in seconds since the Unix epoch, inclusive.
+    pub start: f64,
+    /// End of the window in seconds since the Unix epoch, exclusive.
+    pub end: f64,
+    pub total: u64,
+    pub by_family: BTreeMap<MessageFamily, u64>,
+    pub by_label: BTreeMap<String, u64>,
+    pub by_station: BTreeMap<String, u64>,
+}
+
+impl WindowSummary {
+    fn new(start: f64, end: f64) -> Self {
+        Self { start, end, ..Default::default() }
+    }
+
+    fn record(&mut self, message: &AcarsVdlm2Message) {
+        self.total += 1;
+        *self.by_family.entry(message.get_family()).or_default() += 1;
+        if let Some(label) = message.get_label() {
+            *self.by_label.entry(label.to_string()).or_default() += 1;
+        }
+        if let Some(station) = message.get_station_name() {
+            *self.by_station.entry(station.to_string()).or_default() += 1;
+        }
+    }
+}
+
+/// Groups messages into fixed or sliding time windows based on the message timestamp rather than arrival time.
+///
+/// A window is emitted once a message is seen whose timestamp is past the end of the window plus the allowed lateness.
+/// Messages arriving for windows that have already been emitted are counted as late and otherwise ignored,
+/// and messages without a timestamp are counted as untimed.
+/// ```
+/// use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+/// use acars_vdlm2_parser::window::{WindowSummary, Windower};
+/// let mut windower: Windower = Windower::fixed(60.0).unwrap();
+/// let first: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"H1","timestamp":30.0}"#.decode_message().unwrap();
+/// let second: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"SQ","timestamp":90.0}"#.decode_message().unwrap();
+/// assert!(windower.push(&first).is_empty());
+/// let closed: Vec<WindowSummary> = windower.push(&second);
+/// assert_eq!(closed.len(), 1);
+/// assert_eq!((closed[0].start, closed[0].end, closed[0].total), (0.0, 60.0, 1));
+/// assert_eq!(windower.flush()[0].by_label.get("SQ"), Some(&1));
+/// assert!(Windower::fixed(0.0).is_none() && Windower::sliding(60.0, f64::NAN).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Windower {
+    size: f64,
+    step: f64,
+    allowed_lateness: f64,
+    windows: BTreeMap<i64, WindowSummary>,
+    next_open: Option<i64>,
+    late: u64,
+    untimed: u64,
+    out_of_range: u64,
+}
+
+impl Windower {
+    /// Creates a `Windower` with back to back windows of `size_secs`, or `None` unless `size_secs` is finite and
+    /// greater than zero.
+    pub fn fixed(size_secs: f64) -> Option<Self> {
+        Self::sliding(size_secs, size_secs)
+    }
+
+    /// Creates a `Windower` with windows of `size_secs` starting every `step_secs`.
+    ///
+    /// When `step_secs` is smaller than `size_secs` the windows overlap and a message is counted in each window it falls in.
+    /// Returns `None` unless both are finite and greater than zero, and a message falls in at most
+    /// `MAX_OVERLAPPING_WINDOWS` windows.
+    pub fn sliding(size_secs: f64, step_secs: f64) -> Option<Self> {
+        let valid = |secs: f64| secs.is_finite() && secs > 0.0;
+        if !valid(size_secs) || !valid(step_secs) || size_secs / step_secs > MAX_OVERLAPPING_WINDOWS {
+            return None;
+        }
+        Some(Self {
+            size: size_secs,
+            step: step_secs,
+            allowed_lateness: 0.0,
+            windows: BTreeMap::new(),
+            next_open: None,
+            late: 0,
+            untimed: 0,
+            out_of_range: 0,
+        })
+    }
+
+    /// Sets how far behind the newest timestamp a message may be and still be counted, to tolerate out of order feeds.
+    ///
+    /// Returns `false`, leaving the current lateness in place, unless `lateness_secs` is finite and not negative.
+    pub fn set_allowed_lateness(&mut self, lateness_secs: f64) -> bool {
+        if !lateness_secs.is_finite() || lateness_secs < 0.0 {
+            return false;
+        }
+        self.allowed_lateness = lateness_secs;
+        true
+    }
+
+    /// Number of messages ignored because their windows had already been emitted.
+    pub fn late(&self) -> u64 {
+        self.late
+    }
+
+    /// Number of messages ignored because they had no timestamp.
+    pub fn untimed(&self) -> u64 {
+        self.untimed
+    }
+
+    /// Number of messages ignored because their timestamp is too far from zero to number their windows.
+    pub fn out_of_range(&self) -> u64 {
+        self.out_of_range
+    }
+
+    fn window_start(&self, index: i64) -> f64 {
+        index as f64 * self.step
+    }
+
+    /// Adds a message to every window it falls in, returning any windows that are now complete.
+    pub fn push(&mut self, message: &AcarsVdlm2Message) -> Vec<WindowSummary> {
+        let Some(time) = message.get_time() else {
+            self.untimed += 1;
+            return Vec::new();
+        };
+        let indices = i64::MIN as f64..i64::MAX as f64;
+        let before_first: f64 = ((time - self.size) / self.step).floor();
+        let last: f64 = (time / self.step).floor();
+        let first: Option<i64> = match indices.contains(&before_first) && indices.contains(&last) {
+            true => (before_first as i64).checked_add(1),
+            false => None,
+        };
+        let Some(first) = first else {
+            self.out_of_range += 1;
+            return Vec::new();
+        };
+        let last: i64 = last as i64;
+        for index in first..=last {
+            if self.next_open.is_some_and(|next_open| index < next_open) {
+                continue;
+            }
+            let start: f64 = self.window_start(index);
+            self.windows.entry(index)
+                .or_insert_with(|| WindowSummary::new(start, start + self.size))
+                .record(message);
+        }
+        if self.next_open.is_some_and(|next_open| last < next_open) {
+            self.late += 1;
+        }
+        self.close_before(time - self.allowed_lateness)
+    }
+
+    /// Emits every window that ends at or before the provided time.
+    fn close_before(&mut self, watermark: f64) -> Vec<WindowSummary> {
+        let mut closed: Vec<WindowSummary> = Vec::new();
+        while let Some(entry) = self.windows.first_entry() {
+            if entry.get().end > watermark {
+                break;
+            }
+            let index: i64 = *entry.key();
+            closed.push(entry.remove());
+            self.next_open = Some(index + 1);
+        }
+        closed
+    }
+
+    /// Emits every window still open, regardless of whether it is complete.
+    pub fn flush(&mut self) -> Vec<WindowSummary> {
+        self.close_before(f64::INFINITY)
+    }
+}
@@ -1,7 +1,8 @@
+#![cfg(all(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
 mod common;
 
 use std::error::Error;
-use acars_vdlm2_parser::acars::{AcarsMessage, NewAcarsMessage};
+use acars_vdlm2_parser::acars::{AcarsMessage, AssemblyStatus, LevelType, NewAcarsMessage};
 use crate::common::{combine_files_of_message_type, compare_errors, load_files_of_message_type, MessageType, process_file_as_acars};
 
 /// This test will ingest contents from the acars sample files as a message per line to a `Vec<String>`.
@@ -22,7 +23,7 @@ fn test_acars_parsing() -> Result<(), Box<dyn Error>> {
                 }
             }
             for message in valid_acars_messages {
-                assert!(message.to_string().as_ref().err().is_none());
+                assert!(message.to_json().as_ref().err().is_none());
                 assert!(message.to_bytes().as_ref().err().is_none());
             }
             for line in failed_decodes {
@@ -49,4 +50,59 @@ fn show_acars_ingest() -> Result<(), Box<dyn Error>> {
             Ok(())
         }
     }
-}
\ No newline at end of file
+}
+/// This test decodes levels written with trailing zeros, which serde_json's `arbitrary_precision` feature
+/// does not treat as a plain `f64`. Run it with `--features arbitrary_precision` to cover that case.
+#[test]
+fn test_acars_level_precision() -> Result<(), Box<dyn Error>> {
+    let whole: AcarsMessage = r#"{"freq":131.55,"channel":2,"level":-31}"#.to_acars()?;
+    let decimal: AcarsMessage = r#"{"freq":131.550000,"channel":2,"level":-31.500000}"#.to_acars()?;
+    assert_eq!(whole.level, Some(LevelType::I32(-31)));
+    assert_eq!(decimal.level, Some(LevelType::Float64(-31.5)));
+    assert_eq!(decimal.freq.as_mhz(), 131.55);
+    Ok(())
+}
+
+/// This test decodes acarsdec output with error counts outside a `u8` and levels written in each of the forms seen
+/// in captures, taken from real acarsdec output with the message text removed.
+/// It validates that they decode, that the canonical accessors agree, and that they serialise back unchanged.
+#[test]
+fn test_acars_error_and_level_tolerance() -> Result<(), Box<dyn Error>> {
+    let captures: [(&str, Option<i32>, Option<f64>); 5] = [
+        (r#"{"timestamp":1663644637.171504,"station_id":"CS-KABQ-ACARS","channel":2,"freq":130.45,"level":-30,"error":-1,"mode":"2","label":"_d","block_id":"7","ack":false,"tail":".N8560Z","msgno":"S71A"}"#, Some(-1), Some(-30.0)),
+        (r#"{"timestamp":1663644637.171504,"station_id":"CS-KABQ-ACARS","channel":2,"freq":130.45,"level":-30.4,"error":300,"mode":"2","label":"_d","block_id":"7","ack":false,"tail":".N8560Z","msgno":"S71A"}"#, Some(300), Some(-30.4)),
+        (r#"{"timestamp":1663644637.171504,"station_id":"CS-KABQ-ACARS","channel":2,"freq":130.45,"level":"-31.5","error":"2","mode":"2","label":"_d","block_id":"7","ack":false,"tail":".N8560Z","msgno":"S71A"}"#, Some(2), Some(-31.5)),
+        (r#"{"timestamp":1663644637.171504,"station_id":"CS-KABQ-ACARS","channel":2,"freq":130.45,"level":"-31","error":0,"mode":"2","label":"_d","block_id":"7","ack":false,"tail":".N8560Z","msgno":"S71A"}"#, Some(0), Some(-31.0)),
+        (r#"{"timestamp":1663644637.171504,"station_id":"CS-KABQ-ACARS","channel":2,"freq":130.45,"mode":"2","label":"_d","block_id":"7","ack":false,"tail":".N8560Z","msgno":"S71A"}"#, None, None),
+    ];
+    for (capture, error_count, level_dbm) in captures {
+        let message: AcarsMessage = capture.to_acars()?;
+        assert_eq!(message.error_count(), error_count);
+        assert_eq!(message.level_dbm(), level_dbm);
+        let read_back: AcarsMessage = message.to_json()?.to_acars()?;
+        assert_eq!(read_back.error_count(), error_count);
+        assert_eq!(read_back.level_dbm(), level_dbm);
+    }
+    Ok(())
+}
+
+/// This test reads the `assstat` reassembly status from every acars sample file.
+/// It validates that every status in the samples is one of the known states, and that each serialises back unchanged.
+#[test]
+fn test_acars_assembly_status() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsMessage> = combine_files_of_message_type(MessageType::Acars)?
+        .iter()
+        .filter_map(|line| line.to_acars().ok())
+        .collect();
+    let statuses: Vec<&AssemblyStatus> = messages.iter().filter_map(|message| message.get_assembly_status()).collect();
+    assert!(!statuses.is_empty());
+    for status in statuses {
+        assert!(!matches!(status, AssemblyStatus::Other(_)), "Unexpected assembly status {}", status);
+        assert_eq!(&AssemblyStatus::from(status.as_str()), status);
+        assert_eq!(serde_json::to_string(status)?, format!("\"{}\"", status));
+    }
+    let legacy: AcarsMessage = r#"{"freq":131.55,"channel":2,"assstat":"out of sequence","is_response":1,"is_onground":0}"#.to_acars()?;
+    assert_eq!(legacy.get_assembly_status(), Some(&AssemblyStatus::OutOfSequence));
+    assert_eq!((legacy.is_response(), legacy.is_on_ground()), (Some(true), Some(false)));
+    Ok(())
+}
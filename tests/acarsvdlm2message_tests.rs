@@ -1,8 +1,60 @@
+#![cfg(all(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat", feature = "adsb-json"))]
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
-use rand::prelude::{SliceRandom, ThreadRng};
-use rand::thread_rng;
-use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
-use crate::common::{combine_files_of_message_type, compare_errors, MessageType, SerialisationTarget, test_enum_serialisation};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use serde_json::Value;
+use acars_vdlm2_parser::{decode_bytes, decode_direct, AcarsVdlm2Message, AppDetails, DecodeMessage, ExpectedMessageType, MessageFamily};
+use acars_vdlm2_parser::acars::{AckType, AcarsMessage, LevelType, NewAcarsMessage};
+use acars_vdlm2_parser::adsb_json::AdsbJsonMessage;
+use acars_vdlm2_parser::hfdl::{HfdlMessage, NewHfdlMessage};
+use acars_vdlm2_parser::uat::{NewUatMessage, UatMessage};
+use acars_vdlm2_parser::vdlm2::{NewVdlm2Message, Vdlm2Message};
+use acars_vdlm2_parser::app_template::AppDetailsTemplate;
+use acars_vdlm2_parser::context::{ProcessingContext, ProcessingStage, ProcessingWarning};
+use acars_vdlm2_parser::conformance::{check_corpus, check_directory, ConformanceReport, ConformanceStage};
+use acars_vdlm2_parser::convert::AcarsConversion;
+use acars_vdlm2_parser::corpus::{Corpus, CorpusReport, TagFilter};
+use acars_vdlm2_parser::correlate::{AckCorrelator, AckPair, PendingMessage};
+use acars_vdlm2_parser::csv_legacy::parse_csv_legacy;
+use acars_vdlm2_parser::coverage::{CoverageBin, CoverageBinning, CoverageCell, CoverageHeatmap};
+use acars_vdlm2_parser::explain::Explanation;
+use acars_vdlm2_parser::error_code::{error_code, ErrorCode};
+use acars_vdlm2_parser::equality::FieldClass;
+use acars_vdlm2_parser::envelope::{decode_enveloped, decode_enveloped_bytes, Envelope, OutputMode, ENVELOPE_VERSION};
+use acars_vdlm2_parser::extract::{duplicate_keys, extract_fields};
+use acars_vdlm2_parser::field_stats::{FieldCounts, FieldStatistics};
+use acars_vdlm2_parser::frequency::{Frequency, FrequencyBand};
+use acars_vdlm2_parser::history::{AircraftKey, HistoryBuffer};
+use acars_vdlm2_parser::ingest::{IngestMeta, TaggedMessage};
+use acars_vdlm2_parser::provenance::{Provenance, ProvenanceStep};
+use acars_vdlm2_parser::provider::{FixedClock, Providers};
+use acars_vdlm2_parser::rate::{Rate, RateSnapshot, RateTracker};
+use acars_vdlm2_parser::labels::label_description;
+use acars_vdlm2_parser::normalise::TextNormaliser;
+use acars_vdlm2_parser::pool::{DecodePool, DecodePoolConfig, DecodedInput, OutputOrder};
+use acars_vdlm2_parser::registrations::{RegistrationMapping, RegistrationTable};
+use acars_vdlm2_parser::required::{RequiredField, RequiredFields};
+use acars_vdlm2_parser::redact::{AircraftAnonymiser, StationRedactor};
+use acars_vdlm2_parser::roundtrip::{roundtrip_check, DifferenceKind, FieldDifference, RoundtripReport};
+use acars_vdlm2_parser::sampler::{SampleKey, Sampler};
+use acars_vdlm2_parser::sniff::{sniff, sniff_format, WireFormat};
+use acars_vdlm2_parser::source::{SourceFilter, SourceKind};
+use acars_vdlm2_parser::stream::{JsonSplitter, StreamDecoder};
+use acars_vdlm2_parser::station_policy::{wildcard_match, StationPolicy};
+use acars_vdlm2_parser::time_index::{TimeIndex, TimeIndexEntry, TimeIndexedReader};
+use acars_vdlm2_parser::timestamp::{MessageTimestamp, TimestampPrecision, TimestampSource};
+use acars_vdlm2_parser::visit::{AcarsBlock, MessageVisitor, PositionReport, PositionSource, SignalInfo};
+use acars_vdlm2_parser::merge::{merge_streams, MergedStream, DEFAULT_REORDER_WINDOW_SECS};
+use acars_vdlm2_parser::ndjson::{NdjsonWriter, WriteStatus};
+use acars_vdlm2_parser::summary::{MessageSummary, SNIPPET_CHARS};
+use acars_vdlm2_parser::testing::{assert_corpus_roundtrip, assert_golden_messages, SeededRng};
+use acars_vdlm2_parser::transform::{BatchReport, Transform, TransformOutcome, TransformPipeline};
+use acars_vdlm2_parser::validate::{RejectReason, ValidationHooks, ValidationStats};
+use acars_vdlm2_parser::window::{WindowSummary, Windower};
+use acars_vdlm2_parser::dead_letter::{decode_batch, decode_batch_validated, DeadLetterWriter, FailedDecode};
+use crate::common::{combine_files_of_message_type, compare_errors, read_test_file, MessageType, SerialisationTarget, test_enum_serialisation};
 
 mod common;
 
@@ -17,10 +69,10 @@ fn test_determining_message() -> Result<(), Box<dyn Error>> {
     match combine_files_of_message_type(MessageType::All) {
         Err(load_error) => Err(load_error),
         Ok(mut all_messages) => {
-            let mut rng: ThreadRng = thread_rng();
+            let mut rng: SeededRng = SeededRng::from_env();
             let mut successfully_decoded_items: Vec<AcarsVdlm2Message> = Vec::new();
             let mut failed_decodes: Vec<String> = Vec::new();
-            all_messages.shuffle(&mut rng);
+            rng.shuffle(&mut all_messages);
             for entry in all_messages {
                 match entry.decode_message() {
                     Err(_) =>
@@ -29,7 +81,7 @@ fn test_determining_message() -> Result<(), Box<dyn Error>> {
                         successfully_decoded_items.push(decoded_message),
                 }
             }
-            successfully_decoded_items.shuffle(&mut rng);
+            rng.shuffle(&mut successfully_decoded_items);
             for message in successfully_decoded_items {
                 test_enum_serialisation(&message, SerialisationTarget::Both);
             }
@@ -39,4 +91,1962 @@ fn test_determining_message() -> Result<(), Box<dyn Error>> {
             Ok(())
         }
     }
-}
\ No newline at end of file
+}
+/// This test ingests the contents of all the sample files and decodes them as `AcarsVdlm2Message`.
+/// It validates that the direct byte serialisation paths produce exactly the same output as going via `String`.
+#[test]
+fn test_byte_serialisation_matches_string() -> Result<(), Box<dyn Error>> {
+    match combine_files_of_message_type(MessageType::All) {
+        Err(load_error) => Err(load_error),
+        Ok(all_messages) => {
+            for entry in all_messages {
+                if let Ok(message) = entry.decode_message() {
+                    let string_output: String = message.to_json()?;
+                    let mut writer_output: Vec<u8> = Vec::new();
+                    message.write_to(&mut writer_output)?;
+                    assert_eq!(message.to_bytes()?, string_output.as_bytes());
+                    assert_eq!(writer_output, string_output.as_bytes());
+                    let mut writer_newline_output: Vec<u8> = Vec::new();
+                    message.write_to_newline(&mut writer_newline_output)?;
+                    assert_eq!(message.to_bytes_newline()?, message.to_string_newline()?.as_bytes());
+                    assert_eq!(writer_newline_output, message.to_string_newline()?.as_bytes());
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+
+/// This test runs the sample corpus through `decode_batch` and writes every failure to a `DeadLetterWriter`.
+/// It validates that each dead-letter line reads back as the same `FailedDecode` with its raw input intact, and that
+/// only the family each input was identified as is recorded as attempted.
+#[test]
+fn test_dead_letter_output() -> Result<(), Box<dyn Error>> {
+    let all_messages: Vec<String> = combine_files_of_message_type(MessageType::All)?;
+    let results: Vec<Result<AcarsVdlm2Message, FailedDecode>> = decode_batch(&all_messages, Some("test_files"));
+    assert_eq!(results.len(), all_messages.len());
+    let mut dead_letters: DeadLetterWriter<Vec<u8>> = DeadLetterWriter::new(Vec::new());
+    let mut failures: Vec<FailedDecode> = Vec::new();
+    for (result, line) in results.into_iter().zip(all_messages.iter()) {
+        if let Err(failed) = result {
+            assert_eq!(&failed.raw, line);
+            let expected: Vec<MessageFamily> = match serde_json::from_str::<Value>(line) {
+                Ok(value) if value.is_object() => vec![MessageFamily::detect(&value)],
+                _ => sniff(line.as_bytes()).into_iter().collect(),
+            };
+            assert_eq!(failed.attempted_families, expected, "{}", line);
+            let code: ErrorCode = failed.code.expect("new failures have a code");
+            assert_eq!(failed.to_string(), format!("[{}] {}", code.as_str(), failed.error));
+            dead_letters.write(&failed)?;
+            failures.push(failed);
+        }
+    }
+    assert_eq!(dead_letters.written(), failures.len());
+    let output: String = String::from_utf8(dead_letters.into_inner())?;
+    for (line, failed) in output.lines().zip(failures.iter()) {
+        let read_back: FailedDecode = serde_json::from_str(line)?;
+        assert_eq!(read_back.raw, failed.raw);
+        assert_eq!(read_back.error, failed.error);
+        assert_eq!(read_back.code, failed.code);
+        assert_eq!(read_back.source, failed.source);
+        assert!((read_back.timestamp - failed.timestamp).abs() < 0.001);
+    }
+    Ok(())
+}
+
+
+/// This test sorts the decoded sample corpus by timestamp and runs it through a fixed `Windower`.
+/// It validates that every timed message is counted in exactly one window, that windows are emitted in order, and that
+/// window sizes and steps which are not finite and positive, or overlap too many windows, are refused, as is an allowed
+/// lateness which is not finite or is negative, and that a timestamp too large to number its windows is counted and skipped.
+#[test]
+fn test_fixed_windowing() -> Result<(), Box<dyn Error>> {
+    let mut messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    messages.sort_by(|first, second| first.get_time().partial_cmp(&second.get_time()).unwrap());
+    let timed: u64 = messages.iter().filter(|message| message.get_time().is_some()).count() as u64;
+    let mut windower: Windower = Windower::fixed(300.0).ok_or("invalid window size")?;
+    let mut summaries: Vec<WindowSummary> = Vec::new();
+    for message in &messages {
+        summaries.append(&mut windower.push(message));
+    }
+    summaries.append(&mut windower.flush());
+    assert_eq!(summaries.iter().map(|summary| summary.total).sum::<u64>(), timed);
+    assert_eq!(windower.late(), 0);
+    assert!(summaries.windows(2).all(|pair| pair[0].end <= pair[1].start));
+    for (size, step) in [(0.0, 0.0), (-300.0, 60.0), (300.0, -60.0), (f64::NAN, 60.0), (300.0, f64::INFINITY), (300.0, 1e-9)] {
+        assert!(Windower::sliding(size, step).is_none(), "{} {}", size, step);
+    }
+    assert!(windower.set_allowed_lateness(60.0));
+    for lateness in [-1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(!windower.set_allowed_lateness(lateness), "{}", lateness);
+    }
+    let distant: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"timestamp":1e300}"#.decode_message()?;
+    assert!(windower.push(&distant).is_empty());
+    assert_eq!(windower.out_of_range(), 1);
+    Ok(())
+}
+
+/// This test looks up the ICAO allocation for every aircraft address in the decoded sample corpus.
+/// It validates that addresses are found for every message family and that nearly all of them fall inside an allocated block.
+#[test]
+fn test_icao_allocations() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    for family in MessageFamily::ALL {
+        assert!(messages.iter().any(|message| message.get_family() == family && message.get_icao().is_some()),
+                "No ICAO addresses found for {}", family);
+    }
+    let checked: Vec<bool> = messages.iter().filter_map(|message| message.icao_is_allocated()).collect();
+    let allocated: usize = checked.iter().filter(|allocated| **allocated).count();
+    println!("{} of {} ICAO addresses are allocated", allocated, checked.len());
+    assert!(allocated * 100 >= checked.len() * 95);
+    Ok(())
+}
+
+/// This test decodes each family's sample files through `AcarsVdlm2Message`.
+/// It validates that every message is identified as the family of the file it came from,
+/// and that decode errors name the family that was attempted rather than a generic untagged enum error.
+#[test]
+fn test_family_detection() -> Result<(), Box<dyn Error>> {
+    for (message_type, family) in [(MessageType::Acars, MessageFamily::Acars), (MessageType::Vdlm2, MessageFamily::Vdlm2), (MessageType::Hfdl, MessageFamily::Hfdl), (MessageType::Uat, MessageFamily::Uat)] {
+        for line in combine_files_of_message_type(message_type)? {
+            match line.decode_message() {
+                Ok(message) => assert_eq!(message.get_family(), family, "{} was not decoded as {}", line, family),
+                Err(error) if error.is_data() => assert!(error.to_string().starts_with(&format!("invalid {} message", family)), "{}", error),
+                Err(_) => {}
+            }
+        }
+    }
+    let error: serde_json::Error = "[1, 2, 3]".decode_message().unwrap_err();
+    assert_eq!(error.to_string(), "expected a JSON object, found an array");
+    Ok(())
+}
+
+/// This test quotes the top level numeric fields of every sample message, as some feeders do.
+/// It validates that the quoted messages decode and serialise back identically to the unquoted originals.
+#[test]
+fn test_quoted_numbers() -> Result<(), Box<dyn Error>> {
+    fn quote_numbers(object: &mut serde_json::Map<String, Value>) {
+        for value in object.values_mut() {
+            if value.is_number() {
+                *value = Value::String(value.to_string());
+            }
+        }
+    }
+    let mut quoted_messages: usize = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(original) = line.decode_message() else { continue };
+        let mut value: Value = serde_json::from_str(&line)?;
+        match value.as_object_mut() {
+            Some(object) if object.contains_key("vdl2") || object.contains_key("hfdl") => {
+                for body in object.values_mut().filter_map(|body| body.as_object_mut()) {
+                    quote_numbers(body);
+                }
+            }
+            Some(object) => quote_numbers(object),
+            None => continue
+        }
+        let quoted: AcarsVdlm2Message = value.to_string().decode_message()?;
+        assert_eq!(quoted.to_json()?, original.to_json()?);
+        quoted_messages += 1;
+    }
+    assert!(quoted_messages > 0);
+    Ok(())
+}
+
+/// This test runs the decoded sample corpus through a per second `Sampler` keyed by station.
+/// It validates that no station has more than the limit forwarded in any one second of message time.
+#[test]
+fn test_sampler_max_per_second() -> Result<(), Box<dyn Error>> {
+    let mut messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .filter(|message| message.get_time().is_some())
+        .collect();
+    messages.sort_by(|first, second| first.get_time().partial_cmp(&second.get_time()).unwrap());
+    let mut pipeline: TransformPipeline = TransformPipeline::new();
+    pipeline.add(Sampler::max_per_second(2, SampleKey::Station));
+    let mut forwarded: HashMap<(Option<String>, i64), u64> = HashMap::new();
+    let mut dropped: u64 = 0;
+    for mut message in messages {
+        let time: f64 = message.get_time().unwrap_or_default();
+        match pipeline.apply(&mut message) {
+            TransformOutcome::Forward => {
+                *forwarded.entry((message.get_station_name().map(|station| station.to_string()), time.floor() as i64)).or_default() += 1
+            }
+            TransformOutcome::Drop(_) => dropped += 1,
+        }
+    }
+    assert!(dropped > 0);
+    assert!(forwarded.values().all(|count| *count <= 2));
+    Ok(())
+}
+
+/// This test samples a stream of messages from many aircraft, each heard only a few times, per ICAO address.
+/// It validates that 1 in N counts are dropped once they complete a cycle, that per second counts are dropped once a
+/// newer second starts, and that the number of keys counted never passes `max_keys`, while sampling still passes the
+/// expected messages.
+#[test]
+fn test_sampler_bounded_state() -> Result<(), Box<dyn Error>> {
+    let message = |icao: u32, second: u32| -> Result<AcarsVdlm2Message, Box<dyn Error>> {
+        let line: String = format!(r#"{{"freq":131.55,"channel":2,"timestamp":{}.5,"icao":{},"label":"H1"}}"#, 1700000000 + second, icao);
+        Ok(line.decode_message()?)
+    };
+    let one_in: Sampler = Sampler::one_in(2, SampleKey::Icao);
+    let mut passed: Vec<bool> = Vec::new();
+    for _ in 0..4 {
+        passed.push(one_in.sample(&message(0xA00001, 0)?));
+    }
+    assert_eq!(passed, vec![true, false, true, false]);
+    assert_eq!(one_in.tracked_keys(), 0);
+    let per_second: Sampler = Sampler::max_per_second(1, SampleKey::Icao);
+    for second in 0..50 {
+        for icao in 0..100 {
+            assert!(per_second.sample(&message(0xA00000 + second * 100 + icao, second)?));
+        }
+        assert_eq!(per_second.tracked_keys(), 100);
+    }
+    assert!(!per_second.sample(&message(0xA00000 + 49 * 100, 49)?));
+    let capped: Sampler = Sampler::one_in(3, SampleKey::Icao).max_keys(64);
+    for icao in 0..1000 {
+        assert!(capped.sample(&message(0xA00000 + icao, 0)?));
+        assert!(capped.tracked_keys() <= 64);
+    }
+    Ok(())
+}
+
+/// This test wraps every decoded sample message in a `TaggedMessage` and reads it back.
+/// It validates that the `IngestMeta` survives the round trip and that stripping it gives back the original JSON.
+#[test]
+fn test_ingest_meta_round_trip() -> Result<(), Box<dyn Error>> {
+    let mut ingest_meta: IngestMeta = IngestMeta::new("dongle-2");
+    ingest_meta.frequency = Some(136_975_000);
+    ingest_meta.site_lat = Some(-31.9);
+    ingest_meta.site_lon = Some(116.0);
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else { continue };
+        let original: String = message.to_json()?;
+        let tagged: TaggedMessage = TaggedMessage::new(message, ingest_meta.clone());
+        let read_back: TaggedMessage = serde_json::from_str(&tagged.to_json()?)?;
+        assert_eq!(read_back.ingest_meta.as_ref(), Some(&ingest_meta));
+        assert_eq!(read_back.strip().to_json()?, original);
+    }
+    Ok(())
+}
+
+/// This test runs the time sorted sample corpus through an `AckCorrelator`.
+/// It validates that acknowledgements are paired and every block seen is either paired or reported as unacknowledged.
+#[test]
+fn test_ack_correlation() -> Result<(), Box<dyn Error>> {
+    let mut messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .filter(|message| message.get_time().is_some())
+        .collect();
+    messages.sort_by(|first, second| first.get_time().partial_cmp(&second.get_time()).unwrap());
+    let mut correlator: AckCorrelator = AckCorrelator::new(60.0);
+    let mut pairs: Vec<AckPair> = Vec::new();
+    for message in &messages {
+        pairs.extend(correlator.push(message));
+    }
+    let unacknowledged: Vec<PendingMessage> = correlator.flush();
+    assert!(!pairs.is_empty());
+    assert!(!unacknowledged.is_empty());
+    assert_eq!(correlator.pending_len(), 0);
+    for pair in &pairs {
+        assert!(pair.latency_secs >= 0.0);
+        assert_eq!(pair.ack_time - pair.message.time, pair.latency_secs);
+    }
+    Ok(())
+}
+
+/// This test runs the decoded sample corpus through a `TextNormaliser` that escapes non printable characters.
+/// It validates that no control characters other than `\n` and `\t` are left in the text of any message.
+#[test]
+fn test_text_normalisation() -> Result<(), Box<dyn Error>> {
+    let normaliser: TextNormaliser = TextNormaliser::new().escape_non_printable(true);
+    let mut changed: u64 = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(mut message) = line.decode_message() else { continue };
+        let original: Option<String> = message.get_text().map(|text| text.to_string());
+        normaliser.apply_to(&mut message);
+        if let Some(text) = message.get_text() {
+            assert!(text.chars().all(|character| !character.is_control() || character == '\n' || character == '\t'));
+            if original.as_deref() != Some(text) {
+                changed += 1;
+            }
+        }
+    }
+    assert!(changed > 0);
+    Ok(())
+}
+
+/// This test applies an `AppDetailsTemplate` to every decoded sample message.
+/// It validates that the original decoder name and version are kept and the proxy details are added.
+#[test]
+fn test_app_details_template() -> Result<(), Box<dyn Error>> {
+    let template: AppDetailsTemplate = AppDetailsTemplate::new("acars_router", "1.2.3").with_fallback_app("unknown", "0");
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(mut message) = line.decode_message() else { continue };
+        let original: Option<AppDetails> = message.get_app_details().cloned();
+        template.clone().apply_to(&mut message);
+        let applied: &AppDetails = message.get_app_details().unwrap();
+        match original {
+            Some(original) if !original.name.is_empty() => assert_eq!((&applied.name, &applied.ver), (&original.name, &original.ver)),
+            _ => assert_eq!(applied.name, "unknown"),
+        }
+        assert_eq!(applied.proxied, Some(true));
+        assert!(applied.proxied_by.as_deref().is_some_and(|proxied_by| proxied_by.ends_with("acars_router")));
+        assert_eq!(applied.acars_router_uuid, template.details().acars_router_uuid);
+    }
+    Ok(())
+}
+
+/// This test registers the sample files with a `Corpus`, tagged by protocol.
+/// It validates that every sample line round trips apart from the truncated lines in the third acars and vdlm2 files,
+/// and that the tag filters select the expected files.
+#[test]
+fn test_corpus_roundtrip() -> Result<(), Box<dyn Error>> {
+    let mut corpus: Corpus = Corpus::new();
+    corpus.register_dir("test_files", "acars", &["acarsdec", "acars"])?;
+    corpus.register_dir("test_files", "vdlm2", &["dumpvdl2", "vdlm2"])?;
+    corpus.register_dir("test_files", "hfdl", &["dumphfdl", "hfdl"])?;
+    corpus.register_dir("test_files", "uat", &["dump978", "uat"])?;
+    let report: CorpusReport = corpus.check_roundtrip(&TagFilter::any())?;
+    assert_eq!(report.lines, combine_files_of_message_type(MessageType::All)?.len());
+    assert_eq!(report.decoded + report.failures.len(), report.lines);
+    assert_eq!(report.failures.len(), 4);
+    for failure in &report.failures {
+        assert!(failure.path.ends_with("acars_3") || failure.path.ends_with("vdlm2_3"));
+        assert!(failure.reason.starts_with("decode failed"));
+    }
+    assert_corpus_roundtrip(&corpus, &TagFilter::any().require("hfdl"));
+    assert_eq!(corpus.lines(&TagFilter::any().require("vdlm2"))?, combine_files_of_message_type(MessageType::Vdlm2)?);
+    assert_eq!(corpus.files(&TagFilter::any().exclude("hfdl")).count(), 13);
+    assert_eq!(corpus.lines(&TagFilter::any().require("uat"))?, combine_files_of_message_type(MessageType::Uat)?);
+    Ok(())
+}
+
+/// This test registers a vendored capture directory laid out differently from `test_files`, one family at a time by
+/// file name pattern, and builds a corpus of `test_files` from its layout.
+/// It validates that each family only reads the files registered for it, that files matching no pattern are left out,
+/// and that the sample layout reads the same lines as the glob based helpers did.
+#[test]
+fn test_corpus_registration() -> Result<(), Box<dyn Error>> {
+    let vendored: std::path::PathBuf = std::env::temp_dir().join(format!("acars_corpus_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&vendored)?;
+    let acars: Vec<String> = combine_files_of_message_type(MessageType::Acars)?.into_iter().take(5).collect();
+    let vdlm2: Vec<String> = combine_files_of_message_type(MessageType::Vdlm2)?.into_iter().take(3).collect();
+    std::fs::write(vendored.join("site-a.acarsdec.log"), acars.join("\n"))?;
+    std::fs::write(vendored.join("site-a.dumpvdl2.log"), vdlm2.join("\n"))?;
+    std::fs::write(vendored.join("README.md"), "# Captures\n")?;
+
+    let mut corpus: Corpus = Corpus::new();
+    let registered: (usize, usize) = (corpus.register_family(MessageFamily::Acars, &vendored, "*.ACARSDEC.log")?,
+        corpus.register_family(MessageFamily::Vdlm2, &vendored, "*.dumpvdl2.*")?);
+    let lines: [Vec<String>; 4] = [corpus.family_lines(Some(MessageFamily::Acars))?, corpus.family_lines(Some(MessageFamily::Vdlm2))?,
+        corpus.family_lines(Some(MessageFamily::Hfdl))?, corpus.family_lines(None)?];
+    std::fs::remove_dir_all(&vendored)?;
+    assert_eq!(registered, (1, 1));
+    assert_eq!(lines[0], acars);
+    assert_eq!(lines[1], vdlm2);
+    assert!(lines[2].is_empty());
+    assert_eq!(lines[3].len(), 8);
+
+    let samples: Corpus = Corpus::from_sample_dir("test_files")?;
+    assert_eq!(samples.files(&TagFilter::any()).count(), std::fs::read_dir("test_files")?.count());
+    assert_eq!(samples.family_lines(Some(MessageFamily::Hfdl))?.len(), samples.lines(&TagFilter::any().require("hfdl"))?.len());
+    assert_eq!(samples.files(&TagFilter::any().require("vdlm2")).count(), 9);
+    Ok(())
+}
+
+/// This test reads the frequency of every decoded sample message.
+/// It validates that acars and vdlm2 messages are in the VHF band, hfdl messages in the HF band and uat messages in the
+/// UHF band, whether the decoder wrote the frequency in MHz or Hz.
+#[test]
+fn test_frequency_bands() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else { continue };
+        let expected: FrequencyBand = match message.get_family() {
+            MessageFamily::Hfdl => FrequencyBand::Hf,
+            MessageFamily::Uat => FrequencyBand::Uhf,
+            _ => FrequencyBand::Vhf,
+        };
+        assert_eq!(message.get_frequency().band(), expected, "{}", line);
+    }
+    Ok(())
+}
+
+/// This test explains every decoded sample message.
+/// It validates that every explanation has the family and frequency and one line per field,
+/// and that labels from the dictionary are annotated.
+#[test]
+fn test_explain() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else { continue };
+        let explanation: Explanation = message.explain();
+        assert_eq!(explanation.get("family").map(|line| line.value.clone()), Some(message.get_family().to_string()));
+        assert!(explanation.get("frequency").is_some_and(|line| line.meaning.is_some()));
+        assert_eq!(explanation.to_string().lines().count(), explanation.lines.len());
+        if let Some(label) = message.get_label().filter(|label| *label == "_d") {
+            assert_eq!(explanation.get("label").and_then(|line| line.meaning.as_deref()), label_description(label));
+        }
+    }
+    Ok(())
+}
+
+/// This test runs the decoded sample corpus through a `StationPolicy` denying one of the sample stations.
+/// It validates that only that station is dropped and that the per rule counter matches the messages dropped.
+#[test]
+fn test_station_policy() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let denied_station: String = messages.iter()
+        .find_map(|message| message.get_station_name())
+        .map(|station| station.to_string())
+        .unwrap();
+    let pattern: String = format!("{}*", &denied_station[..denied_station.len() - 1].to_lowercase());
+    let mut pipeline: TransformPipeline = TransformPipeline::new();
+    pipeline.add(StationPolicy::new().deny(&pattern));
+    let mut dropped: u64 = 0;
+    for mut message in messages.clone() {
+        let matches: bool = message.get_station_name().is_some_and(|station| wildcard_match(&pattern, station));
+        match pipeline.apply(&mut message) {
+            TransformOutcome::Forward => assert!(!matches),
+            TransformOutcome::Drop(_) => {
+                assert!(matches);
+                dropped += 1;
+            }
+        }
+    }
+    assert!(dropped > 0);
+    let policy: StationPolicy = StationPolicy::new().deny(&pattern);
+    messages.iter().for_each(|message| { let _ = policy.check(message); });
+    assert_eq!(policy.stats().denied.get(&pattern), Some(&dropped));
+    Ok(())
+}
+
+/// This test decodes every line of the sample corpus from bytes, both with detection and as the family it was
+/// detected as, and compares the result against decoding the same line as a `str`.
+/// It validates that decoding from bytes gives the same messages and the same errors as decoding from a `str`.
+#[test]
+fn test_decode_from_bytes() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let from_str: Result<AcarsVdlm2Message, serde_json::Error> = line.decode_message();
+        let from_bytes: Result<AcarsVdlm2Message, serde_json::Error> = line.as_bytes().decode_message();
+        match (&from_str, &from_bytes) {
+            (Ok(expected), Ok(decoded)) => assert_eq!(expected.to_json()?, decoded.to_json()?),
+            (Err(expected), Err(error)) => assert_eq!(expected.to_string(), error.to_string()),
+            _ => panic!("Decoding from bytes and from a str disagreed for {}", line),
+        }
+        if let Ok(expected) = from_str {
+            let expected_type: ExpectedMessageType = expected.get_family().into();
+            let decoded: AcarsVdlm2Message = decode_bytes(line.as_bytes(), expected_type)?;
+            assert_eq!(decoded.get_family(), expected.get_family());
+            assert_eq!(decoded.to_json()?, expected.to_json()?);
+        }
+    }
+    Ok(())
+}
+
+/// This test runs every line of the sample corpus through `roundtrip_check`, with and without an extra field added.
+/// It validates that the extra field is always reported as lost, and that re-encoded output checks as lossless.
+#[test]
+fn test_roundtrip_check() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let report: RoundtripReport = roundtrip_check(&line);
+        let Ok(message) = line.decode_message() else {
+            assert!(report.error.is_some() && report.family.is_none());
+            continue;
+        };
+        assert_eq!(report.family, Some(message.get_family()));
+        let reencoded: String = message.to_json()?;
+        assert!(roundtrip_check(&reencoded).is_lossless(), "Re-encoded output was not lossless: {}", reencoded);
+        let mut injected: Value = serde_json::from_str(&line)?;
+        injected["injected_field"] = Value::from(1);
+        let report: RoundtripReport = roundtrip_check(&injected.to_string());
+        let lost: Vec<&FieldDifference> = report.of_kind(DifferenceKind::Lost).collect();
+        assert!(lost.iter().any(|difference| difference.path == "injected_field" && difference.original == Some(Value::from(1))));
+    }
+    Ok(())
+}
+
+/// This test sniffs every line of the sample corpus, both as written and pretty printed.
+/// It validates that the sniffed family matches `MessageFamily::detect` on the parsed line and the format is identified.
+#[test]
+fn test_sniff() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(value) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let expected: Option<MessageFamily> = Some(MessageFamily::detect(&value));
+        let pretty: String = serde_json::to_string_pretty(&value)?;
+        assert_eq!(sniff(line.as_bytes()), expected, "Sniffed the wrong family for {}", line);
+        assert_eq!(sniff(pretty.as_bytes()), expected);
+        assert_eq!(sniff_format(line.as_bytes()), WireFormat::Json);
+        assert_eq!(sniff_format(pretty.as_bytes()), WireFormat::PrettyJson);
+    }
+    Ok(())
+}
+
+/// This test processes the sample corpus through a `ProcessingContext`, then decodes a message with quoted numbers,
+/// invalid UTF-8 and a timestamp far in the future.
+/// It validates that the corpus matches a plain decode and that each soft issue is reported as a warning.
+#[test]
+fn test_processing_context() -> Result<(), Box<dyn Error>> {
+    let mut context: ProcessingContext = ProcessingContext::new().report_unknown_fields(true).max_future_secs(60.0);
+    let mut pipeline: TransformPipeline = TransformPipeline::new();
+    pipeline.add(TextNormaliser::new());
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(expected) = line.decode_message() else {
+            assert!(context.decode(&line).is_err());
+            continue;
+        };
+        let mut message: AcarsVdlm2Message = context.decode(&line)?;
+        assert_eq!(context.serialise(&message)?, expected.to_json()?);
+        assert_eq!(context.transform(&pipeline, &mut message), TransformOutcome::Forward);
+        let warnings: Vec<ProcessingWarning> = context.take_warnings();
+        assert!(warnings.iter().all(|warning| warning.stage == ProcessingStage::Decode));
+        assert!(warnings.iter().all(|warning| warning.reason == "unknown_field" || warning.reason == "quoted_number"));
+    }
+    let mut input: Vec<u8> = br#"{"freq":"131.55","channel":2,"timestamp":99999999999,"text":"bad "#.to_vec();
+    input.extend_from_slice(&[0xFF, b'"', b'}']);
+    let message: AcarsVdlm2Message = context.decode_bytes(&input)?;
+    assert_eq!(message.get_text(), Some("bad \u{FFFD}"));
+    let reasons: Vec<String> = context.take_warnings().into_iter().map(|warning| warning.reason).collect();
+    assert_eq!(reasons, vec!["repaired_utf8", "quoted_number", "future_timestamp"]);
+    Ok(())
+}
+
+/// This test reads the timestamp of every message in the sample corpus.
+/// It validates that the source matches the family, that `get_time()` matches the timestamp seconds,
+/// and that station times keep their microseconds.
+#[test]
+fn test_message_timestamps() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    for message in &messages {
+        let Some(timestamp) = message.get_timestamp() else {
+            assert!(message.get_time().is_none());
+            continue;
+        };
+        assert_eq!(message.get_time(), Some(timestamp.seconds));
+        let expected_source: TimestampSource = match message.get_family() {
+            MessageFamily::Acars => TimestampSource::Decoder,
+            MessageFamily::Vdlm2 | MessageFamily::Hfdl | MessageFamily::Uat => TimestampSource::Station,
+        };
+        assert_eq!(timestamp.source, expected_source);
+        let expected_precision: TimestampPrecision = match message.get_family() {
+            MessageFamily::Uat => TimestampPrecision::Milliseconds,
+            _ => TimestampPrecision::Microseconds,
+        };
+        assert_eq!(timestamp.precision, expected_precision);
+        if let Some(usec) = message.get_field("vdl2.t.usec").or_else(|| message.get_field("hfdl.t.usec")).and_then(|usec| usec.as_u64()) {
+            let fraction: f64 = timestamp.seconds - timestamp.seconds.floor();
+            assert!((fraction * 1_000_000.0 - usec as f64).abs() < 1.0, "Lost the microseconds of {:?}", timestamp);
+        }
+    }
+    assert!(MessageTimestamp::ingested_now().seconds > 1_700_000_000.0);
+    Ok(())
+}
+
+/// This test builds a `RegistrationTable` from the sample corpus and exports it as JSON and CSV.
+/// It validates that every message with both an address and a registration is counted, that the exports agree,
+/// and that a conflicting pairing lowers the confidence of the original one.
+#[test]
+fn test_registration_table() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let mut table: RegistrationTable = RegistrationTable::new();
+    let observed: u64 = messages.iter().filter(|message| table.observe(message)).count() as u64;
+    assert!(observed > 0);
+    let mappings: Vec<RegistrationMapping> = table.mappings();
+    assert_eq!(mappings.iter().map(|mapping| mapping.observations).sum::<u64>(), observed);
+    assert!(mappings.iter().all(|mapping| mapping.confidence > 0.0 && mapping.confidence < 1.0));
+    let mut json: Vec<u8> = Vec::new();
+    table.write_json(&mut json)?;
+    let exported: Vec<RegistrationMapping> = serde_json::from_slice(&json)?;
+    assert!(exported.iter().zip(&mappings).all(|(exported, mapping)| (&exported.icao, &exported.registration, exported.observations) == (&mapping.icao, &mapping.registration, mapping.observations)));
+    assert_eq!(exported.len(), mappings.len());
+    let mut csv: Vec<u8> = Vec::new();
+    table.write_csv(&mut csv)?;
+    assert_eq!(String::from_utf8(csv)?.lines().count(), mappings.len() + 1);
+    let first: &RegistrationMapping = &mappings[0];
+    let conflicting: AcarsVdlm2Message = format!(r#"{{"freq":131.55,"channel":2,"tail":"CONFLICT","icao":{}}}"#, u32::from_str_radix(&first.icao, 16)?).decode_message()?;
+    table.observe(&conflicting);
+    let updated: Vec<RegistrationMapping> = table.mappings();
+    let original: &RegistrationMapping = updated.iter().find(|mapping| mapping.icao == first.icao && mapping.registration == first.registration).unwrap();
+    assert!(original.confidence < first.confidence);
+    Ok(())
+}
+
+/// Counts what a `MessageVisitor` is called with, for `test_message_visitor`.
+#[derive(Default)]
+struct CountingVisitor {
+    signals: usize,
+    apps: usize,
+    acars_blocks: usize,
+    positions: HashMap<String, usize>,
+}
+
+impl MessageVisitor for CountingVisitor {
+    fn on_signal_info(&mut self, signal: &SignalInfo) {
+        assert!(signal.frequency.as_hz() > 0);
+        self.signals += 1;
+    }
+
+    fn on_app_details(&mut self, _app: &AppDetails) {
+        self.apps += 1;
+    }
+
+    fn on_acars_block(&mut self, _block: &AcarsBlock<'_>) {
+        self.acars_blocks += 1;
+    }
+
+    fn on_position(&mut self, position: &PositionReport) {
+        assert!((-90.0..=90.0).contains(&position.lat) && (-180.0..=180.0).contains(&position.lon));
+        *self.positions.entry(format!("{:?}", position.source)).or_default() += 1;
+    }
+}
+
+/// This test visits every message in the sample corpus with a single visitor.
+/// It validates that every message reports its signal details, that ACARS blocks and application details match the
+/// per family getters, and that positions are found from each source.
+#[test]
+fn test_message_visitor() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let mut visitor: CountingVisitor = CountingVisitor::default();
+    for message in &messages {
+        message.visit(&mut visitor);
+    }
+    assert_eq!(visitor.signals, messages.len());
+    assert_eq!(visitor.apps, messages.iter().filter(|message| message.get_app_details().is_some()).count());
+    assert_eq!(visitor.acars_blocks, messages.iter().filter(|message| message.get_ack_fields().is_some()).count());
+    for source in [PositionSource::AdscReport, PositionSource::HfdlAircraft, PositionSource::GroundStation] {
+        assert!(visitor.positions.get(&format!("{:?}", source)).is_some_and(|count| *count > 0), "No positions from {:?}", source);
+    }
+    Ok(())
+}
+
+/// This test extracts routing fields from every message in the sample corpus without decoding them.
+/// It validates that every extracted field matches the same field in the fully parsed input, and that missing fields
+/// are left out.
+#[test]
+fn test_extract_fields() -> Result<(), Box<dyn Error>> {
+    let paths: [&str; 6] = ["label", "station_id", "vdl2.station", "vdl2.avlc.acars.label", "hfdl.freq", "hfdl.lpdu.hfnpdu.acars.label"];
+    let mut extracted: usize = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(parsed) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let fields: HashMap<String, Value> = extract_fields(&line, &paths);
+        for path in paths {
+            let pointer: String = format!("/{}", path.replace('.', "/"));
+            assert_eq!(fields.get(path), parsed.pointer(&pointer), "{} in {}", path, line);
+        }
+        extracted += fields.len();
+    }
+    assert!(extracted > 0);
+    assert!(extract_fields(r#"{"label":"Q0","text":"#, &["label", "text"]).contains_key("label"));
+    assert!(extract_fields("not json", &["label"]).is_empty());
+    Ok(())
+}
+
+/// This test decodes the sample corpus on a `DecodePool`, first returning messages in submission order and then with
+/// a separate producer thread and messages returned in completion order.
+/// It validates that the pool gives the same results as decoding each line directly, and that every input is returned
+/// exactly once before the pool shuts down.
+#[test]
+fn test_decode_pool() -> Result<(), Box<dyn Error>> {
+    let lines: Vec<String> = combine_files_of_message_type(MessageType::All)?;
+    let expected_ok: Vec<bool> = lines.iter().map(|line| line.decode_message().is_ok()).collect();
+
+    let pool: DecodePool = DecodePool::new(DecodePoolConfig::new().workers(4).queue_capacity(16).order(OutputOrder::Submission));
+    let decoded: Vec<DecodedInput> = thread::scope(|scope| {
+        let consumer = scope.spawn(|| pool.results().collect::<Vec<DecodedInput>>());
+        for line in &lines {
+            assert!(pool.submit(line.as_bytes().to_vec()).is_ok());
+        }
+        pool.close();
+        consumer.join().expect("consumer thread panicked")
+    });
+    assert_eq!(decoded.len(), lines.len());
+    for (index, input) in decoded.iter().enumerate() {
+        assert_eq!(input.sequence, index as u64);
+        assert_eq!(input.result.is_ok(), expected_ok[index]);
+        if let Err(failed) = &input.result {
+            assert_eq!(&failed.raw, &lines[index]);
+        }
+    }
+    assert!(pool.submit(b"{}".to_vec()).is_err());
+    assert!(pool.shutdown().is_empty());
+
+    let pool: Arc<DecodePool> = Arc::new(DecodePool::new(DecodePoolConfig::new().workers(3).queue_capacity(4).source("pool-test")));
+    let producer_pool: Arc<DecodePool> = pool.clone();
+    let producer_lines: Vec<String> = lines.clone();
+    let producer = thread::spawn(move || {
+        for line in producer_lines {
+            producer_pool.submit(line.into_bytes()).expect("pool closed early");
+        }
+        producer_pool.close();
+    });
+    let mut sequences: Vec<u64> = Vec::new();
+    for input in pool.results() {
+        assert_eq!(input.result.is_ok(), expected_ok[input.sequence as usize]);
+        if let Err(failed) = &input.result {
+            assert_eq!(failed.source.as_deref(), Some("pool-test"));
+        }
+        sequences.push(input.sequence);
+    }
+    producer.join().expect("producer thread panicked");
+    sequences.sort_unstable();
+    assert_eq!(sequences, (0..lines.len() as u64).collect::<Vec<u64>>());
+    Ok(())
+}
+
+/// This test renames the station field of a message of every family in the sample corpus to the name the other
+/// decoders use.
+/// It validates that the station name is read from either name and is always written back under the native name.
+#[test]
+fn test_station_name_aliases() -> Result<(), Box<dyn Error>> {
+    let mut checked: Vec<MessageFamily> = Vec::new();
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else {
+            continue;
+        };
+        let family: MessageFamily = message.get_family();
+        if checked.contains(&family) || message.get_station_name().is_none() {
+            continue;
+        }
+        let mut value: Value = serde_json::to_value(&message)?;
+        let body: &mut serde_json::Map<String, Value> = match family {
+            MessageFamily::Acars => value.as_object_mut(),
+            MessageFamily::Vdlm2 => value["vdl2"].as_object_mut(),
+            MessageFamily::Hfdl => value["hfdl"].as_object_mut(),
+            MessageFamily::Uat => value.as_object_mut(),
+        }.expect("messages serialise as objects");
+        let (native, alias) = match family {
+            MessageFamily::Acars => ("station_id", "station"),
+            _ => ("station", "station_id"),
+        };
+        let station: Value = body.remove(native).expect("station name is set");
+        body.insert(alias.to_string(), station);
+        let renamed: AcarsVdlm2Message = serde_json::to_string(&value)?.decode_message()?;
+        assert_eq!(renamed.get_station_name(), message.get_station_name());
+        assert_eq!(serde_json::to_value(&renamed)?, serde_json::to_value(&message)?);
+        checked.push(family);
+    }
+    assert_eq!(checked.len(), MessageFamily::ALL.len());
+    Ok(())
+}
+
+/// This test decodes every message in the sample corpus with duplicate key detection on, then a message with a
+/// repeated `freq` key.
+/// It validates that the corpus, which has no repeated keys, raises no warnings, and that the repeated key is reported
+/// with the last value kept.
+#[test]
+fn test_duplicate_key_detection() -> Result<(), Box<dyn Error>> {
+    let mut context: ProcessingContext = ProcessingContext::new().detect_duplicate_keys(true);
+    for line in combine_files_of_message_type(MessageType::All)? {
+        assert!(duplicate_keys(&line).is_empty(), "{}", line);
+        let _ = context.decode(&line);
+        assert!(context.take_warnings().iter().all(|warning| warning.reason != "duplicate_key"));
+    }
+    let message: AcarsVdlm2Message = context.decode(r#"{"freq":131.55,"channel":2,"freq":131.725}"#)?;
+    assert_eq!(message.get_frequency().as_hz(), 131_725_000);
+    let warnings: Vec<ProcessingWarning> = context.take_warnings();
+    assert_eq!(warnings, vec![ProcessingWarning { stage: ProcessingStage::Decode, reason: "duplicate_key".to_string(), detail: "freq".to_string() }]);
+    Ok(())
+}
+
+/// This test builds a time index over each sample file and reads several time ranges through it.
+/// It validates that every range returns the same messages, in the same order, as decoding the whole file and keeping
+/// those in the range, that a stored index gives the same results, and that an index with an entry past the end of its
+/// block or built for a different file length is refused.
+#[test]
+fn test_time_indexed_reader() -> Result<(), Box<dyn Error>> {
+    for path in ["test_files/acars", "test_files/vdlm2", "test_files/hfdl"] {
+        let reader: TimeIndexedReader = TimeIndexedReader::open(path, 25)?;
+        let messages: Vec<AcarsVdlm2Message> = read_test_file(path)?
+            .iter()
+            .filter_map(|line| line.decode_message().ok())
+            .collect();
+        let mut times: Vec<f64> = messages.iter().filter_map(|message| message.get_time()).collect();
+        times.sort_by(f64::total_cmp);
+        assert!(!times.is_empty(), "{} has no timed messages", path);
+        let stored: TimeIndex = serde_json::from_str(&serde_json::to_string(reader.index())?)?;
+        let stored_reader: TimeIndexedReader = TimeIndexedReader::with_index(path, stored);
+        let quarter: usize = times.len() / 4;
+        for (start, end) in [(times[0], times[quarter]), (times[quarter], times[3 * quarter]), (times[0], times[times.len() - 1] + 1.0)] {
+            let expected: Vec<String> = messages.iter()
+                .filter(|message| message.get_time().is_some_and(|time| time >= start && time < end))
+                .map(|message| message.to_json())
+                .collect::<Result<_, _>>()?;
+            let found: Vec<String> = reader.read_range(start, end)?.iter().map(|message| message.to_json()).collect::<Result<_, _>>()?;
+            assert_eq!(found, expected, "{} from {} to {}", path, start, end);
+            assert_eq!(stored_reader.read_range(start, end)?.len(), expected.len());
+        }
+        assert!(reader.read_range(times[times.len() - 1] + 1.0, f64::MAX)?.is_empty());
+        let mut corrupt: TimeIndex = reader.index().clone();
+        corrupt.entries[0] = TimeIndexEntry { offset: corrupt.file_len + 1, min_time: Some(times[0]), max_time: Some(times[0]) };
+        let error: std::io::Error = TimeIndexedReader::with_index(path, corrupt).read_range(times[0], times[0] + 1.0).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        let stale: TimeIndex = TimeIndex { file_len: reader.index().file_len - 1, ..reader.index().clone() };
+        let error: std::io::Error = TimeIndexedReader::with_index(path, stale).read_range(times[0], times[0] + 1.0).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+    Ok(())
+}
+
+/// This test pushes every message in the sample corpus into a `HistoryBuffer` with a smaller HFDL capacity.
+/// It validates that each aircraft keeps its most recent messages of each family up to the capacity, that an aircraft
+/// is found the same way by address and by registration, and that expiring removes everything older than the maximum age.
+#[test]
+fn test_history_buffer() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .filter(|message| message.get_time().is_some())
+        .collect();
+    let mut history: HistoryBuffer = HistoryBuffer::new(5).family_capacity(MessageFamily::Hfdl, 2).max_age_secs(60.0);
+    let mut expected: HashMap<(AircraftKey, MessageFamily), Vec<String>> = HashMap::new();
+    for message in &messages {
+        let keys: Vec<AircraftKey> = AircraftKey::of_message(message);
+        assert_eq!(history.push(message.clone()), !keys.is_empty());
+        for key in keys {
+            let kept: &mut Vec<String> = expected.entry((key, message.get_family())).or_default();
+            kept.push(message.to_json()?);
+            if kept.len() > history.capacity_of(message.get_family()) {
+                kept.remove(0);
+            }
+        }
+    }
+    assert!(!history.is_empty());
+    for ((key, family), kept) in &expected {
+        let found: Vec<String> = history.get_family(key, *family).iter().map(|message| AcarsVdlm2Message::to_json(message)).collect::<Result<_, _>>()?;
+        assert_eq!(&found, kept, "{} {}", key, family);
+        assert!(history.get(key).windows(2).all(|pair| pair[0].get_time() <= pair[1].get_time()));
+    }
+    let both: &AcarsVdlm2Message = messages.iter()
+        .rfind(|message| AircraftKey::of_message(message).len() == 2)
+        .expect("the corpus has messages with an address and a registration");
+    history.push(both.clone());
+    let shared: Vec<Vec<Arc<AcarsVdlm2Message>>> = AircraftKey::of_message(both).iter().map(|key| history.get(key)).collect();
+    assert!(shared[0].iter().any(|kept| shared[1].iter().any(|other| Arc::ptr_eq(kept, other))));
+    let latest: f64 = messages.iter().filter_map(|message| message.get_time()).fold(f64::MIN, f64::max);
+    history.expire(latest);
+    for key in history.keys() {
+        assert!(history.get(key).iter().all(|message| message.get_time().is_some_and(|time| time >= latest - 60.0)));
+    }
+    history.expire(latest + 61.0);
+    assert!(history.is_empty());
+    Ok(())
+}
+
+/// This test decodes every sample message while counting its fields, then decodes messages with fields added.
+/// It validates that counting fields does not change how messages decode, and that added fields are counted as ignored
+/// against the right family and path.
+#[test]
+fn test_field_statistics() -> Result<(), Box<dyn Error>> {
+    let mut statistics: FieldStatistics = FieldStatistics::new();
+    let mut decoded: u64 = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        match (statistics.decode(&line), line.decode_message()) {
+            (Ok(counted), Ok(message)) => {
+                assert_eq!(counted.to_json()?, message.to_json()?);
+                decoded += 1;
+            }
+            (Err(_), Err(_)) => {}
+            (counted, message) => panic!("{} decoded as {:?} and {:?}", line, counted.is_ok(), message.is_ok()),
+        }
+    }
+    let totals: FieldCounts = statistics.totals();
+    assert_eq!(totals.messages, decoded);
+    assert!(totals.present > totals.ignored);
+    for family in MessageFamily::ALL {
+        assert!(statistics.family(family).messages > 0, "no {} messages counted", family);
+    }
+    let mut drift: FieldStatistics = FieldStatistics::new();
+    for (path, key) in [("test_files/vdlm2", "vdl2"), ("test_files/hfdl", "hfdl")] {
+        let mut value: Value = serde_json::from_str(&read_test_file(path)?[0])?;
+        value["new_field"] = Value::from(1);
+        if key == "vdl2" {
+            value[key]["avlc"]["extra"] = Value::from(vec![1, 2]);
+        }
+        drift.decode(&value.to_string())?;
+    }
+    let vdlm2: FieldCounts = drift.family(MessageFamily::Vdlm2);
+    assert_eq!((vdlm2.messages, vdlm2.ignored), (1, 2));
+    assert_eq!(vdlm2.ignored_fields.keys().collect::<Vec<&String>>(), vec!["new_field", "vdl2.avlc.extra"]);
+    assert_eq!(drift.family(MessageFamily::Hfdl).ignored_fields["new_field"], 1);
+    assert_eq!(drift.family(MessageFamily::Acars), FieldCounts::default());
+    statistics.merge(&drift);
+    assert_eq!(statistics.totals().messages, decoded + 2);
+    assert_eq!(statistics.family(MessageFamily::Vdlm2).ignored_fields.get("vdl2.avlc.extra"), Some(&1));
+    Ok(())
+}
+
+/// This test decodes inputs that fail in each of the ways a feed commonly goes wrong.
+/// It validates that each failure is given the expected code, and that codes do not depend on the family or the field
+/// involved.
+#[test]
+fn test_error_codes() -> Result<(), Box<dyn Error>> {
+    let cases: [(&str, &str); 9] = [
+        ("", "json.eof"),
+        (r#"{"freq":131.55,"#, "json.eof"),
+        ("{freq:131.55}", "json.syntax"),
+        (r#""a string""#, "message.not_object"),
+        (r#"{"freq":"high","channel":2}"#, "message.bad_frequency"),
+        (r#"{"freq":131.55,"channel":"two"}"#, "message.bad_number"),
+        (r#"{"freq":131.55,"channel":2,"text":12}"#, "json.invalid_type"),
+        (r#"{"hfdl":{"freq":13312000,"bit_rate":300,"slot":"S","unexpected":true}}"#, "json.unknown_field"),
+        (r#"{"vdl2":{"freq":136975000}}"#, "json.missing_field"),
+    ];
+    for (input, expected) in cases {
+        let error: serde_json::Error = input.decode_message().expect_err(input);
+        assert_eq!(error_code(&error), expected, "{} failed with {}", input, error);
+        let failed: FailedDecode = FailedDecode::new(input, &error, &MessageFamily::ALL, None);
+        assert_eq!(failed.code.map(|code| code.as_str()), Some(expected));
+        assert!(ErrorCode::ALL.contains(&ErrorCode::of(&error)));
+        assert!(serde_json::to_string(&failed)?.contains(&format!(r#""code":"{}""#, expected)));
+    }
+    let written: FailedDecode = serde_json::from_str(r#"{"raw":"x","error":"expected value","attempted_families":[],"timestamp":0.0}"#)?;
+    assert_eq!((written.code, written.to_string()), (None, "expected value".to_string()));
+    Ok(())
+}
+
+/// This test builds geographic and range and bearing coverage heatmaps from the sample corpus.
+/// It validates that every message is counted in exactly one cell, that messages with aircraft positions are placed,
+/// that range and bearing bins are only used for stations with a receiver location, and that the CSV and JSON exports
+/// hold every cell.
+#[test]
+fn test_coverage_heatmap() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let mut geographic: CoverageHeatmap = CoverageHeatmap::new(CoverageBinning::Geographic { cell_deg: 1.0 });
+    let mut range_bearing: CoverageHeatmap = CoverageHeatmap::new(CoverageBinning::RangeBearing { range_step_nm: 50.0, bearing_step_deg: 45.0 })
+        .receiver("CS-KABQ-VDLM", 35.04, -106.61);
+    for message in &messages {
+        geographic.record(message);
+        range_bearing.record(message);
+    }
+    for heatmap in [&geographic, &range_bearing] {
+        let cells: Vec<CoverageCell> = heatmap.cells();
+        assert_eq!(cells.iter().map(|cell| cell.messages).sum::<u64>(), messages.len() as u64);
+        let mut csv: Vec<u8> = Vec::new();
+        heatmap.write_csv(&mut csv)?;
+        assert_eq!(String::from_utf8(csv)?.lines().count(), cells.len() + 1);
+        let mut json: Vec<u8> = Vec::new();
+        heatmap.write_json(&mut json)?;
+        assert_eq!(serde_json::from_slice::<Vec<CoverageCell>>(&json)?, cells);
+    }
+    let located: Vec<CoverageCell> = geographic.cells().into_iter().filter(|cell| cell.bin != CoverageBin::Unlocated).collect();
+    assert!(!located.is_empty());
+    assert!(located.iter().all(|cell| matches!(cell.bin, CoverageBin::Geographic { lat, lon } if lat.fract() == 0.0 && lon.fract() == 0.0)));
+    for cell in range_bearing.cells() {
+        match cell.bin {
+            CoverageBin::RangeBearing { range_nm, bearing_deg } => {
+                assert_eq!(cell.station, "CS-KABQ-VDLM");
+                assert!(range_nm >= 0.0 && (0.0..360.0).contains(&bearing_deg));
+            }
+            CoverageBin::Unlocated => {}
+            CoverageBin::Geographic { .. } => panic!("geographic bin in a range and bearing heatmap"),
+        }
+    }
+    assert!(range_bearing.cells().iter().any(|cell| matches!(cell.bin, CoverageBin::RangeBearing { .. })));
+    Ok(())
+}
+
+/// This test runs the sample corpus through a `DecodePool` and `decode_batch_validated` with validation hooks that
+/// reject HFDL messages and ACARS messages with a label.
+/// It validates that rejected messages come back with the `message.rejected` code and their reason, that the hooks
+/// count every checked message by reason, and that both decoders agree.
+#[test]
+fn test_validation_hooks() -> Result<(), Box<dyn Error>> {
+    let lines: Vec<String> = combine_files_of_message_type(MessageType::All)?;
+    let build_hooks = || {
+        let mut hooks: ValidationHooks = ValidationHooks::new();
+        hooks.add(Box::new(|message: &AcarsVdlm2Message| match message.get_family() {
+            MessageFamily::Hfdl => Err(RejectReason::new("hfdl")),
+            _ => Ok(()),
+        }));
+        hooks.add(Box::new(|message: &AcarsVdlm2Message| match (message.get_family(), message.get_label()) {
+            (MessageFamily::Acars, Some(_)) => Err(RejectReason::new("acars label")),
+            _ => Ok(()),
+        }));
+        hooks
+    };
+    let hooks: Arc<ValidationHooks> = Arc::new(build_hooks());
+    let pool: DecodePool = DecodePool::new(DecodePoolConfig::new().workers(3).order(OutputOrder::Submission).validation(Arc::clone(&hooks)));
+    let pooled: Vec<DecodedInput> = thread::scope(|scope| {
+        let consumer = scope.spawn(|| pool.results().collect::<Vec<DecodedInput>>());
+        for line in &lines {
+            assert!(pool.submit(line.as_bytes().to_vec()).is_ok());
+        }
+        pool.close();
+        consumer.join().expect("consumer thread panicked")
+    });
+    let batch_hooks: ValidationHooks = build_hooks();
+    let batch: Vec<Result<AcarsVdlm2Message, FailedDecode>> = decode_batch_validated(&lines, None, &batch_hooks);
+    assert_eq!(pooled.len(), lines.len());
+    let mut expected: ValidationStats = ValidationStats::default();
+    for ((line, pooled), batched) in lines.iter().zip(&pooled).zip(&batch) {
+        assert_eq!(pooled.result.is_ok(), batched.is_ok(), "{}", line);
+        assert_eq!(pooled.result.as_ref().err().map(|failed| (failed.code, &failed.error)), batched.as_ref().err().map(|failed| (failed.code, &failed.error)));
+        let Ok(message) = line.decode_message() else {
+            assert_ne!(batched.as_ref().unwrap_err().code, Some(ErrorCode::Rejected));
+            continue;
+        };
+        expected.checked += 1;
+        let reason: Option<&str> = match (message.get_family(), message.get_label()) {
+            (MessageFamily::Hfdl, _) => Some("hfdl"),
+            (MessageFamily::Acars, Some(_)) => Some("acars label"),
+            _ => None,
+        };
+        match reason {
+            Some(reason) => {
+                *expected.rejected.entry(reason.to_string()).or_default() += 1;
+                let failed: &FailedDecode = batched.as_ref().unwrap_err();
+                assert_eq!((failed.code, failed.error.as_str()), (Some(ErrorCode::Rejected), reason));
+                assert_eq!(failed.attempted_families, vec![message.get_family()]);
+                assert_eq!(&failed.raw, line);
+            }
+            None => assert!(batched.is_ok()),
+        }
+    }
+    assert_eq!(expected.rejected.len(), 2);
+    assert_eq!(hooks.stats(), expected);
+    assert_eq!(batch_hooks.stats(), expected);
+    batch_hooks.reset();
+    assert_eq!(batch_hooks.stats(), ValidationStats::default());
+    Ok(())
+}
+
+/// This test converts every sample message with an ACARS block to the acarsdec layout.
+/// It validates that the ACARS fields, time and aircraft address are carried over, that the converted message decodes
+/// as ACARS, that ACARS messages convert without loss, and that every dropped path is in the original message and is
+/// not one of the fields carried over.
+#[test]
+fn test_acars_conversion_report() -> Result<(), Box<dyn Error>> {
+    let mut converted: HashMap<MessageFamily, usize> = HashMap::new();
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else { continue };
+        let Some(conversion) = message.to_acars_message() else {
+            assert!(message.get_text().is_none() && message.get_label().is_none(), "{}", line);
+            continue;
+        };
+        let AcarsConversion { message: acars, report } = conversion;
+        assert_eq!(report.source, message.get_family());
+        assert_eq!(acars.label.as_deref(), message.get_label());
+        assert_eq!(acars.text.as_deref(), message.get_text());
+        assert_eq!(acars.flight.as_deref(), message.get_flight());
+        assert_eq!(acars.icao, message.get_icao());
+        assert_eq!(acars.get_time(), message.get_time());
+        assert_eq!(acars.freq.as_hz(), message.get_frequency().as_hz());
+        let decoded: AcarsVdlm2Message = acars.to_json()?.decode_message()?;
+        assert_eq!(decoded.get_family(), MessageFamily::Acars);
+        assert_eq!(decoded.get_text(), message.get_text());
+        let original: Value = serde_json::from_str(&message.to_json()?)?;
+        for path in &report.dropped {
+            let pointer: String = format!("/{}", path.replace('.', "/"));
+            assert!(original.pointer(&pointer).is_some(), "{} is not in {}", path, line);
+            assert!(!path.ends_with(".label") && !path.ends_with(".msg_text"), "{} was dropped from {}", path, line);
+        }
+        match message.get_family() {
+            MessageFamily::Acars => assert!(report.is_lossless()),
+            MessageFamily::Vdlm2 => assert!(report.dropped("vdl2.avlc.cr") && !report.dropped("vdl2.freq")),
+            MessageFamily::Hfdl => assert!(report.dropped("hfdl.slot") && !report.dropped("hfdl.freq")),
+            MessageFamily::Uat => unreachable!("UAT messages never carry an ACARS block"),
+        }
+        *converted.entry(message.get_family()).or_default() += 1;
+    }
+    assert_eq!(converted.len(), 3);
+    Ok(())
+}
+
+/// This test wraps every sample message in an envelope and reads it back.
+/// It validates that the envelope names the version and family, that the payload reads back unchanged, and that
+/// envelopes with an unknown version, a family that does not match the payload, or unknown keys are rejected.
+#[test]
+fn test_envelope() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else { continue };
+        let json: String = message.to_string_enveloped()?;
+        let value: Value = serde_json::from_str(&json)?;
+        assert_eq!(value["v"], Value::from(ENVELOPE_VERSION));
+        assert_eq!(value["type"], Value::String(message.get_family().to_string()));
+        assert_eq!(value["payload"], serde_json::from_str::<Value>(&message.to_json()?)?);
+        assert_eq!(decode_enveloped(&json)?.to_json()?, message.to_json()?);
+        assert_eq!(OutputMode::Enveloped.to_bytes_newline(&message)?, format!("{}\n", json).into_bytes());
+        assert_eq!(OutputMode::Plain.to_bytes_newline(&message)?, message.to_bytes_newline()?);
+        let envelope: Envelope = Envelope::from(message.clone());
+        assert_eq!(serde_json::to_string(&envelope)?, json);
+        let wrong_family: MessageFamily = match message.get_family() {
+            MessageFamily::Vdlm2 => MessageFamily::Hfdl,
+            _ => MessageFamily::Vdlm2,
+        };
+        let mut mismatched: Value = value.clone();
+        mismatched["type"] = Value::String(wrong_family.to_string());
+        assert!(decode_enveloped(&mismatched.to_string()).is_err());
+        let mut newer: Value = value.clone();
+        newer["v"] = Value::from(ENVELOPE_VERSION + 1);
+        assert!(decode_enveloped(&newer.to_string()).unwrap_err().to_string().contains("unsupported envelope version"));
+    }
+    assert!(decode_enveloped(r#"{"v":1,"type":"acars","payload":{"freq":131.55},"extra":true}"#).is_err());
+    assert!(decode_enveloped(r#"{"freq":131.55,"channel":2}"#).is_err());
+    assert!(decode_enveloped_bytes(br#"{"v":1,"type":"acars","payload":{"freq":131.55}}"#).is_ok());
+    Ok(())
+}
+
+/// This test decodes lines written by legacy CSV feeders.
+/// It validates that each column maps to its `AcarsMessage` field, that quoted columns and commas in the text are kept,
+/// that the lines are detected by `DecodeMessage` and `sniff_format`, and that short or malformed lines are rejected.
+#[test]
+fn test_csv_legacy() -> Result<(), Box<dyn Error>> {
+    let line: &str = "1672531200.5,EXAMPLE-STATION,2,131.550,-24,0,2,H1,4,!,.N12345,UA0123,M01A,POS N47,W122\r\n";
+    let message: AcarsMessage = parse_csv_legacy(line)?;
+    assert_eq!(message.timestamp, Some(1672531200.5));
+    assert_eq!(message.station_id.as_deref(), Some("EXAMPLE-STATION"));
+    assert_eq!(message.channel, Some(2));
+    assert_eq!(message.freq.as_hz(), 131_550_000);
+    assert_eq!(message.level, Some(LevelType::I32(-24)));
+    assert_eq!(message.error, Some(0));
+    assert_eq!((message.mode.as_deref(), message.label.as_deref(), message.block_id.as_deref()), (Some("2"), Some("H1"), Some("4")));
+    assert_eq!(message.ack, Some(AckType::String("!".to_string())));
+    assert_eq!((message.tail.as_deref(), message.flight.as_deref(), message.msgno.as_deref()), (Some(".N12345"), Some("UA0123"), Some("M01A")));
+    assert_eq!(message.text.as_deref(), Some("POS N47,W122"));
+    assert_eq!(sniff_format(line.as_bytes()), WireFormat::CsvLegacy);
+    let decoded: AcarsVdlm2Message = line.decode_message()?;
+    assert_eq!(decoded.to_json()?, AcarsVdlm2Message::AcarsMessage(message).to_json()?);
+    assert_eq!(line.as_bytes().decode_message()?.to_json()?, decoded.to_json()?);
+    let sparse: AcarsMessage = parse_csv_legacy("1672531200,,,131.725,-20.5,,,_d,,,,,,\"say \"\"hi\"\", twice\"")?;
+    assert_eq!((sparse.station_id, sparse.channel, sparse.tail), (None, None, None));
+    assert_eq!(sparse.level, Some(LevelType::Float64(-20.5)));
+    assert_eq!(sparse.text.as_deref(), Some("say \"hi\", twice"));
+    assert!(parse_csv_legacy("1672531200,STATION,2,131.550").is_err());
+    assert!(parse_csv_legacy("1672531200,,,,,,,,,,,,,").is_err());
+    assert!(parse_csv_legacy("1672531200,,two,131.550,,,,,,,,,,").is_err());
+    assert!("1672531200,,two,131.550,,,,,,,,,,".decode_message().is_err());
+    assert_eq!(sniff_format(b"12345"), WireFormat::Unknown);
+    Ok(())
+}
+
+/// This test decodes ACARS frequencies written with `f32` noise and with trailing zeros.
+/// It validates that the noise is removed so the messages compare equal and serialise cleanly, and that the decimal
+/// places the frequency was written with are kept, and written back when serde_json's `arbitrary_precision` is enabled.
+/// Run it with `--features arbitrary_precision` to cover that case.
+#[test]
+fn test_frequency_precision() -> Result<(), Box<dyn Error>> {
+    let clean: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.decode_message()?;
+    let noisy: AcarsVdlm2Message = r#"{"freq":131.5500030517578,"channel":2}"#.decode_message()?;
+    let quoted: AcarsVdlm2Message = r#"{"freq":"131.550","channel":2}"#.decode_message()?;
+    assert_eq!(noisy.get_frequency(), clean.get_frequency());
+    assert_eq!(noisy.get_frequency().as_hz(), 131_550_000);
+    assert_eq!(noisy.to_json()?, clean.to_json()?);
+    assert_eq!(quoted.get_frequency().precision(), Some(3));
+    assert_eq!(quoted.get_frequency().as_written(), "131.550");
+    let padded: AcarsVdlm2Message = r#"{"freq":131.550,"channel":2}"#.decode_message()?;
+    match cfg!(feature = "arbitrary_precision") {
+        true => {
+            assert_eq!(serde_json::to_string(&quoted.get_frequency())?, "131.550");
+            assert_eq!(padded.to_json()?, r#"{"freq":131.550,"channel":2}"#);
+        }
+        false => {
+            assert_eq!(serde_json::to_string(&quoted.get_frequency())?, "131.55");
+            assert_eq!(padded.to_json()?, r#"{"freq":131.55,"channel":2}"#);
+        }
+    }
+    let precise: AcarsVdlm2Message = r#"{"freq":131.5504,"channel":2}"#.decode_message()?;
+    assert_eq!(precise.get_frequency().as_hz(), 131_550_400);
+    let vdlm2: Frequency = serde_json::from_str("136975000")?;
+    assert_eq!((vdlm2.precision(), vdlm2.as_written()), (None, "136975000".to_string()));
+    Ok(())
+}
+
+/// Drops every message without a label, for `test_transform_apply_batch`.
+struct DropUnlabelled;
+
+impl Transform for DropUnlabelled {
+    fn name(&self) -> &str {
+        "drop_unlabelled"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        match message.get_label() {
+            Some(_) => TransformOutcome::Forward,
+            None => TransformOutcome::Drop("no label".to_string()),
+        }
+    }
+}
+
+/// This test runs the decoded sample corpus through a pipeline as one batch.
+/// It validates that every message gets the same outcome and changes as when applied one at a time, and that the
+/// report gives the position of each dropped message and keeps only the forwarded ones.
+#[test]
+fn test_transform_apply_batch() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let mut pipeline: TransformPipeline = TransformPipeline::new();
+    pipeline.add(StationRedactor::new("batch salt"));
+    pipeline.add(DropUnlabelled);
+    let mut batch: Vec<AcarsVdlm2Message> = messages.clone();
+    let report: BatchReport = pipeline.apply_batch(&mut batch);
+    assert_eq!(report.outcomes.len(), messages.len());
+    for ((mut message, batched), outcome) in messages.into_iter().zip(&batch).zip(&report.outcomes) {
+        assert_eq!(&pipeline.apply(&mut message), outcome);
+        assert_eq!(message.to_json()?, batched.to_json()?);
+    }
+    let dropped: Vec<(usize, &str)> = report.dropped();
+    assert!(!dropped.is_empty() && report.forwarded() > 0);
+    assert_eq!(dropped.len() + report.forwarded(), batch.len());
+    assert!(dropped.iter().all(|(index, reason)| batch[*index].get_label().is_none() && *reason == "no label"));
+    let forwarded: Vec<AcarsVdlm2Message> = report.retain_forwarded(batch);
+    assert_eq!(forwarded.len(), report.forwarded());
+    assert!(forwarded.iter().all(|message| message.get_label().is_some()));
+    Ok(())
+}
+
+/// This test compares every decoded sample message with a copy whose volatile fields have been changed.
+/// It validates that the copy only compares equal once the changed classes are ignored, that a message always equals
+/// itself, and that messages of different families never compare equal.
+#[test]
+fn test_eq_ignoring() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    for message in &messages {
+        assert!(message.eq_ignoring(message, &[]));
+        let mut proxied: AcarsVdlm2Message = message.clone();
+        proxied.set_proxy_details("proxy", "1.0.0");
+        assert!(!message.eq_ignoring(&proxied, &[]));
+        assert!(message.eq_ignoring(&proxied, &[FieldClass::AppDetails]));
+        let mut restamped: AcarsVdlm2Message = message.clone();
+        restamped.set_station_name("ELSEWHERE");
+        restamped.clear_time();
+        restamped.clear_sig_level();
+        restamped.clear_level();
+        assert!(message.eq_ignoring(&restamped, &[FieldClass::Station, FieldClass::Time, FieldClass::Signal]));
+        assert!(!message.eq_ignoring(&restamped, &[FieldClass::Time, FieldClass::Signal]));
+        let mut retexted: AcarsVdlm2Message = restamped.clone();
+        if let AcarsVdlm2Message::AcarsMessage(acars) = &mut retexted {
+            acars.text = Some(format!("{}!", acars.text.as_deref().unwrap_or_default()));
+            assert!(!message.eq_ignoring(&retexted, &FieldClass::ALL));
+        }
+    }
+    let acars: &AcarsVdlm2Message = messages.iter().find(|message| message.get_family() == MessageFamily::Acars).unwrap();
+    let vdlm2: &AcarsVdlm2Message = messages.iter().find(|message| message.get_family() == MessageFamily::Vdlm2).unwrap();
+    assert!(!acars.eq_ignoring(vdlm2, &FieldClass::ALL));
+    Ok(())
+}
+
+/// This test runs the conformance checks over the sample captures.
+/// It validates that every line is counted once as lossless or as a failure, that decode failures carry their error
+/// code, that every family is classified, and that the report reads back from JSON unchanged.
+#[test]
+fn test_conformance_report() -> Result<(), Box<dyn Error>> {
+    let report: ConformanceReport = check_directory("test_files")?;
+    assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+    assert!(report.files > 0 && report.decoded > 0);
+    assert_eq!(report.lossless + report.failures.len(), report.lines);
+    assert_eq!(report.error_codes.values().sum::<usize>(), report.lines - report.decoded);
+    assert_eq!(report.families.values().sum::<usize>(), report.decoded);
+    assert!([MessageFamily::Acars, MessageFamily::Vdlm2, MessageFamily::Hfdl].iter().all(|family| report.families.contains_key(family)));
+    for failure in &report.failures {
+        assert_eq!(failure.code.is_some(), failure.stage == ConformanceStage::Decode);
+        assert!(failure.line > 0 && !failure.error.is_empty());
+    }
+    assert!(report.unknown_fields.keys().chain(report.changed_fields.keys()).all(|path| !path.split('.').any(|segment| segment.parse::<usize>().is_ok())));
+    let read_back: ConformanceReport = serde_json::from_str(&serde_json::to_string(&report)?)?;
+    assert_eq!(read_back, report);
+    let mut corpus: Corpus = Corpus::new();
+    corpus.register("test_files/acars", &["acars"]).register("test_files/vdlm2", &["vdlm2"]);
+    let acars: ConformanceReport = check_corpus(&corpus, &TagFilter::any().require("acars"))?;
+    assert_eq!((acars.files, acars.families.keys().copied().collect::<Vec<MessageFamily>>()), (1, vec![MessageFamily::Acars]));
+    Ok(())
+}
+
+/// This test shuffles the ACARS and VDLM2 sample captures twice with the same seed and decodes a sample of them.
+/// It validates that a seeded shuffle replays exactly, that a different seed gives a different order, and that the
+/// decoded sample still serialises to the golden output in `tests/golden`.
+#[test]
+fn test_seeded_shuffle_golden() -> Result<(), Box<dyn Error>> {
+    let mut lines: Vec<String> = read_test_file("test_files/acars")?;
+    lines.extend(read_test_file("test_files/vdlm2")?);
+    let mut replayed: Vec<String> = lines.clone();
+    let mut reseeded: Vec<String> = lines.clone();
+    SeededRng::new(1216).shuffle(&mut lines);
+    SeededRng::new(1216).shuffle(&mut replayed);
+    SeededRng::new(1217).shuffle(&mut reseeded);
+    assert_eq!(lines, replayed);
+    assert_ne!(lines, reseeded);
+    let sample: Vec<AcarsVdlm2Message> = lines.iter()
+        .filter_map(|line| line.decode_message().ok())
+        .take(8)
+        .collect();
+    assert_eq!(sample.len(), 8);
+    assert_golden_messages("tests/golden/seeded_shuffle.jsonl", &sample);
+    Ok(())
+}
+
+/// This test merges the ACARS, VDLM2 and HFDL sample captures, each from a different feeder, and then merges two
+/// channels fed from threads, one of them with its messages swapped out of order.
+/// It validates that every timed message comes out exactly once and in time order, and that messages out of order by
+/// less than the reordering window are put back in order rather than counted as late.
+#[test]
+fn test_merge_streams() -> Result<(), Box<dyn Error>> {
+    let mut streams: Vec<Vec<AcarsVdlm2Message>> = Vec::new();
+    for path in ["test_files/acars", "test_files/vdlm2", "test_files/hfdl"] {
+        streams.push(read_test_file(path)?.iter()
+            .filter_map(|line| line.decode_message().ok())
+            .filter(|message| message.get_time().is_some())
+            .collect());
+    }
+    let total: usize = streams.iter().map(Vec::len).sum();
+    let mut merged: MergedStream<std::vec::IntoIter<AcarsVdlm2Message>> = merge_streams(streams, DEFAULT_REORDER_WINDOW_SECS);
+    let times: Vec<f64> = merged.by_ref().map(|message| message.get_time().unwrap()).collect();
+    assert_eq!(times.len(), total);
+    assert_eq!(merged.merger().untimed(), 0);
+    assert_eq!(merged.merger().late(), 0);
+    assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+    let decode = |time: f64| -> AcarsVdlm2Message {
+        format!(r#"{{"freq":131.55,"timestamp":{}}}"#, time).decode_message().unwrap()
+    };
+    let (first_sender, first_receiver): (Sender<AcarsVdlm2Message>, Receiver<AcarsVdlm2Message>) = mpsc::channel();
+    let (second_sender, second_receiver): (Sender<AcarsVdlm2Message>, Receiver<AcarsVdlm2Message>) = mpsc::channel();
+    let feeders: Vec<thread::JoinHandle<()>> = vec![
+        thread::spawn(move || [0.0, 2.0, 1.5, 4.0, 6.0].into_iter().for_each(|time| first_sender.send(decode(time)).unwrap())),
+        thread::spawn(move || [0.5, 3.0, 5.0, 5.5].into_iter().for_each(|time| second_sender.send(decode(time)).unwrap())),
+    ];
+    let mut merged = merge_streams(vec![first_receiver, second_receiver], 1.0);
+    let times: Vec<f64> = merged.by_ref().map(|message| message.get_time().unwrap()).collect();
+    for feeder in feeders {
+        feeder.join().unwrap();
+    }
+    assert_eq!(times, vec![0.0, 0.5, 1.5, 2.0, 3.0, 4.0, 5.0, 5.5, 6.0]);
+    assert_eq!(merged.merger().late(), 0);
+    Ok(())
+}
+
+/// This test summarises every decodable sample message and a message with multi-byte text.
+/// It validates that each summary agrees with the getters it is built from, that the snippet is a single line of at
+/// most `SNIPPET_CHARS` characters taken from the start of the text, and that summaries serialise for a UI.
+#[test]
+fn test_message_summary() -> Result<(), Box<dyn Error>> {
+    let mut summarised: usize = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else { continue };
+        let summary: MessageSummary = message.summary();
+        assert_eq!((summary.time, summary.family, summary.icao), (message.get_time(), message.get_family(), message.get_icao()));
+        assert_eq!(summary.label, message.get_label());
+        assert!(summary.tail.is_none_or(|tail| !tail.starts_with('.') && message.get_registration() == Some(tail)));
+        if let Some(snippet) = summary.snippet {
+            assert!(snippet.chars().count() <= SNIPPET_CHARS && !snippet.contains(['\r', '\n']));
+            assert!(message.get_text().is_some_and(|text| text.trim_start().starts_with(snippet)));
+        }
+        assert!(serde_json::to_string(&summary)?.starts_with("{\"time\":"));
+        summarised += 1;
+    }
+    assert!(summarised > 0);
+    let text: String = "Ü".repeat(SNIPPET_CHARS + 10);
+    let message: AcarsVdlm2Message = format!(r#"{{"freq":131.55,"text":"{}"}}"#, text).decode_message()?;
+    assert_eq!(message.summary().snippet.map(|snippet| snippet.chars().count()), Some(SNIPPET_CHARS));
+    assert!(message.summary().is_truncated(&message));
+    Ok(())
+}
+
+/// This test decodes every sample line from a `serde_json::Value`, both borrowed and owned, as well as from the text.
+/// It validates that decoding a `Value` gives the same message as decoding the text for every family, and that lines
+/// which fail to decode from text also fail from a `Value`.
+#[test]
+fn test_decode_from_value() -> Result<(), Box<dyn Error>> {
+    let mut decoded: usize = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(value) = serde_json::from_str::<Value>(&line) else { continue };
+        let from_text: Result<AcarsVdlm2Message, serde_json::Error> = line.decode_message();
+        assert_eq!(value.decode_message().is_ok(), from_text.is_ok(), "{}", line);
+        let Ok(from_text) = from_text else { continue };
+        let expected: String = from_text.to_json()?;
+        assert_eq!(value.decode_message()?.to_json()?, expected);
+        let family: String = match from_text.get_family() {
+            MessageFamily::Acars => {
+                assert_eq!(value.to_acars()?.to_json()?, expected);
+                AcarsMessage::try_from(value.clone())?.to_json()?
+            }
+            MessageFamily::Vdlm2 => {
+                assert_eq!(value.to_vdlm2()?.to_json()?, expected);
+                Vdlm2Message::try_from(value.clone())?.to_json()?
+            }
+            MessageFamily::Hfdl => {
+                assert_eq!(value.to_hfdl()?.to_json()?, expected);
+                HfdlMessage::from_value(value.clone())?.to_json()?
+            }
+            MessageFamily::Uat => {
+                assert_eq!(value.to_uat()?.to_json()?, expected);
+                UatMessage::try_from(value.clone())?.to_json()?
+            }
+        };
+        assert_eq!(family, expected);
+        assert_eq!(AcarsVdlm2Message::from_value(value)?.to_json()?, expected);
+        decoded += 1;
+    }
+    assert!(decoded > 0);
+    Ok(())
+}
+
+/// This test builds the example message of every family, both through the family type and through
+/// `AcarsVdlm2Message::example`.
+/// It validates that each example is populated, decodes as its own family and writes back out without losing a field,
+/// and that every family carrying ACARS has the same registration, label and text.
+#[test]
+fn test_example_messages() -> Result<(), Box<dyn Error>> {
+    let examples: Vec<AcarsVdlm2Message> = vec![
+        AcarsVdlm2Message::AcarsMessage(AcarsMessage::example()),
+        AcarsVdlm2Message::Vdlm2Message(Vdlm2Message::example()),
+        AcarsVdlm2Message::HfdlMessage(HfdlMessage::example()),
+        AcarsVdlm2Message::UatMessage(UatMessage::example()),
+    ];
+    for (family, example) in MessageFamily::ALL.into_iter().zip(&examples) {
+        let line: String = example.to_json()?;
+        assert_eq!(example.get_family(), family);
+        assert_eq!(AcarsVdlm2Message::example(family).to_json()?, line);
+        assert_eq!(line.decode_message()?.get_family(), family);
+        let report: RoundtripReport = roundtrip_check(&line);
+        assert!(report.is_lossless(), "{}: {:?}", family, report.differences);
+        assert_eq!(example.get_flight(), Some("AB0123"));
+        assert_eq!(example.get_station_name(), Some("EXAMPLE-STATION"));
+        assert!(example.get_time().is_some());
+        if family == MessageFamily::Uat {
+            assert_eq!(example.get_icao(), Some(0xA1B2C3));
+            continue;
+        }
+        assert_eq!(example.get_registration(), Some("N123AB"));
+        assert_eq!(example.get_label(), Some("H1"));
+        assert!(example.get_text().is_some_and(|text| text.contains("N47123W122456")));
+    }
+    Ok(())
+}
+
+/// A writer that takes at most `limit` bytes a call and then reports `WouldBlock` until it is unblocked, like a slow
+/// non-blocking socket.
+struct SlowWriter {
+    output: Vec<u8>,
+    limit: usize,
+    blocked: bool,
+}
+
+impl std::io::Write for SlowWriter {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        if self.blocked {
+            return Err(std::io::ErrorKind::WouldBlock.into());
+        }
+        let taken: usize = bytes.len().min(self.limit);
+        self.output.extend_from_slice(&bytes[..taken]);
+        self.blocked = true;
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// This test writes every sample message through an `NdjsonWriter`, in both output modes and into a writer that keeps
+/// blocking.
+/// It validates that the output matches `to_bytes_newline` line for line, that a blocked writer keeps the unwritten
+/// bytes buffered and reports backpressure past the high water mark, and that flushing once it unblocks clears it.
+#[test]
+fn test_ndjson_writer() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let expected: Vec<u8> = messages.iter().map(|message| message.to_bytes_newline()).collect::<Result<Vec<Vec<u8>>, _>>()?.concat();
+    let mut writer: NdjsonWriter<Vec<u8>> = NdjsonWriter::new(Vec::new()).flush_threshold(4096);
+    for message in &messages {
+        assert_eq!(writer.write(message)?, WriteStatus::Ready);
+        assert!(writer.buffered() < 4096);
+    }
+    assert_eq!(writer.written(), messages.len() as u64);
+    assert_eq!(writer.into_inner()?, expected);
+
+    let mut enveloped: NdjsonWriter<Vec<u8>> = NdjsonWriter::new(Vec::new()).flush_threshold(0).output_mode(OutputMode::Enveloped);
+    for message in &messages {
+        enveloped.write(message)?;
+        assert_eq!(enveloped.buffered(), 0);
+    }
+    let output: Vec<u8> = enveloped.into_inner()?;
+    for (line, message) in output.split(|byte| *byte == b'\n').zip(&messages) {
+        assert_eq!(decode_enveloped_bytes(line)?.to_json()?, message.to_json()?);
+    }
+
+    let message: AcarsVdlm2Message = AcarsVdlm2Message::example(MessageFamily::Vdlm2);
+    let line: usize = message.to_bytes_newline()?.len();
+    let slow: SlowWriter = SlowWriter { output: Vec::new(), limit: 10, blocked: false };
+    let mut writer: NdjsonWriter<SlowWriter> = NdjsonWriter::new(slow).flush_threshold(0).high_water_mark(line * 3);
+    assert_eq!(writer.write(&message)?, WriteStatus::Ready);
+    assert_eq!(writer.bytes_flushed(), 10);
+    assert_eq!(writer.write(&message)?, WriteStatus::Ready);
+    assert_eq!(writer.write(&message)?, WriteStatus::Ready);
+    assert_eq!(writer.write(&message)?, WriteStatus::Backpressure);
+    assert_eq!(writer.buffered(), line * 4 - 10);
+    assert_eq!(writer.flush()?, WriteStatus::Backpressure);
+    let mut statuses: Vec<WriteStatus> = Vec::new();
+    while writer.buffered() > 0 {
+        writer.get_mut().blocked = false;
+        statuses.push(writer.flush()?);
+    }
+    assert_eq!(statuses.first(), Some(&WriteStatus::Backpressure));
+    assert_eq!(statuses.last(), Some(&WriteStatus::Ready));
+    assert_eq!(writer.bytes_flushed(), (line * 4) as u64);
+    assert_eq!(writer.into_inner()?.output, message.to_bytes_newline()?.repeat(4));
+    Ok(())
+}
+
+/// This test anonymises every sample message, and the example message of each family, with an `AircraftAnonymiser`.
+/// It validates that each ICAO address and registration is replaced by the pseudonym for its original value, so an
+/// aircraft keeps the same pseudonym in every family, that the messages still write back out and decode, and that a
+/// different key gives different pseudonyms.
+#[test]
+fn test_aircraft_anonymiser() -> Result<(), Box<dyn Error>> {
+    let anonymiser: AircraftAnonymiser = AircraftAnonymiser::new("shared key");
+    let mut anonymised: usize = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(mut message) = line.decode_message() else { continue };
+        let icao: Option<u32> = message.get_icao();
+        let registration: Option<String> = message.get_registration().map(str::to_string);
+        anonymiser.anonymise(&mut message);
+        assert_eq!(message.get_icao(), icao.map(|icao| anonymiser.pseudonym_icao(icao)), "{}", line);
+        let expected: Option<String> = registration.as_deref().map(|registration| anonymiser.pseudonym_registration(registration));
+        assert_eq!(message.get_registration(), expected.as_deref(), "{}", line);
+        assert_eq!(message.to_json()?.decode_message()?.to_json()?, message.to_json()?);
+        if icao.is_some() || registration.as_deref().is_some_and(|registration| !registration.is_empty()) {
+            anonymised += 1;
+        }
+    }
+    assert!(anonymised > 0);
+    let pseudonyms: Vec<(Option<u32>, Option<String>)> = MessageFamily::ALL.into_iter()
+        .filter(|family| *family != MessageFamily::Uat)
+        .map(|family| {
+            let mut example: AcarsVdlm2Message = AcarsVdlm2Message::example(family);
+            anonymiser.anonymise(&mut example);
+            (example.get_icao(), example.get_registration().map(str::to_string))
+        })
+        .collect();
+    let registration: String = anonymiser.pseudonym_registration("N123AB");
+    assert_ne!(registration, "N123AB");
+    assert_eq!(registration.len(), 6);
+    assert!(registration.starts_with(|character: char| character.is_ascii_uppercase()));
+    for (icao, example_registration) in &pseudonyms {
+        assert_eq!(example_registration.as_deref(), Some(registration.as_str()));
+        assert!(icao.is_none_or(|icao| icao == anonymiser.pseudonym_icao(0xA1B2C3)));
+    }
+    let mut uat: AcarsVdlm2Message = AcarsVdlm2Message::example(MessageFamily::Uat);
+    anonymiser.anonymise(&mut uat);
+    assert_eq!(uat.get_icao(), Some(anonymiser.pseudonym_icao(0xA1B2C3)));
+    assert_eq!(anonymiser.pseudonym_icao_hex("A1B2C3"), format!("{:06X}", anonymiser.pseudonym_icao(0xA1B2C3)));
+    let mut dashed: String = anonymiser.pseudonym_registration("VHABC");
+    dashed.insert(2, '-');
+    assert_eq!(anonymiser.pseudonym_registration("VH-ABC"), dashed);
+    assert_ne!(AircraftAnonymiser::new("other key").pseudonym_icao(0xA1B2C3), anonymiser.pseudonym_icao(0xA1B2C3));
+    Ok(())
+}
+
+/// This test anonymises a VDLM2 and an HFDL sample carrying an ARINC 622 ADS-C block, with an airframe ID tag added
+/// and the free text cleared, since free text is not rewritten.
+/// It validates that the original registration and ICAO address appear nowhere in the anonymised JSON, including the
+/// ARINC 622 air address and the ADS-C airframe ID.
+#[test]
+fn test_aircraft_anonymiser_arinc622() -> Result<(), Box<dyn Error>> {
+    let anonymiser: AircraftAnonymiser = AircraftAnonymiser::new("shared key");
+    let samples: [(MessageType, &str, &str, &str); 2] = [
+        (MessageType::Vdlm2, "/vdl2/avlc/acars", ".N449WN", "A56E0D"),
+        (MessageType::Hfdl, "/hfdl/lpdu/hfnpdu/acars", ".CN-RGC", "0201A3"),
+    ];
+    for (message_type, acars_path, air_addr, icao_hex) in samples {
+        let line: String = combine_files_of_message_type(message_type)?
+            .into_iter()
+            .find(|line| line.contains(&format!(r#""air_addr":"{}""#, air_addr)))
+            .ok_or("no ARINC 622 sample")?;
+        let mut original: Value = serde_json::from_str(&line)?;
+        let acars: &mut Value = original.pointer_mut(acars_path).ok_or("no ACARS block")?;
+        acars["msg_text"] = Value::from("");
+        acars["arinc622"]["adsc"]["tags"].as_array_mut().ok_or("no ADS-C tags")?
+            .push(serde_json::json!({"airframe_id": {"icao_hex": icao_hex}}));
+        let mut message: AcarsVdlm2Message = original.to_string().decode_message()?;
+        assert!(message.to_json()?.contains(icao_hex));
+        anonymiser.anonymise(&mut message);
+        let json: String = message.to_json()?;
+        let registration: &str = air_addr.trim_start_matches('.');
+        assert!(!json.contains(registration), "{}", json);
+        assert!(!json.contains(&registration.replace('-', "")), "{}", json);
+        assert!(!json.to_uppercase().contains(icao_hex), "{}", json);
+        assert!(json.contains(&anonymiser.pseudonym_icao_hex(icao_hex)));
+    }
+    Ok(())
+}
+
+/// This test maps ranges of ICAO addresses, AVLC addresses and HFDL ground station ids through the keyed
+/// pseudonyms of an `AircraftAnonymiser` and a `StationRedactor`.
+/// It validates that no two inputs share a pseudonym, that addresses keep their width, and that pseudonyms under a
+/// different key do not match.
+#[test]
+fn test_pseudonym_permutations() {
+    let anonymiser: AircraftAnonymiser = AircraftAnonymiser::new("shared key");
+    let redactor: StationRedactor = StationRedactor::new("test salt");
+    let ids: HashSet<u16> = (0..0x1000).map(|id| redactor.pseudonym_id(id)).collect();
+    assert_eq!(ids.len(), 0x1000);
+    let icaos: HashSet<u32> = (0xA00000..0xA01000).map(|icao| anonymiser.pseudonym_icao(icao)).collect();
+    assert_eq!(icaos.len(), 0x1000);
+    assert!(icaos.iter().all(|icao| *icao <= 0xFF_FFFF));
+    let addresses: HashSet<String> = (0x102000..0x103000).map(|address| redactor.pseudonym_address(&format!("{:06X}", address))).collect();
+    assert_eq!(addresses.len(), 0x1000);
+    assert!(addresses.iter().all(|address| address.len() == 6));
+    let other: AircraftAnonymiser = AircraftAnonymiser::new("shared keY");
+    let matching: usize = (0xA00000..0xA00100).filter(|icao| other.pseudonym_icao(*icao) == anonymiser.pseudonym_icao(*icao)).count();
+    assert!(matching < 4, "{} of 256 pseudonyms match under another key", matching);
+}
+
+/// This test classifies the sender of every message in the sample corpus, and filters the corpus down to
+/// air-originated traffic.
+/// It validates that the classification agrees with the family specific direction of VDLM2 links and HFDL frames,
+/// that every family has messages from aircraft, that ACARS, VDLM2 and HFDL also have messages from ground stations,
+/// that UAT TIS-B and surface vehicles are classified by their address qualifier, and that `SourceFilter` drops
+/// exactly the messages not sent by an aircraft.
+#[test]
+fn test_source_kind() -> Result<(), Box<dyn Error>> {
+    let mut counts: HashMap<(MessageFamily, SourceKind), usize> = HashMap::new();
+    let filter: SourceFilter = SourceFilter::only(&[SourceKind::Aircraft]);
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(mut message) = line.decode_message() else { continue };
+        let kind: SourceKind = message.source_kind();
+        *counts.entry((message.get_family(), kind)).or_default() += 1;
+        match &message {
+            AcarsVdlm2Message::Vdlm2Message(vdlm2) => if let Some(link) = vdlm2.get_link() {
+                assert_eq!(kind.is_ground_station(), link.uplink, "{}", line);
+            },
+            AcarsVdlm2Message::HfdlMessage(hfdl) => match hfdl.is_uplink() {
+                Some(uplink) => assert_eq!(kind.is_ground_station(), uplink, "{}", line),
+                None => assert_eq!(kind, SourceKind::Unknown, "{}", line),
+            },
+            AcarsVdlm2Message::UatMessage(UatMessage::Adsb(adsb)) => match adsb.address_qualifier.as_deref() {
+                Some("tisb_icao" | "tisb_trackfile") => assert_eq!(kind, SourceKind::GroundStation),
+                Some("vehicle") => assert_eq!(kind, SourceKind::Unknown),
+                _ => assert_eq!(kind, SourceKind::Aircraft),
+            },
+            _ => {}
+        }
+        assert_eq!(filter.apply(&mut message) == TransformOutcome::Forward, kind.is_aircraft());
+    }
+    for family in MessageFamily::ALL {
+        assert!(counts.get(&(family, SourceKind::Aircraft)).is_some_and(|count| *count > 0), "{:?}", family);
+    }
+    for family in [MessageFamily::Acars, MessageFamily::Vdlm2, MessageFamily::Hfdl] {
+        assert!(counts.get(&(family, SourceKind::GroundStation)).is_some_and(|count| *count > 0), "{:?}", family);
+    }
+    let uplink: String = format!("+{}{};", "00".repeat(8), "00".repeat(424));
+    assert_eq!(uplink.decode_message()?.source_kind(), SourceKind::GroundStation);
+    let tisb: AdsbJsonMessage = r#"{"hex":"~2d6e2b","type":"tisb_other"}"#.parse()?;
+    let non_transponder: AdsbJsonMessage = r#"{"hex":"a1b2c3","type":"adsb_icao_nt"}"#.parse()?;
+    assert_eq!((tisb.source_kind(), non_transponder.source_kind()), (SourceKind::GroundStation, SourceKind::Unknown));
+    Ok(())
+}
+
+/// A reader handing out its bytes a few at a time, as a socket does.
+struct TrickleReader<'a> {
+    bytes: &'a [u8],
+    chunk: usize,
+}
+
+impl std::io::Read for TrickleReader<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let read: usize = self.chunk.min(buffer.len()).min(self.bytes.len());
+        buffer[..read].copy_from_slice(&self.bytes[..read]);
+        self.bytes = &self.bytes[read..];
+        Ok(read)
+    }
+}
+
+/// This test streams the sample corpus written back to back without newlines, as a JSON array and mixed with legacy
+/// CSV lines, in chunks of different sizes, and splits objects holding braces and escaped quotes in their strings.
+/// It validates that every message decodes the same as it does on its own line wherever the chunks end, that braces
+/// and quotes inside strings do not split an object, and that a segment larger than the limit is returned to fail
+/// decoding rather than holding back the messages after it, and that an object cut off outside a string in newline
+/// delimited input is returned on its own so the lines after it still decode.
+#[test]
+fn test_stream_decoder() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<String> = combine_files_of_message_type(MessageType::All)?
+        .into_iter()
+        .filter(|line| line.starts_with('{') && line.decode_message().is_ok())
+        .take(200)
+        .collect();
+    let concatenated: String = messages.concat();
+    let array: String = format!("[\n{}\n]\n", messages.join(",\n"));
+    for input in [&concatenated, &array] {
+        for chunk in [1, 7, 4096] {
+            let decoded: Vec<AcarsVdlm2Message> = StreamDecoder::new(TrickleReader { bytes: input.as_bytes(), chunk })
+                .map(|result| result.map(|decoded| decoded.map_err(|failed| failed.error)))
+                .collect::<Result<Result<_, _>, _>>()??;
+            assert_eq!(decoded.len(), messages.len(), "chunk {}", chunk);
+            for (message, line) in decoded.iter().zip(&messages) {
+                assert_eq!(message.to_json()?, line.decode_message()?.to_json()?);
+            }
+        }
+    }
+
+    let tricky: &str = r#"{"freq":131.55,"mode":"2","text":"A {\"}\" B }{"}{"freq":131.725,"text":"\\"}"#;
+    let mut splitter: JsonSplitter = JsonSplitter::new();
+    let segments: Vec<Vec<u8>> = tricky.bytes().flat_map(|byte| splitter.push(&[byte])).collect();
+    assert_eq!(segments, vec![br#"{"freq":131.55,"mode":"2","text":"A {\"}\" B }{"}"#.to_vec(), br#"{"freq":131.725,"text":"\\"}"#.to_vec()]);
+    assert_eq!((splitter.buffered(), splitter.finish()), (0, None));
+
+    let mixed: String = format!("{}\n#acarsdec,1,2\r\n{}{}", messages[0], messages[1], messages[2]);
+    let segments: Vec<Vec<u8>> = splitter.push(mixed.as_bytes());
+    assert_eq!(segments, vec![messages[0].as_bytes().to_vec(), b"#acarsdec,1,2".to_vec(), messages[1].as_bytes().to_vec(), messages[2].as_bytes().to_vec()]);
+
+    let unterminated: String = format!("{{\"text\":\"{}", "x".repeat(64));
+    let input: String = format!("{}\n{}", unterminated, messages[0]);
+    let decoded: Vec<Result<AcarsVdlm2Message, _>> = StreamDecoder::new(input.as_bytes()).source("bridge")
+        .collect::<Result<_, _>>()?;
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded[0].as_ref().err().map(|failed| (failed.raw.as_str(), failed.source.as_deref())), Some((unterminated.as_str(), Some("bridge"))));
+    assert_eq!(decoded[1].as_ref().ok().map(|message| message.to_json()).transpose()?, Some(messages[0].decode_message()?.to_json()?));
+    let mut splitter: JsonSplitter = JsonSplitter::new().max_segment_bytes(32);
+    assert_eq!(splitter.push(unterminated.as_bytes()), vec![unterminated.as_bytes().to_vec()]);
+    assert_eq!(splitter.push(messages[0].as_bytes()), vec![messages[0].as_bytes().to_vec()]);
+
+    let truncated: &str = r#"{"freq":131.55,"channel":"#;
+    let ndjson: String = format!("{}\n{}\n{}\n{}\n", messages[0], truncated, messages[1], messages[2]);
+    for chunk in [1, 4096] {
+        let mut splitter: JsonSplitter = JsonSplitter::new();
+        let segments: Vec<Vec<u8>> = ndjson.as_bytes().chunks(chunk).flat_map(|bytes| splitter.push(bytes)).collect();
+        assert_eq!(segments, [&messages[0], truncated, &messages[1], &messages[2]].map(|line| line.as_bytes().to_vec()), "chunk {}", chunk);
+        assert_eq!((splitter.buffered(), splitter.finish()), (0, None));
+    }
+    Ok(())
+}
+
+/// Stamps the proxy details over whatever `app` block a message has, for `test_provenance`.
+struct ProxyStamp;
+
+impl Transform for ProxyStamp {
+    fn name(&self) -> &str {
+        "proxy_stamp"
+    }
+
+    fn apply(&self, message: &mut AcarsVdlm2Message) -> TransformOutcome {
+        message.set_proxy_details("acars_router", "1.0.12");
+        TransformOutcome::Forward
+    }
+}
+
+/// This test runs tagged sample messages through two processes in turn, each with its own pipeline, and reads the
+/// provenance back after each.
+/// It validates that every transform that ran is recorded in order with the time it ran and the fields it changed,
+/// that the transform which modified the `app` details can be found, that a drop is recorded with its reason, that
+/// the provenance survives being forwarded as `x_provenance`, and that `strip` removes it.
+#[test]
+fn test_provenance() -> Result<(), Box<dyn Error>> {
+    let providers: Providers = Providers::new().with_clock(FixedClock(1700000000.0));
+    let mut first: TransformPipeline = TransformPipeline::new();
+    first.add(StationRedactor::new("salt"));
+    first.add(DropUnlabelled);
+    let mut second: TransformPipeline = TransformPipeline::new();
+    second.add(ProxyStamp);
+    let mut dropped: usize = 0;
+    for line in combine_files_of_message_type(MessageType::Acars)? {
+        let Ok(message) = line.decode_message() else { continue };
+        let mut tagged: TaggedMessage = TaggedMessage::from(message);
+        let had_station: bool = tagged.message.get_station_name().is_some();
+        if let TransformOutcome::Drop(reason) = providers.scope(|| tagged.apply(&first)) {
+            let steps: &[ProvenanceStep] = &tagged.provenance.as_ref().expect("provenance is recorded").steps;
+            assert_eq!(steps.last().and_then(|step| step.dropped.as_deref()), Some(reason.as_str()));
+            dropped += 1;
+            continue;
+        }
+        let mut forwarded: TaggedMessage = serde_json::from_str(&tagged.to_json()?)?;
+        assert_eq!(forwarded.provenance, tagged.provenance);
+        assert_eq!(providers.scope(|| forwarded.apply(&second)), TransformOutcome::Forward);
+        let provenance: &Provenance = forwarded.provenance.as_ref().expect("provenance is recorded");
+        assert_eq!(provenance.transforms(), vec!["station_redactor", "drop_unlabelled", "proxy_stamp"]);
+        assert!(provenance.steps.iter().all(|step| step.applied_at == 1700000000.0 && step.dropped.is_none()));
+        assert_eq!(provenance.last_change("station_id").is_some(), had_station, "{}", line);
+        assert!(provenance.steps[1].changed.is_empty());
+        assert_eq!(provenance.last_change("app").map(|step| step.transform.as_str()), Some("proxy_stamp"));
+        assert!(provenance.last_change("app.acars_router").is_none());
+        assert!(!forwarded.strip().to_json()?.contains("x_provenance"));
+    }
+    assert!(dropped > 0);
+    Ok(())
+}
+
+/// This test feeds the ACARS and VDLM2 sample captures into a `RateTracker` at a steady two and one messages a second
+/// respectively, then lets the feed go quiet.
+/// It validates that the averages settle at the rates the messages arrived at, in total and per family, that bytes
+/// are averaged the same way, that each station's counts add up to the total, that the averages halve every half life
+/// once messages stop, and that snapshots serialise.
+#[test]
+fn test_rate_tracker() -> Result<(), Box<dyn Error>> {
+    let acars: Vec<String> = combine_files_of_message_type(MessageType::Acars)?;
+    let vdlm2: Vec<String> = combine_files_of_message_type(MessageType::Vdlm2)?;
+    let mut tracker: RateTracker = RateTracker::new().half_life_secs(30.0);
+    let mut bytes: usize = 0;
+    for second in 0..600 {
+        for (line, offset) in [(&acars[(second * 2) % acars.len()], 0.0), (&acars[(second * 2 + 1) % acars.len()], 0.5), (&vdlm2[second % vdlm2.len()], 0.25)] {
+            let Ok(message) = line.decode_message() else { continue };
+            tracker.record_at(&message, line.len(), second as f64 + offset);
+            bytes += line.len();
+        }
+    }
+    let snapshot: RateSnapshot = tracker.snapshot_at(600.0);
+    let acars_rate: Rate = snapshot.by_family[&MessageFamily::Acars];
+    let vdlm2_rate: Rate = snapshot.by_family[&MessageFamily::Vdlm2];
+    assert!((acars_rate.messages_per_sec / vdlm2_rate.messages_per_sec - acars_rate.messages as f64 / vdlm2_rate.messages as f64).abs() < 0.1);
+    assert!((snapshot.total.messages_per_sec - snapshot.total.messages as f64 / 600.0).abs() < 0.1);
+    assert!((snapshot.total.bytes_per_sec - bytes as f64 / 600.0).abs() < bytes as f64 / 600.0 * 0.1);
+    assert_eq!(snapshot.total.bytes, bytes as u64);
+    assert_eq!(snapshot.by_family.values().map(|rate| rate.messages).sum::<u64>(), snapshot.total.messages);
+    assert!(snapshot.by_station.values().map(|rate| rate.messages).sum::<u64>() <= snapshot.total.messages);
+    assert!(!snapshot.by_station.is_empty());
+
+    let later: RateSnapshot = tracker.snapshot_at(630.0);
+    assert!((later.total.messages_per_sec * 2.0 - snapshot.total.messages_per_sec).abs() < 1e-9);
+    assert_eq!(later.total.messages, snapshot.total.messages);
+    let read_back: RateSnapshot = serde_json::from_str(&serde_json::to_string(&later)?)?;
+    assert_eq!((read_back.total.messages, read_back.by_station.len()), (later.total.messages, later.by_station.len()));
+    assert!((read_back.total.messages_per_sec - later.total.messages_per_sec).abs() < 1e-9);
+    tracker.reset();
+    assert_eq!(tracker.snapshot_at(630.0), RateSnapshot { at: 630.0, ..Default::default() });
+    Ok(())
+}
+
+/// This test runs the decoded sample corpus through pipelines requiring a station, a time and an app block, in turn
+/// and all together.
+/// It validates that a message is only dropped when it lacks a required field, that each missing field has its own
+/// reason, that requiring everything drops a message for the first field it lacks, and that requiring nothing lets
+/// every message through.
+#[test]
+fn test_required_fields() -> Result<(), Box<dyn Error>> {
+    let mut dropped: HashMap<String, usize> = HashMap::new();
+    let mut strict: TransformPipeline = TransformPipeline::new();
+    strict.add(RequiredFields::all());
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(mut message) = line.decode_message() else { continue };
+        let has_station: bool = message.get_station_name().is_some_and(|station| !station.trim().is_empty());
+        let checks: [(RequiredFields, bool, RequiredField); 3] = [
+            (RequiredFields::new().station(true), has_station, RequiredField::Station),
+            (RequiredFields::new().time(true), message.get_time().is_some(), RequiredField::Time),
+            (RequiredFields::new().app(true), message.get_app_details().is_some(), RequiredField::App),
+        ];
+        let mut missing: Vec<RequiredField> = Vec::new();
+        for (policy, present, field) in checks {
+            match policy.apply(&mut message) {
+                TransformOutcome::Forward => assert!(present, "{}", line),
+                TransformOutcome::Drop(reason) => {
+                    assert!(!present, "{}", line);
+                    assert_eq!(reason, field.reason());
+                    missing.push(field);
+                }
+            }
+        }
+        assert_eq!(RequiredFields::all().missing(&message), missing);
+        match strict.apply(&mut message) {
+            TransformOutcome::Forward => assert!(missing.is_empty()),
+            TransformOutcome::Drop(reason) => *dropped.entry(reason).or_default() += 1,
+        }
+        assert_eq!(RequiredFields::new().apply(&mut message), TransformOutcome::Forward);
+    }
+    assert!(dropped.keys().all(|reason| ["missing_station", "missing_time", "missing_app"].contains(&reason.as_str())));
+    assert!(!dropped.is_empty());
+    let configured: RequiredFields = serde_json::from_str(r#"{"app":true}"#)?;
+    assert_eq!(configured, RequiredFields::new().app(true));
+    Ok(())
+}
+
+/// This test decodes every sample line with `decode_direct`, and streams the sample corpus through a `StreamDecoder`
+/// with and without direct decoding.
+/// It validates that direct decoding gives exactly the same message or error as `decode_message` for every line,
+/// including legacy CSV lines, raw UAT frames and objects that repeat a key, and that it records the same warnings.
+#[test]
+fn test_decode_direct() -> Result<(), Box<dyn Error>> {
+    let lines: Vec<String> = combine_files_of_message_type(MessageType::All)?;
+    for line in &lines {
+        match (decode_direct(line.as_bytes()), line.decode_message()) {
+            (Ok(direct), Ok(standard)) => assert_eq!(direct.to_json()?, standard.to_json()?),
+            (Err(direct), Err(standard)) => assert_eq!(direct.to_string(), standard.to_string()),
+            (direct, standard) => panic!("{} decoded as {:?} and {:?}", line, direct.is_ok(), standard.is_ok()),
+        }
+    }
+    let input: String = lines.iter().filter(|line| line.starts_with('{')).map(|line| format!("{}\n", line)).collect();
+    let standard: Vec<Result<AcarsVdlm2Message, FailedDecode>> = StreamDecoder::new(input.as_bytes()).collect::<Result<_, _>>()?;
+    let direct: Vec<Result<AcarsVdlm2Message, FailedDecode>> = StreamDecoder::new(input.as_bytes()).direct_decode(true).collect::<Result<_, _>>()?;
+    assert_eq!(standard.len(), direct.len());
+    for (standard, direct) in standard.iter().zip(&direct) {
+        match (standard, direct) {
+            (Ok(standard), Ok(direct)) => assert_eq!(standard.to_json()?, direct.to_json()?),
+            (Err(standard), Err(direct)) => assert_eq!(standard.error, direct.error),
+            _ => panic!("direct decoding disagreed with decoding through a Value"),
+        }
+    }
+
+    let repeated: &str = r#"{"freq":131.55,"channel":"2","label":"H1","label":"Q0"}"#;
+    let mut direct_context: ProcessingContext = ProcessingContext::new();
+    let mut standard_context: ProcessingContext = ProcessingContext::new();
+    let message: AcarsVdlm2Message = direct_context.capture(ProcessingStage::Decode, || decode_direct(repeated.as_bytes()))?;
+    standard_context.capture(ProcessingStage::Decode, || repeated.decode_message())?;
+    assert_eq!(message.get_label(), Some("Q0"));
+    assert_eq!(direct_context.warnings(), standard_context.warnings());
+    assert_eq!(direct_context.warnings().len(), 1);
+    Ok(())
+}
+
+/// This test decodes the sample corpus through `str::parse` and the deprecated `ReceivedMessage` and `DecodedMessage`
+/// names, and wraps each family with `into()`.
+/// It validates that the old names are the same type as `AcarsVdlm2Message`, that parsing gives the same result as
+/// `decode_message`, and that wrapping a family gives the same variant decoding would.
+#[test]
+#[allow(deprecated)]
+fn test_message_naming_consistency() -> Result<(), Box<dyn Error>> {
+    use acars_vdlm2_parser::{DecodedMessage, ReceivedMessage};
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let (received, decoded): (ReceivedMessage, DecodedMessage) = match (line.parse::<ReceivedMessage>(), line.decode_message()) {
+            (Ok(received), Ok(decoded)) => (received, decoded),
+            (Err(parsed), Err(standard)) => {
+                assert_eq!(parsed.to_string(), standard.to_string());
+                continue;
+            }
+            _ => panic!("{} parsed differently to decode_message", line),
+        };
+        let current: AcarsVdlm2Message = received.clone();
+        assert_eq!(current.to_string()?, decoded.to_string()?);
+        let wrapped: AcarsVdlm2Message = match current {
+            AcarsVdlm2Message::AcarsMessage(message) => message.into(),
+            AcarsVdlm2Message::Vdlm2Message(message) => message.into(),
+            AcarsVdlm2Message::HfdlMessage(message) => message.into(),
+            AcarsVdlm2Message::UatMessage(message) => message.into(),
+        };
+        assert_eq!(wrapped.get_family(), received.get_family());
+        assert_eq!(wrapped.to_string()?, received.to_string()?);
+    }
+    assert!("not a message".parse::<AcarsVdlm2Message>().is_err());
+    Ok(())
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "adsb-json")]
+use std::error::Error;
+use serde_json::Value;
+use acars_vdlm2_parser::adsb_json::{roundtrip_check, AdsbJsonFile, AdsbJsonMessage, AdsbLastPosition, BaroAltitude};
+use acars_vdlm2_parser::roundtrip::RoundtripReport;
+
+/// A sanitised `aircraft.json` written by readsb with an aircraft database loaded, covering an ADS-B aircraft with
+/// every field, one on the ground, an MLAT aircraft, a TIS-B track and one only known by its last position.
+const AIRCRAFT_JSON: &str = r#"{"now":1700000000.123,"messages":98765432,"aircraft":[
+{"hex":"a1b2c3","type":"adsb_icao","flight":"AB0123  ","r":"N123AB","t":"B738","desc":"BOEING 737-800","ownOp":"EXAMPLE AIRLINES","year":"2008","dbFlags":0,"alt_baro":37000,"alt_geom":37650,"gs":452.3,"ias":262,"tas":462,"mach":0.792,"oat":-52,"tat":-24,"wd":275,"ws":48,"track":87.45,"track_rate":-0.03,"roll":-0.18,"mag_heading":72.95,"true_heading":88.51,"baro_rate":-64,"geom_rate":-32,"squawk":"2317","emergency":"none","category":"A3","nav_qnh":1013.6,"nav_altitude_mcp":36992,"nav_altitude_fms":37008,"nav_heading":73.12,"nav_modes":["autopilot","vnav","lnav","tcas"],"lat":47.446548,"lon":-122.301712,"nic":8,"rc":186,"seen_pos":0.3,"r_dst":12.345,"r_dir":131.2,"version":2,"nic_baro":1,"nac_p":9,"nac_v":1,"sil":3,"sil_type":"perhour","gva":2,"sda":2,"alert":0,"spi":0,"mlat":[],"tisb":[],"messages":4521,"seen":0.1,"rssi":-18.4},
+{"hex":"a2c3d4","type":"adsb_icao","flight":"AB0456  ","alt_baro":"ground","gs":12.1,"true_heading":165.94,"squawk":"1200","category":"A3","lat":47.451,"lon":-122.309,"nic":8,"rc":186,"seen_pos":1.2,"version":2,"mlat":[],"tisb":[],"messages":1203,"seen":0.8,"rssi":-9.7},
+{"hex":"a3d4e5","type":"mlat","alt_baro":11275,"gs":318.0,"track":244.1,"calc_track":245,"baro_rate":-1344,"lat":47.621,"lon":-122.025,"nic":0,"rc":0,"seen_pos":2.9,"mlat":["gs","track","baro_rate","lat","lon","nic","rc","nac_p","nac_v","sil","sil_type"],"tisb":[],"messages":221,"seen":2.9,"rssi":-27.3},
+{"hex":"~2d6e2b","type":"tisb_other","alt_baro":3500,"gs":105.0,"track":12.0,"lat":47.302,"lon":-122.214,"nic":0,"rc":0,"seen_pos":6.2,"mlat":[],"tisb":["altitude","gs","track","lat","lon"],"messages":4,"seen":6.2,"rssi":-30.1},
+{"hex":"a4e5f6","type":"adsb_icao","flight":"AB0789  ","alt_baro":24000,"squawk":"7700","emergency":"general","lastPosition":{"lat":48.1,"lon":-121.9,"nic":7,"rc":371,"seen_pos":75.4},"rr_lat":48.0,"rr_lon":-122.0,"gpsOkBefore":1699999900.5,"gpsOkLat":48.12,"gpsOkLon":-121.88,"acas_ra":{"utc":"12:01:05.1","unix_timestamp":1699999265.1,"df_type":16,"full_bytes":"80e19717582a0d","advisory":"Climb","advisory_complement":"","bytes":"e19717582a","ARA":"1000000","RAT":"0","MTE":"0","RAC":"","TTI":"0"},"mlat":[],"tisb":[],"messages":88,"seen":40.1,"rssi":-31.9}
+]}"#;
+
+/// This test decodes a readsb `aircraft.json` file and writes it back out.
+/// It validates that every field of every aircraft survives the round trip, and that the decoded fields match the
+/// input, including `ground` altitudes, non-ICAO addresses, MLAT fields and the last position block.
+#[test]
+fn test_adsb_json_roundtrip() -> Result<(), Box<dyn Error>> {
+    let report: RoundtripReport = roundtrip_check(AIRCRAFT_JSON);
+    assert!(report.is_lossless(), "{:?} {:?}", report.error, report.differences);
+    let file: AdsbJsonFile = AIRCRAFT_JSON.parse()?;
+    assert_eq!(file.messages, Some(98765432));
+    assert_eq!(file.aircraft.len(), 5);
+    let reparsed: AdsbJsonFile = file.to_json()?.parse()?;
+    assert_eq!(reparsed, file);
+
+    let full: &AdsbJsonMessage = &file.aircraft[0];
+    assert_eq!(full.get_icao(), Some(0xA1B2C3));
+    assert_eq!(full.get_flight(), Some("AB0123"));
+    assert_eq!(full.get_registration(), Some("N123AB"));
+    assert_eq!(full.own_op.as_deref(), Some("EXAMPLE AIRLINES"));
+    assert_eq!(full.get_altitude(), Some(37000));
+    assert_eq!(full.nav_modes.as_deref(), Some(&["autopilot".to_string(), "vnav".to_string(), "lnav".to_string(), "tcas".to_string()][..]));
+    assert_eq!(full.get_position(), Some((47.446548, -122.301712)));
+    assert!(!full.is_on_ground() && !full.is_mlat() && !full.is_non_icao());
+
+    let ground: &AdsbJsonMessage = &file.aircraft[1];
+    assert!(ground.is_on_ground());
+    assert_eq!(ground.alt_baro, Some(BaroAltitude::Text("ground".to_string())));
+    assert_eq!(ground.get_altitude(), None);
+
+    assert!(file.aircraft[2].is_mlat());
+    assert_eq!(file.aircraft[2].source_type.as_deref(), Some("mlat"));
+
+    let tisb: &AdsbJsonMessage = &file.aircraft[3];
+    assert!(tisb.is_non_icao());
+    assert_eq!(tisb.get_icao(), None);
+    assert_eq!(tisb.tisb.as_ref().map(Vec::len), Some(5));
+
+    let stale: &AdsbJsonMessage = &file.aircraft[4];
+    assert_eq!(stale.get_position(), None);
+    assert_eq!(stale.last_position, Some(AdsbLastPosition { lat: 48.1, lon: -121.9, nic: Some(7), rc: Some(371), seen_pos: Some(75.4) }));
+    assert_eq!(stale.emergency.as_deref(), Some("general"));
+    assert_eq!(stale.acas_ra.as_ref().and_then(|ra| ra.get("advisory")), Some(&Value::from("Climb")));
+    Ok(())
+}
+
+/// This test decodes aircraft entries with only an address, with fields this library does not model, and with a
+/// `null` altitude, as older dump1090 builds and newer readsb builds write.
+/// It validates that each decodes, that unknown fields are dropped without an error, and that an entry without an
+/// address is rejected.
+#[test]
+fn test_adsb_json_tolerance() -> Result<(), Box<dyn Error>> {
+    let minimal: AdsbJsonMessage = r#"{"hex":"a1b2c3"}"#.parse()?;
+    assert_eq!(minimal, AdsbJsonMessage { hex: "a1b2c3".to_string(), ..Default::default() });
+    assert_eq!(minimal.to_json()?, r#"{"hex":"a1b2c3"}"#);
+    let unknown: AdsbJsonMessage = r#"{"hex":"a1b2c3","alt_baro":null,"future_field":{"nested":[1,2]},"flight":"        "}"#.parse()?;
+    assert_eq!(unknown.alt_baro, None);
+    assert_eq!(unknown.get_flight(), None);
+    assert!(!unknown.to_json()?.contains("future_field"));
+    let report: RoundtripReport = roundtrip_check(r#"{"now":1.0,"aircraft":[{"hex":"a1b2c3","future_field":true}]}"#);
+    assert_eq!(report.differences.iter().map(|difference| difference.path.as_str()).collect::<Vec<&str>>(), vec!["aircraft.0.future_field"]);
+    assert!(r#"{"alt_baro":1000}"#.parse::<AdsbJsonMessage>().is_err());
+    let empty: AdsbJsonFile = r#"{"now":1700000000}"#.parse()?;
+    assert!(empty.aircraft.is_empty());
+    Ok(())
+}
@@ -0,0 +1,218 @@
+#![cfg(all(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use serde_json::Value;
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+use acars_vdlm2_parser::adsc::{Adsc, AdscAirframeId, AdscCancelContract, AdscContractGroup, AdscModulus, AdscTag, AdscVspdThreshold};
+use acars_vdlm2_parser::adsc_contract::{ContractEvent, ContractKind, ContractReport, ContractState, ContractTracker};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// Recursively collects every `adsc` block found in a decoded JSON value.
+fn collect_adsc(value: &Value, found: &mut Vec<Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                match key.as_str() {
+                    "adsc" => found.push(child.clone()),
+                    _ => collect_adsc(child, found),
+                }
+            }
+        }
+        Value::Array(children) => children.iter().for_each(|child| collect_adsc(child, found)),
+        _ => {}
+    }
+}
+
+/// Converts every number to an `f64` so values compare equal regardless of how many decimal places were written,
+/// which matters when serde_json's `arbitrary_precision` feature is enabled.
+fn normalise_numbers(value: Value) -> Value {
+    match value {
+        Value::Number(number) => number.as_f64().map(Value::from).unwrap_or(Value::Null),
+        Value::Array(children) => Value::Array(children.into_iter().map(normalise_numbers).collect()),
+        Value::Object(map) => Value::Object(map.into_iter().map(|(key, child)| (key, normalise_numbers(child))).collect()),
+        other => other,
+    }
+}
+
+/// This test extracts every ADS-C block from the vdlm2 and hfdl sample files.
+/// It validates that every tag and contract group is decoded to a typed variant rather than falling back to `Other`,
+/// and that serialising the typed `Adsc` produces exactly the JSON that libacars output.
+#[test]
+fn test_adsc_typed_decoding() -> Result<(), Box<dyn Error>> {
+    let mut blocks: Vec<Value> = Vec::new();
+    for message_type in [MessageType::Vdlm2, MessageType::Hfdl] {
+        for line in combine_files_of_message_type(message_type)? {
+            if let Ok(value) = serde_json::from_str::<Value>(&line) {
+                collect_adsc(&value, &mut blocks);
+            }
+        }
+    }
+    assert!(!blocks.is_empty());
+    for block in blocks {
+        let adsc: Adsc = serde_json::from_value(block.clone())?;
+        assert_eq!(adsc.other_tags().count(), 0, "Untyped ADS-C tags in {}", block);
+        for tag in &adsc.tags {
+            if let AdscTag::PeriodicContractReq(request) | AdscTag::EventContractReq(request) = tag {
+                assert!(!request.groups.iter().any(|group| matches!(group, AdscContractGroup::Other(_))),
+                        "Untyped ADS-C contract groups in {}", block);
+            }
+        }
+        assert_eq!(normalise_numbers(serde_json::to_value(&adsc)?), normalise_numbers(block));
+    }
+    Ok(())
+}
+
+/// This test decodes the ADS-C contract session in `test_files/vdlm2_9`, which carries the cancellation, emergency,
+/// event and airframe ID tags not seen in the other sample files.
+/// It validates that each of those tags and their contract groups decodes to its typed variant, and that the
+/// emergency and event reports are returned as position reports.
+#[test]
+fn test_adsc_contract_session_tags() -> Result<(), Box<dyn Error>> {
+    let mut tags: Vec<AdscTag> = Vec::new();
+    let mut groups: Vec<AdscContractGroup> = Vec::new();
+    let mut positions: usize = 0;
+    for line in std::fs::read_to_string("test_files/vdlm2_9")?.lines() {
+        let mut blocks: Vec<Value> = Vec::new();
+        collect_adsc(&serde_json::from_str(line)?, &mut blocks);
+        for block in blocks {
+            let adsc: Adsc = serde_json::from_value(block)?;
+            positions += adsc.position_reports().count();
+            for tag in adsc.tags {
+                if let AdscTag::PeriodicContractReq(request) | AdscTag::EventContractReq(request) | AdscTag::EmergencyPeriodicContractReq(request) = &tag {
+                    groups.extend(request.groups.iter().cloned());
+                }
+                tags.push(tag);
+            }
+        }
+    }
+    assert!(tags.contains(&AdscTag::CancelAllContracts {}));
+    assert!(tags.contains(&AdscTag::CancelEmergencyMode {}));
+    assert!(tags.contains(&AdscTag::CancelContract(AdscCancelContract { contract_num: 53 })));
+    assert!(tags.contains(&AdscTag::AirframeId(AdscAirframeId { icao_hex: "A56E0D".to_string() })));
+    assert!(tags.iter().any(|tag| matches!(tag, AdscTag::EmergencyPeriodicContractReq(request) if request.contract_num == 54)));
+    assert_eq!(tags.iter().filter(|tag| matches!(tag, AdscTag::EmergencyBasicReport(_))).count(), 2);
+    assert!(tags.iter().any(|tag| matches!(tag, AdscTag::LateralDevChangeEvent(report) if report.alt == 33980)));
+    assert!(tags.iter().any(|tag| matches!(tag, AdscTag::VspdChangeEvent(report) if report.alt == 35212)));
+    assert!(groups.contains(&AdscContractGroup::ReportWhenVspdExceeds(AdscVspdThreshold { vspd_threshold_ftmin: -2000 })));
+    assert!(groups.contains(&AdscContractGroup::MeteoData(AdscModulus { modulus: 2 })));
+    assert!(groups.contains(&AdscContractGroup::AirframeId(AdscModulus { modulus: 4 })));
+    assert_eq!(positions, 6);
+    Ok(())
+}
+
+/// Whether a report is an event report, and the contract number, type and interval it was annotated with.
+type Annotation = (bool, Option<u16>, Option<ContractKind>, Option<u32>);
+
+/// This test runs the ADS-C contract session in `test_files/vdlm2_9` through a `ContractTracker`.
+/// It validates that each request, acknowledgement and cancellation changes the contract state in order, that
+/// every report is annotated with the contract it answers, its type and its interval, and that no contract is left
+/// open once the ground station cancels all of them.
+#[test]
+fn test_adsc_contract_tracking() -> Result<(), Box<dyn Error>> {
+    let mut tracker: ContractTracker = ContractTracker::new();
+    let mut reports: Vec<ContractReport> = Vec::new();
+    for line in std::fs::read_to_string("test_files/vdlm2_9")?.lines() {
+        reports.extend(tracker.record(&line.decode_message()?));
+        if reports.len() == 2 {
+            let open: Vec<(u16, ContractState)> = tracker.contracts("N449WN", "QXHADS2").map(|contract| (contract.contract_num, contract.state)).collect();
+            assert_eq!(open, vec![(52, ContractState::Established), (53, ContractState::Established)]);
+        }
+    }
+    let annotations: Vec<Annotation> = reports.iter()
+        .map(|report| (report.event, report.contract_num, report.kind, report.interval_secs))
+        .collect();
+    assert_eq!(annotations, vec![
+        (false, Some(52), Some(ContractKind::Periodic), Some(320)),
+        (false, Some(52), Some(ContractKind::Periodic), Some(320)),
+        (true, Some(53), Some(ContractKind::Event), None),
+        (true, Some(53), Some(ContractKind::Event), None),
+        (false, Some(54), Some(ContractKind::EmergencyPeriodic), Some(64)),
+        (false, Some(54), Some(ContractKind::EmergencyPeriodic), Some(64)),
+    ]);
+    assert!(reports.iter().all(|report| report.aircraft == "N449WN" && report.ground_station == "QXHADS2"));
+    let events: Vec<(u16, ContractState)> = tracker.events().iter().map(|event| (event.contract.contract_num, event.contract.state)).collect();
+    assert_eq!(events, vec![
+        (52, ContractState::Requested),
+        (52, ContractState::Established),
+        (53, ContractState::Requested),
+        (53, ContractState::Established),
+        (54, ContractState::Requested),
+        (54, ContractState::Established),
+        (54, ContractState::Cancelled),
+        (53, ContractState::Cancelled),
+        (52, ContractState::Cancelled),
+    ]);
+    assert_eq!(tracker.contracts("N449WN", "QXHADS2").count(), 0);
+    assert_eq!(serde_json::from_str::<Vec<ContractEvent>>(&tracker.to_json()?)?, tracker.take_events());
+    assert!(tracker.events().is_empty());
+    Ok(())
+}
+
+/// This test runs every vdlm2 and hfdl sample message through a `ContractTracker`.
+/// It validates that a contract is only established after it was requested, that a report sent with an
+/// acknowledgement is annotated with an acknowledged contract, and that reports for contracts whose request was not
+/// captured are still returned, without a contract type.
+#[test]
+fn test_adsc_contract_tracking_corpus() -> Result<(), Box<dyn Error>> {
+    let mut tracker: ContractTracker = ContractTracker::new();
+    let mut acknowledged: usize = 0;
+    let mut reports: usize = 0;
+    for message_type in [MessageType::Vdlm2, MessageType::Hfdl] {
+        for line in combine_files_of_message_type(message_type)? {
+            let Ok(message) = line.decode_message() else { continue };
+            let mut blocks: Vec<Value> = Vec::new();
+            collect_adsc(&serde_json::from_str(&line)?, &mut blocks);
+            let acked: Vec<Option<u16>> = blocks.iter()
+                .flat_map(|block| block["tags"].as_array().into_iter().flatten())
+                .filter_map(|tag| tag["ack"]["contract_num"].as_u64())
+                .map(|contract_num| u16::try_from(contract_num).ok())
+                .collect();
+            for report in tracker.record(&message) {
+                if !acked.is_empty() {
+                    assert!(acked.contains(&report.contract_num), "{}", line);
+                    acknowledged += 1;
+                }
+                reports += 1;
+            }
+        }
+    }
+    assert!(acknowledged > 0 && reports > acknowledged);
+    let events: &[ContractEvent] = tracker.events();
+    for (index, event) in events.iter().enumerate() {
+        if event.contract.state == ContractState::Established {
+            assert!(events[..index].iter().any(|earlier| earlier.aircraft == event.aircraft
+                && earlier.ground_station == event.ground_station
+                && earlier.contract.contract_num == event.contract.contract_num
+                && earlier.contract.state == ContractState::Requested));
+        }
+    }
+    Ok(())
+}
+
+/// This test records the first contract request in `test_files/vdlm2_9` and then only unrelated traffic.
+/// It validates that the request stays `Requested` within the request timeout, and that once the message time moves
+/// past it the request is removed and an `Expired` event is recorded.
+#[test]
+fn test_adsc_contract_expiry() -> Result<(), Box<dyn Error>> {
+    let mut tracker: ContractTracker = ContractTracker::new().request_timeout_secs(60.0);
+    let request: AcarsVdlm2Message = std::fs::read_to_string("test_files/vdlm2_9")?
+        .lines()
+        .find(|line| line.contains("periodic_contract_req"))
+        .ok_or("no contract request")?
+        .decode_message()?;
+    let requested: f64 = request.get_time().ok_or("request without a time")?;
+    tracker.record(&request);
+    let later = |secs: f64| format!(r#"{{"freq":131.55,"channel":2,"timestamp":{}}}"#, requested + secs).decode_message();
+    tracker.record(&later(30.0)?);
+    let open: Vec<(u16, ContractState)> = tracker.contracts("N449WN", "QXHADS2").map(|contract| (contract.contract_num, contract.state)).collect();
+    assert_eq!(open, vec![(52, ContractState::Requested)]);
+    tracker.record(&later(61.0)?);
+    assert_eq!(tracker.contracts("N449WN", "QXHADS2").count(), 0);
+    let events: Vec<(u16, ContractState, Option<f64>)> = tracker.take_events().iter()
+        .map(|event| (event.contract.contract_num, event.contract.state, event.time))
+        .collect();
+    assert_eq!(events, vec![(52, ContractState::Requested, Some(requested)), (52, ContractState::Expired, Some(requested + 61.0))]);
+    assert_eq!(tracker.expire(requested + 1_000.0), 0);
+    Ok(())
+}
@@ -0,0 +1,32 @@
+/* Links the C bindings into a C program and decodes a message through them, run by run_tests.sh against both the
+ * static and the shared library. */
+
+#include <stdio.h>
+#include <string.h>
+#include "acars_vdlm2_parser.h"
+
+int main(void) {
+    const char *input = "{\"freq\":131.55,\"channel\":2,\"label\":\"H1\",\"text\":\"HELLO\"}";
+    char *output = NULL;
+    int32_t code = avp_decode_json(input, &output);
+    if (code != AVP_OK || output == NULL || strstr(output, "\"label\":\"H1\"") == NULL) {
+        fprintf(stderr, "decoding a message returned %d: %s\n", code, output ? output : "(null)");
+        return 1;
+    }
+    avp_string_free(output);
+
+    output = NULL;
+    code = avp_decode_raw_frame((const uint8_t *)"not json", 8, &output);
+    if (code <= 0 || output == NULL || avp_error_code_name(code) == NULL) {
+        fprintf(stderr, "decoding invalid input returned %d\n", code);
+        return 1;
+    }
+    avp_string_free(output);
+
+    if (avp_decode_json(NULL, &output) != AVP_ERROR_NULL_POINTER) {
+        fprintf(stderr, "a null input was not refused\n");
+        return 1;
+    }
+    printf("C bindings linked and decoded a message.\n");
+    return 0;
+}
@@ -0,0 +1,128 @@
+#![cfg(all(feature = "capture", feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use uuid::Uuid;
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+use acars_vdlm2_parser::capture::DirectoryTailer;
+use acars_vdlm2_parser::dead_letter::FailedDecode;
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// A directory under the system temporary directory that is removed when dropped.
+struct CaptureDirectory(PathBuf);
+
+impl CaptureDirectory {
+    fn new() -> Self {
+        let path: PathBuf = std::env::temp_dir().join(format!("acars_capture_{}", Uuid::new_v4()));
+        fs::create_dir_all(&path).expect("temporary directory can be created");
+        Self(path)
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for CaptureDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn append(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    OpenOptions::new().create(true).append(true).open(path)?.write_all(contents.as_bytes())?;
+    // Keep modification times apart so the newest file is unambiguous.
+    thread::sleep(Duration::from_millis(20));
+    Ok(())
+}
+
+fn lines_of(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+fn decoded_lines(decoded: Vec<Result<AcarsVdlm2Message, FailedDecode>>) -> Vec<String> {
+    decoded.into_iter()
+        .map(|result| result.map(|message| message.to_json().expect("messages serialise")).unwrap_or_else(|failed| failed.raw))
+        .collect()
+}
+
+/// This test catches up on a directory holding a compressed rotated capture and the live capture, then tails the live
+/// capture through a partial line, a rotation to a new file and a truncation.
+/// It validates that every message written is returned exactly once, in order, and that a line is only returned once
+/// it is finished.
+#[test]
+fn test_directory_tailer() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<String> = combine_files_of_message_type(MessageType::Acars)?
+        .into_iter()
+        .filter_map(|line| line.decode_message().ok())
+        .take(12)
+        .map(|message| message.to_json().expect("messages serialise"))
+        .collect();
+    let directory: CaptureDirectory = CaptureDirectory::new();
+
+    let mut encoder: GzEncoder<File> = GzEncoder::new(File::create(directory.file("acars-1.jsonl.gz"))?, Compression::default());
+    encoder.write_all(lines_of(&messages[0..3]).as_bytes())?;
+    encoder.finish()?;
+    thread::sleep(Duration::from_millis(20));
+    append(&directory.file("acars.jsonl"), &lines_of(&messages[3..5]))?;
+
+    let mut tailer: DirectoryTailer = DirectoryTailer::new(&directory.0).read_existing(true);
+    assert_eq!(decoded_lines(tailer.poll()?), messages[0..5].to_vec());
+    assert_eq!(tailer.current_file(), Some(directory.file("acars.jsonl").as_path()));
+    assert!(tailer.poll()?.is_empty());
+
+    let (first_half, second_half) = messages[6].split_at(messages[6].len() / 2);
+    append(&directory.file("acars.jsonl"), &format!("{}\n{}", messages[5], first_half))?;
+    assert_eq!(decoded_lines(tailer.poll()?), vec![messages[5].clone()]);
+    append(&directory.file("acars.jsonl"), &format!("{}\nnot json\n", second_half))?;
+    assert_eq!(decoded_lines(tailer.poll()?), vec![messages[6].clone(), "not json".to_string()]);
+
+    // The feeder writes a last message to the old file, then rotates to a new one.
+    append(&directory.file("acars.jsonl"), &lines_of(&messages[7..8]))?;
+    append(&directory.file("acars-2.jsonl"), &lines_of(&messages[8..10]))?;
+    assert_eq!(decoded_lines(tailer.poll()?), messages[7..10].to_vec());
+    assert_eq!(tailer.current_file(), Some(directory.file("acars-2.jsonl").as_path()));
+
+    fs::write(directory.file("acars-2.jsonl"), lines_of(&messages[10..11]))?;
+    assert_eq!(decoded_lines(tailer.poll()?), messages[10..11].to_vec());
+
+    let mut tailer: DirectoryTailer = DirectoryTailer::new(&directory.0);
+    assert!(tailer.poll()?.is_empty());
+    append(&directory.file("acars-2.jsonl"), &lines_of(&messages[11..12]))?;
+    assert_eq!(decoded_lines(tailer.poll()?), messages[11..12].to_vec());
+    Ok(())
+}
+
+/// This test tails a capture written by a bridge that puts JSON objects back to back without newlines, flushing part
+/// way through an object.
+/// It validates that each object is returned once it is finished, and that a capture read whole is split the same way.
+#[test]
+fn test_directory_tailer_concatenated() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<String> = combine_files_of_message_type(MessageType::Acars)?
+        .into_iter()
+        .filter_map(|line| line.decode_message().ok())
+        .take(4)
+        .map(|message| message.to_json().expect("messages serialise"))
+        .collect();
+    let directory: CaptureDirectory = CaptureDirectory::new();
+    append(&directory.file("acars.json"), &messages[0..2].concat())?;
+
+    let mut tailer: DirectoryTailer = DirectoryTailer::new(&directory.0).read_existing(true);
+    assert_eq!(decoded_lines(tailer.poll()?), messages[0..2].to_vec());
+    let (first_half, second_half) = messages[3].split_at(messages[3].len() / 2);
+    append(&directory.file("acars.json"), &format!("{}{}", messages[2], first_half))?;
+    assert_eq!(decoded_lines(tailer.poll()?), messages[2..3].to_vec());
+    append(&directory.file("acars.json"), second_half)?;
+    assert_eq!(decoded_lines(tailer.poll()?), messages[3..4].to_vec());
+
+    let mut tailer: DirectoryTailer = DirectoryTailer::new(&directory.0).read_existing(true);
+    assert_eq!(decoded_lines(tailer.poll()?), messages);
+    Ok(())
+}
@@ -12,21 +12,21 @@ use chrono::{DateTime, SecondsFormat, Utc};
 use glob::{glob, GlobResult, Paths, PatternError};
 use prettytable::{Cell, row, Row, Table};
 use prettytable::format::Alignment;
-use rand::rngs::ThreadRng;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 use serde_json::Value;
 use thousands::Separable;
-use acars_vdlm2_parser::AcarsVdlm2Message;
+use acars_vdlm2_parser::{AcarsVdlm2Message, MessageFamily};
+use acars_vdlm2_parser::corpus::Corpus;
 use acars_vdlm2_parser::acars::NewAcarsMessage;
 use acars_vdlm2_parser::vdlm2::NewVdlm2Message;
 use acars_vdlm2_parser::hfdl::NewHfdlMessage;
+use acars_vdlm2_parser::testing::SeededRng;
 
 /// Enum for indicating test data type.
 pub enum MessageType {
     Acars,
     Vdlm2,
     Hfdl,
+    Uat,
     All,
 }
 
@@ -367,12 +367,14 @@ pub fn append_lines(
 pub fn combine_files_of_message_type(
     message_type: MessageType,
 ) -> Result<Vec<String>, Box<dyn Error>> {
-    match message_type {
-        MessageType::Acars => combine_found_files(glob("test_files/acars*")),
-        MessageType::Vdlm2 => combine_found_files(glob("test_files/vdlm2*")),
-        MessageType::Hfdl => combine_found_files(glob("test_files/hfdl*")),
-        MessageType::All => combine_found_files(glob("test_files/*"))
-    }
+    let family: Option<MessageFamily> = match message_type {
+        MessageType::Acars => Some(MessageFamily::Acars),
+        MessageType::Vdlm2 => Some(MessageFamily::Vdlm2),
+        MessageType::Hfdl => Some(MessageFamily::Hfdl),
+        MessageType::Uat => Some(MessageFamily::Uat),
+        MessageType::All => None
+    };
+    Ok(Corpus::from_sample_dir("test_files")?.family_lines(family)?)
 }
 
 /// Assistance function that loads contents of individual message type test files and returns them separately instead of combined.
@@ -383,6 +385,7 @@ pub fn load_files_of_message_type(
         MessageType::Acars => load_found_files(glob("test_files/acars*")),
         MessageType::Vdlm2 => load_found_files(glob("test_files/vdlm2*")),
         MessageType::Hfdl => load_found_files(glob("test_files/hfdl*")),
+        MessageType::Uat => load_found_files(glob("test_files/uat*")),
         MessageType::All => load_found_files(glob("test_files/*"))
     }
 }
@@ -473,16 +476,16 @@ pub fn compare_errors(
 ) {
     match (error_1, error_2) {
         (None, Ok(_)) => {}
-        (Some(library_error), Ok(value_data)) => assert!(false, "Library {}, Value {:?}", &library_error, &value_data),
+        (Some(library_error), Ok(value_data)) => panic!("Library {}, Value {:?}", &library_error, &value_data),
         (Some(library_error), Err(value_error)) => assert_eq!(
             library_error.to_string(),
             value_error.to_string(),
             "Errors processing {} do not match between library {} and serde Value {}",
             line,
             library_error,
-            value_error.to_string()
+            value_error
         ),
-        (None, Err(value_error)) => assert!(false, "Library passed, but Value is {:?}", &value_error)
+        (None, Err(value_error)) => panic!("Library passed, but Value is {:?}", &value_error)
     }
 }
 
@@ -490,10 +493,10 @@ pub fn test_enum_serialisation(message: &AcarsVdlm2Message, serialisation_target
     match serialisation_target {
         SerialisationTarget::String => {
             assert!(
-                message.to_string().as_ref().err().is_none(),
+                message.to_json().as_ref().err().is_none(),
                 "Parsing data {:?} to String failed: {:?}",
                 message,
-                message.to_string().as_ref().err()
+                message.to_json().as_ref().err()
             );
         }
         SerialisationTarget::Bytes => {
@@ -506,10 +509,10 @@ pub fn test_enum_serialisation(message: &AcarsVdlm2Message, serialisation_target
         }
         SerialisationTarget::Both => {
             assert!(
-                message.to_string().as_ref().err().is_none(),
+                message.to_json().as_ref().err().is_none(),
                 "Parsing data {:?} to String failed: {:?}",
                 message,
-                message.to_string().as_ref().err()
+                message.to_json().as_ref().err()
             );
             assert!(
                 message.to_bytes().as_ref().err().is_none(),
@@ -564,9 +567,9 @@ impl ContentDuplicator for Vec<String> {
     fn duplicate_contents(&self, rounds: &i64) -> Self {
         let mut duplicated_contents: Vec<String> = Vec::new();
         let mut data: Vec<String> = self.to_vec();
-        let mut rng: ThreadRng = thread_rng();
+        let mut rng: SeededRng = SeededRng::from_env();
         for _ in 0..*rounds {
-            data.shuffle(&mut rng);
+            rng.shuffle(&mut data);
             for entry in &data {
                 duplicated_contents.push(entry.to_string());
             }
@@ -0,0 +1,125 @@
+#![cfg(all(feature = "compression", feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use std::io::{ErrorKind, Write};
+use flate2::Compression;
+use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+use acars_vdlm2_parser::compression::{decompress, detect, Codec, CompressedPayload, CompressionFormat, DecompressError, TextEncoding, MAX_FRAME_DECOMPRESSED_LEN};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded: String = String::new();
+    for chunk in bytes.chunks(3) {
+        let buffer: u32 = chunk.iter().enumerate().fold(0, |buffer, (index, byte)| buffer | u32::from(*byte) << (16 - 8 * index));
+        for index in 0..=chunk.len() {
+            encoded.push(BASE64_ALPHABET[(buffer >> (18 - 6 * index) & 0x3F) as usize] as char);
+        }
+    }
+    while !encoded.len().is_multiple_of(4) {
+        encoded.push('=');
+    }
+    encoded
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+fn compress(payload: &[u8], format: CompressionFormat) -> Result<Vec<u8>, Box<dyn Error>> {
+    Ok(match format {
+        CompressionFormat::Zlib => {
+            let mut encoder: ZlibEncoder<Vec<u8>> = ZlibEncoder::new(Vec::new(), Compression::best());
+            encoder.write_all(payload)?;
+            encoder.finish()?
+        }
+        CompressionFormat::Gzip => {
+            let mut encoder: GzEncoder<Vec<u8>> = GzEncoder::new(Vec::new(), Compression::best());
+            encoder.write_all(payload)?;
+            encoder.finish()?
+        }
+        CompressionFormat::Deflate => {
+            // A short application header before the stream, as application protocols add.
+            let mut encoder: DeflateEncoder<Vec<u8>> = DeflateEncoder::new(vec![0x01, 0x20, 0x07], Compression::best());
+            encoder.write_all(payload)?;
+            encoder.finish()?
+        }
+    })
+}
+
+/// This test compresses a report in every supported format and text encoding and carries it as message text.
+/// It validates that the payload is detected with the right format and encoding, that it inflates back to the report,
+/// that the size limit is enforced even at its largest, and that a raw deflate stream is only taken for a payload when
+/// it inflates to text.
+#[test]
+fn test_compressed_payloads() -> Result<(), Box<dyn Error>> {
+    let report: Vec<u8> = "POSN42123W071456,KBOS,KJFK,350,M45,270050,".repeat(20).into_bytes();
+    for format in [CompressionFormat::Zlib, CompressionFormat::Gzip, CompressionFormat::Deflate] {
+        let compressed: Vec<u8> = compress(&report, format)?;
+        for (encoding, text) in [(TextEncoding::Base64, encode_base64(&compressed)), (TextEncoding::Hex, encode_hex(&compressed))] {
+            let payload: CompressedPayload = detect(&text).unwrap_or_else(|| panic!("{:?} {:?} not detected", format, encoding));
+            assert_eq!((payload.encoding, payload.format), (encoding, format));
+            assert_eq!(decompress(&text, report.len())?, report);
+            assert_eq!(decompress(&text, report.len() - 1), Err(DecompressError::TooLarge { limit: report.len() - 1 }));
+            let message: AcarsVdlm2Message = format!(r#"{{"freq":131.55,"channel":2,"label":"H1","text":"{}\r\n"}}"#, text).decode_message()?;
+            assert_eq!(message.decompressed_payload(), Some(report.clone()));
+        }
+    }
+    assert_eq!(decompress("not a compressed payload", 1024), Err(DecompressError::NotCompressed));
+    assert_eq!(decompress(&encode_base64(&compress(&report, CompressionFormat::Zlib)?), usize::MAX)?, report);
+
+    let binary: Vec<u8> = (0..=255).cycle().take(1024).collect();
+    assert_eq!(detect(&encode_base64(&compress(&binary, CompressionFormat::Zlib)?)).map(|payload| payload.format), Some(CompressionFormat::Zlib));
+    assert_eq!(detect(&encode_base64(&compress(&binary, CompressionFormat::Deflate)?)), None);
+    Ok(())
+}
+
+/// This test looks for compressed payloads in the text of every message in the sample corpus, none of which have one.
+/// It validates that ordinary message text, including hex and base64 looking reports, is not mistaken for a payload.
+#[test]
+fn test_no_false_positives() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = line.decode_message() else {
+            continue;
+        };
+        assert!(message.decompressed_payload().is_none(), "{:?}", message.get_text());
+    }
+    Ok(())
+}
+
+/// This test compresses every sample message with each codec and decodes the frames back.
+/// It validates that each message survives unchanged, that the frames are smaller than the JSON overall, and that
+/// frames which are not ours, use an unknown codec or decompress to too much are rejected.
+#[test]
+fn test_compressed_frames() -> Result<(), Box<dyn Error>> {
+    for codec in [Codec::Gzip, Codec::Zstd] {
+        let (mut json_len, mut frame_len): (usize, usize) = (0, 0);
+        for line in combine_files_of_message_type(MessageType::All)? {
+            let Ok(message) = line.decode_message() else {
+                continue;
+            };
+            let frame: Vec<u8> = message.to_bytes_compressed(codec)?;
+            let decoded: AcarsVdlm2Message = AcarsVdlm2Message::decode_compressed(&frame)?;
+            assert_eq!(decoded.to_json()?, message.to_json()?);
+            json_len += message.to_bytes()?.len();
+            frame_len += frame.len();
+        }
+        assert!(frame_len < json_len, "{:?} frames are {} bytes for {} bytes of JSON", codec, frame_len, json_len);
+    }
+    let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2}"#.decode_message()?;
+    let mut frame: Vec<u8> = message.to_bytes_compressed(Codec::Zstd)?;
+    for invalid in [&message.to_bytes()?[..], &[0xAC], &[0xAC, 9, 0, 0][..]] {
+        let error: serde_json::Error = AcarsVdlm2Message::decode_compressed(invalid).expect_err("not a valid frame");
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::InvalidData));
+    }
+    frame[1] = 1;
+    assert!(AcarsVdlm2Message::decode_compressed(&frame).is_err());
+    let mut oversized: GzEncoder<Vec<u8>> = GzEncoder::new(vec![0xAC, 1], Compression::fast());
+    oversized.write_all(&vec![b' '; MAX_FRAME_DECOMPRESSED_LEN + 1])?;
+    let error: serde_json::Error = AcarsVdlm2Message::decode_compressed(&oversized.finish()?).expect_err("too large");
+    assert_eq!(error.io_error_kind(), Some(ErrorKind::InvalidData));
+    Ok(())
+}
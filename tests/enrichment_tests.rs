@@ -0,0 +1,38 @@
+#![cfg(all(feature = "enrichment", feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+use acars_vdlm2_parser::enrichment::{AircraftDatabase, AircraftInfo};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// This test builds an `AircraftDatabase` from the ICAO addresses in the sample corpus and enriches every message.
+/// It validates that every message with an address gains an `aircraft_info` block that survives a round trip.
+#[test]
+fn test_aircraft_enrichment() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let mut csv: String = "hex,reg,typecode,owner,built\n".to_string();
+    for icao in messages.iter().filter_map(|message| message.get_icao()) {
+        csv.push_str(&format!("{:06X},REG{:06X},B738,,2001\n", icao, icao));
+    }
+    csv.push_str("not-an-address,VH-XXX,A320,Example Air,2010\n");
+    let database: AircraftDatabase = AircraftDatabase::from_csv_reader(csv.as_bytes())?;
+    assert!(!database.is_empty());
+    for mut message in messages {
+        let enriched: bool = database.enrich(&mut message);
+        assert_eq!(enriched, message.get_icao().is_some());
+        let Some(icao) = message.get_icao() else { continue };
+        let expected: AircraftInfo = AircraftInfo {
+            registration: Some(format!("REG{:06X}", icao)),
+            aircraft_type: Some("B738".to_string()),
+            operator: None,
+        };
+        assert_eq!(message.get_aircraft_info(), Some(&expected));
+        let decoded: AcarsVdlm2Message = message.to_json()?.decode_message()?;
+        assert_eq!(decoded.get_aircraft_info(), Some(&expected));
+    }
+    Ok(())
+}
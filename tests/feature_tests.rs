@@ -0,0 +1,37 @@
+use acars_vdlm2_parser::{decode_bytes, decode_direct, DecodeMessage, ExpectedMessageType, MessageFamily};
+use acars_vdlm2_parser::envelope::decode_enveloped;
+use acars_vdlm2_parser::field_stats::FieldStatistics;
+use acars_vdlm2_parser::sniff::sniff;
+
+/// The smallest input of each family that is identified as that family, whether or not it decodes.
+const FAMILY_INPUTS: [(MessageFamily, &str); 4] = [
+    (MessageFamily::Acars, r#"{"freq":131.55,"channel":2}"#),
+    (MessageFamily::Vdlm2, r#"{"vdl2":{"freq":136975000}}"#),
+    (MessageFamily::Hfdl, r#"{"hfdl":{"freq":11387000}}"#),
+    (MessageFamily::Uat, r#"{"address":"a1b2c3","address_qualifier":"adsb_icao"}"#),
+];
+
+/// This test decodes an input of every family, through each decode path, with whichever family features are enabled.
+/// It validates that every family is still identified when its feature is not enabled, and that its messages then
+/// fail with an error naming the feature, while enabled families never report that error.
+#[test]
+fn test_family_features() {
+    for (family, input) in FAMILY_INPUTS {
+        let feature_error: String = format!("enable the `{}` feature", family);
+        assert_eq!(sniff(input.as_bytes()), Some(family));
+        let errors: Vec<String> = [
+            input.decode_message().err(),
+            decode_direct(input.as_bytes()).err(),
+            decode_bytes(input.as_bytes(), ExpectedMessageType::from(family)).err(),
+            decode_enveloped(&format!(r#"{{"v":1,"type":"{}","payload":{}}}"#, family, input)).err(),
+            FieldStatistics::new().decode(input).err(),
+        ].into_iter().map(|error| error.map(|error| error.to_string()).unwrap_or_default()).collect();
+        for error in errors {
+            assert_eq!(error.contains(&feature_error), !family.is_enabled(), "{} gave {:?}", family, error);
+        }
+    }
+    let csv: &str = "1672531200.5,EXAMPLE-STATION,2,131.550,-24.5,0,2,H1,4,!,.N12345,UA0123,M01A,POS N47,W122";
+    assert_eq!(csv.decode_message().is_ok(), MessageFamily::Acars.is_enabled());
+    let raw_uat: &str = "-00a1b2c30000000000000000000000000000;rs=2;rssi=-17.5;";
+    assert_eq!(raw_uat.decode_message().is_ok(), MessageFamily::Uat.is_enabled());
+}
@@ -0,0 +1,95 @@
+#![cfg(all(feature = "ffi", feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use acars_vdlm2_parser::DecodeMessage;
+use acars_vdlm2_parser::error_code::{error_code, ErrorCode};
+use acars_vdlm2_parser::ffi::{avp_decode_json, avp_decode_raw_frame, avp_error_code_name, avp_string_free, ffi_code, AVP_ERROR_INVALID_UTF8, AVP_ERROR_NULL_POINTER, AVP_OK};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// Takes ownership of a string returned through the bindings, releasing it with `avp_string_free`.
+fn take_output(output: *mut c_char) -> Option<String> {
+    if output.is_null() {
+        return None;
+    }
+    let text: String = unsafe { CStr::from_ptr(output) }.to_string_lossy().into_owned();
+    unsafe { avp_string_free(output) };
+    Some(text)
+}
+
+fn code_name(code: i32) -> Option<String> {
+    let name: *const c_char = avp_error_code_name(code);
+    (!name.is_null()).then(|| unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned())
+}
+
+/// This test decodes the sample corpus through `avp_decode_json` and `avp_decode_raw_frame`.
+/// It validates that both return the same JSON as decoding in Rust, and that inputs which fail return the code and
+/// error text of the Rust decoder.
+#[test]
+fn test_ffi_decode() -> Result<(), Box<dyn Error>> {
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let input: CString = CString::new(line.as_str())?;
+        let mut json_output: *mut c_char = ptr::null_mut();
+        let json_code: i32 = unsafe { avp_decode_json(input.as_ptr(), &mut json_output) };
+        let mut frame_output: *mut c_char = ptr::null_mut();
+        let frame_code: i32 = unsafe { avp_decode_raw_frame(line.as_ptr(), line.len(), &mut frame_output) };
+        let (json_text, frame_text): (Option<String>, Option<String>) = (take_output(json_output), take_output(frame_output));
+        assert_eq!((json_code, &json_text), (frame_code, &frame_text));
+        match line.decode_message() {
+            Ok(message) => {
+                assert_eq!(json_code, AVP_OK);
+                assert_eq!(json_text, Some(message.to_json()?));
+            }
+            Err(error) => {
+                assert_eq!(json_code, ffi_code(ErrorCode::of(&error)));
+                assert_eq!(code_name(json_code).as_deref(), Some(error_code(&error)));
+                assert_eq!(json_text, Some(error.to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// This test calls the bindings with the arguments C callers get wrong.
+/// It validates that null pointers and invalid UTF-8 return their codes without setting an output, that a frame
+/// holding a NUL byte still returns its error text, and that every code has a name.
+#[test]
+fn test_ffi_errors() -> Result<(), Box<dyn Error>> {
+    let mut output: *mut c_char = ptr::null_mut();
+    assert_eq!(unsafe { avp_decode_json(ptr::null(), &mut output) }, AVP_ERROR_NULL_POINTER);
+    assert!(output.is_null());
+    assert_eq!(unsafe { avp_decode_raw_frame(ptr::null(), 4, &mut output) }, AVP_ERROR_NULL_POINTER);
+    assert_eq!(unsafe { avp_decode_json(c"{}".as_ptr(), ptr::null_mut()) }, AVP_ERROR_NULL_POINTER);
+    let invalid: CString = CString::new(vec![b'{', 0xFF, b'}'])?;
+    assert_eq!(unsafe { avp_decode_json(invalid.as_ptr(), &mut output) }, AVP_ERROR_INVALID_UTF8);
+    assert!(output.is_null());
+    let bytes: &[u8] = b"\"text with \0 in it\"";
+    let code: i32 = unsafe { avp_decode_raw_frame(bytes.as_ptr(), bytes.len(), &mut output) };
+    assert_eq!(code_name(code).as_deref(), Some("json.syntax"));
+    assert!(take_output(output).is_some());
+    for code in ErrorCode::ALL {
+        assert_eq!(code_name(ffi_code(code)).as_deref(), Some(code.as_str()));
+    }
+    assert_eq!(code_name(AVP_OK).as_deref(), Some("ok"));
+    assert_eq!(code_name(-100), None);
+    assert_eq!(code_name(ErrorCode::ALL.len() as i32 + 1), None);
+    unsafe { avp_string_free(ptr::null_mut()) };
+    Ok(())
+}
+
+/// This test sends a compressed frame through `avp_decode_raw_frame`.
+/// It validates that the frame is decompressed before decoding when the `compression` feature is enabled.
+#[cfg(feature = "compression")]
+#[test]
+fn test_ffi_compressed_frame() -> Result<(), Box<dyn Error>> {
+    use acars_vdlm2_parser::AcarsVdlm2Message;
+    use acars_vdlm2_parser::compression::Codec;
+    let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"H1","text":"HELLO"}"#.decode_message()?;
+    let frame: Vec<u8> = message.to_bytes_compressed(Codec::Zstd)?;
+    let mut output: *mut c_char = ptr::null_mut();
+    assert_eq!(unsafe { avp_decode_raw_frame(frame.as_ptr(), frame.len(), &mut output) }, AVP_OK);
+    assert_eq!(take_output(output), Some(message.to_json()?));
+    Ok(())
+}
@@ -1,7 +1,13 @@
+#![cfg(all(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
 mod common;
 
 use std::error::Error;
 use acars_vdlm2_parser::hfdl::{NewHfdlMessage, HfdlMessage};
+use acars_vdlm2_parser::hfdl_analytics::HfdlAnalytics;
+use acars_vdlm2_parser::hfdl_systable::{HfdlGroundStation, HfdlGroundStationDatabase, HfdlSystable};
+use acars_vdlm2_parser::frequency::Frequency;
+use acars_vdlm2_parser::redact::StationRedactor;
+use serde_json::{json, Value};
 use crate::common::{combine_files_of_message_type, compare_errors, load_files_of_message_type, MessageType, process_file_as_hfdl};
 
 /// This test will ingest contents from the hfdl sample files as a message per line to a `Vec<String>`.
@@ -22,7 +28,7 @@ fn test_hfdl_parsing() -> Result<(), Box<dyn Error>> {
                 }
             }
             for message in valid_hfdl_messages {
-                assert!(message.to_string().as_ref().err().is_none());
+                assert!(message.to_json().as_ref().err().is_none());
             }
             for line in failed_decodes {
                 compare_errors(line.to_hfdl().err(), serde_json::from_str(&line), &line);
@@ -32,6 +38,59 @@ fn test_hfdl_parsing() -> Result<(), Box<dyn Error>> {
     }
 }
 
+/// The id and name of a ground station found by `ground_stations`.
+type GroundStation = (Value, Option<String>);
+
+/// Collects the id and name of every ground station named in an HFDL message, in the order they are written.
+fn ground_stations(value: &Value, found: &mut Vec<GroundStation>) {
+    match value {
+        Value::Object(object) => {
+            if object.get("type").and_then(Value::as_str) == Some("Ground station") {
+                found.push((object["id"].clone(), object.get("name").and_then(Value::as_str).map(str::to_string)));
+            }
+            object.values().for_each(|child| ground_stations(child, found));
+        }
+        Value::Array(array) => array.iter().for_each(|child| ground_stations(child, found)),
+        _ => {}
+    }
+}
+
+/// This test redacts every hfdl sample message and collects the ground stations before and after.
+/// It validates that no ground station id, ground station name or station name survives wherever the ground station
+/// appears in the frame, and that redacting the same message twice with the same salt gives the same output.
+#[test]
+fn test_hfdl_redaction() -> Result<(), Box<dyn Error>> {
+    let redactor: StationRedactor = StationRedactor::new("test salt");
+    let mut redacted_ground_stations: usize = 0;
+    for line in combine_files_of_message_type(MessageType::Hfdl)? {
+        let Ok(original) = line.to_hfdl() else {
+            continue;
+        };
+        let mut redacted: HfdlMessage = original.clone();
+        redacted.redact_ground_stations(&redactor);
+        let mut redacted_again: HfdlMessage = original.clone();
+        redacted_again.redact_ground_stations(&redactor);
+        let redacted_json: String = redacted.to_json()?;
+        assert_eq!(redacted_json, redacted_again.to_json()?);
+        if let Some(station) = &original.hfdl.station {
+            assert!(!redacted_json.contains(station.as_str()), "Station {} was not redacted", station);
+        }
+        let (mut before, mut after): (Vec<GroundStation>, Vec<GroundStation>) = (Vec::new(), Vec::new());
+        ground_stations(&serde_json::to_value(&original)?, &mut before);
+        ground_stations(&serde_json::to_value(&redacted)?, &mut after);
+        assert_eq!(before.len(), after.len());
+        for ((id, name), (redacted_id, _)) in before.iter().zip(&after) {
+            assert_ne!(id, redacted_id, "Ground station {} was not redacted", id);
+            if let Some(name) = name {
+                assert!(!redacted_json.contains(name.as_str()), "Ground station {} was not redacted", name);
+            }
+        }
+        redacted_ground_stations += before.len();
+    }
+    assert!(redacted_ground_stations > 0);
+    Ok(())
+}
+
 /// Test for displaying the per-item result for hfdl messages, helpful when diagnosing parsing issues.
 /// Marked as `#[ignore]` so it can be run separately as required.
 #[test]
@@ -49,4 +108,170 @@ fn show_hfdl_ingest() -> Result<(), Box<dyn Error>> {
             Ok(())
         }
     }
-}
\ No newline at end of file
+}
+
+/// This test runs the hfdl sample corpus through `HfdlAnalytics`.
+/// It validates that the per-frequency and per-bit rate totals add up and that the JSON export reads back unchanged.
+#[test]
+fn test_hfdl_analytics() -> Result<(), Box<dyn Error>> {
+    let mut analytics: HfdlAnalytics = HfdlAnalytics::default();
+    let mut decoded: u64 = 0;
+    for line in combine_files_of_message_type(MessageType::Hfdl)? {
+        if let Ok(message) = line.to_hfdl() {
+            analytics.record(&message);
+            decoded += 1;
+        }
+    }
+    assert_eq!(analytics.messages, decoded);
+    assert_eq!(analytics.by_frequency.values().map(|stats| stats.messages).sum::<u64>(), decoded);
+    assert_eq!(analytics.by_bit_rate.values().sum::<u64>(), decoded);
+    assert!(!analytics.by_ground_station.is_empty());
+    let read_back: HfdlAnalytics = serde_json::from_str(&analytics.to_json()?)?;
+    assert_eq!(read_back, analytics);
+    Ok(())
+}
+
+/// This test rewrites the `t` block of every hfdl sample frame the ways some forks write it, with quoted seconds and
+/// microseconds, and with fractional seconds.
+/// It validates that each decodes to the same time as the original and is written back with integer seconds.
+#[test]
+fn test_hfdl_lenient_t_block() -> Result<(), Box<dyn Error>> {
+    let mut rewritten: usize = 0;
+    for line in combine_files_of_message_type(MessageType::Hfdl)? {
+        let Ok(message) = line.to_hfdl() else { continue };
+        let original: Value = serde_json::from_str(&line)?;
+        let Some(t) = original["hfdl"].get("t").cloned() else { continue };
+        let (sec, usec): (u64, u64) = (t["sec"].as_u64().unwrap(), t["usec"].as_u64().unwrap());
+        let expected: Value = serde_json::to_value(&message)?;
+        for variant in [
+            json!({"sec": sec.to_string(), "usec": usec}),
+            json!({"sec": sec.to_string(), "usec": usec.to_string()}),
+            json!({"sec": sec as f64 + usec as f64 / 1_000_000.0, "usec": usec}),
+        ] {
+            let mut lenient: Value = original.clone();
+            lenient["hfdl"]["t"] = variant;
+            let decoded: HfdlMessage = lenient.to_string().to_hfdl()?;
+            assert_eq!(decoded.get_time(), message.get_time());
+            assert_eq!(serde_json::to_value(&decoded)?, expected);
+        }
+        rewritten += 1;
+    }
+    assert!(rewritten > 0);
+    Ok(())
+}
+
+/// This test edits the LPDU and SPDU of every hfdl sample frame through their public fields.
+/// It validates that the edited message is written with the change and reads back, as unknown fields are only
+/// rejected when reading.
+#[test]
+fn test_hfdl_public_fields() -> Result<(), Box<dyn Error>> {
+    let mut edited: usize = 0;
+    for line in combine_files_of_message_type(MessageType::Hfdl)? {
+        let Ok(mut message) = line.to_hfdl() else { continue };
+        if let Some(lpdu) = message.hfdl.lpdu.as_mut() {
+            lpdu.err = !lpdu.err;
+            lpdu.lpdu_type.name = "Edited".to_string();
+        }
+        if let Some(spdu) = message.hfdl.spdu.as_mut() {
+            spdu.frame_index = spdu.frame_index.wrapping_add(1);
+        }
+        let written: Value = serde_json::to_value(&message)?;
+        let read_back: HfdlMessage = message.to_json()?.to_hfdl()?;
+        assert_eq!(serde_json::to_value(&read_back)?, written);
+        if let Some(lpdu) = &read_back.hfdl.lpdu {
+            assert_eq!(lpdu.lpdu_type.name, "Edited");
+            assert_eq!(written["hfdl"]["lpdu"]["err"], json!(lpdu.err));
+            edited += 1;
+        }
+    }
+    assert!(edited > 0);
+    Ok(())
+}
+
+/// An excerpt of the system table shipped with dumphfdl, with a station added to cover the other libconfig syntax:
+/// comments in each style, `:` for `=`, joined strings with escapes, hex integers and an array.
+const SYSTABLE: &str = r#"# HFDL system table
+version = 51;
+stations = (
+    {
+        id = 1;
+        name = "San Francisco, California";
+        lat = 37.6156;
+        lon = -122.4048;
+        frequencies = ( 21934.0, 17919.0, 13276.0, 11327.0, 10081.0, 8927.0, 6559.0, 5508.0 );
+    },
+    {
+        id = 2;
+        name = "Molokai, Hawaii";
+        lat = 21.2;
+        lon = -157.2;
+        frequencies = ( 21937.0, 17919.0, 13324.0, 13312.0, 13276.0, 11348.0, 11312.0, 10027.0, 8936.0, 8912.0, 6565.0, 5514.0 );
+    },
+    /* not a real station */
+    {
+        id : 0x7F;  // 127
+        name = "Example " "\"Station\"\x21";
+        lat = 47;
+        lon = -122.5;
+        frequencies = [ 4681.0 ];
+        remarks = true;
+    }
+);
+"#;
+
+/// This test parses a dumphfdl system table and builds a ground station database from it, then applies the newer
+/// system table broadcast in the hfdl samples.
+/// It validates that every station, name and frequency is read, that frequencies shared by two stations are not used
+/// to identify a frame's ground station, and that the broadcast table replaces locations and frequencies but keeps
+/// the names from the file.
+#[test]
+fn test_hfdl_systable() -> Result<(), Box<dyn Error>> {
+    let systable: HfdlSystable = SYSTABLE.parse()?;
+    assert_eq!(systable.version, 51);
+    assert_eq!(systable.stations.iter().map(|station| station.id).collect::<Vec<u16>>(), vec![1, 2, 127]);
+    assert_eq!(systable.stations[2].name, r#"Example "Station"!"#);
+    assert_eq!((systable.stations[2].lat, systable.stations[2].frequencies.len()), (47.0, 1));
+
+    let mut database: HfdlGroundStationDatabase = HfdlGroundStationDatabase::from_systable(&systable);
+    assert_eq!(database.len(), 3);
+    let san_francisco: &HfdlGroundStation = database.get(1).ok_or("station 1 missing")?;
+    assert_eq!(san_francisco.frequencies[0], Frequency::from_hz(21_934_000));
+    assert_eq!(database.stations_on(Frequency::from_hz(17_919_000)).count(), 2);
+    assert_eq!(database.stations_on(Frequency::from_hz(4_681_000)).map(|station| station.id).collect::<Vec<u16>>(), vec![127]);
+
+    let mut aircraft_frame: HfdlMessage = r#"{"hfdl":{"freq":4681000,"bit_rate":300,"slot":"S"}}"#.to_hfdl()?;
+    assert_eq!(database.get_for_message(&aircraft_frame).map(|station| station.id), Some(127));
+    aircraft_frame.hfdl.freq = Frequency::from_hz(17_919_000);
+    assert_eq!(database.get_for_message(&aircraft_frame), None);
+
+    let broadcast: HfdlMessage = combine_files_of_message_type(MessageType::Hfdl)?.iter()
+        .find(|line| line.contains("systable_complete"))
+        .ok_or("no system table broadcast in the samples")?
+        .to_hfdl()?;
+    assert_eq!(database.get_for_message(&broadcast).map(|station| station.name.as_str()), Some("San Francisco, California"));
+    assert!(database.update_from_message(&broadcast));
+    assert!(!database.update_from_message(&broadcast));
+    assert_eq!(database.version(), 52);
+    let updated: &HfdlGroundStation = database.get(1).ok_or("station 1 missing")?;
+    assert_eq!((updated.name.as_str(), updated.lat), ("San Francisco, California", 38.384587));
+    assert_eq!(updated.frequencies.len(), 8);
+    assert!(database.get(127).is_none());
+    Ok(())
+}
+
+/// This test parses system tables with libconfig syntax errors, unsupported directives and missing settings.
+/// It validates that each is rejected, naming the line for syntax errors.
+#[test]
+fn test_hfdl_systable_rejected() {
+    for (input, expected) in [
+        ("version = 51;\nstations = ( { id = 1; name = \"unterminated; } );", "line 2"),
+        ("@include \"stations.cfg\"", "@include"),
+        ("version = 51;\n/* unterminated", "unterminated comment"),
+        ("version = 51;\nstations = ( { id = 1; lat = ; } );", "line 2: invalid value"),
+        ("version = 51;", "missing field `stations`"),
+        ("version = 51; stations = ( { id = 1; name = \"A\"; lat = 1.0; lon = 2.0; } );", "missing field `frequencies`"),
+    ] {
+        let error: String = input.parse::<HfdlSystable>().err().map(|error| error.to_string()).unwrap_or_default();
+        assert!(error.contains(expected), "{} gave {}", input, error);
+    }
+}
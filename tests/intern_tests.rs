@@ -0,0 +1,104 @@
+#![cfg(all(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::error::Error;
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage};
+use acars_vdlm2_parser::intern::{CompactMessage, StringInterner};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// Allocator that keeps a per thread count of the bytes currently allocated, so a test can measure what it builds
+/// without counting what other test threads allocate at the same time.
+struct CountingAllocator;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn count(bytes: isize) {
+    let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + bytes));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size() as isize);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        count(-(layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size as isize - layout.size() as isize);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the value built by `build` with the bytes it still has allocated once built.
+fn measure<T>(build: impl FnOnce() -> T) -> (T, isize) {
+    let before: isize = LIVE_BYTES.with(Cell::get);
+    let built: T = build();
+    (built, LIVE_BYTES.with(Cell::get) - before)
+}
+
+/// The same fields as a `CompactMessage`, each stored as its own `String`.
+#[allow(dead_code)]
+struct OwnedMessage {
+    time: Option<f64>,
+    station: Option<String>,
+    label: Option<String>,
+    registration: Option<String>,
+    flight: Option<String>,
+}
+
+/// This test decodes the full sample corpus into `CompactMessage` entries sharing one `StringInterner`, and into the
+/// same fields stored as owned `String` values, counting the bytes each allocates.
+/// It validates that interning allocates at least a quarter less memory than owned strings on real traffic, counting
+/// the interner itself, and that the interner's `stored_bytes` estimate is below the string data it was asked to store.
+#[test]
+fn test_interning_saves_memory() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let ((interner, compact_messages), interned_bytes): ((StringInterner, Vec<CompactMessage>), isize) = measure(|| {
+        let mut interner: StringInterner = StringInterner::new();
+        let compact_messages: Vec<CompactMessage> = messages.iter()
+            .map(|message| CompactMessage::from_message(message, &mut interner))
+            .collect();
+        (interner, compact_messages)
+    });
+    for (compact, message) in compact_messages.iter().zip(&messages) {
+        assert_eq!(compact.station.as_deref(), message.get_station_name());
+        assert_eq!(compact.label.as_deref(), message.get_label());
+    }
+    let (owned_messages, owned_bytes): (Vec<OwnedMessage>, isize) = measure(|| {
+        messages.iter()
+            .map(|message| OwnedMessage {
+                time: message.get_time(),
+                station: message.get_station_name().map(str::to_string),
+                label: message.get_label().map(str::to_string),
+                registration: message.get_registration().map(str::to_string),
+                flight: message.get_flight().map(str::to_string),
+            })
+            .collect()
+    });
+    println!(
+        "{} messages, {} unique strings: interned {} bytes ({} estimated for the interner), owned {} bytes, {} bytes of string data requested",
+        owned_messages.len(),
+        interner.len(),
+        interned_bytes,
+        interner.stored_bytes(),
+        owned_bytes,
+        interner.requested_bytes()
+    );
+    assert!(interned_bytes * 4 < owned_bytes * 3);
+    assert!(interner.stored_bytes() < interner.requested_bytes());
+    Ok(())
+}
@@ -0,0 +1,124 @@
+#![cfg(all(feature = "rebroadcast", feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+use acars_vdlm2_parser::envelope::{decode_enveloped, OutputMode};
+use acars_vdlm2_parser::rebroadcast::{BroadcastStats, Rebroadcaster};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// Waits for the rebroadcaster to accept the expected number of TCP clients.
+fn wait_for_clients(rebroadcaster: &Rebroadcaster, expected: usize) {
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    while rebroadcaster.tcp_clients() < expected {
+        assert!(Instant::now() < deadline, "TCP clients were not accepted");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// This test rebroadcasts the decoded sample messages to a UDP target filtered to ACARS and to a TCP client of an
+/// unfiltered listener.
+/// It validates that each output receives exactly the messages its filter accepts, one per line and unchanged, and
+/// that the stats count what was sent and filtered.
+#[test]
+fn test_rebroadcast_udp_and_tcp() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .take(200)
+        .collect();
+    let receiver: UdpSocket = UdpSocket::bind("127.0.0.1:0")?;
+    receiver.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut rebroadcaster: Rebroadcaster = Rebroadcaster::new();
+    rebroadcaster.add_udp_target(receiver.local_addr()?, Some(Arc::new(|message: &AcarsVdlm2Message| message.get_family() == MessageFamily::Acars)))?;
+    let listening: SocketAddr = rebroadcaster.listen_tcp("127.0.0.1:0", None)?;
+    let client: TcpStream = TcpStream::connect(listening)?;
+    client.set_read_timeout(Some(Duration::from_secs(5)))?;
+    wait_for_clients(&rebroadcaster, 1);
+    let expected_udp: Vec<String> = messages.iter()
+        .filter(|message| message.get_family() == MessageFamily::Acars)
+        .map(|message| message.to_json())
+        .collect::<Result<Vec<String>, serde_json::Error>>()?;
+    assert!(!expected_udp.is_empty());
+    let reader = thread::spawn(move || -> Vec<String> {
+        BufReader::new(client).lines().map_while(Result::ok).collect()
+    });
+    let mut totals: BroadcastStats = BroadcastStats::default();
+    let mut received_udp: Vec<String> = Vec::new();
+    let mut buffer: Vec<u8> = vec![0; 65536];
+    for message in &messages {
+        let stats: BroadcastStats = rebroadcaster.broadcast(message)?;
+        assert_eq!((stats.udp_failed, stats.tcp_disconnected), (0, 0));
+        totals.udp_sent += stats.udp_sent;
+        totals.tcp_sent += stats.tcp_sent;
+        totals.filtered += stats.filtered;
+        if stats.udp_sent == 1 {
+            let length: usize = receiver.recv(&mut buffer)?;
+            assert_eq!(buffer[length - 1], b'\n');
+            received_udp.push(String::from_utf8(buffer[..length - 1].to_vec())?);
+        }
+    }
+    assert_eq!(received_udp, expected_udp);
+    assert_eq!((totals.udp_sent, totals.tcp_sent), (expected_udp.len(), messages.len()));
+    assert_eq!(totals.filtered, messages.len() - expected_udp.len());
+    drop(rebroadcaster);
+    let received_tcp: Vec<String> = reader.join().unwrap();
+    let expected_tcp: Vec<String> = messages.iter().map(|message| message.to_json()).collect::<Result<Vec<String>, serde_json::Error>>()?;
+    assert_eq!(received_tcp, expected_tcp);
+    Ok(())
+}
+
+/// This test rebroadcasts enveloped messages to a TCP client that then disconnects.
+/// It validates that the output mode is applied, and that the client is dropped once writing to it fails.
+#[test]
+fn test_rebroadcast_enveloped_and_disconnect() -> Result<(), Box<dyn Error>> {
+    let message: AcarsVdlm2Message = r#"{"freq":131.55,"channel":2,"label":"H1"}"#.decode_message()?;
+    let mut rebroadcaster: Rebroadcaster = Rebroadcaster::new().output_mode(OutputMode::Enveloped);
+    let listening: SocketAddr = rebroadcaster.listen_tcp("127.0.0.1:0", Some(Arc::new(|message: &AcarsVdlm2Message| message.get_label() == Some("H1"))))?;
+    let client: TcpStream = TcpStream::connect(listening)?;
+    client.set_read_timeout(Some(Duration::from_secs(5)))?;
+    wait_for_clients(&rebroadcaster, 1);
+    assert_eq!(rebroadcaster.broadcast(&message)?.tcp_sent, 1);
+    let mut line: String = String::new();
+    BufReader::new(&client).read_line(&mut line)?;
+    assert_eq!(decode_enveloped(line.trim_end())?.to_json()?, message.to_json()?);
+    drop(client);
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+    let mut disconnected: usize = 0;
+    while disconnected == 0 {
+        assert!(Instant::now() < deadline, "the closed client was not disconnected");
+        disconnected = rebroadcaster.broadcast(&message)?.tcp_disconnected;
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(rebroadcaster.tcp_clients(), 0);
+    Ok(())
+}
+
+/// This test connects TCP clients that never read and broadcasts large messages to them.
+/// It validates that broadcasting does not wait on the stalled clients, that each is disconnected once its queue is
+/// full, and that dropping the rebroadcaster stops its listener without waiting for any client.
+#[test]
+fn test_rebroadcast_stalled_clients() -> Result<(), Box<dyn Error>> {
+    let message: AcarsVdlm2Message = format!(r#"{{"freq":131.55,"channel":2,"text":"{}"}}"#, "X".repeat(60_000)).decode_message()?;
+    let write_timeout: Duration = Duration::from_secs(5);
+    let mut rebroadcaster: Rebroadcaster = Rebroadcaster::new().write_timeout(write_timeout).client_queue_len(4);
+    let listening: SocketAddr = rebroadcaster.listen_tcp("127.0.0.1:0", None)?;
+    let stalled: Vec<TcpStream> = (0..4).map(|_| TcpStream::connect(listening)).collect::<Result<_, _>>()?;
+    wait_for_clients(&rebroadcaster, stalled.len());
+    let started: Instant = Instant::now();
+    let mut disconnected: usize = 0;
+    while disconnected < stalled.len() {
+        assert!(started.elapsed() < write_timeout, "broadcasting waited on the stalled clients");
+        disconnected += rebroadcaster.broadcast(&message)?.tcp_disconnected;
+    }
+    assert_eq!(rebroadcaster.tcp_clients(), 0);
+    let dropped: Instant = Instant::now();
+    drop(rebroadcaster);
+    assert!(dropped.elapsed() < Duration::from_secs(1), "dropping the rebroadcaster took {:?}", dropped.elapsed());
+    Ok(())
+}
@@ -0,0 +1,141 @@
+#![cfg(all(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use serde_json::Value;
+use uuid::Uuid;
+use acars_vdlm2_parser::{AcarsVdlm2Message, AppDetails, DecodeMessage};
+use acars_vdlm2_parser::dead_letter::FailedDecode;
+use acars_vdlm2_parser::envelope::{decode_enveloped_bytes, OutputMode};
+use acars_vdlm2_parser::provider::{self, FixedClock, Providers, SequentialUuids};
+use acars_vdlm2_parser::timestamp::MessageTimestamp;
+use acars_vdlm2_parser::router::{decode_for_forwarding, forward, prepare_for_forwarding, RouterConfig};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// This test runs every message in the sample corpus through a router that overrides the station name and adds its
+/// proxy details, as acars_router does with both options set.
+/// It validates that every output is a single newline terminated line, that the station name and proxy details are
+/// set, and that the decoder name and version are kept, or filled in when the message had none.
+#[test]
+fn test_router_override_and_proxy() -> Result<(), Box<dyn Error>> {
+    let uuid: Uuid = Uuid::new_v4();
+    let config: RouterConfig = RouterConfig::new("acars_router", "1.3.1")
+        .override_station_name("XX-YYYY-ROUTER")
+        .with_uuid(uuid);
+    let mut forwarded: usize = 0;
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(original) = line.decode_message() else {
+            continue;
+        };
+        let output: Vec<u8> = forward(format!("{}\n", line).as_bytes(), &config)?;
+        assert!(output.ends_with(b"\n") && output.iter().filter(|byte| **byte == b'\n').count() == 1, "{}", line);
+        let message: AcarsVdlm2Message = output.decode_message()?;
+        assert_eq!(message.get_station_name(), Some("XX-YYYY-ROUTER"));
+        let app: &AppDetails = message.get_app_details().expect("forwarded messages have an app block");
+        assert!(!app.name.is_empty() && !app.ver.is_empty(), "{}", line);
+        if let Some(original_app) = original.get_app_details().filter(|original_app| !original_app.name.is_empty()) {
+            assert_eq!((&app.name, &app.ver), (&original_app.name, &original_app.ver));
+        }
+        assert_eq!(app.proxied, Some(true));
+        assert!(app.proxied_by.as_deref().is_some_and(|proxied_by| proxied_by.ends_with("acars_router")));
+        if original.get_app_details().and_then(|original_app| original_app.acars_router_uuid.as_ref()).is_none() {
+            assert_eq!(app.acars_router_uuid, Some(uuid.to_string()));
+        }
+        forwarded += 1;
+    }
+    assert!(forwarded > 0);
+    Ok(())
+}
+
+/// This test runs every message in the sample corpus through a router with no options set.
+/// It validates that the forwarded message is the same as the decoded input.
+#[test]
+fn test_router_passthrough() -> Result<(), Box<dyn Error>> {
+    let config: RouterConfig = RouterConfig::new("acars_router", "1.3.1").add_proxy_id(false);
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(mut message) = decode_for_forwarding(line.as_bytes()) else {
+            continue;
+        };
+        let expected: Value = serde_json::to_value(&message)?;
+        let output: Vec<u8> = prepare_for_forwarding(&mut message, &config)?;
+        assert_eq!(serde_json::from_slice::<Value>(&output)?, expected);
+    }
+    Ok(())
+}
+
+/// This test chains two routers, as happens when one acars_router feeds another.
+/// It validates that both routers are recorded in the proxy details and the decoder details survive both hops.
+#[test]
+fn test_router_chain() -> Result<(), Box<dyn Error>> {
+    let first: RouterConfig = RouterConfig::new("first_router", "1.0.0");
+    let second: RouterConfig = RouterConfig::new("second_router", "2.0.0").override_station_name("SECOND");
+    let input: &str = r#"{"freq":131.55,"channel":2,"station_id":"FIRST","app":{"name":"acarsdec","ver":"3.7"}}"#;
+    let output: Vec<u8> = forward(&forward(input.as_bytes(), &first)?, &second)?;
+    let message: AcarsVdlm2Message = output.decode_message()?;
+    let app: &AppDetails = message.get_app_details().expect("forwarded messages have an app block");
+    assert_eq!((app.name.as_str(), app.ver.as_str()), ("acarsdec", "3.7"));
+    assert_eq!(app.proxied_by.as_deref(), Some("first_router,second_router"));
+    assert_eq!(app.acars_router_version.as_deref(), Some("1.0.0,2.0.0"));
+    assert_eq!(message.get_station_name(), Some("SECOND"));
+    Ok(())
+}
+
+/// This test runs every message in the sample corpus through a router writing enveloped output.
+/// It validates that every output is a single line holding an envelope whose payload is what a plain router writes.
+#[test]
+fn test_router_enveloped_output() -> Result<(), Box<dyn Error>> {
+    let plain: RouterConfig = RouterConfig::new("acars_router", "1.3.1").with_uuid(Uuid::nil());
+    let enveloped: RouterConfig = plain.clone().output_mode(OutputMode::Enveloped);
+    for line in combine_files_of_message_type(MessageType::All)? {
+        let Ok(message) = decode_for_forwarding(line.as_bytes()) else {
+            continue;
+        };
+        let expected: Vec<u8> = prepare_for_forwarding(&mut message.clone(), &plain)?;
+        let output: Vec<u8> = prepare_for_forwarding(&mut message.clone(), &enveloped)?;
+        assert_eq!(output.iter().filter(|byte| **byte == b'\n').count(), 1);
+        let envelope: Value = serde_json::from_slice(&output)?;
+        assert_eq!(envelope["type"], Value::String(message.get_family().to_string()));
+        assert_eq!(envelope["payload"], serde_json::from_slice::<Value>(&expected)?);
+        assert_eq!(decode_enveloped_bytes(&output)?.to_bytes_newline()?, expected);
+    }
+    Ok(())
+}
+
+/// This test forwards the sample corpus twice through a router created with pinned providers, and reads the time and
+/// generates UUIDs inside nested and panicking scopes.
+/// It validates that both runs give byte for byte the same output, that closures can be used as providers, that the
+/// innermost scope wins, and that the defaults are back once a scope ends, even by a panic.
+#[test]
+fn test_router_pinned_providers() -> Result<(), Box<dyn Error>> {
+    let forward_all = || -> Result<Vec<u8>, Box<dyn Error>> {
+        let providers: Providers = Providers::new().with_uuids(SequentialUuids::starting_at(7)).with_clock(FixedClock(1700000000.0));
+        providers.scope(|| {
+            let config: RouterConfig = RouterConfig::new("acars_router", "1.3.1");
+            let mut output: Vec<u8> = Vec::new();
+            for line in combine_files_of_message_type(MessageType::All)? {
+                let Ok(mut message) = line.decode_message() else { continue };
+                message.clear_proxy_details();
+                output.extend(forward(&message.to_bytes()?, &config)?);
+            }
+            Ok(output)
+        })
+    };
+    let first: Vec<u8> = forward_all()?;
+    assert_eq!(first, forward_all()?);
+    assert!(String::from_utf8(first)?.contains(r#""acars_router_uuid":"00000000-0000-0000-0000-000000000007""#));
+
+    let outer: Providers = Providers::new().with_clock(|| 1.5).with_uuids(|| Uuid::from_u128(42));
+    outer.scope(|| {
+        assert_eq!(MessageTimestamp::ingested_now().seconds, 1.5);
+        Providers::new().with_clock(FixedClock(2.5)).scope(|| assert_eq!(provider::now(), 2.5));
+        assert_eq!(provider::now(), 1.5);
+        let error: serde_json::Error = "not json".decode_message().err().ok_or("decoded")?;
+        assert_eq!(FailedDecode::new("not json", &error, &[], None).timestamp, 1.5);
+        assert_eq!(AppDetails::new("acars_router", "1.3.1").acars_router_uuid, Some(Uuid::from_u128(42).to_string()));
+        Ok::<(), Box<dyn Error>>(())
+    })?;
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| outer.scope(|| panic!("inside a scope")))).is_err());
+    assert!(provider::now() > 1_600_000_000.0);
+    assert_ne!(provider::new_uuid(), Uuid::from_u128(42));
+    Ok(())
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "adsb-json")]
+use std::error::Error;
+use acars_vdlm2_parser::adsb_json::{AdsbJsonMessage, BaroAltitude};
+use acars_vdlm2_parser::sbs::{parse_sbs, SbsMessage};
+use acars_vdlm2_parser::sniff::{sniff_format, WireFormat};
+
+/// A sanitised SBS capture from dump1090, following one aircraft through each transmission type, with the `-1` flags
+/// BaseStation writes and the CRLF line endings of port 30003.
+const SBS_LINES: [&str; 6] = [
+    "MSG,1,1,1,A1B2C3,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,AB0123  ,,,,,,,,,,,\r\n",
+    "MSG,3,1,1,A1B2C3,1,2023/11/14,22:13:20.500,2023/11/14,22:13:20.510,,37000,,,47.446548,-122.301712,,,0,0,0,0\r\n",
+    "MSG,4,1,1,A1B2C3,1,2023/11/14,22:13:21.000,2023/11/14,22:13:21.004,,,452.3,87.45,,,-64,,,,,0\r\n",
+    "MSG,5,1,1,A1B2C3,1,2023/11/14,22:13:21.250,2023/11/14,22:13:21.260,,37000,,,,,,,0,,0,0\r\n",
+    "MSG,6,1,1,A1B2C3,1,2023/11/14,22:13:22.000,2023/11/14,22:13:22.011,,37000,,,,,,7700,-1,-1,0,0\r\n",
+    "MSG,8,1,1,A1B2C3,1,2023/11/14,22:13:22.750,2023/11/14,22:13:22.760,,,,,,,,,,,,0\r\n",
+];
+
+/// This test parses a capture of each transmission type for one aircraft and builds up an `AdsbJsonMessage` from it.
+/// It validates that each line parses with the columns its type carries, and that the aircraft ends up with the
+/// callsign, position, velocity and emergency from the lines that carried them.
+#[test]
+fn test_sbs_aircraft() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<SbsMessage> = SBS_LINES.iter().map(|line| parse_sbs(line)).collect::<Result<_, _>>()?;
+    assert_eq!(messages.iter().map(|message| message.transmission_type).collect::<Vec<u8>>(), vec![1, 3, 4, 5, 6, 8]);
+    assert!(messages.iter().all(|message| message.get_icao() == Some(0xA1B2C3)));
+    assert_eq!(messages[0].get_flight(), Some("AB0123"));
+    assert_eq!(messages[1].get_time(), Some(1700000000.5));
+    assert_eq!((messages[2].ground_speed, messages[2].vertical_rate), (Some(452.3), Some(-64)));
+    assert_eq!((messages[4].alert, messages[4].emergency, messages[4].spi), (Some(true), Some(true), Some(false)));
+    let reparsed: SbsMessage = serde_json::from_str(&messages[1].to_json()?)?;
+    assert_eq!(reparsed, messages[1]);
+
+    let mut aircraft: AdsbJsonMessage = messages[0].to_adsb_json();
+    for message in &messages[1..] {
+        message.update_adsb_json(&mut aircraft);
+    }
+    assert_eq!(aircraft.hex, "a1b2c3");
+    assert_eq!(aircraft.get_icao(), Some(0xA1B2C3));
+    assert_eq!(aircraft.get_flight(), Some("AB0123"));
+    assert_eq!(aircraft.get_altitude(), Some(37000));
+    assert_eq!(aircraft.get_position(), Some((47.446548, -122.301712)));
+    assert_eq!((aircraft.gs, aircraft.track, aircraft.baro_rate), (Some(452.3), Some(87.45), Some(-64)));
+    assert_eq!((aircraft.squawk.as_deref(), aircraft.emergency.as_deref()), (Some("7700"), Some("general")));
+    assert_eq!((aircraft.alert, aircraft.spi), (Some(1), Some(0)));
+
+    let ground: SbsMessage = "MSG,2,1,1,A2C3D4,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,,,12.1,165.9,47.451,-122.309,,,,,,-1".parse()?;
+    assert_eq!(ground.to_adsb_json().alt_baro, Some(BaroAltitude::Text("ground".to_string())));
+    assert!(ground.to_adsb_json().is_on_ground());
+    Ok(())
+}
+
+/// This test parses lines with trailing columns left off, with other BaseStation line types, and with columns that do
+/// not parse.
+/// It validates that truncated lines parse with the missing columns unset, that every other malformed line is
+/// rejected, that a date or time out of range gives no time rather than overflowing, and that `sniff_format`
+/// recognises `MSG` lines.
+#[test]
+fn test_sbs_tolerance() -> Result<(), Box<dyn Error>> {
+    let truncated: SbsMessage = parse_sbs("MSG,8,1,1,~2D6E2B")?;
+    assert_eq!(truncated, SbsMessage { transmission_type: 8, session_id: Some("1".to_string()), aircraft_id: Some("1".to_string()), hex_ident: "~2D6E2B".to_string(), ..Default::default() });
+    assert_eq!(truncated.get_icao(), None);
+    assert_eq!(truncated.get_time(), None);
+    for line in ["SEL,,1,1,A1B2C3,1,2023/11/14,22:13:20.125,2023/11/14,22:13:20.130,AB0123", "MSG,3,1,1",
+        "MSG,9,1,1,A1B2C3", "MSG,,1,1,A1B2C3", "MSG,3,1,1,,1", "MSG,3,1,1,A1B2C3,1,,,,,,high",
+        "MSG,3,1,1,A1B2C3,1,,,,,,,,,,,,,2,0,0,0", "MSG,3,1,1,A1B2C3,1,,,,,,,,,,,,,0,0,0,0,extra"] {
+        assert!(parse_sbs(line).is_err(), "{}", line);
+    }
+    for (date, time) in [("2023/11/14", "9999999999999999:00:00.0"), ("2023/11/14", "22:9999999999999999:00.0"),
+        ("9999999999999999/11/14", "22:13:20.125"), ("2023/11/14", "24:00:00.0"), ("2023/11/14", "22:13:61.0"),
+        ("2023/11/14", "22:13:NaN")] {
+        let line: String = format!("MSG,8,1,1,A1B2C3,1,{},{},{},{}", date, time, date, time);
+        assert_eq!(parse_sbs(&line)?.get_time(), None, "{}", line);
+    }
+    assert_eq!(sniff_format(SBS_LINES[1].as_bytes()), WireFormat::Sbs);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+#![cfg(all(feature = "search", feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+use acars_vdlm2_parser::search::{SearchError, SearchErrorKind, SearchIndex, SearchQuery, MAX_QUERY_DEPTH};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// Serialises search results so they can be compared.
+fn as_json(found: &[&AcarsVdlm2Message]) -> Vec<String> {
+    found.iter().map(|message| AcarsVdlm2Message::to_json(message).unwrap()).collect()
+}
+
+/// This test indexes every decodable sample message and queries it by field and with boolean operators.
+/// It validates that field queries return exactly the messages a linear scan finds, in the order they were added, that
+/// `NOT` and `OR` partition the index, and that malformed queries, including ones nested too deep to parse safely, are
+/// errors rather than empty results, reported at the offset in the query where they were found.
+#[test]
+fn test_search_index_queries() -> Result<(), Box<dyn Error>> {
+    let messages: Vec<AcarsVdlm2Message> = combine_files_of_message_type(MessageType::All)?
+        .iter()
+        .filter_map(|line| line.decode_message().ok())
+        .collect();
+    let index: SearchIndex = SearchIndex::from_messages(messages.clone());
+    assert_eq!(index.len(), messages.len());
+    let labelled: Vec<&AcarsVdlm2Message> = messages.iter().filter(|message| message.get_label() == Some("SQ")).collect();
+    let found: Vec<&AcarsVdlm2Message> = index.query("label:sq")?;
+    assert!(!found.is_empty());
+    assert_eq!(as_json(&found), as_json(&labelled));
+    let vdlm2: usize = messages.iter().filter(|message| message.get_family() == MessageFamily::Vdlm2).count();
+    assert_eq!(index.query("family:vdlm2")?.len(), vdlm2);
+    assert_eq!(index.query("family:vdlm2 AND label:SQ")?.len() + index.query("label:SQ NOT family:vdlm2")?.len(), labelled.len());
+    assert_eq!(index.query("family:vdlm2 OR -family:vdlm2")?.len(), messages.len());
+    assert_eq!(as_json(&index.query("(family:acars OR family:hfdl) AND label:SQ")?), as_json(&index.query("label:SQ -family:vdlm2")?));
+    if let Some(registration) = messages.iter().find_map(|message| message.get_registration()) {
+        let tail: String = registration.trim_start_matches('.').to_string();
+        assert!(!index.query(&format!("tail:{}", tail))?.is_empty());
+        assert_eq!(as_json(&index.query(&format!("tail:{}", tail))?), as_json(&index.query(&format!("tail:.{}", tail.replace('-', "")))?));
+    }
+    assert_eq!(SearchQuery::parse("KLAX label:H1")?, SearchQuery::parse("klax AND label:h1")?);
+    let malformed: [(&str, usize, SearchErrorKind); 7] = [
+        ("", 0, SearchErrorKind::UnexpectedEnd),
+        ("label:H1 AND", 12, SearchErrorKind::UnexpectedEnd),
+        ("KLAX (KLAX", 5, SearchErrorKind::Unclosed),
+        ("KLAX)", 4, SearchErrorKind::UnexpectedToken { token: ")".to_string() }),
+        ("KLAX -colour:red", 6, SearchErrorKind::UnknownField { field: "colour".to_string() }),
+        ("OR KLAX", 0, SearchErrorKind::UnexpectedToken { token: "OR".to_string() }),
+        ("KLAX label:--", 5, SearchErrorKind::EmptyTerm { term: "label:--".to_string() }),
+    ];
+    for (query, position, kind) in malformed {
+        assert_eq!(index.query(query).err(), Some(SearchError { position, kind }), "{:?} should not parse", query);
+    }
+    let nested: String = format!("{}KLAX{}", "(".repeat(MAX_QUERY_DEPTH), ")".repeat(MAX_QUERY_DEPTH));
+    assert_eq!(SearchQuery::parse(&nested)?, SearchQuery::parse("KLAX")?);
+    assert_eq!(SearchQuery::parse(&format!("({})", nested)).map_err(|error| (error.position, error.kind)),
+               Err((MAX_QUERY_DEPTH, SearchErrorKind::TooDeep { limit: MAX_QUERY_DEPTH })));
+    assert!(SearchQuery::parse(&"(".repeat(200_000)).is_err());
+    assert!(SearchQuery::parse(&format!("{}KLAX", "NOT ".repeat(200_000))).is_err());
+    Ok(())
+}
+
+/// This test keeps a search index to a time window.
+/// It validates that messages timed before the cut off, and those without a time, are removed and no longer found, and
+/// that messages kept or added afterwards are still found by every term, including `NOT`.
+#[test]
+fn test_search_index_remove_before() -> Result<(), Box<dyn Error>> {
+    let mut index: SearchIndex = SearchIndex::new();
+    index.insert(r#"{"freq":131.55,"timestamp":1000.0,"label":"H1","text":"OLD KLAX"}"#.decode_message()?);
+    index.insert(r#"{"freq":131.55,"timestamp":2000.0,"label":"H1","text":"NEW KLAX"}"#.decode_message()?);
+    index.insert(r#"{"freq":131.55,"label":"H1","text":"UNTIMED KLAX"}"#.decode_message()?);
+    assert_eq!(index.query("KLAX")?.len(), 3);
+    assert_eq!(index.remove_before(1500.0), 2);
+    let found: Vec<&AcarsVdlm2Message> = index.query("KLAX")?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].get_text(), Some("NEW KLAX"));
+    assert!(index.query("OLD")?.is_empty());
+    assert!(index.query("UNTIMED")?.is_empty());
+    assert_eq!(index.remove_before(1500.0), 0);
+    index.insert(r#"{"freq":131.55,"timestamp":3000.0,"label":"SQ","text":"NEWER KLAX"}"#.decode_message()?);
+    assert_eq!(index.len(), 2);
+    assert_eq!(index.query("KLAX")?.len(), 2);
+    let found: Vec<&AcarsVdlm2Message> = index.query("-label:H1")?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].get_text(), Some("NEWER KLAX"));
+    assert_eq!(index.remove_before(2500.0), 1);
+    assert_eq!(index.query("KLAX")?.iter().map(|message| message.get_text()).collect::<Vec<_>>(), vec![Some("NEWER KLAX")]);
+    Ok(())
+}
@@ -0,0 +1,108 @@
+#![cfg(all(feature = "acars", feature = "vdlm2", feature = "hfdl", feature = "uat"))]
+mod common;
+
+use std::error::Error;
+use acars_vdlm2_parser::{AcarsVdlm2Message, DecodeMessage, MessageFamily};
+use acars_vdlm2_parser::quality::FrameQuality;
+use acars_vdlm2_parser::sniff::{sniff_format, WireFormat};
+use acars_vdlm2_parser::uat::{parse_uat_raw, NewUatMessage, UatAdsbMessage, UatDirection, UatFrameMessage, UatMessage, UPLINK_BYTES};
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// The header of a ground uplink with the position valid bit set, for a ground station at 47.5, -122.3.
+const UPLINK_HEADER: &str = "438e39520fefa350";
+
+/// This test will ingest contents from the uat sample files as a message per line to a `Vec<String>`.
+/// Then it will cycle them into `Vec<UatMessage>` and back to `String`.
+/// It validates that every line decodes as a decoded ADS-B message, and that writing it out and decoding it again
+/// gives the same message.
+#[test]
+fn test_uat_parsing() -> Result<(), Box<dyn Error>> {
+    let uat_messages: Vec<String> = combine_files_of_message_type(MessageType::Uat)?;
+    assert!(!uat_messages.is_empty());
+    for line in uat_messages {
+        let message: UatMessage = line.to_uat()?;
+        assert!(matches!(message, UatMessage::Adsb(_)), "{}", line);
+        let reparsed: UatMessage = message.to_json()?.to_uat()?;
+        assert_eq!(reparsed.to_json()?, message.to_json()?);
+        let decoded: AcarsVdlm2Message = line.decode_message()?;
+        assert_eq!(decoded.get_family(), MessageFamily::Uat);
+    }
+    Ok(())
+}
+
+/// This test decodes raw dump978-fa frames for a basic downlink, a long downlink and a ground uplink.
+/// It validates that the direction, Reed-Solomon error count, signal level and time are read, that unknown fields
+/// are kept, that each frame writes back out as the same line, and that the uplink header gives the ground station
+/// position.
+#[test]
+fn test_uat_raw_frames() -> Result<(), Box<dyn Error>> {
+    let basic: &str = "-00a1b2c30000000000000000000000000000;rs=2;rssi=-17.5;t=1700000000.123;";
+    let long: String = format!("-0aa0c1d2{};rs=0;rssi=-9.1;t=1700000000.500;", "00".repeat(30));
+    let uplink: String = format!("+{}{};rs=11;rssi=-21.0;t=1700000001.000;mux=3;", UPLINK_HEADER, "00".repeat(UPLINK_BYTES - 8));
+
+    let basic_frame: UatFrameMessage = parse_uat_raw(basic)?;
+    assert_eq!(basic_frame.uat_frame.direction, UatDirection::Downlink);
+    assert_eq!(basic_frame.uat_frame.get_payload_type(), Some(0));
+    assert_eq!(basic_frame.uat_frame.get_icao(), Some(0xA1B2C3));
+    assert_eq!(basic_frame.uat_frame.to_raw_line(), basic);
+
+    let long_frame: UatFrameMessage = parse_uat_raw(&long)?;
+    assert_eq!(long_frame.uat_frame.get_payload_type(), Some(1));
+    assert_eq!(long_frame.uat_frame.get_address_qualifier(), Some(2));
+    assert_eq!(long_frame.uat_frame.get_icao(), Some(0xA0C1D2));
+    assert_eq!(long_frame.uat_frame.to_raw_line(), long);
+
+    let uplink_frame: UatFrameMessage = parse_uat_raw(&uplink)?;
+    assert_eq!(uplink_frame.uat_frame.direction, UatDirection::Uplink);
+    assert_eq!(uplink_frame.uat_frame.get_icao(), None);
+    assert_eq!(uplink_frame.uat_frame.metadata.get("mux").map(String::as_str), Some("3"));
+    let (lat, lon): (f64, f64) = uplink_frame.uat_frame.get_ground_station_position().ok_or("no ground station position")?;
+    assert!((lat - 47.5).abs() < 0.0001 && (lon + 122.3).abs() < 0.0001, "{} {}", lat, lon);
+    assert_eq!(uplink_frame.uat_frame.to_raw_line(), uplink);
+
+    let decoded: AcarsVdlm2Message = uplink.decode_message()?;
+    assert_eq!(decoded.get_family(), MessageFamily::Uat);
+    assert_eq!(decoded.get_frame_quality(), FrameQuality::Corrected);
+    let reparsed: AcarsVdlm2Message = decoded.to_json()?.decode_message()?;
+    assert_eq!(reparsed.to_json()?, decoded.to_json()?);
+    Ok(())
+}
+
+/// This test decodes raw frames that are truncated, the wrong length for their direction, or not hex, along with
+/// frames whose fields do not parse.
+/// It validates that each is rejected, and that `sniff_format` tells raw frames apart from dump978-fa JSON.
+#[test]
+fn test_uat_raw_rejected() {
+    let uplink_length_downlink: String = format!("-{};", "00".repeat(UPLINK_BYTES));
+    for line in ["-0b28c8;", "+00a1b2c30000000000000000000000000000;", "-00a1b2c3000000000000000000000000000g;",
+        "-00a1b2c3000000000000000000000000000;", "-00a1b2c30000000000000000000000000000;rs=x;",
+        "-00a1b2c30000000000000000000000000000;rssi;", uplink_length_downlink.as_str()] {
+        assert!(parse_uat_raw(line).is_err(), "{}", line);
+    }
+    assert_eq!(sniff_format(b"-00a1b2c30000000000000000000000000000;rs=1;"), WireFormat::UatRaw);
+    assert_eq!(sniff_format(br#"{"address":"a1b2c3","address_qualifier":"adsb_icao"}"#), WireFormat::Json);
+}
+
+/// This test decodes dump978-fa JSON for aircraft with non-ICAO addresses, with quoted numbers and with fields this
+/// library does not model.
+/// It validates that only ICAO addresses are reported as such, that unknown fields are dropped without an error, and
+/// that the frame quality follows the error count.
+#[test]
+fn test_uat_adsb_tolerance() -> Result<(), Box<dyn Error>> {
+    let minimal: UatMessage = r#"{"address":"a1b2c3"}"#.to_uat()?;
+    assert_eq!(minimal.to_json()?, r#"{"address":"a1b2c3"}"#);
+    assert_eq!(minimal.get_frame_quality(), FrameQuality::Ok);
+    let quoted: UatMessage = r#"{"address":"a1b2c3","address_qualifier":"adsb_icao","pressure_altitude":"35000","metadata":{"errors":3},"future_field":[1]}"#.to_uat()?;
+    assert!(!quoted.to_json()?.contains("future_field"));
+    assert_eq!(quoted.get_frame_quality(), FrameQuality::Corrected);
+    match &quoted {
+        UatMessage::Adsb(adsb) => assert_eq!(adsb.pressure_altitude, Some(35000)),
+        UatMessage::Frame(_) => panic!("decoded as a raw frame"),
+    }
+    for (qualifier, icao) in [("adsb_icao", Some(0xA1B2C3)), ("tisb_icao", Some(0xA1B2C3)), ("tisb_trackfile", None), ("vehicle", None)] {
+        let adsb: UatAdsbMessage = UatAdsbMessage { address: "a1b2c3".to_string(), address_qualifier: Some(qualifier.to_string()), ..Default::default() };
+        assert_eq!(adsb.get_icao(), icao, "{}", qualifier);
+    }
+    assert!(r#"{"callsign":"AB0123"}"#.to_uat().is_err());
+    Ok(())
+}
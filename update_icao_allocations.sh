@@ -0,0 +1,26 @@
+# /bin/sh
+
+# Regenerates src/icao_table.rs from data/icao_allocations.csv.
+# The CSV is transcribed from ICAO Annex 10 Volume III, Part I, Chapter 9, Table 9-1.
+# Edit the CSV when ICAO publishes a new allocation and re-run this script.
+
+CSV="data/icao_allocations.csv"
+OUTPUT="src/icao_table.rs"
+
+echo "Generating $OUTPUT from $CSV"
+{
+    echo "// Generated by update_icao_allocations.sh from $CSV, do not edit by hand."
+    echo ""
+    echo "use crate::icao::IcaoAllocation;"
+    echo ""
+    echo "/// Allocated ICAO 24 bit address blocks, sorted by start address."
+    echo "pub(crate) const ICAO_ALLOCATIONS: &[IcaoAllocation] = &["
+    tail -n +2 "$CSV" | sort -t, -k1,1 | awk -F, '{
+        country = $3
+        gsub(/\\/, "\\\\", country)
+        gsub(/"/, "\\\"", country)
+        printf "    IcaoAllocation { start: 0x%s, end: 0x%s, country: \"%s\" },\n", $1, $2, country
+    }'
+    echo "];"
+} > "$OUTPUT"
+echo "Done."